@@ -0,0 +1,53 @@
+use super::errors::{CommandError, LockResultExt};
+use crate::db::{check_schema, run_migrations, SchemaState, SchemaStatus};
+use crate::AppState;
+use log::{debug, info, warn};
+use tauri::State;
+
+/// Error for a database written by a newer version of the app
+fn too_new_error(status: &SchemaStatus) -> CommandError {
+    CommandError::Validation(format!(
+        "Database schema version {} is newer than this version of HomeKaraoke supports ({}). \
+         Please update the app to avoid damaging your data.",
+        status.stored_version, status.expected_version
+    ))
+}
+
+/// Report the stored schema version against the version this build expects.
+/// Fails if the database was written by a newer version of the app.
+#[tauri::command]
+pub fn db_check_schema(state: State<'_, AppState>) -> Result<SchemaStatus, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let status = check_schema(db.connection());
+    debug!(
+        "Schema status: {:?} (stored {}, expected {})",
+        status.state, status.stored_version, status.expected_version
+    );
+
+    if status.state == SchemaState::TooNew {
+        warn!("Database schema is newer than this app supports");
+        return Err(too_new_error(&status));
+    }
+    Ok(status)
+}
+
+/// Apply any pending migrations and return the resulting schema status.
+/// Refuses to touch a database written by a newer version of the app.
+#[tauri::command]
+pub fn db_repair_schema(state: State<'_, AppState>) -> Result<SchemaStatus, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let status = check_schema(db.connection());
+
+    match status.state {
+        SchemaState::UpToDate => Ok(status),
+        SchemaState::TooNew => Err(too_new_error(&status)),
+        SchemaState::NeedsMigration => {
+            info!(
+                "Repairing database schema: migrating from {} to {}",
+                status.stored_version, status.expected_version
+            );
+            run_migrations(db.connection())?;
+            Ok(check_schema(db.connection()))
+        }
+    }
+}
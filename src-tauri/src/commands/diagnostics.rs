@@ -0,0 +1,124 @@
+use super::errors::{CommandError, LockResultExt};
+use crate::db::check_schema;
+use crate::services::ffmpeg::FfmpegService;
+use crate::services::ytdlp::{find_ytdlp_path, search_bin_paths, BinPathStatus};
+use crate::services::{get_expanded_path, YtDlpService};
+use crate::AppState;
+use log::info;
+use serde::Serialize;
+use std::path::PathBuf;
+use tauri::State;
+
+/// What the app sees when looking for external tools.
+/// Explains "yt-dlp/ffmpeg not found" when the tool is installed somewhere the app
+/// doesn't search (macOS apps launched from Finder get a minimal PATH).
+#[derive(Debug, Serialize)]
+pub struct ToolDiscovery {
+    /// PATH inherited by the app process
+    pub process_path: Option<String>,
+    /// PATH the app uses when running tools (common install locations + process PATH)
+    pub expanded_path: String,
+    pub ytdlp_path: Option<String>,
+    pub ffmpeg_path: Option<String>,
+    pub ffprobe_path: Option<String>,
+    /// Install locations checked directly, with whether each exists
+    pub search_paths: Vec<BinPathStatus>,
+}
+
+fn display_path(path: Option<PathBuf>) -> Option<String> {
+    path.map(|p| p.to_string_lossy().to_string())
+}
+
+/// Report PATH and tool discovery results for troubleshooting missing yt-dlp/ffmpeg
+#[tauri::command]
+pub fn diagnostics_tool_discovery() -> ToolDiscovery {
+    let discovery = ToolDiscovery {
+        process_path: std::env::var("PATH").ok(),
+        expanded_path: get_expanded_path(),
+        ytdlp_path: display_path(find_ytdlp_path()),
+        ffmpeg_path: display_path(FfmpegService::find_ffmpeg_path()),
+        ffprobe_path: display_path(FfmpegService::find_ffprobe_path()),
+        search_paths: search_bin_paths(),
+    };
+
+    info!(
+        "Tool discovery: yt-dlp={:?}, ffmpeg={:?}, ffprobe={:?}",
+        discovery.ytdlp_path, discovery.ffmpeg_path, discovery.ffprobe_path
+    );
+    discovery
+}
+
+/// Versions of the app and the tools it depends on, for the About screen.
+/// Tool versions are None when the tool is not installed.
+#[derive(Debug, Serialize)]
+pub struct Versions {
+    pub app_version: String,
+    pub ytdlp_version: Option<String>,
+    pub ffmpeg_version: Option<String>,
+    pub ffprobe_version: Option<String>,
+    pub sqlite_version: String,
+    /// Schema version stored in the database
+    pub schema_version: i32,
+}
+
+impl Versions {
+    fn new(
+        ytdlp_version: Option<String>,
+        ffmpeg_version: Option<String>,
+        ffprobe_version: Option<String>,
+        schema_version: i32,
+    ) -> Self {
+        Self {
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            ytdlp_version,
+            ffmpeg_version,
+            ffprobe_version,
+            sqlite_version: rusqlite::version().to_string(),
+            schema_version,
+        }
+    }
+}
+
+/// Get app, tool and database versions in one call
+#[tauri::command]
+pub async fn get_versions(state: State<'_, AppState>) -> Result<Versions, CommandError> {
+    let schema_version = {
+        let db = state.db.lock().map_lock_err()?;
+        check_schema(db.connection()).stored_version
+    };
+
+    let ytdlp = YtDlpService::new();
+    let (ytdlp_version, ffmpeg_version, ffprobe_version) = tokio::join!(
+        ytdlp.get_version(),
+        FfmpegService::get_ffmpeg_version(),
+        FfmpegService::get_ffprobe_version(),
+    );
+
+    Ok(Versions::new(
+        ytdlp_version,
+        ffmpeg_version,
+        ffprobe_version,
+        schema_version,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_assembly_with_missing_tools() {
+        let versions = Versions::new(Some("2024.12.13".to_string()), None, Some("6.1.1".to_string()), 17);
+
+        assert_eq!(versions.app_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(versions.ytdlp_version.as_deref(), Some("2024.12.13"));
+        assert_eq!(versions.ffmpeg_version, None);
+        assert_eq!(versions.ffprobe_version.as_deref(), Some("6.1.1"));
+        assert_eq!(versions.sqlite_version, rusqlite::version());
+        assert_eq!(versions.schema_version, 17);
+
+        // Missing tools serialize as null so the UI can show "not installed"
+        let json = serde_json::to_value(&versions).unwrap();
+        assert!(json["ffmpeg_version"].is_null());
+    }
+}
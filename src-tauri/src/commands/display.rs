@@ -1,9 +1,9 @@
 use super::errors::{CommandError, LockResultExt};
 use crate::AppState;
 use log::{debug, info, warn};
-use rusqlite::OptionalExtension;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use tauri::{AppHandle, State};
 
 #[cfg(target_os = "macos")]
 use crate::services::{get_display_configuration, DisplayConfiguration};
@@ -34,9 +34,157 @@ pub struct WindowState {
     pub is_fullscreen: bool,
 }
 
+/// A connected monitor as reported by the windowing system (all platforms)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MonitorInfo {
+    pub name: Option<String>,
+    /// Top-left position in physical pixels
+    pub x: i32,
+    pub y: i32,
+    /// Size in physical pixels
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+    pub is_primary: bool,
+}
+
+impl MonitorInfo {
+    fn from_monitor(monitor: &tauri::Monitor, primary: Option<&tauri::Monitor>) -> Self {
+        let position = monitor.position();
+        let size = monitor.size();
+        let is_primary = primary.is_some_and(|p| {
+            p.name() == monitor.name() && p.position() == position && p.size() == size
+        });
+        Self {
+            name: monitor.name().cloned(),
+            x: position.x,
+            y: position.y,
+            width: size.width,
+            height: size.height,
+            scale_factor: monitor.scale_factor(),
+            is_primary,
+        }
+    }
+}
+
+/// Position and size of a display, as used by `suggest_karaoke_layout`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DisplayGeometry {
+    pub display_id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub is_main: bool,
+}
+
+#[cfg(target_os = "macos")]
+impl From<&crate::services::display_watcher::DisplayInfo> for DisplayGeometry {
+    fn from(display: &crate::services::display_watcher::DisplayInfo) -> Self {
+        Self {
+            display_id: display.display_id,
+            x: display.x,
+            y: display.y,
+            width: display.width,
+            height: display.height,
+            is_main: display.is_main,
+        }
+    }
+}
+
+/// Where to put one window
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct WindowPlacement {
+    pub display_id: u32,
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    pub is_fullscreen: bool,
+}
+
+/// Suggested placement of the control (main) window and the player window
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct KaraokeLayout {
+    pub control: WindowPlacement,
+    pub player: WindowPlacement,
+    /// Both windows share one display, side by side
+    pub single_display: bool,
+}
+
+/// Share of a lone display's width given to the control window (the player gets the rest)
+const SINGLE_DISPLAY_CONTROL_PERCENT: u32 = 40;
+
+/// Controls on the main display, player fullscreen on the largest other display.
+/// With one display, controls take the left part and the player the right.
+/// Returns None when there are no displays.
+pub fn suggest_karaoke_layout(displays: &[DisplayGeometry]) -> Option<KaraokeLayout> {
+    let main = displays.iter().find(|d| d.is_main).or_else(|| displays.first())?;
+    let whole = |display: &DisplayGeometry, is_fullscreen: bool| WindowPlacement {
+        display_id: display.display_id,
+        x: display.x,
+        y: display.y,
+        width: display.width,
+        height: display.height,
+        is_fullscreen,
+    };
+
+    // Largest by area; ties go to the lowest display id so the choice is stable
+    let tv = displays
+        .iter()
+        .filter(|d| d.display_id != main.display_id)
+        .max_by(|a, b| {
+            let area = |d: &DisplayGeometry| u64::from(d.width) * u64::from(d.height);
+            area(a).cmp(&area(b)).then(b.display_id.cmp(&a.display_id))
+        });
+
+    Some(match tv {
+        Some(tv) => KaraokeLayout {
+            control: whole(main, false),
+            player: whole(tv, true),
+            single_display: false,
+        },
+        None => {
+            let control_width = main.width * SINGLE_DISPLAY_CONTROL_PERCENT / 100;
+            KaraokeLayout {
+                control: WindowPlacement {
+                    width: control_width,
+                    ..whole(main, false)
+                },
+                player: WindowPlacement {
+                    x: main.x + control_width as i32,
+                    width: main.width - control_width,
+                    ..whole(main, false)
+                },
+                single_display: true,
+            }
+        }
+    })
+}
 
 // ============ Display Configuration Commands ============
 
+/// List connected monitors using Tauri's cross-platform monitor API
+///
+/// Unlike `display_get_configuration`, this works on Windows and Linux too.
+#[tauri::command]
+pub fn window_list_monitors(app: AppHandle) -> Result<Vec<MonitorInfo>, CommandError> {
+    debug!("Listing available monitors");
+
+    let monitors = app
+        .available_monitors()
+        .map_err(|e| CommandError::External(format!("Failed to list monitors: {}", e)))?;
+    let primary = app.primary_monitor().ok().flatten();
+
+    let monitors: Vec<MonitorInfo> = monitors
+        .iter()
+        .map(|m| MonitorInfo::from_monitor(m, primary.as_ref()))
+        .collect();
+
+    debug!("Found {} monitors", monitors.len());
+    Ok(monitors)
+}
+
 /// Get the current display configuration (macOS only)
 #[cfg(target_os = "macos")]
 #[tauri::command]
@@ -52,6 +200,54 @@ pub fn display_get_configuration() -> Result<(), CommandError> {
     Err(CommandError::PlatformNotSupported("Display configuration"))
 }
 
+/// Suggest the classic karaoke setup: controls on the main display (laptop) and the
+/// player fullscreen on the largest other display (TV). The frontend applies it by
+/// moving the windows. On macOS displays come from CoreGraphics, elsewhere from Tauri.
+#[cfg(target_os = "macos")]
+#[tauri::command]
+pub fn display_suggest_karaoke_layout() -> Result<KaraokeLayout, CommandError> {
+    let displays: Vec<DisplayGeometry> = crate::services::display_watcher::get_active_displays()
+        .map_err(CommandError::External)?
+        .iter()
+        .map(DisplayGeometry::from)
+        .collect();
+
+    let layout = suggest_karaoke_layout(&displays)
+        .ok_or_else(|| CommandError::External("No active displays found".to_string()))?;
+    info!(
+        "Suggested karaoke layout: controls on display {}, player on display {}",
+        layout.control.display_id, layout.player.display_id
+    );
+    Ok(layout)
+}
+
+/// Suggest a karaoke window layout from Tauri's monitor list (other platforms).
+/// Display ids are indexes into `window_list_monitors`.
+#[cfg(not(target_os = "macos"))]
+#[tauri::command]
+pub fn display_suggest_karaoke_layout(app: AppHandle) -> Result<KaraokeLayout, CommandError> {
+    let displays: Vec<DisplayGeometry> = window_list_monitors(app)?
+        .iter()
+        .enumerate()
+        .map(|(index, monitor)| DisplayGeometry {
+            display_id: index as u32,
+            x: monitor.x,
+            y: monitor.y,
+            width: monitor.width,
+            height: monitor.height,
+            is_main: monitor.is_primary,
+        })
+        .collect();
+
+    let layout = suggest_karaoke_layout(&displays)
+        .ok_or_else(|| CommandError::External("No active displays found".to_string()))?;
+    info!(
+        "Suggested karaoke layout: controls on display {}, player on display {}",
+        layout.control.display_id, layout.player.display_id
+    );
+    Ok(layout)
+}
+
 /// Save a display configuration to the database
 #[tauri::command]
 pub fn display_save_config(
@@ -211,6 +407,72 @@ pub fn display_delete_config(
     Ok(())
 }
 
+/// Move window states from one display config to another, then delete the emptied config.
+/// Window types the kept config already has a state for keep that state.
+/// Returns the number of window states moved.
+fn merge_display_configs(conn: &Connection, keep_id: i64, merge_id: i64) -> Result<usize, CommandError> {
+    if keep_id == merge_id {
+        return Err(CommandError::Validation(
+            "Cannot merge a display config into itself".to_string(),
+        ));
+    }
+    for id in [keep_id, merge_id] {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM display_configs WHERE id = ?1)",
+            [id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(CommandError::NotFound {
+                resource: "Display config",
+                id: id.to_string(),
+            });
+        }
+    }
+
+    conn.execute("BEGIN IMMEDIATE", [])?;
+
+    let result = (|| -> Result<usize, CommandError> {
+        let moved = conn.execute(
+            "UPDATE OR IGNORE window_state SET display_config_id = ?1 WHERE display_config_id = ?2",
+            rusqlite::params![keep_id, merge_id],
+        )?;
+        // Remaining window states of the merged config are deleted by ON DELETE CASCADE
+        conn.execute("DELETE FROM display_configs WHERE id = ?1", [merge_id])?;
+        Ok(moved)
+    })();
+
+    match result {
+        Ok(moved) => {
+            conn.execute("COMMIT", [])?;
+            Ok(moved)
+        }
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    }
+}
+
+/// Merge a display config into another that describes the same monitors (e.g. one created
+/// after a resolution change). Returns the number of window states moved.
+#[tauri::command]
+pub fn display_merge_configs(
+    state: State<'_, AppState>,
+    keep_id: i64,
+    merge_id: i64,
+) -> Result<usize, CommandError> {
+    debug!("Merging display config {} into {}", merge_id, keep_id);
+    let db = state.db.lock().map_lock_err()?;
+
+    let moved = merge_display_configs(db.connection(), keep_id, merge_id)?;
+    info!(
+        "Merged display config {} into {} ({} window states moved)",
+        merge_id, keep_id, moved
+    );
+    Ok(moved)
+}
+
 // ============ Window State Commands ============
 
 /// Save window state for a display configuration
@@ -327,3 +589,141 @@ pub fn window_clear_states(
     info!("Cleared window states for config: {}", display_config_id);
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn display(display_id: u32, x: i32, width: u32, height: u32, is_main: bool) -> DisplayGeometry {
+        DisplayGeometry {
+            display_id,
+            x,
+            y: 0,
+            width,
+            height,
+            is_main,
+        }
+    }
+
+    #[test]
+    fn test_player_goes_fullscreen_on_largest_other_display() {
+        let displays = [
+            display(1, -1920, 1920, 1080, false),
+            display(2, 0, 1512, 982, true),
+            display(3, 1512, 3840, 2160, false),
+        ];
+
+        let layout = suggest_karaoke_layout(&displays).unwrap();
+        assert!(!layout.single_display);
+        assert_eq!(layout.control.display_id, 2);
+        assert!(!layout.control.is_fullscreen);
+        assert_eq!((layout.control.width, layout.control.height), (1512, 982));
+        assert_eq!(layout.player.display_id, 3);
+        assert!(layout.player.is_fullscreen);
+        assert_eq!((layout.player.x, layout.player.width, layout.player.height), (1512, 3840, 2160));
+    }
+
+    #[test]
+    fn test_equal_displays_pick_lowest_id() {
+        let displays = [
+            display(5, 1920, 1920, 1080, false),
+            display(4, -1920, 1920, 1080, false),
+            display(1, 0, 1440, 900, true),
+        ];
+        assert_eq!(suggest_karaoke_layout(&displays).unwrap().player.display_id, 4);
+    }
+
+    #[test]
+    fn test_single_display_is_split() {
+        let layout = suggest_karaoke_layout(&[display(1, 0, 2000, 1200, true)]).unwrap();
+        assert!(layout.single_display);
+        assert_eq!((layout.control.x, layout.control.width), (0, 800));
+        assert_eq!((layout.player.x, layout.player.width), (800, 1200));
+        assert_eq!(layout.player.height, 1200);
+        assert!(!layout.player.is_fullscreen);
+    }
+
+    fn setup_display_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        for (id, hash) in [(1, "hash-1080p"), (2, "hash-4k")] {
+            conn.execute(
+                "INSERT INTO display_configs (id, config_hash, display_names) VALUES (?1, ?2, '[\"TV\"]')",
+                rusqlite::params![id, hash],
+            )
+            .unwrap();
+        }
+        conn
+    }
+
+    fn add_window_state(conn: &Connection, config_id: i64, window_type: &str, x: i32) {
+        conn.execute(
+            "INSERT INTO window_state (display_config_id, window_type, x, y, width, height)
+             VALUES (?1, ?2, ?3, 0, 800, 600)",
+            rusqlite::params![config_id, window_type, x],
+        )
+        .unwrap();
+    }
+
+    fn window_states(conn: &Connection) -> Vec<(i64, String, i32)> {
+        let mut stmt = conn
+            .prepare("SELECT display_config_id, window_type, x FROM window_state ORDER BY window_type")
+            .unwrap();
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_merge_display_configs() {
+        let conn = setup_display_db();
+        add_window_state(&conn, 1, "main", 10);
+        add_window_state(&conn, 2, "main", 20);
+        add_window_state(&conn, 2, "video", 30);
+
+        // Only the window type the kept config lacks is moved
+        assert_eq!(merge_display_configs(&conn, 1, 2).unwrap(), 1);
+        assert_eq!(
+            window_states(&conn),
+            vec![(1, "main".to_string(), 10), (1, "video".to_string(), 30)]
+        );
+        let configs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM display_configs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(configs, 1);
+    }
+
+    #[test]
+    fn test_merge_display_configs_validates_ids() {
+        let conn = setup_display_db();
+        assert!(matches!(
+            merge_display_configs(&conn, 1, 1),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            merge_display_configs(&conn, 1, 99),
+            Err(CommandError::NotFound { .. })
+        ));
+        assert!(matches!(
+            merge_display_configs(&conn, 99, 1),
+            Err(CommandError::NotFound { .. })
+        ));
+        // Nothing was deleted
+        let configs: i64 = conn
+            .query_row("SELECT COUNT(*) FROM display_configs", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(configs, 2);
+    }
+
+    #[test]
+    fn test_missing_main_flag_uses_first_display() {
+        let displays = [display(7, 0, 1440, 900, false), display(8, 1440, 1920, 1080, false)];
+        let layout = suggest_karaoke_layout(&displays).unwrap();
+        assert_eq!(layout.control.display_id, 7);
+        assert_eq!(layout.player.display_id, 8);
+
+        assert!(suggest_karaoke_layout(&[]).is_none());
+    }
+}
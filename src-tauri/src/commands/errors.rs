@@ -1,4 +1,6 @@
+use log::warn;
 use serde::Serialize;
+use std::sync::{Mutex, MutexGuard};
 use thiserror::Error;
 
 /// Common error type for Tauri commands.
@@ -36,14 +38,14 @@ pub enum CommandError {
     #[error("{0} is only available on macOS")]
     PlatformNotSupported(&'static str),
 
-    /// Mutex lock failed for non-database resources
-    #[error("{0} mutex poisoned")]
-    MutexPoisoned(&'static str),
-
     /// External service error
     #[error("{0}")]
     External(String),
 
+    /// Action is disabled while party mode is on
+    #[error("{0} is disabled in party mode")]
+    Forbidden(&'static str),
+
     /// Ownership conflict - another user has an active resource
     #[error("Another user is currently hosting this session. They must stop hosting before you can host.")]
     OwnershipConflict,
@@ -70,8 +72,8 @@ impl Serialize for CommandError {
             CommandError::Validation(_) => "validation",
             CommandError::NoActiveSession => "no_active_session",
             CommandError::PlatformNotSupported(_) => "platform_not_supported",
-            CommandError::MutexPoisoned(_) => "mutex_poisoned",
             CommandError::External(_) => "external",
+            CommandError::Forbidden(_) => "forbidden",
             CommandError::OwnershipConflict => "ownership_conflict",
         };
 
@@ -92,6 +94,30 @@ impl<T> LockResultExt<T> for Result<T, std::sync::PoisonError<T>> {
     }
 }
 
+/// Helper trait to lock non-database mutexes, recovering from poisoning.
+///
+/// A panic while holding one of these locks shouldn't silently disable the feature
+/// for the rest of the app's lifetime. The poisoned state is logged and cleared,
+/// and the inner guard is returned (same approach as `DisplayWatcherService` uses
+/// for its callback state). The guarded values are plain `Option`s of services,
+/// so they remain valid after a panic elsewhere.
+pub trait LockRecoverExt<T> {
+    fn lock_recover(&self, resource: &'static str) -> MutexGuard<'_, T>;
+}
+
+impl<T> LockRecoverExt<T> for Mutex<T> {
+    fn lock_recover(&self, resource: &'static str) -> MutexGuard<'_, T> {
+        match self.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => {
+                warn!("{} mutex poisoned, recovering", resource);
+                self.clear_poison();
+                poisoned.into_inner()
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,7 +196,6 @@ mod tests {
             CommandError::Validation("invalid input".to_string()),
             CommandError::NoActiveSession,
             CommandError::PlatformNotSupported("Feature"),
-            CommandError::MutexPoisoned("Resource"),
             CommandError::External("external error".to_string()),
             CommandError::OwnershipConflict,
         ];
@@ -206,4 +231,27 @@ mod tests {
             _ => panic!("Expected CommandError::Json variant"),
         }
     }
+
+    #[test]
+    fn test_lock_recover_from_poisoned_mutex() {
+        let mutex = std::sync::Arc::new(Mutex::new(Some(1)));
+
+        // Poison the mutex by panicking while holding the lock
+        let poisoner = std::sync::Arc::clone(&mutex);
+        let _ = std::thread::spawn(move || {
+            let _guard = poisoner.lock().unwrap();
+            panic!("simulated panic while holding lock");
+        })
+        .join();
+        assert!(mutex.is_poisoned());
+
+        // Recovers the inner value and clears the poison flag
+        {
+            let mut guard = mutex.lock_recover("Test");
+            assert_eq!(*guard, Some(1));
+            *guard = Some(2);
+        }
+        assert!(!mutex.is_poisoned());
+        assert_eq!(*mutex.lock().unwrap(), Some(2));
+    }
 }
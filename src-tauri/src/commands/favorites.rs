@@ -1,11 +1,36 @@
 use super::errors::{CommandError, LockResultExt};
+use super::queue::normalize_video_ids;
+use crate::services::{canonical_video_id, catalog_import::split_csv_line};
 use crate::AppState;
 use log::{debug, info};
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
 use tauri::State;
 
+/// Maximum number of matches returned by a cross-singer favorites search
+const MAX_FAVORITES_SEARCH_RESULTS: i64 = 200;
+
+/// Number of a singer's most recent sessions whose songs are left out of suggestions
+const SUGGESTION_RECENT_SESSIONS: i64 = 3;
+
+/// Maximum CSV song list size for favorites import
+const MAX_FAVORITES_CSV_SIZE: u64 = 5 * 1024 * 1024;
+
+/// Current format version of `export_singers_and_favorites` backups
+const SINGERS_BACKUP_VERSION: u32 = 1;
+
+/// Longest singer name accepted from a backup (same limit as creating a singer)
+const MAX_BACKUP_NAME_LENGTH: usize = 100;
+
+/// Default and maximum number of song suggestions
+const DEFAULT_SUGGESTIONS: u32 = 10;
+const MAX_SUGGESTIONS: u32 = 100;
+
 /// Video data for favorites (denormalized for offline support)
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct FavoriteVideo {
     pub video_id: String,
     pub title: String,
@@ -26,18 +51,546 @@ pub struct SingerFavorite {
     pub added_at: String,
 }
 
+/// A favorite matched by a cross-singer search, with the singer who favorited it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SingerFavoriteMatch {
+    pub singer_name: String,
+    pub singer_color: String,
+    pub favorite: SingerFavorite,
+}
+
+/// Backup of persistent singers and their favorites (the long-term data worth keeping)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SingersBackup {
+    pub version: u32,
+    pub singers: Vec<BackupSinger>,
+}
+
+/// A persistent singer in a backup. Favorites are nested, so no database ids are stored.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupSinger {
+    pub name: String,
+    #[serde(default)]
+    pub unique_name: Option<String>,
+    pub color: String,
+    #[serde(default)]
+    pub online_id: Option<String>,
+    #[serde(default)]
+    pub favorites: Vec<BackupFavorite>,
+}
+
+/// A favorite in a backup
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct BackupFavorite {
+    #[serde(flatten)]
+    pub video: FavoriteVideo,
+    #[serde(default)]
+    pub added_at: Option<String>,
+}
+
+/// Result of importing a singers/favorites backup
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct SingersImportResult {
+    pub singers_created: u32,
+    /// Backup singers merged into an existing singer with the same unique name or name
+    pub singers_merged: u32,
+    pub favorites_imported: u32,
+}
+
+// ============ Helper Functions ============
+
+/// Escape LIKE wildcards so user input is matched literally (backslash is the escape character)
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Search favorites of all singers by title/artist (case-insensitive substring match)
+fn search_favorites(conn: &Connection, query: &str) -> Result<Vec<SingerFavoriteMatch>, rusqlite::Error> {
+    let pattern = format!("%{}%", escape_like(&query.trim().to_lowercase()));
+
+    let mut stmt = conn.prepare(
+        "SELECT f.id, f.singer_id, f.video_id, f.title, f.artist, f.duration, f.thumbnail_url, f.source,
+                f.youtube_id, f.file_path, f.added_at, s.name, s.color
+         FROM singer_favorites f
+         JOIN singers s ON s.id = f.singer_id
+         WHERE LOWER(f.title) LIKE ?1 ESCAPE '\\' OR LOWER(COALESCE(f.artist, '')) LIKE ?1 ESCAPE '\\'
+         ORDER BY f.title COLLATE NOCASE, s.name COLLATE NOCASE
+         LIMIT ?2",
+    )?;
+
+    let matches = stmt
+        .query_map(rusqlite::params![pattern, MAX_FAVORITES_SEARCH_RESULTS], |row| {
+            Ok(SingerFavoriteMatch {
+                singer_name: row.get(11)?,
+                singer_color: row.get(12)?,
+                favorite: SingerFavorite {
+                    id: row.get(0)?,
+                    singer_id: row.get(1)?,
+                    video: FavoriteVideo {
+                        video_id: row.get(2)?,
+                        title: row.get(3)?,
+                        artist: row.get(4)?,
+                        duration: row.get(5)?,
+                        thumbnail_url: row.get(6)?,
+                        source: row.get(7)?,
+                        youtube_id: row.get(8)?,
+                        file_path: row.get(9)?,
+                    },
+                    added_at: row.get(10)?,
+                },
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(matches)
+}
+
+/// Normalize a singer's YouTube favorites to canonical video IDs and remove duplicates,
+/// keeping the earliest added. Returns the number of favorites removed.
+fn dedupe_favorites(conn: &Connection, singer_id: i64) -> Result<u32, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, video_id, youtube_id FROM singer_favorites
+         WHERE singer_id = ?1 AND source = 'youtube'
+         ORDER BY added_at, id",
+    )?;
+    let rows = stmt
+        .query_map([singer_id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut duplicates: Vec<i64> = Vec::new();
+    let mut renames: Vec<(i64, String)> = Vec::new();
+
+    for (id, video_id, youtube_id) in rows {
+        let Some(canonical) = canonical_video_id(&video_id)
+            .or_else(|| youtube_id.as_deref().and_then(canonical_video_id))
+        else {
+            continue;
+        };
+
+        if !seen.insert(canonical.clone()) {
+            duplicates.push(id);
+        } else if video_id != canonical || youtube_id.as_deref() != Some(canonical.as_str()) {
+            renames.push((id, canonical));
+        }
+    }
+
+    // Delete duplicates first so renamed keepers don't hit UNIQUE(singer_id, video_id)
+    for id in &duplicates {
+        conn.execute("DELETE FROM singer_favorites WHERE id = ?1", [id])?;
+    }
+    for (id, canonical) in &renames {
+        conn.execute(
+            "UPDATE singer_favorites SET video_id = ?1, youtube_id = ?1 WHERE id = ?2",
+            rusqlite::params![canonical, id],
+        )?;
+    }
+
+    Ok(duplicates.len() as u32)
+}
+
+/// Favorites the singer hasn't sung in their last few sessions: never-sung songs first,
+/// then least recently sung, then oldest favorites. Singers with no history get all favorites.
+fn suggest_favorites(
+    conn: &Connection,
+    singer_id: i64,
+    limit: u32,
+) -> Result<Vec<FavoriteVideo>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "WITH singer_history AS (
+             SELECT qi.session_id, qi.video_id, COALESCE(qi.played_at, qi.added_at) AS sung_at
+             FROM queue_items qi
+             JOIN queue_singers qs ON qs.queue_item_id = qi.id
+             WHERE qi.item_type = 'history' AND qs.singer_id = ?1
+         ),
+         recent_sessions AS (
+             SELECT h.session_id
+             FROM singer_history h
+             JOIN sessions s ON s.id = h.session_id
+             WHERE s.deleted_at IS NULL
+             GROUP BY h.session_id
+             ORDER BY MAX(s.started_at) DESC, h.session_id DESC
+             LIMIT ?2
+         ),
+         last_sung AS (
+             SELECT video_id, MAX(sung_at) AS sung_at,
+                    MAX(session_id IN (SELECT session_id FROM recent_sessions)) AS sung_recently
+             FROM singer_history
+             GROUP BY video_id
+         )
+         SELECT f.video_id, f.title, f.artist, f.duration, f.thumbnail_url, f.source, f.youtube_id, f.file_path
+         FROM singer_favorites f
+         LEFT JOIN last_sung l ON l.video_id = f.video_id
+         WHERE f.singer_id = ?1 AND COALESCE(l.sung_recently, 0) = 0
+         ORDER BY l.sung_at IS NOT NULL, l.sung_at, f.added_at, f.id
+         LIMIT ?3",
+    )?;
+
+    let videos = stmt
+        .query_map(
+            rusqlite::params![singer_id, SUGGESTION_RECENT_SESSIONS, limit],
+            |row| {
+                Ok(FavoriteVideo {
+                    video_id: row.get(0)?,
+                    title: row.get(1)?,
+                    artist: row.get(2)?,
+                    duration: row.get(3)?,
+                    thumbnail_url: row.get(4)?,
+                    source: row.get(5)?,
+                    youtube_id: row.get(6)?,
+                    file_path: row.get(7)?,
+                })
+            },
+        )?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(videos)
+}
+
+/// Ensure a singer exists and is persistent (only persistent singers can have favorites)
+fn require_persistent_singer(conn: &Connection, singer_id: i64) -> Result<(), CommandError> {
+    let is_persistent: bool = conn
+        .query_row(
+            "SELECT is_persistent FROM singers WHERE id = ?1",
+            [singer_id],
+            |row| row.get::<_, i32>(0).map(|v| v != 0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => CommandError::NotFound {
+                resource: "Singer",
+                id: singer_id.to_string(),
+            },
+            _ => CommandError::Database(e),
+        })?;
+
+    if !is_persistent {
+        return Err(CommandError::Validation(
+            "Only persistent singers can have favorites".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Insert favorites, ignoring ones the singer already has. Returns the number inserted.
+fn insert_favorites(
+    conn: &Connection,
+    singer_id: i64,
+    videos: &[FavoriteVideo],
+) -> Result<u32, rusqlite::Error> {
+    let mut inserted = 0;
+    for video in videos {
+        inserted += conn.execute(
+            "INSERT OR IGNORE INTO singer_favorites
+             (singer_id, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+            rusqlite::params![
+                singer_id,
+                video.video_id,
+                video.title,
+                video.artist,
+                video.duration,
+                video.thumbnail_url,
+                video.source,
+                video.youtube_id,
+                video.file_path,
+            ],
+        )? as u32;
+    }
+    Ok(inserted)
+}
+
+/// All persistent singers with their favorites (oldest first)
+fn export_singers_backup(conn: &Connection) -> Result<SingersBackup, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, name, unique_name, color, online_id FROM singers
+         WHERE is_persistent = 1 ORDER BY name, id",
+    )?;
+    let singers = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                BackupSinger {
+                    name: row.get(1)?,
+                    unique_name: row.get(2)?,
+                    color: row.get(3)?,
+                    online_id: row.get(4)?,
+                    favorites: Vec::new(),
+                },
+            ))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut stmt = conn.prepare(
+        "SELECT video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, added_at
+         FROM singer_favorites WHERE singer_id = ?1 ORDER BY added_at, id",
+    )?;
+    let mut backup_singers = Vec::with_capacity(singers.len());
+    for (singer_id, mut singer) in singers {
+        singer.favorites = stmt
+            .query_map([singer_id], |row| {
+                Ok(BackupFavorite {
+                    video: FavoriteVideo {
+                        video_id: row.get(0)?,
+                        title: row.get(1)?,
+                        artist: row.get(2)?,
+                        duration: row.get(3)?,
+                        thumbnail_url: row.get(4)?,
+                        source: row.get(5)?,
+                        youtube_id: row.get(6)?,
+                        file_path: row.get(7)?,
+                    },
+                    added_at: row.get(8)?,
+                })
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        backup_singers.push(singer);
+    }
+
+    Ok(SingersBackup {
+        version: SINGERS_BACKUP_VERSION,
+        singers: backup_singers,
+    })
+}
+
+/// Reject backups from a newer version or with data the database would refuse
+fn validate_singers_backup(backup: &SingersBackup) -> Result<(), CommandError> {
+    if backup.version == 0 || backup.version > SINGERS_BACKUP_VERSION {
+        return Err(CommandError::Validation(format!(
+            "Unsupported backup version {} (expected {})",
+            backup.version, SINGERS_BACKUP_VERSION
+        )));
+    }
+    for singer in &backup.singers {
+        let name = singer.name.trim();
+        if name.is_empty() || name.len() > MAX_BACKUP_NAME_LENGTH {
+            return Err(CommandError::Validation(format!(
+                "Invalid singer name in backup: {:?}",
+                singer.name
+            )));
+        }
+        for favorite in &singer.favorites {
+            let video = &favorite.video;
+            if video.video_id.is_empty() || video.title.trim().is_empty() {
+                return Err(CommandError::Validation(format!(
+                    "Favorite without a video id or title for singer {}",
+                    name
+                )));
+            }
+            if !matches!(video.source.as_str(), "youtube" | "local" | "external") {
+                return Err(CommandError::Validation(format!(
+                    "Unknown favorite source: {}",
+                    video.source
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Existing persistent singer a backup singer merges into: same unique name if the
+/// backup has one, otherwise same name (both case-insensitive)
+fn find_merge_target(conn: &Connection, singer: &BackupSinger) -> Result<Option<i64>, rusqlite::Error> {
+    let unique_name = singer
+        .unique_name
+        .as_deref()
+        .map(str::trim)
+        .filter(|n| !n.is_empty());
+    let (sql, key) = match unique_name {
+        Some(unique_name) => (
+            "SELECT id FROM singers WHERE is_persistent = 1 AND unique_name = ?1 COLLATE NOCASE ORDER BY id LIMIT 1",
+            unique_name,
+        ),
+        None => (
+            "SELECT id FROM singers WHERE is_persistent = 1 AND name = ?1 COLLATE NOCASE ORDER BY id LIMIT 1",
+            singer.name.trim(),
+        ),
+    };
+    conn.query_row(sql, [key], |row| row.get(0)).optional()
+}
+
+/// Import a validated backup. Without `merge`, all persistent singers (and their
+/// favorites) are removed first; with it, backup singers are matched to existing
+/// ones and only new favorites are added. Database ids are always newly assigned.
+fn import_singers_backup(
+    conn: &Connection,
+    backup: &SingersBackup,
+    merge: bool,
+) -> Result<SingersImportResult, rusqlite::Error> {
+    if !merge {
+        conn.execute(
+            "UPDATE sessions SET active_singer_id = NULL
+             WHERE active_singer_id IN (SELECT id FROM singers WHERE is_persistent = 1)",
+            [],
+        )?;
+        conn.execute("DELETE FROM singers WHERE is_persistent = 1", [])?;
+    }
+
+    let mut result = SingersImportResult::default();
+    for singer in &backup.singers {
+        let singer_id = match find_merge_target(conn, singer)? {
+            Some(id) if merge => {
+                result.singers_merged += 1;
+                id
+            }
+            _ => {
+                conn.execute(
+                    "INSERT INTO singers (name, color, is_persistent, unique_name, online_id) VALUES (?1, ?2, 1, ?3, ?4)",
+                    rusqlite::params![singer.name.trim(), singer.color, singer.unique_name, singer.online_id],
+                )?;
+                result.singers_created += 1;
+                conn.last_insert_rowid()
+            }
+        };
+
+        for favorite in &singer.favorites {
+            let video = &favorite.video;
+            result.favorites_imported += conn.execute(
+                "INSERT OR IGNORE INTO singer_favorites
+                 (singer_id, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, added_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, COALESCE(?10, CURRENT_TIMESTAMP))",
+                rusqlite::params![
+                    singer_id,
+                    video.video_id,
+                    video.title,
+                    video.artist,
+                    video.duration,
+                    video.thumbnail_url,
+                    video.source,
+                    video.youtube_id,
+                    video.file_path,
+                    favorite.added_at,
+                ],
+            )? as u32;
+        }
+        // The same YouTube video may be stored under different id spellings
+        result.favorites_imported = result
+            .favorites_imported
+            .saturating_sub(dedupe_favorites(conn, singer_id)?);
+    }
+    Ok(result)
+}
+
+/// Favorites parsed from a CSV song list
+#[derive(Debug, Default, PartialEq)]
+struct ParsedFavoritesCsv {
+    videos: Vec<FavoriteVideo>,
+    /// Rows without a title or without a YouTube ID/local file to play
+    skipped: u32,
+}
+
+/// Parse a CSV song list with columns `title, artist, youtube_id` (`;` or `,` delimited).
+///
+/// A header row is detected by a `title` column, and may also name a `file`/`path`
+/// column for local songs. The third positional column accepts a YouTube ID or URL,
+/// or an absolute file path for local songs.
+fn parse_favorites_csv(content: &str) -> ParsedFavoritesCsv {
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}'))
+        .filter(|l| !l.trim().is_empty())
+        .peekable();
+    let mut parsed = ParsedFavoritesCsv::default();
+
+    let first = match lines.peek() {
+        Some(line) => *line,
+        None => return parsed,
+    };
+    let delimiter = if first.contains(';') { ';' } else { ',' };
+
+    // Column indexes: title, artist, youtube id, file path
+    let header: Vec<String> = split_csv_line(first, delimiter)
+        .iter()
+        .map(|c| c.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| header.iter().position(|c| names.contains(&c.as_str()));
+    let (title_col, artist_col, youtube_col, file_col) = match column(&["title", "song", "song title"]) {
+        Some(title_col) => {
+            lines.next();
+            (
+                title_col,
+                column(&["artist", "singer", "performer"]),
+                column(&["youtube_id", "youtube id", "youtube", "video_id", "url"]),
+                column(&["file", "path", "file_path", "filename"]),
+            )
+        }
+        None => (0, Some(1), Some(2), None),
+    };
+
+    for line in lines {
+        let fields = split_csv_line(line, delimiter);
+        let field = |col: Option<usize>| {
+            col.and_then(|c| fields.get(c))
+                .map(|v| v.trim())
+                .filter(|v| !v.is_empty())
+        };
+
+        let title = match field(Some(title_col)) {
+            Some(title) => title.to_string(),
+            None => {
+                parsed.skipped += 1;
+                continue;
+            }
+        };
+        let artist = field(artist_col).map(String::from);
+        let youtube_field = field(youtube_col);
+        let youtube_id = youtube_field.and_then(canonical_video_id);
+        let file_path = field(file_col)
+            .or_else(|| youtube_field.filter(|v| youtube_id.is_none() && (v.starts_with('/') || Path::new(v).is_absolute())));
+
+        let video = match (youtube_id, file_path) {
+            (Some(id), _) => FavoriteVideo {
+                video_id: id.clone(),
+                title,
+                artist,
+                duration: None,
+                thumbnail_url: None,
+                source: "youtube".to_string(),
+                youtube_id: Some(id),
+                file_path: None,
+            },
+            (None, Some(path)) => FavoriteVideo {
+                video_id: path.to_string(),
+                title,
+                artist,
+                duration: None,
+                thumbnail_url: None,
+                source: "local".to_string(),
+                youtube_id: None,
+                file_path: Some(path.to_string()),
+            },
+            (None, None) => {
+                parsed.skipped += 1;
+                continue;
+            }
+        };
+        parsed.videos.push(video);
+    }
+
+    parsed
+}
+
 // ============ Favorites Commands ============
 
 #[tauri::command]
 pub fn add_favorite(
     state: State<'_, AppState>,
     singer_id: i64,
-    video: FavoriteVideo,
+    mut video: FavoriteVideo,
 ) -> Result<SingerFavorite, CommandError> {
     info!(
         "Adding favorite for singer {}: {}",
         singer_id, video.title
     );
+    normalize_video_ids(&video.source, &mut video.video_id, &mut video.youtube_id)?;
     let db = state.db.lock().map_lock_err()?;
 
     // Verify singer exists and is persistent
@@ -190,60 +743,45 @@ pub fn check_video_favorites(
     Ok(singer_ids)
 }
 
+/// Search favorites across all singers by title or artist
+#[tauri::command]
+pub fn search_all_favorites(
+    state: State<'_, AppState>,
+    query: String,
+) -> Result<Vec<SingerFavoriteMatch>, CommandError> {
+    debug!("Searching favorites of all singers for: {}", query);
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let db = state.db.lock().map_lock_err()?;
+    let matches = search_favorites(db.connection(), &query)?;
+
+    debug!("Found {} favorite matches", matches.len());
+    Ok(matches)
+}
+
 #[tauri::command]
 pub fn bulk_add_favorites(
     state: State<'_, AppState>,
     singer_id: i64,
-    videos: Vec<FavoriteVideo>,
+    mut videos: Vec<FavoriteVideo>,
 ) -> Result<Vec<SingerFavorite>, CommandError> {
     info!(
         "Bulk adding {} favorites for singer {}",
         videos.len(),
         singer_id
     );
+    for video in &mut videos {
+        normalize_video_ids(&video.source, &mut video.video_id, &mut video.youtube_id)?;
+    }
     let db = state.db.lock().map_lock_err()?;
 
     // Verify singer exists and is persistent
-    let is_persistent: bool = db
-        .connection()
-        .query_row(
-            "SELECT is_persistent FROM singers WHERE id = ?1",
-            [singer_id],
-            |row| row.get::<_, i32>(0).map(|v| v != 0),
-        )
-        .map_err(|e| match e {
-            rusqlite::Error::QueryReturnedNoRows => CommandError::NotFound {
-                resource: "Singer",
-                id: singer_id.to_string(),
-            },
-            _ => CommandError::Database(e),
-        })?;
-
-    if !is_persistent {
-        return Err(CommandError::Validation(
-            "Only persistent singers can have favorites".to_string(),
-        ));
-    }
+    require_persistent_singer(db.connection(), singer_id)?;
 
     // Insert all favorites (protected by Mutex lock)
-    for video in &videos {
-        db.connection().execute(
-            "INSERT OR IGNORE INTO singer_favorites
-             (singer_id, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
-            rusqlite::params![
-                singer_id,
-                video.video_id,
-                video.title,
-                video.artist,
-                video.duration,
-                video.thumbnail_url,
-                video.source,
-                video.youtube_id,
-                video.file_path,
-            ],
-        )?;
-    }
+    insert_favorites(db.connection(), singer_id, &videos)?;
 
     // Return all favorites for this singer
     let mut stmt = db.connection().prepare(
@@ -273,3 +811,533 @@ pub fn bulk_add_favorites(
 
     Ok(favorites)
 }
+
+/// Merge duplicate favorites whose video IDs differ only in format
+/// (e.g. with URL params), keeping the earliest. Returns the number removed.
+#[tauri::command]
+pub fn dedupe_singer_favorites(
+    state: State<'_, AppState>,
+    singer_id: i64,
+) -> Result<u32, CommandError> {
+    info!("Deduplicating favorites for singer {}", singer_id);
+    let mut db = state.db.lock().map_lock_err()?;
+
+    let singer_exists: bool = db.connection().query_row(
+        "SELECT EXISTS(SELECT 1 FROM singers WHERE id = ?1)",
+        [singer_id],
+        |row| row.get(0),
+    )?;
+    if !singer_exists {
+        return Err(CommandError::NotFound {
+            resource: "Singer",
+            id: singer_id.to_string(),
+        });
+    }
+
+    let tx = db.connection_mut().transaction()?;
+    let removed = dedupe_favorites(&tx, singer_id)?;
+    tx.commit()?;
+
+    info!("Removed {} duplicate favorites for singer {}", removed, singer_id);
+    Ok(removed)
+}
+
+/// Suggest favorites for a singer to sing next ("what should I sing?"),
+/// skipping songs they sang in their recent sessions
+#[tauri::command]
+pub fn suggest_songs_for_singer(
+    state: State<'_, AppState>,
+    singer_id: i64,
+    limit: Option<u32>,
+) -> Result<Vec<FavoriteVideo>, CommandError> {
+    let limit = limit.unwrap_or(DEFAULT_SUGGESTIONS).clamp(1, MAX_SUGGESTIONS);
+    debug!("Suggesting up to {} songs for singer {}", limit, singer_id);
+    let db = state.db.lock().map_lock_err()?;
+
+    let singer_exists: bool = db.connection().query_row(
+        "SELECT EXISTS(SELECT 1 FROM singers WHERE id = ?1)",
+        [singer_id],
+        |row| row.get(0),
+    )?;
+    if !singer_exists {
+        return Err(CommandError::NotFound {
+            resource: "Singer",
+            id: singer_id.to_string(),
+        });
+    }
+
+    Ok(suggest_favorites(db.connection(), singer_id, limit)?)
+}
+
+/// Result of importing favorites from a CSV file
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct FavoritesCsvImportResult {
+    /// New favorites added
+    pub imported: u32,
+    /// Valid rows the singer already had as favorites
+    pub already_favorited: u32,
+    /// Malformed rows (no title, or nothing to play)
+    pub skipped: u32,
+}
+
+/// Import a singer's favorites from a CSV song list (e.g. exported from other karaoke software)
+#[tauri::command]
+pub fn import_favorites_csv(
+    state: State<'_, AppState>,
+    singer_id: i64,
+    csv_path: String,
+) -> Result<FavoritesCsvImportResult, CommandError> {
+    info!("Importing favorites for singer {} from {}", singer_id, csv_path);
+    let mut db = state.db.lock().map_lock_err()?;
+
+    // Check the singer before touching the file
+    require_persistent_singer(db.connection(), singer_id)?;
+
+    let path = Path::new(&csv_path);
+    let size = fs::metadata(path)
+        .map_err(|e| CommandError::Validation(format!("Cannot read CSV file: {}", e)))?
+        .len();
+    if size > MAX_FAVORITES_CSV_SIZE {
+        return Err(CommandError::Validation(format!(
+            "CSV file is too large ({} bytes)",
+            size
+        )));
+    }
+    let content = fs::read(path)
+        .map_err(|e| CommandError::Validation(format!("Cannot read CSV file: {}", e)))?;
+    // Song lists from older software are often not UTF-8
+    let parsed = parse_favorites_csv(&String::from_utf8_lossy(&content));
+
+    let tx = db.connection_mut().transaction()?;
+    let imported = insert_favorites(&tx, singer_id, &parsed.videos)?;
+    tx.commit()?;
+
+    let result = FavoritesCsvImportResult {
+        imported,
+        already_favorited: parsed.videos.len() as u32 - imported,
+        skipped: parsed.skipped,
+    };
+    info!(
+        "Imported {} favorites for singer {} ({} already favorited, {} skipped)",
+        result.imported, singer_id, result.already_favorited, result.skipped
+    );
+    Ok(result)
+}
+
+/// Export all persistent singers and their favorites as versioned JSON
+#[tauri::command]
+pub fn export_singers_and_favorites(state: State<'_, AppState>) -> Result<String, CommandError> {
+    debug!("Exporting singers and favorites");
+    let db = state.db.lock().map_lock_err()?;
+
+    let backup = export_singers_backup(db.connection())?;
+    info!("Exported {} singers with their favorites", backup.singers.len());
+    Ok(serde_json::to_string_pretty(&backup)?)
+}
+
+/// Import singers and favorites from `export_singers_and_favorites` JSON, either
+/// merging into the existing singers or replacing all persistent singers
+#[tauri::command]
+pub fn import_singers_and_favorites(
+    state: State<'_, AppState>,
+    json: String,
+    merge: bool,
+) -> Result<SingersImportResult, CommandError> {
+    info!("Importing singers and favorites (merge: {})", merge);
+    let backup: SingersBackup = serde_json::from_str(&json)?;
+    validate_singers_backup(&backup)?;
+
+    let mut db = state.db.lock().map_lock_err()?;
+    let tx = db.connection_mut().transaction()?;
+    let result = import_singers_backup(&tx, &backup, merge)?;
+    tx.commit()?;
+
+    info!(
+        "Imported singers: {} created, {} merged, {} favorites added",
+        result.singers_created, result.singers_merged, result.favorites_imported
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn add_singer(conn: &Connection, name: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO singers (name, color, is_persistent) VALUES (?1, '#ff0000', 1)",
+            [name],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn add_fav(conn: &Connection, singer_id: i64, video_id: &str, title: &str, artist: Option<&str>) {
+        conn.execute(
+            "INSERT INTO singer_favorites (singer_id, video_id, title, artist, source, youtube_id)
+             VALUES (?1, ?2, ?3, ?4, 'youtube', ?2)",
+            rusqlite::params![singer_id, video_id, title, artist],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn test_search_favorites_across_singers() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        let bob = add_singer(&conn, "Bob");
+        add_fav(&conn, alice, "yt1", "Bohemian Rhapsody", Some("Queen"));
+        add_fav(&conn, bob, "yt1", "Bohemian Rhapsody", Some("Queen"));
+        add_fav(&conn, bob, "yt2", "Take On Me", Some("a-ha"));
+
+        let matches = search_favorites(&conn, "bohemian").unwrap();
+        assert_eq!(matches.len(), 2);
+        let names: Vec<&str> = matches.iter().map(|m| m.singer_name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Bob"]);
+        assert_eq!(matches[0].favorite.singer_id, alice);
+        assert_eq!(matches[1].favorite.singer_id, bob);
+
+        // Artist match is case-insensitive
+        let matches = search_favorites(&conn, "QUEEN").unwrap();
+        assert_eq!(matches.len(), 2);
+
+        let matches = search_favorites(&conn, "A-HA").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].singer_name, "Bob");
+    }
+
+    #[test]
+    fn test_dedupe_favorites_merges_normalized_ids() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        conn.execute(
+            "INSERT INTO singer_favorites (singer_id, video_id, title, source, youtube_id, added_at)
+             VALUES (?1, 'dQw4w9WgXcQ&t=30s', 'Never Gonna Give You Up', 'youtube', 'dQw4w9WgXcQ&t=30s', '2024-01-01 10:00:00')",
+            [alice],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO singer_favorites (singer_id, video_id, title, source, youtube_id, added_at)
+             VALUES (?1, 'dQw4w9WgXcQ', 'Never Gonna Give You Up', 'youtube', 'dQw4w9WgXcQ', '2024-06-01 10:00:00')",
+            [alice],
+        )
+        .unwrap();
+        add_fav(&conn, alice, "9bZkp7q19f0", "Gangnam Style", None);
+
+        assert_eq!(dedupe_favorites(&conn, alice).unwrap(), 1);
+
+        // Earliest favorite is kept, with its ID normalized
+        let (video_id, youtube_id, added_at): (String, String, String) = conn
+            .query_row(
+                "SELECT video_id, youtube_id, added_at FROM singer_favorites
+                 WHERE singer_id = ?1 AND title = 'Never Gonna Give You Up'",
+                [alice],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(video_id, "dQw4w9WgXcQ");
+        assert_eq!(youtube_id, "dQw4w9WgXcQ");
+        assert_eq!(added_at, "2024-01-01 10:00:00");
+
+        let count: i32 = conn
+            .query_row("SELECT COUNT(*) FROM singer_favorites WHERE singer_id = ?1", [alice], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(count, 2);
+
+        // Idempotent
+        assert_eq!(dedupe_favorites(&conn, alice).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_search_favorites_escapes_wildcards() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        add_fav(&conn, alice, "yt1", "100% Pure Love", None);
+        add_fav(&conn, alice, "yt2", "Pure Love", None);
+
+        let matches = search_favorites(&conn, "100%").unwrap();
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].favorite.video.video_id, "yt1");
+
+        assert!(search_favorites(&conn, "_").unwrap().is_empty());
+    }
+
+    /// Record a played history item for a singer in a session
+    fn add_sung(conn: &Connection, session_id: i64, singer_id: i64, video_id: &str, played_at: &str) {
+        let item_id = format!("{}-{}-{}", session_id, singer_id, video_id);
+        conn.execute(
+            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at, played_at)
+             VALUES (?1, ?2, 'history', ?3, ?3, 'youtube', 0, ?4, ?4)",
+            rusqlite::params![item_id, session_id, video_id, played_at],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO queue_singers (queue_item_id, singer_id) VALUES (?1, ?2)",
+            rusqlite::params![item_id, singer_id],
+        )
+        .unwrap();
+    }
+
+    fn add_session(conn: &Connection, started_at: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO sessions (name, is_active, started_at) VALUES (NULL, 0, ?1)",
+            [started_at],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    #[test]
+    fn test_suggest_favorites_without_history_returns_all() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        add_fav(&conn, alice, "yt1", "Song 1", None);
+        add_fav(&conn, alice, "yt2", "Song 2", None);
+
+        let suggestions = suggest_favorites(&conn, alice, 10).unwrap();
+        assert_eq!(suggestions.len(), 2);
+        assert_eq!(suggest_favorites(&conn, alice, 1).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_suggest_favorites_skips_recent_and_orders_least_recent() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        let bob = add_singer(&conn, "Bob");
+        for video_id in ["old", "older", "recent", "never", "bob-sang"] {
+            add_fav(&conn, alice, video_id, video_id, None);
+        }
+
+        // Oldest sessions fall outside the recent window
+        let s1 = add_session(&conn, "2024-01-01 20:00:00");
+        let s2 = add_session(&conn, "2024-02-01 20:00:00");
+        let s3 = add_session(&conn, "2024-03-01 20:00:00");
+        let s4 = add_session(&conn, "2024-04-01 20:00:00");
+        let s5 = add_session(&conn, "2024-05-01 20:00:00");
+        add_sung(&conn, s1, alice, "older", "2024-01-01 21:00:00");
+        add_sung(&conn, s2, alice, "old", "2024-02-01 21:00:00");
+        add_sung(&conn, s3, alice, "filler-1", "2024-03-01 21:00:00");
+        add_sung(&conn, s4, alice, "filler-2", "2024-04-01 21:00:00");
+        add_sung(&conn, s5, alice, "recent", "2024-05-01 21:00:00");
+        // Another singer's history doesn't affect Alice
+        add_sung(&conn, s5, bob, "bob-sang", "2024-05-01 21:30:00");
+
+        let ids: Vec<String> = suggest_favorites(&conn, alice, 10)
+            .unwrap()
+            .into_iter()
+            .map(|v| v.video_id)
+            .collect();
+        assert_eq!(ids, vec!["never", "bob-sang", "older", "old"]);
+    }
+
+    #[test]
+    fn test_parse_favorites_csv_with_header() {
+        let csv = "\u{feff}Artist;Title;YouTube ID\n\
+                   Queen;Bohemian Rhapsody;https://youtu.be/fJ9rUzIMcZQ\n\
+                   \"Simon; Garfunkel\";\"The Sound of \"\"Silence\"\"\";dQw4w9WgXcQ&t=30s\n\
+                   Nobody;;fJ9rUzIMcZQ\n\
+                   Nobody;No Video;\n";
+        let parsed = parse_favorites_csv(csv);
+
+        assert_eq!(parsed.skipped, 2);
+        assert_eq!(parsed.videos.len(), 2);
+        assert_eq!(parsed.videos[0].video_id, "fJ9rUzIMcZQ");
+        assert_eq!(parsed.videos[0].title, "Bohemian Rhapsody");
+        assert_eq!(parsed.videos[0].artist.as_deref(), Some("Queen"));
+        assert_eq!(parsed.videos[0].source, "youtube");
+        assert_eq!(parsed.videos[1].title, "The Sound of \"Silence\"");
+        assert_eq!(parsed.videos[1].artist.as_deref(), Some("Simon; Garfunkel"));
+        assert_eq!(parsed.videos[1].youtube_id.as_deref(), Some("dQw4w9WgXcQ"));
+    }
+
+    #[test]
+    fn test_parse_favorites_csv_without_header() {
+        let csv = "Take On Me,a-ha,djV11Xbc914\nLocal Song,Someone,/music/Someone - Local Song.mp4\nTitle Only\n";
+        let parsed = parse_favorites_csv(csv);
+
+        assert_eq!(parsed.skipped, 1);
+        assert_eq!(parsed.videos.len(), 2);
+        assert_eq!(parsed.videos[0].video_id, "djV11Xbc914");
+        assert_eq!(parsed.videos[1].source, "local");
+        assert_eq!(parsed.videos[1].video_id, "/music/Someone - Local Song.mp4");
+        assert_eq!(
+            parsed.videos[1].file_path.as_deref(),
+            Some("/music/Someone - Local Song.mp4")
+        );
+
+        assert_eq!(parse_favorites_csv(""), ParsedFavoritesCsv::default());
+    }
+
+    #[test]
+    fn test_insert_favorites_counts_new_rows_only() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        add_fav(&conn, alice, "fJ9rUzIMcZQ", "Bohemian Rhapsody", None);
+
+        let parsed = parse_favorites_csv("Bohemian Rhapsody,Queen,fJ9rUzIMcZQ\nTake On Me,a-ha,djV11Xbc914\n");
+        assert_eq!(insert_favorites(&conn, alice, &parsed.videos).unwrap(), 1);
+
+        // Temporary singers can't have favorites
+        conn.execute("INSERT INTO singers (name, color, is_persistent) VALUES ('Guest', '#fff', 0)", [])
+            .unwrap();
+        let guest = conn.last_insert_rowid();
+        assert!(matches!(
+            require_persistent_singer(&conn, guest),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            require_persistent_singer(&conn, 999),
+            Err(CommandError::NotFound { .. })
+        ));
+    }
+
+    fn favorite_ids(conn: &Connection, singer_id: i64) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT video_id FROM singer_favorites WHERE singer_id = ?1 ORDER BY video_id")
+            .unwrap();
+        stmt.query_map([singer_id], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<String>, _>>()
+            .unwrap()
+    }
+
+    fn singer_id_by_name(conn: &Connection, name: &str) -> i64 {
+        conn.query_row("SELECT id FROM singers WHERE name = ?1", [name], |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_singers_backup_round_trip_replaces() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "Alice");
+        add_fav(&conn, alice, "fJ9rUzIMcZQ", "Bohemian Rhapsody", Some("Queen"));
+        add_fav(&conn, alice, "djV11Xbc914", "Take On Me", Some("a-ha"));
+        add_singer(&conn, "Bob");
+        conn.execute("INSERT INTO singers (name, color, is_persistent) VALUES ('Guest', '#fff', 0)", [])
+            .unwrap();
+
+        let backup = export_singers_backup(&conn).unwrap();
+        assert_eq!(backup.version, SINGERS_BACKUP_VERSION);
+        let names: Vec<&str> = backup.singers.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["Alice", "Bob"]);
+        assert_eq!(backup.singers[0].favorites.len(), 2);
+
+        // Through JSON, as the commands do
+        let json = serde_json::to_string(&backup).unwrap();
+        let backup: SingersBackup = serde_json::from_str(&json).unwrap();
+        validate_singers_backup(&backup).unwrap();
+
+        add_singer(&conn, "Carol");
+        let result = import_singers_backup(&conn, &backup, false).unwrap();
+        assert_eq!(
+            result,
+            SingersImportResult { singers_created: 2, singers_merged: 0, favorites_imported: 2 }
+        );
+
+        // Carol is gone, Alice got a new id with her favorites, the guest is untouched
+        let persistent: i64 = conn
+            .query_row("SELECT COUNT(*) FROM singers WHERE is_persistent = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(persistent, 2);
+        let new_alice = singer_id_by_name(&conn, "Alice");
+        assert_ne!(new_alice, alice);
+        assert_eq!(favorite_ids(&conn, new_alice), vec!["djV11Xbc914", "fJ9rUzIMcZQ"]);
+        singer_id_by_name(&conn, "Guest");
+    }
+
+    #[test]
+    fn test_singers_backup_merge_dedupes_favorites() {
+        let conn = setup_test_db();
+        let alice = add_singer(&conn, "alice");
+        add_fav(&conn, alice, "fJ9rUzIMcZQ", "Bohemian Rhapsody", None);
+
+        let favorite = |video_id: &str| BackupFavorite {
+            video: FavoriteVideo {
+                video_id: video_id.to_string(),
+                title: "Song".to_string(),
+                artist: None,
+                duration: None,
+                thumbnail_url: None,
+                source: "youtube".to_string(),
+                youtube_id: Some(video_id.to_string()),
+                file_path: None,
+            },
+            added_at: None,
+        };
+        let backup = SingersBackup {
+            version: 1,
+            singers: vec![
+                BackupSinger {
+                    name: "Alice".to_string(),
+                    unique_name: None,
+                    color: "#00ff00".to_string(),
+                    online_id: None,
+                    favorites: vec![
+                        favorite("fJ9rUzIMcZQ"),
+                        favorite("https://youtu.be/fJ9rUzIMcZQ"),
+                        favorite("djV11Xbc914"),
+                    ],
+                },
+                BackupSinger {
+                    name: "Dave".to_string(),
+                    unique_name: Some("Dave K".to_string()),
+                    color: "#0000ff".to_string(),
+                    online_id: None,
+                    favorites: vec![favorite("djV11Xbc914")],
+                },
+            ],
+        };
+
+        let result = import_singers_backup(&conn, &backup, true).unwrap();
+        assert_eq!(
+            result,
+            SingersImportResult { singers_created: 1, singers_merged: 1, favorites_imported: 2 }
+        );
+        assert_eq!(favorite_ids(&conn, alice), vec!["djV11Xbc914", "fJ9rUzIMcZQ"]);
+
+        // Importing again adds nothing
+        let result = import_singers_backup(&conn, &backup, true).unwrap();
+        assert_eq!(
+            result,
+            SingersImportResult { singers_created: 0, singers_merged: 2, favorites_imported: 0 }
+        );
+    }
+
+    #[test]
+    fn test_validate_singers_backup() {
+        let backup = |version: u32, name: &str| SingersBackup {
+            version,
+            singers: vec![BackupSinger {
+                name: name.to_string(),
+                unique_name: None,
+                color: "#fff".to_string(),
+                online_id: None,
+                favorites: Vec::new(),
+            }],
+        };
+        assert!(validate_singers_backup(&backup(1, "Alice")).is_ok());
+        assert!(matches!(
+            validate_singers_backup(&backup(2, "Alice")),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_singers_backup(&backup(0, "Alice")),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            validate_singers_backup(&backup(1, "  ")),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(serde_json::from_str::<SingersBackup>(r#"{"singers": []}"#).is_err());
+    }
+}
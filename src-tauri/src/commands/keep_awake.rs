@@ -1,13 +1,10 @@
-use super::errors::CommandError;
+use super::errors::{CommandError, LockRecoverExt};
 use crate::AppState;
 use tauri::State;
 
 #[tauri::command]
 pub fn keep_awake_enable(state: State<AppState>) -> Result<(), CommandError> {
-    let mut guard = state
-        .keep_awake
-        .lock()
-        .map_err(|_| CommandError::MutexPoisoned("Keep awake"))?;
+    let mut guard = state.keep_awake.lock_recover("Keep awake");
 
     if guard.is_none() {
         let awake = keepawake::Builder::default()
@@ -28,10 +25,7 @@ pub fn keep_awake_enable(state: State<AppState>) -> Result<(), CommandError> {
 
 #[tauri::command]
 pub fn keep_awake_disable(state: State<AppState>) -> Result<(), CommandError> {
-    let mut guard = state
-        .keep_awake
-        .lock()
-        .map_err(|_| CommandError::MutexPoisoned("Keep awake"))?;
+    let mut guard = state.keep_awake.lock_recover("Keep awake");
 
     if guard.is_some() {
         *guard = None;
@@ -1,12 +1,58 @@
-use crate::services::{LibraryFolder, LibraryScanner, LibraryStats, LibraryVideo, ScanOptions, ScanResult};
+use crate::services::{
+    export_catalog, import_catalog, write_catalog_export, ArtistCount, CacheStatus, CatalogExport,
+    CatalogFormat, CatalogImportResult, DuplicateContentGroup, DurationMismatch,
+    FilenameParsePreview, FolderProbe, LibraryDistribution, LibraryFacets, LibraryFolder, LibraryScanner, LibraryStats,
+    LibraryVideo, MetadataDurationMismatch, MetadataMergeResult, MigrationResult, ScanOptions, ScanProgress, ScanResult,
+    ThumbResult, ThumbnailSource,
+};
+use crate::services::ffmpeg::FfmpegService;
+use crate::services::metadata_fetcher::{LyricsCandidate, LyricsResult, MetadataFetcher};
+use crate::services::thumbnail_data;
+use crate::services::ytdlp::Chapter;
 use crate::AppState;
 use log::{debug, info, warn};
-use rusqlite::params;
-use tauri::{AppHandle, Manager, State};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::Ordering;
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Maximum number of search results to return (prevents performance issues)
 const MAX_SEARCH_LIMIT: u32 = 1000;
 
+/// Maximum number of files to include in a filename parsing preview
+const MAX_PREVIEW_LIMIT: u32 = 200;
+
+/// Event emitted while fetching song info and lyrics during a scan
+const SCAN_PROGRESS_EVENT: &str = "library:scan-progress";
+
+/// Event emitted while hashing files for `library_find_duplicate_content`
+const DUPLICATE_SCAN_PROGRESS_EVENT: &str = "library:duplicate-scan-progress";
+
+/// Event emitted while `library_generate_thumbnails` runs
+const THUMBNAIL_PROGRESS_EVENT: &str = "library:thumbnail-progress";
+
+/// Subdirectory of the app cache directory holding downloaded YouTube thumbnails
+const THUMBNAIL_CACHE_DIR: &str = "thumbnails";
+
+/// Maximum number of files `library_bulk_tag` changes at once
+const MAX_BULK_TAG_FILES: usize = 1000;
+
+/// Maximum length of a single tag
+const MAX_TAG_LENGTH: usize = 50;
+
+/// Stored durations differing from the file's length by more than this fraction are flagged
+const DURATION_AUDIT_THRESHOLD: f64 = 0.15;
+
+/// Progress of a running duplicate content scan
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateScanProgress {
+    /// Files hashed so far
+    pub processed: u32,
+    /// Video files found across all library folders
+    pub total: u32,
+}
+
 /// Forbidden system paths that should not be added to the library
 const FORBIDDEN_PATHS: &[&str] = &[
     "/System",
@@ -21,13 +67,10 @@ const FORBIDDEN_PATHS: &[&str] = &[
     "/tmp",
 ];
 
-/// Add a folder to the library
-#[tauri::command]
-pub fn library_add_folder(app: AppHandle, state: State<'_, AppState>, path: String) -> Result<LibraryFolder, String> {
-    info!("Adding library folder: {}", path);
-
+/// Check that a path can be a library folder, returning its canonical form
+fn validate_library_path(path: &str) -> Result<PathBuf, String> {
     // Validate the path exists and is a directory
-    let path_obj = std::path::Path::new(&path);
+    let path_obj = std::path::Path::new(path);
     if !path_obj.exists() {
         return Err(format!("Path does not exist: {}", path));
     }
@@ -54,8 +97,35 @@ pub fn library_add_folder(app: AppHandle, state: State<'_, AppState>, path: Stri
         }
     }
 
+    Ok(canonical_path)
+}
+
+/// Add a library folder (and its .homekaraoke directory) to the asset protocol scope for thumbnails
+fn allow_folder_assets(app: &AppHandle, canonical_path: &Path) {
+    let asset_scope = app.asset_protocol_scope();
+    if let Err(e) = asset_scope.allow_directory(canonical_path, true) {
+        warn!("Failed to add {:?} to asset scope: {}", canonical_path, e);
+    } else {
+        debug!("Added {:?} to asset protocol scope", canonical_path);
+    }
+    // Also add .homekaraoke subdirectory for thumbnails
+    let homekaraoke_dir = canonical_path.join(".homekaraoke");
+    if let Err(e) = asset_scope.allow_directory(&homekaraoke_dir, true) {
+        warn!("Failed to add {:?} to asset scope: {}", homekaraoke_dir, e);
+    } else {
+        debug!("Added {:?} to asset protocol scope", homekaraoke_dir);
+    }
+}
+
+/// Add a folder to the library
+#[tauri::command]
+pub fn library_add_folder(app: AppHandle, state: State<'_, AppState>, path: String) -> Result<LibraryFolder, String> {
+    info!("Adding library folder: {}", path);
+
+    let canonical_path = validate_library_path(&path)?;
+
     // Use canonical path for storage
-    let path = canonical_str.to_string();
+    let path = canonical_path.to_string_lossy().to_string();
 
     // Extract folder name from path
     let name = canonical_path
@@ -83,7 +153,7 @@ pub fn library_add_folder(app: AppHandle, state: State<'_, AppState>, path: Stri
             // Get the inserted folder
             let folder = conn
                 .query_row(
-                    "SELECT id, path, name, last_scan_at, file_count FROM library_folders WHERE path = ?1",
+                    "SELECT id, path, name, last_scan_at, file_count, filename_pattern FROM library_folders WHERE path = ?1",
                     params![path],
                     |row| {
                         Ok(LibraryFolder {
@@ -92,25 +162,13 @@ pub fn library_add_folder(app: AppHandle, state: State<'_, AppState>, path: Stri
                             name: row.get(2)?,
                             last_scan_at: row.get(3)?,
                             file_count: row.get::<_, i64>(4)? as u32,
+                            filename_pattern: row.get(5)?,
                         })
                     },
                 )
                 .map_err(|e| format!("Failed to retrieve folder: {}", e))?;
 
-            // Add folder to asset protocol scope for thumbnails
-            let asset_scope = app.asset_protocol_scope();
-            if let Err(e) = asset_scope.allow_directory(&canonical_path, true) {
-                warn!("Failed to add {} to asset scope: {}", folder.path, e);
-            } else {
-                debug!("Added {} to asset protocol scope", folder.path);
-            }
-            // Also add .homekaraoke subdirectory for thumbnails
-            let homekaraoke_dir = canonical_path.join(".homekaraoke");
-            if let Err(e) = asset_scope.allow_directory(&homekaraoke_dir, true) {
-                warn!("Failed to add {:?} to asset scope: {}", homekaraoke_dir, e);
-            } else {
-                debug!("Added {:?} to asset protocol scope", homekaraoke_dir);
-            }
+            allow_folder_assets(&app, &canonical_path);
 
             info!("Added library folder: {} (id: {})", folder.path, folder.id);
             Ok(folder)
@@ -146,6 +204,109 @@ pub fn library_remove_folder(state: State<'_, AppState>, folder_id: i64) -> Resu
     }
 }
 
+/// Point a library folder and every stored path inside it (queue and history items,
+/// favorites, the scan checkpoint) at a new location. Returns the number of stored
+/// file paths that were rewritten.
+fn relocate_folder_paths(conn: &Connection, folder_id: i64, new_path: &str) -> rusqlite::Result<usize> {
+    let old_path: String = conn.query_row(
+        "SELECT path FROM library_folders WHERE id = ?1",
+        params![folder_id],
+        |row| row.get(0),
+    )?;
+    let name = Path::new(new_path)
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| new_path.to_string());
+
+    conn.execute(
+        "UPDATE library_folders SET path = ?1, name = ?2 WHERE id = ?3",
+        params![new_path, name, folder_id],
+    )?;
+
+    // Rewrite `<old>/<rest>` to `<new>/<rest>`; paths that merely share a prefix
+    // (e.g. "/music2" for "/music") are left alone
+    let old_prefix = format!("{}{}", old_path, std::path::MAIN_SEPARATOR);
+    let mut updated = 0;
+    for (table, column) in [
+        ("queue_items", "file_path"),
+        ("queue_items", "video_id"),
+        ("singer_favorites", "file_path"),
+        ("singer_favorites", "video_id"),
+        ("library_scan_checkpoints", "last_file_path"),
+    ] {
+        // OR IGNORE: a favorite already stored under the new path keeps its row
+        updated += conn.execute(
+            &format!(
+                "UPDATE OR IGNORE {table} SET {column} = ?1 || substr({column}, ?2)
+                 WHERE substr({column}, 1, ?3) = ?4",
+            ),
+            params![
+                new_path,
+                old_path.chars().count() as i64 + 1,
+                old_prefix.chars().count() as i64,
+                old_prefix
+            ],
+        )?;
+    }
+
+    Ok(updated)
+}
+
+/// Re-point a moved library folder to its new location, keeping its scan checkpoint,
+/// settings and stored file paths (queue, history, favorites)
+#[tauri::command]
+pub fn library_relocate_folder(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    folder_id: i64,
+    new_path: String,
+) -> Result<LibraryFolder, String> {
+    info!("Relocating library folder {} to: {}", folder_id, new_path);
+
+    let canonical_path = validate_library_path(&new_path)?;
+    let new_path = canonical_path.to_string_lossy().to_string();
+
+    {
+        let mut db = state
+            .db
+            .lock()
+            .map_err(|e| format!("Failed to acquire database lock: {}", e))?;
+        let tx = db
+            .connection_mut()
+            .transaction()
+            .map_err(|e| format!("Failed to begin transaction: {}", e))?;
+
+        let existing: Option<i64> = tx
+            .query_row(
+                "SELECT id FROM library_folders WHERE path = ?1",
+                params![new_path],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|e| format!("Failed to check folder: {}", e))?;
+        match existing {
+            Some(id) if id == folder_id => return Err("Folder is already at this location".to_string()),
+            Some(_) => return Err("Folder already exists in library".to_string()),
+            None => {}
+        }
+
+        let updated = relocate_folder_paths(&tx, folder_id, &new_path).map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => format!("Folder not found: {}", folder_id),
+            e => format!("Failed to relocate folder: {}", e),
+        })?;
+        tx.commit()
+            .map_err(|e| format!("Failed to commit transaction: {}", e))?;
+        info!("Relocated library folder {} ({} stored paths updated)", folder_id, updated);
+    }
+
+    allow_folder_assets(&app, &canonical_path);
+
+    library_get_folders(state)?
+        .into_iter()
+        .find(|folder| folder.id == folder_id)
+        .ok_or_else(|| format!("Folder not found: {}", folder_id))
+}
+
 /// Get all library folders
 #[tauri::command]
 pub fn library_get_folders(state: State<'_, AppState>) -> Result<Vec<LibraryFolder>, String> {
@@ -157,7 +318,7 @@ pub fn library_get_folders(state: State<'_, AppState>) -> Result<Vec<LibraryFold
 
             let mut stmt = conn
                 .prepare(
-                    "SELECT id, path, name, last_scan_at, file_count FROM library_folders ORDER BY name",
+                    "SELECT id, path, name, last_scan_at, file_count, filename_pattern FROM library_folders ORDER BY name",
                 )
                 .map_err(|e| format!("Failed to prepare query: {}", e))?;
 
@@ -169,6 +330,7 @@ pub fn library_get_folders(state: State<'_, AppState>) -> Result<Vec<LibraryFold
                         name: row.get(2)?,
                         last_scan_at: row.get(3)?,
                         file_count: row.get::<_, i64>(4)? as u32,
+                        filename_pattern: row.get(5)?,
                     })
                 })
                 .map_err(|e| format!("Failed to query folders: {}", e))?
@@ -182,22 +344,81 @@ pub fn library_get_folders(state: State<'_, AppState>) -> Result<Vec<LibraryFold
     }
 }
 
-/// Scan a specific folder
-#[tauri::command]
-pub fn library_scan_folder(
-    state: State<'_, AppState>,
-    folder_id: i64,
-    options: ScanOptions,
-) -> Result<ScanResult, String> {
-    info!("Scanning library folder: {}", folder_id);
+/// Progress of an interrupted folder scan, saved after each processed file
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ScanCheckpoint {
+    pub folder_id: i64,
+    /// Last fully processed file (files are scanned in path order)
+    pub last_file_path: String,
+    pub files_processed: u32,
+    /// Options the interrupted scan was started with (reused on resume)
+    pub options: ScanOptions,
+}
+
+fn save_scan_checkpoint(conn: &Connection, checkpoint: &ScanCheckpoint) -> Result<(), String> {
+    let options = serde_json::to_string(&checkpoint.options)
+        .map_err(|e| format!("Failed to serialize scan options: {}", e))?;
+    conn.execute(
+        "INSERT INTO library_scan_checkpoints (folder_id, last_file_path, files_processed, options, updated_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(folder_id) DO UPDATE SET
+            last_file_path = ?2, files_processed = ?3, options = ?4, updated_at = CURRENT_TIMESTAMP",
+        params![
+            checkpoint.folder_id,
+            checkpoint.last_file_path,
+            checkpoint.files_processed as i64,
+            options
+        ],
+    )
+    .map_err(|e| format!("Failed to save scan checkpoint: {}", e))?;
+    Ok(())
+}
+
+fn load_scan_checkpoint(conn: &Connection, folder_id: i64) -> Result<Option<ScanCheckpoint>, String> {
+    let row = conn
+        .query_row(
+            "SELECT last_file_path, files_processed, options FROM library_scan_checkpoints WHERE folder_id = ?1",
+            params![folder_id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, i64>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|e| format!("Failed to load scan checkpoint: {}", e))?;
+
+    match row {
+        Some((last_file_path, files_processed, options)) => Ok(Some(ScanCheckpoint {
+            folder_id,
+            last_file_path,
+            files_processed: files_processed as u32,
+            options: serde_json::from_str(&options)
+                .map_err(|e| format!("Invalid scan checkpoint options: {}", e))?,
+        })),
+        None => Ok(None),
+    }
+}
 
-    // Get the folder from database
-    let folder = match state.db.lock() {
+fn clear_scan_checkpoint(conn: &Connection, folder_id: i64) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM library_scan_checkpoints WHERE folder_id = ?1",
+        params![folder_id],
+    )
+    .map_err(|e| format!("Failed to clear scan checkpoint: {}", e))?;
+    Ok(())
+}
+
+/// Get a library folder by ID
+fn get_folder(state: &State<'_, AppState>, folder_id: i64) -> Result<LibraryFolder, String> {
+    match state.db.lock() {
         Ok(db) => {
             let conn = db.connection();
 
             conn.query_row(
-                "SELECT id, path, name, last_scan_at, file_count FROM library_folders WHERE id = ?1",
+                "SELECT id, path, name, last_scan_at, file_count, filename_pattern FROM library_folders WHERE id = ?1",
                 params![folder_id],
                 |row| {
                     Ok(LibraryFolder {
@@ -206,65 +427,565 @@ pub fn library_scan_folder(
                         name: row.get(2)?,
                         last_scan_at: row.get(3)?,
                         file_count: row.get::<_, i64>(4)? as u32,
+                        filename_pattern: row.get(5)?,
                     })
                 },
             )
-            .map_err(|e| format!("Failed to find folder: {}", e))?
+            .map_err(|e| format!("Failed to find folder: {}", e))
         }
-        Err(e) => return Err(format!("Failed to acquire database lock: {}", e)),
-    };
+        Err(e) => Err(format!("Failed to acquire database lock: {}", e)),
+    }
+}
 
-    // Perform the scan
-    let result = LibraryScanner::scan_folder(&folder, &options);
+/// Scan a folder, checkpointing after each file so an interrupted scan can resume.
+/// The checkpoint is cleared and folder stats updated once the scan completes;
+/// a cancelled scan keeps its checkpoint so it can be resumed later.
+fn scan_with_checkpoints(
+    app: &AppHandle,
+    folder: &LibraryFolder,
+    options: &ScanOptions,
+    resume_after: Option<&str>,
+) -> ScanResult {
+    let state = app.state::<AppState>();
+    let result = LibraryScanner::scan_folder(
+        folder,
+        options,
+        resume_after,
+        &state.scan_cancelled,
+        |file_path, files_processed| {
+            let checkpoint = ScanCheckpoint {
+                folder_id: folder.id,
+                last_file_path: file_path.to_string_lossy().to_string(),
+                files_processed,
+                options: options.clone(),
+            };
+            let saved = match state.db.lock() {
+                Ok(db) => save_scan_checkpoint(db.connection(), &checkpoint),
+                Err(e) => Err(format!("Failed to acquire database lock: {}", e)),
+            };
+            if let Err(e) = saved {
+                warn!("{}", e);
+            }
+        },
+        |progress: &ScanProgress| {
+            let _ = app.emit(SCAN_PROGRESS_EVENT, progress);
+        },
+    );
+
+    if result.cancelled {
+        return result;
+    }
 
     // Update folder stats in database
     if let Ok(db) = state.db.lock() {
         let conn = db.connection();
         let _ = conn.execute(
             "UPDATE library_folders SET last_scan_at = CURRENT_TIMESTAMP, file_count = ?1 WHERE id = ?2",
-            params![result.files_found as i64, folder_id],
+            params![result.files_found as i64, folder.id],
         );
+        if let Err(e) = clear_scan_checkpoint(conn, folder.id) {
+            warn!("{}", e);
+        }
     }
 
-    Ok(result)
+    result
+}
+
+/// Scan a specific folder
+#[tauri::command]
+pub async fn library_scan_folder(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    folder_id: i64,
+    options: ScanOptions,
+) -> Result<ScanResult, String> {
+    info!("Scanning library folder: {}", folder_id);
+
+    let folder = get_folder(&state, folder_id)?;
+    state.scan_cancelled.store(false, Ordering::SeqCst);
+    tokio::task::spawn_blocking(move || scan_with_checkpoints(&app, &folder, &options, None))
+        .await
+        .map_err(|e| format!("Scan failed: {}", e))
+}
+
+/// Cancel the running scan. It stops before the next metadata request and keeps
+/// its checkpoint, so `library_resume_scan` can continue it later.
+#[tauri::command]
+pub fn library_cancel_scan(state: State<'_, AppState>) {
+    info!("Cancelling library scan");
+    state.scan_cancelled.store(true, Ordering::SeqCst);
+}
+
+/// Get the saved progress of an interrupted scan of a folder, if any
+#[tauri::command]
+pub fn library_get_scan_checkpoint(
+    state: State<'_, AppState>,
+    folder_id: i64,
+) -> Result<Option<ScanCheckpoint>, String> {
+    let db = state
+        .db
+        .lock()
+        .map_err(|e| format!("Failed to acquire database lock: {}", e))?;
+    load_scan_checkpoint(db.connection(), folder_id)
+}
+
+/// Continue an interrupted scan from its checkpoint, with the options it was started with
+#[tauri::command]
+pub async fn library_resume_scan(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    folder_id: i64,
+) -> Result<ScanResult, String> {
+    let checkpoint = {
+        let db = state
+            .db
+            .lock()
+            .map_err(|e| format!("Failed to acquire database lock: {}", e))?;
+        load_scan_checkpoint(db.connection(), folder_id)?
+    }
+    .ok_or_else(|| format!("No interrupted scan to resume for folder {}", folder_id))?;
+
+    info!(
+        "Resuming scan of library folder {} after {} files",
+        folder_id, checkpoint.files_processed
+    );
+
+    let folder = get_folder(&state, folder_id)?;
+    state.scan_cancelled.store(false, Ordering::SeqCst);
+    tokio::task::spawn_blocking(move || {
+        scan_with_checkpoints(
+            &app,
+            &folder,
+            &checkpoint.options,
+            Some(&checkpoint.last_file_path),
+        )
+    })
+    .await
+    .map_err(|e| format!("Scan failed: {}", e))
+}
+
+/// Sanity-check a folder before adding it: estimated video count, whether it looks like
+/// a slow network mount, and whether metadata can be written. Doesn't scan or add it.
+#[tauri::command]
+pub async fn library_probe_folder(path: String) -> Result<FolderProbe, String> {
+    info!("Probing folder: {}", path);
+    let canonical_path = validate_library_path(&path)?;
+
+    tokio::task::spawn_blocking(move || LibraryScanner::probe_folder(&canonical_path))
+        .await
+        .map_err(|e| format!("Probe failed: {}", e))
+}
+
+/// Preview filename parsing for a folder without scanning or writing anything
+#[tauri::command]
+pub fn library_preview_parse(
+    state: State<'_, AppState>,
+    folder_id: i64,
+    limit: u32,
+) -> Result<Vec<FilenameParsePreview>, String> {
+    let capped_limit = limit.min(MAX_PREVIEW_LIMIT);
+    debug!("Previewing filename parsing for folder {} (limit: {})", folder_id, capped_limit);
+
+    let folder = library_get_folders(state)?
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder not found: {}", folder_id))?;
+
+    let preview = LibraryScanner::preview_parse(&folder, capped_limit);
+    debug!("Parsed {} filenames for preview", preview.len());
+    Ok(preview)
+}
+
+/// Delete generated thumbnails for one folder (or all folders when `folder_id` is None).
+/// Thumbnail paths are derived from the files on disk, so search and browse results
+/// drop them immediately; the next scan with thumbnails enabled regenerates them.
+#[tauri::command]
+pub fn library_clear_thumbnails(
+    state: State<'_, AppState>,
+    folder_id: Option<i64>,
+) -> Result<u32, String> {
+    info!("Clearing thumbnails (folder: {:?})", folder_id);
+
+    let mut folders = library_get_folders(state)?;
+    if let Some(folder_id) = folder_id {
+        folders.retain(|f| f.id == folder_id);
+        if folders.is_empty() {
+            return Err(format!("Folder not found: {}", folder_id));
+        }
+    }
+
+    let removed: u32 = folders.iter().map(LibraryScanner::clear_thumbnails).sum();
+    info!("Cleared {} thumbnails", removed);
+    Ok(removed)
+}
+
+/// Copy metadata and thumbnails left in one folder's .homekaraoke directory to the
+/// videos that were moved into another folder (newer target files are kept)
+#[tauri::command]
+pub fn library_merge_folder_metadata(
+    state: State<'_, AppState>,
+    from_folder_id: i64,
+    into_folder_id: i64,
+) -> Result<MetadataMergeResult, String> {
+    info!("Merging metadata from folder {} into folder {}", from_folder_id, into_folder_id);
+    if from_folder_id == into_folder_id {
+        return Err("Cannot merge a folder's metadata into itself".to_string());
+    }
+
+    let folders = library_get_folders(state)?;
+    let find = |folder_id: i64| {
+        folders
+            .iter()
+            .find(|f| f.id == folder_id)
+            .ok_or_else(|| format!("Folder not found: {}", folder_id))
+    };
+    let from = find(from_folder_id)?;
+    let into = find(into_folder_id)?;
+
+    Ok(LibraryScanner::merge_folder_metadata(from, into))
+}
+
+/// Move a folder's legacy sidecar `.hkmeta.json` files and thumbnails into its
+/// .homekaraoke directory, keeping the newer file when both exist
+#[tauri::command]
+pub async fn library_migrate_legacy_metadata(
+    state: State<'_, AppState>,
+    folder_id: i64,
+) -> Result<MigrationResult, String> {
+    info!("Migrating legacy metadata in folder {}", folder_id);
+    let folder = library_get_folders(state)?
+        .into_iter()
+        .find(|f| f.id == folder_id)
+        .ok_or_else(|| format!("Folder not found: {}", folder_id))?;
+
+    tokio::task::spawn_blocking(move || LibraryScanner::migrate_legacy_metadata(&folder))
+        .await
+        .map_err(|e| format!("Failed to migrate metadata: {}", e))
+}
+
+/// Import metadata from another karaoke app's catalog into .hkmeta.json files
+/// for catalog entries that resolve to files in the registered library folders
+#[tauri::command]
+pub fn library_import_catalog(
+    state: State<'_, AppState>,
+    path: String,
+    format: CatalogFormat,
+) -> Result<CatalogImportResult, String> {
+    info!("Importing {:?} catalog: {}", format, path);
+
+    let folders = library_get_folders(state)?;
+    if folders.is_empty() {
+        return Err("Add a library folder before importing a catalog".to_string());
+    }
+
+    import_catalog(std::path::Path::new(&path), format, &folders)
+}
+
+/// Export every library video with full metadata as JSON for external tools.
+/// When `output_path` is given the catalog is also written to that file.
+#[tauri::command]
+pub fn library_export_catalog(
+    state: State<'_, AppState>,
+    output_path: Option<String>,
+) -> Result<CatalogExport, String> {
+    info!("Exporting library catalog");
+
+    let folders = library_get_folders(state)?;
+    let export = export_catalog(&folders);
+
+    if let Some(output_path) = output_path {
+        write_catalog_export(&export, std::path::Path::new(&output_path))?;
+    }
+
+    Ok(export)
+}
+
+/// Set (or clear) a folder's custom filename pattern
+/// The pattern is a regex with named groups `title` (required), `artist` and `year`,
+/// matched against the file name without extension before the built-in patterns.
+#[tauri::command]
+pub fn library_set_filename_pattern(
+    state: State<'_, AppState>,
+    folder_id: i64,
+    pattern: Option<String>,
+) -> Result<(), String> {
+    let pattern = pattern
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    info!("Setting filename pattern for folder {}: {:?}", folder_id, pattern);
+
+    // Reject invalid regex before saving
+    if let Some(ref p) = pattern {
+        LibraryScanner::compile_filename_pattern(p)?;
+    }
+
+    match state.db.lock() {
+        Ok(db) => {
+            let rows_affected = db
+                .connection()
+                .execute(
+                    "UPDATE library_folders SET filename_pattern = ?1 WHERE id = ?2",
+                    params![pattern, folder_id],
+                )
+                .map_err(|e| format!("Failed to set filename pattern: {}", e))?;
+
+            if rows_affected == 0 {
+                return Err(format!("Folder not found: {}", folder_id));
+            }
+
+            Ok(())
+        }
+        Err(e) => Err(format!("Failed to acquire database lock: {}", e)),
+    }
 }
 
 /// Scan all folders
 #[tauri::command]
-pub fn library_scan_all(
+pub async fn library_scan_all(
+    app: AppHandle,
     state: State<'_, AppState>,
     options: ScanOptions,
 ) -> Result<Vec<ScanResult>, String> {
     info!("Scanning all library folders");
 
     let folders = library_get_folders(state.clone())?;
-    let mut results = Vec::new();
+    state.scan_cancelled.store(false, Ordering::SeqCst);
+    tokio::task::spawn_blocking(move || {
+        let mut results = Vec::new();
+
+        for folder in folders {
+            let result = scan_with_checkpoints(&app, &folder, &options, None);
+            let cancelled = result.cancelled;
+            results.push(result);
+            if cancelled {
+                break;
+            }
+        }
 
-    for folder in folders {
-        let result = LibraryScanner::scan_folder(&folder, &options);
+        results
+    })
+    .await
+    .map_err(|e| format!("Scan failed: {}", e))
+}
 
-        // Update folder stats
-        if let Ok(db) = state.db.lock() {
-            let conn = db.connection();
-            let _ = conn.execute(
-                "UPDATE library_folders SET last_scan_at = CURRENT_TIMESTAMP, file_count = ?1 WHERE id = ?2",
-                params![result.files_found as i64, folder.id],
+/// Find files with identical content stored under different names or paths.
+/// Uses a partial-content hash (file size + first 4 MB), cached in .hkmeta.json,
+/// and emits `library:duplicate-scan-progress` events while hashing.
+#[tauri::command]
+pub async fn library_find_duplicate_content(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<Vec<DuplicateContentGroup>, String> {
+    info!("Finding duplicate library content");
+
+    let folders = library_get_folders(state)?;
+    let groups = tokio::task::spawn_blocking(move || {
+        LibraryScanner::find_duplicate_content(&folders, |processed, total| {
+            let _ = app.emit(
+                DUPLICATE_SCAN_PROGRESS_EVENT,
+                DuplicateScanProgress { processed, total },
             );
+        })
+    })
+    .await
+    .map_err(|e| format!("Duplicate scan failed: {}", e))?;
+
+    info!("Found {} groups of duplicate library content", groups.len());
+    Ok(groups)
+}
+
+/// Progress of a running thumbnail generation
+#[derive(Debug, Clone, Serialize)]
+pub struct ThumbnailProgress {
+    pub processed: u32,
+    pub total: u32,
+}
+
+/// Generate missing thumbnails (or all of them with `regenerate`) for one folder, or
+/// all folders when `folder_id` is None, without touching metadata. Emits
+/// `library:thumbnail-progress` events while running.
+#[tauri::command]
+pub async fn library_generate_thumbnails(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    folder_id: Option<i64>,
+    regenerate: bool,
+) -> Result<ThumbResult, String> {
+    if !FfmpegService::is_available() {
+        return Err("ffmpeg is not installed".to_string());
+    }
+    info!("Generating thumbnails (folder: {:?}, regenerate: {})", folder_id, regenerate);
+
+    let mut folders = library_get_folders(state)?;
+    if let Some(folder_id) = folder_id {
+        folders.retain(|f| f.id == folder_id);
+        if folders.is_empty() {
+            return Err(format!("Folder not found: {}", folder_id));
+        }
+    }
+
+    let (jobs, skipped) =
+        tokio::task::spawn_blocking(move || LibraryScanner::thumbnail_jobs(&folders, regenerate))
+            .await
+            .map_err(|e| format!("Failed to list library files: {}", e))?;
+
+    let mut result = LibraryScanner::generate_thumbnails(
+        jobs,
+        |video_path, thumbnail_path| async move {
+            FfmpegService::extract_thumbnail_smart(&video_path, &thumbnail_path).await
+        },
+        |processed, total| {
+            let _ = app.emit(THUMBNAIL_PROGRESS_EVENT, ThumbnailProgress { processed, total });
+        },
+    )
+    .await;
+    result.skipped = skipped;
+
+    info!(
+        "Generated {} thumbnails ({} failed, {} skipped)",
+        result.generated, result.failed, result.skipped
+    );
+    Ok(result)
+}
+
+/// Get a thumbnail as a `data:` URL so the UI renders every thumbnail the same way.
+/// Library thumbnails are read from disk; YouTube thumbnails come from the on-disk
+/// cache, downloaded into it first unless `download` is false. Returns None when
+/// there is no thumbnail (missing file, or offline and not cached).
+#[tauri::command]
+pub async fn get_thumbnail_data_url(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    source: ThumbnailSource,
+    download: Option<bool>,
+) -> Result<Option<String>, String> {
+    let bytes = match source {
+        ThumbnailSource::Local { path } => {
+            let folders = library_get_folders(state)?;
+            tokio::task::spawn_blocking(move || thumbnail_data::read_local_thumbnail(&folders, &path))
+                .await
+                .map_err(|e| format!("Failed to read thumbnail: {}", e))??
+        }
+        ThumbnailSource::Youtube { url } => {
+            let cache_dir = app
+                .path()
+                .app_cache_dir()
+                .map_err(|e| format!("Failed to get cache directory: {}", e))?
+                .join(THUMBNAIL_CACHE_DIR);
+            thumbnail_data::youtube_thumbnail(&cache_dir, &url, download.unwrap_or(true)).await?
         }
+        ThumbnailSource::None => None,
+    };
 
-        results.push(result);
+    bytes.map(|bytes| thumbnail_data::to_data_url(&bytes)).transpose()
+}
+
+/// Find library files whose audio and video streams differ in length by more than
+/// `threshold_secs` (bad rips confuse the player's end-of-song detection).
+/// Files with only one stream, or with unknown stream durations, are never reported.
+/// Results are sorted by the largest difference first.
+#[tauri::command]
+pub async fn library_find_duration_mismatches(
+    state: State<'_, AppState>,
+    threshold_secs: f64,
+) -> Result<Vec<DurationMismatch>, String> {
+    if !threshold_secs.is_finite() || threshold_secs < 0.0 {
+        return Err("Threshold must be a non-negative number of seconds".to_string());
+    }
+    if !FfmpegService::is_ffprobe_available() {
+        return Err("ffprobe is not installed".to_string());
     }
+    info!("Finding library files with audio/video duration mismatches (threshold {}s)", threshold_secs);
 
-    Ok(results)
+    let folders = library_get_folders(state)?;
+    let files = tokio::task::spawn_blocking(move || {
+        LibraryScanner::library_video_files(&folders)
+            .into_iter()
+            .map(|(_, file_path)| file_path)
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to list library files: {}", e))?;
+
+    let mut mismatches = Vec::new();
+    for file_path in &files {
+        let (video_duration, audio_duration) = FfmpegService::get_stream_durations(file_path).await;
+        if let Some(mismatch) =
+            LibraryScanner::duration_mismatch(file_path, video_duration, audio_duration, threshold_secs)
+        {
+            mismatches.push(mismatch);
+        }
+    }
+    mismatches.sort_by(|a, b| b.difference.total_cmp(&a.difference));
+
+    info!("Found {} of {} files with duration mismatches", mismatches.len(), files.len());
+    Ok(mismatches)
+}
+
+/// Flag library files whose `.hkmeta.json` duration differs from the real file length
+/// by more than `DURATION_AUDIT_THRESHOLD` (usually a wrong MusicBrainz match).
+/// Checks one folder, or all folders when `folder_id` is None. Files without a stored
+/// duration are skipped. Results are sorted by the largest difference first.
+#[tauri::command]
+pub async fn library_audit_durations(
+    state: State<'_, AppState>,
+    folder_id: Option<i64>,
+) -> Result<Vec<MetadataDurationMismatch>, String> {
+    if !FfmpegService::is_ffprobe_available() {
+        return Err("ffprobe is not installed".to_string());
+    }
+    info!("Auditing stored durations (folder: {:?})", folder_id);
+
+    let mut folders = library_get_folders(state)?;
+    if let Some(folder_id) = folder_id {
+        folders.retain(|f| f.id == folder_id);
+        if folders.is_empty() {
+            return Err(format!("Folder not found: {}", folder_id));
+        }
+    }
+
+    let files = tokio::task::spawn_blocking(move || {
+        LibraryScanner::library_video_files(&folders)
+            .into_iter()
+            .filter_map(|(folder, file_path)| {
+                let stored = LibraryScanner::read_hkmeta(Path::new(&folder.path), &file_path)?.duration?;
+                Some((file_path, stored))
+            })
+            .collect::<Vec<_>>()
+    })
+    .await
+    .map_err(|e| format!("Failed to read library metadata: {}", e))?;
+
+    let mut mismatches = Vec::new();
+    for (file_path, stored_duration) in &files {
+        let Some(actual_duration) = FfmpegService::get_duration(file_path).await else {
+            continue;
+        };
+        if let Some(mismatch) = LibraryScanner::metadata_duration_mismatch(
+            file_path,
+            *stored_duration,
+            actual_duration,
+            DURATION_AUDIT_THRESHOLD,
+        ) {
+            mismatches.push(mismatch);
+        }
+    }
+    mismatches.sort_by(|a, b| b.difference_ratio.total_cmp(&a.difference_ratio));
+
+    info!("Found {} of {} files with suspicious stored durations", mismatches.len(), files.len());
+    Ok(mismatches)
 }
 
 /// Search the library
+/// With `dedupe`, duplicates from overlapping folders (same file, or same title and
+/// artist) are collapsed to the best copy.
 #[tauri::command]
 pub fn library_search(
     state: State<'_, AppState>,
     query: String,
     limit: u32,
     include_lyrics: bool,
+    dedupe: Option<bool>,
 ) -> Result<Vec<LibraryVideo>, String> {
     // Cap limit to prevent performance issues
     let capped_limit = limit.min(MAX_SEARCH_LIMIT);
@@ -275,7 +996,10 @@ pub fn library_search(
     }
 
     let folders = library_get_folders(state)?;
-    let results = LibraryScanner::search(&folders, &query, capped_limit, include_lyrics);
+    let mut results = LibraryScanner::search(&folders, &query, capped_limit, include_lyrics);
+    if dedupe.unwrap_or(false) {
+        results = LibraryScanner::dedupe_videos(results, LibraryScanner::canonical_file_path);
+    }
 
     debug!("Found {} results", results.len());
     Ok(results)
@@ -287,6 +1011,164 @@ pub fn library_check_file(file_path: String) -> bool {
     LibraryScanner::check_file_exists(&file_path)
 }
 
+/// Get chapters embedded in a local video file (empty if it has none)
+#[tauri::command]
+pub async fn library_get_chapters(file_path: String) -> Result<Vec<Chapter>, String> {
+    debug!("Getting chapters for: {}", file_path);
+
+    if !LibraryScanner::check_file_exists(&file_path) {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let chapters = FfmpegService::get_chapters(std::path::Path::new(&file_path))
+        .await
+        .ok_or_else(|| "Failed to read chapters (is ffprobe installed?)".to_string())?;
+
+    info!("Found {} chapters in {}", chapters.len(), file_path);
+    Ok(chapters)
+}
+
+/// List Lrclib matches for a song so the user can pick the right lyrics
+/// when automatic matching chose the wrong one
+#[tauri::command]
+pub async fn search_lyrics_candidates(
+    title: String,
+    artist: Option<String>,
+) -> Result<Vec<LyricsCandidate>, String> {
+    if title.trim().is_empty() {
+        return Err("Title cannot be empty".to_string());
+    }
+    debug!("Searching lyrics candidates for: {} - {:?}", title, artist);
+
+    let fetcher = MetadataFetcher::new()?;
+    let candidates = fetcher.search_lyrics_candidates(&title, artist.as_deref()).await;
+
+    debug!("Found {} lyrics candidates", candidates.len());
+    Ok(candidates)
+}
+
+/// Fetch lyrics for a Lrclib id picked from `search_lyrics_candidates`
+/// (None if the record doesn't exist or has no lyrics)
+#[tauri::command]
+pub async fn fetch_lyrics_by_id(lrclib_id: u64) -> Result<Option<LyricsResult>, String> {
+    info!("Fetching lyrics for Lrclib id {}", lrclib_id);
+
+    let fetcher = MetadataFetcher::new()?;
+    Ok(fetcher.fetch_lyrics_by_id(lrclib_id).await)
+}
+
+/// Write hand-edited or hand-picked lyrics (format "lrc" or "plain") for a library file.
+/// With `lock`, scans keep these lyrics instead of fetching new ones, even when regenerating.
+#[tauri::command]
+pub fn library_set_lyrics(
+    state: State<'_, AppState>,
+    file_path: String,
+    content: String,
+    format: String,
+    lock: bool,
+) -> Result<(), String> {
+    info!("Setting lyrics for {} (format: {}, locked: {})", file_path, format, lock);
+
+    let video_path = Path::new(&file_path);
+    if !LibraryScanner::is_supported_video(video_path) || !video_path.exists() {
+        return Err(format!("File not found: {}", file_path));
+    }
+
+    let folders = library_get_folders(state)?;
+    let folder = LibraryScanner::folder_for_file(&folders, video_path)
+        .ok_or_else(|| format!("File is not in a library folder: {}", file_path))?;
+
+    LibraryScanner::set_lyrics(Path::new(&folder.path), video_path, &content, &format, lock)
+}
+
+/// How many files `library_bulk_tag` changed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BulkTagResult {
+    pub updated: u32,
+    /// Files that already had the requested tags
+    pub unchanged: u32,
+}
+
+/// Add and remove tags on many library files at once (e.g. mark a batch as "kids").
+/// Tags are matched case-insensitively. Every path must be a video inside a library
+/// folder; if any isn't, nothing is changed.
+#[tauri::command]
+pub fn library_bulk_tag(
+    state: State<'_, AppState>,
+    file_paths: Vec<String>,
+    add_tags: Vec<String>,
+    remove_tags: Vec<String>,
+) -> Result<BulkTagResult, String> {
+    info!(
+        "Bulk tagging {} files (add: {:?}, remove: {:?})",
+        file_paths.len(),
+        add_tags,
+        remove_tags
+    );
+    if file_paths.len() > MAX_BULK_TAG_FILES {
+        return Err(format!("Cannot tag more than {} files at once", MAX_BULK_TAG_FILES));
+    }
+    if let Some(tag) = add_tags.iter().find(|tag| tag.trim().len() > MAX_TAG_LENGTH) {
+        return Err(format!("Tag is longer than {} characters: {}", MAX_TAG_LENGTH, tag));
+    }
+
+    let folders = library_get_folders(state)?;
+    let mut targets = Vec::with_capacity(file_paths.len());
+    for file_path in &file_paths {
+        let video_path = Path::new(file_path);
+        if !LibraryScanner::is_supported_video(video_path) {
+            return Err(format!("File not found: {}", file_path));
+        }
+        let folder = LibraryScanner::folder_for_file(&folders, video_path)
+            .ok_or_else(|| format!("File is not in a library folder: {}", file_path))?;
+        targets.push((Path::new(&folder.path), video_path));
+    }
+
+    let mut result = BulkTagResult { updated: 0, unchanged: 0 };
+    for (library_path, video_path) in targets {
+        if LibraryScanner::update_tags(library_path, video_path, &add_tags, &remove_tags)? {
+            result.updated += 1;
+        } else {
+            result.unchanged += 1;
+        }
+    }
+
+    info!("Tagged {} files ({} unchanged)", result.updated, result.unchanged);
+    Ok(result)
+}
+
+/// Count library files per artist, most songs first
+/// Spelling variants of an artist ("The Beatles", "beatles") are counted together.
+#[tauri::command]
+pub fn library_artist_counts(state: State<'_, AppState>) -> Result<Vec<ArtistCount>, String> {
+    debug!("Counting library songs per artist");
+
+    let folders = library_get_folders(state)?;
+    let counts = LibraryScanner::artist_counts(&folders);
+
+    debug!("Found {} distinct artists", counts.len());
+    Ok(counts)
+}
+
+/// Artists listed individually in `library_get_distribution`
+const DISTRIBUTION_TOP_ARTISTS: usize = 15;
+
+/// Song counts per top artist and per decade, for the library overview charts
+#[tauri::command]
+pub fn library_get_distribution(state: State<'_, AppState>) -> Result<LibraryDistribution, String> {
+    debug!("Computing library distribution");
+
+    let folders = library_get_folders(state)?;
+    let distribution = LibraryScanner::distribution(&folders, DISTRIBUTION_TOP_ARTISTS);
+
+    debug!(
+        "Library distribution: {} files, {} decades",
+        distribution.total_files,
+        distribution.decades.len()
+    );
+    Ok(distribution)
+}
+
 /// Get library statistics
 #[tauri::command]
 pub fn library_get_stats(state: State<'_, AppState>) -> Result<LibraryStats, String> {
@@ -329,6 +1211,37 @@ pub fn library_get_stats(state: State<'_, AppState>) -> Result<LibraryStats, Str
     }
 }
 
+/// Estimate how out of date the library is since the last scans, from a sample of
+/// file modification times and file counts (a quick check, not a scan)
+#[tauri::command]
+pub async fn library_cache_status(state: State<'_, AppState>) -> Result<CacheStatus, String> {
+    debug!("Checking library cache status");
+    let folders = library_get_folders(state)?;
+
+    tokio::task::spawn_blocking(move || LibraryScanner::cache_status(&folders))
+        .await
+        .map_err(|e| format!("Failed to check library status: {}", e))
+}
+
+/// Get distinct artists, genres and languages, and the year range, for building filter dropdowns
+#[tauri::command]
+pub fn library_get_facets(state: State<'_, AppState>) -> Result<LibraryFacets, String> {
+    debug!("Getting library facets");
+
+    let folders = library_get_folders(state)?;
+    let facets = LibraryScanner::facets(&folders);
+
+    debug!(
+        "Library facets: {} artists, {} genres, {} languages, years {:?}-{:?}",
+        facets.artists.len(),
+        facets.genres.len(),
+        facets.languages.len(),
+        facets.min_year,
+        facets.max_year
+    );
+    Ok(facets)
+}
+
 /// Filters for browsing library
 #[derive(Debug, serde::Deserialize)]
 pub struct LibraryFilters {
@@ -407,3 +1320,129 @@ pub fn library_browse(
     debug!("Browse result: {} videos (total: {})", videos.len(), total);
     Ok(LibraryBrowseResult { videos, total })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn setup_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO library_folders (id, path, name) VALUES (1, '/music', 'Music')", [])
+            .unwrap();
+        conn
+    }
+
+    fn checkpoint(last_file_path: &str, files_processed: u32) -> ScanCheckpoint {
+        ScanCheckpoint {
+            folder_id: 1,
+            last_file_path: last_file_path.to_string(),
+            files_processed,
+            options: ScanOptions {
+                create_hkmeta: true,
+                fetch_song_info: true,
+                lyrics_subfolder: Some("Lyrics".to_string()),
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_relocate_folder_paths_rewrites_prefixes() {
+        let conn = setup_db();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Party', 1)", [])
+            .unwrap();
+        conn.execute("INSERT INTO singers (id, name, color) VALUES (1, 'Ann', '#fff')", [])
+            .unwrap();
+        for (id, path) in [("q1", "/music/Rock/Song.mp4"), ("q2", "/music2/Other.mp4")] {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, file_path, position, added_at)
+                 VALUES (?1, 1, 'queue', ?2, 'Song', 'local', ?2, 0, '2024-01-01T00:00:00Z')",
+                params![id, path],
+            )
+            .unwrap();
+        }
+        conn.execute(
+            "INSERT INTO singer_favorites (singer_id, video_id, title, source, file_path)
+             VALUES (1, '/music/Song.mp4', 'Song', 'local', '/music/Song.mp4')",
+            [],
+        )
+        .unwrap();
+        save_scan_checkpoint(&conn, &checkpoint("/music/Rock/Song.mp4", 3)).unwrap();
+
+        let updated = relocate_folder_paths(&conn, 1, "/karaoke/Music").unwrap();
+        assert_eq!(updated, 5);
+
+        let (path, name): (String, String) = conn
+            .query_row("SELECT path, name FROM library_folders WHERE id = 1", [], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })
+            .unwrap();
+        assert_eq!(path, "/karaoke/Music");
+        assert_eq!(name, "Music");
+
+        let queue_path = |id: &str| -> (String, String) {
+            conn.query_row(
+                "SELECT video_id, file_path FROM queue_items WHERE id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap()
+        };
+        let moved = "/karaoke/Music/Rock/Song.mp4".to_string();
+        assert_eq!(queue_path("q1"), (moved.clone(), moved.clone()));
+        // A sibling folder sharing the name prefix is untouched
+        let sibling = "/music2/Other.mp4".to_string();
+        assert_eq!(queue_path("q2"), (sibling.clone(), sibling));
+
+        let favorite: String = conn
+            .query_row("SELECT video_id FROM singer_favorites WHERE singer_id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(favorite, "/karaoke/Music/Song.mp4");
+        assert_eq!(load_scan_checkpoint(&conn, 1).unwrap().unwrap().last_file_path, moved);
+    }
+
+    #[test]
+    fn test_relocate_folder_paths_unknown_folder() {
+        let conn = setup_db();
+        assert!(matches!(
+            relocate_folder_paths(&conn, 99, "/karaoke"),
+            Err(rusqlite::Error::QueryReturnedNoRows)
+        ));
+    }
+
+    #[test]
+    fn test_scan_checkpoint_save_and_restore() {
+        let conn = setup_db();
+        assert_eq!(load_scan_checkpoint(&conn, 1).unwrap(), None);
+
+        save_scan_checkpoint(&conn, &checkpoint("/music/a.mp4", 1)).unwrap();
+        // Later progress replaces the earlier checkpoint
+        save_scan_checkpoint(&conn, &checkpoint("/music/b.mp4", 2)).unwrap();
+
+        let restored = load_scan_checkpoint(&conn, 1).unwrap().unwrap();
+        assert_eq!(restored, checkpoint("/music/b.mp4", 2));
+        assert!(restored.options.fetch_song_info);
+        assert_eq!(restored.options.lyrics_subfolder.as_deref(), Some("Lyrics"));
+    }
+
+    #[test]
+    fn test_scan_checkpoint_clear() {
+        let conn = setup_db();
+        save_scan_checkpoint(&conn, &checkpoint("/music/a.mp4", 1)).unwrap();
+
+        clear_scan_checkpoint(&conn, 1).unwrap();
+        assert_eq!(load_scan_checkpoint(&conn, 1).unwrap(), None);
+        // Clearing without a checkpoint is fine
+        clear_scan_checkpoint(&conn, 1).unwrap();
+    }
+
+    #[test]
+    fn test_scan_checkpoint_requires_known_folder() {
+        let conn = setup_db();
+        let mut unknown = checkpoint("/other/a.mp4", 1);
+        unknown.folder_id = 99;
+        assert!(save_scan_checkpoint(&conn, &unknown).is_err());
+    }
+}
@@ -1,7 +1,11 @@
 use super::errors::CommandError;
 use crate::AppState;
+use serde::Serialize;
 use tauri::State;
 
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+use super::errors::LockRecoverExt;
+
 #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
 use log::debug;
 
@@ -15,10 +19,7 @@ pub fn media_controls_update_metadata(
 ) -> Result<(), CommandError> {
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
-        let mut guard = state
-            .media_controls
-            .lock()
-            .map_err(|_| CommandError::MutexPoisoned("Media controls"))?;
+        let mut guard = state.media_controls.lock_recover("Media controls");
         if let Some(ref mut controls) = *guard {
             controls
                 .set_metadata(
@@ -48,10 +49,7 @@ pub fn media_controls_update_playback(
 ) -> Result<(), CommandError> {
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
-        let mut guard = state
-            .media_controls
-            .lock()
-            .map_err(|_| CommandError::MutexPoisoned("Media controls"))?;
+        let mut guard = state.media_controls.lock_recover("Media controls");
         if let Some(ref mut controls) = *guard {
             controls
                 .set_playback(is_playing, position_secs)
@@ -71,10 +69,7 @@ pub fn media_controls_update_playback(
 pub fn media_controls_stop(state: State<AppState>) -> Result<(), CommandError> {
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
     {
-        let mut guard = state
-            .media_controls
-            .lock()
-            .map_err(|_| CommandError::MutexPoisoned("Media controls"))?;
+        let mut guard = state.media_controls.lock_recover("Media controls");
         if let Some(ref mut controls) = *guard {
             controls.stop().map_err(|e| CommandError::External(e))?;
         }
@@ -88,3 +83,95 @@ pub fn media_controls_stop(state: State<AppState>) -> Result<(), CommandError> {
 
     Ok(())
 }
+
+/// Playback state last reported to the OS media controls
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaPlaybackState {
+    Stopped,
+    Playing,
+    Paused,
+}
+
+/// Whether OS media controls are usable, so the UI can hide controls that won't work
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MediaControlsStatus {
+    /// The media controls service initialized successfully
+    pub available: bool,
+    /// Last playback state sent to the service (`None` when unavailable)
+    pub playback_state: Option<MediaPlaybackState>,
+}
+
+impl MediaControlsStatus {
+    /// Build the status from the service's last playback state, or `None` if the
+    /// service failed to initialize (or the platform has no media controls)
+    fn from_service(last_playback: Option<Option<bool>>) -> Self {
+        match last_playback {
+            Some(last_playback) => Self {
+                available: true,
+                playback_state: Some(match last_playback {
+                    Some(true) => MediaPlaybackState::Playing,
+                    Some(false) => MediaPlaybackState::Paused,
+                    None => MediaPlaybackState::Stopped,
+                }),
+            },
+            None => Self {
+                available: false,
+                playback_state: None,
+            },
+        }
+    }
+}
+
+#[tauri::command]
+pub fn media_controls_status(state: State<AppState>) -> Result<MediaControlsStatus, CommandError> {
+    #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+    let last_playback = state
+        .media_controls
+        .lock_recover("Media controls")
+        .as_ref()
+        .map(|controls| controls.last_playback());
+
+    #[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+    let last_playback = {
+        let _ = state;
+        None
+    };
+
+    Ok(MediaControlsStatus::from_service(last_playback))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_without_initialized_service() {
+        let status = MediaControlsStatus::from_service(None);
+        assert!(!status.available);
+        assert_eq!(status.playback_state, None);
+    }
+
+    #[test]
+    fn test_status_with_initialized_service() {
+        let status = MediaControlsStatus::from_service(Some(None));
+        assert!(status.available);
+        assert_eq!(status.playback_state, Some(MediaPlaybackState::Stopped));
+
+        assert_eq!(
+            MediaControlsStatus::from_service(Some(Some(true))).playback_state,
+            Some(MediaPlaybackState::Playing)
+        );
+        assert_eq!(
+            MediaControlsStatus::from_service(Some(Some(false))).playback_state,
+            Some(MediaPlaybackState::Paused)
+        );
+    }
+
+    #[test]
+    fn test_status_serializes_lowercase_state() {
+        let json = serde_json::to_value(MediaControlsStatus::from_service(Some(Some(true)))).unwrap();
+        assert_eq!(json["available"], true);
+        assert_eq!(json["playback_state"], "playing");
+    }
+}
@@ -1,26 +1,36 @@
 pub mod auth;
+pub mod database;
+pub mod diagnostics;
 pub mod display;
 pub mod errors;
 pub mod favorites;
 pub mod keep_awake;
 pub mod library;
 pub mod media_controls;
+pub mod playback;
 pub mod queue;
+pub mod search;
 pub mod search_history;
 pub mod session;
+pub mod setlist;
 pub mod settings;
 pub mod update;
 pub mod youtube;
 
 pub use auth::*;
+pub use database::*;
+pub use diagnostics::*;
 pub use display::*;
 pub use favorites::*;
 pub use keep_awake::*;
 pub use library::*;
 pub use media_controls::*;
+pub use playback::*;
 pub use queue::*;
+pub use search::*;
 pub use search_history::*;
 pub use session::*;
+pub use setlist::*;
 pub use settings::*;
 pub use update::*;
 pub use youtube::*;
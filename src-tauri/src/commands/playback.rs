@@ -0,0 +1,344 @@
+use super::errors::CommandError;
+use super::library::library_get_folders;
+use crate::services::stream_cache;
+use crate::services::ytdlp::StreamInfo;
+use crate::services::{LibraryFolder, LibraryScanner, LibraryVideo, YtDlpService};
+use crate::AppState;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+/// Words ignored when comparing titles (common in YouTube karaoke uploads)
+const IGNORED_TITLE_WORDS: &[&str] = &[
+    "karaoke",
+    "version",
+    "instrumental",
+    "lyrics",
+    "official",
+    "video",
+    "hd",
+];
+
+/// How a local file was matched to the requested song
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LocalMatch {
+    /// The library file's .hkmeta.json records the same YouTube video ID
+    YoutubeId,
+    /// The library file's title and artist match the requested song
+    TitleArtist,
+}
+
+/// Where the player should load a song from
+#[derive(Debug, Serialize)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum PlaybackSource {
+    Local {
+        file_path: String,
+        matched_by: LocalMatch,
+    },
+    Youtube(StreamInfo),
+}
+
+/// Lowercase, drop bracketed text and filler words, keep alphanumeric words
+fn normalize_title(text: &str) -> String {
+    let mut depth = 0u32;
+    let mut cleaned = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = depth.saturating_sub(1),
+            _ if depth > 0 => {}
+            c if c.is_alphanumeric() => cleaned.extend(c.to_lowercase()),
+            _ => cleaned.push(' '),
+        }
+    }
+    cleaned
+        .split_whitespace()
+        .filter(|word| !IGNORED_TITLE_WORDS.contains(word))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Check if `haystack` contains `needle` as whole words
+fn contains_words(haystack: &str, needle: &str) -> bool {
+    !needle.is_empty() && format!(" {} ", haystack).contains(&format!(" {} ", needle))
+}
+
+/// Check if a library video is the requested song by title/artist.
+/// YouTube titles often embed the artist ("Queen - Bohemian Rhapsody (Karaoke)"),
+/// so when no artist is given the library artist must appear in the title.
+fn matches_title_artist(video: &LibraryVideo, title: &str, artist: Option<&str>) -> bool {
+    let wanted_title = normalize_title(title);
+    let local_title = normalize_title(&video.title);
+    if !contains_words(&wanted_title, &local_title) {
+        return false;
+    }
+
+    let local_artist = video.artist.as_deref().map(normalize_title).unwrap_or_default();
+    let wanted_artist = artist.map(normalize_title).unwrap_or_default();
+    match (local_artist.is_empty(), wanted_artist.is_empty()) {
+        (false, false) => {
+            contains_words(&wanted_artist, &local_artist) || contains_words(&local_artist, &wanted_artist)
+        }
+        (false, true) => contains_words(&wanted_title, &local_artist),
+        // Without a library artist only an exact title match is trusted
+        (true, _) => wanted_title == local_title,
+    }
+}
+
+/// Find a library file for a song: by YouTube ID first, then by title/artist
+fn find_local_match<'a>(
+    videos: &'a [LibraryVideo],
+    youtube_id: &str,
+    title: &str,
+    artist: Option<&str>,
+) -> Option<(&'a LibraryVideo, LocalMatch)> {
+    videos
+        .iter()
+        .find(|v| v.youtube_id.as_deref() == Some(youtube_id))
+        .map(|v| (v, LocalMatch::YoutubeId))
+        .or_else(|| {
+            videos
+                .iter()
+                .find(|v| matches_title_artist(v, title, artist))
+                .map(|v| (v, LocalMatch::TitleArtist))
+        })
+}
+
+/// Resolve where to play a YouTube song from, preferring a matching local library
+/// file over streaming (avoids yt-dlp when the song is available offline).
+#[tauri::command]
+pub async fn resolve_playback_source(
+    state: State<'_, AppState>,
+    video_id: String,
+    title: String,
+    artist: Option<String>,
+) -> Result<PlaybackSource, CommandError> {
+    debug!("resolve_playback_source: video_id='{}', title='{}'", video_id, title);
+
+    match library_get_folders(state) {
+        Ok(folders) if !folders.is_empty() => {
+            let lookup_id = video_id.clone();
+            let lookup_title = title.clone();
+            let lookup_artist = artist.clone();
+            let local = tokio::task::spawn_blocking(move || {
+                let videos = LibraryScanner::browse(&folders, None, None);
+                find_local_match(&videos, &lookup_id, &lookup_title, lookup_artist.as_deref())
+                    .map(|(video, matched_by)| (video.file_path.clone(), matched_by))
+            })
+            .await
+            .map_err(|e| CommandError::External(format!("Library lookup failed: {}", e)))?;
+
+            if let Some((file_path, matched_by)) = local {
+                info!(
+                    "resolve_playback_source: using local file for '{}' ({:?})",
+                    video_id, matched_by
+                );
+                return Ok(PlaybackSource::Local {
+                    file_path,
+                    matched_by,
+                });
+            }
+        }
+        Ok(_) => {}
+        Err(e) => warn!("resolve_playback_source: skipping library lookup: {}", e),
+    }
+
+    let stream = stream_cache::cached_stream_url(&YtDlpService::new(), &video_id, None)
+        .await
+        .map_err(|e| CommandError::External(e.to_string()))?;
+    info!("resolve_playback_source: streaming '{}' from YouTube", video_id);
+    Ok(PlaybackSource::Youtube(stream))
+}
+
+/// Canonicalize a file path and check it is inside one of the library folders.
+/// Canonicalizing resolves `..` and symlinks, so neither can escape a folder.
+fn resolve_library_file(folders: &[LibraryFolder], file_path: &str) -> Result<PathBuf, CommandError> {
+    let not_found = || CommandError::NotFound {
+        resource: "File",
+        id: file_path.to_string(),
+    };
+    let canonical = Path::new(file_path).canonicalize().map_err(|_| not_found())?;
+    if !canonical.is_file() {
+        return Err(not_found());
+    }
+
+    let inside_library = folders.iter().any(|folder| {
+        Path::new(&folder.path)
+            .canonicalize()
+            .is_ok_and(|root| canonical.starts_with(root))
+    });
+    if !inside_library {
+        return Err(CommandError::Forbidden("Playing files outside the library folders"));
+    }
+    Ok(canonical)
+}
+
+/// Check that a local file (e.g. from a queue item) is inside a registered library
+/// folder before the webview loads it. Returns the canonical path to load, `not_found`
+/// if the file doesn't exist, or `forbidden` if it is outside every library folder.
+#[tauri::command]
+pub fn resolve_local_file(state: State<'_, AppState>, file_path: String) -> Result<String, CommandError> {
+    debug!("resolve_local_file: {}", file_path);
+    let folders = library_get_folders(state).map_err(CommandError::External)?;
+
+    match resolve_library_file(&folders, &file_path) {
+        Ok(path) => Ok(path.to_string_lossy().to_string()),
+        Err(e) => {
+            warn!("resolve_local_file: rejected {}: {}", file_path, e);
+            Err(e)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ThumbnailSource;
+    use crate::test_utils::scratch_dir;
+
+    fn library_video(title: &str, artist: Option<&str>, youtube_id: Option<&str>) -> LibraryVideo {
+        LibraryVideo {
+            file_path: format!("/music/{}.mp4", title),
+            file_name: format!("{}.mp4", title),
+            title: title.to_string(),
+            artist: artist.map(String::from),
+            album: None,
+            duration: None,
+            has_lyrics: false,
+            has_cdg: false,
+            youtube_id: youtube_id.map(String::from),
+            is_available: true,
+            thumbnail_path: None,
+            aspect_ratio: None,
+            thumbnail: ThumbnailSource::None,
+            thumbnail_alternative: None,
+        }
+    }
+
+    fn library() -> Vec<LibraryVideo> {
+        vec![
+            library_video("Bohemian Rhapsody", Some("Queen"), None),
+            library_video("Take On Me", Some("a-ha"), Some("djV11Xbc914")),
+            library_video("Untitled Jam", None, None),
+        ]
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(
+            normalize_title("Queen - Bohemian Rhapsody (Karaoke Version) [HD]"),
+            "queen bohemian rhapsody"
+        );
+        assert_eq!(normalize_title("Don't Stop Me Now"), "don t stop me now");
+    }
+
+    #[test]
+    fn test_match_by_youtube_id() {
+        let videos = library();
+        let (video, matched_by) =
+            find_local_match(&videos, "djV11Xbc914", "Something Else", None).unwrap();
+        assert_eq!(video.title, "Take On Me");
+        assert_eq!(matched_by, LocalMatch::YoutubeId);
+    }
+
+    #[test]
+    fn test_match_by_title_and_artist() {
+        let videos = library();
+        let (video, matched_by) =
+            find_local_match(&videos, "other", "Bohemian Rhapsody (Karaoke)", Some("Queen")).unwrap();
+        assert_eq!(video.title, "Bohemian Rhapsody");
+        assert_eq!(matched_by, LocalMatch::TitleArtist);
+
+        // Artist embedded in a YouTube title
+        let (video, _) =
+            find_local_match(&videos, "other", "Queen - Bohemian Rhapsody | Karaoke Version", None).unwrap();
+        assert_eq!(video.title, "Bohemian Rhapsody");
+    }
+
+    #[test]
+    fn test_no_match_falls_back_to_youtube() {
+        let videos = library();
+        // Same title, different artist
+        assert!(find_local_match(&videos, "other", "Bohemian Rhapsody", Some("Panic! at the Disco")).is_none());
+        // Title match but library artist missing from the YouTube title
+        assert!(find_local_match(&videos, "other", "Bohemian Rhapsody Karaoke", None).is_none());
+        // Library file without an artist needs an exact title
+        assert!(find_local_match(&videos, "other", "Untitled Jam Session", None).is_none());
+        assert!(find_local_match(&videos, "other", "Untitled Jam (Karaoke)", None).is_some());
+        assert!(find_local_match(&[], "other", "Anything", None).is_none());
+    }
+
+    fn library_folder(path: &Path) -> LibraryFolder {
+        LibraryFolder {
+            id: 1,
+            path: path.to_string_lossy().to_string(),
+            name: "Karaoke".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_library_file_allows_files_inside() {
+        let dir = scratch_dir("resolve-allowed");
+        let library = dir.join("library");
+        std::fs::create_dir_all(library.join("Rock")).unwrap();
+        std::fs::write(library.join("Rock").join("Song.mp4"), "").unwrap();
+
+        let folders = [library_folder(&library)];
+        let file_path = library.join("Rock").join("Song.mp4");
+        let resolved = resolve_library_file(&folders, &file_path.to_string_lossy()).unwrap();
+        assert_eq!(resolved, file_path.canonicalize().unwrap());
+
+        // Missing files and directories are not found, not forbidden
+        let missing = library.join("Missing.mp4");
+        assert!(matches!(
+            resolve_library_file(&folders, &missing.to_string_lossy()),
+            Err(CommandError::NotFound { .. })
+        ));
+        assert!(matches!(
+            resolve_library_file(&folders, &library.join("Rock").to_string_lossy()),
+            Err(CommandError::NotFound { .. })
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_resolve_library_file_rejects_traversal() {
+        let dir = scratch_dir("resolve-traversal");
+        let library = dir.join("library");
+        std::fs::create_dir_all(&library).unwrap();
+        std::fs::create_dir_all(dir.join("private")).unwrap();
+        std::fs::write(dir.join("private").join("secret.mp4"), "").unwrap();
+
+        let folders = [library_folder(&library)];
+        let traversal = format!("{}/../private/secret.mp4", library.to_string_lossy());
+        assert!(matches!(
+            resolve_library_file(&folders, &traversal),
+            Err(CommandError::Forbidden(_))
+        ));
+
+        // A symlink inside the library pointing outside it is rejected too
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("private").join("secret.mp4"), library.join("link.mp4")).unwrap();
+            assert!(matches!(
+                resolve_library_file(&folders, &library.join("link.mp4").to_string_lossy()),
+                Err(CommandError::Forbidden(_))
+            ));
+        }
+
+        // No library folders means nothing is playable
+        assert!(matches!(
+            resolve_library_file(&[], &dir.join("private").join("secret.mp4").to_string_lossy()),
+            Err(CommandError::Forbidden(_))
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
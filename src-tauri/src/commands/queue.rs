@@ -1,8 +1,25 @@
 use super::errors::{CommandError, LockResultExt};
+use super::library::{library_get_folders, LibraryFilters};
+use super::session::{
+    apply_singer_song_preference, read_playback_defaults, validate_playback_adjustments, PlaybackAdjustments,
+};
+use super::settings::{auto_shuffle_after_adds, ensure_not_party_mode};
+use crate::services::ytdlp::{classify_stderr, validate_chapters, Chapter, YtDlpError, YtDlpFailureKind};
+use crate::services::{canonical_video_id, LibraryScanner, LibraryVideo, YouTubeApiService, YtDlpService};
 use crate::AppState;
-use log::{debug, info};
+use log::{debug, info, warn};
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
-use tauri::State;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, State};
+
+/// SQLite expression for a random (version 4) UUID, matching the frontend's queue item ids
+const RANDOM_UUID_SQL: &str = "SELECT lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+    substr(lower(hex(randomblob(2))), 2) || '-' || substr('89ab', abs(random()) % 4 + 1, 1) ||
+    substr(lower(hex(randomblob(2))), 2) || '-' || lower(hex(randomblob(6)))";
+
+/// Maximum number of random library songs added at once
+const MAX_RANDOM_LIBRARY_SONGS: u32 = 100;
 
 // ============ Data Structures ============
 
@@ -20,6 +37,21 @@ pub struct QueueItemData {
     pub position: i64,
     pub added_at: String,
     pub played_at: Option<String>,
+    /// Video chapters for seeking to a song section (verse, chorus, ...)
+    #[serde(default)]
+    pub chapters: Option<Vec<Chapter>>,
+    /// This song's own tempo and key override (unset fields use the session defaults)
+    #[serde(default)]
+    pub playback_adjustments: PlaybackAdjustments,
+}
+
+/// Total playback time of the pending queue
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct QueueRuntime {
+    /// Sum of known durations plus changeover gaps, in seconds
+    pub total_secs: i64,
+    /// Number of items with no known duration (not included in total)
+    pub unknown_count: u32,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,6 +59,11 @@ pub struct QueueState {
     pub queue: Vec<QueueItemData>,
     pub history: Vec<QueueItemData>,
     pub history_index: i64,
+    /// Item that was playing when the state was saved (None if nothing was, or it was removed)
+    pub current_item_id: Option<String>,
+    /// Session-wide playback rate and transpose for songs without their own override
+    #[serde(default)]
+    pub playback_defaults: PlaybackAdjustments,
 }
 
 // ============ Helper Functions ============
@@ -44,6 +81,33 @@ fn get_active_session_id(db: &crate::db::Database) -> Result<i64, CommandError>
         })
 }
 
+/// Columns read by `queue_item_from_row`, in order
+const QUEUE_ITEM_COLUMNS: &str =
+    "id, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, played_at, chapters, playback_rate, transpose";
+
+/// Map a row selected with `QUEUE_ITEM_COLUMNS`
+fn queue_item_from_row(row: &rusqlite::Row) -> rusqlite::Result<QueueItemData> {
+    Ok(QueueItemData {
+        id: row.get(0)?,
+        video_id: row.get(1)?,
+        title: row.get(2)?,
+        artist: row.get(3)?,
+        duration: row.get(4)?,
+        thumbnail_url: row.get(5)?,
+        source: row.get(6)?,
+        youtube_id: row.get(7)?,
+        file_path: row.get(8)?,
+        position: row.get(9)?,
+        added_at: row.get(10)?,
+        played_at: row.get(11)?,
+        chapters: parse_stored_chapters(row.get(12)?),
+        playback_adjustments: PlaybackAdjustments {
+            playback_rate: row.get(13)?,
+            transpose: row.get(14)?,
+        },
+    })
+}
+
 fn reorder_positions(
     db: &crate::db::Database,
     session_id: i64,
@@ -71,56 +135,253 @@ fn reorder_positions(
     Ok(())
 }
 
+/// Append an item to a session's pending queue, then re-run the fair shuffle if the
+/// session has auto-shuffle on. Must run inside the caller's transaction.
+/// Returns the position the item was appended at.
+fn add_item_to_queue(conn: &Connection, session_id: i64, item: &QueueItemData) -> Result<i64, CommandError> {
+    let chapters_json = item.chapters.as_ref().map(serde_json::to_string).transpose()?;
+
+    // Get next position
+    let position: i64 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(position), -1) + 1 FROM queue_items WHERE session_id = ?1 AND item_type = 'queue'",
+            [session_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
+
+    conn.execute(
+        "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, chapters, playback_rate, transpose)
+         VALUES (?1, ?2, 'queue', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        rusqlite::params![
+            item.id,
+            session_id,
+            item.video_id,
+            item.title,
+            item.artist,
+            item.duration,
+            item.thumbnail_url,
+            item.source,
+            item.youtube_id,
+            item.file_path,
+            position,
+            item.added_at,
+            chapters_json,
+            item.playback_adjustments.playback_rate,
+            item.playback_adjustments.transpose
+        ],
+    )?;
+
+    if auto_fair_shuffle(conn, session_id)? {
+        debug!("Auto fair shuffled queue after adding {}", item.id);
+    }
+    Ok(position)
+}
+
+/// Normalize the YouTube IDs of an item before it is stored, so a pasted URL can't
+/// break playback or de-duplication: a YouTube item's `video_id` and any linked
+/// `youtube_id` become the canonical 11-character ID. Invalid IDs on YouTube items
+/// are rejected; an invalid `youtube_id` on a local item (e.g. from a hand-edited
+/// .hkmeta.json) is dropped so the song can still be queued.
+pub(crate) fn normalize_video_ids(
+    source: &str,
+    video_id: &mut String,
+    youtube_id: &mut Option<String>,
+) -> Result<(), CommandError> {
+    let invalid = |id: &str| CommandError::Validation(format!("Invalid YouTube video ID: {}", id));
+
+    if let Some(id) = youtube_id.take().filter(|id| !id.trim().is_empty()) {
+        match canonical_video_id(&id) {
+            Some(canonical) => *youtube_id = Some(canonical),
+            None if source == "youtube" => return Err(invalid(&id)),
+            None => debug!("Dropping invalid YouTube ID {:?} of {}", id, video_id),
+        }
+    }
+    if source == "youtube" {
+        *video_id = canonical_video_id(video_id).ok_or_else(|| invalid(video_id))?;
+    }
+    Ok(())
+}
+
 // ============ Queue Commands ============
 
+/// Event emitted when the queue was reordered after `auto_shuffle_after_adds` additions
+const AUTO_SHUFFLED_EVENT: &str = "queue:auto-shuffled";
+
+/// Count an added song towards the session's scheduled shuffle. When `threshold`
+/// additions are reached the count restarts and the queue is reordered by the
+/// session's rotation mode, keeping the next song in place; a threshold of 0
+/// disables it. Must run inside the caller's transaction. Returns whether it reordered.
+fn count_add_towards_shuffle(conn: &Connection, session_id: i64, threshold: u32) -> Result<bool, CommandError> {
+    if threshold == 0 {
+        conn.execute("UPDATE sessions SET adds_since_shuffle = 0 WHERE id = ?1", [session_id])?;
+        return Ok(false);
+    }
+
+    conn.execute(
+        "UPDATE sessions SET adds_since_shuffle = adds_since_shuffle + 1 WHERE id = ?1",
+        [session_id],
+    )?;
+    let count: u32 = conn.query_row(
+        "SELECT adds_since_shuffle FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| row.get(0),
+    )?;
+    if count < threshold {
+        return Ok(false);
+    }
+
+    conn.execute("UPDATE sessions SET adds_since_shuffle = 0 WHERE id = ?1", [session_id])?;
+    let mode = read_rotation_mode(conn, session_id)?;
+    pinned_rotation(conn, session_id, mode, seed_or_now(None))
+}
+
 #[tauri::command]
-pub fn queue_add_item(state: State<'_, AppState>, item: QueueItemData) -> Result<(), CommandError> {
+pub fn queue_add_item(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    mut item: QueueItemData,
+) -> Result<(), CommandError> {
     debug!("Adding item to queue: {} - {}", item.id, item.title);
+    normalize_video_ids(&item.source, &mut item.video_id, &mut item.youtube_id)?;
+    validate_playback_adjustments(&item.playback_adjustments)?;
     let db = state.db.lock().map_lock_err()?;
     let conn = db.connection();
 
     let session_id = get_active_session_id(&db)?;
+    let threshold = auto_shuffle_after_adds(&db);
 
     // Use transaction for atomicity (prevent duplicate positions)
     conn.execute("BEGIN IMMEDIATE", [])?;
 
-    let result = (|| -> Result<i64, CommandError> {
-        // Get next position
-        let position: i64 = conn
-            .query_row(
-                "SELECT COALESCE(MAX(position), -1) + 1 FROM queue_items WHERE session_id = ?1 AND item_type = 'queue'",
-                [session_id],
-                |row| row.get(0),
-            )
-            .unwrap_or(0);
+    let result = add_item_to_queue(conn, session_id, &item).and_then(|position| {
+        let shuffled = count_add_towards_shuffle(conn, session_id, threshold)?;
+        Ok((position, shuffled))
+    });
 
-        conn.execute(
-            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at)
-             VALUES (?1, ?2, 'queue', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
-            rusqlite::params![
-                item.id,
-                session_id,
-                item.video_id,
-                item.title,
-                item.artist,
-                item.duration,
-                item.thumbnail_url,
-                item.source,
-                item.youtube_id,
-                item.file_path,
-                position,
-                item.added_at
-            ],
+    match result {
+        Ok((position, shuffled)) => {
+            conn.execute("COMMIT", [])?;
+            info!("Added item to queue: {} at position {}", item.id, position);
+
+            if shuffled {
+                info!("Scheduled shuffle of session {}", session_id);
+                let _ = app.emit(AUTO_SHUFFLED_EVENT, session_id);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    }
+}
+
+/// Small deterministic pseudo-random generator (SplitMix64), seedable for tests
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
+
+/// The given seed, or one taken from the clock for a fresh random pick
+fn seed_or_now(seed: Option<u64>) -> u64 {
+    seed.unwrap_or_else(|| {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_nanos() as u64)
+            .unwrap_or_default()
+    })
+}
+
+/// Pick up to `count` items at random, in random order (partial Fisher-Yates shuffle)
+fn sample_random<T>(mut items: Vec<T>, count: usize, seed: u64) -> Vec<T> {
+    let mut rng = SplitMix64(seed);
+    let count = count.min(items.len());
+    for i in 0..count {
+        let j = i + (rng.next_u64() % (items.len() - i) as u64) as usize;
+        items.swap(i, j);
+    }
+    items.truncate(count);
+    items
+}
+
+/// Queue item for a local library video (id and position are set when inserted)
+fn library_video_to_queue_item(video: LibraryVideo, added_at: &str) -> QueueItemData {
+    QueueItemData {
+        id: String::new(),
+        video_id: video.file_path.clone(),
+        title: video.title,
+        artist: video.artist,
+        duration: video.duration.map(i64::from),
+        // Thumbnails need an asset URL, which only the frontend can build
+        thumbnail_url: None,
+        source: "local".to_string(),
+        youtube_id: video.youtube_id,
+        file_path: Some(video.file_path),
+        position: 0,
+        added_at: added_at.to_string(),
+        played_at: None,
+        chapters: None,
+        playback_adjustments: PlaybackAdjustments::default(),
+    }
+}
+
+/// Append items to the end of a session's queue in one transaction,
+/// giving each a fresh id and its position
+pub(super) fn append_queue_items(
+    conn: &Connection,
+    session_id: i64,
+    mut items: Vec<QueueItemData>,
+) -> Result<Vec<QueueItemData>, CommandError> {
+    conn.execute("BEGIN IMMEDIATE", [])?;
+
+    let result = (|| -> Result<(), CommandError> {
+        let next_position: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(position), -1) + 1 FROM queue_items WHERE session_id = ?1 AND item_type = 'queue'",
+            [session_id],
+            |row| row.get(0),
         )?;
 
-        Ok(position)
+        for (offset, item) in items.iter_mut().enumerate() {
+            item.id = conn.query_row(RANDOM_UUID_SQL, [], |row| row.get(0))?;
+            item.position = next_position + offset as i64;
+            let chapters_json = item.chapters.as_ref().map(serde_json::to_string).transpose()?;
+
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, chapters)
+                 VALUES (?1, ?2, 'queue', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13)",
+                rusqlite::params![
+                    item.id,
+                    session_id,
+                    item.video_id,
+                    item.title,
+                    item.artist,
+                    item.duration,
+                    item.thumbnail_url,
+                    item.source,
+                    item.youtube_id,
+                    item.file_path,
+                    item.position,
+                    item.added_at,
+                    chapters_json
+                ],
+            )?;
+        }
+
+        Ok(())
     })();
 
     match result {
-        Ok(position) => {
+        Ok(()) => {
             conn.execute("COMMIT", [])?;
-            info!("Added item to queue: {} at position {}", item.id, position);
-            Ok(())
+            Ok(items)
         }
         Err(e) => {
             let _ = conn.execute("ROLLBACK", []);
@@ -129,6 +390,51 @@ pub fn queue_add_item(state: State<'_, AppState>, item: QueueItemData) -> Result
     }
 }
 
+/// Add `count` random songs from the library (matching `filters`) to the end of the queue.
+/// Pass `seed` for a repeatable pick. Returns the added items; fewer are added if
+/// fewer songs match.
+#[tauri::command]
+pub fn queue_add_random_from_library(
+    state: State<'_, AppState>,
+    count: u32,
+    filters: LibraryFilters,
+    seed: Option<u64>,
+) -> Result<Vec<QueueItemData>, CommandError> {
+    if count == 0 || count > MAX_RANDOM_LIBRARY_SONGS {
+        return Err(CommandError::Validation(format!(
+            "Count must be from 1 to {}",
+            MAX_RANDOM_LIBRARY_SONGS
+        )));
+    }
+    debug!("Adding {} random library songs with filters: {:?}", count, filters);
+
+    let folders: Vec<_> = library_get_folders(state.clone())
+        .map_err(CommandError::External)?
+        .into_iter()
+        .filter(|folder| filters.folder_id.map_or(true, |id| folder.id == id))
+        .collect();
+    let videos = LibraryScanner::browse(&folders, filters.has_lyrics, filters.has_cdg);
+    let matching = videos.len();
+
+    let seed = seed_or_now(seed);
+    let added_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+    let items: Vec<QueueItemData> = sample_random(videos, count as usize, seed)
+        .into_iter()
+        .map(|video| library_video_to_queue_item(video, &added_at))
+        .collect();
+
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+    let items = append_queue_items(db.connection(), session_id, items)?;
+
+    info!(
+        "Added {} random library songs to the queue ({} matching)",
+        items.len(),
+        matching
+    );
+    Ok(items)
+}
+
 #[tauri::command]
 pub fn queue_remove_item(state: State<'_, AppState>, item_id: String) -> Result<(), CommandError> {
     debug!("Removing item from queue: {}", item_id);
@@ -148,33 +454,138 @@ pub fn queue_remove_item(state: State<'_, AppState>, item_id: String) -> Result<
     Ok(())
 }
 
+/// Overwrite a session item's video fields with `new_video`'s, keeping its id,
+/// position, added/played times and singer assignments
+fn replace_item_source(
+    conn: &Connection,
+    session_id: i64,
+    item_id: &str,
+    new_video: &QueueItemData,
+) -> Result<(), CommandError> {
+    if new_video.video_id.trim().is_empty() {
+        return Err(CommandError::Validation("Video ID cannot be empty".to_string()));
+    }
+    let chapters_json = new_video.chapters.as_ref().map(serde_json::to_string).transpose()?;
+
+    let updated = conn.execute(
+        "UPDATE queue_items
+         SET video_id = ?3, title = ?4, artist = ?5, duration = ?6, thumbnail_url = ?7,
+             source = ?8, youtube_id = ?9, file_path = ?10, chapters = ?11
+         WHERE id = ?1 AND session_id = ?2",
+        rusqlite::params![
+            item_id,
+            session_id,
+            new_video.video_id,
+            new_video.title,
+            new_video.artist,
+            new_video.duration,
+            new_video.thumbnail_url,
+            new_video.source,
+            new_video.youtube_id,
+            new_video.file_path,
+            chapters_json
+        ],
+    )?;
+    if updated == 0 {
+        return Err(CommandError::NotFound {
+            resource: "Queue item",
+            id: item_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Swap the video of a queue or history item for another version (e.g. when the
+/// YouTube video was taken down), without losing its place or its singers.
+/// Only the video fields of `new_video` are used.
+#[tauri::command]
+pub fn queue_replace_source(
+    state: State<'_, AppState>,
+    item_id: String,
+    mut new_video: QueueItemData,
+) -> Result<(), CommandError> {
+    debug!("Replacing source of queue item {} with {}", item_id, new_video.video_id);
+    normalize_video_ids(&new_video.source, &mut new_video.video_id, &mut new_video.youtube_id)?;
+    let db = state.db.lock().map_lock_err()?;
+
+    let session_id = get_active_session_id(&db)?;
+    replace_item_source(db.connection(), session_id, &item_id, &new_video)?;
+
+    info!("Replaced source of queue item {} with {}", item_id, new_video.video_id);
+    Ok(())
+}
+
 #[tauri::command]
 pub fn queue_reorder(
     state: State<'_, AppState>,
     item_id: String,
     new_position: i64,
 ) -> Result<(), CommandError> {
-    // Validate new_position is not negative
-    if new_position < 0 {
-        return Err(CommandError::Validation(
-            "Position cannot be negative".to_string(),
-        ));
-    }
-
     debug!(
         "Reordering queue item {} to position {}",
         item_id, new_position
     );
     let db = state.db.lock().map_lock_err()?;
-    let conn = db.connection();
-
     let session_id = get_active_session_id(&db)?;
 
+    if reorder_item(db.connection(), session_id, "queue", &item_id, new_position)? {
+        info!(
+            "Reordered queue item {} to position {}",
+            item_id, new_position
+        );
+    }
+    Ok(())
+}
+
+/// New `history_index` after moving the history item at `from` to `to`, so the
+/// back/forward position keeps pointing at the same song
+fn adjusted_history_index(history_index: i64, from: i64, to: i64) -> i64 {
+    if history_index < 0 {
+        history_index
+    } else if history_index == from {
+        to
+    } else if from < history_index && history_index <= to {
+        history_index - 1
+    } else if to <= history_index && history_index < from {
+        history_index + 1
+    } else {
+        history_index
+    }
+}
+
+/// Move a queue or history item to a new position, shifting the items in between.
+/// Moving a history item keeps `history_index` on the same song.
+/// Returns false if the item was already at that position.
+fn reorder_item(
+    conn: &Connection,
+    session_id: i64,
+    item_type: &str,
+    item_id: &str,
+    new_position: i64,
+) -> Result<bool, CommandError> {
+    let resource = match item_type {
+        "queue" => "Queue item",
+        "history" => "History item",
+        _ => {
+            return Err(CommandError::Validation(format!(
+                "Invalid item_type: {}",
+                item_type
+            )))
+        }
+    };
+
+    // Validate new_position is not negative
+    if new_position < 0 {
+        return Err(CommandError::Validation(
+            "Position cannot be negative".to_string(),
+        ));
+    }
+
     // Get max position to validate bounds
     let max_position: i64 = conn
         .query_row(
-            "SELECT COALESCE(MAX(position), 0) FROM queue_items WHERE session_id = ?1 AND item_type = 'queue'",
-            [session_id],
+            "SELECT COALESCE(MAX(position), 0) FROM queue_items WHERE session_id = ?1 AND item_type = ?2",
+            rusqlite::params![session_id, item_type],
             |row| row.get(0),
         )
         .unwrap_or(0);
@@ -187,14 +598,20 @@ pub fn queue_reorder(
     }
 
     // Get current position
-    let current_position: i64 = conn.query_row(
-        "SELECT position FROM queue_items WHERE id = ?1 AND session_id = ?2 AND item_type = 'queue'",
-        rusqlite::params![item_id, session_id],
-        |row| row.get(0),
-    )?;
+    let current_position: i64 = conn
+        .query_row(
+            "SELECT position FROM queue_items WHERE id = ?1 AND session_id = ?2 AND item_type = ?3",
+            rusqlite::params![item_id, session_id, item_type],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound {
+            resource,
+            id: item_id.to_string(),
+        })?;
 
     if current_position == new_position {
-        return Ok(());
+        return Ok(false);
     }
 
     // Use transaction for atomicity
@@ -206,17 +623,17 @@ pub fn queue_reorder(
             // Moving up: shift items down
             conn.execute(
                 "UPDATE queue_items SET position = position + 1
-                 WHERE session_id = ?1 AND item_type = 'queue'
-                 AND position >= ?2 AND position < ?3",
-                rusqlite::params![session_id, new_position, current_position],
+                 WHERE session_id = ?1 AND item_type = ?2
+                 AND position >= ?3 AND position < ?4",
+                rusqlite::params![session_id, item_type, new_position, current_position],
             )?;
         } else {
             // Moving down: shift items up
             conn.execute(
                 "UPDATE queue_items SET position = position - 1
-                 WHERE session_id = ?1 AND item_type = 'queue'
-                 AND position > ?2 AND position <= ?3",
-                rusqlite::params![session_id, current_position, new_position],
+                 WHERE session_id = ?1 AND item_type = ?2
+                 AND position > ?3 AND position <= ?4",
+                rusqlite::params![session_id, item_type, current_position, new_position],
             )?;
         }
 
@@ -226,17 +643,28 @@ pub fn queue_reorder(
             rusqlite::params![new_position, item_id],
         )?;
 
+        if item_type == "history" {
+            let history_index: i64 = conn.query_row(
+                "SELECT history_index FROM sessions WHERE id = ?1",
+                [session_id],
+                |row| row.get(0),
+            )?;
+            let new_index = adjusted_history_index(history_index, current_position, new_position);
+            if new_index != history_index {
+                conn.execute(
+                    "UPDATE sessions SET history_index = ?1 WHERE id = ?2",
+                    rusqlite::params![new_index, session_id],
+                )?;
+            }
+        }
+
         Ok(())
     })();
 
     match result {
         Ok(()) => {
             conn.execute("COMMIT", [])?;
-            info!(
-                "Reordered queue item {} to position {}",
-                item_id, new_position
-            );
-            Ok(())
+            Ok(true)
         }
         Err(e) => {
             let _ = conn.execute("ROLLBACK", []);
@@ -245,14 +673,34 @@ pub fn queue_reorder(
     }
 }
 
+/// Move a history item to a new position to correct the order of past songs.
+/// `history_index` is adjusted so back/forward navigation stays on the same song.
 #[tauri::command]
-pub fn queue_clear(state: State<'_, AppState>) -> Result<(), CommandError> {
-    info!("Clearing queue");
+pub fn queue_reorder_history(
+    state: State<'_, AppState>,
+    item_id: String,
+    new_position: i64,
+) -> Result<(), CommandError> {
+    debug!(
+        "Reordering history item {} to position {}",
+        item_id, new_position
+    );
     let db = state.db.lock().map_lock_err()?;
-
     let session_id = get_active_session_id(&db)?;
 
-    db.connection().execute(
+    reorder_item(db.connection(), session_id, "history", &item_id, new_position)?;
+    info!(
+        "Reordered history item {} to position {}",
+        item_id, new_position
+    );
+    Ok(())
+}
+
+/// Remove all queued (not yet played) items from a session
+fn clear_queue_items(conn: &Connection, session_id: i64) -> Result<(), CommandError> {
+    ensure_not_party_mode(conn, "Clearing the queue")?;
+
+    conn.execute(
         "DELETE FROM queue_items WHERE session_id = ?1 AND item_type = 'queue'",
         [session_id],
     )?;
@@ -260,6 +708,16 @@ pub fn queue_clear(state: State<'_, AppState>) -> Result<(), CommandError> {
     Ok(())
 }
 
+#[tauri::command]
+pub fn queue_clear(state: State<'_, AppState>) -> Result<(), CommandError> {
+    info!("Clearing queue");
+    let db = state.db.lock().map_lock_err()?;
+
+    let session_id = get_active_session_id(&db)?;
+
+    clear_queue_items(db.connection(), session_id)
+}
+
 // ============ History Commands ============
 
 #[tauri::command]
@@ -313,16 +771,19 @@ pub fn queue_move_to_history(
 #[tauri::command]
 pub fn queue_add_to_history(
     state: State<'_, AppState>,
-    item: QueueItemData,
+    mut item: QueueItemData,
 ) -> Result<(), CommandError> {
     debug!(
         "Adding item directly to history: {} - {}",
         item.id, item.title
     );
+    normalize_video_ids(&item.source, &mut item.video_id, &mut item.youtube_id)?;
+    validate_playback_adjustments(&item.playback_adjustments)?;
     let db = state.db.lock().map_lock_err()?;
     let conn = db.connection();
 
     let session_id = get_active_session_id(&db)?;
+    let chapters_json = item.chapters.as_ref().map(serde_json::to_string).transpose()?;
 
     // Use transaction for atomicity (prevent duplicate positions)
     conn.execute("BEGIN IMMEDIATE", [])?;
@@ -336,8 +797,8 @@ pub fn queue_add_to_history(
         )?;
 
         conn.execute(
-            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, played_at)
-             VALUES (?1, ?2, 'history', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, datetime('now'))",
+            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, played_at, chapters, playback_rate, transpose)
+             VALUES (?1, ?2, 'history', ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, datetime('now'), ?13, ?14, ?15)",
             rusqlite::params![
                 item.id,
                 session_id,
@@ -350,7 +811,10 @@ pub fn queue_add_to_history(
                 item.youtube_id,
                 item.file_path,
                 position,
-                item.added_at
+                item.added_at,
+                chapters_json,
+                item.playback_adjustments.playback_rate,
+                item.playback_adjustments.transpose
             ],
         )?;
 
@@ -377,6 +841,7 @@ pub fn queue_add_to_history(
 pub fn queue_clear_history(state: State<'_, AppState>) -> Result<(), CommandError> {
     info!("Clearing history");
     let db = state.db.lock().map_lock_err()?;
+    ensure_not_party_mode(db.connection(), "Clearing history")?;
 
     let session_id = get_active_session_id(&db)?;
 
@@ -385,9 +850,9 @@ pub fn queue_clear_history(state: State<'_, AppState>) -> Result<(), CommandErro
         [session_id],
     )?;
 
-    // Reset history index
+    // Reset history index and now-playing marker
     db.connection().execute(
-        "UPDATE sessions SET history_index = -1 WHERE id = ?1",
+        "UPDATE sessions SET history_index = -1, current_item_id = NULL WHERE id = ?1",
         [session_id],
     )?;
 
@@ -430,9 +895,9 @@ pub fn queue_move_all_history_to_queue(state: State<'_, AppState>) -> Result<(),
             rusqlite::params![queue_max_position, session_id],
         )?;
 
-        // Reset history index since history is now empty
+        // Reset history index and now-playing marker since history is now empty
         conn.execute(
-            "UPDATE sessions SET history_index = -1 WHERE id = ?1",
+            "UPDATE sessions SET history_index = -1, current_item_id = NULL WHERE id = ?1",
             [session_id],
         )?;
 
@@ -467,6 +932,64 @@ pub fn queue_set_history_index(state: State<'_, AppState>, index: i64) -> Result
     Ok(())
 }
 
+/// Set (or clear) the item currently playing in the active session
+#[tauri::command]
+pub fn queue_set_current_item(
+    state: State<'_, AppState>,
+    item_id: Option<String>,
+) -> Result<(), CommandError> {
+    debug!("Setting current item to {:?}", item_id);
+    let db = state.db.lock().map_lock_err()?;
+
+    let session_id = get_active_session_id(&db)?;
+
+    if let Some(ref item_id) = item_id {
+        if !item_in_session(db.connection(), session_id, item_id)? {
+            return Err(CommandError::NotFound {
+                resource: "Queue item",
+                id: item_id.clone(),
+            });
+        }
+    }
+
+    db.connection().execute(
+        "UPDATE sessions SET current_item_id = ?1 WHERE id = ?2",
+        rusqlite::params![item_id, session_id],
+    )?;
+
+    Ok(())
+}
+
+/// Check whether a queue or history item belongs to a session
+fn item_in_session(conn: &Connection, session_id: i64, item_id: &str) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM queue_items WHERE id = ?1 AND session_id = ?2)",
+        rusqlite::params![item_id, session_id],
+        |row| row.get(0),
+    )
+}
+
+/// Load a session's now-playing marker, clearing it if the item no longer exists
+fn load_current_item_id(conn: &Connection, session_id: i64) -> Result<Option<String>, rusqlite::Error> {
+    let current_item_id: Option<String> = conn.query_row(
+        "SELECT current_item_id FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| row.get(0),
+    )?;
+
+    match current_item_id {
+        Some(item_id) if !item_in_session(conn, session_id, &item_id)? => {
+            debug!("Current item {} was removed, clearing marker", item_id);
+            conn.execute(
+                "UPDATE sessions SET current_item_id = NULL WHERE id = ?1",
+                [session_id],
+            )?;
+            Ok(None)
+        }
+        current_item_id => Ok(current_item_id),
+    }
+}
+
 // ============ Fair Shuffle Command ============
 
 /// Constant for unassigned singer ID
@@ -483,6 +1006,16 @@ const UNASSIGNED_SINGER_ID: i64 = -1;
 /// Complexity: O(n² × s) where n = items, s = singers per item.
 /// Acceptable for typical karaoke queues (<100 items).
 fn compute_fair_shuffle_order(items: &[(String, Vec<i64>)]) -> Vec<String> {
+    fair_shuffle_order(items, false)
+}
+
+/// Fair shuffle order that keeps the first item in place (still counting it for
+/// its singers), so the song about to be played is never bumped by a new addition.
+fn compute_pinned_fair_shuffle_order(items: &[(String, Vec<i64>)]) -> Vec<String> {
+    fair_shuffle_order(items, true)
+}
+
+fn fair_shuffle_order(items: &[(String, Vec<i64>)], pin_first: bool) -> Vec<String> {
     if items.len() <= 1 {
         return items.iter().map(|(id, _)| id.clone()).collect();
     }
@@ -511,33 +1044,37 @@ fn compute_fair_shuffle_order(items: &[(String, Vec<i64>)]) -> Vec<String> {
     let mut shuffled_ids: Vec<String> = Vec::with_capacity(items.len());
 
     while !remaining.is_empty() {
-        // Find the item with the lowest MAX singer count.
+        // Keep a pinned first item; otherwise find the item with the lowest MAX singer count.
         // Using MAX ensures duets are placed when ALL their singers are due,
         // not just when any one of them is due.
-        let best_idx = remaining
-            .iter()
-            .enumerate()
-            .min_by(|(_, a), (_, b)| {
-                let a_max = a.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).max().unwrap_or(0);
-                let b_max = b.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).max().unwrap_or(0);
-
-                a_max.cmp(&b_max)
-                    .then_with(|| {
-                        // Tie-break: prefer items with lower MIN count (more "due" overall)
-                        let a_min = a.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).min().unwrap_or(0);
-                        let b_min = b.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).min().unwrap_or(0);
-                        a_min.cmp(&b_min)
-                    })
-                    .then_with(|| {
-                        // Tie-break: earliest singer in appearance order
-                        let a_earliest = a.1.iter().filter_map(|s| singer_order.iter().position(|x| x == s)).min().unwrap_or(usize::MAX);
-                        let b_earliest = b.1.iter().filter_map(|s| singer_order.iter().position(|x| x == s)).min().unwrap_or(usize::MAX);
-                        a_earliest.cmp(&b_earliest)
-                    })
-                    .then_with(|| a.2.cmp(&b.2))
-            })
-            .map(|(idx, _)| idx)
-            .expect("remaining should not be empty during iteration");
+        let best_idx = if pin_first && shuffled_ids.is_empty() {
+            0
+        } else {
+            remaining
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| {
+                    let a_max = a.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).max().unwrap_or(0);
+                    let b_max = b.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).max().unwrap_or(0);
+
+                    a_max.cmp(&b_max)
+                        .then_with(|| {
+                            // Tie-break: prefer items with lower MIN count (more "due" overall)
+                            let a_min = a.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).min().unwrap_or(0);
+                            let b_min = b.1.iter().map(|s| *singer_counts.get(s).unwrap_or(&0)).min().unwrap_or(0);
+                            a_min.cmp(&b_min)
+                        })
+                        .then_with(|| {
+                            // Tie-break: earliest singer in appearance order
+                            let a_earliest = a.1.iter().filter_map(|s| singer_order.iter().position(|x| x == s)).min().unwrap_or(usize::MAX);
+                            let b_earliest = b.1.iter().filter_map(|s| singer_order.iter().position(|x| x == s)).min().unwrap_or(usize::MAX);
+                            a_earliest.cmp(&b_earliest)
+                        })
+                        .then_with(|| a.2.cmp(&b.2))
+                })
+                .map(|(idx, _)| idx)
+                .expect("remaining should not be empty during iteration")
+        };
 
         let (id, singer_ids, _) = remaining.remove(best_idx);
         shuffled_ids.push(id);
@@ -550,18 +1087,12 @@ fn compute_fair_shuffle_order(items: &[(String, Vec<i64>)]) -> Vec<String> {
     shuffled_ids
 }
 
-/// Reorganize queue items into fair round-robin order by singer.
-/// Multi-singer items (duets) count as one song for ALL singers involved.
-/// Items without singers are treated as "Unassigned" group.
-#[tauri::command]
-pub fn queue_fair_shuffle(state: State<'_, AppState>) -> Result<(), CommandError> {
-    info!("Fair shuffling queue");
-    let db = state.db.lock().map_lock_err()?;
-    let conn = db.connection();
-
-    let session_id = get_active_session_id(&db)?;
-
-    // Get all queue items with ALL their singer IDs
+/// Load pending queue items with ALL their singer IDs, in queue order.
+/// Items without singers get the "unassigned" singer ID.
+fn load_fair_shuffle_items(
+    conn: &Connection,
+    session_id: i64,
+) -> Result<Vec<(String, Vec<i64>)>, CommandError> {
     let mut stmt = conn.prepare(
         "SELECT qi.id, qi.position,
                 (SELECT GROUP_CONCAT(qs.singer_id, ',')
@@ -574,7 +1105,7 @@ pub fn queue_fair_shuffle(state: State<'_, AppState>) -> Result<(), CommandError
     )?;
 
     // Collect items: (id, singer_ids)
-    let items: Vec<(String, Vec<i64>)> = stmt
+    let items = stmt
         .query_map([session_id], |row| {
             let id: String = row.get(0)?;
             let singer_ids_str: Option<String> = row.get(2)?;
@@ -599,22 +1130,315 @@ pub fn queue_fair_shuffle(state: State<'_, AppState>) -> Result<(), CommandError
         })?
         .collect::<Result<Vec<_>, _>>()?;
 
-    if items.len() <= 1 {
-        debug!("Queue has {} pending items, no shuffle needed", items.len());
-        return Ok(());
+    Ok(items)
+}
+
+/// Compute the fair shuffle order for the session's queue without modifying it.
+fn preview_fair_shuffle(conn: &Connection, session_id: i64) -> Result<Vec<String>, CommandError> {
+    let items = load_fair_shuffle_items(conn, session_id)?;
+    Ok(compute_fair_shuffle_order(&items))
+}
+
+/// The session's pending items in the order a fair shuffle would give them, without
+/// modifying the queue. Items keep their current `position`, so a preview can show
+/// what moves.
+fn preview_fair_shuffle_items(conn: &Connection, session_id: i64) -> Result<Vec<QueueItemData>, CommandError> {
+    let order = preview_fair_shuffle(conn, session_id)?;
+
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {} FROM queue_items WHERE session_id = ?1 AND item_type = 'queue'",
+        QUEUE_ITEM_COLUMNS
+    ))?;
+    let mut by_id = stmt
+        .query_map([session_id], queue_item_from_row)?
+        .map(|item| item.map(|item| (item.id.clone(), item)))
+        .collect::<Result<std::collections::HashMap<_, _>, _>>()?;
+
+    Ok(order.iter().filter_map(|id| by_id.remove(id)).collect())
+}
+
+/// Store a new order of the session's pending queue (ids in their new positions)
+fn write_queue_order(conn: &Connection, session_id: i64, ids: &[String]) -> Result<(), CommandError> {
+    for (new_position, id) in ids.iter().enumerate() {
+        conn.execute(
+            "UPDATE queue_items SET position = ?1 WHERE id = ?2 AND session_id = ?3",
+            rusqlite::params![new_position as i64, id, session_id],
+        )?;
+    }
+    Ok(())
+}
+
+/// Apply fair shuffle to the session's queue, returning the number of items reordered.
+fn apply_fair_shuffle(conn: &Connection, session_id: i64) -> Result<usize, CommandError> {
+    apply_rotation(conn, session_id, RotationMode::Fair, 0)
+}
+
+/// Reorder the session's queue by a rotation mode, returning the number of items reordered
+/// (0 for FIFO, which keeps the current order). `seed` is only used by random mode.
+fn apply_rotation(
+    conn: &Connection,
+    session_id: i64,
+    mode: RotationMode,
+    seed: u64,
+) -> Result<usize, CommandError> {
+    let items = load_fair_shuffle_items(conn, session_id)?;
+
+    if items.len() <= 1 || mode == RotationMode::Fifo {
+        debug!("Queue has {} pending items in {:?} mode, no shuffle needed", items.len(), mode);
+        return Ok(0);
     }
 
-    // Compute fair shuffle order using extracted algorithm
-    let shuffled_ids = compute_fair_shuffle_order(&items);
+    let shuffled_ids = compute_rotation_order(mode, &items, seed);
 
     // Update positions in database within a transaction
     conn.execute("BEGIN IMMEDIATE", [])?;
 
+    let result = write_queue_order(conn, session_id, &shuffled_ids);
+
+    match result {
+        Ok(()) => {
+            conn.execute("COMMIT", [])?;
+            Ok(shuffled_ids.len())
+        }
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    }
+}
+
+// ============ Rotation Modes ============
+
+/// How a session's pending queue is ordered by `queue_apply_rotation`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RotationMode {
+    /// First come, first served: the queue keeps the order songs were added in
+    Fifo,
+    /// Round-robin by singer, as `queue_fair_shuffle` does
+    #[default]
+    Fair,
+    /// Random order
+    Random,
+}
+
+impl RotationMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            RotationMode::Fifo => "fifo",
+            RotationMode::Fair => "fair",
+            RotationMode::Random => "random",
+        }
+    }
+
+    fn from_stored(value: &str) -> Option<Self> {
+        match value {
+            "fifo" => Some(RotationMode::Fifo),
+            "fair" => Some(RotationMode::Fair),
+            "random" => Some(RotationMode::Random),
+            _ => None,
+        }
+    }
+}
+
+/// Queue order (item ids) for a rotation mode. Items are (id, singer_ids) in queue order.
+fn compute_rotation_order(mode: RotationMode, items: &[(String, Vec<i64>)], seed: u64) -> Vec<String> {
+    match mode {
+        RotationMode::Fifo => items.iter().map(|(id, _)| id.clone()).collect(),
+        RotationMode::Fair => compute_fair_shuffle_order(items),
+        RotationMode::Random => {
+            let ids: Vec<String> = items.iter().map(|(id, _)| id.clone()).collect();
+            let count = ids.len();
+            sample_random(ids, count, seed)
+        }
+    }
+}
+
+/// A session's rotation mode (fair if never set)
+pub(super) fn read_rotation_mode(conn: &Connection, session_id: i64) -> Result<RotationMode, CommandError> {
+    let stored: String = conn
+        .query_row(
+            "SELECT rotation_mode FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound {
+            resource: "Session",
+            id: session_id.to_string(),
+        })?;
+
+    Ok(RotationMode::from_stored(&stored).unwrap_or_else(|| {
+        warn!("Unknown rotation mode '{}' in session {}, using fair", stored, session_id);
+        RotationMode::default()
+    }))
+}
+
+/// Store a session's rotation mode
+pub(super) fn write_rotation_mode(conn: &Connection, session_id: i64, mode: RotationMode) -> Result<(), CommandError> {
+    let updated = conn.execute(
+        "UPDATE sessions SET rotation_mode = ?1 WHERE id = ?2",
+        rusqlite::params![mode.as_str(), session_id],
+    )?;
+    if updated == 0 {
+        return Err(CommandError::NotFound {
+            resource: "Session",
+            id: session_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Reorder the queue by the active session's rotation mode: fair round-robin,
+/// random (pass `seed` for a repeatable order) or FIFO, which leaves it as is.
+/// Returns the number of items reordered.
+#[tauri::command]
+pub fn queue_apply_rotation(state: State<'_, AppState>, seed: Option<u64>) -> Result<usize, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+    let mode = read_rotation_mode(db.connection(), session_id)?;
+    info!("Applying {:?} rotation to queue", mode);
+
+    let count = apply_rotation(db.connection(), session_id, mode, seed_or_now(seed))?;
+    if count > 0 {
+        info!("Reordered {} queue items ({:?})", count, mode);
+    }
+    Ok(count)
+}
+
+/// Whether a session keeps its queue in fair order automatically
+pub(super) fn auto_shuffle_enabled(conn: &Connection, session_id: i64) -> Result<bool, CommandError> {
+    conn.query_row(
+        "SELECT auto_shuffle FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or_else(|| CommandError::NotFound {
+        resource: "Session",
+        id: session_id.to_string(),
+    })
+}
+
+/// Re-run the fair shuffle if the session has auto-shuffle on, keeping position 0
+/// pinned. Must run inside the caller's transaction. Returns whether it reordered.
+pub(super) fn auto_fair_shuffle(conn: &Connection, session_id: i64) -> Result<bool, CommandError> {
+    if !auto_shuffle_enabled(conn, session_id)? {
+        return Ok(false);
+    }
+    pinned_fair_shuffle(conn, session_id)
+}
+
+/// Fair shuffle the queue keeping the first item (the next song) in place.
+/// Returns false when there's nothing that could move.
+fn pinned_fair_shuffle(conn: &Connection, session_id: i64) -> Result<bool, CommandError> {
+    let items = load_fair_shuffle_items(conn, session_id)?;
+    if items.len() <= 2 {
+        // Nothing can move with the first item pinned
+        return Ok(false);
+    }
+
+    let shuffled_ids = compute_pinned_fair_shuffle_order(&items);
+    write_queue_order(conn, session_id, &shuffled_ids)?;
+    Ok(true)
+}
+
+/// Reorder the queue by a rotation mode keeping the first item (the next song) in
+/// place. FIFO leaves the queue as is. Returns false when nothing was reordered.
+fn pinned_rotation(conn: &Connection, session_id: i64, mode: RotationMode, seed: u64) -> Result<bool, CommandError> {
+    match mode {
+        RotationMode::Fifo => Ok(false),
+        RotationMode::Fair => pinned_fair_shuffle(conn, session_id),
+        RotationMode::Random => {
+            let items = load_fair_shuffle_items(conn, session_id)?;
+            if items.len() <= 2 {
+                return Ok(false);
+            }
+
+            let mut shuffled_ids = vec![items[0].0.clone()];
+            shuffled_ids.extend(compute_rotation_order(mode, &items[1..], seed));
+            write_queue_order(conn, session_id, &shuffled_ids)?;
+            Ok(true)
+        }
+    }
+}
+
+/// Reorganize queue items into fair round-robin order by singer.
+/// Multi-singer items (duets) count as one song for ALL singers involved.
+/// Items without singers are treated as "Unassigned" group.
+#[tauri::command]
+pub fn queue_fair_shuffle(state: State<'_, AppState>) -> Result<(), CommandError> {
+    info!("Fair shuffling queue");
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+
+    let count = apply_fair_shuffle(db.connection(), session_id)?;
+    if count > 0 {
+        info!("Fair shuffled {} queue items", count);
+    }
+    Ok(())
+}
+
+/// Preview the fair shuffle order of the queue without applying it.
+/// Returns the pending items in the order `queue_fair_shuffle` would produce,
+/// so the host can review it before confirming.
+#[tauri::command]
+pub fn queue_preview_fair_shuffle(state: State<'_, AppState>) -> Result<Vec<QueueItemData>, CommandError> {
+    debug!("Previewing fair shuffle");
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+
+    preview_fair_shuffle_items(db.connection(), session_id)
+}
+
+// ============ Round-Robin Assignment ============
+
+/// Assign singers to pending queue items in order, wrapping around
+/// (item 0 → singer 0, item 1 → singer 1, ...). Prior assignments are replaced.
+/// Returns the number of items assigned.
+fn apply_round_robin(
+    conn: &Connection,
+    session_id: i64,
+    singer_ids: &[i64],
+) -> Result<usize, CommandError> {
+    if singer_ids.is_empty() {
+        return Err(CommandError::Validation(
+            "At least one singer is required".to_string(),
+        ));
+    }
+    for singer_id in singer_ids {
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM singers WHERE id = ?1)",
+            [singer_id],
+            |row| row.get(0),
+        )?;
+        if !exists {
+            return Err(CommandError::NotFound {
+                resource: "Singer",
+                id: singer_id.to_string(),
+            });
+        }
+    }
+
+    let mut stmt = conn.prepare(
+        "SELECT id FROM queue_items
+         WHERE session_id = ?1 AND item_type = 'queue'
+         ORDER BY position",
+    )?;
+    let item_ids = stmt
+        .query_map([session_id], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    conn.execute("BEGIN IMMEDIATE", [])?;
+
     let result = (|| -> Result<(), CommandError> {
-        for (new_position, id) in shuffled_ids.iter().enumerate() {
+        for (i, item_id) in item_ids.iter().enumerate() {
+            let singer_id = singer_ids[i % singer_ids.len()];
+            conn.execute("DELETE FROM queue_singers WHERE queue_item_id = ?1", [item_id])?;
+            apply_singer_song_preference(conn, item_id, singer_id)?;
             conn.execute(
-                "UPDATE queue_items SET position = ?1 WHERE id = ?2 AND session_id = ?3",
-                rusqlite::params![new_position as i64, id, session_id],
+                "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, 0)",
+                rusqlite::params![item_id, singer_id],
             )?;
         }
         Ok(())
@@ -623,8 +1447,7 @@ pub fn queue_fair_shuffle(state: State<'_, AppState>) -> Result<(), CommandError
     match result {
         Ok(()) => {
             conn.execute("COMMIT", [])?;
-            info!("Fair shuffled {} queue items", shuffled_ids.len());
-            Ok(())
+            Ok(item_ids.len())
         }
         Err(e) => {
             let _ = conn.execute("ROLLBACK", []);
@@ -633,6 +1456,21 @@ pub fn queue_fair_shuffle(state: State<'_, AppState>) -> Result<(), CommandError
     }
 }
 
+/// Distribute singers across the pending queue in rotation, replacing prior assignments.
+#[tauri::command]
+pub fn queue_assign_round_robin(
+    state: State<'_, AppState>,
+    singer_ids: Vec<i64>,
+) -> Result<(), CommandError> {
+    info!("Assigning {} singers round-robin", singer_ids.len());
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+
+    let count = apply_round_robin(db.connection(), session_id, &singer_ids)?;
+    info!("Assigned singers to {} queue items", count);
+    Ok(())
+}
+
 // ============ Fair Queue Position Command ============
 
 /// Pure function that computes the fair insertion position for a new song.
@@ -752,31 +1590,583 @@ pub fn queue_compute_fair_position(
          ORDER BY qi.position",
     )?;
 
-    let queue_singer_ids: Vec<i64> = stmt
-        .query_map([session_id], |row| {
-            let singer_id: Option<i64> = row.get(1)?;
-            Ok(singer_id.unwrap_or(UNASSIGNED_SINGER_ID))
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+    let queue_singer_ids: Vec<i64> = stmt
+        .query_map([session_id], |row| {
+            let singer_id: Option<i64> = row.get(1)?;
+            Ok(singer_id.unwrap_or(UNASSIGNED_SINGER_ID))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let position = compute_fair_position(&queue_singer_ids, effective_singer_id);
+
+    info!(
+        "Computed fair position {} for singer {} (queue has {} items)",
+        position,
+        effective_singer_id,
+        queue_singer_ids.len()
+    );
+
+    // Safe conversion from usize to i32
+    let position_i32: i32 = position.try_into().map_err(|_| {
+        CommandError::Validation(format!(
+            "Fair position {} exceeds i32::MAX, queue too large",
+            position
+        ))
+    })?;
+
+    Ok(position_i32)
+}
+
+// ============ Queue Runtime Command ============
+
+/// Settings key for the gap between songs (seconds), used in runtime estimates
+const CHANGEOVER_SECONDS_KEY: &str = "changeover_seconds";
+
+/// Pure function that totals queue durations.
+/// Unknown durations are counted separately; a changeover gap is added
+/// between each pair of consecutive songs.
+fn compute_queue_runtime(durations: &[Option<i64>], changeover_secs: i64) -> QueueRuntime {
+    let known_secs: i64 = durations.iter().flatten().map(|d| (*d).max(0)).sum();
+    let unknown_count = durations.iter().filter(|d| d.is_none()).count() as u32;
+    let gaps = durations.len().saturating_sub(1) as i64;
+
+    QueueRuntime {
+        total_secs: known_secs + gaps * changeover_secs.max(0),
+        unknown_count,
+    }
+}
+
+/// Compute how long the pending queue will take to play.
+#[tauri::command]
+pub fn queue_total_runtime(state: State<'_, AppState>) -> Result<QueueRuntime, CommandError> {
+    debug!("Computing queue total runtime");
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+
+    let changeover_secs = db
+        .get_setting(CHANGEOVER_SECONDS_KEY)?
+        .and_then(|v| v.trim().parse::<i64>().ok())
+        .unwrap_or(0);
+
+    let mut stmt = db.connection().prepare(
+        "SELECT duration FROM queue_items
+         WHERE session_id = ?1 AND item_type = 'queue'
+         ORDER BY position",
+    )?;
+    let durations = stmt
+        .query_map([session_id], |row| row.get::<_, Option<i64>>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let runtime = compute_queue_runtime(&durations, changeover_secs);
+    debug!(
+        "Queue runtime: {}s across {} items ({} unknown)",
+        runtime.total_secs,
+        durations.len(),
+        runtime.unknown_count
+    );
+    Ok(runtime)
+}
+
+// ============ Duration Backfill ============
+
+/// Event emitted after each backfill step with a `DurationBackfillProgress` payload
+const DURATION_BACKFILL_PROGRESS_EVENT: &str = "queue:duration-backfill-progress";
+
+/// Maximum video IDs per YouTube Data API videos.list request
+const DURATION_API_BATCH_SIZE: usize = 50;
+
+/// Pause between yt-dlp lookups to avoid being rate limited by YouTube
+const YTDLP_LOOKUP_DELAY: Duration = Duration::from_millis(500);
+
+/// Progress of a running duration backfill
+#[derive(Debug, Clone, Serialize)]
+pub struct DurationBackfillProgress {
+    /// Items looked up so far
+    pub processed: u32,
+    /// Items that needed a duration when the backfill started
+    pub total: u32,
+    /// Items whose duration was filled in so far
+    pub filled: u32,
+}
+
+/// Pending queue items with no duration that can be looked up on YouTube.
+/// Returns (queue_item_id, youtube_id) in queue order.
+fn load_items_missing_duration(
+    conn: &Connection,
+    session_id: i64,
+) -> rusqlite::Result<Vec<(String, String)>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, youtube_id FROM queue_items
+         WHERE session_id = ?1 AND item_type = 'queue'
+           AND duration IS NULL AND youtube_id IS NOT NULL AND youtube_id != ''
+         ORDER BY position",
+    )?;
+    let items = stmt
+        .query_map([session_id], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Store durations for queue items, skipping items that gained a duration meanwhile.
+/// Returns the number of items updated.
+fn store_item_durations<'a>(
+    state: &AppState,
+    durations: impl IntoIterator<Item = (&'a str, i64)>,
+) -> Result<u32, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let mut filled = 0;
+    for (item_id, duration) in durations {
+        filled += db.connection().execute(
+            "UPDATE queue_items SET duration = ?1 WHERE id = ?2 AND duration IS NULL",
+            rusqlite::params![duration, item_id],
+        )? as u32;
+    }
+    Ok(filled)
+}
+
+/// Fetch missing durations for YouTube items in the active session's queue.
+/// Uses the YouTube Data API (batched) when a key is configured, otherwise yt-dlp.
+/// Emits `queue:duration-backfill-progress` as it goes and returns how many items were filled.
+#[tauri::command]
+pub async fn queue_backfill_durations(
+    app: AppHandle,
+    state: State<'_, AppState>,
+) -> Result<u32, CommandError> {
+    // SECURITY: Never log the API key - it should remain secret
+    let (items, api_key) = {
+        let db = state.db.lock().map_lock_err()?;
+        let session_id = get_active_session_id(&db)?;
+        let items = load_items_missing_duration(db.connection(), session_id)?;
+        let api_key = db
+            .get_setting("youtube_api_key")?
+            .filter(|k| !k.trim().is_empty());
+        (items, api_key)
+    };
+
+    if items.is_empty() {
+        debug!("No queue items need a duration");
+        return Ok(0);
+    }
+
+    let mut progress = DurationBackfillProgress {
+        processed: 0,
+        total: items.len() as u32,
+        filled: 0,
+    };
+    info!("Backfilling durations for {} queue items", progress.total);
+
+    if let Some(api_key) = api_key {
+        let service = YouTubeApiService::new(api_key).map_err(CommandError::External)?;
+        for chunk in items.chunks(DURATION_API_BATCH_SIZE) {
+            let video_ids: Vec<&str> = chunk.iter().map(|(_, youtube_id)| youtube_id.as_str()).collect();
+            let durations = match service.fetch_video_durations(&video_ids).await {
+                Ok(durations) => durations,
+                Err(e) => {
+                    // Quota or key problems won't go away for the next batch
+                    warn!("Stopping duration backfill: {}", e);
+                    break;
+                }
+            };
+            progress.filled += store_item_durations(
+                &state,
+                chunk.iter().filter_map(|(item_id, youtube_id)| {
+                    durations.get(youtube_id).map(|&d| (item_id.as_str(), d as i64))
+                }),
+            )?;
+            progress.processed += chunk.len() as u32;
+            let _ = app.emit(DURATION_BACKFILL_PROGRESS_EVENT, &progress);
+        }
+    } else {
+        let service = YtDlpService::new();
+        for (i, (item_id, youtube_id)) in items.iter().enumerate() {
+            if i > 0 {
+                tokio::time::sleep(YTDLP_LOOKUP_DELAY).await;
+            }
+            match service.get_video_info(youtube_id).await {
+                Ok(info) => {
+                    if let Some(duration) = info.duration {
+                        progress.filled +=
+                            store_item_durations(&state, [(item_id.as_str(), duration as i64)])?;
+                    }
+                }
+                Err(YtDlpError::NotFound) => {
+                    warn!("Stopping duration backfill: yt-dlp not found");
+                    break;
+                }
+                Err(e) => warn!("Failed to fetch duration for {}: {}", youtube_id, e),
+            }
+            progress.processed += 1;
+            let _ = app.emit(DURATION_BACKFILL_PROGRESS_EVENT, &progress);
+        }
+    }
+
+    info!(
+        "Duration backfill filled {} of {} queue items",
+        progress.filled, progress.total
+    );
+    Ok(progress.filled)
+}
+
+// ============ Pre-flight Validation ============
+
+/// Maximum yt-dlp availability lookups running at once
+const ONLINE_CHECK_CONCURRENCY: usize = 4;
+
+/// Why a pending queue item would fail to play
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UnavailableReason {
+    /// Local file was moved or deleted, or its drive is not connected
+    FileMissing,
+    /// Item has no file path or YouTube ID to play from
+    NoSource,
+    /// YouTube reports the video as removed, private or blocked
+    VideoUnavailable,
+}
+
+/// A pending queue item that would fail to play
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UnavailableItem {
+    pub item_id: String,
+    pub reason: UnavailableReason,
+}
+
+/// Playback source of a pending queue item
+struct PendingSource {
+    id: String,
+    source: String,
+    youtube_id: Option<String>,
+    file_path: Option<String>,
+}
+
+/// Pending queue items of a session with their playback sources, in queue order
+fn load_pending_sources(conn: &Connection, session_id: i64) -> rusqlite::Result<Vec<PendingSource>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, source, youtube_id, file_path FROM queue_items
+         WHERE session_id = ?1 AND item_type = 'queue'
+         ORDER BY position",
+    )?;
+    let items = stmt
+        .query_map([session_id], |row| {
+            Ok(PendingSource {
+                id: row.get(0)?,
+                source: row.get(1)?,
+                youtube_id: row.get(2)?,
+                file_path: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(items)
+}
+
+/// Offline check of an item: local items need an existing file, others a YouTube ID
+fn offline_unavailable_reason(item: &PendingSource) -> Option<UnavailableReason> {
+    if item.source == "local" {
+        return match item.file_path.as_deref().filter(|p| !p.is_empty()) {
+            Some(path) if LibraryScanner::check_file_exists(path) => None,
+            Some(_) => Some(UnavailableReason::FileMissing),
+            None => Some(UnavailableReason::NoSource),
+        };
+    }
+    match item.youtube_id.as_deref() {
+        Some(id) if !id.trim().is_empty() => None,
+        _ => Some(UnavailableReason::NoSource),
+    }
+}
+
+/// Whether a failed yt-dlp lookup means the video itself can't be played.
+/// Network problems and an outdated yt-dlp say nothing about the video.
+fn ytdlp_error_means_unavailable(error: &YtDlpError) -> bool {
+    match error {
+        YtDlpError::ExecutionError(stderr) => classify_stderr(stderr) == YtDlpFailureKind::Other,
+        YtDlpError::NotFound | YtDlpError::ParseError(_) | YtDlpError::NoResults => false,
+    }
+}
+
+/// Look up YouTube IDs online and return the ones that can't be played.
+/// Uses the Data API (batched) when a key is configured, otherwise a few yt-dlp
+/// lookups at a time. Stops early if the service itself fails.
+async fn find_unavailable_videos(
+    youtube_ids: Vec<String>,
+    api_key: Option<String>,
+) -> Result<std::collections::HashSet<String>, CommandError> {
+    let mut unavailable = std::collections::HashSet::new();
+
+    if let Some(api_key) = api_key {
+        let service = YouTubeApiService::new(api_key).map_err(CommandError::External)?;
+        for chunk in youtube_ids.chunks(DURATION_API_BATCH_SIZE) {
+            let ids: Vec<&str> = chunk.iter().map(String::as_str).collect();
+            match service.fetch_video_infos(&ids).await {
+                // Unavailable videos are missing from the response
+                Ok(infos) => unavailable.extend(chunk.iter().filter(|id| !infos.contains_key(*id)).cloned()),
+                Err(e) => {
+                    warn!("Stopping online queue validation: {}", e);
+                    break;
+                }
+            }
+        }
+        return Ok(unavailable);
+    }
+
+    for chunk in youtube_ids.chunks(ONLINE_CHECK_CONCURRENCY) {
+        let mut tasks = tokio::task::JoinSet::new();
+        for youtube_id in chunk.iter().cloned() {
+            tasks.spawn(async move {
+                let result = YtDlpService::new().get_video_info(&youtube_id).await;
+                (youtube_id, result)
+            });
+        }
+
+        let mut ytdlp_missing = false;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((_, Err(YtDlpError::NotFound))) => ytdlp_missing = true,
+                Ok((youtube_id, Err(e))) => {
+                    if ytdlp_error_means_unavailable(&e) {
+                        unavailable.insert(youtube_id);
+                    } else {
+                        debug!("Could not check availability of {}: {}", youtube_id, e);
+                    }
+                }
+                Ok((_, Ok(_))) => {}
+                Err(e) => warn!("Queue validation: yt-dlp task failed: {}", e),
+            }
+        }
+        if ytdlp_missing {
+            warn!("Stopping online queue validation: yt-dlp not found");
+            break;
+        }
+    }
+    Ok(unavailable)
+}
+
+/// Find pending queue items that would fail to play, in queue order.
+/// Local files are always checked; YouTube videos are only looked up online when
+/// `check_online` is true, since that takes a network request per video (or batch).
+#[tauri::command]
+pub async fn queue_validate_items(
+    state: State<'_, AppState>,
+    check_online: Option<bool>,
+) -> Result<Vec<UnavailableItem>, CommandError> {
+    let check_online = check_online.unwrap_or(false);
+    debug!("Validating queue items (online: {})", check_online);
+
+    // SECURITY: Never log the API key - it should remain secret
+    let (items, api_key) = {
+        let db = state.db.lock().map_lock_err()?;
+        let session_id = get_active_session_id(&db)?;
+        let items = load_pending_sources(db.connection(), session_id)?;
+        let api_key = db
+            .get_setting("youtube_api_key")?
+            .filter(|k| !k.trim().is_empty());
+        (items, api_key)
+    };
+
+    let mut reasons: Vec<Option<UnavailableReason>> = items.iter().map(offline_unavailable_reason).collect();
+
+    if check_online {
+        let mut youtube_ids: Vec<String> = Vec::new();
+        for (item, reason) in items.iter().zip(&reasons) {
+            if reason.is_some() || item.source == "local" {
+                continue;
+            }
+            if let Some(id) = item.youtube_id.as_ref().filter(|id| !youtube_ids.contains(id)) {
+                youtube_ids.push(id.clone());
+            }
+        }
+
+        if !youtube_ids.is_empty() {
+            let unavailable = find_unavailable_videos(youtube_ids, api_key).await?;
+            for (item, reason) in items.iter().zip(reasons.iter_mut()) {
+                if item.source != "local" && item.youtube_id.as_ref().is_some_and(|id| unavailable.contains(id)) {
+                    *reason = Some(UnavailableReason::VideoUnavailable);
+                }
+            }
+        }
+    }
+
+    let unavailable: Vec<UnavailableItem> = items
+        .into_iter()
+        .zip(reasons)
+        .filter_map(|(item, reason)| reason.map(|reason| UnavailableItem { item_id: item.id, reason }))
+        .collect();
+    info!("Queue validation found {} unavailable items", unavailable.len());
+    Ok(unavailable)
+}
+
+// ============ Chapter Commands ============
+
+/// Decode chapters stored as JSON; unreadable data is treated as no chapters
+pub(super) fn parse_stored_chapters(json: Option<String>) -> Option<Vec<Chapter>> {
+    let json = json?;
+    match serde_json::from_str(&json) {
+        Ok(chapters) => Some(chapters),
+        Err(e) => {
+            warn!("Ignoring unreadable stored chapters: {}", e);
+            None
+        }
+    }
+}
+
+/// Validate chapters against the item's duration and store them on the item.
+/// Returns the chapters that were kept.
+fn set_item_chapters(
+    conn: &Connection,
+    session_id: i64,
+    item_id: &str,
+    chapters: Vec<Chapter>,
+) -> Result<Vec<Chapter>, CommandError> {
+    let duration: Option<i64> = conn
+        .query_row(
+            "SELECT duration FROM queue_items WHERE id = ?1 AND session_id = ?2",
+            rusqlite::params![item_id, session_id],
+            |row| row.get(0),
+        )
+        .map_err(|e| match e {
+            rusqlite::Error::QueryReturnedNoRows => CommandError::NotFound {
+                resource: "Queue item",
+                id: item_id.to_string(),
+            },
+            e => CommandError::Database(e),
+        })?;
+
+    let chapters = validate_chapters(chapters, duration.map(|d| d as f64));
+    conn.execute(
+        "UPDATE queue_items SET chapters = ?1 WHERE id = ?2 AND session_id = ?3",
+        rusqlite::params![serde_json::to_string(&chapters)?, item_id, session_id],
+    )?;
+    Ok(chapters)
+}
+
+/// Store chapters on a queue or history item so the player can seek to song sections.
+/// Chapters with invalid times are dropped; the stored list is returned.
+#[tauri::command]
+pub fn queue_set_item_chapters(
+    state: State<'_, AppState>,
+    item_id: String,
+    chapters: Vec<Chapter>,
+) -> Result<Vec<Chapter>, CommandError> {
+    debug!("Setting {} chapters on queue item {}", chapters.len(), item_id);
+    let db = state.db.lock().map_lock_err()?;
+
+    let session_id = get_active_session_id(&db)?;
+    let chapters = set_item_chapters(db.connection(), session_id, &item_id, chapters)?;
+
+    info!("Stored {} chapters on queue item {}", chapters.len(), item_id);
+    Ok(chapters)
+}
+
+// ============ History Navigation ============
+
+/// History position currently playing: `history_index`, with -1 (and out-of-range
+/// values) meaning the last history item. -1 when history is empty.
+fn effective_history_index(history_len: i64, history_index: i64) -> i64 {
+    if history_index < 0 || history_index >= history_len {
+        history_len - 1
+    } else {
+        history_index
+    }
+}
+
+/// Where "next" goes: the following history item while browsing back through
+/// history, otherwise the first queued item
+fn next_history_index(history_len: i64, history_index: i64) -> Option<i64> {
+    let current = effective_history_index(history_len, history_index);
+    (current < history_len - 1).then_some(current + 1)
+}
+
+/// Where "previous" goes: the preceding history item (None at the start of history)
+fn prev_history_index(history_len: i64, history_index: i64) -> Option<i64> {
+    let current = effective_history_index(history_len, history_index);
+    (current > 0).then_some(current - 1)
+}
+
+/// The `offset`-th item (by position) of the given type in a session
+fn load_item_at(
+    conn: &Connection,
+    session_id: i64,
+    item_type: &str,
+    offset: i64,
+) -> Result<Option<QueueItemData>, CommandError> {
+    let item = conn
+        .query_row(
+            &format!(
+                "SELECT {} FROM queue_items WHERE session_id = ?1 AND item_type = ?2
+                 ORDER BY position LIMIT 1 OFFSET ?3",
+                QUEUE_ITEM_COLUMNS
+            ),
+            rusqlite::params![session_id, item_type, offset],
+            queue_item_from_row,
+        )
+        .optional()?;
+    Ok(item)
+}
+
+/// History length and `history_index` of a session
+fn history_position(conn: &Connection, session_id: i64) -> Result<(i64, i64), CommandError> {
+    let history_index: i64 = conn.query_row(
+        "SELECT history_index FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| row.get(0),
+    )?;
+    let history_len: i64 = conn.query_row(
+        "SELECT COUNT(*) FROM queue_items WHERE session_id = ?1 AND item_type = 'history'",
+        [session_id],
+        |row| row.get(0),
+    )?;
+    Ok((history_len, history_index))
+}
+
+/// Give a resolved item the tempo and key the player should use: its own override,
+/// with the fields it leaves unset taken from the session's playback defaults
+fn with_effective_playback(
+    conn: &Connection,
+    session_id: i64,
+    item: Option<QueueItemData>,
+) -> Result<Option<QueueItemData>, CommandError> {
+    let Some(mut item) = item else {
+        return Ok(None);
+    };
+    let defaults = read_playback_defaults(conn, session_id)?;
+    item.playback_adjustments = item.playback_adjustments.or(defaults);
+    Ok(Some(item))
+}
 
-    let position = compute_fair_position(&queue_singer_ids, effective_singer_id);
+fn resolve_next_item(conn: &Connection, session_id: i64) -> Result<Option<QueueItemData>, CommandError> {
+    let (history_len, history_index) = history_position(conn, session_id)?;
+    let item = match next_history_index(history_len, history_index) {
+        Some(index) => load_item_at(conn, session_id, "history", index)?,
+        None => load_item_at(conn, session_id, "queue", 0)?,
+    };
+    with_effective_playback(conn, session_id, item)
+}
 
-    info!(
-        "Computed fair position {} for singer {} (queue has {} items)",
-        position,
-        effective_singer_id,
-        queue_singer_ids.len()
-    );
+fn resolve_prev_item(conn: &Connection, session_id: i64) -> Result<Option<QueueItemData>, CommandError> {
+    let (history_len, history_index) = history_position(conn, session_id)?;
+    let item = match prev_history_index(history_len, history_index) {
+        Some(index) => load_item_at(conn, session_id, "history", index)?,
+        None => None,
+    };
+    with_effective_playback(conn, session_id, item)
+}
 
-    // Safe conversion from usize to i32
-    let position_i32: i32 = position.try_into().map_err(|_| {
-        CommandError::Validation(format!(
-            "Fair position {} exceeds i32::MAX, queue too large",
-            position
-        ))
-    })?;
+/// The item "next" would play, without moving anything: the next history item
+/// when navigated back into history, otherwise the head of the queue. Its
+/// `playback_adjustments` are the effective ones (session defaults filled in).
+#[tauri::command]
+pub fn queue_resolve_next(state: State<'_, AppState>) -> Result<Option<QueueItemData>, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+    resolve_next_item(db.connection(), session_id)
+}
 
-    Ok(position_i32)
+/// The item "previous" would play, without moving anything (None at the start of history).
+/// Its `playback_adjustments` are the effective ones (session defaults filled in).
+#[tauri::command]
+pub fn queue_resolve_prev(state: State<'_, AppState>) -> Result<Option<QueueItemData>, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = get_active_session_id(&db)?;
+    resolve_prev_item(db.connection(), session_id)
 }
 
 // ============ State Recovery Commands ============
@@ -800,75 +2190,48 @@ pub fn queue_get_state(state: State<'_, AppState>) -> Result<Option<QueueState>,
     };
 
     // Get queue items
-    let mut stmt = db.connection().prepare(
-        "SELECT id, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, played_at
-             FROM queue_items
-             WHERE session_id = ?1 AND item_type = 'queue'
-             ORDER BY position",
-    )?;
+    let mut stmt = db.connection().prepare(&format!(
+        "SELECT {} FROM queue_items WHERE session_id = ?1 AND item_type = 'queue' ORDER BY position",
+        QUEUE_ITEM_COLUMNS
+    ))?;
 
     let queue = stmt
-        .query_map([session_id], |row| {
-            Ok(QueueItemData {
-                id: row.get(0)?,
-                video_id: row.get(1)?,
-                title: row.get(2)?,
-                artist: row.get(3)?,
-                duration: row.get(4)?,
-                thumbnail_url: row.get(5)?,
-                source: row.get(6)?,
-                youtube_id: row.get(7)?,
-                file_path: row.get(8)?,
-                position: row.get(9)?,
-                added_at: row.get(10)?,
-                played_at: row.get(11)?,
-            })
-        })?
+        .query_map([session_id], queue_item_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
     // Get history items
-    let mut stmt = db.connection().prepare(
-        "SELECT id, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, position, added_at, played_at
-             FROM queue_items
-             WHERE session_id = ?1 AND item_type = 'history'
-             ORDER BY position",
-    )?;
+    let mut stmt = db.connection().prepare(&format!(
+        "SELECT {} FROM queue_items WHERE session_id = ?1 AND item_type = 'history' ORDER BY position",
+        QUEUE_ITEM_COLUMNS
+    ))?;
 
     let history = stmt
-        .query_map([session_id], |row| {
-            Ok(QueueItemData {
-                id: row.get(0)?,
-                video_id: row.get(1)?,
-                title: row.get(2)?,
-                artist: row.get(3)?,
-                duration: row.get(4)?,
-                thumbnail_url: row.get(5)?,
-                source: row.get(6)?,
-                youtube_id: row.get(7)?,
-                file_path: row.get(8)?,
-                position: row.get(9)?,
-                added_at: row.get(10)?,
-                played_at: row.get(11)?,
-            })
-        })?
+        .query_map([session_id], queue_item_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
 
+    let current_item_id = load_current_item_id(db.connection(), session_id)?;
+    let playback_defaults = read_playback_defaults(db.connection(), session_id)?;
+
     info!(
-        "Loaded queue state: {} queue items, {} history items",
+        "Loaded queue state: {} queue items, {} history items, current item {:?}",
         queue.len(),
-        history.len()
+        history.len(),
+        current_item_id
     );
 
     Ok(Some(QueueState {
         queue,
         history,
         history_index,
+        current_item_id,
+        playback_defaults,
     }))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::services::ThumbnailSource;
 
     /// Helper to create test items with simple string IDs
     fn items(specs: &[(&str, &[i64])]) -> Vec<(String, Vec<i64>)> {
@@ -1006,6 +2369,759 @@ mod tests {
         assert_eq!(ids(&result), vec!["first", "second"]);
     }
 
+    // ============ Tests for compute_queue_runtime ============
+
+    #[test]
+    fn test_queue_runtime_mixed_known_and_unknown() {
+        let durations = vec![Some(200), None, Some(180), Some(240), None];
+        let runtime = compute_queue_runtime(&durations, 30);
+        // 620s of songs + 4 gaps of 30s
+        assert_eq!(
+            runtime,
+            QueueRuntime {
+                total_secs: 740,
+                unknown_count: 2
+            }
+        );
+    }
+
+    #[test]
+    fn test_queue_runtime_no_gap_for_single_or_empty() {
+        assert_eq!(compute_queue_runtime(&[Some(200)], 30).total_secs, 200);
+        assert_eq!(
+            compute_queue_runtime(&[], 30),
+            QueueRuntime {
+                total_secs: 0,
+                unknown_count: 0
+            }
+        );
+    }
+
+    // ============ Tests for fair shuffle preview ============
+
+    fn setup_queue_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Party', 1)", [])
+            .unwrap();
+        conn
+    }
+
+    fn add_queued(conn: &Connection, id: &str, position: i64, singer_ids: &[i64]) {
+        conn.execute(
+            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+             VALUES (?1, 1, 'queue', ?1, 'Song', 'youtube', ?2, '2024-01-01T00:00:00Z')",
+            rusqlite::params![id, position],
+        )
+        .unwrap();
+        for (i, singer_id) in singer_ids.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, ?3)",
+                rusqlite::params![id, singer_id, i as i64],
+            )
+            .unwrap();
+        }
+    }
+
+    fn queue_order(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT id FROM queue_items WHERE session_id = 1 AND item_type = 'queue' ORDER BY position")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<String>, _>>()
+            .unwrap()
+    }
+
+    fn add_history(conn: &Connection, id: &str, position: i64) {
+        conn.execute(
+            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+             VALUES (?1, 1, 'history', ?1, 'Song', 'youtube', ?2, '2024-01-01T00:00:00Z')",
+            rusqlite::params![id, position],
+        )
+        .unwrap();
+    }
+
+    fn history_order(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn
+            .prepare("SELECT id FROM queue_items WHERE session_id = 1 AND item_type = 'history' ORDER BY position")
+            .unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<String>, _>>()
+            .unwrap()
+    }
+
+    fn history_index(conn: &Connection) -> i64 {
+        conn.query_row("SELECT history_index FROM sessions WHERE id = 1", [], |row| row.get(0))
+            .unwrap()
+    }
+
+    fn resolved_id(item: Option<QueueItemData>) -> Option<String> {
+        item.map(|item| item.id)
+    }
+
+    #[test]
+    fn test_resolve_navigation_within_history() {
+        let conn = setup_queue_db();
+        for (i, id) in ["a", "b", "c"].iter().enumerate() {
+            add_history(&conn, id, i as i64);
+        }
+        add_queued(&conn, "q", 0, &[]);
+
+        // Navigated back to "a": next and previous stay in history
+        conn.execute("UPDATE sessions SET history_index = 0 WHERE id = 1", []).unwrap();
+        assert_eq!(resolved_id(resolve_next_item(&conn, 1).unwrap()), Some("b".to_string()));
+        assert_eq!(resolved_id(resolve_prev_item(&conn, 1).unwrap()), None);
+
+        conn.execute("UPDATE sessions SET history_index = 1 WHERE id = 1", []).unwrap();
+        assert_eq!(resolved_id(resolve_next_item(&conn, 1).unwrap()), Some("c".to_string()));
+        assert_eq!(resolved_id(resolve_prev_item(&conn, 1).unwrap()), Some("a".to_string()));
+    }
+
+    #[test]
+    fn test_resolve_navigation_crosses_into_queue() {
+        let conn = setup_queue_db();
+        for (i, id) in ["a", "b"].iter().enumerate() {
+            add_history(&conn, id, i as i64);
+        }
+        add_queued(&conn, "q1", 0, &[]);
+        add_queued(&conn, "q2", 1, &[]);
+
+        // On the last history item (explicitly or via -1), next is the head of the queue
+        for index in [1, -1, 7] {
+            conn.execute("UPDATE sessions SET history_index = ?1 WHERE id = 1", [index])
+                .unwrap();
+            assert_eq!(resolved_id(resolve_next_item(&conn, 1).unwrap()), Some("q1".to_string()));
+            assert_eq!(resolved_id(resolve_prev_item(&conn, 1).unwrap()), Some("a".to_string()));
+        }
+        // Nothing is moved
+        assert_eq!(queue_order(&conn), vec!["q1", "q2"]);
+        assert_eq!(history_order(&conn), vec!["a", "b"]);
+    }
+
+    #[test]
+    fn test_resolve_navigation_empty() {
+        let conn = setup_queue_db();
+        assert!(resolve_next_item(&conn, 1).unwrap().is_none());
+        assert!(resolve_prev_item(&conn, 1).unwrap().is_none());
+
+        add_queued(&conn, "q", 0, &[]);
+        assert_eq!(resolved_id(resolve_next_item(&conn, 1).unwrap()), Some("q".to_string()));
+        assert!(resolve_prev_item(&conn, 1).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_resolved_item_has_effective_playback_adjustments() {
+        let conn = setup_queue_db();
+        add_history(&conn, "a", 0);
+        add_queued(&conn, "q", 0, &[]);
+        conn.execute_batch(
+            "UPDATE sessions SET default_playback_rate = 0.9, default_transpose = -2 WHERE id = 1;
+             UPDATE queue_items SET transpose = 3 WHERE id = 'q';",
+        )
+        .unwrap();
+
+        // The item's own transpose wins; the session's rate fills the gap
+        let next = resolve_next_item(&conn, 1).unwrap().unwrap();
+        assert_eq!(
+            next.playback_adjustments,
+            PlaybackAdjustments {
+                playback_rate: Some(0.9),
+                transpose: Some(3),
+            }
+        );
+
+        // Stored overrides are left alone
+        assert_eq!(load_item(&conn, "q").playback_adjustments.playback_rate, None);
+
+        // A history item without an override follows the session entirely
+        add_history(&conn, "b", 1);
+        conn.execute("UPDATE sessions SET history_index = 0 WHERE id = 1", []).unwrap();
+        let next = resolve_next_item(&conn, 1).unwrap().unwrap();
+        assert_eq!(next.id, "b");
+        assert_eq!(
+            next.playback_adjustments,
+            PlaybackAdjustments {
+                playback_rate: Some(0.9),
+                transpose: Some(-2),
+            }
+        );
+    }
+
+    #[test]
+    fn test_reorder_history_item() {
+        let conn = setup_queue_db();
+        for (i, id) in ["a", "b", "c", "d"].iter().enumerate() {
+            add_history(&conn, id, i as i64);
+        }
+        add_queued(&conn, "q", 0, &[]);
+
+        reorder_item(&conn, 1, "history", "d", 0).unwrap();
+        assert_eq!(history_order(&conn), vec!["d", "a", "b", "c"]);
+        reorder_item(&conn, 1, "history", "a", 3).unwrap();
+        assert_eq!(history_order(&conn), vec!["d", "b", "c", "a"]);
+        // Queue items are untouched
+        assert_eq!(queue_order(&conn), vec!["q"]);
+
+        assert!(matches!(
+            reorder_item(&conn, 1, "history", "b", 4),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            reorder_item(&conn, 1, "history", "b", -1),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            reorder_item(&conn, 1, "history", "q", 0),
+            Err(CommandError::NotFound { .. })
+        ));
+        assert_eq!(history_order(&conn), vec!["d", "b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_reorder_queue_item() {
+        let conn = setup_queue_db();
+        for (i, id) in ["a", "b", "c"].iter().enumerate() {
+            add_queued(&conn, id, i as i64, &[]);
+        }
+        add_history(&conn, "h", 0);
+
+        assert!(reorder_item(&conn, 1, "queue", "c", 0).unwrap());
+        assert_eq!(queue_order(&conn), vec!["c", "a", "b"]);
+        assert!(!reorder_item(&conn, 1, "queue", "c", 0).unwrap());
+        assert_eq!(history_order(&conn), vec!["h"]);
+        assert!(matches!(
+            reorder_item(&conn, 1, "queue", "h", 0),
+            Err(CommandError::NotFound { .. })
+        ));
+        assert!(matches!(
+            reorder_item(&conn, 1, "other", "a", 0),
+            Err(CommandError::Validation(_))
+        ));
+    }
+
+    #[test]
+    fn test_reorder_history_keeps_history_index_on_same_song() {
+        let conn = setup_queue_db();
+        for (i, id) in ["a", "b", "c", "d"].iter().enumerate() {
+            add_history(&conn, id, i as i64);
+        }
+        let set_index = |index: i64| {
+            conn.execute("UPDATE sessions SET history_index = ?1 WHERE id = 1", [index])
+                .unwrap();
+        };
+
+        // Moving an earlier song past the current one shifts the index back
+        set_index(2); // "c"
+        reorder_item(&conn, 1, "history", "a", 3).unwrap();
+        assert_eq!(history_order(&conn), vec!["b", "c", "d", "a"]);
+        assert_eq!(history_index(&conn), 1);
+
+        // Moving a later song before the current one shifts it forward
+        reorder_item(&conn, 1, "history", "a", 0).unwrap();
+        assert_eq!(history_order(&conn), vec!["a", "b", "c", "d"]);
+        assert_eq!(history_index(&conn), 2);
+
+        // Moving the current song moves the index with it
+        reorder_item(&conn, 1, "history", "c", 0).unwrap();
+        assert_eq!(history_index(&conn), 0);
+
+        // Moves that don't cross the index leave it alone
+        reorder_item(&conn, 1, "history", "b", 3).unwrap();
+        assert_eq!(history_order(&conn), vec!["c", "a", "d", "b"]);
+        assert_eq!(history_index(&conn), 0);
+
+        // Not navigating history: index stays -1
+        set_index(-1);
+        reorder_item(&conn, 1, "history", "b", 0).unwrap();
+        assert_eq!(history_index(&conn), -1);
+    }
+
+    #[test]
+    fn test_sample_random_is_seeded_subset() {
+        let items: Vec<u32> = (0..20).collect();
+
+        let picked = sample_random(items.clone(), 5, 42);
+        assert_eq!(picked.len(), 5);
+        let mut unique = picked.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 5);
+        assert!(picked.iter().all(|n| *n < 20));
+
+        // Same seed, same pick; different seed, (almost certainly) different pick
+        assert_eq!(sample_random(items.clone(), 5, 42), picked);
+        assert_ne!(sample_random(items.clone(), 5, 7), picked);
+
+        // Asking for more than available returns everything, shuffled
+        let mut all = sample_random(items.clone(), 50, 1);
+        all.sort();
+        assert_eq!(all, items);
+    }
+
+    #[test]
+    fn test_append_queue_items_adds_library_videos() {
+        let conn = setup_queue_db();
+        add_queued(&conn, "existing", 0, &[]);
+
+        let videos = ["/music/A - One.mp4", "/music/B - Two.mp4"].map(|path| LibraryVideo {
+            file_path: path.to_string(),
+            file_name: path.rsplit('/').next().unwrap().to_string(),
+            title: "Song".to_string(),
+            artist: Some("Artist".to_string()),
+            album: None,
+            duration: Some(180),
+            has_lyrics: true,
+            has_cdg: false,
+            youtube_id: None,
+            is_available: true,
+            thumbnail_path: None,
+            aspect_ratio: None,
+            thumbnail: ThumbnailSource::None,
+            thumbnail_alternative: None,
+        });
+        let items = videos
+            .into_iter()
+            .map(|video| library_video_to_queue_item(video, "2024-01-01T00:00:00.000Z"))
+            .collect();
+
+        let added = append_queue_items(&conn, 1, items).unwrap();
+        assert_eq!(added.len(), 2);
+        assert_eq!(added[0].position, 1);
+        assert_eq!(added[1].position, 2);
+        assert_eq!(added[0].source, "local");
+        assert_eq!(added[0].file_path.as_deref(), Some("/music/A - One.mp4"));
+        assert_eq!(added[0].video_id, "/music/A - One.mp4");
+        assert_eq!(added[0].duration, Some(180));
+        assert_ne!(added[0].id, added[1].id);
+
+        let mut expected = vec!["existing".to_string()];
+        expected.extend(added.iter().map(|item| item.id.clone()));
+        assert_eq!(queue_order(&conn), expected);
+    }
+
+    #[test]
+    fn test_clear_queue_items_rejected_in_party_mode() {
+        let conn = setup_queue_db();
+        add_queued(&conn, "a", 0, &[]);
+        add_queued(&conn, "b", 1, &[]);
+        conn.execute("INSERT INTO settings (key, value) VALUES ('party_mode', 'true')", [])
+            .unwrap();
+
+        let result = clear_queue_items(&conn, 1);
+        assert!(matches!(result, Err(CommandError::Forbidden(_))));
+        assert_eq!(queue_order(&conn), vec!["a", "b"]);
+
+        conn.execute("UPDATE settings SET value = 'false' WHERE key = 'party_mode'", [])
+            .unwrap();
+        clear_queue_items(&conn, 1).unwrap();
+        assert!(queue_order(&conn).is_empty());
+    }
+
+    #[test]
+    fn test_preview_fair_shuffle_matches_apply() {
+        let conn = setup_queue_db();
+        for name in ["A", "B", "C"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        // A A A B B C (+ duet A/C, + unassigned)
+        add_queued(&conn, "a1", 0, &[1]);
+        add_queued(&conn, "a2", 1, &[1]);
+        add_queued(&conn, "a3", 2, &[1]);
+        add_queued(&conn, "b1", 3, &[2]);
+        add_queued(&conn, "b2", 4, &[2]);
+        add_queued(&conn, "c1", 5, &[3]);
+        add_queued(&conn, "ac", 6, &[1, 3]);
+        add_queued(&conn, "u1", 7, &[]);
+
+        let before = queue_order(&conn);
+        let preview = preview_fair_shuffle(&conn, 1).unwrap();
+
+        // Preview leaves the queue untouched
+        assert_eq!(queue_order(&conn), before);
+        assert_ne!(preview, before);
+
+        assert_eq!(apply_fair_shuffle(&conn, 1).unwrap(), 8);
+        assert_eq!(queue_order(&conn), preview);
+    }
+
+    #[test]
+    fn test_preview_fair_shuffle_single_item() {
+        let conn = setup_queue_db();
+        add_queued(&conn, "only", 0, &[]);
+
+        assert_eq!(preview_fair_shuffle(&conn, 1).unwrap(), vec!["only".to_string()]);
+        assert_eq!(apply_fair_shuffle(&conn, 1).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_preview_fair_shuffle_items() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        add_queued(&conn, "a1", 0, &[1]);
+        add_queued(&conn, "a2", 1, &[1]);
+        add_queued(&conn, "b1", 2, &[2]);
+        add_history(&conn, "played", 0);
+
+        let items = preview_fair_shuffle_items(&conn, 1).unwrap();
+        let ids: Vec<&str> = items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, vec!["a1", "b1", "a2"]);
+        // Items are returned in full, with their current positions
+        assert_eq!(items[1].title, "Song");
+        assert_eq!(items[1].position, 2);
+        assert_eq!(queue_order(&conn), vec!["a1", "a2", "b1"]);
+    }
+
+    fn item_singers(conn: &Connection, id: &str) -> Vec<i64> {
+        let mut stmt = conn
+            .prepare("SELECT singer_id FROM queue_singers WHERE queue_item_id = ?1 ORDER BY position")
+            .unwrap();
+        stmt.query_map([id], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<Vec<i64>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_round_robin_wraps_across_queue() {
+        let conn = setup_queue_db();
+        for name in ["A", "B", "C"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        // Prior assignments (including a duet) are replaced
+        add_queued(&conn, "i0", 0, &[3]);
+        add_queued(&conn, "i1", 1, &[1, 3]);
+        for i in 2..7 {
+            add_queued(&conn, &format!("i{}", i), i, &[]);
+        }
+
+        assert_eq!(apply_round_robin(&conn, 1, &[1, 2, 3]).unwrap(), 7);
+
+        let assigned: Vec<Vec<i64>> = (0..7).map(|i| item_singers(&conn, &format!("i{}", i))).collect();
+        assert_eq!(
+            assigned,
+            vec![vec![1], vec![2], vec![3], vec![1], vec![2], vec![3], vec![1]]
+        );
+    }
+
+    #[test]
+    fn test_round_robin_applies_singer_song_preferences() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        add_queued(&conn, "i0", 0, &[]);
+        add_queued(&conn, "i1", 1, &[]);
+        conn.execute(
+            "INSERT INTO singer_song_preferences (singer_id, video_id, pitch_semitones) VALUES (2, 'i1', 2)",
+            [],
+        )
+        .unwrap();
+
+        apply_round_robin(&conn, 1, &[1, 2]).unwrap();
+
+        let transpose = |id: &str| -> Option<i32> {
+            conn.query_row("SELECT transpose FROM queue_items WHERE id = ?1", [id], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(transpose("i0"), None);
+        assert_eq!(transpose("i1"), Some(2));
+    }
+
+    #[test]
+    fn test_round_robin_rejects_empty_or_unknown_singers() {
+        let conn = setup_queue_db();
+        conn.execute("INSERT INTO singers (name, color) VALUES ('A', '#f00')", [])
+            .unwrap();
+        add_queued(&conn, "i0", 0, &[1]);
+
+        assert!(matches!(
+            apply_round_robin(&conn, 1, &[]),
+            Err(CommandError::Validation(_))
+        ));
+        assert!(matches!(
+            apply_round_robin(&conn, 1, &[1, 99]),
+            Err(CommandError::NotFound { .. })
+        ));
+        // Nothing changed
+        assert_eq!(item_singers(&conn, "i0"), vec![1]);
+    }
+
+    #[test]
+    fn test_load_items_missing_duration_selects_backfill_rows() {
+        let conn = setup_queue_db();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Old', 0)", [])
+            .unwrap();
+        let insert = |id: &str, session_id: i64, item_type: &str, duration: Option<i64>, youtube_id: Option<&str>, position: i64| {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, duration, source, youtube_id, position, added_at)
+                 VALUES (?1, ?2, ?3, ?1, 'Song', ?4, 'youtube', ?5, ?6, '2024-01-01T00:00:00Z')",
+                rusqlite::params![id, session_id, item_type, duration, youtube_id, position],
+            )
+            .unwrap();
+        };
+        insert("second", 1, "queue", None, Some("yt2"), 2);
+        insert("first", 1, "queue", None, Some("yt1"), 1);
+        insert("has_duration", 1, "queue", Some(180), Some("yt3"), 3);
+        insert("local", 1, "queue", None, None, 4);
+        insert("empty_id", 1, "queue", None, Some(""), 5);
+        insert("played", 1, "history", None, Some("yt4"), 0);
+        insert("other_session", 2, "queue", None, Some("yt5"), 0);
+
+        let items = load_items_missing_duration(&conn, 1).unwrap();
+        assert_eq!(
+            items,
+            vec![
+                ("first".to_string(), "yt1".to_string()),
+                ("second".to_string(), "yt2".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_offline_unavailable_reason() {
+        let dir = std::env::temp_dir().join(format!("homekaraoke-test-validate-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let existing = dir.join("Song.mp4");
+        std::fs::write(&existing, "").unwrap();
+
+        let item = |source: &str, youtube_id: Option<&str>, file_path: Option<String>| PendingSource {
+            id: "item".to_string(),
+            source: source.to_string(),
+            youtube_id: youtube_id.map(String::from),
+            file_path,
+        };
+
+        let existing = Some(existing.to_string_lossy().to_string());
+        let missing = Some(dir.join("Gone.mp4").to_string_lossy().to_string());
+        assert_eq!(offline_unavailable_reason(&item("local", None, existing)), None);
+        assert_eq!(
+            offline_unavailable_reason(&item("local", Some("yt1"), missing)),
+            Some(UnavailableReason::FileMissing)
+        );
+        assert_eq!(
+            offline_unavailable_reason(&item("local", None, None)),
+            Some(UnavailableReason::NoSource)
+        );
+        assert_eq!(offline_unavailable_reason(&item("youtube", Some("yt1"), None)), None);
+        assert_eq!(
+            offline_unavailable_reason(&item("youtube", Some(" "), None)),
+            Some(UnavailableReason::NoSource)
+        );
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_ytdlp_error_means_unavailable() {
+        assert!(ytdlp_error_means_unavailable(&YtDlpError::ExecutionError(
+            "ERROR: [youtube] abc: Private video".to_string()
+        )));
+        assert!(!ytdlp_error_means_unavailable(&YtDlpError::ExecutionError(
+            "ERROR: HTTP Error 503: Service Unavailable".to_string()
+        )));
+        assert!(!ytdlp_error_means_unavailable(&YtDlpError::NotFound));
+    }
+
+    #[test]
+    fn test_compute_rotation_order_by_mode() {
+        let items = vec![
+            ("a1".to_string(), vec![1]),
+            ("a2".to_string(), vec![1]),
+            ("b1".to_string(), vec![2]),
+            ("b2".to_string(), vec![2]),
+        ];
+
+        assert_eq!(
+            compute_rotation_order(RotationMode::Fifo, &items, 0),
+            vec!["a1", "a2", "b1", "b2"]
+        );
+        assert_eq!(
+            compute_rotation_order(RotationMode::Fair, &items, 0),
+            vec!["a1", "b1", "a2", "b2"]
+        );
+
+        let random = compute_rotation_order(RotationMode::Random, &items, 42);
+        assert_eq!(random, compute_rotation_order(RotationMode::Random, &items, 42));
+        let mut sorted = random.clone();
+        sorted.sort();
+        assert_eq!(sorted, vec!["a1", "a2", "b1", "b2"]);
+    }
+
+    #[test]
+    fn test_apply_rotation_uses_stored_mode() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        add_queued(&conn, "a1", 0, &[1]);
+        add_queued(&conn, "a2", 1, &[1]);
+        add_queued(&conn, "b1", 2, &[2]);
+
+        // New sessions default to fair
+        assert_eq!(read_rotation_mode(&conn, 1).unwrap(), RotationMode::Fair);
+
+        write_rotation_mode(&conn, 1, RotationMode::Fifo).unwrap();
+        assert_eq!(read_rotation_mode(&conn, 1).unwrap(), RotationMode::Fifo);
+        assert_eq!(apply_rotation(&conn, 1, RotationMode::Fifo, 7).unwrap(), 0);
+        assert_eq!(queue_order(&conn), vec!["a1", "a2", "b1"]);
+
+        assert_eq!(apply_rotation(&conn, 1, RotationMode::Fair, 7).unwrap(), 3);
+        assert_eq!(queue_order(&conn), vec!["a1", "b1", "a2"]);
+
+        let expected = compute_rotation_order(
+            RotationMode::Random,
+            &load_fair_shuffle_items(&conn, 1).unwrap(),
+            7,
+        );
+        apply_rotation(&conn, 1, RotationMode::Random, 7).unwrap();
+        assert_eq!(queue_order(&conn), expected);
+
+        assert!(matches!(
+            write_rotation_mode(&conn, 99, RotationMode::Random),
+            Err(CommandError::NotFound { .. })
+        ));
+    }
+
+    fn test_item(id: &str) -> QueueItemData {
+        QueueItemData {
+            id: id.to_string(),
+            video_id: id.to_string(),
+            title: format!("Song {}", id),
+            artist: None,
+            duration: None,
+            thumbnail_url: None,
+            source: "youtube".to_string(),
+            youtube_id: Some(id.to_string()),
+            file_path: None,
+            position: 0,
+            added_at: "2024-01-01T00:00:00Z".to_string(),
+            played_at: None,
+            chapters: None,
+            playback_adjustments: PlaybackAdjustments::default(),
+        }
+    }
+
+    #[test]
+    fn test_add_item_under_auto_shuffle_keeps_first_item_pinned() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        // A is up next, then two more songs by A
+        add_queued(&conn, "a1", 0, &[1]);
+        add_queued(&conn, "a2", 1, &[1]);
+        add_queued(&conn, "a3", 2, &[1]);
+
+        // Without auto-shuffle a new song is appended
+        add_item_to_queue(&conn, 1, &test_item("plain")).unwrap();
+        assert_eq!(queue_order(&conn), vec!["a1", "a2", "a3", "plain"]);
+        conn.execute("DELETE FROM queue_items WHERE id = 'plain'", []).unwrap();
+
+        conn.execute("UPDATE sessions SET auto_shuffle = 1 WHERE id = 1", [])
+            .unwrap();
+        add_item_to_queue(&conn, 1, &test_item("b1")).unwrap();
+        conn.execute("INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES ('b1', 2, 0)", [])
+            .unwrap();
+        // Singer changes re-run the shuffle through auto_fair_shuffle as well
+        assert!(auto_fair_shuffle(&conn, 1).unwrap());
+
+        // a1 stays first; B's song moves ahead of A's second and third songs
+        assert_eq!(queue_order(&conn), vec!["a1", "b1", "a2", "a3"]);
+    }
+
+    #[test]
+    fn test_count_add_towards_shuffle() {
+        let conn = setup_queue_db();
+        let adds_since_shuffle = || -> i64 {
+            conn.query_row("SELECT adds_since_shuffle FROM sessions WHERE id = 1", [], |row| row.get(0))
+                .unwrap()
+        };
+        let fired: Vec<bool> = (0..7)
+            .map(|_| count_add_towards_shuffle(&conn, 1, 3).unwrap())
+            .collect();
+        // Nothing to reorder in an empty queue, so the threshold only restarts the count
+        assert_eq!(fired, vec![false; 7]);
+        assert_eq!(adds_since_shuffle(), 1);
+
+        // Turning the setting off restarts the count
+        assert!(!count_add_towards_shuffle(&conn, 1, 0).unwrap());
+        assert_eq!(adds_since_shuffle(), 0);
+    }
+
+    #[test]
+    fn test_count_add_towards_shuffle_uses_rotation_mode() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        add_queued(&conn, "a1", 0, &[1]);
+        add_queued(&conn, "a2", 1, &[1]);
+        add_queued(&conn, "b1", 2, &[2]);
+
+        // FIFO keeps the order even when the threshold is reached
+        write_rotation_mode(&conn, 1, RotationMode::Fifo).unwrap();
+        assert!(!count_add_towards_shuffle(&conn, 1, 2).unwrap());
+        assert!(!count_add_towards_shuffle(&conn, 1, 2).unwrap());
+        assert_eq!(queue_order(&conn), vec!["a1", "a2", "b1"]);
+
+        // Fair mode reorders on the second add, keeping the next song in place
+        write_rotation_mode(&conn, 1, RotationMode::Fair).unwrap();
+        assert!(!count_add_towards_shuffle(&conn, 1, 2).unwrap());
+        assert!(count_add_towards_shuffle(&conn, 1, 2).unwrap());
+        assert_eq!(queue_order(&conn), vec!["a1", "b1", "a2"]);
+    }
+
+    #[test]
+    fn test_pinned_fair_shuffle_ignores_session_auto_shuffle_flag() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        add_queued(&conn, "a1", 0, &[1]);
+        add_queued(&conn, "a2", 1, &[1]);
+        add_queued(&conn, "b1", 2, &[2]);
+
+        // Session auto-shuffle is off, so only the scheduled shuffle reorders
+        assert!(!auto_fair_shuffle(&conn, 1).unwrap());
+        assert!(pinned_fair_shuffle(&conn, 1).unwrap());
+        assert_eq!(queue_order(&conn), vec!["a1", "b1", "a2"]);
+    }
+
+    #[test]
+    fn test_compute_pinned_fair_shuffle_order() {
+        let items = vec![
+            ("b1".to_string(), vec![2]),
+            ("a1".to_string(), vec![1]),
+            ("b2".to_string(), vec![2]),
+            ("a2".to_string(), vec![1]),
+        ];
+        // Unpinned, A and B alternate starting with the earliest singer
+        assert_eq!(compute_fair_shuffle_order(&items), vec!["b1", "a1", "b2", "a2"]);
+
+        // A pinned duet counts for both singers
+        let items = vec![
+            ("duet".to_string(), vec![1, 2]),
+            ("c1".to_string(), vec![3]),
+            ("a1".to_string(), vec![1]),
+            ("c2".to_string(), vec![3]),
+        ];
+        assert_eq!(compute_pinned_fair_shuffle_order(&items), vec!["duet", "c1", "a1", "c2"]);
+        assert!(compute_pinned_fair_shuffle_order(&[]).is_empty());
+    }
+
     // ============ Tests for compute_fair_position ============
     // Based on algorithm from plan/permanent-shuffle.md:
     // Insert new song after all singers have sung at least N+1 times,
@@ -1183,4 +3299,204 @@ mod tests {
         let queue_all_unassigned = vec![UNASSIGNED_SINGER_ID, UNASSIGNED_SINGER_ID, UNASSIGNED_SINGER_ID];
         assert_eq!(compute_fair_position(&queue_all_unassigned, UNASSIGNED_SINGER_ID), 3);
     }
+
+    fn queue_chapter(title: &str, start_time: f64, end_time: f64) -> Chapter {
+        Chapter {
+            title: title.to_string(),
+            start_time,
+            end_time,
+        }
+    }
+
+    #[test]
+    fn test_set_item_chapters_validates_against_duration() {
+        let conn = setup_queue_db();
+        add_queued(&conn, "a", 0, &[]);
+        conn.execute("UPDATE queue_items SET duration = 180 WHERE id = 'a'", [])
+            .unwrap();
+
+        let stored = set_item_chapters(
+            &conn,
+            1,
+            "a",
+            vec![
+                queue_chapter("Chorus", 60.0, 120.0),
+                queue_chapter("Verse", 0.0, 60.0),
+                queue_chapter("Outro", 120.0, 200.0),
+                queue_chapter("Bonus", 190.0, 210.0),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            stored,
+            vec![
+                queue_chapter("Verse", 0.0, 60.0),
+                queue_chapter("Chorus", 60.0, 120.0),
+                queue_chapter("Outro", 120.0, 180.0),
+            ]
+        );
+
+        let json: Option<String> = conn
+            .query_row("SELECT chapters FROM queue_items WHERE id = 'a'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(parse_stored_chapters(json), Some(stored));
+    }
+
+    #[test]
+    fn test_set_item_chapters_unknown_item() {
+        let conn = setup_queue_db();
+        let result = set_item_chapters(&conn, 1, "missing", vec![queue_chapter("Verse", 0.0, 10.0)]);
+        assert!(matches!(result, Err(CommandError::NotFound { .. })));
+    }
+
+    #[test]
+    fn test_parse_stored_chapters_ignores_bad_json() {
+        assert_eq!(parse_stored_chapters(None), None);
+        assert_eq!(parse_stored_chapters(Some("not json".to_string())), None);
+    }
+
+    #[test]
+    fn test_load_current_item_id_keeps_existing_item() {
+        let conn = setup_queue_db();
+        add_queued(&conn, "a", 0, &[]);
+        conn.execute("UPDATE sessions SET current_item_id = 'a' WHERE id = 1", [])
+            .unwrap();
+
+        assert_eq!(load_current_item_id(&conn, 1).unwrap(), Some("a".to_string()));
+    }
+
+    #[test]
+    fn test_load_current_item_id_clears_removed_item() {
+        let conn = setup_queue_db();
+        add_queued(&conn, "a", 0, &[]);
+        conn.execute("UPDATE sessions SET current_item_id = 'a' WHERE id = 1", [])
+            .unwrap();
+        conn.execute("DELETE FROM queue_items WHERE id = 'a'", []).unwrap();
+
+        assert_eq!(load_current_item_id(&conn, 1).unwrap(), None);
+        let stored: Option<String> = conn
+            .query_row("SELECT current_item_id FROM sessions WHERE id = 1", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(stored, None);
+    }
+
+    #[test]
+    fn test_item_in_session_is_scoped_to_session() {
+        let conn = setup_queue_db();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Other', 0)", [])
+            .unwrap();
+        add_queued(&conn, "a", 0, &[]);
+
+        assert!(item_in_session(&conn, 1, "a").unwrap());
+        assert!(!item_in_session(&conn, 2, "a").unwrap());
+    }
+
+    #[test]
+    fn test_normalize_video_ids_accepts_urls() {
+        let mut video_id = "https://www.youtube.com/watch?v=dQw4w9WgXcQ&t=30s".to_string();
+        let mut youtube_id = Some("https://youtu.be/dQw4w9WgXcQ".to_string());
+        normalize_video_ids("youtube", &mut video_id, &mut youtube_id).unwrap();
+        assert_eq!(video_id, "dQw4w9WgXcQ");
+        assert_eq!(youtube_id.as_deref(), Some("dQw4w9WgXcQ"));
+
+        // Local items keep their file path as the video id
+        let mut video_id = "/music/Song.mp4".to_string();
+        let mut youtube_id = Some(" dQw4w9WgXcQ ".to_string());
+        normalize_video_ids("local", &mut video_id, &mut youtube_id).unwrap();
+        assert_eq!(video_id, "/music/Song.mp4");
+        assert_eq!(youtube_id.as_deref(), Some("dQw4w9WgXcQ"));
+    }
+
+    #[test]
+    fn test_normalize_video_ids_rejects_invalid_ids() {
+        let mut video_id = "not a video".to_string();
+        assert!(matches!(
+            normalize_video_ids("youtube", &mut video_id, &mut None),
+            Err(CommandError::Validation(_))
+        ));
+        let mut video_id = "dQw4w9WgXcQ".to_string();
+        assert!(matches!(
+            normalize_video_ids("youtube", &mut video_id, &mut Some("https://example.com/x".to_string())),
+            Err(CommandError::Validation(_))
+        ));
+
+        // A bad linked id doesn't stop a local song from being queued
+        let mut video_id = "/music/Song.mp4".to_string();
+        let mut youtube_id = Some("garbage".to_string());
+        normalize_video_ids("local", &mut video_id, &mut youtube_id).unwrap();
+        assert_eq!(youtube_id, None);
+    }
+
+    fn load_item(conn: &Connection, item_id: &str) -> QueueItemData {
+        conn.query_row(
+            &format!("SELECT {} FROM queue_items WHERE id = ?1", QUEUE_ITEM_COLUMNS),
+            [item_id],
+            queue_item_from_row,
+        )
+        .unwrap()
+    }
+
+    fn replacement_video() -> QueueItemData {
+        QueueItemData {
+            id: "ignored".to_string(),
+            video_id: "newVideo123".to_string(),
+            title: "Song (Karaoke Version)".to_string(),
+            artist: Some("Artist".to_string()),
+            duration: Some(215),
+            thumbnail_url: Some("https://i.ytimg.com/vi/newVideo123/hqdefault.jpg".to_string()),
+            source: "youtube".to_string(),
+            youtube_id: Some("newVideo123".to_string()),
+            file_path: None,
+            position: 99,
+            added_at: "2030-01-01T00:00:00Z".to_string(),
+            played_at: None,
+            chapters: None,
+            playback_adjustments: PlaybackAdjustments::default(),
+        }
+    }
+
+    #[test]
+    fn test_replace_item_source_keeps_position_and_singers() {
+        let conn = setup_queue_db();
+        for name in ["A", "B"] {
+            conn.execute("INSERT INTO singers (name, color) VALUES (?1, '#f00')", [name])
+                .unwrap();
+        }
+        add_queued(&conn, "first", 0, &[1]);
+        add_queued(&conn, "dead", 1, &[1, 2]);
+        add_queued(&conn, "last", 2, &[2]);
+
+        replace_item_source(&conn, 1, "dead", &replacement_video()).unwrap();
+
+        let item = load_item(&conn, "dead");
+        assert_eq!(item.video_id, "newVideo123");
+        assert_eq!(item.title, "Song (Karaoke Version)");
+        assert_eq!(item.youtube_id.as_deref(), Some("newVideo123"));
+        assert_eq!(item.duration, Some(215));
+        assert_eq!(item.position, 1);
+        assert_eq!(item.added_at, "2024-01-01T00:00:00Z");
+        assert_eq!(item_singers(&conn, "dead"), vec![1, 2]);
+        assert_eq!(queue_order(&conn), vec!["first", "dead", "last"]);
+    }
+
+    #[test]
+    fn test_replace_item_source_requires_item_in_session() {
+        let conn = setup_queue_db();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Other', 0)", [])
+            .unwrap();
+        add_queued(&conn, "a", 0, &[]);
+
+        assert!(matches!(
+            replace_item_source(&conn, 2, "a", &replacement_video()),
+            Err(CommandError::NotFound { .. })
+        ));
+        let mut blank = replacement_video();
+        blank.video_id = " ".to_string();
+        assert!(matches!(
+            replace_item_source(&conn, 1, "a", &blank),
+            Err(CommandError::Validation(_))
+        ));
+        assert_eq!(load_item(&conn, "a").video_id, "a");
+    }
 }
@@ -0,0 +1,206 @@
+use super::errors::{CommandError, LockResultExt};
+use super::library::library_get_folders;
+use super::youtube::{resolve_max_results, youtube_search};
+use crate::services::ytdlp::SearchResult;
+use crate::services::{LibraryScanner, LibraryVideo};
+use crate::AppState;
+use log::{debug, info, warn};
+use serde::Serialize;
+use std::collections::HashSet;
+use tauri::State;
+
+/// A single search hit, tagged with where it came from
+#[derive(Debug, Serialize)]
+#[serde(tag = "source", rename_all = "lowercase")]
+pub enum CombinedSearchResult {
+    Library(LibraryVideo),
+    Youtube(SearchResult),
+}
+
+/// Results of searching the library and YouTube together
+#[derive(Debug, Serialize)]
+pub struct CombinedSearchResults {
+    /// Library matches first, then YouTube results not already in the library
+    pub results: Vec<CombinedSearchResult>,
+    /// Set when the library search failed (YouTube results are still returned)
+    pub library_error: Option<String>,
+    /// Set when the YouTube search failed (library results are still returned)
+    pub youtube_error: Option<String>,
+}
+
+/// Merge both result sets, dropping YouTube results whose video is already in the library.
+fn combine_results(
+    library: Vec<LibraryVideo>,
+    youtube: Vec<SearchResult>,
+) -> Vec<CombinedSearchResult> {
+    let local_youtube_ids: HashSet<String> = library
+        .iter()
+        .filter_map(|video| video.youtube_id.clone())
+        .collect();
+
+    let mut results: Vec<CombinedSearchResult> = library
+        .into_iter()
+        .map(CombinedSearchResult::Library)
+        .collect();
+    results.extend(
+        youtube
+            .into_iter()
+            .filter(|result| !local_youtube_ids.contains(&result.id))
+            .map(CombinedSearchResult::Youtube),
+    );
+    results
+}
+
+/// Search the local library and YouTube concurrently.
+/// YouTube uses the configured backend (API or yt-dlp), like `youtube_search`,
+/// and the result count defaults to the `default_search_results` setting.
+#[tauri::command]
+pub async fn search_all(
+    state: State<'_, AppState>,
+    query: String,
+    max_results: Option<u32>,
+    include_youtube: bool,
+    include_library: bool,
+) -> Result<CombinedSearchResults, CommandError> {
+    if !include_youtube && !include_library {
+        return Err(CommandError::Validation(
+            "At least one search source must be included".to_string(),
+        ));
+    }
+
+    let max = {
+        let db = state.db.lock().map_lock_err()?;
+        resolve_max_results(max_results, &db)
+    };
+    debug!(
+        "search_all: query='{}', max_results={}, youtube={}, library={}",
+        query, max, include_youtube, include_library
+    );
+
+    // Folders are read up front so the filesystem scan can run off the async runtime
+    let folders = if include_library {
+        library_get_folders(state.clone()).map(Some)
+    } else {
+        Ok(None)
+    };
+
+    let library_query = query.clone();
+    let library_task = async move {
+        match folders? {
+            Some(folders) => tokio::task::spawn_blocking(move || {
+                LibraryScanner::search(&folders, &library_query, max, false)
+            })
+            .await
+            .map_err(|e| format!("Library search failed: {}", e)),
+            None => Ok(Vec::new()),
+        }
+    };
+
+    let youtube_state = state.clone();
+    let youtube_query = query.clone();
+    let youtube_task = async move {
+        if include_youtube {
+            youtube_search(youtube_state, youtube_query, Some(max), None, None)
+                .await
+                .map(|response| response.results)
+                .map_err(|e| e.to_string())
+        } else {
+            Ok(Vec::new())
+        }
+    };
+
+    let (library, youtube) = tokio::join!(library_task, youtube_task);
+
+    let (library, library_error) = match library {
+        Ok(results) => (results, None),
+        Err(e) => {
+            warn!("search_all: library search failed: {}", e);
+            (Vec::new(), Some(e))
+        }
+    };
+    let (youtube, youtube_error) = match youtube {
+        Ok(results) => (results, None),
+        Err(e) => {
+            warn!("search_all: YouTube search failed: {}", e);
+            (Vec::new(), Some(e))
+        }
+    };
+
+    let results = combine_results(library, youtube);
+    info!("search_all: {} combined results for '{}'", results.len(), query);
+
+    Ok(CombinedSearchResults {
+        results,
+        library_error,
+        youtube_error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::ThumbnailSource;
+
+    fn library_video(title: &str, youtube_id: Option<&str>) -> LibraryVideo {
+        LibraryVideo {
+            file_path: format!("/music/{}.mp4", title),
+            file_name: format!("{}.mp4", title),
+            title: title.to_string(),
+            artist: None,
+            album: None,
+            duration: None,
+            has_lyrics: false,
+            has_cdg: false,
+            youtube_id: youtube_id.map(String::from),
+            is_available: true,
+            thumbnail_path: None,
+            aspect_ratio: None,
+            thumbnail: ThumbnailSource::None,
+            thumbnail_alternative: None,
+        }
+    }
+
+    fn youtube_result(id: &str) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: format!("Video {}", id),
+            channel: "Channel".to_string(),
+            duration: None,
+            thumbnail: None,
+            view_count: None,
+        }
+    }
+
+    #[test]
+    fn test_combine_results_dedupes_youtube_ids_in_library() {
+        let library = vec![
+            library_video("Downloaded", Some("abc")),
+            library_video("Ripped", None),
+        ];
+        let youtube = vec![youtube_result("abc"), youtube_result("xyz")];
+
+        let results = combine_results(library, youtube);
+        let labels: Vec<String> = results
+            .iter()
+            .map(|r| match r {
+                CombinedSearchResult::Library(v) => format!("library:{}", v.title),
+                CombinedSearchResult::Youtube(r) => format!("youtube:{}", r.id),
+            })
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["library:Downloaded", "library:Ripped", "youtube:xyz"]
+        );
+    }
+
+    #[test]
+    fn test_combined_result_serializes_source_tag() {
+        let json = serde_json::to_value(CombinedSearchResult::Youtube(youtube_result("abc"))).unwrap();
+        assert_eq!(json["source"], "youtube");
+        assert_eq!(json["id"], "abc");
+
+        let json = serde_json::to_value(CombinedSearchResult::Library(library_video("Song", None))).unwrap();
+        assert_eq!(json["source"], "library");
+        assert_eq!(json["title"], "Song");
+    }
+}
@@ -1,7 +1,11 @@
 use super::errors::{CommandError, LockResultExt};
+use super::queue::{auto_fair_shuffle, auto_shuffle_enabled, read_rotation_mode, write_rotation_mode, RotationMode};
+use super::settings::{enforce_contrast, ensure_not_party_mode, preserve_empty_sessions};
+use crate::services::color_contrast::ensure_readable_color;
+use crate::services::singer_sheets::{write_singer_sheets, SheetSong, SingerSheet};
 use crate::AppState;
 use log::{debug, info};
-use rusqlite::OptionalExtension;
+use rusqlite::{Connection, OptionalExtension};
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
@@ -59,10 +63,129 @@ pub struct Session {
     pub hosted_session_status: Option<HostedSessionStatus>,
 }
 
+/// A song's play count across all sessions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SongPlayCount {
+    pub video_id: String,
+    /// Title and artist as of the most recent play
+    pub title: String,
+    pub artist: Option<String>,
+    pub play_count: u32,
+}
+
+/// The singer with the most songs across all sessions
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SingerActivity {
+    pub singer_id: i64,
+    pub name: String,
+    pub song_count: u32,
+}
+
+/// All-time statistics across sessions (trashed sessions excluded)
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct GlobalStats {
+    pub total_sessions: u32,
+    pub total_songs_played: u32,
+    /// Singers assigned to at least one played song
+    pub total_unique_singers: u32,
+    /// Sum of known durations of played songs
+    pub total_sung_hours: f64,
+    /// Most played songs by video, most plays first
+    pub top_songs: Vec<SongPlayCount>,
+    pub most_active_singer: Option<SingerActivity>,
+}
+
+/// Per-session playback defaults, so each event can differ from the global settings.
+/// Unset fields fall back to the global setting.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct SessionSettings {
+    /// Volume (0-100) applied when the session is activated
+    pub default_volume: Option<u32>,
+    /// Play the next queued song when one ends
+    pub autoplay: Option<bool>,
+    /// Show lyrics while playing
+    pub show_lyrics: Option<bool>,
+}
+
+/// Maximum value of `SessionSettings::default_volume`
+const MAX_SESSION_VOLUME: u32 = 100;
+
+/// Tempo and key changes for playback. Used both for a song's own override and
+/// for the session-wide baseline; unset fields mean "no change".
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq)]
+#[serde(default)]
+pub struct PlaybackAdjustments {
+    /// Speed multiplier (1.0 is normal speed)
+    pub playback_rate: Option<f64>,
+    /// Key change in semitones (0 is the original key)
+    pub transpose: Option<i32>,
+}
+
+impl PlaybackAdjustments {
+    /// Fill fields this override leaves unset from `defaults` (the override wins)
+    pub fn or(self, defaults: PlaybackAdjustments) -> PlaybackAdjustments {
+        PlaybackAdjustments {
+            playback_rate: self.playback_rate.or(defaults.playback_rate),
+            transpose: self.transpose.or(defaults.transpose),
+        }
+    }
+}
+
+/// Allowed playback speed multipliers
+const PLAYBACK_RATE_RANGE: std::ops::RangeInclusive<f64> = 0.5..=2.0;
+
+/// Allowed key changes in semitones (one octave either way)
+const TRANSPOSE_RANGE: std::ops::RangeInclusive<i32> = -12..=12;
+
+/// A singer's preferred key and tempo for one song. Applied to the queue item when
+/// the singer is assigned to it; unset fields mean "no change".
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SingerSongPreference {
+    pub singer_id: i64,
+    pub video_id: String,
+    /// Key change in semitones (0 is the original key)
+    pub pitch_semitones: Option<i32>,
+    /// Speed as a percentage of normal (100 is normal speed)
+    pub tempo_percent: Option<u32>,
+    pub notes: Option<String>,
+}
+
+impl SingerSongPreference {
+    /// The queue item override this preference sets
+    pub fn playback_adjustments(&self) -> PlaybackAdjustments {
+        PlaybackAdjustments {
+            playback_rate: self.tempo_percent.map(|percent| f64::from(percent) / 100.0),
+            transpose: self.pitch_semitones,
+        }
+    }
+}
+
+/// Allowed `SingerSongPreference::tempo_percent` values (same as `PLAYBACK_RATE_RANGE`)
+const TEMPO_PERCENT_RANGE: std::ops::RangeInclusive<u32> = 50..=200;
+
+/// Maximum length of `SingerSongPreference::notes`
+const MAX_PREFERENCE_NOTES_LENGTH: usize = 500;
+
 // ============ Singer Commands ============
 
 const MAX_NAME_LENGTH: usize = 100;
 
+/// Background singer names are shown on (Tailwind gray-800), for `enforce_contrast`
+const APP_BACKGROUND_COLOR: &str = "#1F2937";
+
+/// A singer color as stored: adjusted to readable contrast when `enforce_contrast` is on
+fn singer_color(db: &crate::db::Database, color: String) -> String {
+    if !enforce_contrast(db) {
+        return color;
+    }
+    let readable = ensure_readable_color(&color, APP_BACKGROUND_COLOR);
+    if !readable.eq_ignore_ascii_case(&color) {
+        debug!("Adjusted singer color {} to {} for contrast", color, readable);
+    }
+    readable
+}
+
 #[tauri::command]
 pub fn create_singer(
     state: State<'_, AppState>,
@@ -106,6 +229,7 @@ pub fn create_singer(
 
     debug!("Creating singer: {} with color {}", name, color);
     let db = state.db.lock().map_lock_err()?;
+    let color = singer_color(&db, color);
 
     db.connection().execute(
         "INSERT INTO singers (name, color, is_persistent, unique_name, online_id) VALUES (?1, ?2, ?3, ?4, ?5)",
@@ -260,7 +384,7 @@ pub fn update_singer(
             Some(un_trimmed.to_string())
         }));
     }
-    if let Some(c) = color {
+    if let Some(c) = color.map(|c| singer_color(&db, c)) {
         updates.push("color = ?");
         params.push(Box::new(c));
     }
@@ -303,6 +427,79 @@ pub fn update_singer(
     Ok(singer)
 }
 
+/// Set a singer's persistence flag and return the updated singer.
+/// Demoting a singer who still has favorites fails unless `force` is set; once
+/// demoted, the singer (and their favorites) is removed by session cleanup after
+/// they no longer belong to any session.
+fn set_singer_persistence(
+    conn: &Connection,
+    singer_id: i64,
+    persistent: bool,
+    force: bool,
+) -> Result<Singer, CommandError> {
+    let singer = conn
+        .query_row(
+            "SELECT id, name, unique_name, color, is_persistent, online_id FROM singers WHERE id = ?1",
+            [singer_id],
+            |row| {
+                Ok(Singer {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    unique_name: row.get(2)?,
+                    color: row.get(3)?,
+                    is_persistent: row.get::<_, i32>(4)? != 0,
+                    online_id: row.get(5)?,
+                })
+            },
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound {
+            resource: "Singer",
+            id: singer_id.to_string(),
+        })?;
+
+    if singer.is_persistent == persistent {
+        return Ok(singer);
+    }
+
+    if !persistent && !force {
+        let favorites: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM singer_favorites WHERE singer_id = ?1",
+            [singer_id],
+            |row| row.get(0),
+        )?;
+        if favorites > 0 {
+            return Err(CommandError::Validation(format!(
+                "{} has {} favorite(s) that will be lost when the session ends",
+                singer.name, favorites
+            )));
+        }
+    }
+
+    conn.execute(
+        "UPDATE singers SET is_persistent = ?1 WHERE id = ?2",
+        rusqlite::params![persistent as i32, singer_id],
+    )?;
+
+    Ok(Singer {
+        is_persistent: persistent,
+        ..singer
+    })
+}
+
+/// Promote a session-only singer to persistent, or demote a persistent one
+#[tauri::command]
+pub fn set_singer_persistent(
+    state: State<'_, AppState>,
+    singer_id: i64,
+    persistent: bool,
+    force: Option<bool>,
+) -> Result<Singer, CommandError> {
+    info!("Setting singer {} persistent: {}", singer_id, persistent);
+    let db = state.db.lock().map_lock_err()?;
+    set_singer_persistence(db.connection(), singer_id, persistent, force.unwrap_or(false))
+}
+
 #[tauri::command]
 pub fn get_persistent_singers(state: State<'_, AppState>) -> Result<Vec<Singer>, CommandError> {
     debug!("Getting persistent singers");
@@ -366,7 +563,7 @@ pub fn start_session(
         // End old session first to avoid having two active sessions
         if old_session_id.is_some() {
             conn.execute(
-                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE is_active = 1",
+                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP, last_search_query = NULL WHERE is_active = 1",
                 [],
             )?;
         }
@@ -427,14 +624,12 @@ pub fn start_session(
     }
 }
 
-#[tauri::command]
-pub fn end_session(state: State<'_, AppState>) -> Result<(), CommandError> {
-    info!("Ending active session");
-    let db = state.db.lock().map_lock_err()?;
-
+/// End the active session (if any). It is archived, or deleted when it has no queue
+/// items, history or singers unless `preserve_empty` is set. Non-persistent singers
+/// left without a session and orphaned queue singer assignments are cleaned up.
+fn end_active_session(conn: &Connection, preserve_empty: bool) -> Result<(), CommandError> {
     // Get the active session ID first
-    let session_id: Option<i64> = db
-        .connection()
+    let session_id: Option<i64> = conn
         .query_row(
             "SELECT id FROM sessions WHERE is_active = 1",
             [],
@@ -444,7 +639,7 @@ pub fn end_session(state: State<'_, AppState>) -> Result<(), CommandError> {
 
     if let Some(session_id) = session_id {
         // Check if session has any content (queue items, history, or singers)
-        let has_content: bool = db.connection().query_row(
+        let has_content: bool = conn.query_row(
             "SELECT EXISTS(
                     SELECT 1 FROM queue_items WHERE session_id = ?1
                     UNION
@@ -454,29 +649,32 @@ pub fn end_session(state: State<'_, AppState>) -> Result<(), CommandError> {
             |row| row.get(0),
         )?;
 
-        if has_content {
-            // Session has content - just mark as inactive
-            db.connection().execute(
-                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE id = ?1",
+        if has_content || preserve_empty {
+            // Mark as inactive; empty sessions are kept when the user asked for it
+            conn.execute(
+                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP, last_search_query = NULL WHERE id = ?1",
                 [session_id],
             )?;
-            info!("Session {} archived (has content)", session_id);
+            if has_content {
+                info!("Session {} archived (has content)", session_id);
+            } else {
+                info!("Session {} archived (empty, preserved by setting)", session_id);
+            }
         } else {
             // Session is empty - delete it entirely
-            db.connection()
-                .execute("DELETE FROM sessions WHERE id = ?1", [session_id])?;
+            conn.execute("DELETE FROM sessions WHERE id = ?1", [session_id])?;
             info!("Session {} deleted (was empty)", session_id);
         }
     }
 
     // Clean up non-persistent singers that aren't associated with any session
-    db.connection().execute(
+    conn.execute(
         "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
         [],
     )?;
 
     // Clear queue singer assignments (for non-persistent data)
-    db.connection().execute(
+    conn.execute(
         "DELETE FROM queue_singers WHERE queue_item_id NOT IN (SELECT id FROM queue_items)",
         [],
     )?;
@@ -484,6 +682,14 @@ pub fn end_session(state: State<'_, AppState>) -> Result<(), CommandError> {
     Ok(())
 }
 
+#[tauri::command]
+pub fn end_session(state: State<'_, AppState>) -> Result<(), CommandError> {
+    info!("Ending active session");
+    let db = state.db.lock().map_lock_err()?;
+    let preserve_empty = preserve_empty_sessions(&db);
+    end_active_session(db.connection(), preserve_empty)
+}
+
 #[tauri::command]
 pub fn get_active_session(state: State<'_, AppState>) -> Result<Option<Session>, CommandError> {
     debug!("Getting active session");
@@ -643,76 +849,63 @@ pub fn get_session_singers(
     Ok(singers)
 }
 
-// ============ Queue Singer Assignment Commands ============
-
-#[tauri::command]
-pub fn assign_singer_to_queue_item(
-    state: State<'_, AppState>,
-    queue_item_id: String,
-    singer_id: i64,
-) -> Result<(), CommandError> {
-    debug!(
-        "Assigning singer {} to queue item {}",
-        singer_id, queue_item_id
-    );
-    let db = state.db.lock().map_lock_err()?;
-
-    // Get next position for this queue item
-    let position: i32 = db
-        .connection()
-        .query_row(
-            "SELECT COALESCE(MAX(position), -1) + 1 FROM queue_singers WHERE queue_item_id = ?1",
-            [&queue_item_id],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
-
-    db.connection().execute(
-        "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, ?3)",
-        rusqlite::params![queue_item_id, singer_id, position],
+/// Sessions (not in the trash) the singer joined, most recently started first
+pub(crate) fn sessions_with_singer(conn: &Connection, singer_id: i64) -> Result<Vec<Session>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT s.id, s.name, s.started_at, s.ended_at, s.is_active, s.hosted_session_id, s.hosted_by_user_id, s.hosted_session_status
+             FROM sessions s
+             INNER JOIN session_singers ss ON s.id = ss.session_id
+             WHERE ss.singer_id = ?1 AND s.deleted_at IS NULL
+             ORDER BY s.started_at DESC, s.id DESC",
     )?;
 
-    Ok(())
+    let sessions = stmt
+        .query_map([singer_id], |row| {
+            Ok(Session {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                started_at: row.get(2)?,
+                ended_at: row.get(3)?,
+                is_active: row.get::<_, i32>(4)? != 0,
+                hosted_session_id: row.get(5)?,
+                hosted_by_user_id: row.get(6)?,
+                hosted_session_status: row.get::<_, Option<String>>(7)?
+                    .and_then(|s| HostedSessionStatus::from_str(&s)),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(sessions)
 }
 
+/// Sessions a singer took part in, so the UI can warn before deleting or merging them
 #[tauri::command]
-pub fn remove_singer_from_queue_item(
+pub fn get_singer_sessions(
     state: State<'_, AppState>,
-    queue_item_id: String,
     singer_id: i64,
-) -> Result<(), CommandError> {
-    debug!(
-        "Removing singer {} from queue item {}",
-        singer_id, queue_item_id
-    );
+) -> Result<Vec<Session>, CommandError> {
+    debug!("Getting sessions for singer {}", singer_id);
     let db = state.db.lock().map_lock_err()?;
-
-    db.connection().execute(
-        "DELETE FROM queue_singers WHERE queue_item_id = ?1 AND singer_id = ?2",
-        rusqlite::params![queue_item_id, singer_id],
-    )?;
-
-    Ok(())
+    Ok(sessions_with_singer(db.connection(), singer_id)?)
 }
 
-#[tauri::command]
-pub fn get_queue_item_singers(
-    state: State<'_, AppState>,
-    queue_item_id: String,
-) -> Result<Vec<Singer>, CommandError> {
-    debug!("Getting singers for queue item {}", queue_item_id);
-    let db = state.db.lock().map_lock_err()?;
-
-    let mut stmt = db.connection().prepare(
+/// Session singers with no song in this session's history yet, in join order
+pub(crate) fn waiting_singers(conn: &Connection, session_id: i64) -> Result<Vec<Singer>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
         "SELECT s.id, s.name, s.unique_name, s.color, s.is_persistent, s.online_id
              FROM singers s
-             INNER JOIN queue_singers qs ON s.id = qs.singer_id
-             WHERE qs.queue_item_id = ?1
-             ORDER BY qs.position",
+             INNER JOIN session_singers ss ON s.id = ss.singer_id
+             WHERE ss.session_id = ?1
+               AND NOT EXISTS (
+                   SELECT 1 FROM queue_items qi
+                   INNER JOIN queue_singers qs ON qs.queue_item_id = qi.id
+                   WHERE qi.session_id = ?1 AND qi.item_type = 'history' AND qs.singer_id = s.id
+               )
+             ORDER BY ss.joined_at, s.id",
     )?;
 
     let singers = stmt
-        .query_map([&queue_item_id], |row| {
+        .query_map([session_id], |row| {
             Ok(Singer {
                 id: row.get(0)?,
                 name: row.get(1)?,
@@ -727,870 +920,3333 @@ pub fn get_queue_item_singers(
     Ok(singers)
 }
 
+/// Songs in a session's history grouped by singer, in play order: one sheet per singer
+/// (ordered by their first song), then a shared sheet for songs with no singer.
+/// Duets appear on each of their singers' sheets.
+pub(crate) fn load_singer_sheets(conn: &Connection, session_id: i64) -> Result<Vec<SingerSheet>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT qi.title, qi.artist, qi.played_at, s.id, s.name
+             FROM queue_items qi
+             LEFT JOIN queue_singers qs ON qs.queue_item_id = qi.id
+             LEFT JOIN singers s ON s.id = qs.singer_id
+             WHERE qi.session_id = ?1 AND qi.item_type = 'history'
+             ORDER BY qi.position, qs.position",
+    )?;
+
+    let rows = stmt
+        .query_map([session_id], |row| {
+            let song = SheetSong {
+                title: row.get(0)?,
+                artist: row.get(1)?,
+                played_at: row.get(2)?,
+            };
+            let singer: Option<(i64, String)> = match row.get::<_, Option<i64>>(3)? {
+                Some(id) => Some((id, row.get(4)?)),
+                None => None,
+            };
+            Ok((song, singer))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut singer_sheets: Vec<(i64, SingerSheet)> = Vec::new();
+    let mut unassigned: Vec<SheetSong> = Vec::new();
+    for (song, singer) in rows {
+        match singer {
+            Some((singer_id, name)) => match singer_sheets.iter_mut().find(|(id, _)| *id == singer_id) {
+                Some((_, sheet)) => sheet.songs.push(song),
+                None => singer_sheets.push((
+                    singer_id,
+                    SingerSheet {
+                        singer_name: Some(name),
+                        songs: vec![song],
+                    },
+                )),
+            },
+            None => unassigned.push(song),
+        }
+    }
+
+    let mut sheets: Vec<SingerSheet> = singer_sheets.into_iter().map(|(_, sheet)| sheet).collect();
+    if !unassigned.is_empty() {
+        sheets.push(SingerSheet {
+            singer_name: None,
+            songs: unassigned,
+        });
+    }
+    Ok(sheets)
+}
+
+/// Write a Markdown "who sang what" sheet per singer for a session into `output_dir`
+/// (an existing absolute directory), plus one for songs without a singer.
+/// Returns the written file paths.
 #[tauri::command]
-pub fn clear_queue_item_singers(
+pub fn export_singer_sheets(
     state: State<'_, AppState>,
-    queue_item_id: String,
-) -> Result<(), CommandError> {
-    debug!("Clearing singers from queue item {}", queue_item_id);
+    session_id: i64,
+    output_dir: String,
+) -> Result<Vec<String>, CommandError> {
+    debug!("Exporting singer sheets for session {} to {}", session_id, output_dir);
+    let (session_name, sheets) = {
+        let db = state.db.lock().map_lock_err()?;
+        let session_name: Option<String> = db
+            .connection()
+            .query_row("SELECT name FROM sessions WHERE id = ?1", [session_id], |row| row.get(0))
+            .optional()?
+            .ok_or_else(|| CommandError::NotFound {
+                resource: "Session",
+                id: session_id.to_string(),
+            })?;
+        (session_name, load_singer_sheets(db.connection(), session_id)?)
+    };
+
+    let paths = write_singer_sheets(&sheets, session_name.as_deref(), std::path::Path::new(&output_dir))
+        .map_err(CommandError::External)?;
+    Ok(paths.into_iter().map(|p| p.to_string_lossy().to_string()).collect())
+}
+
+/// Singers in the session who haven't sung yet, so the host can make sure everyone gets a turn
+#[tauri::command]
+pub fn get_waiting_singers(
+    state: State<'_, AppState>,
+    session_id: i64,
+) -> Result<Vec<Singer>, CommandError> {
+    debug!("Getting singers who haven't sung yet in session {}", session_id);
     let db = state.db.lock().map_lock_err()?;
+    Ok(waiting_singers(db.connection(), session_id)?)
+}
 
-    db.connection().execute(
-        "DELETE FROM queue_singers WHERE queue_item_id = ?1",
-        [&queue_item_id],
-    )?;
+// ============ Singer Song Preference Commands ============
 
+fn validate_singer_song_preference(preference: &SingerSongPreference) -> Result<(), CommandError> {
+    if preference.video_id.is_empty() {
+        return Err(CommandError::Validation("Video ID cannot be empty".to_string()));
+    }
+    if let Some(pitch) = preference.pitch_semitones {
+        if !TRANSPOSE_RANGE.contains(&pitch) {
+            return Err(CommandError::Validation(format!(
+                "Pitch must be from {} to {} semitones",
+                TRANSPOSE_RANGE.start(),
+                TRANSPOSE_RANGE.end()
+            )));
+        }
+    }
+    if let Some(tempo) = preference.tempo_percent {
+        if !TEMPO_PERCENT_RANGE.contains(&tempo) {
+            return Err(CommandError::Validation(format!(
+                "Tempo must be from {}% to {}%",
+                TEMPO_PERCENT_RANGE.start(),
+                TEMPO_PERCENT_RANGE.end()
+            )));
+        }
+    }
+    if let Some(notes) = &preference.notes {
+        if notes.chars().count() > MAX_PREFERENCE_NOTES_LENGTH {
+            return Err(CommandError::Validation(format!(
+                "Notes must be at most {} characters",
+                MAX_PREFERENCE_NOTES_LENGTH
+            )));
+        }
+    }
     Ok(())
 }
 
-// ============ Session Management Commands ============
+/// Read a singer's preference for a song (None if they have none)
+fn read_singer_song_preference(
+    conn: &Connection,
+    singer_id: i64,
+    video_id: &str,
+) -> Result<Option<SingerSongPreference>, rusqlite::Error> {
+    conn.query_row(
+        "SELECT singer_id, video_id, pitch_semitones, tempo_percent, notes
+         FROM singer_song_preferences WHERE singer_id = ?1 AND video_id = ?2",
+        rusqlite::params![singer_id, video_id],
+        |row| {
+            Ok(SingerSongPreference {
+                singer_id: row.get(0)?,
+                video_id: row.get(1)?,
+                pitch_semitones: row.get(2)?,
+                tempo_percent: row.get(3)?,
+                notes: row.get(4)?,
+            })
+        },
+    )
+    .optional()
+}
 
-#[tauri::command]
-pub fn get_recent_sessions(
-    state: State<'_, AppState>,
-    limit: Option<i32>,
-) -> Result<Vec<Session>, CommandError> {
-    let limit = limit.unwrap_or(10);
-    debug!("Getting recent sessions (limit: {})", limit);
-    let db = state.db.lock().map_lock_err()?;
+/// Validate and store a singer's preference for a song. A preference with no pitch,
+/// tempo or notes clears the stored one. Returns what is stored afterwards.
+fn write_singer_song_preference(
+    conn: &Connection,
+    preference: SingerSongPreference,
+) -> Result<Option<SingerSongPreference>, CommandError> {
+    let preference = SingerSongPreference {
+        video_id: preference.video_id.trim().to_string(),
+        notes: preference
+            .notes
+            .map(|notes| notes.trim().to_string())
+            .filter(|notes| !notes.is_empty()),
+        ..preference
+    };
+    validate_singer_song_preference(&preference)?;
+    ensure_singer_exists(conn, preference.singer_id)?;
+
+    if preference.pitch_semitones.is_none() && preference.tempo_percent.is_none() && preference.notes.is_none() {
+        conn.execute(
+            "DELETE FROM singer_song_preferences WHERE singer_id = ?1 AND video_id = ?2",
+            rusqlite::params![preference.singer_id, preference.video_id],
+        )?;
+        return Ok(None);
+    }
 
-    let mut stmt = db.connection().prepare(
-        "SELECT id, name, started_at, ended_at, is_active, hosted_session_id, hosted_by_user_id, hosted_session_status FROM sessions
-             ORDER BY started_at DESC LIMIT ?1",
+    conn.execute(
+        "INSERT INTO singer_song_preferences (singer_id, video_id, pitch_semitones, tempo_percent, notes)
+         VALUES (?1, ?2, ?3, ?4, ?5)
+         ON CONFLICT(singer_id, video_id) DO UPDATE SET
+             pitch_semitones = excluded.pitch_semitones,
+             tempo_percent = excluded.tempo_percent,
+             notes = excluded.notes,
+             updated_at = CURRENT_TIMESTAMP",
+        rusqlite::params![
+            preference.singer_id,
+            preference.video_id,
+            preference.pitch_semitones,
+            preference.tempo_percent,
+            preference.notes
+        ],
     )?;
+    Ok(Some(preference))
+}
 
-    let sessions = stmt
-        .query_map([limit], |row| {
-            Ok(Session {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                started_at: row.get(2)?,
-                ended_at: row.get(3)?,
-                is_active: row.get::<_, i32>(4)? != 0,
-                hosted_session_id: row.get(5)?,
-                hosted_by_user_id: row.get(6)?,
-                hosted_session_status: row.get::<_, Option<String>>(7)?
-                    .and_then(|s| HostedSessionStatus::from_str(&s)),
-            })
-        })?
-        .collect::<Result<Vec<_>, _>>()?;
+/// Give a queue item its singer's preferred key and tempo for the song, when the
+/// singer is the first one assigned (the one who added it) and the item has no
+/// override of its own yet. For duets the first singer's preference wins; later
+/// singers' preferences may conflict and are ignored. Returns whether it was applied.
+pub(super) fn apply_singer_song_preference(
+    conn: &Connection,
+    queue_item_id: &str,
+    singer_id: i64,
+) -> Result<bool, CommandError> {
+    let has_singers: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM queue_singers WHERE queue_item_id = ?1)",
+        [queue_item_id],
+        |row| row.get(0),
+    )?;
+    if has_singers {
+        return Ok(false);
+    }
 
-    Ok(sessions)
+    let (video_id, current) = conn.query_row(
+        "SELECT video_id, playback_rate, transpose FROM queue_items WHERE id = ?1",
+        [queue_item_id],
+        |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                PlaybackAdjustments {
+                    playback_rate: row.get(1)?,
+                    transpose: row.get(2)?,
+                },
+            ))
+        },
+    )?;
+    if current != PlaybackAdjustments::default() {
+        return Ok(false);
+    }
+
+    let Some(preference) = read_singer_song_preference(conn, singer_id, &video_id)? else {
+        return Ok(false);
+    };
+    let adjustments = preference.playback_adjustments();
+    if adjustments == PlaybackAdjustments::default() {
+        return Ok(false);
+    }
+
+    conn.execute(
+        "UPDATE queue_items SET playback_rate = ?1, transpose = ?2 WHERE id = ?3",
+        rusqlite::params![adjustments.playback_rate, adjustments.transpose, queue_item_id],
+    )?;
+    debug!(
+        "Applied singer {} preference to queue item {}: {:?}",
+        singer_id, queue_item_id, adjustments
+    );
+    Ok(true)
 }
 
+/// Get a singer's preferred key, tempo and notes for a song (None if not set)
 #[tauri::command]
-pub fn rename_session(
+pub fn get_singer_song_preference(
     state: State<'_, AppState>,
-    session_id: i64,
-    name: String,
-) -> Result<Session, CommandError> {
-    let name = name.trim().to_string();
-    if name.is_empty() {
-        return Err(CommandError::Validation(
-            "Session name cannot be empty".to_string(),
-        ));
-    }
-    if name.len() > MAX_NAME_LENGTH {
-        return Err(CommandError::Validation(format!(
-            "Session name cannot exceed {} characters",
-            MAX_NAME_LENGTH
-        )));
-    }
+    singer_id: i64,
+    video_id: String,
+) -> Result<Option<SingerSongPreference>, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    Ok(read_singer_song_preference(db.connection(), singer_id, video_id.trim())?)
+}
 
-    info!("Renaming session {} to: {}", session_id, name);
+/// Set a singer's preferred pitch (-12 to 12 semitones), tempo (50-200%) and notes for
+/// a song. Applied to the song's queue item when the singer is assigned to it first.
+/// Leaving every field empty clears the preference.
+#[tauri::command]
+pub fn set_singer_song_preference(
+    state: State<'_, AppState>,
+    preference: SingerSongPreference,
+) -> Result<Option<SingerSongPreference>, CommandError> {
+    info!(
+        "Setting singer {} preference for {}: pitch={:?}, tempo={:?}",
+        preference.singer_id, preference.video_id, preference.pitch_semitones, preference.tempo_percent
+    );
     let db = state.db.lock().map_lock_err()?;
+    write_singer_song_preference(db.connection(), preference)
+}
 
-    db.connection().execute(
-        "UPDATE sessions SET name = ?1 WHERE id = ?2",
-        rusqlite::params![name, session_id],
-    )?;
+// ============ Queue Singer Assignment Commands ============
 
-    let session = db.connection().query_row(
-        "SELECT id, name, started_at, ended_at, is_active, hosted_session_id, hosted_by_user_id, hosted_session_status FROM sessions WHERE id = ?1",
-        [session_id],
-        |row| {
-            Ok(Session {
-                id: row.get(0)?,
-                name: row.get(1)?,
-                started_at: row.get(2)?,
-                ended_at: row.get(3)?,
-                is_active: row.get::<_, i32>(4)? != 0,
-                hosted_session_id: row.get(5)?,
-                hosted_by_user_id: row.get(6)?,
-                hosted_session_status: row.get::<_, Option<String>>(7)?
-                    .and_then(|s| HostedSessionStatus::from_str(&s)),
-            })
-        },
-    )?;
+#[tauri::command]
+pub fn assign_singer_to_queue_item(
+    state: State<'_, AppState>,
+    queue_item_id: String,
+    singer_id: i64,
+) -> Result<(), CommandError> {
+    debug!(
+        "Assigning singer {} to queue item {}",
+        singer_id, queue_item_id
+    );
+    let mut db = state.db.lock().map_lock_err()?;
 
-    Ok(session)
+    let tx = db.connection_mut().transaction()?;
+    ensure_singer_and_queue_item_exist(&tx, &queue_item_id, singer_id)?;
+    apply_singer_song_preference(&tx, &queue_item_id, singer_id)?;
+    insert_queue_singer(&tx, &queue_item_id, singer_id)?;
+    auto_shuffle_after_singer_change(&tx, &queue_item_id)?;
+    tx.commit()?;
+
+    Ok(())
 }
 
-#[tauri::command]
-pub fn delete_session(state: State<'_, AppState>, session_id: i64) -> Result<(), CommandError> {
-    info!("Deleting session: {}", session_id);
-    let db = state.db.lock().map_lock_err()?;
+/// Check a singer exists, returning `NotFound` for the singer if not
+fn ensure_singer_exists(conn: &Connection, singer_id: i64) -> Result<(), CommandError> {
+    let exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM singers WHERE id = ?1)",
+        [singer_id],
+        |row| row.get(0),
+    )?;
+    if !exists {
+        return Err(CommandError::NotFound {
+            resource: "Singer",
+            id: singer_id.to_string(),
+        });
+    }
+    Ok(())
+}
 
-    // Don't allow deleting the active session
-    let is_active: bool = db
-        .connection()
+/// Check both sides of a queue singer assignment exist, so callers get a `NotFound`
+/// naming the missing one instead of a foreign key error
+fn ensure_singer_and_queue_item_exist(
+    conn: &Connection,
+    queue_item_id: &str,
+    singer_id: i64,
+) -> Result<(), CommandError> {
+    let item_exists: bool = conn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM queue_items WHERE id = ?1)",
+        [queue_item_id],
+        |row| row.get(0),
+    )?;
+    if !item_exists {
+        return Err(CommandError::NotFound {
+            resource: "Queue item",
+            id: queue_item_id.to_string(),
+        });
+    }
+    ensure_singer_exists(conn, singer_id)
+}
+
+/// Re-run the auto fair shuffle after a pending item's singers changed.
+/// History items and sessions without auto-shuffle are left alone.
+fn auto_shuffle_after_singer_change(conn: &Connection, queue_item_id: &str) -> Result<(), CommandError> {
+    let session_id: Option<i64> = conn
         .query_row(
-            "SELECT is_active FROM sessions WHERE id = ?1",
-            [session_id],
-            |row| row.get::<_, i32>(0).map(|v| v != 0),
+            "SELECT session_id FROM queue_items WHERE id = ?1 AND item_type = 'queue'",
+            [queue_item_id],
+            |row| row.get(0),
         )
-        .unwrap_or(false);
+        .optional()?;
 
-    if is_active {
-        return Err(CommandError::Validation(
-            "Cannot delete the active session".to_string(),
-        ));
+    if let Some(session_id) = session_id {
+        if auto_fair_shuffle(conn, session_id)? {
+            debug!("Auto fair shuffled queue after singer change on {}", queue_item_id);
+        }
     }
+    Ok(())
+}
 
-    // Delete session (cascade will handle queue_items and session_singers)
-    db.connection()
-        .execute("DELETE FROM sessions WHERE id = ?1", [session_id])?;
-
-    // Clean up non-persistent singers that are now orphaned
-    db.connection().execute(
-        "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
-        [],
-    )?;
+/// Append a singer to a queue item's singer list (next position after existing singers)
+fn insert_queue_singer(
+    conn: &Connection,
+    queue_item_id: &str,
+    singer_id: i64,
+) -> Result<(), rusqlite::Error> {
+    // Get next position for this queue item
+    let position: i32 = conn
+        .query_row(
+            "SELECT COALESCE(MAX(position), -1) + 1 FROM queue_singers WHERE queue_item_id = ?1",
+            [queue_item_id],
+            |row| row.get(0),
+        )
+        .unwrap_or(0);
 
-    // Clean up orphaned queue singer assignments
-    db.connection().execute(
-        "DELETE FROM queue_singers WHERE queue_item_id NOT IN (SELECT id FROM queue_items)",
-        [],
+    conn.execute(
+        "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, ?3)",
+        rusqlite::params![queue_item_id, singer_id, position],
     )?;
 
-    info!("Session {} deleted", session_id);
     Ok(())
 }
 
-#[tauri::command]
-pub fn load_session(
-    state: State<'_, AppState>,
+/// Assign a singer to every pending queue item in a session whose artist matches
+/// (case-insensitive, surrounding whitespace ignored), applying their song preferences.
+/// Items the singer is already assigned to are skipped. Returns the number of items assigned.
+fn assign_singer_to_artist_items(
+    conn: &Connection,
     session_id: i64,
-) -> Result<Session, CommandError> {
-    info!("Loading session: {}", session_id);
-    let db = state.db.lock().map_lock_err()?;
-    let conn = db.connection();
-
-    // Use transaction for atomicity
-    conn.execute("BEGIN IMMEDIATE", [])?;
-
-    let result = (|| -> Result<Session, CommandError> {
-        // End any active session first
-        conn.execute(
-            "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE is_active = 1",
-            [],
-        )?;
-
-        // Activate the selected session
-        conn.execute(
-            "UPDATE sessions SET is_active = 1, ended_at = NULL WHERE id = ?1",
-            [session_id],
-        )?;
-
-        let session = conn.query_row(
-            "SELECT id, name, started_at, ended_at, is_active, hosted_session_id, hosted_by_user_id, hosted_session_status FROM sessions WHERE id = ?1",
-            [session_id],
-            |row| {
-                Ok(Session {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    started_at: row.get(2)?,
-                    ended_at: row.get(3)?,
-                    is_active: row.get::<_, i32>(4)? != 0,
-                    hosted_session_id: row.get(5)?,
-                    hosted_by_user_id: row.get(6)?,
-                    hosted_session_status: row.get::<_, Option<String>>(7)?
-                        .and_then(|s| HostedSessionStatus::from_str(&s)),
-                })
-            },
-        )?;
-
-        Ok(session)
-    })();
+    singer_id: i64,
+    artist: &str,
+) -> Result<u32, CommandError> {
+    let item_ids: Vec<String> = conn
+        .prepare(
+            "SELECT id FROM queue_items
+             WHERE session_id = ?1 AND item_type = 'queue'
+               AND LOWER(TRIM(artist)) = LOWER(TRIM(?2))
+               AND id NOT IN (SELECT queue_item_id FROM queue_singers WHERE singer_id = ?3)
+             ORDER BY position",
+        )?
+        .query_map(rusqlite::params![session_id, artist, singer_id], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
 
-    match result {
-        Ok(session) => {
-            conn.execute("COMMIT", [])?;
-            info!("Session {} loaded and activated", session_id);
-            Ok(session)
-        }
-        Err(e) => {
-            let _ = conn.execute("ROLLBACK", []);
-            Err(e)
-        }
+    for item_id in &item_ids {
+        apply_singer_song_preference(conn, item_id, singer_id)?;
+        insert_queue_singer(conn, item_id, singer_id)?;
     }
-}
 
-// ============ Hosted Session Commands ============
+    Ok(item_ids.len() as u32)
+}
 
+/// Assign a singer to all pending queue items by an artist (e.g. theme nights)
 #[tauri::command]
-pub fn session_set_hosted(
+pub fn assign_singer_by_artist(
     state: State<'_, AppState>,
     session_id: i64,
-    hosted_session_id: String,
-    hosted_by_user_id: String,
-    status: String,
-) -> Result<(), CommandError> {
-    info!(
-        "Setting hosted session for session {}: hosted_id={}, user_id={}, status={}",
-        session_id, hosted_session_id, hosted_by_user_id, status
-    );
-
-    // Parse status string into HostedSessionStatus enum (TYPE-003)
-    let status_enum = HostedSessionStatus::from_str(&status).ok_or_else(|| {
-        CommandError::Validation(format!(
-            "Invalid hosted session status: '{}'. Must be one of: active, paused, ended",
-            status
-        ))
-    })?;
-
-    let db = state.db.lock().map_lock_err()?;
-
-    // First verify session exists (needed to distinguish "not found" from "ownership conflict")
-    let session_exists: bool = db
-        .connection()
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?1)",
-            [session_id],
-            |row| row.get(0),
-        )?;
-
-    if !session_exists {
-        return Err(CommandError::Validation(format!(
-            "Session {} does not exist",
-            session_id
-        )));
+    singer_id: i64,
+    artist: String,
+) -> Result<u32, CommandError> {
+    let artist = artist.trim().to_string();
+    if artist.is_empty() {
+        return Err(CommandError::Validation(
+            "Artist cannot be empty".to_string(),
+        ));
     }
 
-    // Use conditional UPDATE with WHERE clause for atomic ownership check (CONC-003).
-    // This prevents race conditions by combining the ownership check and update in a single
-    // SQL statement, avoiding the TOCTOU (time-of-check-time-of-use) vulnerability where:
-    // - The session could be deleted between the EXISTS check above and this UPDATE
-    // - Another user could claim ownership between a SELECT and separate UPDATE
-    // - Status could change between reading current state and writing new state
-    // By using a conditional UPDATE, the database guarantees atomicity. The update only succeeds if:
-    // - No existing hosted_by_user_id (NULL) - session never hosted before
-    // - Same user is updating (hosted_by_user_id matches)
-    // - Previous status is 'ended' (session was released, can be taken over)
-    // Note: We intentionally don't allow override when hosted_session_status IS NULL but
-    // hosted_by_user_id is set to a different user - that would be a data integrity issue
-    // that should be investigated, not silently overwritten.
-    let affected_rows = db.connection().execute(
-        "UPDATE sessions
-         SET hosted_session_id = ?1, hosted_by_user_id = ?2, hosted_session_status = ?3
-         WHERE id = ?4
-         AND (
-             hosted_by_user_id IS NULL
-             OR hosted_by_user_id = ?2
-             OR hosted_session_status = 'ended'
-         )",
-        rusqlite::params![hosted_session_id, hosted_by_user_id, status_enum.as_str(), session_id],
-    )?;
+    debug!(
+        "Assigning singer {} to queue items by artist '{}' in session {}",
+        singer_id, artist, session_id
+    );
+    let mut db = state.db.lock().map_lock_err()?;
 
-    // If no rows affected, it means ownership conflict (session exists but conditions not met)
-    if affected_rows == 0 {
-        info!(
-            "Ownership conflict: session {} is being hosted by another user",
-            session_id
-        );
-        return Err(CommandError::OwnershipConflict);
+    ensure_singer_exists(db.connection(), singer_id)?;
+
+    let tx = db.connection_mut().transaction()?;
+    let assigned = assign_singer_to_artist_items(&tx, session_id, singer_id, &artist)?;
+    if assigned > 0 {
+        auto_fair_shuffle(&tx, session_id)?;
     }
+    tx.commit()?;
 
     info!(
-        "Hosted session set for session {}: hosted_id={}, status={}",
-        session_id, hosted_session_id, status
+        "Assigned singer {} to {} queue items by '{}'",
+        singer_id, assigned, artist
     );
-    Ok(())
+    Ok(assigned)
 }
 
 #[tauri::command]
-pub fn session_update_hosted_status(
+pub fn remove_singer_from_queue_item(
     state: State<'_, AppState>,
-    session_id: i64,
-    status: String,
+    queue_item_id: String,
+    singer_id: i64,
 ) -> Result<(), CommandError> {
-    info!(
-        "Updating hosted session status for session {}: status={}",
-        session_id, status
+    debug!(
+        "Removing singer {} from queue item {}",
+        singer_id, queue_item_id
     );
+    let mut db = state.db.lock().map_lock_err()?;
 
-    // Parse status string into HostedSessionStatus enum (TYPE-004)
-    let status_enum = HostedSessionStatus::from_str(&status).ok_or_else(|| {
-        CommandError::Validation(format!(
-            "Invalid hosted session status: '{}'. Must be one of: active, paused, ended",
-            status
-        ))
-    })?;
-
-    let db = state.db.lock().map_lock_err()?;
+    let tx = db.connection_mut().transaction()?;
+    ensure_singer_and_queue_item_exist(&tx, &queue_item_id, singer_id)?;
+    tx.execute(
+        "DELETE FROM queue_singers WHERE queue_item_id = ?1 AND singer_id = ?2",
+        rusqlite::params![queue_item_id, singer_id],
+    )?;
+    auto_shuffle_after_singer_change(&tx, &queue_item_id)?;
+    tx.commit()?;
 
-    // Verify session exists
-    let session_exists: bool = db
-        .connection()
-        .query_row(
-            "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?1)",
-            [session_id],
-            |row| row.get(0),
-        )?;
+    Ok(())
+}
 
-    if !session_exists {
-        return Err(CommandError::Validation(format!(
-            "Session {} does not exist",
-            session_id
-        )));
-    }
+#[tauri::command]
+pub fn get_queue_item_singers(
+    state: State<'_, AppState>,
+    queue_item_id: String,
+) -> Result<Vec<Singer>, CommandError> {
+    debug!("Getting singers for queue item {}", queue_item_id);
+    let db = state.db.lock().map_lock_err()?;
 
-    // Update only the status field
-    db.connection().execute(
-        "UPDATE sessions SET hosted_session_status = ?1 WHERE id = ?2",
-        rusqlite::params![status_enum.as_str(), session_id],
+    let mut stmt = db.connection().prepare(
+        "SELECT s.id, s.name, s.unique_name, s.color, s.is_persistent, s.online_id
+             FROM singers s
+             INNER JOIN queue_singers qs ON s.id = qs.singer_id
+             WHERE qs.queue_item_id = ?1
+             ORDER BY qs.position",
     )?;
 
-    info!(
-        "Hosted session status updated for session {}: status={}",
-        session_id, status
-    );
-    Ok(())
+    let singers = stmt
+        .query_map([&queue_item_id], |row| {
+            Ok(Singer {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                unique_name: row.get(2)?,
+                color: row.get(3)?,
+                is_persistent: row.get::<_, i32>(4)? != 0,
+                online_id: row.get(5)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(singers)
 }
 
-// ============ Active Singer Commands ============
+/// Check if a singer already sang a video in a session (played history items only)
+fn singer_sang_in_session(
+    conn: &Connection,
+    session_id: i64,
+    singer_id: i64,
+    video_id: &str,
+) -> Result<bool, rusqlite::Error> {
+    conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM queue_items qi
+            INNER JOIN queue_singers qs ON qs.queue_item_id = qi.id
+            WHERE qi.session_id = ?1 AND qi.item_type = 'history'
+              AND qs.singer_id = ?2 AND qi.video_id = ?3
+        )",
+        rusqlite::params![session_id, singer_id, video_id],
+        |row| row.get(0),
+    )
+}
 
+/// Check if a singer already sang this video in the active session,
+/// so the frontend can warn before queuing a repeat
 #[tauri::command]
-pub fn session_set_active_singer(
+pub fn check_singer_repeat(
     state: State<'_, AppState>,
-    session_id: i64,
-    singer_id: Option<i64>,
-) -> Result<(), CommandError> {
-    debug!(
-        "Setting active singer for session {}: {:?}",
-        session_id, singer_id
-    );
+    singer_id: i64,
+    video_id: String,
+) -> Result<bool, CommandError> {
     let db = state.db.lock().map_lock_err()?;
+    let session_id = active_session_id(db.connection())?;
 
-    // If singer_id is provided, verify singer exists and is in the session
-    if let Some(sid) = singer_id {
-        let exists: bool = db
-            .connection()
-            .query_row(
-                "SELECT EXISTS(SELECT 1 FROM session_singers WHERE session_id = ?1 AND singer_id = ?2)",
-                rusqlite::params![session_id, sid],
-                |row| row.get(0),
-            )?;
+    let repeat = singer_sang_in_session(db.connection(), session_id, singer_id, &video_id)?;
+    debug!(
+        "Singer {} repeat check for {}: {}",
+        singer_id, video_id, repeat
+    );
+    Ok(repeat)
+}
 
-        if !exists {
-            return Err(CommandError::Validation(
-                "Singer is not part of this session".to_string(),
-            ));
-        }
-    }
+#[tauri::command]
+pub fn clear_queue_item_singers(
+    state: State<'_, AppState>,
+    queue_item_id: String,
+) -> Result<(), CommandError> {
+    debug!("Clearing singers from queue item {}", queue_item_id);
+    let mut db = state.db.lock().map_lock_err()?;
 
-    db.connection().execute(
-        "UPDATE sessions SET active_singer_id = ?1 WHERE id = ?2",
-        rusqlite::params![singer_id, session_id],
+    let tx = db.connection_mut().transaction()?;
+    tx.execute(
+        "DELETE FROM queue_singers WHERE queue_item_id = ?1",
+        [&queue_item_id],
     )?;
+    auto_shuffle_after_singer_change(&tx, &queue_item_id)?;
+    tx.commit()?;
 
-    info!(
-        "Active singer set for session {}: {:?}",
-        session_id, singer_id
-    );
     Ok(())
 }
 
+// ============ Session Management Commands ============
+
 #[tauri::command]
-pub fn session_get_active_singer(
+pub fn get_recent_sessions(
     state: State<'_, AppState>,
-    session_id: i64,
-) -> Result<Option<Singer>, CommandError> {
-    debug!("Getting active singer for session {}", session_id);
+    limit: Option<i32>,
+) -> Result<Vec<Session>, CommandError> {
+    let limit = limit.unwrap_or(10);
+    debug!("Getting recent sessions (limit: {})", limit);
     let db = state.db.lock().map_lock_err()?;
 
-    let result = db.connection().query_row(
-        "SELECT s.id, s.name, s.unique_name, s.color, s.is_persistent, s.online_id
-         FROM singers s
-         INNER JOIN sessions sess ON sess.active_singer_id = s.id
-         WHERE sess.id = ?1",
-        [session_id],
-        |row| {
-            Ok(Singer {
+    let mut stmt = db.connection().prepare(
+        "SELECT id, name, started_at, ended_at, is_active, hosted_session_id, hosted_by_user_id, hosted_session_status FROM sessions
+             WHERE deleted_at IS NULL
+             ORDER BY started_at DESC LIMIT ?1",
+    )?;
+
+    let sessions = stmt
+        .query_map([limit], |row| {
+            Ok(Session {
                 id: row.get(0)?,
                 name: row.get(1)?,
-                unique_name: row.get(2)?,
-                color: row.get(3)?,
-                is_persistent: row.get::<_, i32>(4)? != 0,
-                online_id: row.get(5)?,
+                started_at: row.get(2)?,
+                ended_at: row.get(3)?,
+                is_active: row.get::<_, i32>(4)? != 0,
+                hosted_session_id: row.get(5)?,
+                hosted_by_user_id: row.get(6)?,
+                hosted_session_status: row.get::<_, Option<String>>(7)?
+                    .and_then(|s| HostedSessionStatus::from_str(&s)),
             })
-        },
-    );
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
 
-    match result {
-        Ok(singer) => Ok(Some(singer)),
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
-        Err(e) => Err(CommandError::Database(e)),
-    }
+    Ok(sessions)
 }
 
-#[cfg(test)]
-mod tests {
-    use rusqlite::Connection;
+/// Number of songs in `GlobalStats::top_songs`
+const GLOBAL_STATS_TOP_SONGS: u32 = 10;
 
-    /// Create an in-memory database with the required schema for testing
-    fn setup_test_db() -> Connection {
-        let conn = Connection::open_in_memory().unwrap();
-        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+/// Aggregate played songs and singers over every session not in the trash
+fn load_global_stats(conn: &Connection) -> Result<GlobalStats, rusqlite::Error> {
+    let total_sessions: u32 = conn.query_row(
+        "SELECT COUNT(*) FROM sessions WHERE deleted_at IS NULL",
+        [],
+        |row| row.get(0),
+    )?;
 
-        // Create minimal schema for session/singer tests
-        conn.execute_batch(
-            r#"
-            CREATE TABLE singers (
-                id INTEGER PRIMARY KEY,
-                name TEXT NOT NULL,
-                unique_name TEXT,
-                color TEXT NOT NULL,
-                is_persistent INTEGER DEFAULT 0,
-                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP
-            );
+    let (total_songs_played, total_secs): (u32, i64) = conn.query_row(
+        "SELECT COUNT(*), COALESCE(SUM(qi.duration), 0)
+         FROM queue_items qi
+         INNER JOIN sessions s ON s.id = qi.session_id AND s.deleted_at IS NULL
+         WHERE qi.item_type = 'history'",
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    )?;
 
-            CREATE TABLE sessions (
-                id INTEGER PRIMARY KEY,
-                name TEXT,
-                started_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                ended_at TIMESTAMP,
-                is_active INTEGER DEFAULT 1,
-                history_index INTEGER DEFAULT -1,
-                active_singer_id INTEGER REFERENCES singers(id) ON DELETE SET NULL,
-                hosted_session_id TEXT,
-                hosted_by_user_id TEXT,
-                hosted_session_status TEXT
-            );
+    let total_unique_singers: u32 = conn.query_row(
+        "SELECT COUNT(DISTINCT qs.singer_id)
+         FROM queue_singers qs
+         INNER JOIN queue_items qi ON qi.id = qs.queue_item_id AND qi.item_type = 'history'
+         INNER JOIN sessions s ON s.id = qi.session_id AND s.deleted_at IS NULL",
+        [],
+        |row| row.get(0),
+    )?;
 
-            CREATE TABLE session_singers (
-                session_id INTEGER NOT NULL,
-                singer_id INTEGER NOT NULL,
-                joined_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
-                PRIMARY KEY (session_id, singer_id),
-                FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
-                FOREIGN KEY (singer_id) REFERENCES singers(id) ON DELETE CASCADE
-            );
+    // Bare title/artist columns come from the row with MAX(played_at) (SQLite semantics)
+    let mut stmt = conn.prepare(
+        "SELECT qi.video_id, qi.title, qi.artist, COUNT(*) AS plays, MAX(qi.played_at)
+         FROM queue_items qi
+         INNER JOIN sessions s ON s.id = qi.session_id AND s.deleted_at IS NULL
+         WHERE qi.item_type = 'history'
+         GROUP BY qi.video_id
+         ORDER BY plays DESC, qi.video_id
+         LIMIT ?1",
+    )?;
+    let top_songs = stmt
+        .query_map([GLOBAL_STATS_TOP_SONGS], |row| {
+            Ok(SongPlayCount {
+                video_id: row.get(0)?,
+                title: row.get(1)?,
+                artist: row.get(2)?,
+                play_count: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
 
-            CREATE TABLE queue_singers (
-                id INTEGER PRIMARY KEY,
-                queue_item_id TEXT NOT NULL,
-                singer_id INTEGER NOT NULL,
-                position INTEGER DEFAULT 0,
-                FOREIGN KEY (singer_id) REFERENCES singers(id) ON DELETE CASCADE
-            );
+    let most_active_singer = conn
+        .query_row(
+            "SELECT sg.id, sg.name, COUNT(*) AS songs
+             FROM queue_singers qs
+             INNER JOIN singers sg ON sg.id = qs.singer_id
+             INNER JOIN queue_items qi ON qi.id = qs.queue_item_id AND qi.item_type = 'history'
+             INNER JOIN sessions s ON s.id = qi.session_id AND s.deleted_at IS NULL
+             GROUP BY sg.id
+             ORDER BY songs DESC, sg.name, sg.id
+             LIMIT 1",
+            [],
+            |row| {
+                Ok(SingerActivity {
+                    singer_id: row.get(0)?,
+                    name: row.get(1)?,
+                    song_count: row.get(2)?,
+                })
+            },
+        )
+        .optional()?;
 
-            CREATE TABLE queue_items (
-                id TEXT PRIMARY KEY,
-                session_id INTEGER NOT NULL,
-                item_type TEXT NOT NULL,
-                video_id TEXT NOT NULL,
-                title TEXT NOT NULL,
-                artist TEXT,
-                duration INTEGER,
-                thumbnail_url TEXT,
-                source TEXT NOT NULL,
-                youtube_id TEXT,
-                file_path TEXT,
-                position INTEGER NOT NULL,
-                added_at TEXT NOT NULL,
-                played_at TEXT,
-                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
-            );
+    Ok(GlobalStats {
+        total_sessions,
+        total_songs_played,
+        total_unique_singers,
+        total_sung_hours: total_secs as f64 / 3600.0,
+        top_songs,
+        most_active_singer,
+    })
+}
 
-            CREATE INDEX idx_queue_singers_queue_item ON queue_singers(queue_item_id);
-            CREATE INDEX idx_session_singers_session ON session_singers(session_id);
-            "#,
-        )
-        .unwrap();
+/// All-time statistics across every session, for a dashboard
+#[tauri::command]
+pub fn get_global_stats(state: State<'_, AppState>) -> Result<GlobalStats, CommandError> {
+    debug!("Getting global stats");
+    let db = state.db.lock().map_lock_err()?;
+    Ok(load_global_stats(db.connection())?)
+}
+
+/// Trim and validate a session name
+fn validate_session_name(name: &str) -> Result<String, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::Validation(
+            "Session name cannot be empty".to_string(),
+        ));
+    }
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(CommandError::Validation(format!(
+            "Session name cannot exceed {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    Ok(name)
+}
+
+/// Set a session's name and return the updated session
+fn set_session_name(conn: &Connection, session_id: i64, name: &str) -> Result<Session, CommandError> {
+    conn.execute(
+        "UPDATE sessions SET name = ?1 WHERE id = ?2",
+        rusqlite::params![name, session_id],
+    )?;
+
+    get_session_by_id(conn, session_id)
+}
+
+/// Load a session by id
+fn get_session_by_id(conn: &Connection, session_id: i64) -> Result<Session, CommandError> {
+    let session = conn.query_row(
+        "SELECT id, name, started_at, ended_at, is_active, hosted_session_id, hosted_by_user_id, hosted_session_status FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| {
+            Ok(Session {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                started_at: row.get(2)?,
+                ended_at: row.get(3)?,
+                is_active: row.get::<_, i32>(4)? != 0,
+                hosted_session_id: row.get(5)?,
+                hosted_by_user_id: row.get(6)?,
+                hosted_session_status: row.get::<_, Option<String>>(7)?
+                    .and_then(|s| HostedSessionStatus::from_str(&s)),
+            })
+        },
+    )?;
+
+    Ok(session)
+}
+
+/// Parse an ISO 8601 timestamp and normalize it to SQLite's UTC `YYYY-MM-DD HH:MM:SS`
+/// format, so edited sessions sort correctly against ones stamped with CURRENT_TIMESTAMP.
+/// Timestamps without an offset are taken as UTC.
+fn normalize_session_timestamp(value: &str, field: &str) -> Result<String, CommandError> {
+    let value = value.trim();
+    let parsed = chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.naive_utc())
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.f"))
+        .map_err(|_| {
+            CommandError::Validation(format!("Invalid {} timestamp: {}", field, value))
+        })?;
+
+    Ok(parsed.format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+/// Set an inactive session's start and end times and return the updated session
+fn update_session_times(
+    conn: &Connection,
+    session_id: i64,
+    started_at: &str,
+    ended_at: Option<&str>,
+) -> Result<Session, CommandError> {
+    let started_at = normalize_session_timestamp(started_at, "start")?;
+    let ended_at = ended_at
+        .map(|value| normalize_session_timestamp(value, "end"))
+        .transpose()?;
+
+    if let Some(ref ended_at) = ended_at {
+        if *ended_at < started_at {
+            return Err(CommandError::Validation(
+                "Session end time cannot be before its start time".to_string(),
+            ));
+        }
+    }
+
+    let is_active: bool = conn
+        .query_row(
+            "SELECT is_active FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| Ok(row.get::<_, i32>(0)? != 0),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound {
+            resource: "Session",
+            id: session_id.to_string(),
+        })?;
+    if is_active {
+        return Err(CommandError::Validation(
+            "Cannot edit the times of the active session".to_string(),
+        ));
+    }
+
+    conn.execute(
+        "UPDATE sessions SET started_at = ?1, ended_at = ?2 WHERE id = ?3",
+        rusqlite::params![started_at, ended_at, session_id],
+    )?;
+
+    get_session_by_id(conn, session_id)
+}
+
+/// Get the active session's id, or `NoActiveSession` if none
+pub(super) fn active_session_id(conn: &Connection) -> Result<i64, CommandError> {
+    conn.query_row(
+        "SELECT id FROM sessions WHERE is_active = 1",
+        [],
+        |row| row.get(0),
+    )
+    .optional()?
+    .ok_or(CommandError::NoActiveSession)
+}
+
+#[tauri::command]
+pub fn rename_session(
+    state: State<'_, AppState>,
+    session_id: i64,
+    name: String,
+) -> Result<Session, CommandError> {
+    let name = validate_session_name(&name)?;
+
+    info!("Renaming session {} to: {}", session_id, name);
+    let db = state.db.lock().map_lock_err()?;
+    ensure_not_party_mode(db.connection(), "Saving sessions")?;
+
+    set_session_name(db.connection(), session_id, &name)
+}
+
+/// Backfill the start/end times of a past (inactive) session, e.g. one logged after the event
+#[tauri::command]
+pub fn set_session_times(
+    state: State<'_, AppState>,
+    session_id: i64,
+    started_at: String,
+    ended_at: Option<String>,
+) -> Result<Session, CommandError> {
+    info!(
+        "Setting session {} times: {} - {:?}",
+        session_id, started_at, ended_at
+    );
+    let db = state.db.lock().map_lock_err()?;
+
+    update_session_times(db.connection(), session_id, &started_at, ended_at.as_deref())
+}
+
+/// Set the active session's name without looking up its id first
+#[tauri::command]
+pub fn name_active_session(
+    state: State<'_, AppState>,
+    name: String,
+) -> Result<Session, CommandError> {
+    let name = validate_session_name(&name)?;
+
+    let db = state.db.lock().map_lock_err()?;
+    ensure_not_party_mode(db.connection(), "Saving sessions")?;
+    let session_id = active_session_id(db.connection())?;
+
+    info!("Naming active session {}: {}", session_id, name);
+    set_session_name(db.connection(), session_id, &name)
+}
+
+fn validate_session_settings(settings: &SessionSettings) -> Result<(), CommandError> {
+    if let Some(volume) = settings.default_volume {
+        if volume > MAX_SESSION_VOLUME {
+            return Err(CommandError::Validation(format!(
+                "Default volume must be from 0 to {}",
+                MAX_SESSION_VOLUME
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Read a session's playback defaults (all unset if never configured)
+fn read_session_settings(conn: &Connection, session_id: i64) -> Result<SessionSettings, CommandError> {
+    let json: Option<String> = conn
+        .query_row(
+            "SELECT session_settings FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )
+        .optional()?
+        .ok_or_else(|| CommandError::NotFound {
+            resource: "Session",
+            id: session_id.to_string(),
+        })?;
+
+    match json {
+        Some(json) => Ok(serde_json::from_str(&json)?),
+        None => Ok(SessionSettings::default()),
+    }
+}
+
+/// Validate and store a session's playback defaults
+fn write_session_settings(
+    conn: &Connection,
+    session_id: i64,
+    settings: &SessionSettings,
+) -> Result<(), CommandError> {
+    validate_session_settings(settings)?;
+
+    let updated = conn.execute(
+        "UPDATE sessions SET session_settings = ?1 WHERE id = ?2",
+        rusqlite::params![serde_json::to_string(settings)?, session_id],
+    )?;
+    if updated == 0 {
+        return Err(CommandError::NotFound {
+            resource: "Session",
+            id: session_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Get a session's playback defaults (volume, autoplay, lyrics).
+/// The frontend applies the active session's settings when it is activated.
+#[tauri::command]
+pub fn get_session_settings(
+    state: State<'_, AppState>,
+    session_id: i64,
+) -> Result<SessionSettings, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    read_session_settings(db.connection(), session_id)
+}
+
+/// Set a session's playback defaults. Volume must be from 0 to 100.
+#[tauri::command]
+pub fn set_session_settings(
+    state: State<'_, AppState>,
+    session_id: i64,
+    settings: SessionSettings,
+) -> Result<SessionSettings, CommandError> {
+    info!("Setting session {} settings: {:?}", session_id, settings);
+    let db = state.db.lock().map_lock_err()?;
+    write_session_settings(db.connection(), session_id, &settings)?;
+    Ok(settings)
+}
+
+pub(super) fn validate_playback_adjustments(adjustments: &PlaybackAdjustments) -> Result<(), CommandError> {
+    if let Some(rate) = adjustments.playback_rate {
+        if !PLAYBACK_RATE_RANGE.contains(&rate) {
+            return Err(CommandError::Validation(format!(
+                "Playback rate must be from {} to {}",
+                PLAYBACK_RATE_RANGE.start(),
+                PLAYBACK_RATE_RANGE.end()
+            )));
+        }
+    }
+    if let Some(transpose) = adjustments.transpose {
+        if !TRANSPOSE_RANGE.contains(&transpose) {
+            return Err(CommandError::Validation(format!(
+                "Transpose must be from {} to {} semitones",
+                TRANSPOSE_RANGE.start(),
+                TRANSPOSE_RANGE.end()
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Read a session's default playback rate and transpose (unset if never configured)
+pub(super) fn read_playback_defaults(
+    conn: &Connection,
+    session_id: i64,
+) -> Result<PlaybackAdjustments, CommandError> {
+    conn.query_row(
+        "SELECT default_playback_rate, default_transpose FROM sessions WHERE id = ?1",
+        [session_id],
+        |row| {
+            Ok(PlaybackAdjustments {
+                playback_rate: row.get(0)?,
+                transpose: row.get(1)?,
+            })
+        },
+    )
+    .optional()?
+    .ok_or_else(|| CommandError::NotFound {
+        resource: "Session",
+        id: session_id.to_string(),
+    })
+}
+
+/// Validate and store a session's default playback rate and transpose
+fn write_playback_defaults(
+    conn: &Connection,
+    session_id: i64,
+    defaults: &PlaybackAdjustments,
+) -> Result<(), CommandError> {
+    validate_playback_adjustments(defaults)?;
+
+    let updated = conn.execute(
+        "UPDATE sessions SET default_playback_rate = ?1, default_transpose = ?2 WHERE id = ?3",
+        rusqlite::params![defaults.playback_rate, defaults.transpose, session_id],
+    )?;
+    if updated == 0 {
+        return Err(CommandError::NotFound {
+            resource: "Session",
+            id: session_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// Get a session's default playback rate and transpose
+#[tauri::command]
+pub fn get_session_playback_defaults(
+    state: State<'_, AppState>,
+    session_id: i64,
+) -> Result<PlaybackAdjustments, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    read_playback_defaults(db.connection(), session_id)
+}
+
+/// Set a session's default playback rate (0.5-2.0) and transpose (-12 to 12 semitones).
+/// The player applies them to songs that don't have their own override.
+#[tauri::command]
+pub fn set_session_playback_defaults(
+    state: State<'_, AppState>,
+    session_id: i64,
+    defaults: PlaybackAdjustments,
+) -> Result<PlaybackAdjustments, CommandError> {
+    info!("Setting session {} playback defaults: {:?}", session_id, defaults);
+    let db = state.db.lock().map_lock_err()?;
+    write_playback_defaults(db.connection(), session_id, &defaults)?;
+    Ok(defaults)
+}
+
+/// Whether the active session keeps its queue in fair order automatically
+#[tauri::command]
+pub fn get_auto_shuffle(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = active_session_id(db.connection())?;
+    auto_shuffle_enabled(db.connection(), session_id)
+}
+
+/// Turn automatic fair shuffling on or off for the active session. While on, adding a
+/// song or changing singer assignments re-runs the fair shuffle, keeping the first
+/// pending item in place. Turning it on shuffles the existing queue right away.
+#[tauri::command]
+pub fn set_auto_shuffle(state: State<'_, AppState>, enabled: bool) -> Result<(), CommandError> {
+    let mut db = state.db.lock().map_lock_err()?;
+    let session_id = active_session_id(db.connection())?;
+    info!("Setting auto-shuffle for session {}: {}", session_id, enabled);
+
+    let tx = db.connection_mut().transaction()?;
+    tx.execute(
+        "UPDATE sessions SET auto_shuffle = ?1 WHERE id = ?2",
+        rusqlite::params![enabled, session_id],
+    )?;
+    auto_fair_shuffle(&tx, session_id)?;
+    tx.commit()?;
+    Ok(())
+}
+
+/// The active session's queue rotation mode (fifo, fair or random)
+#[tauri::command]
+pub fn get_rotation_mode(state: State<'_, AppState>) -> Result<RotationMode, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = active_session_id(db.connection())?;
+    read_rotation_mode(db.connection(), session_id)
+}
+
+/// Set how `queue_apply_rotation` orders the active session's queue
+#[tauri::command]
+pub fn set_rotation_mode(state: State<'_, AppState>, mode: RotationMode) -> Result<(), CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = active_session_id(db.connection())?;
+    info!("Setting rotation mode for session {}: {:?}", session_id, mode);
+    write_rotation_mode(db.connection(), session_id, mode)
+}
+
+/// Longest search query kept as a session's last search
+const MAX_LAST_SEARCH_LENGTH: usize = 500;
+
+/// A session's last search query, if any
+fn read_last_search(conn: &Connection, session_id: i64) -> Result<Option<String>, CommandError> {
+    Ok(conn
+        .query_row(
+            "SELECT last_search_query FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get::<_, Option<String>>(0),
+        )
+        .optional()?
+        .flatten())
+}
+
+/// Store a session's last search query; an empty query clears it
+fn write_last_search(conn: &Connection, session_id: i64, query: &str) -> Result<(), CommandError> {
+    let query = query.trim();
+    if query.chars().count() > MAX_LAST_SEARCH_LENGTH {
+        return Err(CommandError::Validation(format!(
+            "Search query is too long (max {} characters)",
+            MAX_LAST_SEARCH_LENGTH
+        )));
+    }
+
+    let query = if query.is_empty() { None } else { Some(query) };
+    conn.execute(
+        "UPDATE sessions SET last_search_query = ?1 WHERE id = ?2",
+        rusqlite::params![query, session_id],
+    )?;
+    Ok(())
+}
+
+/// The active session's last search query, so the search box can be restored after
+/// a restart. None if there is no active session or nothing was searched yet.
+#[tauri::command]
+pub fn get_last_search(state: State<'_, AppState>) -> Result<Option<String>, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    match active_session_id(db.connection()) {
+        Ok(session_id) => read_last_search(db.connection(), session_id),
+        Err(CommandError::NoActiveSession) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Remember the active session's last search query (cleared when the session ends)
+#[tauri::command]
+pub fn set_last_search(state: State<'_, AppState>, query: String) -> Result<(), CommandError> {
+    debug!("Setting last search: {}", query);
+    let db = state.db.lock().map_lock_err()?;
+    let session_id = active_session_id(db.connection())?;
+    write_last_search(db.connection(), session_id, &query)
+}
+
+/// Remove non-persistent singers and queue singer assignments left behind by deleted sessions
+fn cleanup_orphans_after_session_delete(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // Clean up non-persistent singers that are now orphaned
+    conn.execute(
+        "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
+        [],
+    )?;
+
+    cleanup_orphaned_queue_singers(conn)
+}
+
+/// Remove queue singer assignments whose queue item no longer exists
+fn cleanup_orphaned_queue_singers(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "DELETE FROM queue_singers WHERE queue_item_id NOT IN (SELECT id FROM queue_items)",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Move a session to the trash. Returns false if it doesn't exist or is already deleted.
+fn soft_delete_session(conn: &Connection, session_id: i64) -> Result<bool, rusqlite::Error> {
+    let updated = conn.execute(
+        "UPDATE sessions SET deleted_at = CURRENT_TIMESTAMP WHERE id = ?1 AND deleted_at IS NULL",
+        [session_id],
+    )?;
+    Ok(updated > 0)
+}
+
+/// Restore a session from the trash. Returns false if it isn't in the trash.
+fn restore_deleted_session(conn: &Connection, session_id: i64) -> Result<bool, rusqlite::Error> {
+    let updated = conn.execute(
+        "UPDATE sessions SET deleted_at = NULL WHERE id = ?1 AND deleted_at IS NOT NULL",
+        [session_id],
+    )?;
+    Ok(updated > 0)
+}
+
+/// Permanently delete trashed sessions deleted at least `older_than_days` ago.
+/// Returns the number of sessions purged.
+fn purge_sessions_deleted_before(
+    conn: &Connection,
+    older_than_days: u32,
+) -> Result<u32, rusqlite::Error> {
+    // Cascade handles queue_items and session_singers
+    let purged = conn.execute(
+        "DELETE FROM sessions
+         WHERE deleted_at IS NOT NULL
+         AND deleted_at <= datetime('now', '-' || ?1 || ' days')",
+        [older_than_days],
+    )?;
+
+    if purged > 0 {
+        cleanup_orphans_after_session_delete(conn)?;
+    }
+
+    Ok(purged as u32)
+}
+
+/// Delete played history items older than `older_than_days` from inactive sessions.
+/// Pending queue items and the active session are never touched. Returns rows deleted.
+fn purge_history_played_before(
+    conn: &Connection,
+    older_than_days: u32,
+) -> Result<u64, rusqlite::Error> {
+    let deleted = conn.execute(
+        "DELETE FROM queue_items
+         WHERE item_type = 'history'
+         AND played_at IS NOT NULL
+         AND datetime(played_at) < datetime('now', '-' || ?1 || ' days')
+         AND session_id IN (SELECT id FROM sessions WHERE is_active = 0)",
+        [older_than_days],
+    )?;
+
+    if deleted > 0 {
+        cleanup_orphaned_queue_singers(conn)?;
+    }
+
+    Ok(deleted as u64)
+}
+
+/// Delete a session.
+///
+/// By default the session is moved to the trash and can be brought back with
+/// `restore_session`. Pass `force` to delete it (and its queue/history) immediately.
+#[tauri::command]
+pub fn delete_session(
+    state: State<'_, AppState>,
+    session_id: i64,
+    force: Option<bool>,
+) -> Result<(), CommandError> {
+    let force = force.unwrap_or(false);
+    info!("Deleting session: {} (force: {})", session_id, force);
+    let db = state.db.lock().map_lock_err()?;
+    ensure_not_party_mode(db.connection(), "Deleting sessions")?;
+
+    // Don't allow deleting the active session
+    let is_active: bool = db
+        .connection()
+        .query_row(
+            "SELECT is_active FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get::<_, i32>(0).map(|v| v != 0),
+        )
+        .unwrap_or(false);
+
+    if is_active {
+        return Err(CommandError::Validation(
+            "Cannot delete the active session".to_string(),
+        ));
+    }
+
+    if !force {
+        if !soft_delete_session(db.connection(), session_id)? {
+            return Err(CommandError::NotFound {
+                resource: "Session",
+                id: session_id.to_string(),
+            });
+        }
+        info!("Session {} moved to trash", session_id);
+        return Ok(());
+    }
+
+    // Delete session (cascade will handle queue_items and session_singers)
+    db.connection()
+        .execute("DELETE FROM sessions WHERE id = ?1", [session_id])?;
+
+    cleanup_orphans_after_session_delete(db.connection())?;
+
+    info!("Session {} deleted", session_id);
+    Ok(())
+}
+
+/// Restore a session from the trash
+#[tauri::command]
+pub fn restore_session(state: State<'_, AppState>, session_id: i64) -> Result<(), CommandError> {
+    info!("Restoring session: {}", session_id);
+    let db = state.db.lock().map_lock_err()?;
+
+    if !restore_deleted_session(db.connection(), session_id)? {
+        return Err(CommandError::NotFound {
+            resource: "Deleted session",
+            id: session_id.to_string(),
+        });
+    }
+
+    info!("Session {} restored", session_id);
+    Ok(())
+}
+
+/// Permanently delete sessions that have been in the trash for at least `older_than_days`.
+/// Returns the number of sessions purged.
+#[tauri::command]
+pub fn purge_deleted_sessions(
+    state: State<'_, AppState>,
+    older_than_days: u32,
+) -> Result<u32, CommandError> {
+    info!("Purging sessions deleted more than {} days ago", older_than_days);
+    let db = state.db.lock().map_lock_err()?;
+
+    let purged = purge_sessions_deleted_before(db.connection(), older_than_days)?;
+
+    info!("Purged {} deleted sessions", purged);
+    Ok(purged)
+}
+
+/// Delete played history older than `days` across all inactive sessions (maintenance for
+/// long-running installs). Returns the number of history items deleted.
+#[tauri::command]
+pub fn purge_old_history(state: State<'_, AppState>, days: u32) -> Result<u64, CommandError> {
+    info!("Purging history played more than {} days ago", days);
+    let mut db = state.db.lock().map_lock_err()?;
+
+    let tx = db.connection_mut().transaction()?;
+    let deleted = purge_history_played_before(&tx, days)?;
+    tx.commit()?;
+
+    info!("Purged {} old history items", deleted);
+    Ok(deleted)
+}
+
+/// Reactivate the most recently used session that still has pending queue items,
+/// ending the currently active session. Returns None (changing nothing) when the
+/// active session already has pending items or no other session has any.
+fn reactivate_recoverable_session(conn: &Connection) -> Result<Option<Session>, CommandError> {
+    let active_has_queue: bool = conn.query_row(
+        "SELECT EXISTS(
+            SELECT 1 FROM queue_items qi
+            INNER JOIN sessions s ON s.id = qi.session_id
+            WHERE s.is_active = 1 AND qi.item_type = 'queue'
+        )",
+        [],
+        |row| row.get(0),
+    )?;
+    if active_has_queue {
+        return Ok(None);
+    }
+
+    let session_id: Option<i64> = conn
+        .query_row(
+            "SELECT s.id FROM sessions s
+             WHERE s.is_active = 0 AND s.deleted_at IS NULL
+               AND EXISTS (SELECT 1 FROM queue_items qi WHERE qi.session_id = s.id AND qi.item_type = 'queue')
+             ORDER BY COALESCE(s.ended_at, s.started_at) DESC, s.id DESC
+             LIMIT 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()?;
+    let Some(session_id) = session_id else {
+        return Ok(None);
+    };
+
+    conn.execute(
+        "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP, last_search_query = NULL WHERE is_active = 1",
+        [],
+    )?;
+    conn.execute(
+        "UPDATE sessions SET is_active = 1, ended_at = NULL WHERE id = ?1",
+        [session_id],
+    )?;
+    get_session_by_id(conn, session_id).map(Some)
+}
+
+/// Resume the last session that still has songs queued, e.g. after a crash left it
+/// inactive. Any other active session is ended. Returns the resumed session, or None
+/// if there was nothing to recover.
+#[tauri::command]
+pub fn recover_last_session(state: State<'_, AppState>) -> Result<Option<Session>, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let conn = db.connection();
+    ensure_not_party_mode(conn, "Recovering sessions")?;
+
+    conn.execute("BEGIN IMMEDIATE", [])?;
+    match reactivate_recoverable_session(conn) {
+        Ok(session) => {
+            conn.execute("COMMIT", [])?;
+            match &session {
+                Some(session) => info!("Recovered session {}", session.id),
+                None => debug!("No session to recover"),
+            }
+            Ok(session)
+        }
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    }
+}
+
+#[tauri::command]
+pub fn load_session(
+    state: State<'_, AppState>,
+    session_id: i64,
+) -> Result<Session, CommandError> {
+    info!("Loading session: {}", session_id);
+    let db = state.db.lock().map_lock_err()?;
+    let conn = db.connection();
+    ensure_not_party_mode(conn, "Loading sessions")?;
+
+    // Use transaction for atomicity
+    conn.execute("BEGIN IMMEDIATE", [])?;
+
+    let result = (|| -> Result<Session, CommandError> {
+        // Trashed sessions must be restored before they can be loaded
+        let is_deleted: bool = conn.query_row(
+            "SELECT deleted_at IS NOT NULL FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| row.get(0),
+        )?;
+        if is_deleted {
+            return Err(CommandError::Validation(
+                "Cannot load a deleted session; restore it first".to_string(),
+            ));
+        }
+
+        // End any active session first
+        conn.execute(
+            "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP, last_search_query = NULL WHERE is_active = 1",
+            [],
+        )?;
+
+        // Activate the selected session
+        conn.execute(
+            "UPDATE sessions SET is_active = 1, ended_at = NULL WHERE id = ?1",
+            [session_id],
+        )?;
+
+        let session = conn.query_row(
+            "SELECT id, name, started_at, ended_at, is_active, hosted_session_id, hosted_by_user_id, hosted_session_status FROM sessions WHERE id = ?1",
+            [session_id],
+            |row| {
+                Ok(Session {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    started_at: row.get(2)?,
+                    ended_at: row.get(3)?,
+                    is_active: row.get::<_, i32>(4)? != 0,
+                    hosted_session_id: row.get(5)?,
+                    hosted_by_user_id: row.get(6)?,
+                    hosted_session_status: row.get::<_, Option<String>>(7)?
+                        .and_then(|s| HostedSessionStatus::from_str(&s)),
+                })
+            },
+        )?;
+
+        Ok(session)
+    })();
+
+    match result {
+        Ok(session) => {
+            conn.execute("COMMIT", [])?;
+            info!("Session {} loaded and activated", session_id);
+            Ok(session)
+        }
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    }
+}
+
+// ============ Hosted Session Commands ============
+
+#[tauri::command]
+pub fn session_set_hosted(
+    state: State<'_, AppState>,
+    session_id: i64,
+    hosted_session_id: String,
+    hosted_by_user_id: String,
+    status: String,
+) -> Result<(), CommandError> {
+    info!(
+        "Setting hosted session for session {}: hosted_id={}, user_id={}, status={}",
+        session_id, hosted_session_id, hosted_by_user_id, status
+    );
+
+    // Parse status string into HostedSessionStatus enum (TYPE-003)
+    let status_enum = HostedSessionStatus::from_str(&status).ok_or_else(|| {
+        CommandError::Validation(format!(
+            "Invalid hosted session status: '{}'. Must be one of: active, paused, ended",
+            status
+        ))
+    })?;
+
+    let db = state.db.lock().map_lock_err()?;
+
+    // First verify session exists (needed to distinguish "not found" from "ownership conflict")
+    let session_exists: bool = db
+        .connection()
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?1)",
+            [session_id],
+            |row| row.get(0),
+        )?;
+
+    if !session_exists {
+        return Err(CommandError::Validation(format!(
+            "Session {} does not exist",
+            session_id
+        )));
+    }
+
+    // Use conditional UPDATE with WHERE clause for atomic ownership check (CONC-003).
+    // This prevents race conditions by combining the ownership check and update in a single
+    // SQL statement, avoiding the TOCTOU (time-of-check-time-of-use) vulnerability where:
+    // - The session could be deleted between the EXISTS check above and this UPDATE
+    // - Another user could claim ownership between a SELECT and separate UPDATE
+    // - Status could change between reading current state and writing new state
+    // By using a conditional UPDATE, the database guarantees atomicity. The update only succeeds if:
+    // - No existing hosted_by_user_id (NULL) - session never hosted before
+    // - Same user is updating (hosted_by_user_id matches)
+    // - Previous status is 'ended' (session was released, can be taken over)
+    // Note: We intentionally don't allow override when hosted_session_status IS NULL but
+    // hosted_by_user_id is set to a different user - that would be a data integrity issue
+    // that should be investigated, not silently overwritten.
+    let affected_rows = db.connection().execute(
+        "UPDATE sessions
+         SET hosted_session_id = ?1, hosted_by_user_id = ?2, hosted_session_status = ?3
+         WHERE id = ?4
+         AND (
+             hosted_by_user_id IS NULL
+             OR hosted_by_user_id = ?2
+             OR hosted_session_status = 'ended'
+         )",
+        rusqlite::params![hosted_session_id, hosted_by_user_id, status_enum.as_str(), session_id],
+    )?;
+
+    // If no rows affected, it means ownership conflict (session exists but conditions not met)
+    if affected_rows == 0 {
+        info!(
+            "Ownership conflict: session {} is being hosted by another user",
+            session_id
+        );
+        return Err(CommandError::OwnershipConflict);
+    }
+
+    info!(
+        "Hosted session set for session {}: hosted_id={}, status={}",
+        session_id, hosted_session_id, status
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn session_update_hosted_status(
+    state: State<'_, AppState>,
+    session_id: i64,
+    status: String,
+) -> Result<(), CommandError> {
+    info!(
+        "Updating hosted session status for session {}: status={}",
+        session_id, status
+    );
+
+    // Parse status string into HostedSessionStatus enum (TYPE-004)
+    let status_enum = HostedSessionStatus::from_str(&status).ok_or_else(|| {
+        CommandError::Validation(format!(
+            "Invalid hosted session status: '{}'. Must be one of: active, paused, ended",
+            status
+        ))
+    })?;
+
+    let db = state.db.lock().map_lock_err()?;
+
+    // Verify session exists
+    let session_exists: bool = db
+        .connection()
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?1)",
+            [session_id],
+            |row| row.get(0),
+        )?;
+
+    if !session_exists {
+        return Err(CommandError::Validation(format!(
+            "Session {} does not exist",
+            session_id
+        )));
+    }
+
+    // Update only the status field
+    db.connection().execute(
+        "UPDATE sessions SET hosted_session_status = ?1 WHERE id = ?2",
+        rusqlite::params![status_enum.as_str(), session_id],
+    )?;
+
+    info!(
+        "Hosted session status updated for session {}: status={}",
+        session_id, status
+    );
+    Ok(())
+}
+
+// ============ Active Singer Commands ============
+
+#[tauri::command]
+pub fn session_set_active_singer(
+    state: State<'_, AppState>,
+    session_id: i64,
+    singer_id: Option<i64>,
+) -> Result<(), CommandError> {
+    debug!(
+        "Setting active singer for session {}: {:?}",
+        session_id, singer_id
+    );
+    let db = state.db.lock().map_lock_err()?;
+
+    // If singer_id is provided, verify singer exists and is in the session
+    if let Some(sid) = singer_id {
+        let exists: bool = db
+            .connection()
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM session_singers WHERE session_id = ?1 AND singer_id = ?2)",
+                rusqlite::params![session_id, sid],
+                |row| row.get(0),
+            )?;
+
+        if !exists {
+            return Err(CommandError::Validation(
+                "Singer is not part of this session".to_string(),
+            ));
+        }
+    }
+
+    db.connection().execute(
+        "UPDATE sessions SET active_singer_id = ?1 WHERE id = ?2",
+        rusqlite::params![singer_id, session_id],
+    )?;
+
+    info!(
+        "Active singer set for session {}: {:?}",
+        session_id, singer_id
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn session_get_active_singer(
+    state: State<'_, AppState>,
+    session_id: i64,
+) -> Result<Option<Singer>, CommandError> {
+    debug!("Getting active singer for session {}", session_id);
+    let db = state.db.lock().map_lock_err()?;
+
+    let result = db.connection().query_row(
+        "SELECT s.id, s.name, s.unique_name, s.color, s.is_persistent, s.online_id
+         FROM singers s
+         INNER JOIN sessions sess ON sess.active_singer_id = s.id
+         WHERE sess.id = ?1",
+        [session_id],
+        |row| {
+            Ok(Singer {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                unique_name: row.get(2)?,
+                color: row.get(3)?,
+                is_persistent: row.get::<_, i32>(4)? != 0,
+                online_id: row.get(5)?,
+            })
+        },
+    );
+
+    match result {
+        Ok(singer) => Ok(Some(singer)),
+        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(None),
+        Err(e) => Err(CommandError::Database(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rusqlite::Connection;
+
+    /// Create an in-memory database with the full schema from the migrations
+    fn setup_migrated_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        crate::db::run_migrations(&conn).unwrap();
+        conn
+    }
+
+    /// Create an in-memory database with the required schema for testing
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+
+        // Create minimal schema for session/singer tests
+        conn.execute_batch(
+            r#"
+            CREATE TABLE singers (
+                id INTEGER PRIMARY KEY,
+                name TEXT NOT NULL,
+                unique_name TEXT,
+                color TEXT NOT NULL,
+                is_persistent INTEGER DEFAULT 0,
+                created_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                online_id TEXT
+            );
+
+            CREATE TABLE sessions (
+                id INTEGER PRIMARY KEY,
+                name TEXT,
+                started_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                ended_at TIMESTAMP,
+                is_active INTEGER DEFAULT 1,
+                history_index INTEGER DEFAULT -1,
+                active_singer_id INTEGER REFERENCES singers(id) ON DELETE SET NULL,
+                hosted_session_id TEXT,
+                hosted_by_user_id TEXT,
+                hosted_session_status TEXT,
+                deleted_at TIMESTAMP,
+                session_settings TEXT,
+                default_playback_rate REAL,
+                default_transpose INTEGER,
+                auto_shuffle INTEGER NOT NULL DEFAULT 0,
+                rotation_mode TEXT NOT NULL DEFAULT 'fair',
+                last_search_query TEXT
+            );
+
+            CREATE TABLE session_singers (
+                session_id INTEGER NOT NULL,
+                singer_id INTEGER NOT NULL,
+                joined_at TIMESTAMP DEFAULT CURRENT_TIMESTAMP,
+                PRIMARY KEY (session_id, singer_id),
+                FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE,
+                FOREIGN KEY (singer_id) REFERENCES singers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE queue_singers (
+                id INTEGER PRIMARY KEY,
+                queue_item_id TEXT NOT NULL,
+                singer_id INTEGER NOT NULL,
+                position INTEGER DEFAULT 0,
+                FOREIGN KEY (singer_id) REFERENCES singers(id) ON DELETE CASCADE
+            );
+
+            CREATE TABLE queue_items (
+                id TEXT PRIMARY KEY,
+                session_id INTEGER NOT NULL,
+                item_type TEXT NOT NULL,
+                video_id TEXT NOT NULL,
+                title TEXT NOT NULL,
+                artist TEXT,
+                duration INTEGER,
+                thumbnail_url TEXT,
+                source TEXT NOT NULL,
+                youtube_id TEXT,
+                file_path TEXT,
+                position INTEGER NOT NULL,
+                added_at TEXT NOT NULL,
+                played_at TEXT,
+                created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+                FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE
+            );
+
+            CREATE INDEX idx_queue_singers_queue_item ON queue_singers(queue_item_id);
+            CREATE INDEX idx_session_singers_session ON session_singers(session_id);
+            "#,
+        )
+        .unwrap();
+
+        conn
+    }
+
+    mod singer_crud {
+        use super::*;
+        use crate::commands::session::MAX_NAME_LENGTH;
+
+        #[test]
+        fn test_create_singer_basic() {
+            let conn = setup_test_db();
+
+            conn.execute(
+                "INSERT INTO singers (name, color, is_persistent) VALUES (?1, ?2, ?3)",
+                rusqlite::params!["Alice", "#ff0000", false],
+            )
+            .unwrap();
+
+            let singer: (i64, String, String, i32) = conn
+                .query_row(
+                    "SELECT id, name, color, is_persistent FROM singers WHERE name = 'Alice'",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+                )
+                .unwrap();
+
+            assert_eq!(singer.1, "Alice");
+            assert_eq!(singer.2, "#ff0000");
+            assert_eq!(singer.3, 0);
+        }
+
+        #[test]
+        fn test_create_singer_with_unique_name() {
+            let conn = setup_test_db();
+
+            conn.execute(
+                "INSERT INTO singers (name, unique_name, color, is_persistent) VALUES (?1, ?2, ?3, ?4)",
+                rusqlite::params!["Bob", "bob123", "#00ff00", true],
+            )
+            .unwrap();
+
+            let singer: (String, Option<String>, i32) = conn
+                .query_row(
+                    "SELECT name, unique_name, is_persistent FROM singers WHERE name = 'Bob'",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .unwrap();
+
+            assert_eq!(singer.0, "Bob");
+            assert_eq!(singer.1, Some("bob123".to_string()));
+            assert_eq!(singer.2, 1);
+        }
+
+        #[test]
+        fn test_name_validation_empty() {
+            let name = "   ".trim();
+            assert!(name.is_empty(), "Trimmed whitespace-only name should be empty");
+        }
+
+        #[test]
+        fn test_name_validation_max_length() {
+            let long_name = "a".repeat(MAX_NAME_LENGTH + 1);
+            assert!(
+                long_name.len() > MAX_NAME_LENGTH,
+                "Name exceeding {} characters should be rejected",
+                MAX_NAME_LENGTH
+            );
+        }
+
+        #[test]
+        fn test_name_validation_at_limit() {
+            let max_name = "a".repeat(MAX_NAME_LENGTH);
+            assert_eq!(max_name.len(), MAX_NAME_LENGTH);
+            assert!(
+                max_name.len() <= MAX_NAME_LENGTH,
+                "Name at exactly {} characters should be valid",
+                MAX_NAME_LENGTH
+            );
+        }
+
+        #[test]
+        fn test_delete_singer() {
+            let conn = setup_test_db();
+
+            conn.execute(
+                "INSERT INTO singers (name, color) VALUES ('ToDelete', '#000000')",
+                [],
+            )
+            .unwrap();
+
+            let count: i32 = conn
+                .query_row("SELECT COUNT(*) FROM singers", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, 1);
+
+            conn.execute("DELETE FROM singers WHERE name = 'ToDelete'", [])
+                .unwrap();
+
+            let count: i32 = conn
+                .query_row("SELECT COUNT(*) FROM singers", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, 0);
+        }
+
+        #[test]
+        fn test_get_singers_ordered_by_name() {
+            let conn = setup_test_db();
+
+            conn.execute("INSERT INTO singers (name, color) VALUES ('Zoe', '#111')", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (name, color) VALUES ('Alice', '#222')", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (name, color) VALUES ('Mike', '#333')", [])
+                .unwrap();
+
+            let mut stmt = conn
+                .prepare("SELECT name FROM singers ORDER BY name")
+                .unwrap();
+            let names: Vec<String> = stmt
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+
+            assert_eq!(names, vec!["Alice", "Mike", "Zoe"]);
+        }
+
+        #[test]
+        fn test_get_persistent_singers_only() {
+            let conn = setup_test_db();
+
+            conn.execute(
+                "INSERT INTO singers (name, color, is_persistent) VALUES ('Temp', '#111', 0)",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO singers (name, color, is_persistent) VALUES ('Perm', '#222', 1)",
+                [],
+            )
+            .unwrap();
+
+            let count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM singers WHERE is_persistent = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(count, 1);
+        }
+    }
+
+    mod session_lifecycle {
+        use super::*;
+        use crate::commands::session::end_active_session;
+
+        #[test]
+        fn test_start_session_basic() {
+            let conn = setup_test_db();
+
+            conn.execute(
+                "INSERT INTO sessions (name, is_active) VALUES ('Test Session', 1)",
+                [],
+            )
+            .unwrap();
+
+            let session: (i64, Option<String>, i32) = conn
+                .query_row(
+                    "SELECT id, name, is_active FROM sessions WHERE is_active = 1",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .unwrap();
+
+            assert_eq!(session.1, Some("Test Session".to_string()));
+            assert_eq!(session.2, 1);
+        }
+
+        #[test]
+        fn test_start_session_ends_previous() {
+            let conn = setup_test_db();
+
+            // Create first session
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('First', 1)", [])
+                .unwrap();
+
+            // Simulate starting new session (end old first)
+            conn.execute(
+                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE is_active = 1",
+                [],
+            )
+            .unwrap();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Second', 1)", [])
+                .unwrap();
+
+            let active_count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sessions WHERE is_active = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(active_count, 1, "Only one session should be active at a time");
+
+            let active_name: String = conn
+                .query_row(
+                    "SELECT name FROM sessions WHERE is_active = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(active_name, "Second");
+        }
+
+        #[test]
+        fn test_end_session_marks_inactive() {
+            let conn = setup_test_db();
+
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('ToEnd', 1)", [])
+                .unwrap();
+            let session_id: i64 = conn.last_insert_rowid();
+
+            // Add a singer to make the session non-empty
+            conn.execute(
+                "INSERT INTO singers (name, color) VALUES ('Singer', '#fff')",
+                [],
+            )
+            .unwrap();
+            let singer_id: i64 = conn.last_insert_rowid();
+            conn.execute(
+                "INSERT INTO session_singers (session_id, singer_id) VALUES (?1, ?2)",
+                [session_id, singer_id],
+            )
+            .unwrap();
+
+            // End session
+            conn.execute(
+                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE id = ?1",
+                [session_id],
+            )
+            .unwrap();
+
+            let is_active: i32 = conn
+                .query_row(
+                    "SELECT is_active FROM sessions WHERE id = ?1",
+                    [session_id],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(is_active, 0);
+        }
+
+        #[test]
+        fn test_end_empty_session_deletes() {
+            let conn = setup_test_db();
+
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Empty', 1)", [])
+                .unwrap();
+            let session_id: i64 = conn.last_insert_rowid();
+
+            // Check if session has content
+            let has_content: bool = conn
+                .query_row(
+                    "SELECT EXISTS(
+                        SELECT 1 FROM queue_items WHERE session_id = ?1
+                        UNION
+                        SELECT 1 FROM session_singers WHERE session_id = ?1
+                    )",
+                    [session_id],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            if !has_content {
+                conn.execute("DELETE FROM sessions WHERE id = ?1", [session_id])
+                    .unwrap();
+            }
+
+            let count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sessions WHERE id = ?1",
+                    [session_id],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(count, 0, "Empty session should be deleted on end");
+        }
+
+        fn session_state(conn: &Connection, session_id: i64) -> Option<(i32, bool)> {
+            conn.query_row(
+                "SELECT is_active, ended_at IS NOT NULL FROM sessions WHERE id = ?1",
+                [session_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok()
+        }
+
+        #[test]
+        fn test_end_active_session_deletes_empty_session_by_default() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Reserved', 1)", [])
+                .unwrap();
+
+            end_active_session(&conn, false).unwrap();
+
+            assert_eq!(session_state(&conn, 1), None);
+        }
+
+        #[test]
+        fn test_end_active_session_archives_empty_session_when_preserved() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Reserved', 1)", [])
+                .unwrap();
+
+            end_active_session(&conn, true).unwrap();
+
+            assert_eq!(session_state(&conn, 1), Some((0, true)));
+        }
+
+        #[test]
+        fn test_end_active_session_archives_session_with_content() {
+            let conn = setup_test_db();
+            conn.execute_batch(
+                "INSERT INTO sessions (id, name, is_active) VALUES (1, 'Party', 1);
+                 INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#fff');
+                 INSERT INTO session_singers (session_id, singer_id) VALUES (1, 1);",
+            )
+            .unwrap();
+
+            end_active_session(&conn, false).unwrap();
+
+            assert_eq!(session_state(&conn, 1), Some((0, true)));
+        }
+
+        #[test]
+        fn test_session_cleanup_removes_orphaned_singers() {
+            let conn = setup_test_db();
+
+            // Create non-persistent singer without session association
+            conn.execute(
+                "INSERT INTO singers (name, color, is_persistent) VALUES ('Orphan', '#000', 0)",
+                [],
+            )
+            .unwrap();
+
+            // Cleanup orphaned non-persistent singers
+            conn.execute(
+                "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
+                [],
+            )
+            .unwrap();
+
+            let count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM singers WHERE name = 'Orphan'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(count, 0, "Orphaned non-persistent singers should be cleaned up");
+        }
+
+        #[test]
+        fn test_persistent_singer_survives_cleanup() {
+            let conn = setup_test_db();
+
+            // Create persistent singer without session association
+            conn.execute(
+                "INSERT INTO singers (name, color, is_persistent) VALUES ('Persistent', '#000', 1)",
+                [],
+            )
+            .unwrap();
+
+            // Cleanup orphaned non-persistent singers
+            conn.execute(
+                "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
+                [],
+            )
+            .unwrap();
+
+            let count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM singers WHERE name = 'Persistent'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(count, 1, "Persistent singers should survive cleanup");
+        }
+
+        #[test]
+        fn test_add_singer_to_session() {
+            let conn = setup_test_db();
+
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Test', 1)", [])
+                .unwrap();
+            let session_id: i64 = conn.last_insert_rowid();
+
+            conn.execute(
+                "INSERT INTO singers (name, color) VALUES ('Alice', '#fff')",
+                [],
+            )
+            .unwrap();
+            let singer_id: i64 = conn.last_insert_rowid();
+
+            conn.execute(
+                "INSERT OR IGNORE INTO session_singers (session_id, singer_id) VALUES (?1, ?2)",
+                [session_id, singer_id],
+            )
+            .unwrap();
+
+            let count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM session_singers WHERE session_id = ?1 AND singer_id = ?2",
+                    [session_id, singer_id],
+                    |row| row.get(0),
+                )
+                .unwrap();
+
+            assert_eq!(count, 1);
+        }
+    }
+
+    mod soft_delete {
+        use super::*;
+        use crate::commands::session::{
+            purge_sessions_deleted_before, restore_deleted_session, soft_delete_session,
+        };
+
+        fn add_session(conn: &Connection, name: &str) -> i64 {
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES (?1, 0)", [name])
+                .unwrap();
+            conn.last_insert_rowid()
+        }
+
+        fn visible_session_names(conn: &Connection) -> Vec<String> {
+            let mut stmt = conn
+                .prepare("SELECT name FROM sessions WHERE deleted_at IS NULL ORDER BY id")
+                .unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<Vec<String>, _>>()
+                .unwrap()
+        }
+
+        #[test]
+        fn test_soft_delete_hides_session_but_keeps_content() {
+            let conn = setup_test_db();
+            add_session(&conn, "Keep");
+            let gone = add_session(&conn, "Gone");
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES ('q1', ?1, 'history', 'v1', 'Song', 'youtube', 0, '2024-01-01T00:00:00Z')",
+                [gone],
+            )
+            .unwrap();
+
+            assert!(soft_delete_session(&conn, gone).unwrap());
+            assert_eq!(visible_session_names(&conn), vec!["Keep"]);
+
+            // Already in trash
+            assert!(!soft_delete_session(&conn, gone).unwrap());
+
+            let items: i32 = conn
+                .query_row("SELECT COUNT(*) FROM queue_items WHERE session_id = ?1", [gone], |row| {
+                    row.get(0)
+                })
+                .unwrap();
+            assert_eq!(items, 1, "Soft delete should not cascade");
+        }
+
+        #[test]
+        fn test_restore_session() {
+            let conn = setup_test_db();
+            let id = add_session(&conn, "Oops");
+
+            // Not in trash yet
+            assert!(!restore_deleted_session(&conn, id).unwrap());
+
+            soft_delete_session(&conn, id).unwrap();
+            assert!(restore_deleted_session(&conn, id).unwrap());
+            assert_eq!(visible_session_names(&conn), vec!["Oops"]);
+        }
+
+        #[test]
+        fn test_purge_only_removes_old_trashed_sessions() {
+            let conn = setup_test_db();
+            let old = add_session(&conn, "Old");
+            let recent = add_session(&conn, "Recent");
+            add_session(&conn, "Live");
+
+            conn.execute(
+                "UPDATE sessions SET deleted_at = datetime('now', '-40 days') WHERE id = ?1",
+                [old],
+            )
+            .unwrap();
+            soft_delete_session(&conn, recent).unwrap();
+
+            assert_eq!(purge_sessions_deleted_before(&conn, 30).unwrap(), 1);
+
+            let remaining: Vec<String> = {
+                let mut stmt = conn.prepare("SELECT name FROM sessions ORDER BY id").unwrap();
+                stmt.query_map([], |row| row.get(0))
+                    .unwrap()
+                    .collect::<Result<Vec<String>, _>>()
+                    .unwrap()
+            };
+            assert_eq!(remaining, vec!["Recent", "Live"]);
+
+            // Zero days purges everything in the trash
+            assert_eq!(purge_sessions_deleted_before(&conn, 0).unwrap(), 1);
+            assert_eq!(visible_session_names(&conn), vec!["Live"]);
+        }
+    }
+
+    mod purge_old_history {
+        use super::*;
+        use crate::commands::session::purge_history_played_before;
+
+        fn add_item(conn: &Connection, id: &str, session_id: i64, item_type: &str, played_at: Option<&str>) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at, played_at)
+                 VALUES (?1, ?2, ?3, ?1, 'Song', 'youtube', 0, '2024-01-01T00:00:00Z', ?4)",
+                rusqlite::params![id, session_id, item_type, played_at],
+            )
+            .unwrap();
+        }
+
+        fn remaining_items(conn: &Connection) -> Vec<String> {
+            let mut stmt = conn.prepare("SELECT id FROM queue_items ORDER BY id").unwrap();
+            stmt.query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<Vec<String>, _>>()
+                .unwrap()
+        }
+
+        #[test]
+        fn test_purges_only_old_history_of_inactive_sessions() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Past', 0)", [])
+                .unwrap();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Now', 1)", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#fff')", [])
+                .unwrap();
+
+            add_item(&conn, "old-history", 1, "history", Some("2020-01-01T20:00:00.000Z"));
+            add_item(&conn, "recent-history", 1, "history", Some(chrono::Utc::now().to_rfc3339().as_str()));
+            add_item(&conn, "unplayed-history", 1, "history", None);
+            add_item(&conn, "old-pending", 1, "queue", Some("2020-01-01T20:00:00.000Z"));
+            add_item(&conn, "old-active-history", 2, "history", Some("2020-01-01 20:00:00"));
+            conn.execute(
+                "INSERT INTO queue_singers (queue_item_id, singer_id) VALUES ('old-history', 1), ('recent-history', 1)",
+                [],
+            )
+            .unwrap();
+
+            assert_eq!(purge_history_played_before(&conn, 30).unwrap(), 1);
+            assert_eq!(
+                remaining_items(&conn),
+                vec!["old-active-history", "old-pending", "recent-history", "unplayed-history"]
+            );
+
+            // Orphaned singer assignment is removed, the other one is kept
+            let assignments: Vec<String> = conn
+                .prepare("SELECT queue_item_id FROM queue_singers")
+                .unwrap()
+                .query_map([], |row| row.get(0))
+                .unwrap()
+                .collect::<Result<Vec<String>, _>>()
+                .unwrap();
+            assert_eq!(assignments, vec!["recent-history"]);
+
+            // Nothing left to purge
+            assert_eq!(purge_history_played_before(&conn, 30).unwrap(), 0);
+        }
+    }
+
+    mod set_singer_persistent {
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::set_singer_persistence;
+        use rusqlite::Connection;
+
+        fn add_singer(conn: &Connection, name: &str, persistent: bool) -> i64 {
+            conn.execute(
+                "INSERT INTO singers (name, color, is_persistent) VALUES (?1, '#fff', ?2)",
+                rusqlite::params![name, persistent as i32],
+            )
+            .unwrap();
+            conn.last_insert_rowid()
+        }
+
+        fn add_favorite(conn: &Connection, singer_id: i64) {
+            conn.execute(
+                "INSERT INTO singer_favorites (singer_id, video_id, title, source) VALUES (?1, 'v1', 'Song', 'youtube')",
+                [singer_id],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_promote_guest_survives_cleanup() {
+            let conn = super::setup_migrated_db();
+            let guest = add_singer(&conn, "Guest", false);
+
+            let singer = set_singer_persistence(&conn, guest, true, false).unwrap();
+            assert!(singer.is_persistent);
+
+            // Same cleanup as end_session
+            conn.execute(
+                "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
+                [],
+            )
+            .unwrap();
+            let count: i32 = conn
+                .query_row("SELECT COUNT(*) FROM singers WHERE id = ?1", [guest], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, 1);
+        }
+
+        #[test]
+        fn test_demote_with_favorites_requires_force() {
+            let conn = super::setup_migrated_db();
+            let regular = add_singer(&conn, "Regular", true);
+            add_favorite(&conn, regular);
+
+            assert!(matches!(
+                set_singer_persistence(&conn, regular, false, false),
+                Err(CommandError::Validation(_))
+            ));
+
+            let singer = set_singer_persistence(&conn, regular, false, true).unwrap();
+            assert!(!singer.is_persistent);
+        }
+
+        #[test]
+        fn test_demote_without_favorites() {
+            let conn = super::setup_migrated_db();
+            let regular = add_singer(&conn, "Regular", true);
+
+            let singer = set_singer_persistence(&conn, regular, false, false).unwrap();
+            assert!(!singer.is_persistent);
+        }
+
+        #[test]
+        fn test_unknown_singer() {
+            let conn = super::setup_migrated_db();
+            assert!(matches!(
+                set_singer_persistence(&conn, 42, true, false),
+                Err(CommandError::NotFound { .. })
+            ));
+        }
+    }
+
+    mod check_singer_repeat {
+        use super::*;
+        use crate::commands::session::singer_sang_in_session;
+
+        fn add_item(conn: &Connection, id: &str, session_id: i64, item_type: &str, video_id: &str, singer_id: i64) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES (?1, ?2, ?3, ?4, 'Song', 'youtube', 0, '2024-01-01T00:00:00Z')",
+                rusqlite::params![id, session_id, item_type, video_id],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, 0)",
+                rusqlite::params![id, singer_id],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_detects_repeat_in_session_history() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Tonight', 1)", [])
+                .unwrap();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Last week', 0)", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#f00')", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (2, 'Bob', '#0f0')", [])
+                .unwrap();
 
-        conn
+            add_item(&conn, "played", 1, "history", "song-a", 1);
+            add_item(&conn, "queued", 1, "queue", "song-b", 1);
+            add_item(&conn, "old", 2, "history", "song-c", 1);
+
+            // Already sang tonight
+            assert!(singer_sang_in_session(&conn, 1, 1, "song-a").unwrap());
+            // Someone else sang it
+            assert!(!singer_sang_in_session(&conn, 1, 2, "song-a").unwrap());
+            // Only queued, not sung yet
+            assert!(!singer_sang_in_session(&conn, 1, 1, "song-b").unwrap());
+            // Sung in a different session
+            assert!(!singer_sang_in_session(&conn, 1, 1, "song-c").unwrap());
+        }
     }
 
-    mod singer_crud {
+    mod recover_last_session {
         use super::*;
-        use crate::commands::session::MAX_NAME_LENGTH;
+        use crate::commands::session::reactivate_recoverable_session;
+
+        fn add_pending(conn: &Connection, id: &str, session_id: i64) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES (?1, ?2, 'queue', ?1, 'Song', 'youtube', 0, '2024-01-01T00:00:00Z')",
+                rusqlite::params![id, session_id],
+            )
+            .unwrap();
+        }
+
+        fn active_session(conn: &Connection) -> Option<i64> {
+            conn.query_row("SELECT id FROM sessions WHERE is_active = 1", [], |row| row.get(0))
+                .ok()
+        }
 
         #[test]
-        fn test_create_singer_basic() {
+        fn test_recovers_latest_inactive_session_with_queue() {
             let conn = setup_test_db();
-
             conn.execute(
-                "INSERT INTO singers (name, color, is_persistent) VALUES (?1, ?2, ?3)",
-                rusqlite::params!["Alice", "#ff0000", false],
+                "INSERT INTO sessions (id, name, is_active, started_at, ended_at) VALUES
+                    (1, 'Older', 0, '2024-01-01 18:00:00', '2024-01-01 23:00:00'),
+                    (2, 'Crashed', 0, '2024-01-02 18:00:00', NULL),
+                    (3, 'Trashed', 0, '2024-01-03 18:00:00', '2024-01-03 19:00:00'),
+                    (4, 'Fresh', 1, '2024-01-04 18:00:00', NULL)",
+                [],
             )
             .unwrap();
-
-            let singer: (i64, String, String, i32) = conn
-                .query_row(
-                    "SELECT id, name, color, is_persistent FROM singers WHERE name = 'Alice'",
-                    [],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
-                )
+            conn.execute("UPDATE sessions SET deleted_at = CURRENT_TIMESTAMP WHERE id = 3", [])
                 .unwrap();
-
-            assert_eq!(singer.1, "Alice");
-            assert_eq!(singer.2, "#ff0000");
-            assert_eq!(singer.3, 0);
+            add_pending(&conn, "older", 1);
+            add_pending(&conn, "crashed", 2);
+            add_pending(&conn, "trashed", 3);
+
+            let session = reactivate_recoverable_session(&conn).unwrap().unwrap();
+            assert_eq!(session.id, 2);
+            assert!(session.is_active);
+            assert_eq!(active_session(&conn), Some(2));
+
+            // The recovered session now has the queue, so there's nothing more to do
+            assert!(reactivate_recoverable_session(&conn).unwrap().is_none());
+            assert_eq!(active_session(&conn), Some(2));
         }
 
         #[test]
-        fn test_create_singer_with_unique_name() {
+        fn test_nothing_to_recover() {
             let conn = setup_test_db();
+            assert!(reactivate_recoverable_session(&conn).unwrap().is_none());
+
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Empty', 0)", [])
+                .unwrap();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Current', 1)", [])
+                .unwrap();
+            assert!(reactivate_recoverable_session(&conn).unwrap().is_none());
+            // The active session is left alone
+            assert_eq!(active_session(&conn), Some(2));
+        }
+    }
+
+    mod singer_sessions {
+        use super::*;
+        use crate::commands::session::sessions_with_singer;
 
+        #[test]
+        fn test_lists_sessions_newest_first() {
+            let conn = setup_test_db();
             conn.execute(
-                "INSERT INTO singers (name, unique_name, color, is_persistent) VALUES (?1, ?2, ?3, ?4)",
-                rusqlite::params!["Bob", "bob123", "#00ff00", true],
+                "INSERT INTO sessions (id, name, is_active, started_at) VALUES
+                    (1, 'Birthday', 0, '2024-01-01 18:00:00'),
+                    (2, 'Office Party', 1, '2024-02-01 18:00:00'),
+                    (3, 'Without Alice', 0, '2024-03-01 18:00:00')",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO singers (id, name, color, is_persistent) VALUES
+                    (1, 'Alice', '#fff', 1), (2, 'Bob', '#000', 1)",
+                [],
+            )
+            .unwrap();
+            conn.execute(
+                "INSERT INTO session_singers (session_id, singer_id) VALUES (1, 1), (2, 1), (3, 2)",
+                [],
             )
             .unwrap();
 
-            let singer: (String, Option<String>, i32) = conn
-                .query_row(
-                    "SELECT name, unique_name, is_persistent FROM singers WHERE name = 'Bob'",
-                    [],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-                )
-                .unwrap();
+            let sessions = sessions_with_singer(&conn, 1).unwrap();
+            let names: Vec<_> = sessions.iter().map(|s| s.name.as_deref().unwrap()).collect();
+            assert_eq!(names, vec!["Office Party", "Birthday"]);
+            assert!(sessions[0].is_active);
 
-            assert_eq!(singer.0, "Bob");
-            assert_eq!(singer.1, Some("bob123".to_string()));
-            assert_eq!(singer.2, 1);
+            assert!(sessions_with_singer(&conn, 99).unwrap().is_empty());
         }
 
         #[test]
-        fn test_name_validation_empty() {
-            let name = "   ".trim();
-            assert!(name.is_empty(), "Trimmed whitespace-only name should be empty");
+        fn test_skips_trashed_sessions() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Trashed', 0)", [])
+                .unwrap();
+            conn.execute(
+                "INSERT INTO singers (id, name, color, is_persistent) VALUES (1, 'Alice', '#fff', 1)",
+                [],
+            )
+            .unwrap();
+            conn.execute("INSERT INTO session_singers (session_id, singer_id) VALUES (1, 1)", [])
+                .unwrap();
+            conn.execute("UPDATE sessions SET deleted_at = CURRENT_TIMESTAMP WHERE id = 1", [])
+                .unwrap();
+
+            assert!(sessions_with_singer(&conn, 1).unwrap().is_empty());
+        }
+    }
+
+    mod global_stats {
+        use super::*;
+        use crate::commands::session::{load_global_stats, SingerActivity};
+
+        fn add_played(conn: &Connection, id: &str, session_id: i64, video_id: &str, duration: i64, singers: &[i64]) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, duration, source, position, added_at, played_at)
+                 VALUES (?1, ?2, 'history', ?3, ?3 || ' title', ?4, 'youtube', 0, '2024-01-01T00:00:00Z', '2024-01-01 20:00:00')",
+                rusqlite::params![id, session_id, video_id, duration],
+            )
+            .unwrap();
+            for singer_id in singers {
+                conn.execute(
+                    "INSERT INTO queue_singers (queue_item_id, singer_id) VALUES (?1, ?2)",
+                    rusqlite::params![id, singer_id],
+                )
+                .unwrap();
+            }
         }
 
         #[test]
-        fn test_name_validation_max_length() {
-            let long_name = "a".repeat(MAX_NAME_LENGTH + 1);
-            assert!(
-                long_name.len() > MAX_NAME_LENGTH,
-                "Name exceeding {} characters should be rejected",
-                MAX_NAME_LENGTH
+        fn test_aggregates_across_sessions() {
+            let conn = setup_test_db();
+            conn.execute(
+                "INSERT INTO sessions (id, name, is_active) VALUES (1, 'Birthday', 0), (2, 'Office', 1), (3, 'Trashed', 0)",
+                [],
+            )
+            .unwrap();
+            conn.execute("UPDATE sessions SET deleted_at = CURRENT_TIMESTAMP WHERE id = 3", [])
+                .unwrap();
+            conn.execute(
+                "INSERT INTO singers (id, name, color, is_persistent) VALUES
+                    (1, 'Alice', '#fff', 1), (2, 'Bob', '#000', 1), (3, 'Carol', '#f00', 1)",
+                [],
+            )
+            .unwrap();
+
+            add_played(&conn, "b1", 1, "song-a", 1800, &[1]);
+            add_played(&conn, "b2", 1, "song-b", 1800, &[2]);
+            add_played(&conn, "o1", 2, "song-a", 1800, &[1, 2]);
+            add_played(&conn, "o2", 2, "song-a", 1800, &[1]);
+            add_played(&conn, "o3", 2, "song-c", 0, &[]);
+            // Trashed sessions don't count
+            add_played(&conn, "t1", 3, "song-c", 1800, &[3]);
+            add_played(&conn, "t2", 3, "song-c", 1800, &[3]);
+            add_played(&conn, "t3", 3, "song-c", 1800, &[3]);
+            // Pending songs weren't played
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES ('q1', 2, 'queue', 'song-b', 'Song B', 'youtube', 0, '2024-01-01T00:00:00Z')",
+                [],
+            )
+            .unwrap();
+
+            let stats = load_global_stats(&conn).unwrap();
+            assert_eq!(stats.total_sessions, 2);
+            assert_eq!(stats.total_songs_played, 5);
+            assert_eq!(stats.total_unique_singers, 2);
+            assert_eq!(stats.total_sung_hours, 2.0);
+
+            let top: Vec<(&str, u32)> = stats
+                .top_songs
+                .iter()
+                .map(|song| (song.video_id.as_str(), song.play_count))
+                .collect();
+            assert_eq!(top, vec![("song-a", 3), ("song-b", 1), ("song-c", 1)]);
+            assert_eq!(stats.top_songs[0].title, "song-a title");
+            assert_eq!(
+                stats.most_active_singer,
+                Some(SingerActivity {
+                    singer_id: 1,
+                    name: "Alice".to_string(),
+                    song_count: 3,
+                })
             );
         }
 
         #[test]
-        fn test_name_validation_at_limit() {
-            let max_name = "a".repeat(MAX_NAME_LENGTH);
-            assert_eq!(max_name.len(), MAX_NAME_LENGTH);
-            assert!(
-                max_name.len() <= MAX_NAME_LENGTH,
-                "Name at exactly {} characters should be valid",
-                MAX_NAME_LENGTH
-            );
+        fn test_empty_database() {
+            let conn = setup_test_db();
+            let stats = load_global_stats(&conn).unwrap();
+            assert_eq!(stats.total_sessions, 0);
+            assert_eq!(stats.total_songs_played, 0);
+            assert_eq!(stats.total_sung_hours, 0.0);
+            assert!(stats.top_songs.is_empty());
+            assert_eq!(stats.most_active_singer, None);
         }
+    }
+
+    mod last_search {
+        use super::*;
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{read_last_search, write_last_search};
 
         #[test]
-        fn test_delete_singer() {
+        fn test_last_search_round_trip() {
             let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Tonight', 1)", [])
+                .unwrap();
+
+            assert_eq!(read_last_search(&conn, 1).unwrap(), None);
+
+            write_last_search(&conn, 1, "  bohemian rhapsody ").unwrap();
+            assert_eq!(read_last_search(&conn, 1).unwrap().as_deref(), Some("bohemian rhapsody"));
+
+            // An empty query clears it
+            write_last_search(&conn, 1, "   ").unwrap();
+            assert_eq!(read_last_search(&conn, 1).unwrap(), None);
+
+            assert!(matches!(
+                write_last_search(&conn, 1, &"a".repeat(501)),
+                Err(CommandError::Validation(_))
+            ));
+            // Unknown sessions have no last search
+            assert_eq!(read_last_search(&conn, 99).unwrap(), None);
+        }
+    }
+
+    mod queue_singer_guards {
+        use super::*;
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{ensure_singer_and_queue_item_exist, insert_queue_singer};
 
+        fn seed(conn: &Connection) {
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Tonight', 1)", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#f00')", [])
+                .unwrap();
             conn.execute(
-                "INSERT INTO singers (name, color) VALUES ('ToDelete', '#000000')",
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES ('item', 1, 'queue', 'item', 'Song', 'youtube', 0, '2024-01-01T00:00:00Z')",
                 [],
             )
             .unwrap();
+        }
 
-            let count: i32 = conn
-                .query_row("SELECT COUNT(*) FROM singers", [], |row| row.get(0))
-                .unwrap();
-            assert_eq!(count, 1);
+        #[test]
+        fn test_accepts_existing_singer_and_item() {
+            let conn = setup_test_db();
+            seed(&conn);
+            ensure_singer_and_queue_item_exist(&conn, "item", 1).unwrap();
+            insert_queue_singer(&conn, "item", 1).unwrap();
+        }
 
-            conn.execute("DELETE FROM singers WHERE name = 'ToDelete'", [])
-                .unwrap();
+        #[test]
+        fn test_rejects_unknown_singer() {
+            let conn = setup_test_db();
+            seed(&conn);
+            match ensure_singer_and_queue_item_exist(&conn, "item", 99) {
+                Err(CommandError::NotFound { resource, id }) => {
+                    assert_eq!(resource, "Singer");
+                    assert_eq!(id, "99");
+                }
+                other => panic!("expected singer not found, got {:?}", other),
+            }
+        }
 
-            let count: i32 = conn
-                .query_row("SELECT COUNT(*) FROM singers", [], |row| row.get(0))
+        #[test]
+        fn test_rejects_unknown_queue_item() {
+            let conn = setup_test_db();
+            seed(&conn);
+            match ensure_singer_and_queue_item_exist(&conn, "missing", 1) {
+                Err(CommandError::NotFound { resource, id }) => {
+                    assert_eq!(resource, "Queue item");
+                    assert_eq!(id, "missing");
+                }
+                other => panic!("expected queue item not found, got {:?}", other),
+            }
+        }
+    }
+
+    mod singer_sheets {
+        use super::*;
+        use crate::commands::session::load_singer_sheets;
+        use crate::services::singer_sheets::write_singer_sheets;
+
+        fn add_history(conn: &Connection, id: &str, position: i64, title: &str, played_at: &str, singer_ids: &[i64]) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, source, position, added_at, played_at)
+                 VALUES (?1, 1, 'history', ?1, ?2, 'Artist', 'youtube', ?3, '2024-06-01T19:00:00Z', ?4)",
+                rusqlite::params![id, title, position, played_at],
+            )
+            .unwrap();
+            for (i, singer_id) in singer_ids.iter().enumerate() {
+                conn.execute(
+                    "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, ?3)",
+                    rusqlite::params![id, singer_id, i as i64],
+                )
                 .unwrap();
-            assert_eq!(count, 0);
+            }
         }
 
         #[test]
-        fn test_get_singers_ordered_by_name() {
+        fn test_exports_a_sheet_per_singer() {
             let conn = setup_test_db();
-
-            conn.execute("INSERT INTO singers (name, color) VALUES ('Zoe', '#111')", [])
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Birthday', 1)", [])
                 .unwrap();
-            conn.execute("INSERT INTO singers (name, color) VALUES ('Alice', '#222')", [])
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#f00')", [])
                 .unwrap();
-            conn.execute("INSERT INTO singers (name, color) VALUES ('Mike', '#333')", [])
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (2, 'Bob/Rob', '#0f0')", [])
                 .unwrap();
+            add_history(&conn, "h1", 0, "First", "2024-06-01 20:00:00", &[2]);
+            add_history(&conn, "h2", 1, "Second", "2024-06-01 20:05:00", &[1]);
+            add_history(&conn, "h3", 2, "Duet", "2024-06-01 20:10:00", &[1, 2]);
+            add_history(&conn, "h4", 3, "Nobody", "2024-06-01 20:15:00", &[]);
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES ('pending', 1, 'queue', 'pending', 'Not yet', 'youtube', 0, '2024-06-01T19:00:00Z')",
+                [],
+            )
+            .unwrap();
 
-            let mut stmt = conn
-                .prepare("SELECT name FROM singers ORDER BY name")
-                .unwrap();
-            let names: Vec<String> = stmt
-                .query_map([], |row| row.get(0))
-                .unwrap()
-                .collect::<Result<Vec<_>, _>>()
-                .unwrap();
+            let sheets = load_singer_sheets(&conn, 1).unwrap();
+            let summary: Vec<(Option<&str>, Vec<&str>)> = sheets
+                .iter()
+                .map(|sheet| {
+                    (
+                        sheet.singer_name.as_deref(),
+                        sheet.songs.iter().map(|song| song.title.as_str()).collect(),
+                    )
+                })
+                .collect();
+            assert_eq!(
+                summary,
+                vec![
+                    (Some("Bob/Rob"), vec!["First", "Duet"]),
+                    (Some("Alice"), vec!["Second", "Duet"]),
+                    (None, vec!["Nobody"]),
+                ]
+            );
 
-            assert_eq!(names, vec!["Alice", "Mike", "Zoe"]);
+            let dir = std::env::temp_dir().join(format!("homekaraoke-test-singer-sheets-{}", std::process::id()));
+            let _ = std::fs::remove_dir_all(&dir);
+            std::fs::create_dir_all(&dir).unwrap();
+
+            let paths = write_singer_sheets(&sheets, Some("Birthday"), &dir).unwrap();
+            let names: Vec<String> = paths
+                .iter()
+                .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+                .collect();
+            // The slash in Bob's name can't create a subdirectory
+            assert_eq!(names, vec!["Bob_Rob.md", "Alice.md", "Unassigned.md"]);
+
+            let alice = std::fs::read_to_string(dir.join("Alice.md")).unwrap();
+            assert!(alice.starts_with("# Songs sung by Alice\n\nBirthday\n"));
+            assert!(alice.contains("- 2024-06-01 20:05:00 — Second (Artist)"));
+            assert!(alice.contains("- 2024-06-01 20:10:00 — Duet (Artist)"));
+
+            // A second export keeps the first one's files
+            let again = write_singer_sheets(&sheets, Some("Birthday"), &dir).unwrap();
+            assert_eq!(again[1].file_name().unwrap(), "Alice 2.md");
+
+            let _ = std::fs::remove_dir_all(&dir);
         }
+    }
 
-        #[test]
-        fn test_get_persistent_singers_only() {
-            let conn = setup_test_db();
+    mod waiting_singers {
+        use super::*;
+        use crate::commands::session::waiting_singers;
 
+        fn join(conn: &Connection, session_id: i64, singer_id: i64, joined_at: &str) {
             conn.execute(
-                "INSERT INTO singers (name, color, is_persistent) VALUES ('Temp', '#111', 0)",
-                [],
+                "INSERT INTO session_singers (session_id, singer_id, joined_at) VALUES (?1, ?2, ?3)",
+                rusqlite::params![session_id, singer_id, joined_at],
+            )
+            .unwrap();
+        }
+
+        fn add_item(conn: &Connection, id: &str, session_id: i64, item_type: &str, singer_id: i64) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES (?1, ?2, ?3, ?1, 'Song', 'youtube', 0, '2024-01-01T00:00:00Z')",
+                rusqlite::params![id, session_id, item_type],
             )
             .unwrap();
             conn.execute(
-                "INSERT INTO singers (name, color, is_persistent) VALUES ('Perm', '#222', 1)",
-                [],
+                "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES (?1, ?2, 0)",
+                rusqlite::params![id, singer_id],
             )
             .unwrap();
+        }
 
-            let count: i32 = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM singers WHERE is_persistent = 1",
-                    [],
-                    |row| row.get(0),
+        #[test]
+        fn test_lists_singers_without_history_in_join_order() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Tonight', 1)", [])
+                .unwrap();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Last week', 0)", [])
+                .unwrap();
+            for (id, name) in [(1, "Alice"), (2, "Bob"), (3, "Carol"), (4, "Dave")] {
+                conn.execute(
+                    "INSERT INTO singers (id, name, color) VALUES (?1, ?2, '#f00')",
+                    rusqlite::params![id, name],
                 )
                 .unwrap();
+            }
+            join(&conn, 1, 3, "2024-01-01 20:00:00");
+            join(&conn, 1, 1, "2024-01-01 20:05:00");
+            join(&conn, 1, 2, "2024-01-01 20:10:00");
+            join(&conn, 1, 4, "2024-01-01 20:15:00");
+            join(&conn, 2, 4, "2023-12-25 20:00:00");
+
+            // Alice sang tonight; Bob is only queued; Dave sang in another session
+            add_item(&conn, "sung", 1, "history", 1);
+            add_item(&conn, "queued", 1, "queue", 2);
+            add_item(&conn, "old", 2, "history", 4);
+
+            let names: Vec<String> = waiting_singers(&conn, 1)
+                .unwrap()
+                .into_iter()
+                .map(|singer| singer.name)
+                .collect();
+            assert_eq!(names, vec!["Carol", "Bob", "Dave"]);
 
-            assert_eq!(count, 1);
+            // Everyone in the old session has sung
+            assert!(waiting_singers(&conn, 2).unwrap().is_empty());
         }
     }
 
-    mod session_lifecycle {
+    mod name_active_session {
         use super::*;
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{active_session_id, set_session_name, validate_session_name};
 
         #[test]
-        fn test_start_session_basic() {
+        fn test_validate_session_name_trims_and_rejects_invalid() {
+            assert_eq!(validate_session_name("  Friday Night  ").unwrap(), "Friday Night");
+            assert!(matches!(
+                validate_session_name("   "),
+                Err(CommandError::Validation(_))
+            ));
+            assert!(matches!(
+                validate_session_name(&"x".repeat(101)),
+                Err(CommandError::Validation(_))
+            ));
+        }
+
+        #[test]
+        fn test_names_the_active_session() {
             let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Old', 0)", [])
+                .unwrap();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES (NULL, 1)", [])
+                .unwrap();
+            let active_id = conn.last_insert_rowid();
 
-            conn.execute(
-                "INSERT INTO sessions (name, is_active) VALUES ('Test Session', 1)",
-                [],
-            )
-            .unwrap();
+            let session_id = active_session_id(&conn).unwrap();
+            assert_eq!(session_id, active_id);
 
-            let session: (i64, Option<String>, i32) = conn
-                .query_row(
-                    "SELECT id, name, is_active FROM sessions WHERE is_active = 1",
-                    [],
-                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
-                )
+            let session = set_session_name(&conn, session_id, "Karaoke Night").unwrap();
+            assert_eq!(session.id, active_id);
+            assert_eq!(session.name.as_deref(), Some("Karaoke Night"));
+            assert!(session.is_active);
+        }
+
+        #[test]
+        fn test_no_active_session() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Ended', 0)", [])
                 .unwrap();
 
-            assert_eq!(session.1, Some("Test Session".to_string()));
-            assert_eq!(session.2, 1);
+            assert!(matches!(
+                active_session_id(&conn),
+                Err(CommandError::NoActiveSession)
+            ));
+        }
+    }
+
+    mod set_session_times {
+        use super::*;
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{normalize_session_timestamp, update_session_times};
+
+        #[test]
+        fn test_normalize_session_timestamp() {
+            assert_eq!(
+                normalize_session_timestamp("2024-05-01T20:30:00Z", "start").unwrap(),
+                "2024-05-01 20:30:00"
+            );
+            // Offsets are converted to UTC
+            assert_eq!(
+                normalize_session_timestamp("2024-05-01T22:30:00+02:00", "start").unwrap(),
+                "2024-05-01 20:30:00"
+            );
+            assert_eq!(
+                normalize_session_timestamp("2024-05-01 20:30:00", "start").unwrap(),
+                "2024-05-01 20:30:00"
+            );
+            assert!(matches!(
+                normalize_session_timestamp("last friday", "start"),
+                Err(CommandError::Validation(_))
+            ));
         }
 
         #[test]
-        fn test_start_session_ends_previous() {
+        fn test_updates_inactive_session_times() {
             let conn = setup_test_db();
-
-            // Create first session
-            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('First', 1)", [])
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Past', 0)", [])
                 .unwrap();
+            let session_id = conn.last_insert_rowid();
 
-            // Simulate starting new session (end old first)
+            let session = update_session_times(
+                &conn,
+                session_id,
+                "2024-05-01T20:00:00Z",
+                Some("2024-05-01T23:30:00Z"),
+            )
+            .unwrap();
+            assert_eq!(session.started_at, "2024-05-01 20:00:00");
+            assert_eq!(session.ended_at.as_deref(), Some("2024-05-01 23:30:00"));
+
+            // Backfilled session sorts before one started later
             conn.execute(
-                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE is_active = 1",
+                "INSERT INTO sessions (name, is_active, started_at) VALUES ('Later', 0, '2024-06-01 19:00:00')",
                 [],
             )
             .unwrap();
-            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Second', 1)", [])
+            let first: String = conn
+                .query_row("SELECT name FROM sessions ORDER BY started_at DESC LIMIT 1", [], |row| row.get(0))
                 .unwrap();
+            assert_eq!(first, "Later");
+        }
 
-            let active_count: i32 = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM sessions WHERE is_active = 1",
-                    [],
-                    |row| row.get(0),
-                )
+        #[test]
+        fn test_rejects_end_before_start() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Past', 0)", [])
                 .unwrap();
+            let session_id = conn.last_insert_rowid();
 
-            assert_eq!(active_count, 1, "Only one session should be active at a time");
+            assert!(matches!(
+                update_session_times(&conn, session_id, "2024-05-01T20:00:00Z", Some("2024-05-01T19:00:00Z")),
+                Err(CommandError::Validation(_))
+            ));
+        }
 
-            let active_name: String = conn
-                .query_row(
-                    "SELECT name FROM sessions WHERE is_active = 1",
-                    [],
-                    |row| row.get(0),
-                )
+        #[test]
+        fn test_rejects_active_and_missing_sessions() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Now', 1)", [])
                 .unwrap();
+            let active_id = conn.last_insert_rowid();
 
-            assert_eq!(active_name, "Second");
+            assert!(matches!(
+                update_session_times(&conn, active_id, "2024-05-01T20:00:00Z", None),
+                Err(CommandError::Validation(_))
+            ));
+            assert!(matches!(
+                update_session_times(&conn, 999, "2024-05-01T20:00:00Z", None),
+                Err(CommandError::NotFound { .. })
+            ));
         }
+    }
+
+    mod session_settings {
+        use super::*;
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{read_session_settings, write_session_settings, SessionSettings};
 
         #[test]
-        fn test_end_session_marks_inactive() {
+        fn test_defaults_when_unset() {
             let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name) VALUES ('Venue')", []).unwrap();
+            let session_id = conn.last_insert_rowid();
 
-            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('ToEnd', 1)", [])
-                .unwrap();
-            let session_id: i64 = conn.last_insert_rowid();
-
-            // Add a singer to make the session non-empty
-            conn.execute(
-                "INSERT INTO singers (name, color) VALUES ('Singer', '#fff')",
-                [],
-            )
-            .unwrap();
-            let singer_id: i64 = conn.last_insert_rowid();
-            conn.execute(
-                "INSERT INTO session_singers (session_id, singer_id) VALUES (?1, ?2)",
-                [session_id, singer_id],
-            )
-            .unwrap();
+            assert_eq!(read_session_settings(&conn, session_id).unwrap(), SessionSettings::default());
+        }
 
-            // End session
-            conn.execute(
-                "UPDATE sessions SET is_active = 0, ended_at = CURRENT_TIMESTAMP WHERE id = ?1",
-                [session_id],
-            )
-            .unwrap();
+        #[test]
+        fn test_roundtrip_per_session() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Bar', 0)", []).unwrap();
+            let bar = conn.last_insert_rowid();
+            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Party', 1)", []).unwrap();
+            let party = conn.last_insert_rowid();
+
+            let settings = SessionSettings {
+                default_volume: Some(40),
+                autoplay: Some(false),
+                show_lyrics: Some(true),
+            };
+            write_session_settings(&conn, bar, &settings).unwrap();
+
+            assert_eq!(read_session_settings(&conn, bar).unwrap(), settings);
+            assert_eq!(read_session_settings(&conn, party).unwrap(), SessionSettings::default());
+        }
 
-            let is_active: i32 = conn
-                .query_row(
-                    "SELECT is_active FROM sessions WHERE id = ?1",
-                    [session_id],
-                    |row| row.get(0),
-                )
-                .unwrap();
+        #[test]
+        fn test_rejects_out_of_range_volume_and_missing_session() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (name) VALUES ('Venue')", []).unwrap();
+            let session_id = conn.last_insert_rowid();
+
+            let loud = SessionSettings {
+                default_volume: Some(101),
+                ..Default::default()
+            };
+            assert!(matches!(
+                write_session_settings(&conn, session_id, &loud),
+                Err(CommandError::Validation(_))
+            ));
+            assert_eq!(read_session_settings(&conn, session_id).unwrap(), SessionSettings::default());
 
-            assert_eq!(is_active, 0);
+            assert!(matches!(
+                write_session_settings(&conn, 999, &SessionSettings::default()),
+                Err(CommandError::NotFound { .. })
+            ));
+            assert!(matches!(
+                read_session_settings(&conn, 999),
+                Err(CommandError::NotFound { .. })
+            ));
         }
+    }
+
+    mod playback_defaults {
+        use super::*;
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{read_playback_defaults, write_playback_defaults, PlaybackAdjustments};
 
         #[test]
-        fn test_end_empty_session_deletes() {
+        fn test_round_trip_and_clear() {
             let conn = setup_test_db();
-
-            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Empty', 1)", [])
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Tonight', 1)", [])
                 .unwrap();
-            let session_id: i64 = conn.last_insert_rowid();
 
-            // Check if session has content
-            let has_content: bool = conn
-                .query_row(
-                    "SELECT EXISTS(
-                        SELECT 1 FROM queue_items WHERE session_id = ?1
-                        UNION
-                        SELECT 1 FROM session_singers WHERE session_id = ?1
-                    )",
-                    [session_id],
-                    |row| row.get(0),
-                )
+            assert_eq!(read_playback_defaults(&conn, 1).unwrap(), PlaybackAdjustments::default());
+
+            let defaults = PlaybackAdjustments {
+                playback_rate: Some(1.1),
+                transpose: Some(-2),
+            };
+            write_playback_defaults(&conn, 1, &defaults).unwrap();
+            assert_eq!(read_playback_defaults(&conn, 1).unwrap(), defaults);
+
+            write_playback_defaults(&conn, 1, &PlaybackAdjustments::default()).unwrap();
+            assert_eq!(read_playback_defaults(&conn, 1).unwrap(), PlaybackAdjustments::default());
+        }
+
+        #[test]
+        fn test_rejects_out_of_range_values() {
+            let conn = setup_test_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Tonight', 1)", [])
                 .unwrap();
 
-            if !has_content {
-                conn.execute("DELETE FROM sessions WHERE id = ?1", [session_id])
-                    .unwrap();
+            for invalid in [
+                PlaybackAdjustments { playback_rate: Some(0.4), transpose: None },
+                PlaybackAdjustments { playback_rate: Some(2.5), transpose: None },
+                PlaybackAdjustments { playback_rate: Some(f64::NAN), transpose: None },
+                PlaybackAdjustments { playback_rate: None, transpose: Some(13) },
+                PlaybackAdjustments { playback_rate: None, transpose: Some(-13) },
+            ] {
+                let result = write_playback_defaults(&conn, 1, &invalid);
+                assert!(matches!(result, Err(CommandError::Validation(_))), "{:?}", invalid);
             }
+            // Nothing was stored
+            assert_eq!(read_playback_defaults(&conn, 1).unwrap(), PlaybackAdjustments::default());
 
-            let count: i32 = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM sessions WHERE id = ?1",
-                    [session_id],
-                    |row| row.get(0),
-                )
-                .unwrap();
+            let edges = PlaybackAdjustments {
+                playback_rate: Some(0.5),
+                transpose: Some(12),
+            };
+            write_playback_defaults(&conn, 1, &edges).unwrap();
 
-            assert_eq!(count, 0, "Empty session should be deleted on end");
+            let missing = write_playback_defaults(&conn, 99, &edges);
+            assert!(matches!(missing, Err(CommandError::NotFound { .. })));
         }
 
         #[test]
-        fn test_session_cleanup_removes_orphaned_singers() {
-            let conn = setup_test_db();
+        fn test_item_override_beats_session_default() {
+            let session = PlaybackAdjustments {
+                playback_rate: Some(1.05),
+                transpose: Some(-1),
+            };
+
+            // Item with no override follows the session
+            assert_eq!(PlaybackAdjustments::default().or(session), session);
+
+            // Item override wins field by field
+            let item = PlaybackAdjustments {
+                playback_rate: Some(1.0),
+                transpose: None,
+            };
+            assert_eq!(
+                item.or(session),
+                PlaybackAdjustments {
+                    playback_rate: Some(1.0),
+                    transpose: Some(-1),
+                }
+            );
 
-            // Create non-persistent singer without session association
-            conn.execute(
-                "INSERT INTO singers (name, color, is_persistent) VALUES ('Orphan', '#000', 0)",
-                [],
+            // No session default keeps the item's values
+            assert_eq!(item.or(PlaybackAdjustments::default()), item);
+        }
+    }
+
+    mod singer_song_preferences {
+        use crate::commands::errors::CommandError;
+        use crate::commands::session::{
+            apply_singer_song_preference, insert_queue_singer, read_singer_song_preference,
+            write_singer_song_preference, PlaybackAdjustments, SingerSongPreference,
+        };
+        use rusqlite::Connection;
+
+        fn setup() -> Connection {
+            let conn = super::setup_migrated_db();
+            conn.execute_batch(
+                "INSERT INTO sessions (id, name, is_active) VALUES (1, 'Party', 1);
+                 INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#f00'), (2, 'Bob', '#0f0');
+                 INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+                 VALUES ('item-1', 1, 'queue', 'v1', 'Song', 'youtube', 0, '2024-01-01T00:00:00Z');",
             )
             .unwrap();
+            conn
+        }
 
-            // Cleanup orphaned non-persistent singers
-            conn.execute(
-                "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
+        fn preference(singer_id: i64, pitch: Option<i32>, tempo: Option<u32>) -> SingerSongPreference {
+            SingerSongPreference {
+                singer_id,
+                video_id: "v1".to_string(),
+                pitch_semitones: pitch,
+                tempo_percent: tempo,
+                notes: None,
+            }
+        }
+
+        fn item_adjustments(conn: &Connection) -> PlaybackAdjustments {
+            conn.query_row(
+                "SELECT playback_rate, transpose FROM queue_items WHERE id = 'item-1'",
                 [],
+                |row| {
+                    Ok(PlaybackAdjustments {
+                        playback_rate: row.get(0)?,
+                        transpose: row.get(1)?,
+                    })
+                },
             )
-            .unwrap();
-
-            let count: i32 = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM singers WHERE name = 'Orphan'",
-                    [],
-                    |row| row.get(0),
-                )
-                .unwrap();
+            .unwrap()
+        }
 
-            assert_eq!(count, 0, "Orphaned non-persistent singers should be cleaned up");
+        /// Same steps as `assign_singer_to_queue_item`, without the app state
+        fn assign(conn: &Connection, singer_id: i64) -> bool {
+            let applied = apply_singer_song_preference(conn, "item-1", singer_id).unwrap();
+            insert_queue_singer(conn, "item-1", singer_id).unwrap();
+            applied
         }
 
         #[test]
-        fn test_persistent_singer_survives_cleanup() {
-            let conn = setup_test_db();
-
-            // Create persistent singer without session association
-            conn.execute(
-                "INSERT INTO singers (name, color, is_persistent) VALUES ('Persistent', '#000', 1)",
-                [],
+        fn test_set_get_and_clear_preference() {
+            let conn = setup();
+            let stored = write_singer_song_preference(
+                &conn,
+                SingerSongPreference {
+                    video_id: " v1 ".to_string(),
+                    notes: Some("  Skip the second verse ".to_string()),
+                    ..preference(1, Some(-3), Some(90))
+                },
             )
+            .unwrap()
             .unwrap();
+            assert_eq!(stored.video_id, "v1");
+            assert_eq!(stored.notes.as_deref(), Some("Skip the second verse"));
+            assert_eq!(read_singer_song_preference(&conn, 1, "v1").unwrap(), Some(stored));
+            assert_eq!(read_singer_song_preference(&conn, 2, "v1").unwrap(), None);
 
-            // Cleanup orphaned non-persistent singers
-            conn.execute(
-                "DELETE FROM singers WHERE is_persistent = 0 AND id NOT IN (SELECT singer_id FROM session_singers)",
-                [],
-            )
-            .unwrap();
+            // Updating replaces every field
+            write_singer_song_preference(&conn, preference(1, Some(2), None)).unwrap();
+            assert_eq!(
+                read_singer_song_preference(&conn, 1, "v1").unwrap(),
+                Some(preference(1, Some(2), None))
+            );
 
-            let count: i32 = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM singers WHERE name = 'Persistent'",
-                    [],
-                    |row| row.get(0),
-                )
-                .unwrap();
+            // An empty preference clears it
+            assert_eq!(write_singer_song_preference(&conn, preference(1, None, None)).unwrap(), None);
+            assert_eq!(read_singer_song_preference(&conn, 1, "v1").unwrap(), None);
+        }
 
-            assert_eq!(count, 1, "Persistent singers should survive cleanup");
+        #[test]
+        fn test_rejects_out_of_range_values_and_unknown_singers() {
+            let conn = setup();
+            for invalid in [
+                preference(1, Some(13), None),
+                preference(1, Some(-13), None),
+                preference(1, None, Some(49)),
+                preference(1, None, Some(201)),
+                SingerSongPreference {
+                    video_id: "  ".to_string(),
+                    ..preference(1, Some(1), None)
+                },
+                SingerSongPreference {
+                    notes: Some("x".repeat(501)),
+                    ..preference(1, None, None)
+                },
+            ] {
+                assert!(matches!(
+                    write_singer_song_preference(&conn, invalid),
+                    Err(CommandError::Validation(_))
+                ));
+            }
+            assert!(matches!(
+                write_singer_song_preference(&conn, preference(99, Some(1), None)),
+                Err(CommandError::NotFound { resource: "Singer", .. })
+            ));
+
+            // The notes limit counts characters, not bytes
+            let accented = SingerSongPreference {
+                notes: Some("é".repeat(500)),
+                ..preference(1, None, None)
+            };
+            assert!(write_singer_song_preference(&conn, accented).is_ok());
         }
 
         #[test]
-        fn test_add_singer_to_session() {
-            let conn = setup_test_db();
+        fn test_assigning_first_singer_applies_their_preference() {
+            let conn = setup();
+            write_singer_song_preference(&conn, preference(1, Some(-2), Some(110))).unwrap();
 
-            conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Test', 1)", [])
-                .unwrap();
-            let session_id: i64 = conn.last_insert_rowid();
+            assert!(assign(&conn, 1));
+            assert_eq!(
+                item_adjustments(&conn),
+                PlaybackAdjustments {
+                    playback_rate: Some(1.1),
+                    transpose: Some(-2),
+                }
+            );
+        }
 
-            conn.execute(
-                "INSERT INTO singers (name, color) VALUES ('Alice', '#fff')",
-                [],
+        #[test]
+        fn test_duet_keeps_the_adders_preference() {
+            let conn = setup();
+            write_singer_song_preference(&conn, preference(1, Some(-2), None)).unwrap();
+            write_singer_song_preference(&conn, preference(2, Some(4), Some(80))).unwrap();
+
+            assert!(assign(&conn, 1));
+            assert!(!assign(&conn, 2));
+            assert_eq!(
+                item_adjustments(&conn),
+                PlaybackAdjustments {
+                    playback_rate: None,
+                    transpose: Some(-2),
+                }
+            );
+        }
+
+        #[test]
+        fn test_existing_override_and_notes_only_preferences_are_left_alone() {
+            let conn = setup();
+            write_singer_song_preference(
+                &conn,
+                SingerSongPreference {
+                    notes: Some("Loves the key change".to_string()),
+                    ..preference(1, None, None)
+                },
             )
             .unwrap();
-            let singer_id: i64 = conn.last_insert_rowid();
+            assert!(!assign(&conn, 1));
+            assert_eq!(item_adjustments(&conn), PlaybackAdjustments::default());
 
-            conn.execute(
-                "INSERT OR IGNORE INTO session_singers (session_id, singer_id) VALUES (?1, ?2)",
-                [session_id, singer_id],
+            conn.execute_batch(
+                "DELETE FROM queue_singers;
+                 UPDATE queue_items SET transpose = 1 WHERE id = 'item-1';",
             )
             .unwrap();
-
-            let count: i32 = conn
-                .query_row(
-                    "SELECT COUNT(*) FROM session_singers WHERE session_id = ?1 AND singer_id = ?2",
-                    [session_id, singer_id],
-                    |row| row.get(0),
-                )
-                .unwrap();
-
-            assert_eq!(count, 1);
+            write_singer_song_preference(&conn, preference(1, Some(-5), None)).unwrap();
+            assert!(!assign(&conn, 1));
+            assert_eq!(item_adjustments(&conn).transpose, Some(1));
         }
     }
 
@@ -1747,6 +4403,99 @@ mod tests {
         }
     }
 
+    mod assign_singer_by_artist {
+        use super::*;
+        use crate::commands::session::assign_singer_to_artist_items;
+
+        fn add_queue_item(conn: &Connection, id: &str, item_type: &str, artist: Option<&str>, position: i64) {
+            conn.execute(
+                "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, source, position, added_at)
+                 VALUES (?1, 1, ?2, ?1, 'Song', ?3, 'youtube', ?4, '2024-01-01T00:00:00Z')",
+                rusqlite::params![id, item_type, artist, position],
+            )
+            .unwrap();
+        }
+
+        #[test]
+        fn test_assigns_matching_pending_items_case_insensitive() {
+            let conn = setup_migrated_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Party', 1)", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (name, color) VALUES ('Alice', '#f00')", [])
+                .unwrap();
+            let alice_id = conn.last_insert_rowid();
+            conn.execute("INSERT INTO singers (name, color) VALUES ('Bob', '#0f0')", [])
+                .unwrap();
+            let bob_id = conn.last_insert_rowid();
+
+            add_queue_item(&conn, "q1", "queue", Some("Queen"), 0);
+            add_queue_item(&conn, "q2", "queue", Some("a-ha"), 1);
+            add_queue_item(&conn, "q3", "queue", Some(" QUEEN "), 2);
+            add_queue_item(&conn, "q4", "queue", None, 3);
+            add_queue_item(&conn, "h1", "history", Some("Queen"), 0);
+
+            // Bob already sings q1, Alice is appended after him
+            conn.execute(
+                "INSERT INTO queue_singers (queue_item_id, singer_id, position) VALUES ('q1', ?1, 0)",
+                [bob_id],
+            )
+            .unwrap();
+
+            let assigned = assign_singer_to_artist_items(&conn, 1, alice_id, "queen").unwrap();
+            assert_eq!(assigned, 2);
+
+            let position: i32 = conn
+                .query_row(
+                    "SELECT position FROM queue_singers WHERE queue_item_id = 'q1' AND singer_id = ?1",
+                    [alice_id],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(position, 1);
+
+            // History items are not touched
+            let history_count: i32 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM queue_singers WHERE queue_item_id = 'h1'",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(history_count, 0);
+
+            // Running again doesn't duplicate assignments
+            assert_eq!(assign_singer_to_artist_items(&conn, 1, alice_id, "Queen").unwrap(), 0);
+        }
+
+        #[test]
+        fn test_applies_singer_song_preferences() {
+            let conn = setup_migrated_db();
+            conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Party', 1)", [])
+                .unwrap();
+            conn.execute("INSERT INTO singers (id, name, color) VALUES (1, 'Alice', '#f00')", [])
+                .unwrap();
+            add_queue_item(&conn, "q1", "queue", Some("Queen"), 0);
+            conn.execute(
+                "INSERT INTO singer_song_preferences (singer_id, video_id, pitch_semitones, tempo_percent)
+                 VALUES (1, 'q1', -2, 90)",
+                [],
+            )
+            .unwrap();
+
+            assert_eq!(assign_singer_to_artist_items(&conn, 1, 1, "Queen").unwrap(), 1);
+
+            let (rate, transpose): (Option<f64>, Option<i32>) = conn
+                .query_row(
+                    "SELECT playback_rate, transpose FROM queue_items WHERE id = 'q1'",
+                    [],
+                    |row| Ok((row.get(0)?, row.get(1)?)),
+                )
+                .unwrap();
+            assert_eq!(rate, Some(0.9));
+            assert_eq!(transpose, Some(-2));
+        }
+    }
+
     mod active_singer {
         use super::*;
 
@@ -0,0 +1,368 @@
+use super::errors::{CommandError, LockResultExt};
+use super::queue::{append_queue_items, parse_stored_chapters, QueueItemData};
+use super::session::active_session_id;
+use crate::AppState;
+use log::{debug, info};
+use rusqlite::{Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const MAX_SETLIST_NAME_LENGTH: usize = 100;
+
+/// A named, reusable list of songs saved from a queue.
+/// Unlike sessions, setlists are templates: loading one copies its songs into the queue.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Setlist {
+    pub id: i64,
+    pub name: String,
+    pub created_at: String,
+    pub item_count: i64,
+}
+
+// ============ Helper Functions ============
+
+fn validate_setlist_name(name: &str) -> Result<String, CommandError> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(CommandError::Validation(
+            "Setlist name cannot be empty".to_string(),
+        ));
+    }
+    if name.len() > MAX_SETLIST_NAME_LENGTH {
+        return Err(CommandError::Validation(format!(
+            "Setlist name cannot exceed {} characters",
+            MAX_SETLIST_NAME_LENGTH
+        )));
+    }
+    Ok(name)
+}
+
+fn get_setlist(conn: &Connection, setlist_id: i64) -> Result<Setlist, CommandError> {
+    conn.query_row(
+        "SELECT s.id, s.name, s.created_at, COUNT(i.id)
+         FROM setlists s LEFT JOIN setlist_items i ON i.setlist_id = s.id
+         WHERE s.id = ?1
+         GROUP BY s.id",
+        [setlist_id],
+        |row| {
+            Ok(Setlist {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                item_count: row.get(3)?,
+            })
+        },
+    )
+    .optional()?
+    .ok_or_else(|| CommandError::NotFound {
+        resource: "Setlist",
+        id: setlist_id.to_string(),
+    })
+}
+
+/// Save a session's queue (or the given items, in the given order) as a new setlist
+fn save_queue_as_setlist(
+    conn: &Connection,
+    session_id: i64,
+    name: &str,
+    item_ids: Option<&[String]>,
+) -> Result<Setlist, CommandError> {
+    let queue_ids: Vec<String> = {
+        let mut stmt = conn.prepare(
+            "SELECT id FROM queue_items WHERE session_id = ?1 AND item_type = 'queue' ORDER BY position",
+        )?;
+        let ids = stmt
+            .query_map([session_id], |row| row.get(0))?
+            .collect::<Result<Vec<String>, _>>()?;
+        ids
+    };
+
+    let item_ids = match item_ids {
+        Some(item_ids) => {
+            if let Some(missing) = item_ids.iter().find(|id| !queue_ids.contains(*id)) {
+                return Err(CommandError::NotFound {
+                    resource: "Queue item",
+                    id: missing.clone(),
+                });
+            }
+            item_ids.to_vec()
+        }
+        None => queue_ids,
+    };
+    if item_ids.is_empty() {
+        return Err(CommandError::Validation(
+            "Cannot save an empty setlist".to_string(),
+        ));
+    }
+
+    // Use transaction for atomicity
+    conn.execute("BEGIN IMMEDIATE", [])?;
+
+    let result = (|| -> Result<i64, CommandError> {
+        conn.execute("INSERT INTO setlists (name) VALUES (?1)", [name])?;
+        let setlist_id = conn.last_insert_rowid();
+
+        for (position, item_id) in item_ids.iter().enumerate() {
+            conn.execute(
+                "INSERT INTO setlist_items (setlist_id, position, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, chapters)
+                 SELECT ?1, ?2, video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, chapters
+                 FROM queue_items WHERE id = ?3 AND session_id = ?4",
+                rusqlite::params![setlist_id, position as i64, item_id, session_id],
+            )?;
+        }
+
+        Ok(setlist_id)
+    })();
+
+    match result {
+        Ok(setlist_id) => {
+            conn.execute("COMMIT", [])?;
+            get_setlist(conn, setlist_id)
+        }
+        Err(e) => {
+            let _ = conn.execute("ROLLBACK", []);
+            Err(e)
+        }
+    }
+}
+
+/// Append a setlist's songs to the end of a session's queue with fresh ids
+fn add_setlist_to_queue(
+    conn: &Connection,
+    session_id: i64,
+    setlist_id: i64,
+) -> Result<Vec<QueueItemData>, CommandError> {
+    get_setlist(conn, setlist_id)?;
+    let added_at = chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+    let mut stmt = conn.prepare(
+        "SELECT video_id, title, artist, duration, thumbnail_url, source, youtube_id, file_path, chapters
+         FROM setlist_items WHERE setlist_id = ?1 ORDER BY position",
+    )?;
+    let items = stmt
+        .query_map([setlist_id], |row| {
+            Ok(QueueItemData {
+                id: String::new(),
+                video_id: row.get(0)?,
+                title: row.get(1)?,
+                artist: row.get(2)?,
+                duration: row.get(3)?,
+                thumbnail_url: row.get(4)?,
+                source: row.get(5)?,
+                youtube_id: row.get(6)?,
+                file_path: row.get(7)?,
+                position: 0,
+                added_at: added_at.clone(),
+                played_at: None,
+                chapters: parse_stored_chapters(row.get(8)?),
+                playback_adjustments: Default::default(),
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    append_queue_items(conn, session_id, items)
+}
+
+// ============ Setlist Commands ============
+
+/// Save the active session's queue, or the given queue items in the given order,
+/// as a named setlist
+#[tauri::command]
+pub fn save_setlist(
+    state: State<'_, AppState>,
+    name: String,
+    item_ids: Option<Vec<String>>,
+) -> Result<Setlist, CommandError> {
+    let name = validate_setlist_name(&name)?;
+    debug!("Saving setlist: {}", name);
+
+    let db = state.db.lock().map_lock_err()?;
+    let conn = db.connection();
+    let session_id = active_session_id(conn)?;
+
+    let setlist = save_queue_as_setlist(conn, session_id, &name, item_ids.as_deref())?;
+    info!("Saved setlist {} ({} songs)", setlist.id, setlist.item_count);
+    Ok(setlist)
+}
+
+/// Add a setlist's songs to the end of the active session's queue.
+/// Returns the new queue items (with fresh ids).
+#[tauri::command]
+pub fn load_setlist(
+    state: State<'_, AppState>,
+    setlist_id: i64,
+) -> Result<Vec<QueueItemData>, CommandError> {
+    debug!("Loading setlist {}", setlist_id);
+
+    let db = state.db.lock().map_lock_err()?;
+    let conn = db.connection();
+    let session_id = active_session_id(conn)?;
+
+    let items = add_setlist_to_queue(conn, session_id, setlist_id)?;
+    info!("Added {} songs from setlist {} to the queue", items.len(), setlist_id);
+    Ok(items)
+}
+
+/// List saved setlists, newest first
+#[tauri::command]
+pub fn get_setlists(state: State<'_, AppState>) -> Result<Vec<Setlist>, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    let mut stmt = db.connection().prepare(
+        "SELECT s.id, s.name, s.created_at, COUNT(i.id)
+         FROM setlists s LEFT JOIN setlist_items i ON i.setlist_id = s.id
+         GROUP BY s.id
+         ORDER BY s.created_at DESC, s.id DESC",
+    )?;
+    let setlists = stmt
+        .query_map([], |row| {
+            Ok(Setlist {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                created_at: row.get(2)?,
+                item_count: row.get(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(setlists)
+}
+
+#[tauri::command]
+pub fn delete_setlist(state: State<'_, AppState>, setlist_id: i64) -> Result<(), CommandError> {
+    info!("Deleting setlist {}", setlist_id);
+    let db = state.db.lock().map_lock_err()?;
+    let conn = db.connection();
+    conn.execute("DELETE FROM setlist_items WHERE setlist_id = ?1", [setlist_id])?;
+    let deleted = conn.execute("DELETE FROM setlists WHERE id = ?1", [setlist_id])?;
+    if deleted == 0 {
+        return Err(CommandError::NotFound {
+            resource: "Setlist",
+            id: setlist_id.to_string(),
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        run_migrations(&conn).unwrap();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (1, 'Friday', 0)", [])
+            .unwrap();
+        conn.execute("INSERT INTO sessions (id, name, is_active) VALUES (2, 'Saturday', 1)", [])
+            .unwrap();
+        conn
+    }
+
+    fn add_queued(conn: &Connection, session_id: i64, id: &str, title: &str, position: i64) {
+        conn.execute(
+            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, artist, duration, source, position, added_at, chapters)
+             VALUES (?1, ?2, 'queue', ?1, ?3, 'Artist', 200, 'youtube', ?4, '2024-01-01T00:00:00Z', ?5)",
+            rusqlite::params![
+                id,
+                session_id,
+                title,
+                position,
+                (id == "a").then_some(r#"[{"title":"Chorus","start_time":30.0,"end_time":60.0}]"#)
+            ],
+        )
+        .unwrap();
+    }
+
+    fn queue(conn: &Connection, session_id: i64) -> Vec<(String, String, i64)> {
+        let mut stmt = conn
+            .prepare("SELECT id, title, position FROM queue_items WHERE session_id = ?1 AND item_type = 'queue' ORDER BY position")
+            .unwrap();
+        stmt.query_map([session_id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_save_full_queue_and_load_into_another_session() {
+        let conn = setup_test_db();
+        add_queued(&conn, 1, "a", "First", 0);
+        add_queued(&conn, 1, "b", "Second", 1);
+        add_queued(&conn, 1, "c", "Third", 2);
+        add_queued(&conn, 2, "x", "Already queued", 0);
+
+        let setlist = save_queue_as_setlist(&conn, 1, "Crowd pleasers", None).unwrap();
+        assert_eq!(setlist.name, "Crowd pleasers");
+        assert_eq!(setlist.item_count, 3);
+
+        // The setlist is a template: changing the source queue doesn't change it
+        conn.execute("DELETE FROM queue_items WHERE session_id = 1", []).unwrap();
+
+        let items = add_setlist_to_queue(&conn, 2, setlist.id).unwrap();
+        let titles: Vec<&str> = items.iter().map(|i| i.title.as_str()).collect();
+        assert_eq!(titles, vec!["First", "Second", "Third"]);
+        assert_eq!(items[0].artist.as_deref(), Some("Artist"));
+        assert_eq!(items[0].duration, Some(200));
+        assert_eq!(items[0].chapters.as_ref().map(|c| c.len()), Some(1));
+
+        // Appended after the existing queue, with fresh ids
+        let queue = queue(&conn, 2);
+        assert_eq!(queue.len(), 4);
+        assert_eq!(queue[0].0, "x");
+        for (item, (id, title, position)) in items.iter().zip(&queue[1..]) {
+            assert_eq!(&item.id, id);
+            assert_eq!(&item.title, title);
+            assert_eq!(item.position, *position);
+            assert!(!["a", "b", "c"].contains(&id.as_str()));
+            assert_eq!(id.len(), 36);
+        }
+        assert_ne!(items[0].id, items[1].id);
+
+        // Loading again adds another copy
+        add_setlist_to_queue(&conn, 2, setlist.id).unwrap();
+        assert_eq!(queue(&conn, 2).len(), 7);
+    }
+
+    #[test]
+    fn test_save_subset_in_given_order() {
+        let conn = setup_test_db();
+        add_queued(&conn, 2, "a", "First", 0);
+        add_queued(&conn, 2, "b", "Second", 1);
+        add_queued(&conn, 2, "c", "Third", 2);
+
+        let ids = vec!["c".to_string(), "a".to_string()];
+        let setlist = save_queue_as_setlist(&conn, 2, "Encore", Some(&ids)).unwrap();
+        assert_eq!(setlist.item_count, 2);
+
+        conn.execute("DELETE FROM queue_items", []).unwrap();
+        let titles: Vec<String> = add_setlist_to_queue(&conn, 2, setlist.id)
+            .unwrap()
+            .into_iter()
+            .map(|i| i.title)
+            .collect();
+        assert_eq!(titles, vec!["Third", "First"]);
+    }
+
+    #[test]
+    fn test_save_rejects_empty_and_unknown_items() {
+        let conn = setup_test_db();
+        assert!(matches!(
+            save_queue_as_setlist(&conn, 2, "Empty", None),
+            Err(CommandError::Validation(_))
+        ));
+
+        add_queued(&conn, 1, "other-session", "Elsewhere", 0);
+        let ids = vec!["other-session".to_string()];
+        assert!(matches!(
+            save_queue_as_setlist(&conn, 2, "Wrong", Some(&ids)),
+            Err(CommandError::NotFound { .. })
+        ));
+
+        assert!(matches!(
+            add_setlist_to_queue(&conn, 2, 999),
+            Err(CommandError::NotFound { .. })
+        ));
+        assert!(validate_setlist_name("   ").is_err());
+    }
+}
@@ -1,8 +1,12 @@
+use super::errors::{CommandError, LockResultExt};
+use crate::services::http_client;
+use crate::services::log_levels::{self, LogLevels, LogTarget};
 use crate::AppState;
-use log::debug;
+use log::{debug, info};
+use rusqlite::{Connection, OptionalExtension};
 use std::collections::HashMap;
 use std::sync::atomic::Ordering;
-use tauri::State;
+use tauri::{AppHandle, Emitter, State};
 
 /// Allowed setting keys - prevents arbitrary key injection
 const ALLOWED_SETTING_KEYS: &[&str] = &[
@@ -14,14 +18,22 @@ const ALLOWED_SETTING_KEYS: &[&str] = &[
     "next_song_overlay_seconds",
     "singer_announcement_seconds",
     "remember_player_position",
+    "remember_window_layout",   // auto-save window layout per display config (macOS)
+    "pause_on_display_disconnect", // pause and release keep-awake when a display is removed (macOS)
     "history_limit",
     "clear_queue_on_exit",
+    "changeover_seconds",       // gap between songs for queue runtime estimates
+    "auto_shuffle_after_adds",  // fair shuffle after every N added songs (0 = off)
+    "enforce_contrast",         // adjust singer colors to stay readable on the app background
+    "preserve_empty_sessions",  // archive sessions with no songs or singers on end instead of deleting them
     "debug_mode",
     "playback_mode",
     "ytdlp_available",          // cached yt-dlp check result
     "search_include_lyrics",    // include lyrics content in local library search
     "youtube_api_key",          // YouTube Data API key
     "youtube_search_method",    // "auto" | "api" | "ytdlp"
+    "youtube_blocklist",        // JSON array of blocked channels/keywords
+    "default_search_results",   // search result count when the caller doesn't specify one
     // Search history settings
     "search_history_global",         // show history from all sessions
     "search_history_session_limit",  // max entries per session
@@ -29,6 +41,159 @@ const ALLOWED_SETTING_KEYS: &[&str] = &[
     // Note: hosted_session_id removed - now stored in sessions table (Migration 11)
 ];
 
+/// Settings key for the default number of search results
+pub(crate) const DEFAULT_SEARCH_RESULTS_KEY: &str = "default_search_results";
+
+/// Allowed range for `default_search_results` (YouTube Data API returns at most 50)
+pub(crate) const SEARCH_RESULTS_RANGE: std::ops::RangeInclusive<u32> = 1..=50;
+
+/// Parse a `default_search_results` value, None if invalid or out of range
+pub(crate) fn parse_default_search_results(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|count| SEARCH_RESULTS_RANGE.contains(count))
+}
+
+/// Settings key for fair shuffling the queue after every N added songs
+pub(crate) const AUTO_SHUFFLE_AFTER_ADDS_KEY: &str = "auto_shuffle_after_adds";
+
+/// Largest allowed `auto_shuffle_after_adds`
+const AUTO_SHUFFLE_AFTER_ADDS_MAX: u32 = 100;
+
+/// Parse an `auto_shuffle_after_adds` value, None if invalid or out of range
+fn parse_auto_shuffle_after_adds(value: &str) -> Option<u32> {
+    value
+        .trim()
+        .parse::<u32>()
+        .ok()
+        .filter(|adds| *adds <= AUTO_SHUFFLE_AFTER_ADDS_MAX)
+}
+
+/// Number of added songs after which the queue is fair shuffled (0 = off, the default)
+pub(crate) fn auto_shuffle_after_adds(db: &crate::db::Database) -> u32 {
+    match db.get_setting(AUTO_SHUFFLE_AFTER_ADDS_KEY) {
+        Ok(value) => value.as_deref().and_then(parse_auto_shuffle_after_adds).unwrap_or(0),
+        Err(e) => {
+            log::warn!("Failed to read {}: {}", AUTO_SHUFFLE_AFTER_ADDS_KEY, e);
+            0
+        }
+    }
+}
+
+/// Settings key for adjusting singer colors to readable contrast
+pub(crate) const ENFORCE_CONTRAST_KEY: &str = "enforce_contrast";
+
+/// Whether singer colors are adjusted to WCAG contrast against the app background (default off)
+pub(crate) fn enforce_contrast(db: &crate::db::Database) -> bool {
+    match db.get_setting(ENFORCE_CONTRAST_KEY) {
+        Ok(value) => value.as_deref() == Some("true"),
+        Err(e) => {
+            log::warn!("Failed to read {}: {}", ENFORCE_CONTRAST_KEY, e);
+            false
+        }
+    }
+}
+
+/// Settings key for keeping empty sessions when they end
+pub(crate) const PRESERVE_EMPTY_SESSIONS_KEY: &str = "preserve_empty_sessions";
+
+/// Whether ending a session with no songs or singers archives it instead of deleting it (default off)
+pub(crate) fn preserve_empty_sessions(db: &crate::db::Database) -> bool {
+    match db.get_setting(PRESERVE_EMPTY_SESSIONS_KEY) {
+        Ok(value) => value.as_deref() == Some("true"),
+        Err(e) => {
+            log::warn!("Failed to read {}: {}", PRESERVE_EMPTY_SESSIONS_KEY, e);
+            false
+        }
+    }
+}
+
+/// Settings key for pausing playback when a display disconnects
+#[cfg(target_os = "macos")]
+pub(crate) const PAUSE_ON_DISPLAY_DISCONNECT_KEY: &str = "pause_on_display_disconnect";
+
+/// Whether to pause playback and release keep-awake when a display disconnects (default on)
+#[cfg(target_os = "macos")]
+pub(crate) fn pause_on_display_disconnect(db: &crate::db::Database) -> bool {
+    match db.get_setting(PAUSE_ON_DISPLAY_DISCONNECT_KEY) {
+        Ok(value) => value.as_deref() != Some("false"),
+        Err(e) => {
+            log::warn!("Failed to read {}: {}", PAUSE_ON_DISPLAY_DISCONNECT_KEY, e);
+            true
+        }
+    }
+}
+
+/// Settings key for party mode (destructive menu actions and commands disabled)
+pub(crate) const PARTY_MODE_KEY: &str = "party_mode";
+
+/// Event emitted when party mode is turned on or off
+const PARTY_MODE_CHANGED_EVENT: &str = "party-mode-changed";
+
+/// Whether party mode is on (default off)
+pub(crate) fn is_party_mode(conn: &Connection) -> Result<bool, rusqlite::Error> {
+    let value: Option<String> = conn
+        .query_row(
+            "SELECT value FROM settings WHERE key = ?1",
+            [PARTY_MODE_KEY],
+            |row| row.get(0),
+        )
+        .optional()?;
+    Ok(value.as_deref() == Some("true"))
+}
+
+/// Reject a destructive action while party mode is on
+pub(crate) fn ensure_not_party_mode(conn: &Connection, action: &'static str) -> Result<(), CommandError> {
+    if is_party_mode(conn)? {
+        return Err(CommandError::Forbidden(action));
+    }
+    Ok(())
+}
+
+/// Settings key for a custom user agent sent with external API requests
+pub(crate) const API_USER_AGENT_KEY: &str = "api_user_agent";
+
+/// Load the custom user agent from settings and apply it to new API clients
+pub(crate) fn apply_api_user_agent(db: &crate::db::Database) {
+    match db.get_setting(API_USER_AGENT_KEY) {
+        Ok(Some(value)) => match http_client::validate_user_agent(&value) {
+            Ok(user_agent) => http_client::set_user_agent_override(Some(user_agent)),
+            Err(e) => log::warn!("Ignoring invalid {}: {}", API_USER_AGENT_KEY, e),
+        },
+        Ok(None) => {}
+        Err(e) => log::warn!("Failed to read {}: {}", API_USER_AGENT_KEY, e),
+    }
+}
+
+/// Validate values for settings that have constraints
+fn validate_setting(key: &str, value: &str) -> Result<(), String> {
+    // Changing party mode also updates the menu, so it has its own command
+    if key == PARTY_MODE_KEY {
+        return Err(format!("{} must be changed with set_party_mode", key));
+    }
+    // The user agent is applied to API clients when set, so it has its own command
+    if key == API_USER_AGENT_KEY {
+        return Err(format!("{} must be changed with set_api_user_agent", key));
+    }
+    if key == DEFAULT_SEARCH_RESULTS_KEY && parse_default_search_results(value).is_none() {
+        return Err(format!(
+            "{} must be a number from {} to {}",
+            key,
+            SEARCH_RESULTS_RANGE.start(),
+            SEARCH_RESULTS_RANGE.end()
+        ));
+    }
+    if key == AUTO_SHUFFLE_AFTER_ADDS_KEY && parse_auto_shuffle_after_adds(value).is_none() {
+        return Err(format!(
+            "{} must be a number from 0 to {}",
+            key, AUTO_SHUFFLE_AFTER_ADDS_MAX
+        ));
+    }
+    Ok(())
+}
+
 /// Get the current debug mode state
 #[tauri::command]
 pub fn get_debug_mode(state: State<'_, AppState>) -> bool {
@@ -56,12 +221,85 @@ pub fn set_debug_mode(state: State<'_, AppState>, enabled: bool) {
     }
 }
 
+/// Get the current log level of each target (stdout, webview, file)
+#[tauri::command]
+pub fn get_log_levels() -> LogLevels {
+    log_levels::levels()
+}
+
+/// Change how verbose one log target is, e.g. "debug" on stdout while investigating an issue.
+/// Takes effect immediately and lasts until the app restarts.
+#[tauri::command]
+pub fn set_log_level(target: LogTarget, level: String) -> Result<(), CommandError> {
+    let level = log_levels::parse_level(&level).map_err(CommandError::Validation)?;
+    log_levels::set_level(target, level);
+    info!("Log level for {:?} set to {}", target, level);
+    Ok(())
+}
+
 /// Get the log directory path
 #[tauri::command]
 pub fn get_log_path(state: State<'_, AppState>) -> String {
     state.log_dir.to_string_lossy().to_string()
 }
 
+/// Get the current party mode state
+#[tauri::command]
+pub fn get_party_mode(state: State<'_, AppState>) -> Result<bool, CommandError> {
+    let db = state.db.lock().map_lock_err()?;
+    Ok(is_party_mode(db.connection())?)
+}
+
+/// Turn party mode on or off. While on, destructive menu items are disabled and
+/// their commands (clearing the queue or history, saving, loading or deleting
+/// sessions) return a `forbidden` error.
+#[tauri::command]
+pub fn set_party_mode(app: AppHandle, state: State<'_, AppState>, enabled: bool) -> Result<(), CommandError> {
+    info!("Setting party mode: {}", enabled);
+    {
+        let db = state.db.lock().map_lock_err()?;
+        db.set_setting(PARTY_MODE_KEY, if enabled { "true" } else { "false" })?;
+    }
+
+    crate::apply_party_mode_to_menu(&app, enabled);
+    let _ = app.emit(PARTY_MODE_CHANGED_EVENT, enabled);
+    Ok(())
+}
+
+/// Get the user agent sent with MusicBrainz, Lrclib and YouTube API requests
+#[tauri::command]
+pub fn get_api_user_agent() -> String {
+    http_client::user_agent()
+}
+
+/// Set a custom user agent (e.g. with contact info for MusicBrainz) for API requests,
+/// or restore the default with None. Returns the user agent now in use.
+#[tauri::command]
+pub fn set_api_user_agent(
+    state: State<'_, AppState>,
+    user_agent: Option<String>,
+) -> Result<String, CommandError> {
+    let user_agent = user_agent
+        .map(|value| http_client::validate_user_agent(&value))
+        .transpose()
+        .map_err(CommandError::Validation)?;
+    info!("Setting API user agent: {:?}", user_agent);
+
+    {
+        let db = state.db.lock().map_lock_err()?;
+        match &user_agent {
+            Some(value) => db.set_setting(API_USER_AGENT_KEY, value)?,
+            None => {
+                db.connection()
+                    .execute("DELETE FROM settings WHERE key = ?1", [API_USER_AGENT_KEY])?;
+            }
+        }
+    }
+
+    http_client::set_user_agent_override(user_agent);
+    Ok(http_client::user_agent())
+}
+
 /// Get a setting value by key
 #[tauri::command]
 pub fn settings_get(state: State<'_, AppState>, key: String) -> Result<Option<String>, String> {
@@ -76,6 +314,7 @@ pub fn settings_get(state: State<'_, AppState>, key: String) -> Result<Option<St
 #[tauri::command]
 pub fn settings_set(state: State<'_, AppState>, key: String, value: String) -> Result<(), String> {
     debug!("settings_set called for key: {} = {}", key, value);
+    validate_setting(&key, &value)?;
     match state.db.lock() {
         Ok(db) => db.set_setting(&key, &value).map_err(|e| e.to_string()),
         Err(e) => Err(format!("Failed to acquire database lock: {}", e)),
@@ -164,6 +403,9 @@ pub fn settings_reset_all(
             return Err(format!("Invalid setting key: {}", key));
         }
     }
+    for (key, value) in defaults.iter() {
+        validate_setting(key, value)?;
+    }
 
     match state.db.lock() {
         Ok(mut db) => {
@@ -194,3 +436,29 @@ pub fn settings_reset_all(
         )),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_default_search_results_range() {
+        assert_eq!(parse_default_search_results("1"), Some(1));
+        assert_eq!(parse_default_search_results(" 25 "), Some(25));
+        assert_eq!(parse_default_search_results("50"), Some(50));
+        assert_eq!(parse_default_search_results("0"), None);
+        assert_eq!(parse_default_search_results("51"), None);
+        assert_eq!(parse_default_search_results("ten"), None);
+    }
+
+    #[test]
+    fn test_validate_setting() {
+        assert!(validate_setting(DEFAULT_SEARCH_RESULTS_KEY, "20").is_ok());
+        assert!(validate_setting(DEFAULT_SEARCH_RESULTS_KEY, "100").is_err());
+        // Keys without constraints accept any value
+        assert!(validate_setting("video_quality", "anything").is_ok());
+        // Party mode and the API user agent have their own commands
+        assert!(validate_setting(PARTY_MODE_KEY, "true").is_err());
+        assert!(validate_setting(API_USER_AGENT_KEY, "MyKaraoke/1.0").is_err());
+    }
+}
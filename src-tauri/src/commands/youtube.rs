@@ -1,11 +1,26 @@
 use crate::services::{
-    get_expanded_path,
-    ytdlp::{SearchResult, StreamInfo, VideoInfo},
+    canonical_video_id, get_expanded_path,
+    search_classifier::is_karaoke_friendly,
+    stream_cache::{self, CachedStream},
+    youtube_api::MAX_VIDEO_IDS_PER_REQUEST,
+    ytdlp::{
+        classify_stderr, Chapter, SearchResult, StreamFormat, StreamInfo, VideoInfo, YtDlpError,
+        YtDlpFailureKind,
+    },
     YouTubeApiService, YtDlpService,
 };
+use super::errors::LockRecoverExt;
+use super::settings::{
+    parse_default_search_results, DEFAULT_SEARCH_RESULTS_KEY, SEARCH_RESULTS_RANGE,
+};
+use crate::db::Database;
 use crate::AppState;
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
 use tauri::State;
 use thiserror::Error;
 
@@ -57,32 +72,320 @@ impl Serialize for YouTubeError {
     }
 }
 
+/// Backend that served a YouTube search
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum YouTubeSearchSource {
+    Api,
+    Ytdlp,
+}
+
+/// YouTube search results with pagination metadata
+#[derive(Debug, Serialize, Deserialize)]
+pub struct YouTubeSearchResponse {
+    pub results: Vec<SearchResult>,
+    pub source: YouTubeSearchSource,
+    /// Whether more results are likely available
+    pub has_more: bool,
+    /// Token for fetching the next page (YouTube API only)
+    pub next_page_token: Option<String>,
+}
+
+impl YouTubeSearchResponse {
+    /// Build a response from a YouTube Data API page
+    fn from_api(results: Vec<SearchResult>, next_page_token: Option<String>) -> Self {
+        Self {
+            results,
+            source: YouTubeSearchSource::Api,
+            has_more: next_page_token.is_some(),
+            next_page_token,
+        }
+    }
+
+    /// Build a response from a yt-dlp search
+    /// yt-dlp has no page tokens; a full result set means more results may exist
+    /// (the caller can re-search with a larger `max_results`).
+    fn from_ytdlp(results: Vec<SearchResult>, max_results: u32) -> Self {
+        Self {
+            has_more: results.len() >= max_results as usize && max_results > 0,
+            results,
+            source: YouTubeSearchSource::Ytdlp,
+            next_page_token: None,
+        }
+    }
+}
+
+/// Settings key holding the search blocklist as a JSON array
+const BLOCKLIST_SETTING_KEY: &str = "youtube_blocklist";
+
+/// What a blocklist entry is matched against
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum YouTubeBlockKind {
+    /// Matches the result's channel name
+    Channel,
+    /// Matches the result's title
+    Keyword,
+}
+
+/// A blocklist entry hiding matching YouTube search results
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct YouTubeBlock {
+    pub pattern: String,
+    pub kind: YouTubeBlockKind,
+}
+
+impl YouTubeBlock {
+    /// Case-insensitive substring match against the channel or title
+    fn matches(&self, result: &SearchResult) -> bool {
+        let pattern = self.pattern.to_lowercase();
+        let haystack = match self.kind {
+            YouTubeBlockKind::Channel => &result.channel,
+            YouTubeBlockKind::Keyword => &result.title,
+        };
+        haystack.to_lowercase().contains(&pattern)
+    }
+}
+
+/// Remove search results matching any blocklist entry
+fn filter_blocked(results: Vec<SearchResult>, blocks: &[YouTubeBlock]) -> Vec<SearchResult> {
+    if blocks.is_empty() {
+        return results;
+    }
+    results
+        .into_iter()
+        .filter(|result| !blocks.iter().any(|block| block.matches(result)))
+        .collect()
+}
+
+/// Drop results that look like original recordings when only karaoke-friendly results are wanted
+fn filter_karaoke_only(results: Vec<SearchResult>, karaoke_only: bool) -> Vec<SearchResult> {
+    if !karaoke_only {
+        return results;
+    }
+    results.into_iter().filter(is_karaoke_friendly).collect()
+}
+
+/// Load the blocklist from settings (empty if unset or unreadable)
+fn load_blocklist(db: &Database) -> Result<Vec<YouTubeBlock>, YouTubeError> {
+    let json = db
+        .get_setting(BLOCKLIST_SETTING_KEY)
+        .map_err(|e| YouTubeError::Config(format!("Failed to get blocklist: {}", e)))?;
+
+    match json {
+        Some(json) => Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            warn!("Ignoring malformed YouTube blocklist: {}", e);
+            Vec::new()
+        })),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Search result count used when neither the caller nor settings specify one
+const BUILTIN_SEARCH_RESULTS: u32 = 10;
+
+/// Pick the result count: explicit argument (clamped to the allowed range), then the
+/// `default_search_results` setting (ignored if invalid), then the built-in default.
+fn pick_max_results(explicit: Option<u32>, setting: Option<&str>) -> u32 {
+    explicit
+        .map(|count| count.clamp(*SEARCH_RESULTS_RANGE.start(), *SEARCH_RESULTS_RANGE.end()))
+        .or_else(|| setting.and_then(parse_default_search_results))
+        .unwrap_or(BUILTIN_SEARCH_RESULTS)
+}
+
+/// Resolve the result count for a search, reading the setting if no count was given
+pub(crate) fn resolve_max_results(explicit: Option<u32>, db: &Database) -> u32 {
+    let setting = match explicit {
+        Some(_) => None,
+        None => db.get_setting(DEFAULT_SEARCH_RESULTS_KEY).unwrap_or_else(|e| {
+            warn!("Failed to read {}: {}", DEFAULT_SEARCH_RESULTS_KEY, e);
+            None
+        }),
+    };
+    pick_max_results(explicit, setting.as_deref())
+}
+
+/// Whether a failed API search may be retried with yt-dlp
+///
+/// yt-dlp can't continue an API page token, so a failed "load more" request
+/// would only return the first page again.
+fn can_fall_back_to_ytdlp(page_token: Option<&str>) -> bool {
+    page_token.is_none()
+}
+
+/// Search YouTube using the configured backend
+///
+/// Uses the YouTube Data API when a key is configured (and the search method
+/// isn't "ytdlp"), falling back to yt-dlp if the API request fails. A failed
+/// request for a later page returns the error instead.
+/// With `karaoke_only`, results that look like original recordings (music videos,
+/// lyric videos, live performances) are dropped; unclassified results are kept.
 #[tauri::command]
 pub async fn youtube_search(
+    state: State<'_, AppState>,
     query: String,
     max_results: Option<u32>,
-) -> Result<Vec<SearchResult>, YouTubeError> {
-    let max = max_results.unwrap_or(10);
-    debug!("youtube_search: query='{}', max_results={}", query, max);
+    page_token: Option<String>,
+    karaoke_only: Option<bool>,
+) -> Result<YouTubeSearchResponse, YouTubeError> {
+    let karaoke_only = karaoke_only.unwrap_or(false);
+    // SECURITY: Never log the API key - it should remain secret
+    let (search_method, api_key, blocks, max) = {
+        let db = state.db.lock().map_err(|e| {
+            log::error!("Database mutex poisoned: {}", e);
+            YouTubeError::Config("Database error - please restart the app".to_string())
+        })?;
+        let method = db
+            .get_setting("youtube_search_method")
+            .map_err(|e| YouTubeError::Config(format!("Failed to get search method: {}", e)))?;
+        let key = db
+            .get_setting("youtube_api_key")
+            .map_err(|e| YouTubeError::Config(format!("Failed to get API key: {}", e)))?;
+        let max = resolve_max_results(max_results, &db);
+        (method, key, load_blocklist(&db)?, max)
+    };
+    debug!(
+        "youtube_search: query='{}', max_results={}, karaoke_only={}",
+        query, max, karaoke_only
+    );
+
+    // Use the API unless yt-dlp was explicitly chosen or no key is configured
+    let api_key = api_key
+        .filter(|k| !k.trim().is_empty() && search_method.as_deref() != Some("ytdlp"));
+
+    if let Some(api_key) = api_key {
+        let service = YouTubeApiService::new(api_key).map_err(YouTubeError::Config)?;
+        match service.search_page(&query, max, page_token.as_deref()).await {
+            Ok((results, next_page_token)) => {
+                info!("youtube_search: API found {} results for '{}'", results.len(), query);
+                let mut response = YouTubeSearchResponse::from_api(results, next_page_token);
+                response.results =
+                    filter_karaoke_only(filter_blocked(response.results, &blocks), karaoke_only);
+                return Ok(response);
+            }
+            Err(e) => {
+                if !can_fall_back_to_ytdlp(page_token.as_deref()) {
+                    warn!("youtube_search: API search for next page failed: {}", e);
+                    return Err(e.into());
+                }
+                warn!("youtube_search: API search failed, falling back to yt-dlp: {}", e);
+                let service = YtDlpService::new();
+                if !service.is_available().await {
+                    return Err(e.into());
+                }
+            }
+        }
+    }
 
     let service = YtDlpService::new();
     let results = service.search(&query, max).await?;
 
-    info!("youtube_search: found {} results for '{}'", results.len(), query);
-    Ok(results)
+    info!("youtube_search: yt-dlp found {} results for '{}'", results.len(), query);
+    // Filter after computing has_more so blocked results don't hide further pages
+    let mut response = YouTubeSearchResponse::from_ytdlp(results, max);
+    response.results = filter_karaoke_only(filter_blocked(response.results, &blocks), karaoke_only);
+    Ok(response)
 }
 
+/// Add an entry to the YouTube search blocklist
+///
+/// Duplicate entries (same kind, case-insensitive pattern) are ignored.
+/// Returns the updated blocklist.
 #[tauri::command]
-pub async fn youtube_get_stream_url(video_id: String) -> Result<StreamInfo, YouTubeError> {
-    debug!("youtube_get_stream_url: video_id='{}'", video_id);
+pub fn youtube_add_block(
+    state: State<'_, AppState>,
+    pattern: String,
+    kind: YouTubeBlockKind,
+) -> Result<Vec<YouTubeBlock>, YouTubeError> {
+    let pattern = pattern.trim().to_string();
+    if pattern.is_empty() {
+        return Err(YouTubeError::Config("Block pattern cannot be empty".to_string()));
+    }
+    debug!("youtube_add_block: kind={:?}, pattern='{}'", kind, pattern);
+
+    let db = state.db.lock().map_err(|e| {
+        log::error!("Database mutex poisoned: {}", e);
+        YouTubeError::Config("Database error - please restart the app".to_string())
+    })?;
+
+    let mut blocks = load_blocklist(&db)?;
+    let exists = blocks
+        .iter()
+        .any(|b| b.kind == kind && b.pattern.to_lowercase() == pattern.to_lowercase());
+    if !exists {
+        blocks.push(YouTubeBlock { pattern, kind });
+        let json = serde_json::to_string(&blocks)
+            .map_err(|e| YouTubeError::Config(format!("Failed to serialize blocklist: {}", e)))?;
+        db.set_setting(BLOCKLIST_SETTING_KEY, &json)
+            .map_err(|e| YouTubeError::Config(format!("Failed to save blocklist: {}", e)))?;
+        info!("youtube_add_block: blocklist now has {} entries", blocks.len());
+    }
+
+    Ok(blocks)
+}
+
+/// List the YouTube search blocklist
+#[tauri::command]
+pub fn youtube_list_blocks(state: State<'_, AppState>) -> Result<Vec<YouTubeBlock>, YouTubeError> {
+    let db = state.db.lock().map_err(|e| {
+        log::error!("Database mutex poisoned: {}", e);
+        YouTubeError::Config("Database error - please restart the app".to_string())
+    })?;
+    load_blocklist(&db)
+}
+
+#[tauri::command]
+pub async fn youtube_get_stream_url(
+    video_id: String,
+    format_id: Option<String>,
+) -> Result<StreamInfo, YouTubeError> {
+    debug!("youtube_get_stream_url: video_id='{}', format_id={:?}", video_id, format_id);
 
     let service = YtDlpService::new();
-    let stream_info = service.get_stream_url(&video_id).await?;
+    let stream_info = stream_cache::cached_stream_url(&service, &video_id, format_id.as_deref()).await?;
 
     info!("youtube_get_stream_url: got stream URL for '{}'", video_id);
     Ok(stream_info)
 }
 
+/// List cached stream URLs (video, resolve and expiry times; not the signed URLs)
+#[tauri::command]
+pub fn youtube_stream_cache_status() -> Vec<CachedStream> {
+    stream_cache::cached_streams()
+}
+
+/// Drop cached stream URLs so the next playback re-resolves them. Returns entries removed.
+#[tauri::command]
+pub fn youtube_clear_stream_cache() -> usize {
+    let cleared = stream_cache::clear_stream_cache();
+    info!("youtube_clear_stream_cache: cleared {} cached stream URLs", cleared);
+    cleared
+}
+
+/// List the playable stream formats (qualities) for a video
+#[tauri::command]
+pub async fn youtube_list_formats(video_id: String) -> Result<Vec<StreamFormat>, YouTubeError> {
+    debug!("youtube_list_formats: video_id='{}'", video_id);
+
+    let service = YtDlpService::new();
+    let formats = service.list_formats(&video_id).await?;
+
+    info!("youtube_list_formats: {} formats for '{}'", formats.len(), video_id);
+    Ok(formats)
+}
+
+/// Get a video's chapters for karaoke section markers (empty if none are defined)
+#[tauri::command]
+pub async fn youtube_get_chapters(video_id: String) -> Result<Vec<Chapter>, YouTubeError> {
+    debug!("youtube_get_chapters: video_id='{}'", video_id);
+
+    let service = YtDlpService::new();
+    let chapters = service.get_chapters(&video_id).await?;
+
+    info!("youtube_get_chapters: {} chapters for '{}'", chapters.len(), video_id);
+    Ok(chapters)
+}
+
 #[tauri::command]
 pub async fn youtube_get_info(video_id: String) -> Result<VideoInfo, YouTubeError> {
     debug!("youtube_get_info: video_id='{}'", video_id);
@@ -94,6 +397,172 @@ pub async fn youtube_get_info(video_id: String) -> Result<VideoInfo, YouTubeErro
     Ok(video_info)
 }
 
+/// Maximum number of video IDs accepted by one batch info request
+const MAX_INFO_BATCH_SIZE: usize = 500;
+
+/// Number of yt-dlp processes run at once when fetching info without an API key
+const YTDLP_INFO_CONCURRENCY: usize = 4;
+
+/// Info lookup result for one requested video ID
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoInfoBatchEntry {
+    /// The ID as it was requested
+    pub video_id: String,
+    pub info: Option<VideoInfo>,
+    pub error: Option<String>,
+}
+
+impl VideoInfoBatchEntry {
+    fn new(video_id: &str, result: Result<VideoInfo, String>) -> Self {
+        let (info, error) = match result {
+            Ok(info) => (Some(info), None),
+            Err(e) => (None, Some(e)),
+        };
+        Self {
+            video_id: video_id.to_string(),
+            info,
+            error,
+        }
+    }
+}
+
+/// Fetch info for `video_ids` in chunks of `batch_size` unique IDs, one chunk at a time.
+/// Entries are returned in input order; invalid IDs are not fetched and IDs missing from
+/// the fetched results are reported as not found.
+async fn fetch_info_in_batches<F, Fut>(
+    video_ids: &[String],
+    batch_size: usize,
+    mut fetch: F,
+) -> Vec<VideoInfoBatchEntry>
+where
+    F: FnMut(Vec<String>) -> Fut,
+    Fut: Future<Output = HashMap<String, Result<VideoInfo, String>>>,
+{
+    let canonical: Vec<Option<String>> =
+        video_ids.iter().map(|id| canonical_video_id(id)).collect();
+
+    let mut unique: Vec<String> = Vec::new();
+    for id in canonical.iter().flatten() {
+        if !unique.contains(id) {
+            unique.push(id.clone());
+        }
+    }
+
+    let mut fetched = HashMap::new();
+    for chunk in unique.chunks(batch_size.max(1)) {
+        fetched.extend(fetch(chunk.to_vec()).await);
+    }
+
+    video_ids
+        .iter()
+        .zip(canonical)
+        .map(|(input, id)| {
+            let result = match id {
+                None => Err("Invalid video ID".to_string()),
+                Some(id) => fetched
+                    .get(&id)
+                    .cloned()
+                    .unwrap_or_else(|| Err("Video not found or unavailable".to_string())),
+            };
+            VideoInfoBatchEntry::new(input, result)
+        })
+        .collect()
+}
+
+/// Fetch info for one chunk of IDs with concurrent yt-dlp calls
+async fn fetch_info_with_ytdlp(video_ids: Vec<String>) -> HashMap<String, Result<VideoInfo, String>> {
+    let mut tasks = tokio::task::JoinSet::new();
+    for video_id in video_ids {
+        tasks.spawn(async move {
+            let result = YtDlpService::new()
+                .get_video_info(&video_id)
+                .await
+                .map_err(|e| e.to_string());
+            (video_id, result)
+        });
+    }
+
+    let mut results = HashMap::new();
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((video_id, result)) => {
+                results.insert(video_id, result);
+            }
+            Err(e) => warn!("youtube_get_info_batch: yt-dlp task failed: {}", e),
+        }
+    }
+    results
+}
+
+/// Get info for many videos at once, in input order.
+///
+/// Uses the Data API (50 IDs per request) when a key is configured, otherwise
+/// runs a few yt-dlp lookups at a time. Failed lookups are returned as entries
+/// with an error rather than failing the whole batch.
+#[tauri::command]
+pub async fn youtube_get_info_batch(
+    state: State<'_, AppState>,
+    video_ids: Vec<String>,
+) -> Result<Vec<VideoInfoBatchEntry>, YouTubeError> {
+    debug!("youtube_get_info_batch: {} video ids", video_ids.len());
+
+    if video_ids.len() > MAX_INFO_BATCH_SIZE {
+        return Err(YouTubeError::Config(format!(
+            "Too many video IDs (max {})",
+            MAX_INFO_BATCH_SIZE
+        )));
+    }
+
+    // SECURITY: Never log the API key - it should remain secret
+    let (search_method, api_key) = {
+        let db = state.db.lock().map_err(|e| {
+            log::error!("Database mutex poisoned: {}", e);
+            YouTubeError::Config("Database error - please restart the app".to_string())
+        })?;
+        let method = db
+            .get_setting("youtube_search_method")
+            .map_err(|e| YouTubeError::Config(format!("Failed to get search method: {}", e)))?;
+        let key = db
+            .get_setting("youtube_api_key")
+            .map_err(|e| YouTubeError::Config(format!("Failed to get API key: {}", e)))?;
+        (method, key)
+    };
+
+    // Use the API unless yt-dlp was explicitly chosen or no key is configured
+    let api_key = api_key
+        .filter(|k| !k.trim().is_empty() && search_method.as_deref() != Some("ytdlp"));
+
+    let entries = match api_key {
+        Some(api_key) => {
+            let service = YouTubeApiService::new(api_key).map_err(YouTubeError::Config)?;
+            let service = &service;
+            fetch_info_in_batches(&video_ids, MAX_VIDEO_IDS_PER_REQUEST, move |ids| async move {
+                let id_refs: Vec<&str> = ids.iter().map(String::as_str).collect();
+                let results: HashMap<String, Result<VideoInfo, String>> =
+                    match service.fetch_video_infos(&id_refs).await {
+                        Ok(infos) => infos.into_iter().map(|(id, info)| (id, Ok(info))).collect(),
+                        Err(e) => {
+                            warn!("youtube_get_info_batch: API request failed: {}", e);
+                            ids.iter().map(|id| (id.clone(), Err(e.to_string()))).collect()
+                        }
+                    };
+                results
+            })
+            .await
+        }
+        None => {
+            fetch_info_in_batches(&video_ids, YTDLP_INFO_CONCURRENCY, fetch_info_with_ytdlp).await
+        }
+    };
+
+    info!(
+        "youtube_get_info_batch: got info for {} of {} videos",
+        entries.iter().filter(|entry| entry.info.is_some()).count(),
+        entries.len()
+    );
+    Ok(entries)
+}
+
 #[tauri::command]
 pub async fn youtube_check_available() -> Result<bool, YouTubeError> {
     debug!("youtube_check_available: checking yt-dlp availability");
@@ -105,6 +574,91 @@ pub async fn youtube_check_available() -> Result<bool, YouTubeError> {
     Ok(available)
 }
 
+/// Benign reference query used to check that yt-dlp search still works
+const HEALTH_CHECK_QUERY: &str = "karaoke";
+
+/// How long a health check result is reused before searching again
+const HEALTH_CHECK_CACHE_TTL: Duration = Duration::from_secs(5 * 60);
+
+/// Last health check result and when it was taken
+static HEALTH_CHECK_CACHE: Mutex<Option<(Instant, YouTubeHealth)>> = Mutex::new(None);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum YouTubeHealthStatus {
+    Healthy,
+    /// yt-dlp can't extract YouTube any more; prompt the user to update it
+    NeedsUpdate,
+    NetworkError,
+    NotInstalled,
+    /// Search failed for a reason that couldn't be classified
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct YouTubeHealth {
+    pub status: YouTubeHealthStatus,
+    /// Error message from the failed reference search
+    pub message: Option<String>,
+    pub checked_at: String,
+}
+
+impl YouTubeHealth {
+    /// Classify the outcome of the reference search
+    fn from_search_result(result: Result<Vec<SearchResult>, YtDlpError>) -> Self {
+        let (status, message) = match result {
+            Ok(_) => (YouTubeHealthStatus::Healthy, None),
+            Err(YtDlpError::NotFound) => (YouTubeHealthStatus::NotInstalled, None),
+            // A fixed popular query with no (parseable) results means YouTube's output changed
+            Err(e @ YtDlpError::NoResults) | Err(e @ YtDlpError::ParseError(_)) => {
+                (YouTubeHealthStatus::NeedsUpdate, Some(e.to_string()))
+            }
+            Err(YtDlpError::ExecutionError(stderr)) => {
+                let status = match classify_stderr(&stderr) {
+                    YtDlpFailureKind::NeedsUpdate => YouTubeHealthStatus::NeedsUpdate,
+                    YtDlpFailureKind::Network => YouTubeHealthStatus::NetworkError,
+                    YtDlpFailureKind::Other => YouTubeHealthStatus::Unknown,
+                };
+                (status, Some(stderr.trim().to_string()))
+            }
+        };
+
+        Self {
+            status,
+            message,
+            checked_at: chrono::Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+/// Cached health result if it is younger than the TTL
+fn fresh_health(cache: &Option<(Instant, YouTubeHealth)>, now: Instant) -> Option<YouTubeHealth> {
+    cache
+        .as_ref()
+        .filter(|(checked, _)| now.duration_since(*checked) < HEALTH_CHECK_CACHE_TTL)
+        .map(|(_, health)| health.clone())
+}
+
+/// Check whether yt-dlp search still works by running a small reference search.
+/// Results are cached for a few minutes; pass `force` to re-check immediately.
+#[tauri::command]
+pub async fn youtube_health_check(force: Option<bool>) -> Result<YouTubeHealth, YouTubeError> {
+    if !force.unwrap_or(false) {
+        let cache = HEALTH_CHECK_CACHE.lock_recover("YouTube health cache");
+        if let Some(health) = fresh_health(&cache, Instant::now()) {
+            debug!("youtube_health_check: using cached status {:?}", health.status);
+            return Ok(health);
+        }
+    }
+
+    let service = YtDlpService::new();
+    let health = YouTubeHealth::from_search_result(service.search(HEALTH_CHECK_QUERY, 1).await);
+    info!("youtube_health_check: status={:?}", health.status);
+
+    *HEALTH_CHECK_CACHE.lock_recover("YouTube health cache") = Some((Instant::now(), health.clone()));
+    Ok(health)
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct InstallResult {
     pub success: bool,
@@ -321,16 +875,15 @@ pub async fn youtube_api_search(
 
     // Get API key from settings
     // SECURITY: Never log the API key - it should remain secret
-    let api_key = {
-        let db = state
-            .db
-            .lock()
-            .map_err(|e| {
-                log::error!("Database mutex poisoned: {}", e);
-                YouTubeError::Config("Database error - please restart the app".to_string())
-            })?;
-        db.get_setting("youtube_api_key")
-            .map_err(|e| YouTubeError::Config(format!("Failed to get API key: {}", e)))?
+    let (api_key, blocks) = {
+        let db = state.db.lock().map_err(|e| {
+            log::error!("Database mutex poisoned: {}", e);
+            YouTubeError::Config("Database error - please restart the app".to_string())
+        })?;
+        let key = db
+            .get_setting("youtube_api_key")
+            .map_err(|e| YouTubeError::Config(format!("Failed to get API key: {}", e)))?;
+        (key, load_blocklist(&db)?)
     };
 
     // Validate API key exists and is not empty (combined check)
@@ -348,7 +901,7 @@ pub async fn youtube_api_search(
         results.len(),
         query
     );
-    Ok(results)
+    Ok(filter_blocked(results, &blocks))
 }
 
 /// Validate the currently saved YouTube API key
@@ -363,13 +916,10 @@ pub async fn youtube_validate_api_key(
 
     // Read API key from database (not from parameter for security)
     let api_key = {
-        let db = state
-            .db
-            .lock()
-            .map_err(|e| {
-                log::error!("Database mutex poisoned: {}", e);
-                YouTubeError::Config("Database error - please restart the app".to_string())
-            })?;
+        let db = state.db.lock().map_err(|e| {
+            log::error!("Database mutex poisoned: {}", e);
+            YouTubeError::Config("Database error - please restart the app".to_string())
+        })?;
         db.get_setting("youtube_api_key")
             .map_err(|e| YouTubeError::Config(format!("Failed to get API key: {}", e)))?
     };
@@ -443,3 +993,242 @@ pub async fn youtube_get_search_method(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn info(id: &str) -> VideoInfo {
+        VideoInfo {
+            id: id.to_string(),
+            title: format!("Title {}", id),
+            channel: "Channel".to_string(),
+            duration: Some(200),
+            thumbnail: None,
+            description: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_info_in_batches_chunks_and_preserves_order() {
+        let ids: Vec<String> = [
+            "ccccccccccc",
+            "not a video id",
+            "aaaaaaaaaaa",
+            "https://www.youtube.com/watch?v=bbbbbbbbbbb",
+            "ccccccccccc",
+            "ddddddddddd",
+        ]
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+
+        let mut calls: Vec<Vec<String>> = Vec::new();
+        let entries = fetch_info_in_batches(&ids, 2, |chunk| {
+            calls.push(chunk.clone());
+            async move {
+                chunk
+                    .into_iter()
+                    .filter_map(|id| match id.as_str() {
+                        // Missing from the response
+                        "ddddddddddd" => None,
+                        "aaaaaaaaaaa" => Some((id.clone(), Err("Private video".to_string()))),
+                        _ => Some((id.clone(), Ok(info(&id)))),
+                    })
+                    .collect::<HashMap<_, _>>()
+            }
+        })
+        .await;
+
+        // Unique valid IDs, in first-seen order, chunked by the batch size
+        assert_eq!(
+            calls,
+            vec![
+                vec!["ccccccccccc".to_string(), "aaaaaaaaaaa".to_string()],
+                vec!["bbbbbbbbbbb".to_string(), "ddddddddddd".to_string()],
+            ]
+        );
+
+        let requested: Vec<&str> = entries.iter().map(|e| e.video_id.as_str()).collect();
+        assert_eq!(requested, ids.iter().map(String::as_str).collect::<Vec<_>>());
+
+        assert_eq!(entries[0].info.as_ref().unwrap().id, "ccccccccccc");
+        assert_eq!(entries[1].error.as_deref(), Some("Invalid video ID"));
+        assert_eq!(entries[2].error.as_deref(), Some("Private video"));
+        assert_eq!(entries[3].info.as_ref().unwrap().id, "bbbbbbbbbbb");
+        assert_eq!(entries[4].info.as_ref().unwrap().id, "ccccccccccc");
+        assert!(entries[5].info.is_none());
+        assert_eq!(entries[5].error.as_deref(), Some("Video not found or unavailable"));
+    }
+
+    #[test]
+    fn test_health_from_search_result() {
+        assert_eq!(
+            YouTubeHealth::from_search_result(Ok(vec![])).status,
+            YouTubeHealthStatus::Healthy
+        );
+        assert_eq!(
+            YouTubeHealth::from_search_result(Err(YtDlpError::NotFound)).status,
+            YouTubeHealthStatus::NotInstalled
+        );
+
+        let health = YouTubeHealth::from_search_result(Err(YtDlpError::ExecutionError(
+            "ERROR: [youtube] Unable to extract initial data\n".to_string(),
+        )));
+        assert_eq!(health.status, YouTubeHealthStatus::NeedsUpdate);
+        assert_eq!(health.message.as_deref(), Some("ERROR: [youtube] Unable to extract initial data"));
+
+        assert_eq!(
+            YouTubeHealth::from_search_result(Err(YtDlpError::ExecutionError(
+                "ERROR: Unable to download webpage: timed out".to_string()
+            )))
+            .status,
+            YouTubeHealthStatus::NetworkError
+        );
+        assert_eq!(
+            YouTubeHealth::from_search_result(Err(YtDlpError::ExecutionError("boom".to_string()))).status,
+            YouTubeHealthStatus::Unknown
+        );
+    }
+
+    #[test]
+    fn test_fresh_health_expires_after_ttl() {
+        let checked = Instant::now();
+        let cache = Some((checked, YouTubeHealth::from_search_result(Ok(vec![]))));
+
+        assert!(fresh_health(&cache, checked + Duration::from_secs(60)).is_some());
+        assert!(fresh_health(&cache, checked + HEALTH_CHECK_CACHE_TTL).is_none());
+        assert!(fresh_health(&None, checked).is_none());
+    }
+
+    fn result(id: &str) -> SearchResult {
+        SearchResult {
+            id: id.to_string(),
+            title: format!("Song {}", id),
+            channel: "Karaoke Channel".to_string(),
+            duration: Some(200),
+            thumbnail: None,
+            view_count: None,
+        }
+    }
+
+    #[test]
+    fn test_response_from_api_with_next_page() {
+        let response = YouTubeSearchResponse::from_api(
+            vec![result("a"), result("b")],
+            Some("CAoQAA".to_string()),
+        );
+        assert_eq!(response.source, YouTubeSearchSource::Api);
+        assert!(response.has_more);
+        assert_eq!(response.next_page_token, Some("CAoQAA".to_string()));
+        assert_eq!(response.results.len(), 2);
+    }
+
+    #[test]
+    fn test_response_from_api_last_page() {
+        let response = YouTubeSearchResponse::from_api(vec![result("a")], None);
+        assert!(!response.has_more);
+        assert_eq!(response.next_page_token, None);
+    }
+
+    #[test]
+    fn test_response_from_ytdlp_full_page_has_more() {
+        let response = YouTubeSearchResponse::from_ytdlp(vec![result("a"), result("b")], 2);
+        assert_eq!(response.source, YouTubeSearchSource::Ytdlp);
+        assert!(response.has_more);
+        assert_eq!(response.next_page_token, None);
+    }
+
+    #[test]
+    fn test_response_from_ytdlp_partial_page() {
+        let response = YouTubeSearchResponse::from_ytdlp(vec![result("a")], 10);
+        assert!(!response.has_more);
+        assert!(YouTubeSearchResponse::from_ytdlp(Vec::new(), 0).results.is_empty());
+    }
+
+    #[test]
+    fn test_only_first_page_falls_back_to_ytdlp() {
+        assert!(can_fall_back_to_ytdlp(None));
+        assert!(!can_fall_back_to_ytdlp(Some("CAoQAA")));
+    }
+
+    #[test]
+    fn test_filter_blocked_removes_matching_channels_and_keywords() {
+        let mut results = vec![result("a"), result("b"), result("c"), result("d")];
+        results[1].channel = "Sing King Karaoke".to_string();
+        results[2].title = "Bohemian Rhapsody (LYRICS ONLY, No Music)".to_string();
+        results[3].channel = "Kingdom Hearts OST".to_string();
+
+        let blocks = vec![
+            YouTubeBlock {
+                pattern: "sing king".to_string(),
+                kind: YouTubeBlockKind::Channel,
+            },
+            YouTubeBlock {
+                pattern: "Lyrics Only".to_string(),
+                kind: YouTubeBlockKind::Keyword,
+            },
+        ];
+
+        let filtered = filter_blocked(results, &blocks);
+        let ids: Vec<&str> = filtered.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, vec!["a", "d"]);
+    }
+
+    #[test]
+    fn test_filter_blocked_keyword_does_not_match_channel() {
+        let blocks = vec![YouTubeBlock {
+            pattern: "karaoke channel".to_string(),
+            kind: YouTubeBlockKind::Keyword,
+        }];
+        let filtered = filter_blocked(vec![result("a")], &blocks);
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filter_blocked(vec![result("a")], &[]).len(), 1);
+    }
+
+    #[test]
+    fn test_block_kind_serializes_lowercase() {
+        let block = YouTubeBlock {
+            pattern: "x".to_string(),
+            kind: YouTubeBlockKind::Channel,
+        };
+        let json = serde_json::to_string(&vec![block.clone()]).unwrap();
+        assert_eq!(json, r#"[{"pattern":"x","kind":"channel"}]"#);
+        let parsed: Vec<YouTubeBlock> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, vec![block]);
+    }
+
+    #[test]
+    fn test_response_serializes_source_lowercase() {
+        let response = YouTubeSearchResponse::from_ytdlp(vec![result("a")], 10);
+        let json = serde_json::to_value(response).unwrap();
+        assert_eq!(json["source"], "ytdlp");
+        assert_eq!(json["has_more"], false);
+        assert!(json["next_page_token"].is_null());
+        assert_eq!(json["results"][0]["id"], "a");
+    }
+
+    #[test]
+    fn test_max_results_precedence() {
+        // Explicit argument wins over the setting
+        assert_eq!(pick_max_results(Some(5), Some("25")), 5);
+        // Explicit counts outside 1-50 are clamped
+        assert_eq!(pick_max_results(Some(0), Some("25")), 1);
+        assert_eq!(pick_max_results(Some(10000), None), 50);
+        // Setting is used when the caller omits the count
+        assert_eq!(pick_max_results(None, Some("25")), 25);
+        // Built-in default when the setting is missing or invalid
+        assert_eq!(pick_max_results(None, None), BUILTIN_SEARCH_RESULTS);
+        assert_eq!(pick_max_results(None, Some("500")), BUILTIN_SEARCH_RESULTS);
+    }
+
+    #[test]
+    fn test_resolve_max_results_reads_setting() {
+        let db = Database::new(std::path::Path::new(":memory:")).unwrap();
+        assert_eq!(resolve_max_results(None, &db), BUILTIN_SEARCH_RESULTS);
+
+        db.set_setting(DEFAULT_SEARCH_RESULTS_KEY, "30").unwrap();
+        assert_eq!(resolve_max_results(None, &db), 30);
+        assert_eq!(resolve_max_results(Some(3), &db), 3);
+    }
+}
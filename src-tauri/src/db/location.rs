@@ -0,0 +1,146 @@
+use log::{info, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that overrides the database location
+pub const DB_PATH_ENV_VAR: &str = "HOMEKARAOKE_DB_PATH";
+
+/// Bootstrap file in the app data directory whose first non-empty line overrides
+/// the database location (used when the environment variable is not set)
+pub const DB_PATH_BOOTSTRAP_FILE: &str = "database-path.txt";
+
+/// Database file name in the app data directory (and in override directories)
+pub const DEFAULT_DB_FILE_NAME: &str = "karaoke.db";
+
+/// Resolve where the database lives.
+///
+/// An override (from `env_override`, else the bootstrap file) may name the database
+/// file or an existing directory to hold `karaoke.db`. The override is only used if
+/// its directory already exists and is writable; missing directories are not created,
+/// so an unmounted volume falls back to `app_data_dir/karaoke.db` instead of silently
+/// starting an empty database elsewhere.
+pub fn resolve_db_path(app_data_dir: &Path, env_override: Option<&str>) -> PathBuf {
+    let default_path = app_data_dir.join(DEFAULT_DB_FILE_NAME);
+
+    let env_override = env_override
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(|value| (value.to_string(), DB_PATH_ENV_VAR));
+    let override_path = env_override.or_else(|| {
+        read_bootstrap_override(app_data_dir).map(|value| (value, DB_PATH_BOOTSTRAP_FILE))
+    });
+
+    let (value, source) = match override_path {
+        Some(found) => found,
+        None => {
+            info!("Using default database location: {:?}", default_path);
+            return default_path;
+        }
+    };
+
+    let mut path = PathBuf::from(&value);
+    if path.is_dir() {
+        path = path.join(DEFAULT_DB_FILE_NAME);
+    }
+
+    let dir = path.parent().unwrap_or(Path::new(""));
+    if let Err(e) = check_writable_dir(dir) {
+        warn!(
+            "Database location {:?} from {} is not usable ({}), falling back to {:?}",
+            path, source, e, default_path
+        );
+        return default_path;
+    }
+
+    info!("Using database location {:?} from {}", path, source);
+    path
+}
+
+/// First non-empty line of the bootstrap file, if present
+fn read_bootstrap_override(app_data_dir: &Path) -> Option<String> {
+    let content = fs::read_to_string(app_data_dir.join(DB_PATH_BOOTSTRAP_FILE)).ok()?;
+    content
+        .lines()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+        .map(String::from)
+}
+
+/// Check that a directory exists and a file can be created in it
+fn check_writable_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_absolute() {
+        return Err("path must be absolute".to_string());
+    }
+    if !dir.is_dir() {
+        return Err("directory does not exist".to_string());
+    }
+
+    let probe = dir.join(format!(".homekaraoke-write-test-{}", std::process::id()));
+    fs::write(&probe, b"").map_err(|e| format!("directory is not writable: {}", e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::scratch_dir;
+
+    #[test]
+    fn test_resolve_db_path_default_when_env_unset() {
+        let app_data = scratch_dir("db-path-default");
+
+        assert_eq!(resolve_db_path(&app_data, None), app_data.join(DEFAULT_DB_FILE_NAME));
+        assert_eq!(resolve_db_path(&app_data, Some("  ")), app_data.join(DEFAULT_DB_FILE_NAME));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_resolve_db_path_env_file_or_directory() {
+        let app_data = scratch_dir("db-path-env");
+        let volume = scratch_dir("db-path-env-volume");
+
+        let file = volume.join("party.db");
+        assert_eq!(resolve_db_path(&app_data, file.to_str()), file);
+        assert_eq!(
+            resolve_db_path(&app_data, volume.to_str()),
+            volume.join(DEFAULT_DB_FILE_NAME)
+        );
+
+        let _ = fs::remove_dir_all(&app_data);
+        let _ = fs::remove_dir_all(&volume);
+    }
+
+    #[test]
+    fn test_resolve_db_path_falls_back_on_unusable_override() {
+        let app_data = scratch_dir("db-path-fallback");
+        let default_path = app_data.join(DEFAULT_DB_FILE_NAME);
+
+        let missing = app_data.join("unmounted").join("karaoke.db");
+        assert_eq!(resolve_db_path(&app_data, missing.to_str()), default_path);
+        assert_eq!(resolve_db_path(&app_data, Some("relative/karaoke.db")), default_path);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_resolve_db_path_bootstrap_file() {
+        let app_data = scratch_dir("db-path-bootstrap");
+        let volume = scratch_dir("db-path-bootstrap-volume");
+        fs::write(
+            app_data.join(DB_PATH_BOOTSTRAP_FILE),
+            format!("\n  {}  \n", volume.display()),
+        )
+        .unwrap();
+
+        assert_eq!(resolve_db_path(&app_data, None), volume.join(DEFAULT_DB_FILE_NAME));
+
+        // The environment variable takes precedence over the bootstrap file
+        let env_file = app_data.join("env.db");
+        assert_eq!(resolve_db_path(&app_data, env_file.to_str()), env_file);
+
+        let _ = fs::remove_dir_all(&app_data);
+        let _ = fs::remove_dir_all(&volume);
+    }
+}
@@ -1,9 +1,11 @@
+mod location;
 mod schema;
 
 use rusqlite::{Connection, Result, params};
 use std::path::Path;
 
-pub use schema::run_migrations;
+pub use location::{resolve_db_path, DB_PATH_ENV_VAR};
+pub use schema::{check_schema, run_migrations, SchemaState, SchemaStatus};
 
 pub struct Database {
     conn: Connection,
@@ -1,4 +1,6 @@
+use log::warn;
 use rusqlite::{Connection, Result};
+use serde::Serialize;
 
 const MIGRATIONS: &[&str] = &[
     // Migration 1: Initial schema
@@ -329,8 +331,147 @@ const MIGRATIONS: &[&str] = &[
     ALTER TABLE singers ADD COLUMN online_id TEXT;
     CREATE INDEX IF NOT EXISTS idx_singers_online_id ON singers(online_id);
     "#,
+    // Migration 14: Per-folder custom filename pattern (regex with named groups)
+    r#"
+    ALTER TABLE library_folders ADD COLUMN filename_pattern TEXT;
+    "#,
+    // Migration 15: Soft-delete sessions (trash with restore window)
+    r#"
+    ALTER TABLE sessions ADD COLUMN deleted_at TIMESTAMP;
+    CREATE INDEX IF NOT EXISTS idx_sessions_deleted_at ON sessions(deleted_at);
+    "#,
+    // Migration 16: Video chapters on queue items (JSON array) for section seeking
+    r#"
+    ALTER TABLE queue_items ADD COLUMN chapters TEXT;
+    "#,
+    // Migration 17: Library scan checkpoints so interrupted scans can resume
+    r#"
+    CREATE TABLE IF NOT EXISTS library_scan_checkpoints (
+        folder_id INTEGER PRIMARY KEY,
+        last_file_path TEXT NOT NULL,       -- Last fully processed file (scan order is sorted by path)
+        files_processed INTEGER NOT NULL,
+        options TEXT NOT NULL,              -- JSON ScanOptions to resume with
+        updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+        FOREIGN KEY (folder_id) REFERENCES library_folders(id) ON DELETE CASCADE
+    );
+    "#,
+    // Migration 18: Now-playing marker so the player can resume on the right item
+    r#"
+    ALTER TABLE sessions ADD COLUMN current_item_id TEXT;
+    "#,
+    // Migration 19: Per-session playback defaults (JSON: volume, autoplay, lyrics)
+    r#"
+    ALTER TABLE sessions ADD COLUMN session_settings TEXT;
+    "#,
+    // Migration 20: Setlists (named queue templates, denormalized like favorites)
+    r#"
+    CREATE TABLE IF NOT EXISTS setlists (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        name TEXT NOT NULL,
+        created_at TEXT DEFAULT CURRENT_TIMESTAMP
+    );
+
+    CREATE TABLE IF NOT EXISTS setlist_items (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        setlist_id INTEGER NOT NULL,
+        position INTEGER NOT NULL,
+        video_id TEXT NOT NULL,
+        title TEXT NOT NULL,
+        artist TEXT,
+        duration INTEGER,
+        thumbnail_url TEXT,
+        source TEXT NOT NULL,
+        youtube_id TEXT,
+        file_path TEXT,
+        chapters TEXT,
+        FOREIGN KEY (setlist_id) REFERENCES setlists(id) ON DELETE CASCADE
+    );
+    CREATE INDEX IF NOT EXISTS idx_setlist_items_setlist ON setlist_items(setlist_id, position);
+    "#,
+    // Migration 21: Session-wide tempo and key baseline for songs without their own override
+    r#"
+    ALTER TABLE sessions ADD COLUMN default_playback_rate REAL;
+    ALTER TABLE sessions ADD COLUMN default_transpose INTEGER;
+    "#,
+    // Migration 22: Keep the queue in fair order automatically as songs are added
+    r#"
+    ALTER TABLE sessions ADD COLUMN auto_shuffle INTEGER NOT NULL DEFAULT 0;
+    "#,
+    // Migration 23: How the queue is reordered by the rotation command (fifo, fair or random)
+    r#"
+    ALTER TABLE sessions ADD COLUMN rotation_mode TEXT NOT NULL DEFAULT 'fair';
+    "#,
+    // Migration 24: Last search query of a session, restored after a restart
+    r#"
+    ALTER TABLE sessions ADD COLUMN last_search_query TEXT;
+    "#,
+    // Migration 25: Per-song tempo and key override on queue items, and each singer's
+    // preferred key and tempo per song (applied when they are assigned to it)
+    r#"
+    ALTER TABLE queue_items ADD COLUMN playback_rate REAL;
+    ALTER TABLE queue_items ADD COLUMN transpose INTEGER;
+
+    CREATE TABLE IF NOT EXISTS singer_song_preferences (
+        singer_id INTEGER NOT NULL,
+        video_id TEXT NOT NULL,
+        pitch_semitones INTEGER,
+        tempo_percent INTEGER,
+        notes TEXT,
+        updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+        PRIMARY KEY (singer_id, video_id),
+        FOREIGN KEY (singer_id) REFERENCES singers(id) ON DELETE CASCADE
+    );
+    "#,
+    // Migration 26: Songs added since the last scheduled shuffle (auto_shuffle_after_adds setting)
+    r#"
+    ALTER TABLE sessions ADD COLUMN adds_since_shuffle INTEGER NOT NULL DEFAULT 0;
+    "#,
 ];
 
+/// Schema version this build of the app expects (one per migration)
+pub const EXPECTED_SCHEMA_VERSION: i32 = MIGRATIONS.len() as i32;
+
+/// How the stored schema compares to what this build expects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum SchemaState {
+    UpToDate,
+    NeedsMigration,
+    /// Written by a newer version of the app (e.g. after a downgrade)
+    TooNew,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaStatus {
+    pub state: SchemaState,
+    pub stored_version: i32,
+    pub expected_version: i32,
+}
+
+/// Get the stored schema version (0 for a fresh database)
+fn stored_schema_version(conn: &Connection) -> i32 {
+    conn.query_row(
+        "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
+        [],
+        |row| row.get(0),
+    )
+    .unwrap_or(0)
+}
+
+/// Compare the stored schema version with the version this build expects
+pub fn check_schema(conn: &Connection) -> SchemaStatus {
+    let stored_version = stored_schema_version(conn);
+    let state = match stored_version.cmp(&EXPECTED_SCHEMA_VERSION) {
+        std::cmp::Ordering::Equal => SchemaState::UpToDate,
+        std::cmp::Ordering::Less => SchemaState::NeedsMigration,
+        std::cmp::Ordering::Greater => SchemaState::TooNew,
+    };
+    SchemaStatus {
+        state,
+        stored_version,
+        expected_version: EXPECTED_SCHEMA_VERSION,
+    }
+}
+
 pub fn run_migrations(conn: &Connection) -> Result<()> {
     // Ensure schema_version table exists for fresh databases
     conn.execute(
@@ -339,13 +480,13 @@ pub fn run_migrations(conn: &Connection) -> Result<()> {
     )?;
 
     // Get current version
-    let current_version: i32 = conn
-        .query_row(
-            "SELECT version FROM schema_version ORDER BY version DESC LIMIT 1",
-            [],
-            |row| row.get(0),
-        )
-        .unwrap_or(0);
+    let current_version = stored_schema_version(conn);
+    if current_version > EXPECTED_SCHEMA_VERSION {
+        warn!(
+            "Database schema version {} is newer than this app supports ({})",
+            current_version, EXPECTED_SCHEMA_VERSION
+        );
+    }
 
     // Run pending migrations
     for (i, migration) in MIGRATIONS.iter().enumerate() {
@@ -414,8 +555,51 @@ mod tests {
         assert_eq!(status, None);
     }
 
+    fn seed_schema_version(version: i32) -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute(
+            "CREATE TABLE schema_version (version INTEGER PRIMARY KEY)",
+            [],
+        )
+        .unwrap();
+        if version > 0 {
+            conn.execute("INSERT INTO schema_version (version) VALUES (?1)", [version])
+                .unwrap();
+        }
+        conn
+    }
+
+    #[test]
+    fn test_check_schema_up_to_date() {
+        let conn = seed_schema_version(EXPECTED_SCHEMA_VERSION);
+        let status = check_schema(&conn);
+        assert_eq!(status.state, SchemaState::UpToDate);
+        assert_eq!(status.stored_version, EXPECTED_SCHEMA_VERSION);
+        assert_eq!(status.expected_version, EXPECTED_SCHEMA_VERSION);
+    }
+
     #[test]
-    fn test_schema_version_is_13_after_all_migrations() {
+    fn test_check_schema_needs_migration() {
+        let conn = seed_schema_version(EXPECTED_SCHEMA_VERSION - 1);
+        assert_eq!(check_schema(&conn).state, SchemaState::NeedsMigration);
+
+        // Fresh database without a version table
+        let fresh = Connection::open_in_memory().unwrap();
+        let status = check_schema(&fresh);
+        assert_eq!(status.state, SchemaState::NeedsMigration);
+        assert_eq!(status.stored_version, 0);
+    }
+
+    #[test]
+    fn test_check_schema_too_new() {
+        let conn = seed_schema_version(EXPECTED_SCHEMA_VERSION + 3);
+        let status = check_schema(&conn);
+        assert_eq!(status.state, SchemaState::TooNew);
+        assert_eq!(status.stored_version, EXPECTED_SCHEMA_VERSION + 3);
+    }
+
+    #[test]
+    fn test_schema_version_matches_migrations_after_run() {
         let conn = Connection::open_in_memory().unwrap();
         run_migrations(&conn).unwrap();
 
@@ -427,7 +611,7 @@ mod tests {
             )
             .unwrap();
 
-        assert_eq!(version, 13);
+        assert_eq!(version, EXPECTED_SCHEMA_VERSION);
     }
 
     #[test]
@@ -628,4 +812,104 @@ mod tests {
 
         assert!(index_exists);
     }
+
+    #[test]
+    fn test_migration_14_adds_filename_pattern_to_library_folders() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute(
+            "INSERT INTO library_folders (path, name) VALUES ('/music', 'music')",
+            [],
+        )
+        .unwrap();
+
+        // Defaults to NULL (built-in patterns only)
+        let pattern: Option<String> = conn
+            .query_row(
+                "SELECT filename_pattern FROM library_folders WHERE path = '/music'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(pattern, None);
+    }
+
+    #[test]
+    fn test_migration_15_adds_deleted_at_to_sessions() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Party', 0)", [])
+            .unwrap();
+
+        // New sessions are not deleted
+        let deleted_at: Option<String> = conn
+            .query_row(
+                "SELECT deleted_at FROM sessions WHERE name = 'Party'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(deleted_at, None);
+    }
+
+    #[test]
+    fn test_migration_16_adds_chapters_to_queue_items() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Party', 1)", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO queue_items (id, session_id, item_type, video_id, title, source, position, added_at)
+             VALUES ('item-1', 1, 'queue', 'abc', 'Song', 'youtube', 0, '2026-01-01T00:00:00Z')",
+            [],
+        )
+        .unwrap();
+
+        let chapters: Option<String> = conn
+            .query_row("SELECT chapters FROM queue_items WHERE id = 'item-1'", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(chapters, None);
+    }
+
+    #[test]
+    fn test_migration_17_scan_checkpoints_cascade_with_folder() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute("INSERT INTO library_folders (id, path, name) VALUES (1, '/music', 'Music')", [])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO library_scan_checkpoints (folder_id, last_file_path, files_processed, options)
+             VALUES (1, '/music/a.mp4', 1, '{}')",
+            [],
+        )
+        .unwrap();
+
+        conn.execute("DELETE FROM library_folders WHERE id = 1", []).unwrap();
+        let count: i64 = conn
+            .query_row("SELECT COUNT(*) FROM library_scan_checkpoints", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(count, 0);
+    }
+
+    #[test]
+    fn test_migration_18_adds_current_item_to_sessions() {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+
+        conn.execute("INSERT INTO sessions (name, is_active) VALUES ('Party', 1)", [])
+            .unwrap();
+        let current_item_id: Option<String> = conn
+            .query_row("SELECT current_item_id FROM sessions WHERE id = 1", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        assert_eq!(current_item_id, None);
+    }
 }
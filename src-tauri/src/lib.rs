@@ -2,15 +2,24 @@ mod commands;
 mod db;
 mod keychain;
 mod services;
+#[cfg(test)]
+mod test_utils;
 
 use chrono::Datelike;
+#[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
+use commands::errors::LockRecoverExt;
 use db::Database;
 use log::{debug, error, info, warn};
 use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "macos")]
+use std::sync::atomic::AtomicU64;
 use std::sync::{Arc, Mutex};
 
 #[cfg(target_os = "macos")]
-use services::{DisplayEvent, DisplayWatcherService};
+use services::{
+    display_watcher::display_removed, get_display_configuration, DisplayEvent,
+    DisplayWatcherService,
+};
 #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
 use services::MediaControlsService;
 #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
@@ -23,6 +32,7 @@ use std::thread::JoinHandle;
 use std::time::Duration;
 use tauri::menu::{AboutMetadata, CheckMenuItem, Menu, MenuItemKind, MenuItem, PredefinedMenuItem, Submenu};
 use tauri::{Emitter, Manager};
+use services::log_levels::{self, LogTarget};
 use tauri_plugin_log::{Target, TargetKind};
 use tauri_plugin_deep_link::DeepLinkExt;
 use tauri_plugin_opener::OpenerExt;
@@ -32,6 +42,8 @@ pub struct AppState {
     pub keep_awake: Mutex<Option<keepawake::KeepAwake>>,
     pub debug_mode: AtomicBool,
     pub log_dir: std::path::PathBuf,
+    /// Set by `library_cancel_scan` to stop the running library scan
+    pub scan_cancelled: AtomicBool,
     /// Pending auth callback from deep link (stored until frontend is ready)
     pub pending_auth_callback: Mutex<Option<std::collections::HashMap<String, String>>>,
     #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
@@ -48,6 +60,9 @@ pub struct AppState {
     pub display_event_rx: Mutex<Option<mpsc::Receiver<DisplayEvent>>>,
     #[cfg(target_os = "macos")]
     pub display_event_thread: Mutex<Option<JoinHandle<()>>>,
+    /// Bumped on every window move/resize to debounce automatic layout saves
+    #[cfg(target_os = "macos")]
+    pub window_layout_generation: AtomicU64,
 }
 
 const SETTINGS_MENU_ID: &str = "settings";
@@ -59,7 +74,55 @@ const SAVE_DISPLAY_LAYOUT_MENU_ID: &str = "save-display-layout";
 const LOAD_FAVORITES_MENU_ID: &str = "load-favorites";
 const MANAGE_FAVORITES_MENU_ID: &str = "manage-favorites";
 
-fn create_menu(app: &tauri::App, debug_enabled: bool) -> Result<Menu<tauri::Wry>, tauri::Error> {
+/// Save window layout automatically when windows move/resize or the main window closes
+#[cfg(target_os = "macos")]
+fn handle_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    match event {
+        tauri::WindowEvent::Moved(_) | tauri::WindowEvent::Resized(_) => {
+            services::window_layout::schedule_layout_save(window.app_handle());
+        }
+        tauri::WindowEvent::CloseRequested { .. } if window.label() == "main" => {
+            services::window_layout::save_current_layout(window.app_handle());
+        }
+        _ => {}
+    }
+}
+
+#[cfg(not(target_os = "macos"))]
+fn handle_window_event(_window: &tauri::Window, _event: &tauri::WindowEvent) {}
+
+/// Menu items disabled while party mode is on
+const PARTY_MODE_LOCKED_MENU_IDS: &[&str] =
+    &[SAVE_SESSION_AS_MENU_ID, LOAD_SESSION_MENU_ID, MANAGE_FAVORITES_MENU_ID];
+
+/// Enable or disable the destructive menu items for party mode
+pub(crate) fn apply_party_mode_to_menu(app: &tauri::AppHandle, party_mode: bool) {
+    let Some(menu) = app.menu() else {
+        return;
+    };
+    // Menu::get only searches top-level items; the locked items live in submenus
+    let submenus: Vec<Submenu<tauri::Wry>> = match menu.items() {
+        Ok(items) => items.into_iter().filter_map(|item| item.as_submenu().cloned()).collect(),
+        Err(e) => {
+            log::error!("Failed to read menu items: {}", e);
+            return;
+        }
+    };
+    for id in PARTY_MODE_LOCKED_MENU_IDS {
+        let found = submenus.iter().find_map(|submenu| submenu.get(*id));
+        if let Some(MenuItemKind::MenuItem(item)) = found {
+            if let Err(e) = item.set_enabled(!party_mode) {
+                log::error!("Failed to update menu item {} enabled state: {}", id, e);
+            }
+        }
+    }
+}
+
+fn create_menu(
+    app: &tauri::App,
+    debug_enabled: bool,
+    party_mode: bool,
+) -> Result<Menu<tauri::Wry>, tauri::Error> {
     // About metadata with app info
     // Note: On macOS, `authors` and `website` fields are not supported
     // Use `credits` for additional info that appears as scrollable text
@@ -136,9 +199,9 @@ fn create_menu(app: &tauri::App, debug_enabled: bool) -> Result<Menu<tauri::Wry>
 
     // Sessions menu
     let save_session_item =
-        MenuItem::with_id(app, SAVE_SESSION_AS_MENU_ID, "Save Session As...", true, None::<&str>)?;
+        MenuItem::with_id(app, SAVE_SESSION_AS_MENU_ID, "Save Session As...", !party_mode, None::<&str>)?;
     let load_session_item =
-        MenuItem::with_id(app, LOAD_SESSION_MENU_ID, "Stored Sessions...", true, None::<&str>)?;
+        MenuItem::with_id(app, LOAD_SESSION_MENU_ID, "Stored Sessions...", !party_mode, None::<&str>)?;
 
     let sessions_menu = Submenu::with_items(
         app,
@@ -154,7 +217,7 @@ fn create_menu(app: &tauri::App, debug_enabled: bool) -> Result<Menu<tauri::Wry>
     let load_favorites_item =
         MenuItem::with_id(app, LOAD_FAVORITES_MENU_ID, "Load Favorites to Queue...", true, None::<&str>)?;
     let manage_favorites_item =
-        MenuItem::with_id(app, MANAGE_FAVORITES_MENU_ID, "Manage Favorites...", true, None::<&str>)?;
+        MenuItem::with_id(app, MANAGE_FAVORITES_MENU_ID, "Manage Favorites...", !party_mode, None::<&str>)?;
 
     let singers_menu = Submenu::with_items(
         app,
@@ -239,20 +302,22 @@ pub fn run() {
         // Configure logging with file + stdout + webview targets
         // File logs capture everything (debug level) for issue reporting
         // Stdout uses info level by default
+        // Per-target levels can be changed at runtime with set_log_level
         .plugin(
             tauri_plugin_log::Builder::new()
                 .targets([
                     // Log to file - captures all levels including debug for issue reporting
-                    Target::new(TargetKind::LogDir { file_name: Some("karaoke".into()) }),
+                    Target::new(TargetKind::LogDir { file_name: Some("karaoke".into()) })
+                        .filter(|metadata| log_levels::allows(LogTarget::File, metadata.level())),
                     // Log to stdout - info level for cleaner terminal output
                     Target::new(TargetKind::Stdout)
-                        .filter(|metadata| metadata.level() <= log::Level::Info),
+                        .filter(|metadata| log_levels::allows(LogTarget::Stdout, metadata.level())),
                     // Log to webview console - info level (debug controlled by frontend)
                     Target::new(TargetKind::Webview)
-                        .filter(|metadata| metadata.level() <= log::Level::Info),
+                        .filter(|metadata| log_levels::allows(LogTarget::Webview, metadata.level())),
                 ])
-                // Allow debug level globally (file will capture it, others filter it out)
-                .level(log::LevelFilter::Debug)
+                // Allow every level globally; the per-target gates filter records
+                .level(log_levels::MAX_LEVEL)
                 // Reduce noise from some verbose crates
                 .level_for("tao", log::LevelFilter::Warn)
                 .level_for("wry", log::LevelFilter::Warn)
@@ -265,23 +330,44 @@ pub fn run() {
         )
         .invoke_handler(tauri::generate_handler![
             commands::youtube_search,
+            commands::search_all,
             commands::youtube_get_stream_url,
+            commands::youtube_stream_cache_status,
+            commands::youtube_clear_stream_cache,
+            commands::youtube_list_formats,
+            commands::youtube_get_chapters,
+            commands::resolve_playback_source,
+            commands::resolve_local_file,
             commands::youtube_get_info,
+            commands::youtube_get_info_batch,
             commands::youtube_check_available,
+            commands::youtube_health_check,
             commands::youtube_install_ytdlp,
             commands::youtube_api_search,
             commands::youtube_validate_api_key,
             commands::youtube_get_search_method,
+            commands::youtube_add_block,
+            commands::youtube_list_blocks,
             commands::keep_awake_enable,
             commands::keep_awake_disable,
             commands::get_debug_mode,
             commands::set_debug_mode,
+            commands::get_log_levels,
+            commands::set_log_level,
+            commands::get_party_mode,
+            commands::set_party_mode,
+            commands::get_api_user_agent,
+            commands::set_api_user_agent,
             commands::get_log_path,
             // Settings commands
             commands::settings_get,
             commands::settings_set,
             commands::settings_get_all,
             commands::settings_reset_all,
+            commands::db_check_schema,
+            commands::db_repair_schema,
+            commands::diagnostics_tool_discovery,
+            commands::get_versions,
             commands::open_log_folder,
             // Session & Singer commands
             commands::create_singer,
@@ -289,6 +375,7 @@ pub fn run() {
             commands::find_singer_by_online_id,
             commands::delete_singer,
             commands::update_singer,
+            commands::set_singer_persistent,
             commands::get_persistent_singers,
             // Favorites commands
             commands::add_favorite,
@@ -296,34 +383,81 @@ pub fn run() {
             commands::get_singer_favorites,
             commands::bulk_add_favorites,
             commands::check_video_favorites,
+            commands::search_all_favorites,
+            commands::dedupe_singer_favorites,
+            commands::suggest_songs_for_singer,
+            commands::import_favorites_csv,
+            commands::export_singers_and_favorites,
+            commands::import_singers_and_favorites,
             commands::start_session,
             commands::end_session,
             commands::get_active_session,
             commands::add_singer_to_session,
             commands::remove_singer_from_session,
             commands::get_session_singers,
+            commands::get_singer_sessions,
+            commands::get_waiting_singers,
+            commands::export_singer_sheets,
+            commands::get_singer_song_preference,
+            commands::set_singer_song_preference,
             commands::assign_singer_to_queue_item,
+            commands::assign_singer_by_artist,
             commands::remove_singer_from_queue_item,
             commands::get_queue_item_singers,
             commands::clear_queue_item_singers,
+            commands::check_singer_repeat,
             // Queue persistence commands
             commands::queue_add_item,
             commands::queue_remove_item,
+            commands::queue_replace_source,
             commands::queue_reorder,
+            commands::queue_reorder_history,
+            commands::queue_add_random_from_library,
+            commands::save_setlist,
+            commands::load_setlist,
+            commands::get_setlists,
+            commands::delete_setlist,
             commands::queue_clear,
             commands::queue_move_to_history,
             commands::queue_add_to_history,
             commands::queue_clear_history,
             commands::queue_move_all_history_to_queue,
             commands::queue_set_history_index,
+            commands::queue_resolve_next,
+            commands::queue_resolve_prev,
+            commands::queue_set_current_item,
             commands::queue_get_state,
             commands::queue_fair_shuffle,
+            commands::queue_preview_fair_shuffle,
+            commands::queue_apply_rotation,
+            commands::queue_assign_round_robin,
+            commands::queue_set_item_chapters,
+            commands::queue_total_runtime,
+            commands::queue_backfill_durations,
+            commands::queue_validate_items,
             commands::queue_compute_fair_position,
             // Session management commands
             commands::get_recent_sessions,
+            commands::get_global_stats,
             commands::rename_session,
+            commands::name_active_session,
+            commands::get_session_settings,
+            commands::set_session_settings,
+            commands::get_session_playback_defaults,
+            commands::set_session_playback_defaults,
+            commands::get_auto_shuffle,
+            commands::set_auto_shuffle,
+            commands::get_rotation_mode,
+            commands::set_rotation_mode,
+            commands::get_last_search,
+            commands::set_last_search,
+            commands::set_session_times,
             commands::load_session,
+            commands::recover_last_session,
             commands::delete_session,
+            commands::restore_session,
+            commands::purge_deleted_sessions,
+            commands::purge_old_history,
             // Active singer commands
             commands::session_set_active_singer,
             commands::session_get_active_singer,
@@ -334,12 +468,16 @@ pub fn run() {
             commands::media_controls_update_metadata,
             commands::media_controls_update_playback,
             commands::media_controls_stop,
+            commands::media_controls_status,
             // Display commands
             commands::display_get_configuration,
+            commands::window_list_monitors,
+            commands::display_suggest_karaoke_layout,
             commands::display_save_config,
             commands::display_get_saved_config,
             commands::display_update_auto_apply,
             commands::display_delete_config,
+            commands::display_merge_configs,
             commands::window_save_state,
             commands::window_get_states,
             commands::window_clear_states,
@@ -348,13 +486,39 @@ pub fn run() {
             // Library commands
             commands::library_add_folder,
             commands::library_remove_folder,
+            commands::library_relocate_folder,
             commands::library_get_folders,
             commands::library_scan_folder,
+            commands::library_cancel_scan,
             commands::library_scan_all,
+            commands::library_get_scan_checkpoint,
+            commands::library_resume_scan,
+            commands::library_preview_parse,
+            commands::library_probe_folder,
+            commands::library_clear_thumbnails,
+            commands::library_merge_folder_metadata,
+            commands::library_migrate_legacy_metadata,
+            commands::library_import_catalog,
+            commands::library_export_catalog,
+            commands::library_set_filename_pattern,
             commands::library_search,
             commands::library_browse,
             commands::library_check_file,
+            commands::library_get_chapters,
+            commands::search_lyrics_candidates,
+            commands::fetch_lyrics_by_id,
+            commands::library_set_lyrics,
+            commands::library_bulk_tag,
+            commands::library_find_duplicate_content,
+            commands::library_find_duration_mismatches,
+            commands::library_audit_durations,
+            commands::library_generate_thumbnails,
+            commands::get_thumbnail_data_url,
             commands::library_get_stats,
+            commands::library_cache_status,
+            commands::library_get_facets,
+            commands::library_artist_counts,
+            commands::library_get_distribution,
             // Search history commands
             commands::search_history_add,
             commands::search_history_get,
@@ -385,7 +549,10 @@ pub fn run() {
             debug!("App data directory: {:?}", app_data_dir);
             info!("Log directory: {:?}", log_dir);
 
-            let db_path = app_data_dir.join("karaoke.db");
+            let db_path = db::resolve_db_path(
+                &app_data_dir,
+                std::env::var(db::DB_PATH_ENV_VAR).ok().as_deref(),
+            );
             let db = Database::new(&db_path)?;
             info!("Database initialized at {:?}", db_path);
 
@@ -393,6 +560,16 @@ pub fn run() {
             let debug_enabled = load_debug_preference(&db);
             debug!("Debug mode loaded from preferences: {}", debug_enabled);
 
+            let party_mode = commands::settings::is_party_mode(db.connection()).unwrap_or_else(|e| {
+                warn!("Failed to read party mode setting: {}", e);
+                false
+            });
+            if party_mode {
+                info!("Party mode is on");
+            }
+
+            commands::settings::apply_api_user_agent(&db);
+
             // Initialize media controls (macOS, Linux, and Windows)
             #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
             let shutdown_flag = Arc::new(AtomicBool::new(false));
@@ -446,6 +623,7 @@ pub fn run() {
                 keep_awake: Mutex::new(None),
                 debug_mode: AtomicBool::new(debug_enabled),
                 log_dir: log_dir.clone(),
+                scan_cancelled: AtomicBool::new(false),
                 pending_auth_callback: Mutex::new(None),
                 #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
                 media_controls: Mutex::new(media_controls),
@@ -461,6 +639,8 @@ pub fn run() {
                 display_event_rx: Mutex::new(display_event_rx),
                 #[cfg(target_os = "macos")]
                 display_event_thread: Mutex::new(None),
+                #[cfg(target_os = "macos")]
+                window_layout_generation: AtomicU64::new(0),
             });
 
             // Add library folders to asset protocol scope for thumbnails
@@ -514,7 +694,7 @@ pub fn run() {
                 let shutdown_flag_clone = shutdown_flag.clone();
                 let thread_handle = std::thread::spawn(move || {
                     let state = app_handle.state::<AppState>();
-                    let rx = state.media_event_rx.lock().ok().and_then(|mut guard| guard.take());
+                    let rx = state.media_event_rx.lock_recover("Media event receiver").take();
 
                     if let Some(receiver) = rx {
                         loop {
@@ -564,9 +744,7 @@ pub fn run() {
 
                 // Store the thread handle for graceful shutdown
                 let state = app.state::<AppState>();
-                if let Ok(mut guard) = state.media_event_thread.lock() {
-                    *guard = Some(thread_handle);
-                };
+                *state.media_event_thread.lock_recover("Media event thread") = Some(thread_handle);
             }
 
             // Spawn display event polling thread (macOS only)
@@ -576,9 +754,10 @@ pub fn run() {
                 let shutdown_flag_clone = shutdown_flag.clone();
                 let thread_handle = std::thread::spawn(move || {
                     let state = app_handle.state::<AppState>();
-                    let rx = state.display_event_rx.lock().ok().and_then(|mut guard| guard.take());
+                    let rx = state.display_event_rx.lock_recover("Display event receiver").take();
 
                     if let Some(receiver) = rx {
+                        let mut last_config = get_display_configuration().ok();
                         loop {
                             match receiver.recv_timeout(Duration::from_millis(100)) {
                                 Ok(DisplayEvent::ConfigurationChanged(config)) => {
@@ -588,6 +767,22 @@ pub fn run() {
                                         &config.config_hash[..8.min(config.config_hash.len())]
                                     );
                                     let _ = app_handle.emit("display:configuration-changed", &config);
+
+                                    let removed = last_config
+                                        .as_ref()
+                                        .map(|before| display_removed(before, &config))
+                                        .unwrap_or(false);
+                                    let pause_enabled = removed
+                                        && match state.db.lock() {
+                                            Ok(db) => commands::settings::pause_on_display_disconnect(&db),
+                                            Err(_) => true,
+                                        };
+                                    if pause_enabled {
+                                        info!("Display removed, pausing playback and releasing keep-awake");
+                                        *state.keep_awake.lock_recover("Keep awake") = None;
+                                        let _ = app_handle.emit("playback:should-pause", &config);
+                                    }
+                                    last_config = Some(config);
                                 }
                                 Err(mpsc::RecvTimeoutError::Timeout) => {
                                     if shutdown_flag_clone.load(Ordering::SeqCst) {
@@ -604,9 +799,7 @@ pub fn run() {
 
                 // Store the thread handle for graceful shutdown
                 let state = app.state::<AppState>();
-                if let Ok(mut guard) = state.display_event_thread.lock() {
-                    *guard = Some(thread_handle);
-                };
+                *state.display_event_thread.lock_recover("Display event thread") = Some(thread_handle);
             }
 
             // Register deep link handler for OAuth callback
@@ -686,7 +879,7 @@ pub fn run() {
             info!("Deep link handler registered for homekaraoke:// scheme");
 
             // Create the application menu
-            let menu = create_menu(app, debug_enabled)?;
+            let menu = create_menu(app, debug_enabled, party_mode)?;
             app.set_menu(menu)?;
             debug!("Application menu created");
 
@@ -767,6 +960,7 @@ pub fn run() {
                 _ => {}
             }
         })
+        .on_window_event(|window, event| handle_window_event(window, event))
         .build(context)
         .expect("error while building tauri application")
         .run(|app_handle, event| {
@@ -783,32 +977,33 @@ pub fn run() {
                     debug!("Shutdown flag set");
 
                     // Wait for media event thread to finish
-                    if let Ok(mut guard) = state.media_event_thread.lock() {
-                        if let Some(handle) = guard.take() {
-                            debug!("Waiting for media event thread to finish...");
-                            match handle.join() {
-                                Ok(()) => info!("Media event thread shut down gracefully"),
-                                Err(_) => warn!("Media event thread panicked during shutdown"),
-                            }
+                    let handle = state.media_event_thread.lock_recover("Media event thread").take();
+                    if let Some(handle) = handle {
+                        debug!("Waiting for media event thread to finish...");
+                        match handle.join() {
+                            Ok(()) => info!("Media event thread shut down gracefully"),
+                            Err(_) => warn!("Media event thread panicked during shutdown"),
                         }
-                    };
+                    }
                 }
 
                 // Shutdown display watcher (macOS only)
                 #[cfg(target_os = "macos")]
                 {
+                    // Save window layout while windows are still available
+                    services::window_layout::save_current_layout(app_handle);
+
                     let state = app_handle.state::<AppState>();
 
                     // Wait for display event thread to finish
-                    if let Ok(mut guard) = state.display_event_thread.lock() {
-                        if let Some(handle) = guard.take() {
-                            debug!("Waiting for display event thread to finish...");
-                            match handle.join() {
-                                Ok(()) => info!("Display event thread shut down gracefully"),
-                                Err(_) => warn!("Display event thread panicked during shutdown"),
-                            }
+                    let handle = state.display_event_thread.lock_recover("Display event thread").take();
+                    if let Some(handle) = handle {
+                        debug!("Waiting for display event thread to finish...");
+                        match handle.join() {
+                            Ok(()) => info!("Display event thread shut down gracefully"),
+                            Err(_) => warn!("Display event thread panicked during shutdown"),
                         }
-                    };
+                    }
                 }
             }
         });
@@ -0,0 +1,116 @@
+//! Export the library catalog as JSON for companion apps and other external tools.
+//!
+//! The export is read-only: it lists every video found in the registered library
+//! folders with the same metadata the library browser shows.
+
+use crate::services::library_scanner::{LibraryFolder, LibraryScanner, LibraryVideo};
+use log::info;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Full library catalog
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogExport {
+    /// RFC 3339 timestamp of when the export was generated
+    pub generated_at: String,
+    /// Number of videos in `videos`
+    pub count: u32,
+    /// All library videos, ordered by file path
+    pub videos: Vec<LibraryVideo>,
+}
+
+/// Build the catalog by scanning all library folders
+pub fn export_catalog(folders: &[LibraryFolder]) -> CatalogExport {
+    let mut videos = LibraryScanner::browse(folders, None, None);
+    videos.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    CatalogExport {
+        generated_at: chrono::Utc::now().to_rfc3339(),
+        count: videos.len() as u32,
+        videos,
+    }
+}
+
+/// Write the catalog as pretty-printed JSON. The parent directory must already exist.
+pub fn write_catalog_export(export: &CatalogExport, output_path: &Path) -> Result<(), String> {
+    if !output_path.is_absolute() {
+        return Err("Export path must be absolute".to_string());
+    }
+    match output_path.parent() {
+        Some(parent) if parent.is_dir() => {}
+        _ => return Err(format!("Export directory does not exist: {:?}", output_path.parent())),
+    }
+
+    let json = serde_json::to_string_pretty(export)
+        .map_err(|e| format!("Failed to serialize catalog: {}", e))?;
+    fs::write(output_path, json).map_err(|e| format!("Failed to write catalog: {}", e))?;
+
+    info!("Exported {} videos to {:?}", export.count, output_path);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::library_scanner::HkMeta;
+    use crate::test_utils::scratch_dir;
+
+    #[test]
+    fn test_export_catalog_contains_library_videos() {
+        let dir = scratch_dir("catalog-export");
+        let library = dir.join("library");
+        fs::create_dir_all(&library).unwrap();
+        fs::write(library.join("Queen - Bohemian Rhapsody.mp4"), "").unwrap();
+        fs::write(library.join("take_on_me.mkv"), "").unwrap();
+        LibraryScanner::write_hkmeta(
+            &library,
+            &library.join("take_on_me.mkv"),
+            &HkMeta {
+                version: Some(1),
+                title: Some("Take On Me".to_string()),
+                artist: Some("a-ha".to_string()),
+                duration: Some(225),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let folders = vec![LibraryFolder {
+            id: 1,
+            path: library.to_string_lossy().to_string(),
+            name: "library".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        }];
+
+        let export = export_catalog(&folders);
+        assert_eq!(export.count, 2);
+        assert!(chrono::DateTime::parse_from_rfc3339(&export.generated_at).is_ok());
+
+        let songs: Vec<(&str, Option<&str>)> = export
+            .videos
+            .iter()
+            .map(|v| (v.title.as_str(), v.artist.as_deref()))
+            .collect();
+        assert_eq!(
+            songs,
+            vec![("Bohemian Rhapsody", Some("Queen")), ("Take On Me", Some("a-ha"))]
+        );
+        assert_eq!(export.videos[1].duration, Some(225));
+
+        // Written file round-trips to the same catalog
+        let output = dir.join("catalog.json");
+        write_catalog_export(&export, &output).unwrap();
+        let written: CatalogExport =
+            serde_json::from_str(&fs::read_to_string(&output).unwrap()).unwrap();
+        assert_eq!(written.count, 2);
+        assert_eq!(written.videos[1].file_path, export.videos[1].file_path);
+
+        assert!(write_catalog_export(&export, &dir.join("missing").join("catalog.json")).is_err());
+        assert!(write_catalog_export(&export, Path::new("catalog.json")).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
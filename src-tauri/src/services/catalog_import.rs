@@ -0,0 +1,375 @@
+//! Import song metadata from other karaoke software catalogs.
+//!
+//! Catalog entries whose file resolves under a registered library folder are merged
+//! into that file's .hkmeta.json (catalog values win for the fields they provide).
+
+use crate::services::library_scanner::{HkMeta, LibraryFolder, LibraryScanner};
+use log::{debug, info, warn};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+/// Maximum catalog file size (prevents loading huge files into memory)
+const MAX_CATALOG_SIZE: u64 = 50 * 1024 * 1024;
+
+/// Maximum number of error messages reported back to the UI
+const MAX_REPORTED_ERRORS: usize = 50;
+
+/// Supported catalog formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CatalogFormat {
+    /// KaraFun library export: CSV with a header row, `;` or `,` delimited.
+    /// Recognized columns: file/path, title, artist, year, duration.
+    KarafunCsv,
+    /// KJ song list: one song per line, tab-separated
+    /// `<file>\t<artist>\t<title>[\t<year>]`. Lines starting with `#` are skipped.
+    Kjfile,
+}
+
+impl CatalogFormat {
+    fn parse(self, content: &str) -> Result<Vec<CatalogEntry>, String> {
+        match self {
+            CatalogFormat::KarafunCsv => parse_karafun_csv(content),
+            CatalogFormat::Kjfile => Ok(parse_kjfile(content)),
+        }
+    }
+}
+
+/// A single song from a catalog
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct CatalogEntry {
+    /// File path as written in the catalog (absolute or relative to a library folder)
+    pub file: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub year: Option<u32>,
+    /// Duration in seconds
+    pub duration: Option<u32>,
+}
+
+/// Result of importing a catalog
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct CatalogImportResult {
+    /// Entries resolved to a file in a library folder
+    pub matched: u32,
+    /// .hkmeta.json files created or updated
+    pub written: u32,
+    /// Entries whose file is not in any library folder
+    pub unmatched: u32,
+    pub errors: Vec<String>,
+}
+
+/// Split a CSV line into fields, honoring double quotes ("" escapes a quote)
+pub(crate) fn split_csv_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => in_quotes = !in_quotes,
+            c if c == delimiter && !in_quotes => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// Parse a duration as seconds, "m:ss" or "h:mm:ss"
+fn parse_duration(value: &str) -> Option<u32> {
+    let value = value.trim();
+    if value.is_empty() {
+        return None;
+    }
+    value.split(':').try_fold(0u32, |total, part| {
+        let part: u32 = part.trim().parse().ok()?;
+        total.checked_mul(60)?.checked_add(part)
+    })
+}
+
+/// Trim a field, treating empty values as missing
+fn non_empty(value: Option<&String>) -> Option<String> {
+    value
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .map(|v| v.to_string())
+}
+
+fn parse_year(value: Option<&String>) -> Option<u32> {
+    non_empty(value)?.parse().ok().filter(|y| (1900..=2099).contains(y))
+}
+
+fn parse_karafun_csv(content: &str) -> Result<Vec<CatalogEntry>, String> {
+    let mut lines = content
+        .lines()
+        .map(|l| l.trim_start_matches('\u{feff}'))
+        .filter(|l| !l.trim().is_empty());
+    let header = lines.next().ok_or("Catalog is empty")?;
+    let delimiter = if header.contains(';') { ';' } else { ',' };
+
+    let columns: Vec<String> = split_csv_line(header, delimiter)
+        .iter()
+        .map(|c| c.trim().to_lowercase())
+        .collect();
+    let column = |names: &[&str]| columns.iter().position(|c| names.contains(&c.as_str()));
+
+    let file_col = column(&["file", "path", "filename", "file name", "file_path", "location"])
+        .ok_or("Catalog has no file/path column")?;
+    let title_col = column(&["title", "song", "song title"]);
+    let artist_col = column(&["artist", "singer", "performer"]);
+    let year_col = column(&["year"]);
+    let duration_col = column(&["duration", "length", "time"]);
+
+    Ok(lines
+        .filter_map(|line| {
+            let fields = split_csv_line(line, delimiter);
+            let field = |col: Option<usize>| col.and_then(|c| fields.get(c));
+            Some(CatalogEntry {
+                file: non_empty(fields.get(file_col))?,
+                title: non_empty(field(title_col)),
+                artist: non_empty(field(artist_col)),
+                year: parse_year(field(year_col)),
+                duration: field(duration_col).and_then(|d| parse_duration(d)),
+            })
+        })
+        .collect())
+}
+
+fn parse_kjfile(content: &str) -> Vec<CatalogEntry> {
+    content
+        .lines()
+        .filter(|l| !l.trim().is_empty() && !l.trim_start().starts_with('#'))
+        .filter_map(|line| {
+            let fields: Vec<String> = line.split('\t').map(|f| f.to_string()).collect();
+            Some(CatalogEntry {
+                file: non_empty(fields.first())?,
+                artist: non_empty(fields.get(1)),
+                title: non_empty(fields.get(2)),
+                year: parse_year(fields.get(3)),
+                duration: None,
+            })
+        })
+        .collect()
+}
+
+/// Resolve a catalog file path to (library folder path, video path).
+/// Relative paths are tried against each folder; `..` components are rejected.
+fn resolve_catalog_file(folders: &[LibraryFolder], file: &str) -> Option<(PathBuf, PathBuf)> {
+    // Catalogs exported on Windows use backslashes
+    let file = if cfg!(windows) {
+        file.to_string()
+    } else {
+        file.replace('\\', "/")
+    };
+    let file_path = Path::new(&file);
+    if file_path.components().any(|c| c == Component::ParentDir) {
+        return None;
+    }
+
+    folders.iter().find_map(|folder| {
+        let root = Path::new(&folder.path);
+        let candidate = if file_path.is_absolute() {
+            file_path.to_path_buf()
+        } else {
+            root.join(file_path)
+        };
+        (candidate.starts_with(root) && LibraryScanner::is_supported_video(&candidate))
+            .then(|| (root.to_path_buf(), candidate))
+    })
+}
+
+/// Merge catalog values into existing metadata (catalog wins where it has a value)
+fn merge_entry(mut hkmeta: HkMeta, entry: &CatalogEntry) -> HkMeta {
+    hkmeta.version = hkmeta.version.or(Some(1));
+    if entry.title.is_some() {
+        hkmeta.title = entry.title.clone();
+    }
+    if entry.artist.is_some() {
+        hkmeta.artist = entry.artist.clone();
+    }
+    if entry.year.is_some() {
+        hkmeta.year = entry.year;
+    }
+    if entry.duration.is_some() {
+        hkmeta.duration = entry.duration;
+    }
+    hkmeta
+}
+
+/// Import a catalog file, writing metadata for entries found in the library folders
+pub fn import_catalog(
+    path: &Path,
+    format: CatalogFormat,
+    folders: &[LibraryFolder],
+) -> Result<CatalogImportResult, String> {
+    let size = fs::metadata(path)
+        .map_err(|e| format!("Failed to read catalog: {}", e))?
+        .len();
+    if size > MAX_CATALOG_SIZE {
+        return Err(format!("Catalog is too large ({} bytes)", size));
+    }
+    let content = fs::read(path).map_err(|e| format!("Failed to read catalog: {}", e))?;
+    // Older catalogs are often not UTF-8; replace invalid bytes rather than failing
+    let content = String::from_utf8_lossy(&content);
+
+    let entries = format.parse(&content)?;
+    info!("Importing {} catalog entries from {:?} ({:?})", entries.len(), path, format);
+
+    let mut result = CatalogImportResult::default();
+    for entry in &entries {
+        let Some((library_path, video_path)) = resolve_catalog_file(folders, &entry.file) else {
+            debug!("Catalog entry not in library: {}", entry.file);
+            result.unmatched += 1;
+            continue;
+        };
+        result.matched += 1;
+
+        let hkmeta = LibraryScanner::read_hkmeta(&library_path, &video_path).unwrap_or_default();
+        match LibraryScanner::write_hkmeta(&library_path, &video_path, &merge_entry(hkmeta, entry)) {
+            Ok(()) => result.written += 1,
+            Err(e) => {
+                warn!("Failed to write metadata for {:?}: {}", video_path, e);
+                if result.errors.len() < MAX_REPORTED_ERRORS {
+                    result.errors.push(format!("{}: {}", video_path.display(), e));
+                }
+            }
+        }
+    }
+
+    info!(
+        "Catalog import: {} matched, {} written, {} unmatched",
+        result.matched, result.written, result.unmatched
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::scratch_dir;
+
+    #[test]
+    fn test_parse_karafun_csv() {
+        let csv = "\u{feff}Title;Artist;Year;Duration;File\n\
+                   \"Bohemian Rhapsody\";Queen;1975;5:55;Queen\\Bohemian Rhapsody.mp4\n\
+                   \"Say \"\"Hello\"\"\";Adele;;245;hello.mkv\n\
+                   No File;Nobody;2000;1:00;\n";
+        let entries = parse_karafun_csv(csv).unwrap();
+        assert_eq!(
+            entries,
+            vec![
+                CatalogEntry {
+                    file: "Queen\\Bohemian Rhapsody.mp4".to_string(),
+                    title: Some("Bohemian Rhapsody".to_string()),
+                    artist: Some("Queen".to_string()),
+                    year: Some(1975),
+                    duration: Some(355),
+                },
+                CatalogEntry {
+                    file: "hello.mkv".to_string(),
+                    title: Some("Say \"Hello\"".to_string()),
+                    artist: Some("Adele".to_string()),
+                    year: None,
+                    duration: Some(245),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_karafun_csv_requires_file_column() {
+        assert!(parse_karafun_csv("Title,Artist\nSong,Someone\n").is_err());
+        assert!(parse_karafun_csv("").is_err());
+    }
+
+    #[test]
+    fn test_parse_kjfile() {
+        let content = "# exported songbook\n\
+                       Rock/anthem.mp4\tQueen\tWe Will Rock You\t1977\n\
+                       \n\
+                       solo.mp4\t\tInstrumental\n";
+        let entries = parse_kjfile(content);
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].artist.as_deref(), Some("Queen"));
+        assert_eq!(entries[0].title.as_deref(), Some("We Will Rock You"));
+        assert_eq!(entries[0].year, Some(1977));
+        assert_eq!(entries[1].artist, None);
+        assert_eq!(entries[1].title.as_deref(), Some("Instrumental"));
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("245"), Some(245));
+        assert_eq!(parse_duration("4:05"), Some(245));
+        assert_eq!(parse_duration("1:02:03"), Some(3723));
+        assert_eq!(parse_duration("abc"), None);
+        assert_eq!(parse_duration(""), None);
+    }
+
+    #[test]
+    fn test_import_catalog_merges_matched_files() {
+        let dir = scratch_dir("catalog-import");
+        let library = dir.join("library");
+        fs::create_dir_all(library.join("Queen")).unwrap();
+        fs::write(library.join("Queen").join("Bohemian Rhapsody.mp4"), "").unwrap();
+        fs::write(library.join("hello.mkv"), "").unwrap();
+
+        // Existing metadata keeps fields the catalog doesn't provide
+        LibraryScanner::write_hkmeta(
+            &library,
+            &library.join("hello.mkv"),
+            &HkMeta {
+                version: Some(1),
+                title: Some("hello".to_string()),
+                genre: Some("Pop".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let catalog = dir.join("catalog.csv");
+        fs::write(
+            &catalog,
+            "Title;Artist;Year;Duration;File\n\
+             Bohemian Rhapsody;Queen;1975;5:55;Queen\\Bohemian Rhapsody.mp4\n\
+             Hello;Adele;2015;;hello.mkv\n\
+             Missing;Nobody;;;missing.mp4\n\
+             Escape;Nobody;;;../outside.mp4\n",
+        )
+        .unwrap();
+
+        let folders = vec![LibraryFolder {
+            id: 1,
+            path: library.to_string_lossy().to_string(),
+            name: "library".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        }];
+
+        let result = import_catalog(&catalog, CatalogFormat::KarafunCsv, &folders).unwrap();
+        assert_eq!(result.matched, 2);
+        assert_eq!(result.written, 2);
+        assert_eq!(result.unmatched, 2);
+        assert!(result.errors.is_empty());
+
+        let queen = LibraryScanner::read_hkmeta(&library, &library.join("Queen").join("Bohemian Rhapsody.mp4")).unwrap();
+        assert_eq!(queen.artist.as_deref(), Some("Queen"));
+        assert_eq!(queen.year, Some(1975));
+        assert_eq!(queen.duration, Some(355));
+
+        let hello = LibraryScanner::read_hkmeta(&library, &library.join("hello.mkv")).unwrap();
+        assert_eq!(hello.title.as_deref(), Some("Hello"));
+        assert_eq!(hello.artist.as_deref(), Some("Adele"));
+        assert_eq!(hello.genre.as_deref(), Some("Pop"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,211 @@
+//! WCAG contrast checks for singer colors, so names stay readable for low-vision hosts.
+//!
+//! Contrast math follows WCAG 2.x: relative luminance of sRGB colors and the
+//! `(lighter + 0.05) / (darker + 0.05)` ratio.
+
+/// Minimum contrast ratio for normal text (WCAG AA)
+pub const MIN_CONTRAST_RATIO: f64 = 4.5;
+
+/// Lightness change per step when nudging a color towards readable contrast
+const LIGHTNESS_STEP: f64 = 0.02;
+
+/// An sRGB color with 8-bit channels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parse `#RRGGBB` or `#RGB` (the `#` is optional)
+    pub fn from_hex(hex: &str) -> Option<Rgb> {
+        let hex = hex.trim();
+        let hex = hex.strip_prefix('#').unwrap_or(hex);
+        if !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+        let channel = |s: &str| u8::from_str_radix(s, 16).ok();
+        match hex.len() {
+            6 => Some(Rgb(channel(&hex[0..2])?, channel(&hex[2..4])?, channel(&hex[4..6])?)),
+            3 => {
+                let short = |i: usize| channel(&hex[i..=i]).map(|v| v * 17);
+                Some(Rgb(short(0)?, short(1)?, short(2)?))
+            }
+            _ => None,
+        }
+    }
+
+    /// Uppercase `#RRGGBB`
+    pub fn to_hex(self) -> String {
+        format!("#{:02X}{:02X}{:02X}", self.0, self.1, self.2)
+    }
+}
+
+/// WCAG relative luminance, from 0.0 (black) to 1.0 (white)
+pub fn relative_luminance(color: Rgb) -> f64 {
+    let linear = |channel: u8| {
+        let c = f64::from(channel) / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * linear(color.0) + 0.7152 * linear(color.1) + 0.0722 * linear(color.2)
+}
+
+/// WCAG contrast ratio between two colors, from 1.0 (same) to 21.0 (black on white)
+pub fn contrast_ratio(a: Rgb, b: Rgb) -> f64 {
+    let (la, lb) = (relative_luminance(a), relative_luminance(b));
+    let (lighter, darker) = if la >= lb { (la, lb) } else { (lb, la) };
+    (lighter + 0.05) / (darker + 0.05)
+}
+
+/// Hue (degrees), saturation and lightness (0.0-1.0)
+fn rgb_to_hsl(color: Rgb) -> (f64, f64, f64) {
+    let (r, g, b) = (
+        f64::from(color.0) / 255.0,
+        f64::from(color.1) / 255.0,
+        f64::from(color.2) / 255.0,
+    );
+    let max = r.max(g).max(b);
+    let min = r.min(g).min(b);
+    let lightness = (max + min) / 2.0;
+    let delta = max - min;
+    if delta == 0.0 {
+        return (0.0, 0.0, lightness);
+    }
+
+    let saturation = delta / (1.0 - (2.0 * lightness - 1.0).abs());
+    let hue = if max == r {
+        60.0 * ((g - b) / delta).rem_euclid(6.0)
+    } else if max == g {
+        60.0 * ((b - r) / delta + 2.0)
+    } else {
+        60.0 * ((r - g) / delta + 4.0)
+    };
+    (hue, saturation, lightness)
+}
+
+fn hsl_to_rgb(hue: f64, saturation: f64, lightness: f64) -> Rgb {
+    let chroma = (1.0 - (2.0 * lightness - 1.0).abs()) * saturation;
+    let x = chroma * (1.0 - ((hue / 60.0).rem_euclid(2.0) - 1.0).abs());
+    let m = lightness - chroma / 2.0;
+    let (r, g, b) = match hue {
+        h if h < 60.0 => (chroma, x, 0.0),
+        h if h < 120.0 => (x, chroma, 0.0),
+        h if h < 180.0 => (0.0, chroma, x),
+        h if h < 240.0 => (0.0, x, chroma),
+        h if h < 300.0 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    let channel = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    Rgb(channel(r), channel(g), channel(b))
+}
+
+/// Return `color` as `#RRGGBB` if it has at least `MIN_CONTRAST_RATIO` against
+/// `background`, otherwise the nearest color with the same hue and saturation that
+/// does: lighter on dark backgrounds, darker on light ones. Colors that can't be
+/// parsed (or an unparseable background) are returned unchanged.
+pub fn ensure_readable_color(color: &str, background: &str) -> String {
+    let (Some(rgb), Some(background)) = (Rgb::from_hex(color), Rgb::from_hex(background)) else {
+        return color.to_string();
+    };
+    if contrast_ratio(rgb, background) >= MIN_CONTRAST_RATIO {
+        return rgb.to_hex();
+    }
+
+    let (hue, saturation, mut lightness) = rgb_to_hsl(rgb);
+    let step = if relative_luminance(background) < 0.5 {
+        LIGHTNESS_STEP
+    } else {
+        -LIGHTNESS_STEP
+    };
+    loop {
+        lightness = (lightness + step).clamp(0.0, 1.0);
+        let candidate = hsl_to_rgb(hue, saturation, lightness);
+        // White or black is always readable on a dark or light background
+        if contrast_ratio(candidate, background) >= MIN_CONTRAST_RATIO || lightness <= 0.0 || lightness >= 1.0 {
+            return candidate.to_hex();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: Rgb = Rgb(255, 255, 255);
+    const BLACK: Rgb = Rgb(0, 0, 0);
+    /// Tailwind gray-800, the app's panel background
+    const DARK_BACKGROUND: &str = "#1F2937";
+
+    fn ratio(a: &str, b: &str) -> f64 {
+        contrast_ratio(Rgb::from_hex(a).unwrap(), Rgb::from_hex(b).unwrap())
+    }
+
+    #[test]
+    fn test_from_hex() {
+        assert_eq!(Rgb::from_hex("#EF4444"), Some(Rgb(0xEF, 0x44, 0x44)));
+        assert_eq!(Rgb::from_hex("ef4444"), Some(Rgb(0xEF, 0x44, 0x44)));
+        assert_eq!(Rgb::from_hex("#fff"), Some(WHITE));
+        assert_eq!(Rgb::from_hex("#12345"), None);
+        assert_eq!(Rgb::from_hex("#GGGGGG"), None);
+        assert_eq!(Rgb::from_hex("red"), None);
+        assert_eq!(Rgb(0xEF, 0x44, 0x44).to_hex(), "#EF4444");
+    }
+
+    #[test]
+    fn test_relative_luminance() {
+        assert_eq!(relative_luminance(BLACK), 0.0);
+        assert!((relative_luminance(WHITE) - 1.0).abs() < 1e-9);
+        // Mid gray #777777 is about 0.184
+        assert!((relative_luminance(Rgb(0x77, 0x77, 0x77)) - 0.184).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_contrast_ratio() {
+        assert!((contrast_ratio(BLACK, WHITE) - 21.0).abs() < 1e-9);
+        assert!((contrast_ratio(WHITE, BLACK) - 21.0).abs() < 1e-9);
+        assert_eq!(contrast_ratio(WHITE, WHITE), 1.0);
+        // #777777 on white is the classic "just below AA" gray
+        assert!((ratio("#777777", "#FFFFFF") - 4.48).abs() < 0.01);
+    }
+
+    #[test]
+    fn test_hsl_round_trip() {
+        for hex in ["#EF4444", "#22C55E", "#3B82F6", "#8B5CF6", "#777777", "#000000", "#FFFFFF"] {
+            let rgb = Rgb::from_hex(hex).unwrap();
+            let (h, s, l) = rgb_to_hsl(rgb);
+            assert_eq!(hsl_to_rgb(h, s, l), rgb, "{}", hex);
+        }
+    }
+
+    #[test]
+    fn test_ensure_readable_color_keeps_readable_colors() {
+        assert_eq!(ensure_readable_color("#eab308", DARK_BACKGROUND), "#EAB308");
+        assert_eq!(ensure_readable_color("#000", "#FFFFFF"), "#000000");
+    }
+
+    #[test]
+    fn test_ensure_readable_color_nudges_lightness() {
+        // Indigo is too dark on the dark background: lightened until it passes
+        assert!(ratio("#6366F1", DARK_BACKGROUND) < MIN_CONTRAST_RATIO);
+        let adjusted = ensure_readable_color("#6366F1", DARK_BACKGROUND);
+        assert!(ratio(&adjusted, DARK_BACKGROUND) >= MIN_CONTRAST_RATIO);
+        let (hue, _, lightness) = rgb_to_hsl(Rgb::from_hex(&adjusted).unwrap());
+        let (original_hue, _, original_lightness) = rgb_to_hsl(Rgb::from_hex("#6366F1").unwrap());
+        assert!(lightness > original_lightness);
+        assert!((hue - original_hue).abs() < 2.0);
+
+        // Yellow is too light on white: darkened
+        let adjusted = ensure_readable_color("#EAB308", "#FFFFFF");
+        assert!(ratio(&adjusted, "#FFFFFF") >= MIN_CONTRAST_RATIO);
+
+        // Grays have no saturation and are only lightened
+        assert!(ratio(&ensure_readable_color("#808080", "#404040"), "#404040") >= MIN_CONTRAST_RATIO);
+    }
+
+    #[test]
+    fn test_ensure_readable_color_ignores_unparseable_input() {
+        assert_eq!(ensure_readable_color("tomato", DARK_BACKGROUND), "tomato");
+        assert_eq!(ensure_readable_color("#EF4444", "transparent"), "#EF4444");
+    }
+}
@@ -367,6 +367,12 @@ pub fn compute_config_hash(displays: &[DisplayInfo]) -> String {
     hex::encode(result)
 }
 
+/// Check whether a configuration change removed a display (e.g. the player screen
+/// was unplugged mid-song)
+pub fn display_removed(before: &DisplayConfiguration, after: &DisplayConfiguration) -> bool {
+    after.displays.len() < before.displays.len()
+}
+
 // Simple hex encoding without additional dependency
 mod hex {
     pub fn encode(bytes: impl AsRef<[u8]>) -> String {
@@ -544,4 +550,30 @@ mod tests {
         // Hashes should be different because resolution changed
         assert_ne!(compute_config_hash(&displays1), compute_config_hash(&displays2));
     }
+
+    #[test]
+    fn test_display_removed() {
+        let display = |display_id: u32, is_main: bool| DisplayInfo {
+            display_id,
+            name: format!("Display {}", display_id),
+            x: 0,
+            y: 0,
+            width: 1920,
+            height: 1080,
+            is_main,
+        };
+        let config = |displays: Vec<DisplayInfo>| DisplayConfiguration {
+            config_hash: compute_config_hash(&displays),
+            displays,
+        };
+
+        let two = config(vec![display(1, true), display(2, false)]);
+        let one = config(vec![display(1, true)]);
+        let swapped = config(vec![display(1, true), display(3, false)]);
+
+        assert!(display_removed(&two, &one));
+        assert!(!display_removed(&one, &two));
+        // Same display count (rearranged or replaced) isn't a removal
+        assert!(!display_removed(&two, &swapped));
+    }
 }
@@ -1,7 +1,7 @@
 //! FFmpeg service for thumbnail extraction and video duration detection.
 //!
 //! This module provides utilities for extracting thumbnails from video files
-//! and detecting video duration using ffmpeg and ffprobe.
+//! and detecting video duration and dimensions using ffmpeg and ffprobe.
 
 use log::{debug, info, warn};
 use std::path::{Path, PathBuf};
@@ -9,7 +9,7 @@ use std::process::Stdio;
 use std::sync::OnceLock;
 use tokio::process::Command;
 
-use super::ytdlp::{get_expanded_path, find_executable_in_path, CommandNoWindow};
+use super::ytdlp::{get_expanded_path, find_executable_in_path, validate_chapters, Chapter, CommandNoWindow};
 
 /// Thumbnail extraction width in pixels (height auto-calculated to maintain aspect ratio)
 const THUMBNAIL_WIDTH: u32 = 320;
@@ -23,16 +23,193 @@ const MIN_THUMBNAIL_TIMESTAMP_SECS: u32 = 1;
 /// Maximum timestamp for smart thumbnail extraction (avoid spoilers in long videos)
 const MAX_THUMBNAIL_TIMESTAMP_SECS: u32 = 30;
 
+/// Fallback timestamp tried when the smart timestamp's frame cannot be extracted
+const FALLBACK_THUMBNAIL_TIMESTAMP_SECS: u32 = 1;
+
 /// Valid year range for song release dates
 const MIN_VALID_YEAR: u32 = 1900;
 const MAX_VALID_YEAR: u32 = 2099;
 
+/// Well-known display aspect ratios that near-miss dimensions are snapped to
+const COMMON_ASPECT_RATIOS: &[(u64, u64)] = &[(4, 3), (16, 9), (16, 10), (3, 2), (5, 4), (1, 1), (21, 9)];
+
+/// Relative tolerance when snapping to a common aspect ratio (2%)
+const ASPECT_RATIO_TOLERANCE: f64 = 0.02;
+
+/// Largest terms kept for an exact reduced ratio (e.g. "32:27"); larger ones are approximated
+const MAX_ASPECT_RATIO_TERM: u64 = 32;
+
 /// Cached ffmpeg path (looked up once on first use)
 static FFMPEG_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
 
 /// Cached ffprobe path (looked up once on first use)
 static FFPROBE_PATH: OnceLock<Option<PathBuf>> = OnceLock::new();
 
+/// Dimensions of a video's first video stream
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VideoStreamInfo {
+    pub width: u32,
+    pub height: u32,
+    /// Sample (pixel) aspect ratio for anamorphic video, e.g. (32, 27)
+    pub sample_aspect_ratio: Option<(u32, u32)>,
+}
+
+impl VideoStreamInfo {
+    /// Display aspect ratio label, e.g. "16:9"
+    pub fn aspect_ratio(&self) -> Option<String> {
+        reduce_aspect_ratio(self.width, self.height, self.sample_aspect_ratio)
+    }
+}
+
+fn gcd(a: u64, b: u64) -> u64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Reduce video dimensions to a display aspect ratio label.
+///
+/// Applies the sample aspect ratio for anamorphic video (e.g. 720x480 with SAR 32:27
+/// displays as 16:9), then reduces by GCD. Ratios that don't reduce to small terms
+/// (e.g. 1280x534) are snapped to a common ratio within 2%, or shown as "2.40:1".
+pub fn reduce_aspect_ratio(width: u32, height: u32, sample_aspect_ratio: Option<(u32, u32)>) -> Option<String> {
+    if width == 0 || height == 0 {
+        return None;
+    }
+
+    // Ignore unset/invalid SAR (ffprobe reports "0:1" or "N/A")
+    let (sar_num, sar_den) = match sample_aspect_ratio {
+        Some((n, d)) if n > 0 && d > 0 => (n as u64, d as u64),
+        _ => (1, 1),
+    };
+    let display_width = width as u64 * sar_num;
+    let display_height = height as u64 * sar_den;
+
+    let divisor = gcd(display_width, display_height);
+    let (w, h) = (display_width / divisor, display_height / divisor);
+    if w <= MAX_ASPECT_RATIO_TERM && h <= MAX_ASPECT_RATIO_TERM {
+        return Some(format!("{}:{}", w, h));
+    }
+
+    let ratio = display_width as f64 / display_height as f64;
+    let closest = COMMON_ASPECT_RATIOS.iter().min_by(|a, b| {
+        let da = (a.0 as f64 / a.1 as f64 - ratio).abs();
+        let db = (b.0 as f64 / b.1 as f64 - ratio).abs();
+        da.total_cmp(&db)
+    })?;
+    let closest_ratio = closest.0 as f64 / closest.1 as f64;
+    if ((closest_ratio - ratio) / closest_ratio).abs() <= ASPECT_RATIO_TOLERANCE {
+        Some(format!("{}:{}", closest.0, closest.1))
+    } else {
+        Some(format!("{:.2}:1", ratio))
+    }
+}
+
+/// Extract chapters from `ffprobe -show_chapters -of json` output.
+/// ffprobe reports times as decimal strings; untitled chapters get an empty title.
+fn parse_ffprobe_chapters(probe: &serde_json::Value) -> Vec<Chapter> {
+    let seconds = |value: &serde_json::Value| value.as_str()?.trim().parse::<f64>().ok();
+
+    let chapters = probe["chapters"]
+        .as_array()
+        .map(|chapters| chapters.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|c| {
+            Some(Chapter {
+                title: c["tags"]["title"].as_str().unwrap_or_default().trim().to_string(),
+                start_time: seconds(&c["start_time"])?,
+                end_time: seconds(&c["end_time"])?,
+            })
+        })
+        .collect();
+
+    validate_chapters(chapters, seconds(&probe["format"]["duration"]))
+}
+
+/// Parse an `HH:MM:SS.fraction` duration (as stored in Matroska `DURATION` tags) to seconds
+fn parse_clock_duration(value: &str) -> Option<f64> {
+    let mut parts = value.trim().splitn(3, ':');
+    let hours = parts.next()?.parse::<f64>().ok()?;
+    let minutes = parts.next()?.parse::<f64>().ok()?;
+    let seconds = parts.next()?.parse::<f64>().ok()?;
+    Some(hours * 3600.0 + minutes * 60.0 + seconds)
+}
+
+/// Extract (video, audio) durations of the first video and audio streams from
+/// `ffprobe -show_entries stream=... -of json` output.
+/// Embedded cover art is not counted as a video stream. Streams without a `duration`
+/// field (e.g. in MKV files) fall back to the `DURATION` tag.
+fn parse_ffprobe_stream_durations(probe: &serde_json::Value) -> (Option<f64>, Option<f64>) {
+    let streams = probe["streams"]
+        .as_array()
+        .map(|streams| streams.as_slice())
+        .unwrap_or_default();
+
+    let duration_of = |codec_type: &str| {
+        streams
+            .iter()
+            .filter(|stream| stream["codec_type"].as_str() == Some(codec_type))
+            .find(|stream| stream["disposition"]["attached_pic"].as_i64() != Some(1))
+            .and_then(|stream| {
+                stream["duration"]
+                    .as_str()
+                    .and_then(|duration| duration.trim().parse::<f64>().ok())
+                    .or_else(|| stream["tags"]["DURATION"].as_str().and_then(parse_clock_duration))
+            })
+            .filter(|duration| duration.is_finite() && *duration >= 0.0)
+    };
+
+    (duration_of("video"), duration_of("audio"))
+}
+
+/// Timestamps to try for a thumbnail, in order: the smart timestamp, then fallbacks
+fn thumbnail_timestamps(duration: Option<u32>) -> Vec<u32> {
+    let mut timestamps = match duration {
+        // Use 10% of duration, clamped to reasonable bounds
+        Some(duration) => vec![
+            (duration / 10).clamp(MIN_THUMBNAIL_TIMESTAMP_SECS, MAX_THUMBNAIL_TIMESTAMP_SECS),
+            FALLBACK_THUMBNAIL_TIMESTAMP_SECS,
+            duration / 4,
+        ],
+        None => vec![DEFAULT_THUMBNAIL_TIMESTAMP_SECS, FALLBACK_THUMBNAIL_TIMESTAMP_SECS],
+    };
+
+    let mut seen = std::collections::HashSet::new();
+    timestamps.retain(|timestamp| seen.insert(*timestamp));
+    timestamps
+}
+
+/// Run `extract` at each timestamp until one succeeds, returning the timestamp used.
+/// Returns the last error if every timestamp fails.
+async fn extract_first_success<F, Fut>(timestamps: &[u32], mut extract: F) -> Result<u32, String>
+where
+    F: FnMut(u32) -> Fut,
+    Fut: std::future::Future<Output = Result<(), String>>,
+{
+    let mut last_error = "No thumbnail timestamps to try".to_string();
+    for &timestamp in timestamps {
+        match extract(timestamp).await {
+            Ok(()) => return Ok(timestamp),
+            Err(e) => {
+                debug!("Thumbnail extraction at {}s failed: {}", timestamp, e);
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+/// Extract the version from `ffmpeg -version` / `ffprobe -version` output.
+/// The first line looks like "ffmpeg version 6.1.1 Copyright (c) 2000-2023 ...".
+fn parse_tool_version(output: &str) -> Option<String> {
+    let mut words = output.lines().next()?.split_whitespace();
+    words.find(|word| *word == "version")?;
+    words.next().map(String::from)
+}
+
 pub struct FfmpegService;
 
 impl FfmpegService {
@@ -46,6 +223,33 @@ impl FfmpegService {
         FFPROBE_PATH.get_or_init(|| find_executable_in_path("ffprobe")).clone()
     }
 
+    /// Get the installed ffmpeg version, None if not installed
+    pub async fn get_ffmpeg_version() -> Option<String> {
+        Self::get_tool_version(&Self::find_ffmpeg_path()?).await
+    }
+
+    /// Get the installed ffprobe version, None if not installed
+    pub async fn get_ffprobe_version() -> Option<String> {
+        Self::get_tool_version(&Self::find_ffprobe_path()?).await
+    }
+
+    async fn get_tool_version(tool_path: &Path) -> Option<String> {
+        let output = Command::new(tool_path)
+            .arg("-version")
+            .env("PATH", get_expanded_path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .no_window()
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+        parse_tool_version(&String::from_utf8_lossy(&output.stdout))
+    }
+
     /// Check if ffmpeg is available on the system
     pub fn is_available() -> bool {
         Self::find_ffmpeg_path().is_some()
@@ -96,6 +300,138 @@ impl FfmpegService {
             .map(|d| d.round() as u32)
     }
 
+    /// Get the durations of the first video and audio streams in seconds using ffprobe
+    ///
+    /// Returns (video, audio); a side is None if the file has no such stream, its
+    /// duration is unknown, or ffprobe is not available.
+    pub async fn get_stream_durations(video_path: &Path) -> (Option<f64>, Option<f64>) {
+        let Some(ffprobe_path) = Self::find_ffprobe_path() else {
+            return (None, None);
+        };
+
+        debug!("Getting stream durations for: {:?}", video_path);
+
+        let output = Command::new(&ffprobe_path)
+            .arg("-v")
+            .arg("error")
+            .arg("-show_entries")
+            .arg("stream=codec_type,duration:stream_tags=DURATION:stream_disposition=attached_pic")
+            .arg("-of")
+            .arg("json")
+            .arg(video_path)
+            .env("PATH", get_expanded_path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .no_window()
+            .output()
+            .await;
+
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            Ok(output) => {
+                let stderr = String::from_utf8_lossy(&output.stderr);
+                warn!("ffprobe failed for {:?}: {}", video_path, stderr);
+                return (None, None);
+            }
+            Err(e) => {
+                warn!("Failed to run ffprobe for {:?}: {}", video_path, e);
+                return (None, None);
+            }
+        };
+
+        match serde_json::from_slice::<serde_json::Value>(&output.stdout) {
+            Ok(probe) => parse_ffprobe_stream_durations(&probe),
+            Err(e) => {
+                warn!("Failed to parse ffprobe output for {:?}: {}", video_path, e);
+                (None, None)
+            }
+        }
+    }
+
+    /// Get dimensions of the first video stream using ffprobe
+    ///
+    /// Returns None if ffprobe is not available or the file has no video stream.
+    pub async fn get_video_stream_info(video_path: &Path) -> Option<VideoStreamInfo> {
+        let ffprobe_path = Self::find_ffprobe_path()?;
+
+        debug!("Getting video stream info for: {:?}", video_path);
+
+        let output = Command::new(&ffprobe_path)
+            .arg("-v")
+            .arg("error")
+            .arg("-select_streams")
+            .arg("v:0")
+            .arg("-show_entries")
+            .arg("stream=width,height,sample_aspect_ratio")
+            .arg("-of")
+            .arg("csv=p=0")
+            .arg(video_path)
+            .env("PATH", get_expanded_path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .no_window()
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("ffprobe failed for {:?}: {}", video_path, stderr);
+            return None;
+        }
+
+        // Format: "1920,1080,1:1" (SAR may be "N/A" or missing)
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let mut fields = stdout.lines().next()?.split(',').map(str::trim);
+        let width = fields.next()?.parse::<u32>().ok()?;
+        let height = fields.next()?.parse::<u32>().ok()?;
+        let sample_aspect_ratio = fields.next().and_then(|sar| {
+            let (num, den) = sar.split_once(':')?;
+            Some((num.parse::<u32>().ok()?, den.parse::<u32>().ok()?))
+        });
+
+        Some(VideoStreamInfo {
+            width,
+            height,
+            sample_aspect_ratio,
+        })
+    }
+
+    /// Get chapters embedded in a video file using ffprobe
+    ///
+    /// Returns None if ffprobe is not available or the file cannot be probed.
+    pub async fn get_chapters(video_path: &Path) -> Option<Vec<Chapter>> {
+        let ffprobe_path = Self::find_ffprobe_path()?;
+
+        debug!("Getting chapters for: {:?}", video_path);
+
+        let output = Command::new(&ffprobe_path)
+            .arg("-v")
+            .arg("error")
+            .arg("-show_chapters")
+            .arg("-show_entries")
+            .arg("format=duration")
+            .arg("-of")
+            .arg("json")
+            .arg(video_path)
+            .env("PATH", get_expanded_path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .no_window()
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("ffprobe failed for {:?}: {}", video_path, stderr);
+            return None;
+        }
+
+        let probe: serde_json::Value = serde_json::from_slice(&output.stdout).ok()?;
+        Some(parse_ffprobe_chapters(&probe))
+    }
+
     /// Get year from video metadata using ffprobe
     ///
     /// Extracts year from embedded metadata tags (date, year, creation_time).
@@ -246,20 +582,178 @@ impl FfmpegService {
     /// Extract a thumbnail at 10% of the video duration
     ///
     /// First gets the video duration, then extracts a frame at 10% of the total length.
-    /// Falls back to 5 seconds if duration cannot be determined.
+    /// Falls back to 5 seconds if duration cannot be determined. If that frame fails
+    /// (corrupt keyframe, bad seek in variable-FPS files), retries at 1 second and
+    /// then at 25% of the duration before giving up.
     pub async fn extract_thumbnail_smart(
         video_path: &Path,
         output_path: &Path,
     ) -> Result<(), String> {
-        // Try to get duration first
-        let timestamp = if let Some(duration) = Self::get_duration(video_path).await {
-            // Use 10% of duration, clamped to reasonable bounds
-            let ten_percent = duration / 10;
-            ten_percent.max(MIN_THUMBNAIL_TIMESTAMP_SECS).min(MAX_THUMBNAIL_TIMESTAMP_SECS)
-        } else {
-            DEFAULT_THUMBNAIL_TIMESTAMP_SECS
-        };
+        let duration = Self::get_duration(video_path).await;
+        let timestamps = thumbnail_timestamps(duration);
+
+        let timestamp = extract_first_success(&timestamps, |timestamp| {
+            Self::extract_thumbnail(video_path, output_path, Some(timestamp))
+        })
+        .await?;
+
+        if timestamp != timestamps[0] {
+            info!(
+                "Thumbnail for {:?} extracted at fallback timestamp {}s",
+                video_path, timestamp
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reduce_aspect_ratio_common_sizes() {
+        assert_eq!(reduce_aspect_ratio(1920, 1080, None), Some("16:9".to_string()));
+        assert_eq!(reduce_aspect_ratio(640, 480, None), Some("4:3".to_string()));
+        assert_eq!(reduce_aspect_ratio(500, 500, Some((1, 1))), Some("1:1".to_string()));
+    }
+
+    #[test]
+    fn test_reduce_aspect_ratio_anamorphic() {
+        // NTSC DVD widescreen and fullscreen
+        assert_eq!(reduce_aspect_ratio(720, 480, Some((32, 27))), Some("16:9".to_string()));
+        assert_eq!(reduce_aspect_ratio(720, 480, Some((8, 9))), Some("4:3".to_string()));
+        // PAL DVD widescreen
+        assert_eq!(reduce_aspect_ratio(720, 576, Some((64, 45))), Some("16:9".to_string()));
+        // Unset SAR is treated as square pixels
+        assert_eq!(reduce_aspect_ratio(640, 480, Some((0, 1))), Some("4:3".to_string()));
+    }
+
+    #[test]
+    fn test_reduce_aspect_ratio_approximate() {
+        // 854x480 is "16:9" rounded to even width
+        assert_eq!(reduce_aspect_ratio(854, 480, None), Some("16:9".to_string()));
+        // Cinemascope crop doesn't snap to a common ratio
+        assert_eq!(reduce_aspect_ratio(1920, 800, None), Some("12:5".to_string()));
+        assert_eq!(reduce_aspect_ratio(1280, 534, None), Some("2.40:1".to_string()));
+        assert_eq!(reduce_aspect_ratio(0, 480, None), None);
+    }
 
-        Self::extract_thumbnail(video_path, output_path, Some(timestamp)).await
+    #[test]
+    fn test_parse_ffprobe_chapters() {
+        let probe = serde_json::json!({
+            "chapters": [
+                {"id": 0, "start_time": "0.000000", "end_time": "45.500000", "tags": {"title": "Intro"}},
+                {"id": 1, "start_time": "45.500000", "end_time": "190.000000"},
+                {"id": 2, "start_time": "bogus", "end_time": "200.000000"}
+            ],
+            "format": {"duration": "180.000000"}
+        });
+
+        let chapters = parse_ffprobe_chapters(&probe);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!(chapters[0].end_time, 45.5);
+        assert_eq!(chapters[1].title, "");
+        assert_eq!(chapters[1].end_time, 180.0);
+    }
+
+    #[test]
+    fn test_parse_ffprobe_chapters_none() {
+        let probe = serde_json::json!({"chapters": [], "format": {"duration": "180.0"}});
+        assert!(parse_ffprobe_chapters(&probe).is_empty());
+    }
+
+    #[test]
+    fn test_parse_ffprobe_stream_durations() {
+        let probe = serde_json::json!({
+            "streams": [
+                {"codec_type": "video", "duration": "215.048000", "disposition": {"attached_pic": 0}},
+                {"codec_type": "audio", "duration": "198.500000", "disposition": {"attached_pic": 0}},
+                {"codec_type": "audio", "duration": "215.000000", "disposition": {"attached_pic": 0}}
+            ]
+        });
+        assert_eq!(parse_ffprobe_stream_durations(&probe), (Some(215.048), Some(198.5)));
+    }
+
+    #[test]
+    fn test_parse_ffprobe_stream_durations_mkv_tags_and_cover_art() {
+        let probe = serde_json::json!({
+            "streams": [
+                {"codec_type": "video", "duration": "0.040000", "disposition": {"attached_pic": 1}},
+                {"codec_type": "video", "disposition": {"attached_pic": 0}, "tags": {"DURATION": "00:03:20.500000000"}},
+                {"codec_type": "audio", "disposition": {"attached_pic": 0}, "tags": {"DURATION": "00:03:21.000000000"}}
+            ]
+        });
+        assert_eq!(parse_ffprobe_stream_durations(&probe), (Some(200.5), Some(201.0)));
+    }
+
+    #[test]
+    fn test_parse_ffprobe_stream_durations_missing_streams() {
+        // Audio-only file (cover art only) and a file with no readable durations
+        let probe = serde_json::json!({
+            "streams": [
+                {"codec_type": "video", "duration": "0.040000", "disposition": {"attached_pic": 1}},
+                {"codec_type": "audio", "duration": "180.0", "disposition": {"attached_pic": 0}}
+            ]
+        });
+        assert_eq!(parse_ffprobe_stream_durations(&probe), (None, Some(180.0)));
+
+        let probe = serde_json::json!({"streams": [{"codec_type": "video", "duration": "N/A"}]});
+        assert_eq!(parse_ffprobe_stream_durations(&probe), (None, None));
+        assert_eq!(parse_ffprobe_stream_durations(&serde_json::json!({})), (None, None));
+    }
+
+    #[test]
+    fn test_thumbnail_timestamps() {
+        // 10% of 200s, then 1s, then 25%
+        assert_eq!(thumbnail_timestamps(Some(200)), vec![20, 1, 50]);
+        // Long video: smart timestamp clamped to 30s
+        assert_eq!(thumbnail_timestamps(Some(600)), vec![30, 1, 150]);
+        // Short video: duplicates dropped (10% clamps to 1s, 25% of 3s is 0s)
+        assert_eq!(thumbnail_timestamps(Some(3)), vec![1, 0]);
+        assert_eq!(thumbnail_timestamps(None), vec![5, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_first_success_retries_after_failure() {
+        let mut attempts = Vec::new();
+        let result = extract_first_success(&[20, 1, 50], |timestamp| {
+            attempts.push(timestamp);
+            async move {
+                if timestamp == 20 {
+                    Err("corrupt keyframe".to_string())
+                } else {
+                    Ok(())
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result, Ok(1));
+        assert_eq!(attempts, vec![20, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_extract_first_success_reports_last_error() {
+        let result = extract_first_success(&[20, 1], |timestamp| async move {
+            Err(format!("failed at {}", timestamp))
+        })
+        .await;
+        assert_eq!(result, Err("failed at 1".to_string()));
+    }
+
+    #[test]
+    fn test_parse_tool_version() {
+        assert_eq!(
+            parse_tool_version("ffmpeg version 6.1.1 Copyright (c) 2000-2023 the FFmpeg developers\nbuilt with clang"),
+            Some("6.1.1".to_string())
+        );
+        assert_eq!(
+            parse_tool_version("ffprobe version n7.0-static https://johnvansickle.com/ffmpeg/"),
+            Some("n7.0-static".to_string())
+        );
+        assert_eq!(parse_tool_version(""), None);
+        assert_eq!(parse_tool_version("something else"), None);
     }
 }
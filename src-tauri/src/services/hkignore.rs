@@ -0,0 +1,211 @@
+//! `.hkignore` support: gitignore-style patterns that exclude files and folders
+//! inside a library folder from scanning.
+//!
+//! Supported syntax (one pattern per line, matched case-insensitively):
+//! - `#` starts a comment line; blank lines are ignored
+//! - `*` matches anything except `/`, `?` matches one character except `/`
+//! - `**` matches across folders (`**/extras`, `misc/**`)
+//! - a trailing `/` only matches folders (`extras/`)
+//! - a pattern containing `/` is relative to the library folder; otherwise it
+//!   matches a file or folder name at any depth (`*.avi`)
+//! - a leading `!` re-includes a path excluded by an earlier pattern
+
+use log::{debug, warn};
+use regex::{Regex, RegexBuilder};
+use std::fs;
+use std::path::{Component, Path};
+
+/// Name of the ignore file placed in the root of a library folder
+pub const IGNORE_FILE_NAME: &str = ".hkignore";
+
+/// Maximum ignore file size (it is a short hand-written list)
+const MAX_IGNORE_FILE_SIZE: u64 = 64 * 1024;
+
+#[derive(Debug)]
+struct IgnoreRule {
+    regex: Regex,
+    negated: bool,
+    dir_only: bool,
+}
+
+/// Parsed `.hkignore` patterns for one library folder
+#[derive(Debug, Default)]
+pub struct IgnoreRules {
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreRules {
+    /// Load `.hkignore` from a library folder (no rules if the file is missing or unreadable)
+    pub fn load(library_root: &Path) -> Self {
+        let path = library_root.join(IGNORE_FILE_NAME);
+        match fs::metadata(&path) {
+            Ok(metadata) if metadata.len() > MAX_IGNORE_FILE_SIZE => {
+                warn!("Skipping oversized {:?} ({} bytes)", path, metadata.len());
+                return Self::default();
+            }
+            Err(_) => return Self::default(),
+            _ => {}
+        }
+
+        match fs::read_to_string(&path) {
+            Ok(content) => {
+                let rules = Self::parse(&content);
+                debug!("Loaded {} ignore patterns from {:?}", rules.rules.len(), path);
+                rules
+            }
+            Err(e) => {
+                warn!("Failed to read {:?}: {}", path, e);
+                Self::default()
+            }
+        }
+    }
+
+    /// Parse ignore file content
+    pub fn parse(content: &str) -> Self {
+        let rules = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| {
+                let rule = parse_rule(line);
+                if rule.is_none() {
+                    warn!("Ignoring invalid {} pattern: {}", IGNORE_FILE_NAME, line);
+                }
+                rule
+            })
+            .collect();
+        Self { rules }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Check if a path (relative to the library folder) is excluded.
+    /// The last matching pattern wins, so `!` patterns can re-include paths.
+    pub fn is_ignored(&self, relative_path: &Path, is_dir: bool) -> bool {
+        let path = relative_path
+            .components()
+            .filter_map(|component| match component {
+                Component::Normal(name) => Some(name.to_string_lossy()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("/");
+
+        self.rules
+            .iter()
+            .filter(|rule| is_dir || !rule.dir_only)
+            .filter(|rule| rule.regex.is_match(&path))
+            .last()
+            .map(|rule| !rule.negated)
+            .unwrap_or(false)
+    }
+}
+
+fn parse_rule(line: &str) -> Option<IgnoreRule> {
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+
+    // Patterns with a slash are anchored to the library folder
+    let anchored = pattern.contains('/');
+    let pattern = pattern.trim_start_matches('/');
+    if pattern.is_empty() {
+        return None;
+    }
+
+    let prefix = if anchored { "^" } else { "^(?:.*/)?" };
+    let regex = RegexBuilder::new(&format!("{}{}$", prefix, glob_to_regex(pattern)))
+        .case_insensitive(true)
+        .build()
+        .ok()?;
+
+    Some(IgnoreRule {
+        regex,
+        negated,
+        dir_only,
+    })
+}
+
+/// Translate glob syntax to a regex body
+fn glob_to_regex(pattern: &str) -> String {
+    let mut regex = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    // `**/` matches zero or more folders
+                    chars.next();
+                    regex.push_str("(?:.*/)?");
+                } else {
+                    regex.push_str(".*");
+                }
+            }
+            '*' => regex.push_str("[^/]*"),
+            '?' => regex.push_str("[^/]"),
+            c => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_subdirectory_pattern() {
+        let rules = IgnoreRules::parse("# skip extras\nextras/\n");
+
+        assert!(rules.is_ignored(Path::new("extras"), true));
+        assert!(rules.is_ignored(Path::new("Rock/Extras"), true));
+        // Folder-only pattern doesn't match a file with that name
+        assert!(!rules.is_ignored(Path::new("extras"), false));
+        assert!(!rules.is_ignored(Path::new("extras-2"), true));
+    }
+
+    #[test]
+    fn test_anchored_subdirectory_pattern() {
+        let rules = IgnoreRules::parse("/misc\nRock/**/live\n");
+
+        assert!(rules.is_ignored(Path::new("misc"), true));
+        assert!(!rules.is_ignored(Path::new("Rock/misc"), true));
+        assert!(rules.is_ignored(Path::new("Rock/live"), true));
+        assert!(rules.is_ignored(Path::new("Rock/1980s/live"), true));
+        assert!(!rules.is_ignored(Path::new("Pop/live"), true));
+    }
+
+    #[test]
+    fn test_extension_pattern() {
+        let rules = IgnoreRules::parse("*.avi\n");
+
+        assert!(rules.is_ignored(Path::new("old.avi"), false));
+        assert!(rules.is_ignored(Path::new("Rock/Queen - Song.AVI"), false));
+        assert!(!rules.is_ignored(Path::new("Rock/Queen - Song.mp4"), false));
+    }
+
+    #[test]
+    fn test_negation_reincludes() {
+        let rules = IgnoreRules::parse("*.avi\n!keep.avi\n");
+
+        assert!(rules.is_ignored(Path::new("drop.avi"), false));
+        assert!(!rules.is_ignored(Path::new("Rock/keep.avi"), false));
+    }
+
+    #[test]
+    fn test_empty_rules() {
+        let rules = IgnoreRules::parse("\n# only comments\n   \n");
+        assert!(rules.is_empty());
+        assert!(!rules.is_ignored(Path::new("anything.mp4"), false));
+    }
+}
@@ -0,0 +1,122 @@
+//! HTTP client setup shared by services that call external APIs
+//! (MusicBrainz, Lrclib, YouTube Data API).
+
+use std::sync::RwLock;
+use std::time::Duration;
+
+/// Default user agent for API requests (MusicBrainz requires contact info)
+pub const DEFAULT_USER_AGENT: &str = concat!(
+    "HomeKaraoke/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/zalun/karaoke-app)"
+);
+
+/// Maximum length of a custom user agent
+pub const MAX_USER_AGENT_LEN: usize = 256;
+
+/// User agent configured in settings, used instead of the default when set
+static USER_AGENT_OVERRIDE: RwLock<Option<String>> = RwLock::new(None);
+
+/// Validate a custom user agent, returning it trimmed.
+/// It must be non-empty, at most `MAX_USER_AGENT_LEN` characters, and contain
+/// only printable ASCII so it is a valid header value.
+pub fn validate_user_agent(user_agent: &str) -> Result<String, String> {
+    let user_agent = user_agent.trim();
+    if user_agent.is_empty() {
+        return Err("User agent cannot be empty".to_string());
+    }
+    if user_agent.len() > MAX_USER_AGENT_LEN {
+        return Err(format!(
+            "User agent must be at most {} characters",
+            MAX_USER_AGENT_LEN
+        ));
+    }
+    if !user_agent.chars().all(|c| c == ' ' || c.is_ascii_graphic()) {
+        return Err("User agent may only contain printable ASCII characters".to_string());
+    }
+    Ok(user_agent.to_string())
+}
+
+/// Set (or clear, with None) the user agent used by clients built afterwards
+pub fn set_user_agent_override(user_agent: Option<String>) {
+    match USER_AGENT_OVERRIDE.write() {
+        Ok(mut current) => *current = user_agent,
+        Err(poisoned) => *poisoned.into_inner() = user_agent,
+    }
+}
+
+/// The user agent for API requests: the configured override, or the default
+pub fn user_agent() -> String {
+    let current = match USER_AGENT_OVERRIDE.read() {
+        Ok(current) => current.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    };
+    current.unwrap_or_else(|| DEFAULT_USER_AGENT.to_string())
+}
+
+fn client_builder(timeout: Duration) -> reqwest::ClientBuilder {
+    reqwest::Client::builder()
+        .user_agent(user_agent())
+        .timeout(timeout)
+}
+
+/// Build an HTTP client for API requests with the current user agent
+pub fn build_client(timeout: Duration) -> Result<reqwest::Client, String> {
+    client_builder(timeout)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_validate_user_agent() {
+        assert_eq!(
+            validate_user_agent("  MyKaraoke/1.0 (me@example.com) ").unwrap(),
+            "MyKaraoke/1.0 (me@example.com)"
+        );
+        assert!(validate_user_agent("").is_err());
+        assert!(validate_user_agent("   ").is_err());
+        assert!(validate_user_agent("Bad\r\nX-Injected: 1").is_err());
+        assert!(validate_user_agent("Karaoke/1.0 \u{1F3A4}").is_err());
+        assert!(validate_user_agent(&"a".repeat(MAX_USER_AGENT_LEN)).is_ok());
+        assert!(validate_user_agent(&"a".repeat(MAX_USER_AGENT_LEN + 1)).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_configured_user_agent_is_sent() {
+        // Minimal HTTP server that returns the request head it received
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            socket
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 0\r\nConnection: close\r\n\r\n")
+                .await
+                .unwrap();
+            String::from_utf8_lossy(&request).to_lowercase()
+        });
+
+        set_user_agent_override(Some("MyKaraoke/1.0 (me@example.com)".to_string()));
+        let client = client_builder(Duration::from_secs(5)).no_proxy().build().unwrap();
+        set_user_agent_override(None);
+
+        client.get(&url).send().await.unwrap();
+        let request = server.await.unwrap();
+        assert!(request.contains("user-agent: mykaraoke/1.0 (me@example.com)\r\n"));
+        assert_eq!(user_agent(), DEFAULT_USER_AGENT);
+    }
+}
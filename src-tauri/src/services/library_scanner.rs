@@ -1,13 +1,23 @@
 use crate::services::ffmpeg::FfmpegService;
-use crate::services::metadata_fetcher::{LyricsResult, MetadataFetcher, SongInfo};
+use crate::services::metadata_fetcher::{
+    FetchOptions, LyricsPreference, LyricsResult, MetadataFetcher, SongInfo, MUSICBRAINZ_RATE_LIMIT_MS,
+};
+use crate::services::hkignore::IgnoreRules;
 use log::{debug, info, warn};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::OnceLock;
 use std::time::Instant;
 
+/// Bytes read from the start of a file when computing its content hash
+const CONTENT_HASH_PREFIX_BYTES: u64 = 4 * 1024 * 1024;
+
 /// Valid year range for song release dates
 const MIN_VALID_YEAR: u32 = 1900;
 const MAX_VALID_YEAR: u32 = 2099;
@@ -41,9 +51,38 @@ const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mkv", "webm", "avi", "mov"];
 /// Maximum recursion depth for directory scanning (prevents stack overflow)
 const MAX_SCAN_DEPTH: usize = 20;
 
+/// Number of video files `probe_folder` counts before extrapolating
+const PROBE_FILE_LIMIT: usize = 2000;
+
+/// Number of files `probe_folder` stats to measure latency
+const PROBE_STAT_SAMPLES: usize = 20;
+
+/// Average stat time above which a folder is treated as a slow (network) mount
+const SLOW_STAT_MS: f64 = 10.0;
+
+/// Number of files per folder whose mtime `cache_status` compares with the last scan
+const CACHE_STATUS_SAMPLES: usize = 100;
+
+/// Maximum thumbnails extracted at once by `generate_thumbnails`
+const THUMBNAIL_CONCURRENCY: usize = 4;
+
 /// Maximum .hkmeta.json file size in bytes (1MB) to prevent DoS attacks
 const MAX_HKMETA_SIZE: u64 = 1024 * 1024;
 
+/// Default companion subfolder checked for lyrics files (e.g. "Lyrics/Song.lrc")
+pub const DEFAULT_LYRICS_SUBFOLDER: &str = "Lyrics";
+
+/// Lyrics file extensions in priority order (synced .lrc before plain .txt)
+const LYRICS_EXTENSIONS: &[&str] = &["lrc", "txt"];
+
+/// `HkMetaLyrics::lyrics_source` values
+pub const LYRICS_SOURCE_LRCLIB: &str = "lrclib";
+pub const LYRICS_SOURCE_FILE: &str = "file";
+pub const LYRICS_SOURCE_MANUAL: &str = "manual";
+
+/// `HkMetaLyrics::format` values
+const LYRICS_FORMATS: &[&str] = &["lrc", "plain"];
+
 /// Library folder stored in database
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LibraryFolder {
@@ -52,6 +91,9 @@ pub struct LibraryFolder {
     pub name: String,
     pub last_scan_at: Option<String>,
     pub file_count: u32,
+    /// Custom filename regex with named groups `title`, `artist`, `year`
+    /// (applied before the built-in filename patterns)
+    pub filename_pattern: Option<String>,
 }
 
 /// Video file found in library
@@ -69,10 +111,96 @@ pub struct LibraryVideo {
     pub is_available: bool,
     /// Path to the video thumbnail (if generated)
     pub thumbnail_path: Option<String>,
+    /// Display aspect ratio detected during scanning, e.g. "16:9" or "4:3"
+    pub aspect_ratio: Option<String>,
+    /// Thumbnail to display, chosen by `resolve_thumbnail`
+    #[serde(default)]
+    pub thumbnail: ThumbnailSource,
+    /// The other available thumbnail, to fall back to if the chosen one fails to load
+    #[serde(default)]
+    pub thumbnail_alternative: Option<ThumbnailSource>,
+}
+
+impl LibraryVideo {
+    /// Fill in `thumbnail` and `thumbnail_alternative` from the thumbnail path and YouTube ID
+    pub fn with_resolved_thumbnails(mut self) -> Self {
+        self.thumbnail = resolve_thumbnail(&self);
+        self.thumbnail_alternative = alternative_thumbnail(&self);
+        self
+    }
+}
+
+/// Where a library video's thumbnail comes from
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ThumbnailSource {
+    /// Thumbnail generated by ffmpeg during scanning
+    Local { path: String },
+    /// YouTube thumbnail for the video's linked YouTube ID
+    Youtube { url: String },
+    #[default]
+    None,
+}
+
+/// Parse a SQLite `CURRENT_TIMESTAMP` value ("YYYY-MM-DD HH:MM:SS", UTC)
+fn parse_db_timestamp(timestamp: &str) -> Option<std::time::SystemTime> {
+    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|time| time.and_utc().into())
+}
+
+/// Fraction of a folder that changed since its scan: the share of sampled files
+/// modified since then, or the relative change in file count (added or removed
+/// files) if that is larger
+fn estimate_stale_fraction(scanned_files: u32, current_files: u32, sampled: u32, changed: u32) -> f64 {
+    let modified = if sampled == 0 { 0.0 } else { changed as f64 / sampled as f64 };
+    let largest = scanned_files.max(current_files);
+    let count_change = if largest == 0 {
+        0.0
+    } else {
+        scanned_files.abs_diff(current_files) as f64 / largest as f64
+    };
+    modified.max(count_change)
+}
+
+/// YouTube thumbnail URL for a video ID, if the ID looks valid
+fn youtube_thumbnail_url(youtube_id: &str) -> Option<String> {
+    let valid = !youtube_id.is_empty()
+        && youtube_id.len() <= 20
+        && youtube_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    valid.then(|| format!("https://i.ytimg.com/vi/{}/hqdefault.jpg", youtube_id))
+}
+
+fn local_thumbnail(video: &LibraryVideo) -> Option<ThumbnailSource> {
+    video
+        .thumbnail_path
+        .clone()
+        .map(|path| ThumbnailSource::Local { path })
+}
+
+fn youtube_thumbnail(video: &LibraryVideo) -> Option<ThumbnailSource> {
+    video
+        .youtube_id
+        .as_deref()
+        .and_then(youtube_thumbnail_url)
+        .map(|url| ThumbnailSource::Youtube { url })
+}
+
+/// Pick the thumbnail to display: the generated local thumbnail when there is one
+/// (works offline), otherwise the YouTube thumbnail
+pub fn resolve_thumbnail(video: &LibraryVideo) -> ThumbnailSource {
+    local_thumbnail(video)
+        .or_else(|| youtube_thumbnail(video))
+        .unwrap_or_default()
+}
+
+/// The thumbnail not chosen by `resolve_thumbnail`, when both are available
+pub fn alternative_thumbnail(video: &LibraryVideo) -> Option<ThumbnailSource> {
+    local_thumbnail(video).and_then(|_| youtube_thumbnail(video))
 }
 
 /// Scan options
-#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct ScanOptions {
     pub create_hkmeta: bool,
     pub fetch_song_info: bool,
@@ -81,6 +209,12 @@ pub struct ScanOptions {
     pub regenerate: bool,
     /// Generate thumbnails for videos (requires ffmpeg)
     pub generate_thumbnails: bool,
+    /// Companion subfolder (next to the video) to check for lyrics files.
+    /// Defaults to "Lyrics" when not set.
+    pub lyrics_subfolder: Option<String>,
+    /// Whether to store synced (LRC) or plain lyrics when fetching
+    #[serde(default)]
+    pub lyrics_preference: LyricsPreference,
 }
 
 /// Result of scanning a folder
@@ -94,6 +228,36 @@ pub struct ScanResult {
     pub thumbnails_failed: u32,
     pub errors: Vec<String>,
     pub duration_ms: u64,
+    /// Files skipped because a previous scan already processed them (resumed scans)
+    #[serde(default)]
+    pub files_skipped: u32,
+    /// The scan was cancelled before all files were processed (it can be resumed)
+    #[serde(default)]
+    pub cancelled: bool,
+}
+
+/// Progress of fetching song info and lyrics during a scan
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScanProgress {
+    pub folder_id: i64,
+    /// Files whose metadata has been fetched so far
+    pub fetched: u32,
+    /// Files that need metadata fetched in this scan
+    pub total: u32,
+    /// Song being fetched now
+    pub title: String,
+    pub artist: Option<String>,
+    /// Estimated time left, based on the MusicBrainz rate limit
+    pub estimated_remaining_secs: u64,
+}
+
+/// Result of generating thumbnails without a full scan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct ThumbResult {
+    pub generated: u32,
+    pub failed: u32,
+    /// Videos that already had a thumbnail (when not regenerating)
+    pub skipped: u32,
 }
 
 /// Library statistics
@@ -104,6 +268,55 @@ pub struct LibraryStats {
     pub last_scan_at: Option<String>,
 }
 
+/// Filename parsing result for a single file (used to preview parsing before scanning)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FilenameParsePreview {
+    pub file_name: String,
+    pub parsed_title: String,
+    pub parsed_artist: Option<String>,
+    pub parsed_year: Option<u32>,
+}
+
+/// Distinct values available for library filter dropdowns
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct LibraryFacets {
+    pub artists: Vec<String>,
+    pub genres: Vec<String>,
+    pub languages: Vec<String>,
+    /// Earliest year in the library, or None if no file has a year
+    pub min_year: Option<u32>,
+    /// Latest year in the library, or None if no file has a year
+    pub max_year: Option<u32>,
+}
+
+/// Number of library files by one artist
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArtistCount {
+    /// Artist name as spelled in the first matching file
+    pub artist: String,
+    pub count: u32,
+}
+
+/// Number of library files from one decade
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DecadeCount {
+    /// First year of the decade (e.g. 1980), or None for files without a year
+    pub decade: Option<u32>,
+    pub count: u32,
+}
+
+/// Library breakdown for charts: top artists and songs per decade
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
+pub struct LibraryDistribution {
+    pub total_files: u32,
+    /// Artists with the most songs, most songs first
+    pub top_artists: Vec<ArtistCount>,
+    /// Songs by artists outside `top_artists` or without an artist
+    pub other_artists_count: u32,
+    /// Oldest decade first; the unknown-year bucket (if any) comes last
+    pub decades: Vec<DecadeCount>,
+}
+
 /// HomeKaraoke metadata file schema (.hkmeta.json)
 /// All fields are optional
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -125,11 +338,136 @@ pub struct HkMeta {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub duration: Option<u32>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub aspect_ratio: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub lyrics: Option<HkMetaLyrics>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub source: Option<HkMetaSource>,
+    /// Cached partial-content hash (see `LibraryScanner::compute_content_hash`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash: Option<String>,
+    /// File size in bytes when `content_hash` was computed (a change invalidates it)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content_hash_size: Option<u64>,
+}
+
+/// How a library video matched a search query, from weakest to strongest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SearchMatch {
+    /// Only the lyrics contain the query
+    Lyrics,
+    /// Album, year, genre, language or tags contain the query
+    Metadata,
+    FileName,
+    Artist,
+    /// Title contains the query
+    Title,
+    TitlePrefix,
+    ExactTitle,
+}
+
+/// Library files that share the same content hash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateContentGroup {
+    pub content_hash: String,
+    pub videos: Vec<LibraryVideo>,
+}
+
+/// Library file whose audio and video streams end at noticeably different times
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DurationMismatch {
+    pub file_path: String,
+    /// Video stream duration in seconds
+    pub video_duration: f64,
+    /// Audio stream duration in seconds
+    pub audio_duration: f64,
+    /// Absolute difference in seconds
+    pub difference: f64,
+}
+
+/// Library file whose `.hkmeta.json` duration differs a lot from the file's real length,
+/// usually because song info came from the wrong MusicBrainz match
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetadataDurationMismatch {
+    pub file_path: String,
+    /// Duration stored in .hkmeta.json, in seconds
+    pub stored_duration: u32,
+    /// Duration probed from the file, in seconds
+    pub actual_duration: u32,
+    /// Difference as a fraction of the actual duration (0.2 = 20%)
+    pub difference_ratio: f64,
+}
+
+/// How well a folder's last scan matches its files, estimated from a sample
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderCacheStatus {
+    pub folder_id: i64,
+    pub name: String,
+    pub last_scan_at: Option<String>,
+    /// Folder path exists; unavailable folders (e.g. unmounted drives) aren't estimated
+    pub available: bool,
+    /// Video files counted by the last scan
+    pub scanned_files: u32,
+    /// Video files there now (extrapolated for very large folders)
+    pub current_files: u32,
+    /// Files whose modification time was checked
+    pub sampled: u32,
+    /// Sampled files modified after the last scan
+    pub changed: u32,
+    /// Estimated fraction (0.0-1.0) of the folder that changed since the last scan
+    pub stale_fraction: f64,
+}
+
+/// Library-wide freshness estimate, so the UI can suggest a rescan
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CacheStatus {
+    pub folders: Vec<FolderCacheStatus>,
+    /// Estimated fraction (0.0-1.0) of the available library that is out of date
+    pub stale_fraction: f64,
+}
+
+/// Quick check of a folder before adding it to the library
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FolderProbe {
+    pub path: String,
+    /// Video files counted (stops at the probe limit)
+    pub video_count: u32,
+    /// The count stopped at the limit, so `estimated_total` is extrapolated
+    pub count_capped: bool,
+    /// Estimated number of video files in the whole folder
+    pub estimated_total: u32,
+    /// Average time to stat a file, in milliseconds
+    pub avg_stat_ms: f64,
+    /// Stats are slow enough to suggest a network mount
+    pub is_slow: bool,
+    /// Metadata and thumbnails can be written to the folder's .homekaraoke directory
+    pub metadata_writable: bool,
+    /// Rough time to fetch song info for every file during a scan
+    pub estimated_fetch_secs: u64,
+}
+
+/// Result of copying stranded metadata from one library folder into another
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MetadataMergeResult {
+    /// Videos in the target folder that received metadata or a thumbnail
+    pub videos_migrated: u32,
+    pub hkmeta_copied: u32,
+    pub thumbnails_copied: u32,
+    /// Files not copied because the target already had newer ones
+    pub skipped_newer: u32,
+}
+
+/// Result of moving legacy sidecar files into a folder's .homekaraoke directory
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MigrationResult {
+    /// Sidecar `.hkmeta.json` files and thumbnails moved into .homekaraoke
+    pub moved: u32,
+    /// Sidecar files deleted because .homekaraoke already had a newer copy
+    pub discarded_older: u32,
+    /// Sidecar files that could not be moved (left in place)
+    pub failed: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -138,6 +476,33 @@ pub struct HkMetaLyrics {
     pub format: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
+    /// Where the lyrics came from ("lrclib", "file" or "manual")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lyrics_source: Option<String>,
+    /// Picked or edited by the user: scans never replace them, even when regenerating
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lyrics_locked: bool,
+}
+
+impl HkMetaLyrics {
+    /// Lyrics from an API or companion file, picking synced or plain per `preference`.
+    /// None if the result has no lyrics of an allowed kind.
+    fn from_result(result: LyricsResult, source: &str, preference: LyricsPreference) -> Option<Self> {
+        let synced = result.synced_lyrics.map(|content| ("lrc", content));
+        let plain = result.plain_lyrics.map(|content| ("plain", content));
+        let (format, content) = match preference {
+            LyricsPreference::SyncedFirst => synced.or(plain),
+            LyricsPreference::PlainFirst => plain.or(synced),
+            LyricsPreference::SyncedOnly => synced,
+            LyricsPreference::PlainOnly => plain,
+        }?;
+        Some(Self {
+            format: Some(format.to_string()),
+            content: Some(content),
+            lyrics_source: Some(source.to_string()),
+            lyrics_locked: false,
+        })
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -152,7 +517,19 @@ pub struct LibraryScanner;
 
 impl LibraryScanner {
     /// Scan a folder for video files
-    pub fn scan_folder(folder: &LibraryFolder, options: &ScanOptions) -> ScanResult {
+    ///
+    /// Files are processed in path order. With `resume_after`, files up to and including
+    /// that path are skipped (they were processed by an interrupted scan).
+    /// `on_file_done(path, files_processed)` is called after each file so callers can
+    /// checkpoint progress; `files_processed` includes skipped files.
+    pub fn scan_folder(
+        folder: &LibraryFolder,
+        options: &ScanOptions,
+        resume_after: Option<&str>,
+        cancel: &AtomicBool,
+        mut on_file_done: impl FnMut(&Path, u32),
+        mut on_progress: impl FnMut(&ScanProgress),
+    ) -> ScanResult {
         let start = Instant::now();
         let mut result = ScanResult {
             folder_id: folder.id,
@@ -163,6 +540,8 @@ impl LibraryScanner {
             thumbnails_failed: 0,
             errors: Vec::new(),
             duration_ms: 0,
+            files_skipped: 0,
+            cancelled: false,
         };
 
         let path = Path::new(&folder.path);
@@ -178,8 +557,9 @@ impl LibraryScanner {
             return result;
         }
 
-        // Recursively find all video files
-        let video_files = Self::find_video_files(path);
+        // Recursively find all video files (sorted so a checkpoint marks a stable position)
+        let mut video_files = Self::find_video_files(path);
+        video_files.sort();
         result.files_found = video_files.len() as u32;
 
         info!(
@@ -187,6 +567,18 @@ impl LibraryScanner {
             result.files_found, folder.path
         );
 
+        if let Some(resume_after) = resume_after {
+            let resume_after = Path::new(resume_after);
+            result.files_skipped = video_files
+                .iter()
+                .take_while(|file_path| file_path.as_path() <= resume_after)
+                .count() as u32;
+            info!(
+                "Resuming scan of {} after {} already processed files",
+                folder.path, result.files_skipped
+            );
+        }
+
         // Create metadata fetcher if needed
         let needs_fetching = options.fetch_song_info || options.fetch_lyrics;
         let fetcher = if needs_fetching {
@@ -220,8 +612,37 @@ impl LibraryScanner {
         // Check ffmpeg availability once if thumbnail generation is enabled
         let ffmpeg_available = options.generate_thumbnails && FfmpegService::is_available();
 
+        let lyrics_subfolder = options
+            .lyrics_subfolder
+            .as_deref()
+            .unwrap_or(DEFAULT_LYRICS_SUBFOLDER);
+
+        // Compile the folder's custom filename pattern once
+        let custom_pattern = Self::folder_filename_pattern(folder);
+
+        // Count the files that will have metadata fetched, for progress reporting
+        let fetch_total = if fetcher.is_some() && runtime.is_some() {
+            video_files
+                .iter()
+                .skip(result.files_skipped as usize)
+                .filter(|file_path| {
+                    (options.create_hkmeta || options.regenerate)
+                        && (options.regenerate || Self::find_hkmeta_path(path, file_path).is_none())
+                })
+                .count() as u32
+        } else {
+            0
+        };
+        let mut fetched = 0;
+
         // Process each file
-        for file_path in &video_files {
+        let mut files_processed = result.files_skipped;
+        for file_path in video_files.iter().skip(result.files_skipped as usize) {
+            if cancel.load(Ordering::SeqCst) {
+                result.cancelled = true;
+                break;
+            }
+
             // Check for existing hkmeta in either new or legacy location
             let existing_hkmeta = Self::find_hkmeta_path(path, file_path);
 
@@ -229,43 +650,86 @@ impl LibraryScanner {
             if existing_hkmeta.is_some() && !options.regenerate {
                 result.hkmeta_existing += 1;
             } else if options.create_hkmeta || options.regenerate {
-                // Parse filename first
-                let (title, artist) = Self::parse_filename(file_path);
+                // Parse filename first (custom folder pattern, then built-in patterns)
+                let custom_parse = custom_pattern
+                    .as_ref()
+                    .and_then(|pattern| Self::parse_filename_with_pattern(file_path, pattern));
+                let (title, artist) = match &custom_parse {
+                    Some((title, artist, _)) => (title.clone(), artist.clone()),
+                    None => Self::parse_filename(file_path),
+                };
+                let custom_year = custom_parse.and_then(|(_, _, year)| year);
+
+                // Lyrics the user picked or edited are kept, even when regenerating
+                let locked_lyrics = existing_hkmeta
+                    .as_ref()
+                    .and_then(|_| Self::read_hkmeta(path, file_path))
+                    .and_then(|hkmeta| hkmeta.lyrics)
+                    .filter(|lyrics| lyrics.lyrics_locked);
+                let fetch_lyrics = options.fetch_lyrics && locked_lyrics.is_none();
+
+                // Probe the file's length up front so Lrclib can match it exactly
+                let probed_duration = if fetch_lyrics && fetcher.is_some() && ffmpeg_available {
+                    runtime.as_ref().and_then(|rt| rt.block_on(FfmpegService::get_duration(file_path)))
+                } else {
+                    None
+                };
 
                 // Fetch metadata if enabled
                 let (song_info, mut lyrics) =
                     if let (Some(ref fetcher), Some(ref rt)) = (&fetcher, &runtime) {
-                        rt.block_on(async {
+                        on_progress(&ScanProgress {
+                            folder_id: folder.id,
+                            fetched,
+                            total: fetch_total,
+                            title: title.clone(),
+                            artist: artist.clone(),
+                            estimated_remaining_secs: Self::estimated_fetch_secs(
+                                fetch_total.saturating_sub(fetched),
+                                options.fetch_song_info,
+                            ),
+                        });
+                        let fetched_metadata = rt.block_on(async {
                             fetcher
                                 .fetch_all(
                                     &title,
                                     artist.as_deref(),
-                                    options.fetch_song_info,
-                                    options.fetch_lyrics,
+                                    FetchOptions {
+                                        song_info: options.fetch_song_info,
+                                        lyrics: fetch_lyrics,
+                                        lyrics_preference: options.lyrics_preference,
+                                        duration: probed_duration,
+                                    },
+                                    cancel,
                                 )
                                 .await
-                        })
+                        });
+                        fetched += 1;
+
+                        // Cancelled mid-fetch: leave this file for a resumed scan
+                        if cancel.load(Ordering::SeqCst) {
+                            result.cancelled = true;
+                            break;
+                        }
+                        fetched_metadata
                     } else {
                         (None, None)
                     };
 
-                // Check for companion .lrc file as fallback if no lyrics from API
-                if lyrics.is_none() {
-                    if let Some(lrc_content) = Self::read_lrc_file(file_path) {
-                        debug!("Found companion .lrc file for {:?}", file_path);
-                        lyrics = Some(LyricsResult {
-                            synced_lyrics: Some(lrc_content),
-                            plain_lyrics: None,
-                            duration: None,
-                        });
-                    }
+                // Check for companion lyrics file as fallback if no lyrics from API
+                let mut lyrics_source = LYRICS_SOURCE_LRCLIB;
+                if lyrics.is_none() && locked_lyrics.is_none() {
+                    lyrics = Self::read_lyrics_file(file_path, lyrics_subfolder);
+                    lyrics_source = LYRICS_SOURCE_FILE;
                 }
 
                 // Detect duration using ffprobe if we don't have it from API
                 let api_has_duration = song_info.as_ref().map(|s| s.duration_ms.is_some()).unwrap_or(false)
                     || lyrics.as_ref().map(|l| l.duration.is_some()).unwrap_or(false);
 
-                let detected_duration = if !api_has_duration && ffmpeg_available {
+                let detected_duration = if probed_duration.is_some() {
+                    probed_duration
+                } else if !api_has_duration && ffmpeg_available {
                     if let Some(ref rt) = runtime {
                         let duration = rt.block_on(FfmpegService::get_duration(file_path));
                         if let Some(d) = duration {
@@ -282,7 +746,8 @@ impl LibraryScanner {
                 // Detect year using fallback chain: filename → ffprobe → (MusicBrainz handled in create_hkmeta)
                 let detected_year = {
                     // 1. Try filename parsing first (instant, no I/O)
-                    let year_from_filename = Self::parse_year_from_filename(file_path);
+                    let year_from_filename =
+                        custom_year.or_else(|| Self::parse_year_from_filename(file_path));
                     if year_from_filename.is_some() {
                         year_from_filename
                     } else if ffmpeg_available {
@@ -301,8 +766,26 @@ impl LibraryScanner {
                     }
                 };
 
+                // Detect aspect ratio from video stream dimensions
+                let detected_aspect_ratio = if ffmpeg_available {
+                    runtime.as_ref().and_then(|rt| {
+                        let info = rt.block_on(FfmpegService::get_video_stream_info(file_path))?;
+                        let aspect_ratio = info.aspect_ratio();
+                        debug!("Detected aspect ratio for {:?}: {:?}", file_path, aspect_ratio);
+                        aspect_ratio
+                    })
+                } else {
+                    None
+                };
+
+                // Duration from the lyrics API is preferred over ffprobe
+                let detected_duration = lyrics.as_ref().and_then(|l| l.duration).or(detected_duration);
+                let lyrics = locked_lyrics.or_else(|| {
+                    lyrics.and_then(|l| HkMetaLyrics::from_result(l, lyrics_source, options.lyrics_preference))
+                });
+
                 // Create .hkmeta.json with fetched metadata
-                match Self::create_hkmeta_with_metadata(path, file_path, &title, artist, song_info, lyrics, detected_duration, detected_year)
+                match Self::create_hkmeta_with_metadata(path, file_path, &title, artist, song_info, lyrics, detected_duration, detected_year, detected_aspect_ratio)
                 {
                     Ok(_) => {
                         result.hkmeta_created += 1;
@@ -339,6 +822,16 @@ impl LibraryScanner {
                     }
                 }
             }
+
+            files_processed += 1;
+            on_file_done(file_path, files_processed);
+        }
+
+        if result.cancelled {
+            info!(
+                "Scan of {} cancelled after {} of {} files",
+                folder.path, files_processed, result.files_found
+            );
         }
 
         result.duration_ms = start.elapsed().as_millis() as u64;
@@ -356,13 +849,67 @@ impl LibraryScanner {
         result
     }
 
-    /// Find all video files recursively with depth limiting
+    /// Preview filename parsing for the first `limit` video files in a folder
+    /// Read-only: does not create .hkmeta.json files or touch the database
+    pub fn preview_parse(folder: &LibraryFolder, limit: u32) -> Vec<FilenameParsePreview> {
+        let path = Path::new(&folder.path);
+        if !path.exists() || !path.is_dir() {
+            return Vec::new();
+        }
+
+        let mut video_files = Self::find_video_files(path);
+        video_files.sort();
+
+        let custom_pattern = Self::folder_filename_pattern(folder);
+
+        video_files
+            .iter()
+            .take(limit as usize)
+            .map(|file_path| {
+                let custom_parse = custom_pattern
+                    .as_ref()
+                    .and_then(|pattern| Self::parse_filename_with_pattern(file_path, pattern));
+                let (parsed_title, parsed_artist, custom_year) = match custom_parse {
+                    Some(parsed) => parsed,
+                    None => {
+                        let (title, artist) = Self::parse_filename(file_path);
+                        (title, artist, None)
+                    }
+                };
+                FilenameParsePreview {
+                    file_name: file_path
+                        .file_name()
+                        .unwrap_or_default()
+                        .to_string_lossy()
+                        .to_string(),
+                    parsed_title,
+                    parsed_artist,
+                    parsed_year: custom_year
+                        .or_else(|| Self::parse_year_from_filename(file_path)),
+                }
+            })
+            .collect()
+    }
+
+    /// Find all video files recursively with depth limiting, honoring the folder's `.hkignore`
     fn find_video_files(dir: &Path) -> Vec<PathBuf> {
-        Self::find_video_files_with_depth(dir, 0)
+        Self::find_video_files_limited(dir, usize::MAX)
+    }
+
+    /// Like `find_video_files`, but stops once `limit` files are found
+    fn find_video_files_limited(dir: &Path, limit: usize) -> Vec<PathBuf> {
+        let ignore_rules = IgnoreRules::load(dir);
+        Self::find_video_files_with_depth(dir, dir, &ignore_rules, 0, limit)
     }
 
     /// Internal helper for recursive file finding with depth tracking
-    fn find_video_files_with_depth(dir: &Path, depth: usize) -> Vec<PathBuf> {
+    fn find_video_files_with_depth(
+        root: &Path,
+        dir: &Path,
+        ignore_rules: &IgnoreRules,
+        depth: usize,
+        limit: usize,
+    ) -> Vec<PathBuf> {
         let mut files = Vec::new();
 
         // Prevent excessive recursion
@@ -377,6 +924,9 @@ impl LibraryScanner {
 
         if let Ok(entries) = fs::read_dir(dir) {
             for entry in entries.flatten() {
+                if files.len() >= limit {
+                    break;
+                }
                 let path = entry.path();
 
                 // Skip symlinks to prevent infinite loops
@@ -393,9 +943,24 @@ impl LibraryScanner {
                     }
                 }
 
-                if path.is_dir() {
+                let is_dir = path.is_dir();
+                if !ignore_rules.is_empty() {
+                    let relative = path.strip_prefix(root).unwrap_or(&path);
+                    if ignore_rules.is_ignored(relative, is_dir) {
+                        debug!("Skipping ignored path: {}", path.display());
+                        continue;
+                    }
+                }
+
+                if is_dir {
                     // Recurse into subdirectories with incremented depth
-                    files.extend(Self::find_video_files_with_depth(&path, depth + 1));
+                    files.extend(Self::find_video_files_with_depth(
+                        root,
+                        &path,
+                        ignore_rules,
+                        depth + 1,
+                        limit - files.len(),
+                    ));
                 } else if Self::is_video_file(&path) {
                     files.push(path);
                 }
@@ -473,96 +1038,147 @@ impl LibraryScanner {
 
     /// Search files by query across all folders
     /// If include_lyrics is true, also searches within lyrics content
-    /// Note: Results are returned in folder order (first-come). Once limit is reached,
-    /// remaining folders are not searched.
+    /// Results are ranked by `SearchMatch` (exact title first, lyrics-only matches last),
+    /// in folder order within each rank, before the limit is applied.
     pub fn search(folders: &[LibraryFolder], query: &str, limit: u32, include_lyrics: bool) -> Vec<LibraryVideo> {
         let query_lower = query.to_lowercase();
-        let mut results = Vec::new();
+        let mut matches: Vec<(SearchMatch, LibraryVideo)> = Vec::new();
 
-        'outer: for folder in folders {
+        for folder in folders {
             let path = Path::new(&folder.path);
             if !path.exists() || !path.is_dir() {
                 continue;
             }
 
             let video_files = Self::find_video_files(path);
+            let custom_pattern = Self::folder_filename_pattern(folder);
 
             for file_path in video_files {
-                if results.len() >= limit as usize {
-                    break 'outer;
-                }
-
                 // Load metadata
-                let (title, artist, album, duration, has_lyrics, has_cdg, youtube_id, thumbnail_path) =
-                    Self::load_metadata(path, &file_path);
-
-                // Search in title, artist, album, and filename
-                let file_name = file_path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string();
-
-                // Build searchable string with all metadata fields
-                let mut searchable = format!(
-                    "{} {} {} {}",
-                    title.to_lowercase(),
-                    artist.as_deref().unwrap_or("").to_lowercase(),
-                    album.as_deref().unwrap_or("").to_lowercase(),
-                    file_name.to_lowercase()
-                );
+                let video = Self::load_metadata(path, &file_path, custom_pattern.as_ref());
 
-                // Check for early match on basic fields (title/artist/filename)
-                // before loading full hkmeta which may contain large lyrics content
-                let basic_match = searchable.contains(&query_lower);
+                // Check title/artist/album/filename first, before loading full hkmeta
+                // which may contain large lyrics content
+                let mut best_match = Self::search_match(&video, &query_lower);
 
-                // Add additional metadata fields from hkmeta
-                if let Some(hkmeta) = Self::load_hkmeta(path, &file_path) {
-                    if let Some(year) = hkmeta.year {
-                        searchable.push(' ');
-                        searchable.push_str(&year.to_string());
-                    }
-                    if let Some(genre) = &hkmeta.genre {
-                        searchable.push(' ');
-                        searchable.push_str(&genre.to_lowercase());
-                    }
-                    if let Some(language) = &hkmeta.language {
-                        searchable.push(' ');
-                        searchable.push_str(&language.to_lowercase());
-                    }
-                    if let Some(tags) = &hkmeta.tags {
-                        for tag in tags {
+                if best_match.is_none() {
+                    if let Some(hkmeta) = Self::load_hkmeta(path, &file_path) {
+                        // Additional metadata fields from hkmeta
+                        let mut searchable = String::new();
+                        if let Some(year) = hkmeta.year {
                             searchable.push(' ');
-                            searchable.push_str(&tag.to_lowercase());
+                            searchable.push_str(&year.to_string());
                         }
-                    }
-                    // Only include lyrics in search if no basic match found
-                    // This optimization avoids loading/processing large lyrics content
-                    // when the file already matches on title/artist/filename
-                    if include_lyrics && !basic_match {
-                        if let Some(lyrics) = &hkmeta.lyrics {
-                            if let Some(content) = &lyrics.content {
+                        if let Some(genre) = &hkmeta.genre {
+                            searchable.push(' ');
+                            searchable.push_str(&genre.to_lowercase());
+                        }
+                        if let Some(language) = &hkmeta.language {
+                            searchable.push(' ');
+                            searchable.push_str(&language.to_lowercase());
+                        }
+                        if let Some(tags) = &hkmeta.tags {
+                            for tag in tags {
                                 searchable.push(' ');
-                                searchable.push_str(&content.to_lowercase());
+                                searchable.push_str(&tag.to_lowercase());
+                            }
+                        }
+
+                        if searchable.contains(&query_lower) {
+                            best_match = Some(SearchMatch::Metadata);
+                        } else if include_lyrics {
+                            // Lyrics are only searched when nothing else matched
+                            let lyrics_match = hkmeta
+                                .lyrics
+                                .as_ref()
+                                .and_then(|lyrics| lyrics.content.as_deref())
+                                .map(|content| content.to_lowercase().contains(&query_lower))
+                                .unwrap_or(false);
+                            if lyrics_match {
+                                best_match = Some(SearchMatch::Lyrics);
                             }
                         }
                     }
                 }
 
-                if searchable.contains(&query_lower) {
-                    results.push(LibraryVideo {
-                        file_path: file_path.to_string_lossy().to_string(),
-                        file_name,
-                        title,
-                        artist,
-                        album,
-                        duration,
-                        has_lyrics,
-                        has_cdg,
-                        youtube_id,
-                        is_available: true, // We just found it, so it's available
-                        thumbnail_path,
-                    });
+                if let Some(search_match) = best_match {
+                    matches.push((search_match, video));
+                }
+            }
+        }
+
+        // Best matches first; the stable sort keeps folder order within a match level
+        matches.sort_by(|a, b| b.0.cmp(&a.0));
+        matches
+            .into_iter()
+            .take(limit as usize)
+            .map(|(_, video)| video)
+            .collect()
+    }
+
+    /// How well a video's title, artist, album or filename matches a lowercase query
+    /// (None if none of them contain it). Lyrics and other .hkmeta fields are not checked.
+    pub fn search_match(video: &LibraryVideo, query_lower: &str) -> Option<SearchMatch> {
+        let title = video.title.trim().to_lowercase();
+        let contains = |field: Option<&str>| {
+            field
+                .map(|value| value.to_lowercase().contains(query_lower))
+                .unwrap_or(false)
+        };
+
+        if title == query_lower.trim() {
+            Some(SearchMatch::ExactTitle)
+        } else if title.starts_with(query_lower) {
+            Some(SearchMatch::TitlePrefix)
+        } else if title.contains(query_lower) {
+            Some(SearchMatch::Title)
+        } else if contains(video.artist.as_deref()) {
+            Some(SearchMatch::Artist)
+        } else if contains(Some(&video.file_name)) {
+            Some(SearchMatch::FileName)
+        } else if contains(video.album.as_deref()) {
+            Some(SearchMatch::Metadata)
+        } else {
+            None
+        }
+    }
+
+    /// Remove duplicate results from overlapping or nested library folders.
+    ///
+    /// Videos are first de-duplicated by canonical file path (`canonical_path` maps a
+    /// file path to its canonical form, e.g. `canonical_file_path`), then collapsed by
+    /// case-insensitive title and normalized artist. Within a group the best copy is kept, preferring
+    /// lyrics, then CDG, then a thumbnail; it takes the position of the group's first result.
+    pub fn dedupe_videos(
+        videos: Vec<LibraryVideo>,
+        canonical_path: impl Fn(&str) -> String,
+    ) -> Vec<LibraryVideo> {
+        fn rank(video: &LibraryVideo) -> (bool, bool, bool) {
+            (video.has_lyrics, video.has_cdg, video.thumbnail_path.is_some())
+        }
+
+        let mut seen_paths = HashSet::new();
+        let mut song_index: BTreeMap<(String, String), usize> = BTreeMap::new();
+        let mut results: Vec<LibraryVideo> = Vec::new();
+
+        for video in videos {
+            if !seen_paths.insert(canonical_path(&video.file_path)) {
+                continue;
+            }
+
+            let key = (
+                video.title.trim().to_lowercase(),
+                Self::normalize_artist(video.artist.as_deref().unwrap_or_default()),
+            );
+            match song_index.get(&key) {
+                Some(&index) => {
+                    if rank(&video) > rank(&results[index]) {
+                        results[index] = video;
+                    }
+                }
+                None => {
+                    song_index.insert(key, results.len());
+                    results.push(video);
                 }
             }
         }
@@ -570,6 +1186,23 @@ impl LibraryScanner {
         results
     }
 
+    /// Normalize an artist name for grouping and duplicate detection: case and
+    /// extra whitespace are ignored, as is a leading "The" ("The Beatles" == "beatles")
+    pub fn normalize_artist(artist: &str) -> String {
+        let normalized = artist.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase();
+        match normalized.strip_prefix("the ") {
+            Some(rest) => rest.to_string(),
+            None => normalized,
+        }
+    }
+
+    /// Resolve symlinks and relative components, falling back to the path as given
+    pub fn canonical_file_path(file_path: &str) -> String {
+        fs::canonicalize(file_path)
+            .map(|path| path.to_string_lossy().to_string())
+            .unwrap_or_else(|_| file_path.to_string())
+    }
+
     /// Browse all files in folders with optional filters
     pub fn browse(
         folders: &[LibraryFolder],
@@ -585,597 +1218,2775 @@ impl LibraryScanner {
             }
 
             let video_files = Self::find_video_files(path);
+            let custom_pattern = Self::folder_filename_pattern(folder);
 
             for file_path in video_files {
                 // Load metadata
-                let (title, artist, album, duration, has_lyrics, has_cdg, youtube_id, thumbnail_path) =
-                    Self::load_metadata(path, &file_path);
+                let video = Self::load_metadata(path, &file_path, custom_pattern.as_ref());
 
                 // Apply filters
                 if let Some(filter_has_lyrics) = has_lyrics_filter {
-                    if has_lyrics != filter_has_lyrics {
+                    if video.has_lyrics != filter_has_lyrics {
                         continue;
                     }
                 }
 
                 if let Some(filter_has_cdg) = has_cdg_filter {
-                    if has_cdg != filter_has_cdg {
+                    if video.has_cdg != filter_has_cdg {
                         continue;
                     }
                 }
 
-                let file_name = file_path
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy()
-                    .to_string();
-
-                results.push(LibraryVideo {
-                    file_path: file_path.to_string_lossy().to_string(),
-                    file_name,
-                    title,
-                    artist,
-                    album,
-                    duration,
-                    has_lyrics,
-                    has_cdg,
-                    youtube_id,
-                    is_available: true,
-                    thumbnail_path,
-                });
+                results.push(video);
             }
         }
 
         results
     }
 
-    /// Load HkMeta from .hkmeta.json file (checks both new and legacy locations)
-    fn load_hkmeta(library_path: &Path, video_path: &Path) -> Option<HkMeta> {
-        let hkmeta_path = Self::find_hkmeta_path(library_path, video_path)?;
-
-        // Check file size before reading
-        let metadata = fs::metadata(&hkmeta_path).ok()?;
-        if metadata.len() > MAX_HKMETA_SIZE {
-            warn!("Skipping oversized .hkmeta.json ({} bytes): {:?}", metadata.len(), hkmeta_path);
-            return None;
+    /// Compute a content hash used to find the same video stored under different paths.
+    ///
+    /// This is a partial-content hash: SHA-256 over the file size and the first
+    /// `CONTENT_HASH_PREFIX_BYTES` bytes, so multi-gigabyte files are never read in full.
+    /// Two files only collide if they have the same size and identical leading bytes;
+    /// files that differ solely past that prefix (rare for real videos) would be
+    /// reported as duplicates, so results are meant for review, not automatic deletion.
+    pub fn compute_content_hash(video_path: &Path) -> std::io::Result<String> {
+        let file = fs::File::open(video_path)?;
+        let size = file.metadata()?.len();
+
+        let mut hasher = Sha256::new();
+        hasher.update(size.to_le_bytes());
+
+        let mut reader = file.take(CONTENT_HASH_PREFIX_BYTES);
+        let mut buffer = [0u8; 64 * 1024];
+        loop {
+            let read = reader.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.update(&buffer[..read]);
         }
 
-        let content = fs::read_to_string(&hkmeta_path).ok()?;
-        serde_json::from_str::<HkMeta>(&content).ok()
+        Ok(format!("{:x}", hasher.finalize()))
     }
 
-    /// Load metadata from .hkmeta.json or parse from filename
-    /// Returns: (title, artist, album, duration, has_lyrics, has_cdg, youtube_id, thumbnail_path)
-    fn load_metadata(library_path: &Path, video_path: &Path) -> (String, Option<String>, Option<String>, Option<u32>, bool, bool, Option<String>, Option<String>) {
-        // Check for CDG companion file (MP3+G karaoke format)
-        let has_cdg = Self::has_cdg_companion(video_path);
-
-        // Check for thumbnail
-        let thumbnail_path = Self::get_thumbnail_path(library_path, video_path);
-        let thumbnail = if thumbnail_path.exists() {
-            Some(thumbnail_path.to_string_lossy().to_string())
-        } else {
-            None
-        };
-
-        // Try to load from .hkmeta.json (new location first, then legacy)
-        if let Some(hkmeta_path) = Self::find_hkmeta_path(library_path, video_path) {
-            // Check file size before reading to prevent DoS
-            if let Ok(metadata) = fs::metadata(&hkmeta_path) {
-                if metadata.len() > MAX_HKMETA_SIZE {
-                    warn!("Skipping oversized .hkmeta.json ({} bytes): {:?}", metadata.len(), hkmeta_path);
-                } else if let Ok(content) = fs::read_to_string(&hkmeta_path) {
-                    if let Ok(hkmeta) = serde_json::from_str::<HkMeta>(&content) {
-                        let (parsed_title, parsed_artist) = Self::parse_filename(video_path);
-                        // Check for CDG tag in metadata or companion file
-                        let has_cdg_from_meta = hkmeta
-                            .tags
-                            .as_ref()
-                            .map(|tags| tags.iter().any(|t| t.to_lowercase() == "cdg"))
-                            .unwrap_or(false);
-                        return (
-                            hkmeta.title.unwrap_or(parsed_title),
-                            hkmeta.artist.or(parsed_artist),
-                            hkmeta.album,
-                            hkmeta.duration,
-                            hkmeta.lyrics.is_some(),
-                            has_cdg || has_cdg_from_meta,
-                            hkmeta.source.and_then(|s| s.youtube_id),
-                            thumbnail,
-                        );
-                    }
-                }
+    /// Get a file's content hash, reusing the one cached in its .hkmeta.json while the
+    /// file size is unchanged. New hashes are cached only if the file already has metadata.
+    fn cached_content_hash(library_path: &Path, video_path: &Path) -> std::io::Result<String> {
+        let size = fs::metadata(video_path)?.len();
+        let hkmeta = Self::read_hkmeta(library_path, video_path);
+
+        if let Some(HkMeta {
+            content_hash: Some(hash),
+            content_hash_size: Some(hashed_size),
+            ..
+        }) = &hkmeta
+        {
+            if *hashed_size == size {
+                return Ok(hash.clone());
             }
         }
 
-        // Check for LRC companion file
-        let lrc_path = video_path.with_extension("lrc");
-        let has_lyrics = lrc_path.exists();
-
-        // Fall back to filename parsing
-        let (title, artist) = Self::parse_filename(video_path);
-        (title, artist, None, None, has_lyrics, has_cdg, None, thumbnail)
+        let hash = Self::compute_content_hash(video_path)?;
+        if let Some(mut hkmeta) = hkmeta {
+            hkmeta.content_hash = Some(hash.clone());
+            hkmeta.content_hash_size = Some(size);
+            if let Err(e) = Self::write_hkmeta(library_path, video_path, &hkmeta) {
+                warn!("Failed to cache content hash for {:?}: {}", video_path, e);
+            }
+        }
+        Ok(hash)
     }
 
-    /// Parse filename for artist and title
-    /// Supports patterns: "Artist - Title.mp4", "Title (Artist).mp4"
-    pub fn parse_filename(video_path: &Path) -> (String, Option<String>) {
-        let stem = video_path
-            .file_stem()
-            .unwrap_or_default()
-            .to_string_lossy()
-            .to_string();
-
-        // Try "Artist - Title" pattern (use find to split on first separator)
-        // This handles "Artist - Title - Subtitle" correctly but not "AC-DC - Title"
-        // For hyphenated artists, use .hkmeta.json or "Title (Artist).mp4" format
-        if let Some(idx) = stem.find(" - ") {
-            let artist = stem[..idx].trim().to_string();
-            let title = stem[idx + 3..].trim().to_string();
-            if !artist.is_empty() && !title.is_empty() {
-                return (title, Some(artist));
+    /// Videos that need a thumbnail, as (video, thumbnail path) pairs, and the number
+    /// skipped because they already have one. With `regenerate`, every video is returned.
+    pub fn thumbnail_jobs(folders: &[LibraryFolder], regenerate: bool) -> (Vec<(PathBuf, PathBuf)>, u32) {
+        let mut jobs = Vec::new();
+        let mut skipped = 0;
+        for (folder, file_path) in Self::library_video_files(folders) {
+            let thumbnail_path = Self::get_thumbnail_path(Path::new(&folder.path), &file_path);
+            if thumbnail_path.exists() && !regenerate {
+                skipped += 1;
+            } else {
+                jobs.push((file_path, thumbnail_path));
             }
         }
+        (jobs, skipped)
+    }
 
-        // Try "Title (Artist)" pattern
-        if let Some(start) = stem.rfind('(') {
-            if let Some(end) = stem.rfind(')') {
-                if start < end {
-                    let title = stem[..start].trim().to_string();
-                    let artist = stem[start + 1..end].trim().to_string();
-                    if !title.is_empty() && !artist.is_empty() {
-                        return (title, Some(artist));
+    /// Run `extract(video, thumbnail)` for each job, `THUMBNAIL_CONCURRENCY` at a time,
+    /// calling `on_progress(processed, total)` after each batch. Only thumbnails are
+    /// written; metadata is left alone.
+    pub async fn generate_thumbnails<F, Fut>(
+        jobs: Vec<(PathBuf, PathBuf)>,
+        extract: F,
+        mut on_progress: impl FnMut(u32, u32),
+    ) -> ThumbResult
+    where
+        F: Fn(PathBuf, PathBuf) -> Fut,
+        Fut: std::future::Future<Output = Result<(), String>> + Send + 'static,
+    {
+        let total = jobs.len() as u32;
+        let mut result = ThumbResult::default();
+        for chunk in jobs.chunks(THUMBNAIL_CONCURRENCY) {
+            let mut tasks = tokio::task::JoinSet::new();
+            for (video_path, thumbnail_path) in chunk.iter().cloned() {
+                let extraction = extract(video_path.clone(), thumbnail_path);
+                tasks.spawn(async move { (video_path, extraction.await) });
+            }
+
+            while let Some(joined) = tasks.join_next().await {
+                match joined {
+                    Ok((_, Ok(()))) => result.generated += 1,
+                    Ok((video_path, Err(e))) => {
+                        result.failed += 1;
+                        debug!("Failed to generate thumbnail for {:?}: {}", video_path, e);
+                    }
+                    Err(e) => {
+                        result.failed += 1;
+                        warn!("Thumbnail task failed: {}", e);
                     }
                 }
             }
+            on_progress(result.generated + result.failed, total);
         }
-
-        // Fall back to full filename as title
-        (stem, None)
+        result
     }
 
-    /// Parse year from filename using common patterns
-    /// Returns year if found (valid range: 1900-2099)
-    /// Patterns checked in priority order:
-    /// - (YYYY) - e.g., "Artist - Title (2023).mp4"
-    /// - [YYYY] - e.g., "Artist - Title [1985].mp4"
-    /// - delimited YYYY - e.g., "Artist - Title - 2020 - Karaoke.mp4"
-    /// - trailing YYYY - e.g., "Artist - Title - 2020.mp4"
-    pub fn parse_year_from_filename(video_path: &Path) -> Option<u32> {
-        let stem = video_path
-            .file_stem()
-            .unwrap_or_default()
-            .to_string_lossy();
+    /// All video files in the given folders, each with the folder it was found in.
+    /// Nested library folders can list the same file twice; each path is returned once.
+    pub fn library_video_files(folders: &[LibraryFolder]) -> Vec<(&LibraryFolder, PathBuf)> {
+        let mut seen = HashSet::new();
+        let mut files = Vec::new();
+        for folder in folders {
+            let path = Path::new(&folder.path);
+            if !path.exists() || !path.is_dir() {
+                continue;
+            }
+            for file_path in Self::find_video_files(path) {
+                if seen.insert(file_path.clone()) {
+                    files.push((folder, file_path));
+                }
+            }
+        }
+        files
+    }
 
-        // Try patterns in priority order
-        let patterns: &[&Regex] = &[
-            year_pattern_parens(),
-            year_pattern_brackets(),
-            year_pattern_delimited(),
-            year_pattern_trailing(),
-        ];
+    /// Compare a file's stream durations; files missing either stream never mismatch
+    pub fn duration_mismatch(
+        file_path: &Path,
+        video_duration: Option<f64>,
+        audio_duration: Option<f64>,
+        threshold_secs: f64,
+    ) -> Option<DurationMismatch> {
+        let (video_duration, audio_duration) = (video_duration?, audio_duration?);
+        let difference = (video_duration - audio_duration).abs();
+        (difference > threshold_secs).then(|| DurationMismatch {
+            file_path: file_path.to_string_lossy().to_string(),
+            video_duration,
+            audio_duration,
+            difference,
+        })
+    }
 
-        for pattern in patterns {
-            if let Some(caps) = pattern.captures(&stem) {
-                if let Some(year_match) = caps.get(1) {
-                    if let Ok(year) = year_match.as_str().parse::<u32>() {
-                        // Valid range: 1900-2099
-                        if year >= MIN_VALID_YEAR && year <= MAX_VALID_YEAR {
-                            debug!("Year {} extracted from filename: {:?}", year, video_path);
-                            return Some(year);
-                        }
-                    }
+    /// Compare a stored duration with the probed one; flags differences larger than
+    /// `threshold_ratio` of the actual duration. Zero-length files are never flagged.
+    pub fn metadata_duration_mismatch(
+        file_path: &Path,
+        stored_duration: u32,
+        actual_duration: u32,
+        threshold_ratio: f64,
+    ) -> Option<MetadataDurationMismatch> {
+        if actual_duration == 0 {
+            return None;
+        }
+        let difference_ratio = stored_duration.abs_diff(actual_duration) as f64 / actual_duration as f64;
+        (difference_ratio > threshold_ratio).then(|| MetadataDurationMismatch {
+            file_path: file_path.to_string_lossy().to_string(),
+            stored_duration,
+            actual_duration,
+            difference_ratio,
+        })
+    }
+
+    /// Check a folder without scanning it: count video files (up to a limit, extrapolating
+    /// beyond it), time a few file stats to spot slow network mounts, and check that
+    /// .homekaraoke can be written. Nothing is left behind on disk.
+    pub fn probe_folder(path: &Path) -> FolderProbe {
+        Self::probe_folder_with_limit(path, PROBE_FILE_LIMIT)
+    }
+
+    fn probe_folder_with_limit(path: &Path, limit: usize) -> FolderProbe {
+        let files = Self::find_video_files_limited(path, limit);
+        let count_capped = files.len() >= limit;
+        let video_count = files.len() as u32;
+
+        let estimated_total = if count_capped {
+            Self::extrapolate_file_count(path, &files)
+        } else {
+            video_count
+        };
+
+        // Time stats of the root and a spread of the files found
+        let step = (files.len() / PROBE_STAT_SAMPLES).max(1);
+        let samples: Vec<&Path> = std::iter::once(path)
+            .chain(files.iter().step_by(step).take(PROBE_STAT_SAMPLES).map(PathBuf::as_path))
+            .collect();
+        let started = Instant::now();
+        for sample in &samples {
+            let _ = fs::metadata(sample);
+        }
+        let avg_stat_ms = started.elapsed().as_secs_f64() * 1000.0 / samples.len() as f64;
+
+        let probe = FolderProbe {
+            path: path.to_string_lossy().to_string(),
+            video_count,
+            count_capped,
+            estimated_total,
+            avg_stat_ms,
+            is_slow: avg_stat_ms > SLOW_STAT_MS,
+            metadata_writable: Self::metadata_dir_writable(path),
+            estimated_fetch_secs: Self::estimated_fetch_secs(estimated_total, true),
+        };
+        info!(
+            "Probed {}: {}{} videos (estimated {}), {:.2}ms per stat, metadata writable: {}",
+            probe.path,
+            probe.video_count,
+            if count_capped { "+" } else { "" },
+            probe.estimated_total,
+            probe.avg_stat_ms,
+            probe.metadata_writable
+        );
+        probe
+    }
+
+    /// Estimate the total file count from a capped walk: the walk finishes one top-level
+    /// directory before starting the next, so scale by the share of top-level
+    /// directories it reached.
+    fn extrapolate_file_count(root: &Path, found: &[PathBuf]) -> u32 {
+        let top_level_dirs = fs::read_dir(root)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|entry| entry.path())
+                    .filter(|path| {
+                        !path.is_symlink() && path.is_dir() && path.file_name().is_some_and(|name| name != ".homekaraoke")
+                    })
+                    .count()
+            })
+            .unwrap_or(0);
+        let reached: HashSet<_> = found
+            .iter()
+            .filter_map(|file| file.strip_prefix(root).ok())
+            .filter(|relative| relative.components().count() > 1)
+            .filter_map(|relative| relative.components().next())
+            .collect();
+
+        if reached.is_empty() || top_level_dirs <= reached.len() {
+            return found.len() as u32;
+        }
+        (found.len() as u64 * top_level_dirs as u64 / reached.len() as u64).min(u32::MAX as u64) as u32
+    }
+
+    /// Whether .homekaraoke can be created (or written to, if it exists). Any directory
+    /// or file created for the check is removed again.
+    fn metadata_dir_writable(root: &Path) -> bool {
+        let dir = root.join(".homekaraoke");
+        if !dir.exists() {
+            return match fs::create_dir(&dir) {
+                Ok(()) => {
+                    let _ = fs::remove_dir(&dir);
+                    true
                 }
+                Err(_) => false,
+            };
+        }
+        let probe_file = dir.join(".write-probe");
+        match fs::write(&probe_file, b"") {
+            Ok(()) => {
+                let _ = fs::remove_file(&probe_file);
+                true
             }
+            Err(_) => false,
         }
-
-        None
     }
 
-    /// Read .hkmeta.json sidecar file (checks both new and legacy locations)
-    #[allow(dead_code)]
-    pub fn read_hkmeta(library_path: &Path, video_path: &Path) -> Option<HkMeta> {
-        let hkmeta_path = Self::find_hkmeta_path(library_path, video_path)?;
+    /// Rough time left to fetch metadata for `remaining` files. Song info lookups are
+    /// rate limited by MusicBrainz; lyrics lookups are not, so they aren't counted.
+    fn estimated_fetch_secs(remaining: u32, fetch_song_info: bool) -> u64 {
+        if !fetch_song_info {
+            return 0;
+        }
+        (remaining as u64 * MUSICBRAINZ_RATE_LIMIT_MS).div_ceil(1000)
+    }
 
-        // Check file size before reading to prevent DoS
-        match fs::metadata(&hkmeta_path) {
-            Ok(metadata) if metadata.len() > MAX_HKMETA_SIZE => {
-                warn!("Skipping oversized .hkmeta.json ({} bytes): {:?}", metadata.len(), hkmeta_path);
-                return None;
+    /// Find files with identical content (by partial-content hash) across all folders.
+    /// `on_progress(processed, total)` is called after each file is hashed.
+    pub fn find_duplicate_content(
+        folders: &[LibraryFolder],
+        mut on_progress: impl FnMut(u32, u32),
+    ) -> Vec<DuplicateContentGroup> {
+        let files = Self::library_video_files(folders);
+        let total = files.len() as u32;
+        let mut by_hash: BTreeMap<String, Vec<(&LibraryFolder, PathBuf)>> = BTreeMap::new();
+        for (i, (folder, file_path)) in files.into_iter().enumerate() {
+            match Self::cached_content_hash(Path::new(&folder.path), &file_path) {
+                Ok(hash) => by_hash.entry(hash).or_default().push((folder, file_path)),
+                Err(e) => warn!("Failed to hash {:?}: {}", file_path, e),
             }
-            Err(_) => return None,
-            _ => {}
+            on_progress(i as u32 + 1, total);
         }
 
-        match fs::read_to_string(&hkmeta_path) {
-            Ok(content) => match serde_json::from_str::<HkMeta>(&content) {
-                Ok(hkmeta) => Some(hkmeta),
-                Err(e) => {
-                    warn!("Failed to parse .hkmeta.json at {:?}: {}", hkmeta_path, e);
-                    None
+        by_hash
+            .into_iter()
+            .filter(|(_, files)| files.len() > 1)
+            .map(|(content_hash, files)| DuplicateContentGroup {
+                content_hash,
+                videos: files
+                    .into_iter()
+                    .map(|(folder, file_path)| {
+                        let custom_pattern = Self::folder_filename_pattern(folder);
+                        Self::load_metadata(Path::new(&folder.path), &file_path, custom_pattern.as_ref())
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Delete generated thumbnails (`*.thumb.*`) under a folder's .homekaraoke directory.
+    /// Metadata files are left untouched. Returns the number of files removed.
+    pub fn clear_thumbnails(folder: &LibraryFolder) -> u32 {
+        let dir = Path::new(&folder.path).join(".homekaraoke");
+        let removed = Self::remove_thumbnails_in(&dir);
+        info!("Removed {} thumbnails from {}", removed, folder.path);
+        removed
+    }
+
+    fn remove_thumbnails_in(dir: &Path) -> u32 {
+        let mut removed = 0;
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
+
+                // Skip symlinks to prevent infinite loops
+                if path.is_symlink() {
+                    continue;
+                }
+
+                if path.is_dir() {
+                    removed += Self::remove_thumbnails_in(&path);
+                } else if Self::is_thumbnail_file(&path) {
+                    match fs::remove_file(&path) {
+                        Ok(()) => removed += 1,
+                        Err(e) => warn!("Failed to remove thumbnail {:?}: {}", path, e),
+                    }
                 }
-            },
-            Err(e) => {
-                warn!("Failed to read .hkmeta.json at {:?}: {}", hkmeta_path, e);
-                None
             }
         }
+        removed
     }
 
-    /// Create .hkmeta.json from parsed filename
-    #[allow(dead_code)]
-    fn create_hkmeta_from_filename(library_path: &Path, video_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
-        let (title, artist) = Self::parse_filename(video_path);
-
-        let hkmeta = HkMeta {
-            version: Some(1),
-            title: Some(title),
-            artist,
-            ..Default::default()
-        };
+    /// Copy `.hkmeta.json` files and thumbnails stranded in `from`'s .homekaraoke directory
+    /// to the videos that now live in `into`.
+    /// A video's metadata is looked up at the same relative path under `from` first, then by
+    /// file name anywhere under `from`'s .homekaraoke (only if the name is unique there).
+    /// Target files at least as new as the source are left alone.
+    pub fn merge_folder_metadata(from: &LibraryFolder, into: &LibraryFolder) -> MetadataMergeResult {
+        let mut result = MetadataMergeResult::default();
+        let from_root = Path::new(&from.path);
+        let into_root = Path::new(&into.path);
+        if from_root == into_root {
+            return result;
+        }
 
-        let hkmeta_path = Self::get_hkmeta_path(library_path, video_path);
-        // Ensure the .homekaraoke directory exists
-        if let Some(parent) = hkmeta_path.parent() {
-            fs::create_dir_all(parent)?;
+        // Source metadata directories by video file stem
+        let mut by_stem: BTreeMap<String, BTreeSet<PathBuf>> = BTreeMap::new();
+        Self::index_metadata_dirs(&from_root.join(".homekaraoke"), &mut by_stem);
+
+        for video_path in Self::find_video_files(into_root) {
+            let stem = video_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let hkmeta_name = format!("{}.hkmeta.json", stem);
+            let thumbnail_name = format!("{}.thumb.jpg", stem);
+
+            let relative = video_path.strip_prefix(into_root).unwrap_or(&video_path);
+            let same_place = Self::get_homekaraoke_dir(from_root, &from_root.join(relative));
+            let source_dir = if same_place.join(&hkmeta_name).exists() || same_place.join(&thumbnail_name).exists() {
+                same_place
+            } else {
+                match by_stem.get(&stem) {
+                    Some(dirs) if dirs.len() == 1 => dirs.iter().next().cloned().unwrap_or_default(),
+                    Some(_) => {
+                        debug!("Ambiguous metadata for {:?} in {}, skipping", video_path, from.path);
+                        continue;
+                    }
+                    None => continue,
+                }
+            };
+
+            let existing_hkmeta = Self::find_hkmeta_path(into_root, &video_path);
+            let copies = [
+                (
+                    source_dir.join(&hkmeta_name),
+                    Self::get_hkmeta_path(into_root, &video_path),
+                    existing_hkmeta,
+                    &mut result.hkmeta_copied,
+                ),
+                (
+                    source_dir.join(&thumbnail_name),
+                    Self::get_thumbnail_path(into_root, &video_path),
+                    None,
+                    &mut result.thumbnails_copied,
+                ),
+            ];
+
+            let mut migrated = false;
+            for (source, target, existing, copied) in copies {
+                let existing = existing.unwrap_or_else(|| target.clone());
+                match Self::copy_if_newer(&source, &target, &existing) {
+                    Ok(Some(true)) => {
+                        *copied += 1;
+                        migrated = true;
+                    }
+                    Ok(Some(false)) => result.skipped_newer += 1,
+                    Ok(None) => {}
+                    Err(e) => warn!("Failed to copy {:?} to {:?}: {}", source, target, e),
+                }
+            }
+            if migrated {
+                result.videos_migrated += 1;
+            }
         }
-        let content = serde_json::to_string_pretty(&hkmeta)?;
-        fs::write(&hkmeta_path, content)?;
 
-        Ok(())
+        info!(
+            "Merged metadata from {} into {}: {} videos ({} hkmeta, {} thumbnails, {} newer kept)",
+            from.path,
+            into.path,
+            result.videos_migrated,
+            result.hkmeta_copied,
+            result.thumbnails_copied,
+            result.skipped_newer
+        );
+        result
     }
 
-    /// Create .hkmeta.json with fetched metadata from APIs
-    fn create_hkmeta_with_metadata(
-        library_path: &Path,
-        video_path: &Path,
-        title: &str,
-        artist: Option<String>,
-        song_info: Option<SongInfo>,
-        lyrics_result: Option<LyricsResult>,
-        detected_duration: Option<u32>,
-        detected_year: Option<u32>,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        // Start with parsed filename data
-        let mut hkmeta = HkMeta {
-            version: Some(1),
-            title: Some(title.to_string()),
-            artist: artist.clone(),
-            ..Default::default()
-        };
+    /// Record the directory of every `.hkmeta.json` and `.thumb.jpg` under `dir`, by video stem
+    fn index_metadata_dirs(dir: &Path, by_stem: &mut BTreeMap<String, BTreeSet<PathBuf>>) {
+        if let Ok(entries) = fs::read_dir(dir) {
+            for entry in entries.flatten() {
+                let path = entry.path();
 
-        // Check for CDG companion file and add tag if found
-        if Self::has_cdg_companion(video_path) {
-            hkmeta.tags = Some(vec!["cdg".to_string()]);
-        }
+                // Skip symlinks to prevent infinite loops
+                if path.is_symlink() {
+                    continue;
+                }
 
-        // Merge in MusicBrainz song info if available
-        if let Some(info) = song_info {
-            // Duration from API (in milliseconds, convert to seconds)
-            if let Some(duration_ms) = info.duration_ms {
-                hkmeta.duration = Some(duration_ms / 1000);
+                if path.is_dir() {
+                    Self::index_metadata_dirs(&path, by_stem);
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().to_string();
+                let stem = name
+                    .strip_suffix(".hkmeta.json")
+                    .or_else(|| name.strip_suffix(".thumb.jpg"));
+                if let Some(stem) = stem {
+                    by_stem.entry(stem.to_string()).or_default().insert(dir.to_path_buf());
+                }
             }
+        }
+    }
 
-            // Album from first release
-            if info.album.is_some() {
-                hkmeta.album = info.album;
+    /// Copy `source` to `target` unless `existing` (the target's current file) is at least as new.
+    /// Returns None if there is no source, Some(false) if the existing file was kept.
+    fn copy_if_newer(source: &Path, target: &Path, existing: &Path) -> std::io::Result<Option<bool>> {
+        let Ok(source_meta) = fs::metadata(source) else {
+            return Ok(None);
+        };
+        if let Ok(existing_meta) = fs::metadata(existing) {
+            if existing_meta.modified()? >= source_meta.modified()? {
+                return Ok(Some(false));
             }
+        }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(source, target)?;
+        Ok(Some(true))
+    }
 
-            // Year: prefer detected_year (filename/ffprobe), fallback to MusicBrainz
-            if detected_year.is_some() {
-                hkmeta.year = detected_year;
-            } else if info.year.is_some() {
-                hkmeta.year = info.year;
+    /// Move legacy sidecar `.hkmeta.json` files and thumbnails (next to the videos) into
+    /// the folder's .homekaraoke directory, so every lookup finds them in one place.
+    /// When both locations have a file, the newer one wins and the other is removed.
+    pub fn migrate_legacy_metadata(folder: &LibraryFolder) -> MigrationResult {
+        let mut result = MigrationResult::default();
+        let root = Path::new(&folder.path);
+
+        for video_path in Self::find_video_files(root) {
+            let stem = video_path.file_stem().unwrap_or_default().to_string_lossy().to_string();
+            let parent = video_path.parent().unwrap_or(root);
+            let moves = [
+                (
+                    Self::get_legacy_hkmeta_path(&video_path),
+                    Self::get_hkmeta_path(root, &video_path),
+                ),
+                (
+                    parent.join(format!("{}.thumb.jpg", stem)),
+                    Self::get_thumbnail_path(root, &video_path),
+                ),
+            ];
+
+            for (legacy, target) in moves {
+                match Self::move_legacy_file(&legacy, &target) {
+                    Ok(Some(true)) => result.moved += 1,
+                    Ok(Some(false)) => result.discarded_older += 1,
+                    Ok(None) => {}
+                    Err(e) => {
+                        warn!("Failed to move {:?} to {:?}: {}", legacy, target, e);
+                        result.failed += 1;
+                    }
+                }
             }
+        }
 
-            // Use artist credit from API if we didn't have one from filename
-            if hkmeta.artist.is_none() && info.artist_credit.is_some() {
-                hkmeta.artist = info.artist_credit;
+        info!(
+            "Migrated legacy metadata in {}: {} moved, {} older discarded, {} failed",
+            folder.path, result.moved, result.discarded_older, result.failed
+        );
+        result
+    }
+
+    /// Move `legacy` to `target`, or delete it if `target` is at least as new.
+    /// Returns None if there is no legacy file, Some(false) if it was discarded.
+    fn move_legacy_file(legacy: &Path, target: &Path) -> std::io::Result<Option<bool>> {
+        let Ok(legacy_meta) = fs::metadata(legacy) else {
+            return Ok(None);
+        };
+        if !legacy_meta.is_file() {
+            return Ok(None);
+        }
+        if let Ok(target_meta) = fs::metadata(target) {
+            if target_meta.modified()? >= legacy_meta.modified()? {
+                fs::remove_file(legacy)?;
+                return Ok(Some(false));
             }
         }
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // .homekaraoke may be on another file system (e.g. a symlinked directory)
+        if fs::rename(legacy, target).is_err() {
+            fs::copy(legacy, target)?;
+            fs::remove_file(legacy)?;
+        }
+        Ok(Some(true))
+    }
 
-        // Add lyrics if available
-        if let Some(lyrics) = lyrics_result {
-            // Prefer synced lyrics over plain
-            if let Some(synced) = lyrics.synced_lyrics {
-                hkmeta.lyrics = Some(HkMetaLyrics {
-                    format: Some("lrc".to_string()),
-                    content: Some(synced),
-                });
-            } else if let Some(plain) = lyrics.plain_lyrics {
-                hkmeta.lyrics = Some(HkMetaLyrics {
-                    format: Some("plain".to_string()),
-                    content: Some(plain),
-                });
+    /// Check if a path is a generated thumbnail (`<stem>.thumb.<ext>`)
+    fn is_thumbnail_file(path: &Path) -> bool {
+        path.file_stem()
+            .and_then(|stem| stem.to_str())
+            .map(|stem| stem.ends_with(".thumb"))
+            .unwrap_or(false)
+    }
+
+    /// Collect distinct artists, genres and languages, and the year range, across all folders
+    /// Artists, genres and languages are de-duplicated case-insensitively (first spelling wins)
+    /// and sorted alphabetically.
+    pub fn facets(folders: &[LibraryFolder]) -> LibraryFacets {
+        let mut artists: BTreeMap<String, String> = BTreeMap::new();
+        let mut genres: BTreeMap<String, String> = BTreeMap::new();
+        let mut languages: BTreeMap<String, String> = BTreeMap::new();
+        let mut min_year: Option<u32> = None;
+        let mut max_year: Option<u32> = None;
+
+        for folder in folders {
+            let path = Path::new(&folder.path);
+            if !path.exists() || !path.is_dir() {
+                continue;
             }
 
-            // Use duration from lyrics API if we don't have one yet
-            if hkmeta.duration.is_none() {
-                if let Some(duration) = lyrics.duration {
-                    hkmeta.duration = Some(duration);
+            let custom_pattern = Self::folder_filename_pattern(folder);
+
+            // Sort so the "first spelling wins" rule is deterministic
+            let mut video_files = Self::find_video_files(path);
+            video_files.sort();
+
+            for file_path in video_files {
+                let hkmeta = Self::load_hkmeta(path, &file_path).unwrap_or_default();
+
+                if let Some(artist) = Self::file_artist(&hkmeta, &file_path, custom_pattern.as_ref()) {
+                    artists.entry(artist.to_lowercase()).or_insert(artist);
+                }
+
+                if let Some(genre) = hkmeta.genre.map(|g| g.trim().to_string()).filter(|g| !g.is_empty()) {
+                    genres.entry(genre.to_lowercase()).or_insert(genre);
+                }
+
+                if let Some(language) = hkmeta.language.map(|l| l.trim().to_string()).filter(|l| !l.is_empty()) {
+                    languages.entry(language.to_lowercase()).or_insert(language);
+                }
+
+                if let Some(year) = hkmeta.year.or_else(|| Self::parse_year_from_filename(&file_path)) {
+                    min_year = Some(min_year.map_or(year, |min| min.min(year)));
+                    max_year = Some(max_year.map_or(year, |max| max.max(year)));
                 }
             }
         }
 
-        // Use ffprobe-detected duration if we still don't have one
-        if hkmeta.duration.is_none() && detected_duration.is_some() {
-            hkmeta.duration = detected_duration;
+        LibraryFacets {
+            artists: artists.into_values().collect(),
+            genres: genres.into_values().collect(),
+            languages: languages.into_values().collect(),
+            min_year,
+            max_year,
         }
+    }
 
-        // Use detected year (filename/ffprobe) if we still don't have one
-        if hkmeta.year.is_none() && detected_year.is_some() {
-            hkmeta.year = detected_year;
+    /// Estimate how out of date each folder's last scan is: compare the modification
+    /// times of a spread of files with the scan time, and the file count with the
+    /// scanned count. Walks at most `PROBE_FILE_LIMIT` files per folder.
+    pub fn cache_status(folders: &[LibraryFolder]) -> CacheStatus {
+        let statuses: Vec<FolderCacheStatus> = folders.iter().map(Self::folder_cache_status).collect();
+
+        let (weighted, total) = statuses
+            .iter()
+            .filter(|status| status.available)
+            .fold((0.0, 0u64), |(weighted, total), status| {
+                let size = status.scanned_files.max(status.current_files) as u64;
+                (weighted + status.stale_fraction * size as f64, total + size)
+            });
+        let stale_fraction = if total == 0 { 0.0 } else { weighted / total as f64 };
+
+        info!(
+            "Library cache status: {:.0}% stale across {} folders",
+            stale_fraction * 100.0,
+            statuses.len()
+        );
+        CacheStatus {
+            folders: statuses,
+            stale_fraction,
         }
+    }
 
-        let hkmeta_path = Self::get_hkmeta_path(library_path, video_path);
-        // Ensure the .homekaraoke directory exists
-        if let Some(parent) = hkmeta_path.parent() {
-            fs::create_dir_all(parent)?;
+    fn folder_cache_status(folder: &LibraryFolder) -> FolderCacheStatus {
+        let root = Path::new(&folder.path);
+        let mut status = FolderCacheStatus {
+            folder_id: folder.id,
+            name: folder.name.clone(),
+            last_scan_at: folder.last_scan_at.clone(),
+            available: root.is_dir(),
+            scanned_files: folder.file_count,
+            current_files: 0,
+            sampled: 0,
+            changed: 0,
+            stale_fraction: 0.0,
+        };
+        if !status.available {
+            return status;
         }
-        let content = serde_json::to_string_pretty(&hkmeta)?;
-        fs::write(&hkmeta_path, content)?;
 
-        info!(
-            "Created .hkmeta.json for {:?}: title={:?}, artist={:?}, album={:?}, year={:?}, duration={:?}s, has_lyrics={}, has_cdg={}",
-            video_path.file_name(),
-            hkmeta.title,
-            hkmeta.artist,
-            hkmeta.album,
-            hkmeta.year,
-            hkmeta.duration,
-            hkmeta.lyrics.is_some(),
-            hkmeta.tags.is_some()
-        );
+        let files = Self::find_video_files_limited(root, PROBE_FILE_LIMIT);
+        status.current_files = if files.len() >= PROBE_FILE_LIMIT {
+            Self::extrapolate_file_count(root, &files)
+        } else {
+            files.len() as u32
+        };
 
-        Ok(())
+        // Never scanned (or unparseable scan time): everything is out of date
+        let Some(scanned_at) = folder.last_scan_at.as_deref().and_then(parse_db_timestamp) else {
+            status.stale_fraction = if status.current_files > 0 { 1.0 } else { 0.0 };
+            return status;
+        };
+
+        let step = (files.len() / CACHE_STATUS_SAMPLES).max(1);
+        for file in files.iter().step_by(step).take(CACHE_STATUS_SAMPLES) {
+            let Ok(modified) = fs::metadata(file).and_then(|meta| meta.modified()) else {
+                continue;
+            };
+            status.sampled += 1;
+            if modified > scanned_at {
+                status.changed += 1;
+            }
+        }
+
+        status.stale_fraction =
+            estimate_stale_fraction(status.scanned_files, status.current_files, status.sampled, status.changed);
+        status
     }
 
-    /// Check if a file exists
+    /// Number of files per artist across all folders, most songs first.
+    /// Artists are grouped by `normalize_artist`; files without an artist are skipped.
+    pub fn artist_counts(folders: &[LibraryFolder]) -> Vec<ArtistCount> {
+        Self::tally_artists_and_years(folders).0
+    }
+
+    /// Top `top_artists` artists and per-decade counts across all folders.
+    /// Years come from .hkmeta.json or the filename; files without one are
+    /// counted in a `None` decade bucket.
+    pub fn distribution(folders: &[LibraryFolder], top_artists: usize) -> LibraryDistribution {
+        let (mut artists, years, total_files) = Self::tally_artists_and_years(folders);
+
+        let mut decades: BTreeMap<Option<u32>, u32> = BTreeMap::new();
+        for year in years {
+            *decades.entry(year.map(|y| y / 10 * 10)).or_insert(0) += 1;
+        }
+        let unknown = decades.remove(&None);
+        let mut decades: Vec<DecadeCount> = decades
+            .into_iter()
+            .map(|(decade, count)| DecadeCount { decade, count })
+            .collect();
+        if let Some(count) = unknown {
+            decades.push(DecadeCount { decade: None, count });
+        }
+
+        artists.truncate(top_artists);
+        let top_count: u32 = artists.iter().map(|a| a.count).sum();
+        LibraryDistribution {
+            total_files,
+            top_artists: artists,
+            other_artists_count: total_files - top_count,
+            decades,
+        }
+    }
+
+    /// Per-artist counts (sorted as in `artist_counts`), each file's year, and the
+    /// number of files, in one pass over the library
+    fn tally_artists_and_years(folders: &[LibraryFolder]) -> (Vec<ArtistCount>, Vec<Option<u32>>, u32) {
+        // Sort so the "first spelling wins" rule is deterministic
+        let mut files = Self::library_video_files(folders);
+        files.sort_by(|a, b| a.1.cmp(&b.1));
+        let total_files = files.len() as u32;
+
+        let mut patterns: BTreeMap<i64, Option<Regex>> = BTreeMap::new();
+        let mut counts: BTreeMap<String, ArtistCount> = BTreeMap::new();
+        let mut years = Vec::with_capacity(files.len());
+        for (folder, file_path) in files {
+            let custom_pattern = patterns
+                .entry(folder.id)
+                .or_insert_with(|| Self::folder_filename_pattern(folder));
+            let hkmeta = Self::load_hkmeta(Path::new(&folder.path), &file_path).unwrap_or_default();
+
+            if let Some(artist) = Self::file_artist(&hkmeta, &file_path, custom_pattern.as_ref()) {
+                counts
+                    .entry(Self::normalize_artist(&artist))
+                    .or_insert(ArtistCount { artist, count: 0 })
+                    .count += 1;
+            }
+            years.push(hkmeta.year.or_else(|| Self::parse_year_from_filename(&file_path)));
+        }
+
+        let mut counts: Vec<ArtistCount> = counts.into_values().collect();
+        counts.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then_with(|| a.artist.to_lowercase().cmp(&b.artist.to_lowercase()))
+        });
+        (counts, years, total_files)
+    }
+
+    /// Artist from .hkmeta.json, or parsed from the filename (None if blank)
+    fn file_artist(hkmeta: &HkMeta, video_path: &Path, custom_pattern: Option<&Regex>) -> Option<String> {
+        let artist = match &hkmeta.artist {
+            Some(artist) => Some(artist.clone()),
+            None => Self::parse_title_artist(video_path, custom_pattern).1,
+        };
+        artist.map(|a| a.trim().to_string()).filter(|a| !a.is_empty())
+    }
+
+    /// Load HkMeta from .hkmeta.json file (checks both new and legacy locations)
+    fn load_hkmeta(library_path: &Path, video_path: &Path) -> Option<HkMeta> {
+        let hkmeta_path = Self::find_hkmeta_path(library_path, video_path)?;
+
+        // Check file size before reading
+        let metadata = fs::metadata(&hkmeta_path).ok()?;
+        if metadata.len() > MAX_HKMETA_SIZE {
+            warn!("Skipping oversized .hkmeta.json ({} bytes): {:?}", metadata.len(), hkmeta_path);
+            return None;
+        }
+
+        let content = fs::read_to_string(&hkmeta_path).ok()?;
+        serde_json::from_str::<HkMeta>(&content).ok()
+    }
+
+    /// Load metadata from .hkmeta.json or parse from filename
+    fn load_metadata(library_path: &Path, video_path: &Path, custom_pattern: Option<&Regex>) -> LibraryVideo {
+        let file_path = video_path.to_string_lossy().to_string();
+        let file_name = video_path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        // Check for CDG companion file (MP3+G karaoke format)
+        let has_cdg = Self::has_cdg_companion(video_path);
+
+        // Check for thumbnail
+        let thumbnail_path = Self::get_thumbnail_path(library_path, video_path);
+        let thumbnail = if thumbnail_path.exists() {
+            Some(thumbnail_path.to_string_lossy().to_string())
+        } else {
+            None
+        };
+
+        // Try to load from .hkmeta.json (new location first, then legacy)
+        if let Some(hkmeta_path) = Self::find_hkmeta_path(library_path, video_path) {
+            // Check file size before reading to prevent DoS
+            if let Ok(metadata) = fs::metadata(&hkmeta_path) {
+                if metadata.len() > MAX_HKMETA_SIZE {
+                    warn!("Skipping oversized .hkmeta.json ({} bytes): {:?}", metadata.len(), hkmeta_path);
+                } else if let Ok(content) = fs::read_to_string(&hkmeta_path) {
+                    if let Ok(hkmeta) = serde_json::from_str::<HkMeta>(&content) {
+                        let (parsed_title, parsed_artist) = Self::parse_title_artist(video_path, custom_pattern);
+                        // Check for CDG tag in metadata or companion file
+                        let has_cdg_from_meta = hkmeta
+                            .tags
+                            .as_ref()
+                            .map(|tags| tags.iter().any(|t| t.to_lowercase() == "cdg"))
+                            .unwrap_or(false);
+                        return LibraryVideo {
+                            file_path,
+                            file_name,
+                            title: hkmeta.title.unwrap_or(parsed_title),
+                            artist: hkmeta.artist.or(parsed_artist),
+                            album: hkmeta.album,
+                            duration: hkmeta.duration,
+                            has_lyrics: hkmeta.lyrics.is_some(),
+                            has_cdg: has_cdg || has_cdg_from_meta,
+                            youtube_id: hkmeta.source.and_then(|s| s.youtube_id),
+                            is_available: true, // We just found it, so it's available
+                            thumbnail_path: thumbnail,
+                            aspect_ratio: hkmeta.aspect_ratio,
+                            thumbnail: ThumbnailSource::None,
+                            thumbnail_alternative: None,
+                        }
+                        .with_resolved_thumbnails();
+                    }
+                }
+            }
+        }
+
+        // Check for companion lyrics file (.lrc/.txt, next to video or in lyrics subfolder)
+        let has_lyrics = Self::find_lyrics_file(video_path, DEFAULT_LYRICS_SUBFOLDER).is_some();
+
+        // Fall back to filename parsing
+        let (title, artist) = Self::parse_title_artist(video_path, custom_pattern);
+        LibraryVideo {
+            file_path,
+            file_name,
+            title,
+            artist,
+            album: None,
+            duration: None,
+            has_lyrics,
+            has_cdg,
+            youtube_id: None,
+            is_available: true,
+            thumbnail_path: thumbnail,
+            aspect_ratio: None,
+            thumbnail: ThumbnailSource::None,
+            thumbnail_alternative: None,
+        }
+        .with_resolved_thumbnails()
+    }
+
+    /// Compile a custom filename pattern
+    /// The regex is matched against the file stem and must contain a `title` named group;
+    /// `artist` and `year` groups are optional
+    pub fn compile_filename_pattern(pattern: &str) -> Result<Regex, String> {
+        let regex = Regex::new(pattern).map_err(|e| format!("Invalid filename pattern: {}", e))?;
+        if !regex.capture_names().flatten().any(|name| name == "title") {
+            return Err("Filename pattern must contain a (?P<title>...) named group".to_string());
+        }
+        Ok(regex)
+    }
+
+    /// Compile a folder's custom filename pattern, if set
+    /// Invalid patterns are logged and ignored (patterns are validated when saved)
+    fn folder_filename_pattern(folder: &LibraryFolder) -> Option<Regex> {
+        let pattern = folder.filename_pattern.as_deref()?;
+        match Self::compile_filename_pattern(pattern) {
+            Ok(regex) => Some(regex),
+            Err(e) => {
+                warn!("Ignoring filename pattern for {}: {}", folder.path, e);
+                None
+            }
+        }
+    }
+
+    /// Parse filename using a custom pattern with named groups `title`, `artist`, `year`
+    /// Returns None if the pattern doesn't match or captures an empty title
+    /// Returns: (title, artist, year)
+    pub fn parse_filename_with_pattern(video_path: &Path, pattern: &Regex) -> Option<(String, Option<String>, Option<u32>)> {
+        let stem = video_path.file_stem()?.to_string_lossy();
+        let caps = pattern.captures(&stem)?;
+
+        let title = caps.name("title")?.as_str().trim().to_string();
+        if title.is_empty() {
+            return None;
+        }
+        let artist = caps
+            .name("artist")
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|a| !a.is_empty());
+        let year = caps
+            .name("year")
+            .and_then(|m| m.as_str().trim().parse::<u32>().ok())
+            .filter(|y| (MIN_VALID_YEAR..=MAX_VALID_YEAR).contains(y));
+
+        Some((title, artist, year))
+    }
+
+    /// Parse title and artist, trying the custom pattern before the built-in patterns
+    fn parse_title_artist(video_path: &Path, custom_pattern: Option<&Regex>) -> (String, Option<String>) {
+        custom_pattern
+            .and_then(|pattern| Self::parse_filename_with_pattern(video_path, pattern))
+            .map(|(title, artist, _)| (title, artist))
+            .unwrap_or_else(|| Self::parse_filename(video_path))
+    }
+
+    /// Parse filename for artist and title
+    /// Supports patterns: "Artist - Title.mp4", "Title (Artist).mp4"
+    pub fn parse_filename(video_path: &Path) -> (String, Option<String>) {
+        let stem = video_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+
+        // Try "Artist - Title" pattern (use find to split on first separator)
+        // This handles "Artist - Title - Subtitle" correctly but not "AC-DC - Title"
+        // For hyphenated artists, use .hkmeta.json or "Title (Artist).mp4" format
+        if let Some(idx) = stem.find(" - ") {
+            let artist = stem[..idx].trim().to_string();
+            let title = stem[idx + 3..].trim().to_string();
+            if !artist.is_empty() && !title.is_empty() {
+                return (title, Some(artist));
+            }
+        }
+
+        // Try "Title (Artist)" pattern
+        if let Some(start) = stem.rfind('(') {
+            if let Some(end) = stem.rfind(')') {
+                if start < end {
+                    let title = stem[..start].trim().to_string();
+                    let artist = stem[start + 1..end].trim().to_string();
+                    if !title.is_empty() && !artist.is_empty() {
+                        return (title, Some(artist));
+                    }
+                }
+            }
+        }
+
+        // Fall back to full filename as title
+        (stem, None)
+    }
+
+    /// Parse year from filename using common patterns
+    /// Returns year if found (valid range: 1900-2099)
+    /// Patterns checked in priority order:
+    /// - (YYYY) - e.g., "Artist - Title (2023).mp4"
+    /// - [YYYY] - e.g., "Artist - Title [1985].mp4"
+    /// - delimited YYYY - e.g., "Artist - Title - 2020 - Karaoke.mp4"
+    /// - trailing YYYY - e.g., "Artist - Title - 2020.mp4"
+    pub fn parse_year_from_filename(video_path: &Path) -> Option<u32> {
+        let stem = video_path
+            .file_stem()
+            .unwrap_or_default()
+            .to_string_lossy();
+
+        // Try patterns in priority order
+        let patterns: &[&Regex] = &[
+            year_pattern_parens(),
+            year_pattern_brackets(),
+            year_pattern_delimited(),
+            year_pattern_trailing(),
+        ];
+
+        for pattern in patterns {
+            if let Some(caps) = pattern.captures(&stem) {
+                if let Some(year_match) = caps.get(1) {
+                    if let Ok(year) = year_match.as_str().parse::<u32>() {
+                        // Valid range: 1900-2099
+                        if year >= MIN_VALID_YEAR && year <= MAX_VALID_YEAR {
+                            debug!("Year {} extracted from filename: {:?}", year, video_path);
+                            return Some(year);
+                        }
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Read .hkmeta.json sidecar file (checks both new and legacy locations)
+    pub fn read_hkmeta(library_path: &Path, video_path: &Path) -> Option<HkMeta> {
+        let hkmeta_path = Self::find_hkmeta_path(library_path, video_path)?;
+
+        // Check file size before reading to prevent DoS
+        match fs::metadata(&hkmeta_path) {
+            Ok(metadata) if metadata.len() > MAX_HKMETA_SIZE => {
+                warn!("Skipping oversized .hkmeta.json ({} bytes): {:?}", metadata.len(), hkmeta_path);
+                return None;
+            }
+            Err(_) => return None,
+            _ => {}
+        }
+
+        match fs::read_to_string(&hkmeta_path) {
+            Ok(content) => match serde_json::from_str::<HkMeta>(&content) {
+                Ok(hkmeta) => Some(hkmeta),
+                Err(e) => {
+                    warn!("Failed to parse .hkmeta.json at {:?}: {}", hkmeta_path, e);
+                    None
+                }
+            },
+            Err(e) => {
+                warn!("Failed to read .hkmeta.json at {:?}: {}", hkmeta_path, e);
+                None
+            }
+        }
+    }
+
+    /// Write .hkmeta.json to the .homekaraoke directory (replacing any existing file)
+    pub fn write_hkmeta(library_path: &Path, video_path: &Path, hkmeta: &HkMeta) -> Result<(), Box<dyn std::error::Error>> {
+        let hkmeta_path = Self::get_hkmeta_path(library_path, video_path);
+        // Ensure the .homekaraoke directory exists
+        if let Some(parent) = hkmeta_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(hkmeta)?;
+        fs::write(&hkmeta_path, content)?;
+        Ok(())
+    }
+
+    /// Whether a path is inside a library folder. Paths with ".." are rejected, since
+    /// `starts_with` compares components and `<library>/../x` would otherwise match.
+    fn is_inside_library(library_path: &Path, file_path: &Path) -> bool {
+        let escapes = file_path
+            .components()
+            .any(|component| matches!(component, std::path::Component::ParentDir));
+        !escapes && file_path.starts_with(library_path)
+    }
+
+    /// The library folder containing a file, if any
+    pub fn folder_for_file<'a>(folders: &'a [LibraryFolder], file_path: &Path) -> Option<&'a LibraryFolder> {
+        folders
+            .iter()
+            .find(|folder| Self::is_inside_library(Path::new(&folder.path), file_path))
+    }
+
+    /// Write hand-edited or hand-picked lyrics to a file's .hkmeta.json, keeping its other
+    /// metadata. With `lock`, later scans keep these lyrics even when regenerating.
+    pub fn set_lyrics(
+        library_path: &Path,
+        video_path: &Path,
+        content: &str,
+        format: &str,
+        lock: bool,
+    ) -> Result<(), String> {
+        if !LYRICS_FORMATS.contains(&format) {
+            return Err(format!("Unsupported lyrics format: {} (expected lrc or plain)", format));
+        }
+        if content.trim().is_empty() {
+            return Err("Lyrics cannot be empty".to_string());
+        }
+        if content.len() as u64 > MAX_HKMETA_SIZE {
+            return Err(format!("Lyrics are too large ({} bytes)", content.len()));
+        }
+        if !Self::is_inside_library(library_path, video_path) {
+            return Err(format!("File is not in the library folder: {}", video_path.display()));
+        }
+
+        let mut hkmeta = Self::read_hkmeta(library_path, video_path).unwrap_or_else(|| {
+            let (title, artist) = Self::parse_filename(video_path);
+            HkMeta {
+                version: Some(1),
+                title: Some(title),
+                artist,
+                ..Default::default()
+            }
+        });
+        hkmeta.lyrics = Some(HkMetaLyrics {
+            format: Some(format.to_string()),
+            content: Some(content.to_string()),
+            lyrics_source: Some(LYRICS_SOURCE_MANUAL.to_string()),
+            lyrics_locked: lock,
+        });
+
+        Self::write_hkmeta(library_path, video_path, &hkmeta)
+            .map_err(|e| format!("Failed to write lyrics: {}", e))
+    }
+
+    /// Apply tag changes: removals first, then additions. Tags are trimmed and compared
+    /// case-insensitively (the first spelling wins). Returns None when no tags remain.
+    pub fn merge_tags(existing: Option<Vec<String>>, add: &[String], remove: &[String]) -> Option<Vec<String>> {
+        let removed: HashSet<String> = remove.iter().map(|tag| tag.trim().to_lowercase()).collect();
+        let existing = existing.unwrap_or_default();
+        let kept = existing
+            .iter()
+            .filter(|tag| !removed.contains(&tag.trim().to_lowercase()));
+        let mut seen = HashSet::new();
+        let tags: Vec<String> = kept
+            .chain(add)
+            .map(|tag| tag.trim())
+            .filter(|tag| !tag.is_empty() && seen.insert(tag.to_lowercase()))
+            .map(String::from)
+            .collect();
+        (!tags.is_empty()).then_some(tags)
+    }
+
+    /// Add and remove tags in a file's .hkmeta.json, keeping its other metadata.
+    /// Returns false (and writes nothing) if the tags didn't change.
+    pub fn update_tags(
+        library_path: &Path,
+        video_path: &Path,
+        add: &[String],
+        remove: &[String],
+    ) -> Result<bool, String> {
+        let mut hkmeta = Self::read_hkmeta(library_path, video_path).unwrap_or_else(|| {
+            let (title, artist) = Self::parse_filename(video_path);
+            HkMeta {
+                version: Some(1),
+                title: Some(title),
+                artist,
+                ..Default::default()
+            }
+        });
+
+        let tags = Self::merge_tags(hkmeta.tags.clone(), add, remove);
+        if tags == hkmeta.tags {
+            return Ok(false);
+        }
+        hkmeta.tags = tags;
+
+        Self::write_hkmeta(library_path, video_path, &hkmeta)
+            .map_err(|e| format!("Failed to write tags: {}", e))?;
+        Ok(true)
+    }
+
+    /// Check if a path is a supported video file
+    pub fn is_supported_video(path: &Path) -> bool {
+        path.is_file() && Self::is_video_file(path)
+    }
+
+    /// Create .hkmeta.json from parsed filename
+    #[allow(dead_code)]
+    fn create_hkmeta_from_filename(library_path: &Path, video_path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+        let (title, artist) = Self::parse_filename(video_path);
+
+        let hkmeta = HkMeta {
+            version: Some(1),
+            title: Some(title),
+            artist,
+            ..Default::default()
+        };
+
+        let hkmeta_path = Self::get_hkmeta_path(library_path, video_path);
+        // Ensure the .homekaraoke directory exists
+        if let Some(parent) = hkmeta_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&hkmeta)?;
+        fs::write(&hkmeta_path, content)?;
+
+        Ok(())
+    }
+
+    /// Create .hkmeta.json with fetched metadata from APIs
+    fn create_hkmeta_with_metadata(
+        library_path: &Path,
+        video_path: &Path,
+        title: &str,
+        artist: Option<String>,
+        song_info: Option<SongInfo>,
+        lyrics: Option<HkMetaLyrics>,
+        detected_duration: Option<u32>,
+        detected_year: Option<u32>,
+        detected_aspect_ratio: Option<String>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Start with parsed filename data
+        let mut hkmeta = HkMeta {
+            version: Some(1),
+            title: Some(title.to_string()),
+            artist: artist.clone(),
+            aspect_ratio: detected_aspect_ratio,
+            lyrics,
+            ..Default::default()
+        };
+
+        // Check for CDG companion file and add tag if found
+        if Self::has_cdg_companion(video_path) {
+            hkmeta.tags = Some(vec!["cdg".to_string()]);
+        }
+
+        // Merge in MusicBrainz song info if available
+        if let Some(info) = song_info {
+            // Duration from API (in milliseconds, convert to seconds)
+            if let Some(duration_ms) = info.duration_ms {
+                hkmeta.duration = Some(duration_ms / 1000);
+            }
+
+            // Album from first release
+            if info.album.is_some() {
+                hkmeta.album = info.album;
+            }
+
+            // Year: prefer detected_year (filename/ffprobe), fallback to MusicBrainz
+            if detected_year.is_some() {
+                hkmeta.year = detected_year;
+            } else if info.year.is_some() {
+                hkmeta.year = info.year;
+            }
+
+            // Use artist credit from API if we didn't have one from filename
+            if hkmeta.artist.is_none() && info.artist_credit.is_some() {
+                hkmeta.artist = info.artist_credit;
+            }
+        }
+
+        // Use lyrics API or ffprobe-detected duration if we still don't have one
+        if hkmeta.duration.is_none() && detected_duration.is_some() {
+            hkmeta.duration = detected_duration;
+        }
+
+        // Use detected year (filename/ffprobe) if we still don't have one
+        if hkmeta.year.is_none() && detected_year.is_some() {
+            hkmeta.year = detected_year;
+        }
+
+        let hkmeta_path = Self::get_hkmeta_path(library_path, video_path);
+        // Ensure the .homekaraoke directory exists
+        if let Some(parent) = hkmeta_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(&hkmeta)?;
+        fs::write(&hkmeta_path, content)?;
+
+        info!(
+            "Created .hkmeta.json for {:?}: title={:?}, artist={:?}, album={:?}, year={:?}, duration={:?}s, has_lyrics={}, has_cdg={}",
+            video_path.file_name(),
+            hkmeta.title,
+            hkmeta.artist,
+            hkmeta.album,
+            hkmeta.year,
+            hkmeta.duration,
+            hkmeta.lyrics.is_some(),
+            hkmeta.tags.is_some()
+        );
+
+        Ok(())
+    }
+
+    /// Check if a file exists
     pub fn check_file_exists(file_path: &str) -> bool {
         Path::new(file_path).exists()
     }
 
-    /// Check for companion .cdg file (MP3+G karaoke format)
-    fn has_cdg_companion(video_path: &Path) -> bool {
-        let cdg_path = video_path.with_extension("cdg");
-        if cdg_path.exists() {
-            debug!("Found CDG companion file: {:?}", cdg_path);
-            return true;
+    /// Check for companion .cdg file (MP3+G karaoke format)
+    fn has_cdg_companion(video_path: &Path) -> bool {
+        let cdg_path = video_path.with_extension("cdg");
+        if cdg_path.exists() {
+            debug!("Found CDG companion file: {:?}", cdg_path);
+            return true;
+        }
+
+        // Also check for uppercase .CDG
+        let stem = video_path.file_stem().unwrap_or_default();
+        let parent = video_path.parent().unwrap_or(Path::new("."));
+        let cdg_upper = parent.join(format!("{}.CDG", stem.to_string_lossy()));
+        if cdg_upper.exists() {
+            debug!("Found CDG companion file (uppercase): {:?}", cdg_upper);
+            return true;
+        }
+
+        false
+    }
+
+    /// Find a companion lyrics file for a video
+    /// Checks, in order: same-dir .lrc, subfolder .lrc, same-dir .txt, subfolder .txt.
+    /// The subfolder is resolved relative to the video's directory and must be a plain
+    /// relative path (no "..", no absolute paths).
+    fn find_lyrics_file(video_path: &Path, lyrics_subfolder: &str) -> Option<PathBuf> {
+        let stem = video_path.file_stem()?.to_string_lossy().to_string();
+        let parent = video_path.parent().unwrap_or(Path::new("."));
+
+        let subfolder = Path::new(lyrics_subfolder);
+        let subfolder_dir = if !lyrics_subfolder.is_empty()
+            && subfolder
+                .components()
+                .all(|c| matches!(c, std::path::Component::Normal(_)))
+        {
+            Some(parent.join(subfolder))
+        } else {
+            if !lyrics_subfolder.is_empty() {
+                warn!("Ignoring invalid lyrics subfolder: {:?}", lyrics_subfolder);
+            }
+            None
+        };
+
+        for ext in LYRICS_EXTENSIONS {
+            let file_name = format!("{}.{}", stem, ext);
+            let same_dir = parent.join(&file_name);
+            if same_dir.is_file() {
+                return Some(same_dir);
+            }
+            if let Some(ref dir) = subfolder_dir {
+                let in_subfolder = dir.join(&file_name);
+                if in_subfolder.is_file() {
+                    return Some(in_subfolder);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Read companion lyrics file for a video
+    /// .lrc content is returned as synced lyrics, .txt content as plain lyrics
+    fn read_lyrics_file(video_path: &Path, lyrics_subfolder: &str) -> Option<LyricsResult> {
+        let lyrics_path = Self::find_lyrics_file(video_path, lyrics_subfolder)?;
+
+        // Check file size (lyrics files should be small, limit to 1MB)
+        match fs::metadata(&lyrics_path) {
+            Ok(metadata) if metadata.len() > MAX_HKMETA_SIZE => {
+                warn!(
+                    "Skipping oversized lyrics file ({} bytes): {:?}",
+                    metadata.len(),
+                    lyrics_path
+                );
+                return None;
+            }
+            Err(e) => {
+                warn!("Failed to read lyrics file metadata: {}", e);
+                return None;
+            }
+            _ => {}
+        }
+
+        let content = match fs::read_to_string(&lyrics_path) {
+            Ok(content) => content,
+            Err(e) => {
+                warn!("Failed to read lyrics file {:?}: {}", lyrics_path, e);
+                return None;
+            }
+        };
+
+        let is_synced = lyrics_path
+            .extension()
+            .map(|ext| ext.eq_ignore_ascii_case("lrc"))
+            .unwrap_or(false);
+        info!(
+            "Read companion lyrics file for {:?}: {:?} ({})",
+            video_path.file_name(),
+            lyrics_path,
+            if is_synced { "synced" } else { "plain" }
+        );
+
+        Some(if is_synced {
+            LyricsResult {
+                synced_lyrics: Some(content),
+                plain_lyrics: None,
+                duration: None,
+            }
+        } else {
+            LyricsResult {
+                synced_lyrics: None,
+                plain_lyrics: Some(content),
+                duration: None,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::scratch_dir;
+
+    #[test]
+    fn test_parse_filename_artist_title() {
+        let path = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
+        let (title, artist) = LibraryScanner::parse_filename(path);
+        assert_eq!(title, "Bohemian Rhapsody");
+        assert_eq!(artist, Some("Queen".to_string()));
+    }
+
+    #[test]
+    fn test_parse_filename_title_artist_parens() {
+        let path = Path::new("/music/Bohemian Rhapsody (Queen).mp4");
+        let (title, artist) = LibraryScanner::parse_filename(path);
+        assert_eq!(title, "Bohemian Rhapsody");
+        assert_eq!(artist, Some("Queen".to_string()));
+    }
+
+    #[test]
+    fn test_parse_filename_title_only() {
+        let path = Path::new("/music/Bohemian Rhapsody.mp4");
+        let (title, artist) = LibraryScanner::parse_filename(path);
+        assert_eq!(title, "Bohemian Rhapsody");
+        assert_eq!(artist, None);
+    }
+
+    #[test]
+    fn test_is_video_file() {
+        assert!(LibraryScanner::is_video_file(Path::new("video.mp4")));
+        assert!(LibraryScanner::is_video_file(Path::new("video.MKV")));
+        assert!(LibraryScanner::is_video_file(Path::new("video.webm")));
+        assert!(!LibraryScanner::is_video_file(Path::new("audio.mp3")));
+        assert!(!LibraryScanner::is_video_file(Path::new("image.jpg")));
+    }
+
+    #[test]
+    fn test_get_hkmeta_path() {
+        let library = Path::new("/music");
+        let video = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
+        let hkmeta = LibraryScanner::get_hkmeta_path(library, video);
+        assert_eq!(
+            hkmeta,
+            Path::new("/music/.homekaraoke/Queen - Bohemian Rhapsody.hkmeta.json")
+        );
+
+        // Test with subdirectory
+        let video2 = Path::new("/music/Queen/Bohemian Rhapsody.mp4");
+        let hkmeta2 = LibraryScanner::get_hkmeta_path(library, video2);
+        assert_eq!(
+            hkmeta2,
+            Path::new("/music/.homekaraoke/Queen/Bohemian Rhapsody.hkmeta.json")
+        );
+    }
+
+    #[test]
+    fn test_get_thumbnail_path() {
+        let library = Path::new("/music");
+        let video = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
+        let thumb = LibraryScanner::get_thumbnail_path(library, video);
+        assert_eq!(
+            thumb,
+            Path::new("/music/.homekaraoke/Queen - Bohemian Rhapsody.thumb.jpg")
+        );
+    }
+
+    #[test]
+    fn test_get_legacy_hkmeta_path() {
+        let video = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
+        let hkmeta = LibraryScanner::get_legacy_hkmeta_path(video);
+        assert_eq!(
+            hkmeta,
+            Path::new("/music/Queen - Bohemian Rhapsody.hkmeta.json")
+        );
+    }
+
+    #[test]
+    fn test_parse_filename_artist_with_hyphen() {
+        // "AC-DC" has a hyphen but NOT " - " (space-hyphen-space), so it parses correctly
+        let path = Path::new("/music/AC-DC - Back In Black.mp4");
+        let (title, artist) = LibraryScanner::parse_filename(path);
+        assert_eq!(title, "Back In Black");
+        assert_eq!(artist, Some("AC-DC".to_string()));
+    }
+
+    #[test]
+    fn test_parse_filename_multiple_hyphens() {
+        // Multiple " - " separators - splits on first one for correct Artist/Title-Subtitle
+        let path = Path::new("/music/Twenty One Pilots - Heathens - From Suicide Squad.mp4");
+        let (title, artist) = LibraryScanner::parse_filename(path);
+        assert_eq!(title, "Heathens - From Suicide Squad");
+        assert_eq!(artist, Some("Twenty One Pilots".to_string()));
+    }
+
+    #[test]
+    fn test_parse_filename_hyphenated_artist_with_subtitle() {
+        // Complex case: hyphenated artist AND subtitle
+        // "Artist-Name - Title - Subtitle" → splits on first " - "
+        let path = Path::new("/music/Twenty-One Pilots - Heathens - Live Version.mp4");
+        let (title, artist) = LibraryScanner::parse_filename(path);
+        assert_eq!(title, "Heathens - Live Version");
+        assert_eq!(artist, Some("Twenty-One Pilots".to_string()));
+    }
+
+    #[test]
+    fn test_cdg_companion_detection() {
+        // CDG detection relies on file system, so we test the path logic
+        let video = Path::new("/music/karaoke.mp4");
+        let cdg_path = video.with_extension("cdg");
+        assert_eq!(cdg_path, Path::new("/music/karaoke.cdg"));
+    }
+
+    // Tests for parse_year_from_filename
+
+    #[test]
+    fn test_parse_year_parentheses() {
+        // Year in parentheses: (YYYY)
+        let path = Path::new("/music/Artist - Song Title (1985).mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(1985));
+    }
+
+    #[test]
+    fn test_parse_year_brackets() {
+        // Year in brackets: [YYYY]
+        let path = Path::new("/music/Artist - Song Title [2020].mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2020));
+    }
+
+    #[test]
+    fn test_parse_year_delimited() {
+        // Year delimited by spaces/hyphens/underscores
+        let path = Path::new("/music/Artist - Song Title - 2015 - Karaoke.mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2015));
+    }
+
+    #[test]
+    fn test_parse_year_trailing() {
+        // Year at end of filename
+        let path = Path::new("/music/Artist - Song Title - 2018.mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2018));
+    }
+
+    #[test]
+    fn test_parse_year_priority_parens_over_brackets() {
+        // Parentheses should take priority over brackets
+        let path = Path::new("/music/Song [1985] (2023).mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2023));
+    }
+
+    #[test]
+    fn test_parse_year_invalid_range_too_old() {
+        // Year before 1900 should be rejected
+        let path = Path::new("/music/Song (1850).mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    }
+
+    #[test]
+    fn test_parse_year_invalid_range_too_new() {
+        // Year after 2099 should be rejected
+        let path = Path::new("/music/Song (2150).mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    }
+
+    #[test]
+    fn test_parse_year_no_year() {
+        // Filename without year
+        let path = Path::new("/music/Artist - Song Title.mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    }
+
+    #[test]
+    fn test_parse_year_not_a_year() {
+        // 4-digit number that's not a valid year (e.g., track number)
+        let path = Path::new("/music/Artist - 0001 Song Title.mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    }
+
+    #[test]
+    fn test_parse_year_underscore_delimited() {
+        // Year with underscores
+        let path = Path::new("/music/Artist_Song_2010_Karaoke.mp4");
+        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2010));
+    }
+
+    // Tests for companion lyrics discovery
+
+    fn lyrics_result(synced: Option<&str>, plain: Option<&str>) -> LyricsResult {
+        LyricsResult {
+            synced_lyrics: synced.map(String::from),
+            plain_lyrics: plain.map(String::from),
+            duration: None,
+        }
+    }
+
+    /// (format, content) stored for a result under a preference
+    fn stored_lyrics(
+        synced: Option<&str>,
+        plain: Option<&str>,
+        preference: LyricsPreference,
+    ) -> Option<(String, String)> {
+        HkMetaLyrics::from_result(lyrics_result(synced, plain), LYRICS_SOURCE_LRCLIB, preference)
+            .map(|lyrics| (lyrics.format.unwrap(), lyrics.content.unwrap()))
+    }
+
+    #[test]
+    fn test_lyrics_preference_with_both() {
+        let both = |preference| stored_lyrics(Some("[00:01.00]Hi"), Some("Hi"), preference);
+        let lrc = Some(("lrc".to_string(), "[00:01.00]Hi".to_string()));
+        let plain = Some(("plain".to_string(), "Hi".to_string()));
+
+        assert_eq!(both(LyricsPreference::SyncedFirst), lrc);
+        assert_eq!(both(LyricsPreference::PlainFirst), plain);
+        assert_eq!(both(LyricsPreference::SyncedOnly), lrc);
+        assert_eq!(both(LyricsPreference::PlainOnly), plain);
+    }
+
+    #[test]
+    fn test_lyrics_preference_with_only_synced() {
+        let synced = |preference| stored_lyrics(Some("[00:01.00]Hi"), None, preference);
+        let lrc = Some(("lrc".to_string(), "[00:01.00]Hi".to_string()));
+
+        assert_eq!(synced(LyricsPreference::SyncedFirst), lrc);
+        assert_eq!(synced(LyricsPreference::PlainFirst), lrc);
+        assert_eq!(synced(LyricsPreference::SyncedOnly), lrc);
+        assert_eq!(synced(LyricsPreference::PlainOnly), None);
+    }
+
+    #[test]
+    fn test_lyrics_preference_with_only_plain() {
+        let plain_only = |preference| stored_lyrics(None, Some("Hi"), preference);
+        let plain = Some(("plain".to_string(), "Hi".to_string()));
+
+        assert_eq!(plain_only(LyricsPreference::SyncedFirst), plain);
+        assert_eq!(plain_only(LyricsPreference::PlainFirst), plain);
+        assert_eq!(plain_only(LyricsPreference::SyncedOnly), None);
+        assert_eq!(plain_only(LyricsPreference::PlainOnly), plain);
+    }
+
+    #[test]
+    fn test_lyrics_preference_accepts() {
+        assert!(LyricsPreference::SyncedFirst.accepts(false, true));
+        assert!(!LyricsPreference::SyncedFirst.accepts(false, false));
+        assert!(LyricsPreference::SyncedOnly.accepts(true, false));
+        assert!(!LyricsPreference::SyncedOnly.accepts(false, true));
+        assert!(!LyricsPreference::PlainOnly.accepts(true, false));
+        assert_eq!(
+            serde_json::from_str::<LyricsPreference>("\"plain_first\"").unwrap(),
+            LyricsPreference::PlainFirst
+        );
+    }
+
+    #[test]
+    fn test_find_lyrics_same_dir_lrc() {
+        let dir = scratch_dir("lyrics-same-dir");
+        let video = dir.join("Song.mp4");
+        fs::write(dir.join("Song.lrc"), "[00:01.00]Hello").unwrap();
+        fs::create_dir_all(dir.join("Lyrics")).unwrap();
+        fs::write(dir.join("Lyrics").join("Song.lrc"), "[00:01.00]Subfolder").unwrap();
+
+        assert_eq!(
+            LibraryScanner::find_lyrics_file(&video, DEFAULT_LYRICS_SUBFOLDER),
+            Some(dir.join("Song.lrc"))
+        );
+        let lyrics = LibraryScanner::read_lyrics_file(&video, DEFAULT_LYRICS_SUBFOLDER).unwrap();
+        assert_eq!(lyrics.synced_lyrics.as_deref(), Some("[00:01.00]Hello"));
+        assert!(lyrics.plain_lyrics.is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_lyrics_subfolder_lrc() {
+        let dir = scratch_dir("lyrics-subfolder");
+        let video = dir.join("Song.mp4");
+        fs::create_dir_all(dir.join("Texts")).unwrap();
+        fs::write(dir.join("Texts").join("Song.lrc"), "[00:01.00]Hello").unwrap();
+
+        // Only found when the configured subfolder matches
+        assert_eq!(LibraryScanner::find_lyrics_file(&video, DEFAULT_LYRICS_SUBFOLDER), None);
+        assert_eq!(
+            LibraryScanner::find_lyrics_file(&video, "Texts"),
+            Some(dir.join("Texts").join("Song.lrc"))
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_lyrics_txt_fallback_precedence() {
+        let dir = scratch_dir("lyrics-txt-fallback");
+        let video = dir.join("Song.mp4");
+        fs::write(dir.join("Song.txt"), "Plain lyrics").unwrap();
+
+        // .txt is used when no .lrc exists
+        let lyrics = LibraryScanner::read_lyrics_file(&video, DEFAULT_LYRICS_SUBFOLDER).unwrap();
+        assert_eq!(lyrics.plain_lyrics.as_deref(), Some("Plain lyrics"));
+        assert!(lyrics.synced_lyrics.is_none());
+
+        // .lrc in the subfolder wins over .txt next to the video
+        fs::create_dir_all(dir.join("Lyrics")).unwrap();
+        fs::write(dir.join("Lyrics").join("Song.lrc"), "[00:01.00]Synced").unwrap();
+        assert_eq!(
+            LibraryScanner::find_lyrics_file(&video, DEFAULT_LYRICS_SUBFOLDER),
+            Some(dir.join("Lyrics").join("Song.lrc"))
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_lyrics_rejects_parent_subfolder() {
+        let dir = scratch_dir("lyrics-traversal");
+        let videos = dir.join("Videos");
+        fs::create_dir_all(&videos).unwrap();
+        fs::create_dir_all(dir.join("Lyrics")).unwrap();
+        let video = videos.join("Song.mp4");
+        fs::write(&video, "").unwrap();
+        // A file the traversal would reach, outside the video's folder
+        fs::write(dir.join("Lyrics").join("Song.lrc"), "[00:01.00]Hello").unwrap();
+
+        assert_eq!(LibraryScanner::find_lyrics_file(&video, "../Lyrics"), None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_preview_parse_limits_and_parses() {
+        let dir = scratch_dir("preview-parse");
+        fs::write(dir.join("Queen - Bohemian Rhapsody (1975).mp4"), "").unwrap();
+        fs::write(dir.join("Take On Me (a-ha).mkv"), "").unwrap();
+        fs::write(dir.join("notes.txt"), "").unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "preview".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let preview = LibraryScanner::preview_parse(&folder, 10);
+        assert_eq!(preview.len(), 2);
+        assert_eq!(preview[0].file_name, "Queen - Bohemian Rhapsody (1975).mp4");
+        assert_eq!(preview[0].parsed_title, "Bohemian Rhapsody (1975)");
+        assert_eq!(preview[0].parsed_artist, Some("Queen".to_string()));
+        assert_eq!(preview[0].parsed_year, Some(1975));
+        assert_eq!(preview[1].parsed_title, "Take On Me");
+        assert_eq!(preview[1].parsed_artist, Some("a-ha".to_string()));
+        assert_eq!(preview[1].parsed_year, None);
+
+        assert_eq!(LibraryScanner::preview_parse(&folder, 1).len(), 1);
+        // Nothing was written
+        assert!(!dir.join(".homekaraoke").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    // Tests for custom filename patterns
+
+    #[test]
+    fn test_compile_filename_pattern_rejects_invalid() {
+        assert!(LibraryScanner::compile_filename_pattern(r"(?P<title>[^_]+").is_err());
+        // Missing title group
+        assert!(LibraryScanner::compile_filename_pattern(r"(?P<artist>.+)").is_err());
+        assert!(LibraryScanner::compile_filename_pattern(r"(?P<title>.+)").is_ok());
+    }
+
+    #[test]
+    fn test_parse_filename_with_pattern_named_groups() {
+        let pattern = LibraryScanner::compile_filename_pattern(
+            r"^(?P<title>[^_]+)_(?P<artist>[^_]+)_[^_]+_(?P<year>\d{4})$",
+        )
+        .unwrap();
+        let path = Path::new("/music/Bohemian Rhapsody_Queen_Bb_1975.mp4");
+        assert_eq!(
+            LibraryScanner::parse_filename_with_pattern(path, &pattern),
+            Some(("Bohemian Rhapsody".to_string(), Some("Queen".to_string()), Some(1975)))
+        );
+
+        // Non-matching filenames fall back to built-in patterns
+        let other = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
+        assert_eq!(LibraryScanner::parse_filename_with_pattern(other, &pattern), None);
+        assert_eq!(
+            LibraryScanner::parse_title_artist(other, Some(&pattern)),
+            ("Bohemian Rhapsody".to_string(), Some("Queen".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_parse_filename_with_pattern_invalid_year_ignored() {
+        let pattern =
+            LibraryScanner::compile_filename_pattern(r"^(?P<title>.+)_(?P<year>\d+)$").unwrap();
+        let path = Path::new("/music/Song_0042.mp4");
+        assert_eq!(
+            LibraryScanner::parse_filename_with_pattern(path, &pattern),
+            Some(("Song".to_string(), None, None))
+        );
+    }
+
+    #[test]
+    fn test_facets_distinct_values() {
+        let dir = scratch_dir("facets");
+        fs::write(dir.join("Queen - Bohemian Rhapsody (1975).mp4"), "").unwrap();
+        fs::write(dir.join("queen - We Will Rock You.mp4"), "").unwrap();
+        fs::write(dir.join("Untitled.mp4"), "").unwrap();
+        fs::create_dir_all(dir.join(".homekaraoke")).unwrap();
+        fs::write(
+            dir.join(".homekaraoke").join("Untitled.hkmeta.json"),
+            r#"{"title": "Take On Me", "artist": "a-ha", "genre": "Pop", "language": "Norwegian", "year": 1985}"#,
+        )
+        .unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "filters".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let facets = LibraryScanner::facets(&[folder]);
+        assert_eq!(facets.artists, vec!["a-ha".to_string(), "Queen".to_string()]);
+        assert_eq!(facets.genres, vec!["Pop".to_string()]);
+        assert_eq!(facets.languages, vec!["Norwegian".to_string()]);
+        assert_eq!(facets.min_year, Some(1975));
+        assert_eq!(facets.max_year, Some(1985));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_facets_empty_library() {
+        let dir = scratch_dir("facets-empty");
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "empty".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let facets = LibraryScanner::facets(&[folder]);
+        assert!(facets.artists.is_empty());
+        assert!(facets.genres.is_empty());
+        assert!(facets.languages.is_empty());
+        assert_eq!(facets.min_year, None);
+        assert_eq!(facets.max_year, None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_merge_folder_metadata() {
+        let from_dir = scratch_dir("merge-from");
+        let into_dir = scratch_dir("merge-into");
+        let from_meta = from_dir.join(".homekaraoke");
+        fs::create_dir_all(from_meta.join("Rock")).unwrap();
+        fs::create_dir_all(from_meta.join("Old")).unwrap();
+
+        // Same relative path in both folders
+        fs::write(from_meta.join("Rock").join("Anthem.hkmeta.json"), r#"{"title":"Anthem"}"#).unwrap();
+        fs::write(from_meta.join("Rock").join("Anthem.thumb.jpg"), "jpg").unwrap();
+        // Moved to a different subfolder; found by its unique name
+        fs::write(from_meta.join("Old").join("Ballad.hkmeta.json"), r#"{"title":"Ballad"}"#).unwrap();
+        // Target already has newer metadata for this one
+        fs::write(from_meta.join("Keep.hkmeta.json"), r#"{"title":"Stale"}"#).unwrap();
+
+        fs::create_dir_all(into_dir.join("Rock")).unwrap();
+        fs::create_dir_all(into_dir.join("Slow")).unwrap();
+        fs::write(into_dir.join("Rock").join("Anthem.mp4"), "").unwrap();
+        fs::write(into_dir.join("Slow").join("Ballad.mp4"), "").unwrap();
+        fs::write(into_dir.join("Keep.mp4"), "").unwrap();
+        fs::write(into_dir.join("NoMeta.mp4"), "").unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::create_dir_all(into_dir.join(".homekaraoke")).unwrap();
+        fs::write(into_dir.join(".homekaraoke").join("Keep.hkmeta.json"), r#"{"title":"Fresh"}"#).unwrap();
+
+        let folder = |id: i64, dir: &Path| LibraryFolder {
+            id,
+            path: dir.to_string_lossy().to_string(),
+            name: format!("folder {}", id),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let result = LibraryScanner::merge_folder_metadata(&folder(1, &from_dir), &folder(2, &into_dir));
+
+        assert_eq!(
+            result,
+            MetadataMergeResult {
+                videos_migrated: 2,
+                hkmeta_copied: 2,
+                thumbnails_copied: 1,
+                skipped_newer: 1,
+            }
+        );
+        let into_meta = into_dir.join(".homekaraoke");
+        assert!(fs::read_to_string(into_meta.join("Rock").join("Anthem.hkmeta.json")).unwrap().contains("Anthem"));
+        assert!(into_meta.join("Rock").join("Anthem.thumb.jpg").exists());
+        assert!(fs::read_to_string(into_meta.join("Slow").join("Ballad.hkmeta.json")).unwrap().contains("Ballad"));
+        assert!(fs::read_to_string(into_meta.join("Keep.hkmeta.json")).unwrap().contains("Fresh"));
+        assert!(!into_meta.join("NoMeta.hkmeta.json").exists());
+
+        // Copies are newer than their sources, so a second merge changes nothing
+        let again = LibraryScanner::merge_folder_metadata(&folder(1, &from_dir), &folder(2, &into_dir));
+        assert_eq!(again.videos_migrated, 0);
+        assert_eq!(again.skipped_newer, 4);
+
+        let _ = fs::remove_dir_all(&from_dir);
+        let _ = fs::remove_dir_all(&into_dir);
+    }
+
+    #[test]
+    fn test_migrate_legacy_metadata() {
+        let dir = scratch_dir("migrate-legacy");
+        let meta = dir.join(".homekaraoke");
+        fs::create_dir_all(dir.join("Rock")).unwrap();
+        fs::create_dir_all(&meta).unwrap();
+
+        // Only legacy sidecar files: moved, mirroring the subdirectory
+        fs::write(dir.join("Rock").join("Anthem.mp4"), "").unwrap();
+        fs::write(dir.join("Rock").join("Anthem.hkmeta.json"), r#"{"title":"Anthem"}"#).unwrap();
+        fs::write(dir.join("Rock").join("Anthem.thumb.jpg"), "jpg").unwrap();
+        // .homekaraoke copy is newer: the legacy file is discarded
+        fs::write(dir.join("Keep.mp4"), "").unwrap();
+        fs::write(dir.join("Keep.hkmeta.json"), r#"{"title":"Stale"}"#).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(meta.join("Keep.hkmeta.json"), r#"{"title":"Fresh"}"#).unwrap();
+        // Legacy file is newer: it replaces the .homekaraoke copy
+        fs::write(meta.join("Edited.hkmeta.json"), r#"{"title":"Old"}"#).unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        fs::write(dir.join("Edited.mp4"), "").unwrap();
+        fs::write(dir.join("Edited.hkmeta.json"), r#"{"title":"New"}"#).unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "Music".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let result = LibraryScanner::migrate_legacy_metadata(&folder);
+
+        assert_eq!(
+            result,
+            MigrationResult {
+                moved: 3,
+                discarded_older: 1,
+                failed: 0,
+            }
+        );
+        assert!(fs::read_to_string(meta.join("Rock").join("Anthem.hkmeta.json")).unwrap().contains("Anthem"));
+        assert!(meta.join("Rock").join("Anthem.thumb.jpg").exists());
+        assert!(fs::read_to_string(meta.join("Keep.hkmeta.json")).unwrap().contains("Fresh"));
+        assert!(fs::read_to_string(meta.join("Edited.hkmeta.json")).unwrap().contains("New"));
+        for legacy in ["Rock/Anthem.hkmeta.json", "Rock/Anthem.thumb.jpg", "Keep.hkmeta.json", "Edited.hkmeta.json"] {
+            assert!(!dir.join(legacy).exists(), "{} should be gone", legacy);
         }
 
-        // Also check for uppercase .CDG
-        let stem = video_path.file_stem().unwrap_or_default();
-        let parent = video_path.parent().unwrap_or(Path::new("."));
-        let cdg_upper = parent.join(format!("{}.CDG", stem.to_string_lossy()));
-        if cdg_upper.exists() {
-            debug!("Found CDG companion file (uppercase): {:?}", cdg_upper);
-            return true;
-        }
+        // Nothing left to migrate
+        assert_eq!(LibraryScanner::migrate_legacy_metadata(&folder), MigrationResult::default());
 
-        false
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    /// Read companion .lrc file for a video
-    /// Returns the content of the LRC file if it exists and is readable
-    fn read_lrc_file(video_path: &Path) -> Option<String> {
-        let lrc_path = video_path.with_extension("lrc");
+    #[test]
+    fn test_estimate_stale_fraction() {
+        assert_eq!(estimate_stale_fraction(0, 0, 0, 0), 0.0);
+        assert_eq!(estimate_stale_fraction(100, 100, 50, 0), 0.0);
+        assert_eq!(estimate_stale_fraction(100, 100, 50, 10), 0.2);
+        // 25 files added or removed since the scan
+        assert_eq!(estimate_stale_fraction(75, 100, 50, 0), 0.25);
+        assert_eq!(estimate_stale_fraction(100, 75, 50, 0), 0.25);
+        assert_eq!(estimate_stale_fraction(100, 75, 50, 40), 0.8);
+    }
 
-        if !lrc_path.exists() {
-            return None;
+    #[test]
+    fn test_cache_status() {
+        let dir = scratch_dir("cache-status");
+        for name in ["A.mp4", "B.mp4", "C.mp4", "D.mp4"] {
+            fs::write(dir.join(name), "").unwrap();
         }
+        let folder = |id: i64, path: &Path, last_scan_at: Option<&str>, file_count: u32| LibraryFolder {
+            id,
+            path: path.to_string_lossy().to_string(),
+            name: format!("folder {}", id),
+            last_scan_at: last_scan_at.map(String::from),
+            file_count,
+            filename_pattern: None,
+        };
 
-        // Check file size (LRC files should be small, limit to 1MB)
-        match fs::metadata(&lrc_path) {
-            Ok(metadata) if metadata.len() > MAX_HKMETA_SIZE => {
-                warn!(
-                    "Skipping oversized .lrc file ({} bytes): {:?}",
-                    metadata.len(),
-                    lrc_path
-                );
-                return None;
-            }
-            Err(e) => {
-                warn!("Failed to read .lrc metadata: {}", e);
-                return None;
-            }
-            _ => {}
-        }
+        // Scanned after the files were written, but two files have appeared since
+        let fresh = folder(1, &dir, Some("2999-01-01 00:00:00"), 2);
+        let status = LibraryScanner::cache_status(std::slice::from_ref(&fresh));
+        let folder_status = &status.folders[0];
+        assert!(folder_status.available);
+        assert_eq!(folder_status.current_files, 4);
+        assert_eq!(folder_status.sampled, 4);
+        assert_eq!(folder_status.changed, 0);
+        assert_eq!(status.stale_fraction, 0.5);
+
+        // Every file was modified after this scan
+        let old = folder(1, &dir, Some("2000-01-01 00:00:00"), 4);
+        assert_eq!(LibraryScanner::cache_status(&[old]).stale_fraction, 1.0);
+
+        // Never scanned, and a missing folder that doesn't count towards the estimate
+        let never = folder(1, &dir, None, 0);
+        let missing = folder(2, &dir.join("unmounted"), Some("2000-01-01 00:00:00"), 500);
+        let status = LibraryScanner::cache_status(&[never, missing]);
+        assert_eq!(status.folders[0].stale_fraction, 1.0);
+        assert!(!status.folders[1].available);
+        assert_eq!(status.stale_fraction, 1.0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 
-        match fs::read_to_string(&lrc_path) {
-            Ok(content) => {
-                info!("Read companion .lrc file: {:?}", lrc_path);
-                Some(content)
-            }
-            Err(e) => {
-                warn!("Failed to read .lrc file {:?}: {}", lrc_path, e);
-                None
-            }
-        }
+    #[test]
+    fn test_clear_thumbnails_keeps_metadata() {
+        let dir = scratch_dir("clear-thumbnails");
+        let meta_dir = dir.join(".homekaraoke");
+        fs::create_dir_all(meta_dir.join("Rock")).unwrap();
+        fs::write(dir.join("Song.mp4"), "").unwrap();
+        fs::write(meta_dir.join("Song.thumb.jpg"), "jpg").unwrap();
+        fs::write(meta_dir.join("Song.hkmeta.json"), "{}").unwrap();
+        fs::write(meta_dir.join("Rock").join("Anthem.thumb.webp"), "webp").unwrap();
+        fs::write(meta_dir.join("Rock").join("Anthem.hkmeta.json"), "{}").unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "thumbs".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        assert_eq!(LibraryScanner::clear_thumbnails(&folder), 2);
+        assert!(!meta_dir.join("Song.thumb.jpg").exists());
+        assert!(!meta_dir.join("Rock").join("Anthem.thumb.webp").exists());
+        assert!(meta_dir.join("Song.hkmeta.json").exists());
+        assert!(meta_dir.join("Rock").join("Anthem.hkmeta.json").exists());
+        assert!(dir.join("Song.mp4").exists());
+
+        // Nothing left to remove
+        assert_eq!(LibraryScanner::clear_thumbnails(&folder), 0);
+
+        let _ = fs::remove_dir_all(&dir);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    fn search_video(path: &str, title: &str, artist: Option<&str>) -> LibraryVideo {
+        LibraryVideo {
+            file_path: path.to_string(),
+            file_name: path.rsplit('/').next().unwrap_or_default().to_string(),
+            title: title.to_string(),
+            artist: artist.map(String::from),
+            album: None,
+            duration: None,
+            has_lyrics: false,
+            has_cdg: false,
+            youtube_id: None,
+            is_available: true,
+            thumbnail_path: None,
+            aspect_ratio: None,
+            thumbnail: ThumbnailSource::None,
+            thumbnail_alternative: None,
+        }
+    }
 
     #[test]
-    fn test_parse_filename_artist_title() {
-        let path = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
-        let (title, artist) = LibraryScanner::parse_filename(path);
-        assert_eq!(title, "Bohemian Rhapsody");
-        assert_eq!(artist, Some("Queen".to_string()));
+    fn test_search_match_levels() {
+        let mut video = search_video("/music/Queen - Bohemian Rhapsody.mp4", "Bohemian Rhapsody", Some("Queen"));
+        video.album = Some("A Night at the Opera".to_string());
+
+        assert_eq!(LibraryScanner::search_match(&video, "bohemian rhapsody"), Some(SearchMatch::ExactTitle));
+        assert_eq!(LibraryScanner::search_match(&video, "bohemian"), Some(SearchMatch::TitlePrefix));
+        assert_eq!(LibraryScanner::search_match(&video, "rhapsody"), Some(SearchMatch::Title));
+        assert_eq!(LibraryScanner::search_match(&video, "quee"), Some(SearchMatch::Artist));
+        assert_eq!(LibraryScanner::search_match(&video, ".mp4"), Some(SearchMatch::FileName));
+        assert_eq!(LibraryScanner::search_match(&video, "opera"), Some(SearchMatch::Metadata));
+        assert_eq!(LibraryScanner::search_match(&video, "abba"), None);
+
+        assert!(SearchMatch::ExactTitle > SearchMatch::TitlePrefix);
+        assert!(SearchMatch::TitlePrefix > SearchMatch::Artist);
+        assert!(SearchMatch::Artist > SearchMatch::FileName);
+        assert!(SearchMatch::FileName > SearchMatch::Lyrics);
     }
 
     #[test]
-    fn test_parse_filename_title_artist_parens() {
-        let path = Path::new("/music/Bohemian Rhapsody (Queen).mp4");
-        let (title, artist) = LibraryScanner::parse_filename(path);
-        assert_eq!(title, "Bohemian Rhapsody");
-        assert_eq!(artist, Some("Queen".to_string()));
+    fn test_search_ranks_exact_title_first() {
+        let dir = scratch_dir("search-ranking");
+        // One file per match level, named so that directory order doesn't matter
+        fs::write(dir.join("Hellogoodbye - Here.mp4"), "").unwrap();
+        fs::write(dir.join("Adele - Say Hello.mp4"), "").unwrap();
+        fs::write(dir.join("Hello Again.mp4"), "").unwrap();
+        fs::write(dir.join("Lionel Richie - Hello.mp4"), "").unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "ranking".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let results = LibraryScanner::search(std::slice::from_ref(&folder), "hello", 10, false);
+        let titles: Vec<&str> = results.iter().map(|video| video.title.as_str()).collect();
+        assert_eq!(titles, vec!["Hello", "Hello Again", "Say Hello", "Here"]);
+
+        // The limit applies after ranking
+        let results = LibraryScanner::search(&[folder], "hello", 1, false);
+        assert_eq!(results[0].title, "Hello");
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_filename_title_only() {
-        let path = Path::new("/music/Bohemian Rhapsody.mp4");
-        let (title, artist) = LibraryScanner::parse_filename(path);
-        assert_eq!(title, "Bohemian Rhapsody");
-        assert_eq!(artist, None);
+    fn test_normalize_artist() {
+        assert_eq!(LibraryScanner::normalize_artist("The Beatles"), "beatles");
+        assert_eq!(LibraryScanner::normalize_artist("  beatles "), "beatles");
+        assert_eq!(LibraryScanner::normalize_artist("THE  BEATLES"), "beatles");
+        assert_eq!(LibraryScanner::normalize_artist("The The"), "the");
+        assert_eq!(LibraryScanner::normalize_artist("Theatre of Tragedy"), "theatre of tragedy");
+        assert_eq!(LibraryScanner::normalize_artist("The"), "the");
     }
 
     #[test]
-    fn test_is_video_file() {
-        assert!(LibraryScanner::is_video_file(Path::new("video.mp4")));
-        assert!(LibraryScanner::is_video_file(Path::new("video.MKV")));
-        assert!(LibraryScanner::is_video_file(Path::new("video.webm")));
-        assert!(!LibraryScanner::is_video_file(Path::new("audio.mp3")));
-        assert!(!LibraryScanner::is_video_file(Path::new("image.jpg")));
+    fn test_distribution_top_artists_and_decades() {
+        let dir = scratch_dir("distribution");
+        fs::write(dir.join("Queen - Bohemian Rhapsody (1975).mp4"), "").unwrap();
+        fs::write(dir.join("Queen - Radio Ga Ga (1984).mp4"), "").unwrap();
+        fs::write(dir.join("ABBA - Waterloo (1974).mp4"), "").unwrap();
+        fs::write(dir.join("Toto - Africa.mp4"), "").unwrap();
+        fs::write(dir.join("Untitled.mp4"), "").unwrap();
+        fs::create_dir_all(dir.join(".homekaraoke")).unwrap();
+        fs::write(
+            dir.join(".homekaraoke").join("Toto - Africa.hkmeta.json"),
+            r#"{"year": 1982}"#,
+        )
+        .unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "distribution".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let distribution = LibraryScanner::distribution(&[folder], 1);
+        assert_eq!(distribution.total_files, 5);
+        assert_eq!(
+            distribution.top_artists,
+            vec![ArtistCount { artist: "Queen".to_string(), count: 2 }]
+        );
+        assert_eq!(distribution.other_artists_count, 3);
+        assert_eq!(
+            distribution.decades,
+            vec![
+                DecadeCount { decade: Some(1970), count: 2 },
+                DecadeCount { decade: Some(1980), count: 2 },
+                DecadeCount { decade: None, count: 1 },
+            ]
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_generate_thumbnails_writes_only_thumbnails() {
+        let dir = scratch_dir("thumbnails");
+        fs::write(dir.join("Has Thumb.mp4"), "").unwrap();
+        fs::write(dir.join("New.mp4"), "").unwrap();
+        fs::write(dir.join("Broken.mp4"), "").unwrap();
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "thumbnails".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let existing = LibraryScanner::get_thumbnail_path(&dir, &dir.join("Has Thumb.mp4"));
+        fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        fs::write(&existing, "old").unwrap();
+
+        let (jobs, skipped) = LibraryScanner::thumbnail_jobs(std::slice::from_ref(&folder), false);
+        assert_eq!(skipped, 1);
+        assert_eq!(jobs.len(), 2);
+
+        // Stand-in for ffmpeg: writes the thumbnail, fails for one video
+        let mut progress = Vec::new();
+        let result = LibraryScanner::generate_thumbnails(
+            jobs,
+            |video_path: PathBuf, thumbnail_path: PathBuf| async move {
+                if video_path.ends_with("Broken.mp4") {
+                    return Err("no video stream".to_string());
+                }
+                fs::write(&thumbnail_path, "jpg").map_err(|e| e.to_string())
+            },
+            |processed, total| progress.push((processed, total)),
+        )
+        .await;
+        assert_eq!(result, ThumbResult { generated: 1, failed: 1, skipped: 0 });
+        assert_eq!(progress.last(), Some(&(2, 2)));
+
+        // Only thumbnails were written: no metadata files, existing thumbnail untouched
+        let mut written: Vec<String> = fs::read_dir(existing.parent().unwrap())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        written.sort();
+        assert_eq!(written, vec!["Has Thumb.thumb.jpg", "New.thumb.jpg"]);
+        assert_eq!(fs::read_to_string(&existing).unwrap(), "old");
+
+        // Regenerating includes videos that already have a thumbnail
+        let (jobs, skipped) = LibraryScanner::thumbnail_jobs(&[folder], true);
+        assert_eq!((jobs.len(), skipped), (3, 0));
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_get_hkmeta_path() {
-        let library = Path::new("/music");
-        let video = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
-        let hkmeta = LibraryScanner::get_hkmeta_path(library, video);
+    fn test_artist_counts_groups_variants() {
+        let dir = scratch_dir("artist-counts");
+        fs::write(dir.join("The Beatles - Help.mp4"), "").unwrap();
+        fs::write(dir.join("Beatles - Yesterday.mp4"), "").unwrap();
+        fs::write(dir.join("the beatles - Let It Be.mp4"), "").unwrap();
+        fs::write(dir.join("Queen - Bohemian Rhapsody.mp4"), "").unwrap();
+        fs::write(dir.join("QUEEN - We Will Rock You.mp4"), "").unwrap();
+        fs::write(dir.join("ABBA - Waterloo.mp4"), "").unwrap();
+        fs::write(dir.join("Untitled.mp4"), "").unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "artists".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let counts = LibraryScanner::artist_counts(&[folder]);
         assert_eq!(
-            hkmeta,
-            Path::new("/music/.homekaraoke/Queen - Bohemian Rhapsody.hkmeta.json")
+            counts,
+            vec![
+                ArtistCount { artist: "Beatles".to_string(), count: 3 },
+                ArtistCount { artist: "QUEEN".to_string(), count: 2 },
+                ArtistCount { artist: "ABBA".to_string(), count: 1 },
+            ]
         );
 
-        // Test with subdirectory
-        let video2 = Path::new("/music/Queen/Bohemian Rhapsody.mp4");
-        let hkmeta2 = LibraryScanner::get_hkmeta_path(library, video2);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_dedupe_videos_by_canonical_path() {
+        let videos = vec![
+            search_video("/music/Song.mp4", "Song", Some("Band")),
+            search_video("/music/nested/../Song.mp4", "Song", Some("Band")),
+            search_video("/links/Song.mp4", "Song", Some("Band")),
+        ];
+        // Stub canonicalization: the symlink and `..` path resolve to the same file
+        let canonical = |path: &str| match path {
+            "/links/Song.mp4" | "/music/nested/../Song.mp4" => "/music/Song.mp4".to_string(),
+            other => other.to_string(),
+        };
+
+        let deduped = LibraryScanner::dedupe_videos(videos, canonical);
+        assert_eq!(deduped.len(), 1);
+        assert_eq!(deduped[0].file_path, "/music/Song.mp4");
+    }
+
+    #[test]
+    fn test_resolve_thumbnail_prefers_local() {
+        let mut video = search_video("/a/Song.mp4", "Song", None);
+        video.thumbnail_path = Some("/a/.homekaraoke/Song.thumb.jpg".to_string());
+        video.youtube_id = Some("dQw4w9WgXcQ".to_string());
+
+        let video = video.with_resolved_thumbnails();
         assert_eq!(
-            hkmeta2,
-            Path::new("/music/.homekaraoke/Queen/Bohemian Rhapsody.hkmeta.json")
+            video.thumbnail,
+            ThumbnailSource::Local { path: "/a/.homekaraoke/Song.thumb.jpg".to_string() }
+        );
+        assert_eq!(
+            video.thumbnail_alternative,
+            Some(ThumbnailSource::Youtube { url: "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg".to_string() })
         );
     }
 
     #[test]
-    fn test_get_thumbnail_path() {
-        let library = Path::new("/music");
-        let video = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
-        let thumb = LibraryScanner::get_thumbnail_path(library, video);
+    fn test_resolve_thumbnail_local_only() {
+        let mut video = search_video("/a/Song.mp4", "Song", None);
+        video.thumbnail_path = Some("/a/.homekaraoke/Song.thumb.jpg".to_string());
+
         assert_eq!(
-            thumb,
-            Path::new("/music/.homekaraoke/Queen - Bohemian Rhapsody.thumb.jpg")
+            resolve_thumbnail(&video),
+            ThumbnailSource::Local { path: "/a/.homekaraoke/Song.thumb.jpg".to_string() }
         );
+        assert_eq!(alternative_thumbnail(&video), None);
     }
 
     #[test]
-    fn test_get_legacy_hkmeta_path() {
-        let video = Path::new("/music/Queen - Bohemian Rhapsody.mp4");
-        let hkmeta = LibraryScanner::get_legacy_hkmeta_path(video);
+    fn test_resolve_thumbnail_falls_back_to_youtube() {
+        let mut video = search_video("/a/Song.mp4", "Song", None);
+        video.youtube_id = Some("dQw4w9WgXcQ".to_string());
+
         assert_eq!(
-            hkmeta,
-            Path::new("/music/Queen - Bohemian Rhapsody.hkmeta.json")
+            resolve_thumbnail(&video),
+            ThumbnailSource::Youtube { url: "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg".to_string() }
         );
+        assert_eq!(alternative_thumbnail(&video), None);
     }
 
     #[test]
-    fn test_parse_filename_artist_with_hyphen() {
-        // "AC-DC" has a hyphen but NOT " - " (space-hyphen-space), so it parses correctly
-        let path = Path::new("/music/AC-DC - Back In Black.mp4");
-        let (title, artist) = LibraryScanner::parse_filename(path);
-        assert_eq!(title, "Back In Black");
-        assert_eq!(artist, Some("AC-DC".to_string()));
+    fn test_resolve_thumbnail_none() {
+        let mut video = search_video("/a/Song.mp4", "Song", None);
+        assert_eq!(resolve_thumbnail(&video), ThumbnailSource::None);
+        assert_eq!(alternative_thumbnail(&video), None);
+
+        // A malformed YouTube ID never ends up in a URL
+        video.youtube_id = Some("../evil?x=1".to_string());
+        assert_eq!(resolve_thumbnail(&video), ThumbnailSource::None);
     }
 
     #[test]
-    fn test_parse_filename_multiple_hyphens() {
-        // Multiple " - " separators - splits on first one for correct Artist/Title-Subtitle
-        let path = Path::new("/music/Twenty One Pilots - Heathens - From Suicide Squad.mp4");
-        let (title, artist) = LibraryScanner::parse_filename(path);
-        assert_eq!(title, "Heathens - From Suicide Squad");
-        assert_eq!(artist, Some("Twenty One Pilots".to_string()));
+    fn test_thumbnail_source_serialization() {
+        let json = serde_json::to_value(ThumbnailSource::Local { path: "/t.jpg".to_string() }).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "local", "path": "/t.jpg" }));
+        let json = serde_json::to_value(ThumbnailSource::None).unwrap();
+        assert_eq!(json, serde_json::json!({ "kind": "none" }));
     }
 
     #[test]
-    fn test_parse_filename_hyphenated_artist_with_subtitle() {
-        // Complex case: hyphenated artist AND subtitle
-        // "Artist-Name - Title - Subtitle" → splits on first " - "
-        let path = Path::new("/music/Twenty-One Pilots - Heathens - Live Version.mp4");
-        let (title, artist) = LibraryScanner::parse_filename(path);
-        assert_eq!(title, "Heathens - Live Version");
-        assert_eq!(artist, Some("Twenty-One Pilots".to_string()));
+    fn test_dedupe_videos_collapses_title_artist_by_preference() {
+        let mut with_thumbnail = search_video("/a/Song.mp4", "Song", Some("Band"));
+        with_thumbnail.thumbnail_path = Some("/a/.homekaraoke/Song.thumb.jpg".to_string());
+        let mut with_cdg = search_video("/b/Song.mp3", "song ", Some("BAND"));
+        with_cdg.has_cdg = true;
+        let mut with_lyrics = search_video("/c/Song.mkv", "Song", Some("Band"));
+        with_lyrics.has_lyrics = true;
+
+        let videos = vec![
+            search_video("/a/Other.mp4", "Other", None),
+            with_thumbnail,
+            with_cdg,
+            search_video("/d/Song.mp4", "Song", Some("Cover Band")),
+            with_lyrics,
+        ];
+
+        let deduped = LibraryScanner::dedupe_videos(videos, |path| path.to_string());
+        let paths: Vec<&str> = deduped.iter().map(|v| v.file_path.as_str()).collect();
+        // Lyrics copy wins, in the position of the first "Song" by "Band"
+        assert_eq!(paths, vec!["/a/Other.mp4", "/c/Song.mkv", "/d/Song.mp4"]);
     }
 
     #[test]
-    fn test_cdg_companion_detection() {
-        // CDG detection relies on file system, so we test the path logic
-        let video = Path::new("/music/karaoke.mp4");
-        let cdg_path = video.with_extension("cdg");
-        assert_eq!(cdg_path, Path::new("/music/karaoke.cdg"));
+    fn test_scan_folder_resumes_after_checkpoint() {
+        let dir = scratch_dir("scan-resume");
+        for name in ["a.mp4", "b.mp4", "c.mp4", "d.mp4"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "resume".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let options = ScanOptions {
+            create_hkmeta: true,
+            ..Default::default()
+        };
+
+        let checkpoint = dir.join("b.mp4").to_string_lossy().to_string();
+        let mut done = Vec::new();
+        let result = LibraryScanner::scan_folder(
+            &folder,
+            &options,
+            Some(&checkpoint),
+            &AtomicBool::new(false),
+            |path, processed| done.push((path.file_name().unwrap().to_string_lossy().to_string(), processed)),
+            |_| {},
+        );
+
+        assert_eq!(result.files_found, 4);
+        assert_eq!(result.files_skipped, 2);
+        assert_eq!(result.hkmeta_created, 2);
+        assert_eq!(done, vec![("c.mp4".to_string(), 3), ("d.mp4".to_string(), 4)]);
+        // Files before the checkpoint were not reprocessed
+        assert!(LibraryScanner::read_hkmeta(&dir, &dir.join("a.mp4")).is_none());
+        assert!(LibraryScanner::read_hkmeta(&dir, &dir.join("c.mp4")).is_some());
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
-    // Tests for parse_year_from_filename
+    #[test]
+    fn test_scan_folder_stops_when_cancelled() {
+        let dir = scratch_dir("scan-cancel");
+        for name in ["a.mp4", "b.mp4", "c.mp4", "d.mp4"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "cancel".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let options = ScanOptions {
+            create_hkmeta: true,
+            ..Default::default()
+        };
+
+        // Cancel from the UI while the second file is being processed
+        let cancel = AtomicBool::new(false);
+        let mut done = Vec::new();
+        let result = LibraryScanner::scan_folder(
+            &folder,
+            &options,
+            None,
+            &cancel,
+            |path, processed| {
+                done.push(path.file_name().unwrap().to_string_lossy().to_string());
+                if processed == 2 {
+                    cancel.store(true, Ordering::SeqCst);
+                }
+            },
+            |_| {},
+        );
+
+        assert!(result.cancelled);
+        assert_eq!(result.files_found, 4);
+        assert_eq!(result.hkmeta_created, 2);
+        assert_eq!(done, vec!["a.mp4", "b.mp4"]);
+        assert!(LibraryScanner::read_hkmeta(&dir, &dir.join("c.mp4")).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
 
     #[test]
-    fn test_parse_year_parentheses() {
-        // Year in parentheses: (YYYY)
-        let path = Path::new("/music/Artist - Song Title (1985).mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(1985));
+    fn test_scan_folder_leaves_file_cancelled_mid_fetch_for_resume() {
+        let dir = scratch_dir("scan-cancel-fetch");
+        for name in ["Queen - Bohemian Rhapsody.mp4", "Toto - Africa.mp4"] {
+            fs::write(dir.join(name), "").unwrap();
+        }
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "cancel".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let options = ScanOptions {
+            create_hkmeta: true,
+            fetch_song_info: true,
+            fetch_lyrics: true,
+            ..Default::default()
+        };
+
+        // Cancel as soon as the first song's fetch starts, so no request is sent
+        let cancel = AtomicBool::new(false);
+        let mut done = Vec::new();
+        let mut progress = Vec::new();
+        let result = LibraryScanner::scan_folder(
+            &folder,
+            &options,
+            None,
+            &cancel,
+            |path, _| done.push(path.to_path_buf()),
+            |p| {
+                progress.push(p.clone());
+                cancel.store(true, Ordering::SeqCst);
+            },
+        );
+
+        assert!(result.cancelled);
+        assert_eq!(result.hkmeta_created, 0);
+        assert_eq!(
+            progress,
+            vec![ScanProgress {
+                folder_id: 1,
+                fetched: 0,
+                total: 2,
+                title: "Bohemian Rhapsody".to_string(),
+                artist: Some("Queen".to_string()),
+                // Two MusicBrainz requests at 1.1s each, rounded up
+                estimated_remaining_secs: 3,
+            }]
+        );
+        // No checkpoint past the in-flight file, and no half-fetched metadata for it
+        assert!(done.is_empty());
+        let in_flight = dir.join("Queen - Bohemian Rhapsody.mp4");
+        assert!(LibraryScanner::find_hkmeta_path(&dir, &in_flight).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_brackets() {
-        // Year in brackets: [YYYY]
-        let path = Path::new("/music/Artist - Song Title [2020].mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2020));
+    fn test_set_lyrics_rejects_parent_dir_paths() {
+        let dir = scratch_dir("lyrics-parent-dir");
+        let library = dir.join("Library");
+        let elsewhere = dir.join("Elsewhere");
+        fs::create_dir_all(&library).unwrap();
+        fs::create_dir_all(&elsewhere).unwrap();
+        fs::write(elsewhere.join("x.mp4"), "").unwrap();
+        let escaping = library.join("..").join("Elsewhere").join("x.mp4");
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: library.to_string_lossy().to_string(),
+            name: "library".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        assert!(LibraryScanner::folder_for_file(std::slice::from_ref(&folder), &escaping).is_none());
+        assert!(LibraryScanner::folder_for_file(&[folder], &library.join("y.mp4")).is_some());
+
+        assert!(LibraryScanner::set_lyrics(&library, &escaping, "Hello", "plain", false).is_err());
+        // `.homekaraoke/../Elsewhere` would have landed the metadata in the library itself
+        assert!(!library.join("Elsewhere").exists());
+        assert!(!library.join(".homekaraoke").exists());
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_delimited() {
-        // Year delimited by spaces/hyphens/underscores
-        let path = Path::new("/music/Artist - Song Title - 2015 - Karaoke.mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2015));
+    fn test_regenerate_keeps_locked_lyrics() {
+        let dir = scratch_dir("locked-lyrics");
+        fs::write(dir.join("Locked.mp4"), "").unwrap();
+        fs::write(dir.join("Unlocked.mp4"), "").unwrap();
+        LibraryScanner::set_lyrics(&dir, &dir.join("Locked.mp4"), "[00:01.00]Mine", "lrc", true).unwrap();
+        LibraryScanner::set_lyrics(&dir, &dir.join("Unlocked.mp4"), "Replace me", "plain", false).unwrap();
+
+        assert!(LibraryScanner::set_lyrics(&dir, &dir.join("Locked.mp4"), "x", "srt", true).is_err());
+        assert!(LibraryScanner::set_lyrics(&dir, &dir.join("Locked.mp4"), "  ", "plain", true).is_err());
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "lyrics".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+        let options = ScanOptions {
+            create_hkmeta: true,
+            regenerate: true,
+            ..Default::default()
+        };
+        let result = LibraryScanner::scan_folder(&folder, &options, None, &AtomicBool::new(false), |_, _| {}, |_| {});
+        assert_eq!(result.hkmeta_created, 2);
+
+        let locked = LibraryScanner::read_hkmeta(&dir, &dir.join("Locked.mp4"))
+            .unwrap()
+            .lyrics
+            .unwrap();
+        assert_eq!(locked.content.as_deref(), Some("[00:01.00]Mine"));
+        assert_eq!(locked.format.as_deref(), Some("lrc"));
+        assert_eq!(locked.lyrics_source.as_deref(), Some(LYRICS_SOURCE_MANUAL));
+        assert!(locked.lyrics_locked);
+
+        let unlocked = LibraryScanner::read_hkmeta(&dir, &dir.join("Unlocked.mp4")).unwrap();
+        assert!(unlocked.lyrics.is_none());
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_trailing() {
-        // Year at end of filename
-        let path = Path::new("/music/Artist - Song Title - 2018.mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2018));
+    fn test_estimated_fetch_secs() {
+        assert_eq!(LibraryScanner::estimated_fetch_secs(0, true), 0);
+        assert_eq!(LibraryScanner::estimated_fetch_secs(10, true), 11);
+        assert_eq!(LibraryScanner::estimated_fetch_secs(1, true), 2);
+        assert_eq!(LibraryScanner::estimated_fetch_secs(10, false), 0);
     }
 
     #[test]
-    fn test_parse_year_priority_parens_over_brackets() {
-        // Parentheses should take priority over brackets
-        let path = Path::new("/music/Song [1985] (2023).mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2023));
+    fn test_compute_content_hash_ignores_name_and_checks_size() {
+        let dir = scratch_dir("content-hash");
+        fs::write(dir.join("a.mp4"), "same bytes").unwrap();
+        fs::write(dir.join("b.mkv"), "same bytes").unwrap();
+        fs::write(dir.join("c.mp4"), "same bytes, longer").unwrap();
+
+        let a = LibraryScanner::compute_content_hash(&dir.join("a.mp4")).unwrap();
+        let b = LibraryScanner::compute_content_hash(&dir.join("b.mkv")).unwrap();
+        let c = LibraryScanner::compute_content_hash(&dir.join("c.mp4")).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert!(LibraryScanner::compute_content_hash(&dir.join("missing.mp4")).is_err());
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_invalid_range_too_old() {
-        // Year before 1900 should be rejected
-        let path = Path::new("/music/Song (1850).mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    fn test_find_duplicate_content_groups_and_caches_hash() {
+        let dir = scratch_dir("duplicate-content");
+        fs::create_dir_all(dir.join("Backup")).unwrap();
+        fs::write(dir.join("Queen - Bohemian Rhapsody.mp4"), "video one").unwrap();
+        fs::write(dir.join("Backup").join("bohemian_copy.mp4"), "video one").unwrap();
+        fs::write(dir.join("Other Song.mp4"), "video two").unwrap();
+        LibraryScanner::write_hkmeta(
+            &dir,
+            &dir.join("Queen - Bohemian Rhapsody.mp4"),
+            &HkMeta {
+                version: Some(1),
+                title: Some("Bohemian Rhapsody".to_string()),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+
+        let folder = LibraryFolder {
+            id: 1,
+            path: dir.to_string_lossy().to_string(),
+            name: "dupes".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        };
+
+        let mut progress = Vec::new();
+        // The same folder listed twice must not report files as their own duplicates
+        let groups = LibraryScanner::find_duplicate_content(&[folder.clone(), folder], |done, total| {
+            progress.push((done, total))
+        });
+
+        assert_eq!(progress, vec![(1, 3), (2, 3), (3, 3)]);
+        assert_eq!(groups.len(), 1);
+        let mut names: Vec<&str> = groups[0].videos.iter().map(|v| v.file_name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["Queen - Bohemian Rhapsody.mp4", "bohemian_copy.mp4"]);
+
+        // Hash cached in existing metadata, which keeps its other fields
+        let hkmeta = LibraryScanner::read_hkmeta(&dir, &dir.join("Queen - Bohemian Rhapsody.mp4")).unwrap();
+        assert_eq!(hkmeta.content_hash.as_deref(), Some(groups[0].content_hash.as_str()));
+        assert_eq!(hkmeta.content_hash_size, Some(9));
+        assert_eq!(hkmeta.title.as_deref(), Some("Bohemian Rhapsody"));
+        // No metadata file is created just to cache a hash
+        assert!(LibraryScanner::read_hkmeta(&dir, &dir.join("Other Song.mp4")).is_none());
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_invalid_range_too_new() {
-        // Year after 2099 should be rejected
-        let path = Path::new("/music/Song (2150).mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    fn test_find_video_files_skips_hkignore_subdirectory() {
+        let dir = scratch_dir("hkignore-subdir");
+        fs::create_dir_all(dir.join("Rock").join("extras")).unwrap();
+        fs::write(dir.join("Rock").join("Song.mp4"), b"").unwrap();
+        fs::write(dir.join("Rock").join("extras").join("Bonus.mp4"), b"").unwrap();
+        fs::write(dir.join(crate::services::hkignore::IGNORE_FILE_NAME), "extras/\n").unwrap();
+
+        let files = LibraryScanner::find_video_files(&dir);
+        assert_eq!(files, vec![dir.join("Rock").join("Song.mp4")]);
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_no_year() {
-        // Filename without year
-        let path = Path::new("/music/Artist - Song Title.mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    fn test_find_video_files_skips_hkignore_extension() {
+        let dir = scratch_dir("hkignore-extension");
+        fs::write(dir.join("Song.mp4"), b"").unwrap();
+        fs::write(dir.join("Old Song.avi"), b"").unwrap();
+        fs::write(dir.join(crate::services::hkignore::IGNORE_FILE_NAME), "# legacy rips\n*.avi\n").unwrap();
+
+        let files = LibraryScanner::find_video_files(&dir);
+        assert_eq!(files, vec![dir.join("Song.mp4")]);
+
+        let _ = fs::remove_dir_all(&dir);
     }
 
     #[test]
-    fn test_parse_year_not_a_year() {
-        // 4-digit number that's not a valid year (e.g., track number)
-        let path = Path::new("/music/Artist - 0001 Song Title.mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), None);
+    fn test_duration_mismatch() {
+        let path = Path::new("/karaoke/Queen - Bohemian Rhapsody.mp4");
+
+        let mismatch = LibraryScanner::duration_mismatch(path, Some(360.0), Some(354.5), 2.0).unwrap();
+        assert_eq!(mismatch.file_path, "/karaoke/Queen - Bohemian Rhapsody.mp4");
+        assert_eq!(mismatch.difference, 5.5);
+
+        // Audio longer than video counts too; within the threshold does not
+        assert!(LibraryScanner::duration_mismatch(path, Some(200.0), Some(203.0), 2.0).is_some());
+        assert!(LibraryScanner::duration_mismatch(path, Some(200.0), Some(201.5), 2.0).is_none());
+
+        // Single-stream files and unknown durations are never a mismatch
+        assert!(LibraryScanner::duration_mismatch(path, Some(200.0), None, 2.0).is_none());
+        assert!(LibraryScanner::duration_mismatch(path, None, Some(200.0), 2.0).is_none());
+        assert!(LibraryScanner::duration_mismatch(path, None, None, 0.0).is_none());
     }
 
     #[test]
-    fn test_parse_year_underscore_delimited() {
-        // Year with underscores
-        let path = Path::new("/music/Artist_Song_2010_Karaoke.mp4");
-        assert_eq!(LibraryScanner::parse_year_from_filename(&path), Some(2010));
+    fn test_update_tags_adds_and_removes() {
+        let dir = scratch_dir("bulk-tags");
+        let video = dir.join("Song.mp4");
+        fs::write(&video, "").unwrap();
+        let hkmeta = HkMeta {
+            version: Some(1),
+            title: Some("Song".to_string()),
+            artist: Some("Band".to_string()),
+            tags: Some(vec!["Rock".to_string(), "duet".to_string(), "80s".to_string()]),
+            ..Default::default()
+        };
+        LibraryScanner::write_hkmeta(&dir, &video, &hkmeta).unwrap();
+
+        let tags = |list: &[&str]| list.iter().map(|t| t.to_string()).collect::<Vec<_>>();
+        let changed = LibraryScanner::update_tags(&dir, &video, &tags(&["kids", " rock ", "KIDS"]), &tags(&["Duet"]))
+            .unwrap();
+        assert!(changed);
+
+        let saved = LibraryScanner::read_hkmeta(&dir, &video).unwrap();
+        assert_eq!(saved.tags, Some(tags(&["Rock", "80s", "kids"])));
+        // Other metadata is kept
+        assert_eq!(saved.artist.as_deref(), Some("Band"));
+
+        // Re-applying the same change is a no-op
+        assert!(!LibraryScanner::update_tags(&dir, &video, &tags(&["Kids"]), &tags(&["duet"])).unwrap());
+
+        // Removals apply first, so a tag both removed and added is kept (with the new spelling)
+        assert_eq!(
+            LibraryScanner::merge_tags(Some(tags(&["Rock", "80s"])), &tags(&["ROCK"]), &tags(&["rock"])),
+            Some(tags(&["80s", "ROCK"]))
+        );
+
+        // Removing every tag clears the list
+        assert!(LibraryScanner::update_tags(&dir, &video, &[], &tags(&["rock", "80S", "kids"])).unwrap());
+        assert_eq!(LibraryScanner::read_hkmeta(&dir, &video).unwrap().tags, None);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_probe_folder() {
+        let dir = scratch_dir("probe-folder");
+        for artist in ["A", "B", "C", "D"] {
+            fs::create_dir_all(dir.join(artist)).unwrap();
+            for song in 0..5 {
+                fs::write(dir.join(artist).join(format!("Song {}.mp4", song)), "").unwrap();
+            }
+            fs::write(dir.join(artist).join("cover.jpg"), "").unwrap();
+        }
+
+        let probe = LibraryScanner::probe_folder(&dir);
+        assert_eq!(probe.video_count, 20);
+        assert!(!probe.count_capped);
+        assert_eq!(probe.estimated_total, 20);
+        assert!(probe.metadata_writable);
+        // The writability check leaves nothing behind
+        assert!(!dir.join(".homekaraoke").exists());
+
+        // Stopping after one and a half folders extrapolates over all four
+        let probe = LibraryScanner::probe_folder_with_limit(&dir, 7);
+        assert_eq!(probe.video_count, 7);
+        assert!(probe.count_capped);
+        assert_eq!(probe.estimated_total, 14);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_metadata_duration_mismatch() {
+        let path = Path::new("/karaoke/Queen - Bohemian Rhapsody.mp4");
+
+        // Wrong MusicBrainz match: a 3:00 radio edit for a 6:00 file
+        let mismatch = LibraryScanner::metadata_duration_mismatch(path, 180, 360, 0.15).unwrap();
+        assert_eq!(mismatch.file_path, "/karaoke/Queen - Bohemian Rhapsody.mp4");
+        assert_eq!((mismatch.stored_duration, mismatch.actual_duration), (180, 360));
+        assert_eq!(mismatch.difference_ratio, 0.5);
+
+        // Exactly at the threshold is fine; just over it is flagged, either direction
+        assert!(LibraryScanner::metadata_duration_mismatch(path, 230, 200, 0.15).is_none());
+        assert!(LibraryScanner::metadata_duration_mismatch(path, 170, 200, 0.15).is_none());
+        assert!(LibraryScanner::metadata_duration_mismatch(path, 231, 200, 0.15).is_some());
+        assert!(LibraryScanner::metadata_duration_mismatch(path, 169, 200, 0.15).is_some());
+
+        // Small differences from rounding never count; empty files are skipped
+        assert!(LibraryScanner::metadata_duration_mismatch(path, 201, 200, 0.15).is_none());
+        assert!(LibraryScanner::metadata_duration_mismatch(path, 200, 0, 0.15).is_none());
     }
 }
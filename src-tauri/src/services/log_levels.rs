@@ -0,0 +1,119 @@
+//! Runtime log level gates for the log plugin's targets.
+//! The plugin's per-target filters are fixed at startup, so each filter consults
+//! an atomic level here that `set_log_level` can change while the app runs.
+//! Levels are not persisted; every start uses the defaults.
+
+use log::{Level, LevelFilter};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Log output the level applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogTarget {
+    Stdout,
+    Webview,
+    File,
+}
+
+/// Current level of each log target
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LogLevels {
+    pub stdout: String,
+    pub webview: String,
+    pub file: String,
+}
+
+// Stdout and webview default to info for cleaner output; the file captures debug for issue reports
+static STDOUT_LEVEL: AtomicU8 = AtomicU8::new(LevelFilter::Info as u8);
+static WEBVIEW_LEVEL: AtomicU8 = AtomicU8::new(LevelFilter::Info as u8);
+static FILE_LEVEL: AtomicU8 = AtomicU8::new(LevelFilter::Debug as u8);
+
+/// Most verbose level any target can be set to (the log plugin's global level)
+pub const MAX_LEVEL: LevelFilter = LevelFilter::Trace;
+
+fn gate(target: LogTarget) -> &'static AtomicU8 {
+    match target {
+        LogTarget::Stdout => &STDOUT_LEVEL,
+        LogTarget::Webview => &WEBVIEW_LEVEL,
+        LogTarget::File => &FILE_LEVEL,
+    }
+}
+
+fn level_from_u8(value: u8) -> LevelFilter {
+    match value {
+        0 => LevelFilter::Off,
+        1 => LevelFilter::Error,
+        2 => LevelFilter::Warn,
+        3 => LevelFilter::Info,
+        4 => LevelFilter::Debug,
+        _ => LevelFilter::Trace,
+    }
+}
+
+/// Parse a level name ("off", "error", "warn", "info", "debug", "trace"), case-insensitively
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Invalid log level: {} (expected off, error, warn, info, debug or trace)", level))
+}
+
+/// Current level of a target
+pub fn level(target: LogTarget) -> LevelFilter {
+    level_from_u8(gate(target).load(Ordering::Relaxed))
+}
+
+/// Change a target's level; takes effect for the next log record
+pub fn set_level(target: LogTarget, level: LevelFilter) {
+    gate(target).store(level as u8, Ordering::Relaxed);
+}
+
+/// Whether a record at `level` should be written to `target`
+pub fn allows(target: LogTarget, level: Level) -> bool {
+    level <= self::level(target)
+}
+
+/// Current level of every target, as lowercase names
+pub fn levels() -> LogLevels {
+    let name = |target| level(target).as_str().to_lowercase();
+    LogLevels {
+        stdout: name(LogTarget::Stdout),
+        webview: name(LogTarget::Webview),
+        file: name(LogTarget::File),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug").unwrap(), LevelFilter::Debug);
+        assert_eq!(parse_level(" TRACE ").unwrap(), LevelFilter::Trace);
+        assert_eq!(parse_level("off").unwrap(), LevelFilter::Off);
+        assert!(parse_level("loud").is_err());
+        assert!(parse_level("").is_err());
+    }
+
+    #[test]
+    fn test_level_gates() {
+        assert_eq!(levels().stdout, "info");
+        assert!(allows(LogTarget::Stdout, Level::Info));
+        assert!(!allows(LogTarget::Stdout, Level::Debug));
+
+        set_level(LogTarget::Stdout, LevelFilter::Trace);
+        assert!(allows(LogTarget::Stdout, Level::Trace));
+        // Other targets are unaffected
+        assert!(!allows(LogTarget::Webview, Level::Debug));
+        assert!(allows(LogTarget::File, Level::Debug));
+
+        set_level(LogTarget::Stdout, LevelFilter::Off);
+        assert!(!allows(LogTarget::Stdout, Level::Error));
+        assert_eq!(levels().stdout, "off");
+
+        set_level(LogTarget::Stdout, LevelFilter::Info);
+        assert_eq!(level(LogTarget::Stdout), LevelFilter::Info);
+    }
+}
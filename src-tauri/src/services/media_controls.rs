@@ -8,6 +8,8 @@ use std::time::Duration;
 
 pub struct MediaControlsService {
     controls: MediaControls,
+    /// Last playback state sent to the OS (`None` = stopped or never set)
+    last_playback: Option<bool>,
 }
 
 impl MediaControlsService {
@@ -38,7 +40,10 @@ impl MediaControlsService {
             .map_err(|e| format!("Failed to attach media controls handler: {}", e))?;
 
         info!("Media controls initialized successfully");
-        Ok(Self { controls })
+        Ok(Self {
+            controls,
+            last_playback: None,
+        })
     }
 
     pub fn set_metadata(
@@ -82,6 +87,7 @@ impl MediaControlsService {
             .set_playback(playback)
             .map_err(|e| format!("Failed to set playback state: {}", e))?;
 
+        self.last_playback = Some(is_playing);
         Ok(())
     }
 
@@ -90,6 +96,12 @@ impl MediaControlsService {
         self.controls
             .set_playback(MediaPlayback::Stopped)
             .map_err(|e| format!("Failed to stop media controls: {}", e))?;
+        self.last_playback = None;
         Ok(())
     }
+
+    /// Last playback state: `Some(true)` playing, `Some(false)` paused, `None` stopped
+    pub fn last_playback(&self) -> Option<bool> {
+        self.last_playback
+    }
 }
@@ -4,18 +4,13 @@
 //! - MusicBrainz API for song metadata (duration, album, year)
 //! - Lrclib API for lyrics (synced and plain)
 
+use crate::services::http_client;
 use log::{debug, info, warn};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
 use tokio::time::sleep;
 
-/// User agent for API requests (MusicBrainz requires contact info)
-const USER_AGENT: &str = concat!(
-    "HomeKaraoke/",
-    env!("CARGO_PKG_VERSION"),
-    " (+https://github.com/zalun/karaoke-app)"
-);
-
 /// MusicBrainz API base URL
 const MUSICBRAINZ_API: &str = "https://musicbrainz.org/ws/2";
 
@@ -23,7 +18,7 @@ const MUSICBRAINZ_API: &str = "https://musicbrainz.org/ws/2";
 const LRCLIB_API: &str = "https://lrclib.net/api";
 
 /// Rate limit delay for MusicBrainz (1 request per second)
-const MUSICBRAINZ_RATE_LIMIT_MS: u64 = 1100;
+pub(crate) const MUSICBRAINZ_RATE_LIMIT_MS: u64 = 1100;
 
 /// Song information fetched from external APIs
 #[derive(Debug, Clone, Default)]
@@ -35,13 +30,51 @@ pub struct SongInfo {
 }
 
 /// Lyrics result from external APIs
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct LyricsResult {
     pub synced_lyrics: Option<String>,
     pub plain_lyrics: Option<String>,
     pub duration: Option<u32>,
 }
 
+/// Which lyrics to store when a result has synced (LRC) and/or plain lyrics
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LyricsPreference {
+    /// Synced if available, otherwise plain
+    #[default]
+    SyncedFirst,
+    /// Plain if available, otherwise synced
+    PlainFirst,
+    /// Synced only; results with only plain lyrics are skipped
+    SyncedOnly,
+    /// Plain only; results with only synced lyrics are skipped
+    PlainOnly,
+}
+
+impl LyricsPreference {
+    /// Whether a result with these kinds of lyrics has anything to store
+    pub fn accepts(self, has_synced: bool, has_plain: bool) -> bool {
+        match self {
+            LyricsPreference::SyncedFirst | LyricsPreference::PlainFirst => has_synced || has_plain,
+            LyricsPreference::SyncedOnly => has_synced,
+            LyricsPreference::PlainOnly => has_plain,
+        }
+    }
+}
+
+/// What `MetadataFetcher::fetch_all` looks up for one song
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FetchOptions {
+    /// Fetch song info from MusicBrainz
+    pub song_info: bool,
+    /// Fetch lyrics from Lrclib
+    pub lyrics: bool,
+    pub lyrics_preference: LyricsPreference,
+    /// The file's known length in seconds, for exact lyrics matching
+    pub duration: Option<u32>,
+}
+
 /// MusicBrainz recording search response
 #[derive(Debug, Deserialize)]
 struct MusicBrainzResponse {
@@ -71,36 +104,80 @@ struct MusicBrainzRelease {
     date: Option<String>,
 }
 
-/// Lrclib search response item
+/// Lrclib search response item (also the `/get/{id}` response)
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 struct LrclibResult {
-    #[allow(dead_code)]
     id: u64,
-    #[allow(dead_code)]
     track_name: String,
-    #[allow(dead_code)]
     artist_name: String,
+    album_name: Option<String>,
     duration: Option<f64>,
     synced_lyrics: Option<String>,
     plain_lyrics: Option<String>,
 }
 
+impl LrclibResult {
+    fn has_lyrics(&self) -> bool {
+        self.synced_lyrics.is_some() || self.plain_lyrics.is_some()
+    }
+
+    fn into_lyrics(self) -> LyricsResult {
+        LyricsResult {
+            synced_lyrics: self.synced_lyrics,
+            plain_lyrics: self.plain_lyrics,
+            duration: self.duration.map(|d| d.round() as u32),
+        }
+    }
+}
+
+/// A Lrclib match the user can pick when automatic matching chose the wrong song
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LyricsCandidate {
+    /// Lrclib id, for `fetch_lyrics_by_id`
+    pub id: u64,
+    pub track_name: String,
+    pub artist_name: String,
+    pub album_name: Option<String>,
+    /// Duration in seconds
+    pub duration: Option<u32>,
+    pub has_synced_lyrics: bool,
+    pub has_plain_lyrics: bool,
+}
+
+impl From<&LrclibResult> for LyricsCandidate {
+    fn from(result: &LrclibResult) -> Self {
+        Self {
+            id: result.id,
+            track_name: result.track_name.clone(),
+            artist_name: result.artist_name.clone(),
+            album_name: result.album_name.clone(),
+            duration: result.duration.map(|d| d.round() as u32),
+            has_synced_lyrics: result.synced_lyrics.is_some(),
+            has_plain_lyrics: result.plain_lyrics.is_some(),
+        }
+    }
+}
+
 /// Metadata fetcher service
 pub struct MetadataFetcher {
     client: reqwest::Client,
+    /// MusicBrainz API base URL (overridden in tests)
+    musicbrainz_api: String,
+    /// Lrclib API base URL (overridden in tests)
+    lrclib_api: String,
 }
 
 impl MetadataFetcher {
     /// Create a new metadata fetcher
     pub fn new() -> Result<Self, String> {
-        let client = reqwest::Client::builder()
-            .user_agent(USER_AGENT)
-            .timeout(Duration::from_secs(10))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let client = http_client::build_client(Duration::from_secs(10))?;
 
-        Ok(Self { client })
+        Ok(Self {
+            client,
+            musicbrainz_api: MUSICBRAINZ_API.to_string(),
+            lrclib_api: LRCLIB_API.to_string(),
+        })
     }
 
     /// Fetch song info from MusicBrainz
@@ -134,7 +211,7 @@ impl MetadataFetcher {
 
         let url = format!(
             "{}/recording?query={}&fmt=json&limit=1",
-            MUSICBRAINZ_API,
+            self.musicbrainz_api,
             urlencoding::encode(&query)
         );
 
@@ -202,15 +279,8 @@ impl MetadataFetcher {
         Some(info)
     }
 
-    /// Fetch lyrics from Lrclib
-    ///
-    /// Searches for lyrics by title and artist.
-    /// Prefers synced lyrics (LRC format) over plain lyrics.
-    pub async fn fetch_lyrics(
-        &self,
-        title: &str,
-        artist: Option<&str>,
-    ) -> Option<LyricsResult> {
+    /// Search Lrclib by title and optional artist
+    async fn search_lrclib(&self, title: &str, artist: Option<&str>) -> Option<Vec<LrclibResult>> {
         // Skip if title is empty or whitespace-only
         if title.trim().is_empty() {
             debug!("Skipping Lrclib search: empty title");
@@ -224,14 +294,14 @@ impl MetadataFetcher {
         let url = if let Some(artist) = artist {
             format!(
                 "{}/search?track_name={}&artist_name={}",
-                LRCLIB_API,
+                self.lrclib_api,
                 urlencoding::encode(title),
                 urlencoding::encode(artist)
             )
         } else {
             format!(
                 "{}/search?track_name={}",
-                LRCLIB_API,
+                self.lrclib_api,
                 urlencoding::encode(title)
             )
         };
@@ -251,25 +321,90 @@ impl MetadataFetcher {
             return None;
         }
 
-        let results: Vec<LrclibResult> = match response.json().await {
-            Ok(r) => r,
+        match response.json().await {
+            Ok(r) => Some(r),
             Err(e) => {
                 warn!("Failed to parse Lrclib response: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Look up the Lrclib record matching title, artist and duration exactly.
+    /// Lrclib only matches records within a couple of seconds of `duration`.
+    async fn get_lrclib(&self, title: &str, artist: &str, duration: u32) -> Option<LrclibResult> {
+        if title.trim().is_empty() || artist.trim().is_empty() {
+            return None;
+        }
+
+        let url = format!(
+            "{}/get?track_name={}&artist_name={}&duration={}",
+            self.lrclib_api,
+            urlencoding::encode(title),
+            urlencoding::encode(artist),
+            duration
+        );
+        debug!("Lrclib get: {}", url);
+
+        let response = match self.client.get(&url).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Lrclib request failed: {}", e);
                 return None;
             }
         };
 
-        // Get first result with lyrics
-        let result = results.into_iter().find(|r| {
-            r.synced_lyrics.is_some() || r.plain_lyrics.is_some()
-        })?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            debug!("Lrclib has no exact match for {:?} by {:?} ({}s)", title, artist, duration);
+            return None;
+        }
+        if !response.status().is_success() {
+            warn!("Lrclib returned status: {}", response.status());
+            return None;
+        }
 
-        let lyrics = LyricsResult {
-            synced_lyrics: result.synced_lyrics,
-            plain_lyrics: result.plain_lyrics,
-            duration: result.duration.map(|d| d.round() as u32),
+        match response.json().await {
+            Ok(r) => Some(r),
+            Err(e) => {
+                warn!("Failed to parse Lrclib response: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Fetch lyrics from Lrclib
+    ///
+    /// When the artist and the file's duration (in seconds) are known, asks Lrclib for
+    /// an exact match first; otherwise, or if that has nothing usable, searches by title
+    /// and artist. Returns the first result that has the kind of lyrics `preference` allows.
+    pub async fn fetch_lyrics(
+        &self,
+        title: &str,
+        artist: Option<&str>,
+        duration: Option<u32>,
+        preference: LyricsPreference,
+    ) -> Option<LyricsResult> {
+        let accepted =
+            |result: &LrclibResult| preference.accepts(result.synced_lyrics.is_some(), result.plain_lyrics.is_some());
+
+        let exact_match = match (artist, duration) {
+            (Some(artist), Some(duration)) => self
+                .get_lrclib(title, artist, duration)
+                .await
+                .filter(|result| accepted(result)),
+            _ => None,
         };
 
+        let result = match exact_match {
+            Some(result) => result,
+            None => self
+                .search_lrclib(title, artist)
+                .await?
+                .into_iter()
+                .find(|result| accepted(result))?,
+        };
+        let lyrics = result.into_lyrics();
+
         info!(
             "Lrclib found: synced={}, plain={}, duration={:?}s",
             lyrics.synced_lyrics.is_some(),
@@ -280,27 +415,83 @@ impl MetadataFetcher {
         Some(lyrics)
     }
 
+    /// List the Lrclib matches (with lyrics) for a title and artist, in Lrclib's order,
+    /// so the user can pick the right one when automatic matching got it wrong.
+    pub async fn search_lyrics_candidates(
+        &self,
+        title: &str,
+        artist: Option<&str>,
+    ) -> Vec<LyricsCandidate> {
+        self.search_lrclib(title, artist)
+            .await
+            .unwrap_or_default()
+            .iter()
+            .filter(|result| result.has_lyrics())
+            .map(LyricsCandidate::from)
+            .collect()
+    }
+
+    /// Fetch lyrics for a specific Lrclib record (picked from `search_lyrics_candidates`).
+    /// Returns None if the record doesn't exist or has no lyrics.
+    pub async fn fetch_lyrics_by_id(&self, lrclib_id: u64) -> Option<LyricsResult> {
+        let url = format!("{}/get/{}", self.lrclib_api, lrclib_id);
+        debug!("Lrclib get: {}", url);
+
+        let response = match self.client.get(&url).send().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Lrclib request failed: {}", e);
+                return None;
+            }
+        };
+
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            debug!("Lrclib record {} not found", lrclib_id);
+            return None;
+        }
+        if !response.status().is_success() {
+            warn!("Lrclib returned status: {}", response.status());
+            return None;
+        }
+
+        let result: LrclibResult = match response.json().await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("Failed to parse Lrclib response: {}", e);
+                return None;
+            }
+        };
+
+        if !result.has_lyrics() {
+            return None;
+        }
+        Some(result.into_lyrics())
+    }
+
     /// Fetch both song info and lyrics with rate limiting
     ///
     /// Adds a delay between MusicBrainz requests to respect rate limits.
+    /// `cancel` is checked before each request; once set, remaining requests are skipped.
     pub async fn fetch_all(
         &self,
         title: &str,
         artist: Option<&str>,
-        fetch_song_info: bool,
-        fetch_lyrics: bool,
+        options: FetchOptions,
+        cancel: &AtomicBool,
     ) -> (Option<SongInfo>, Option<LyricsResult>) {
         let mut song_info = None;
         let mut lyrics = None;
 
-        if fetch_song_info {
+        if options.song_info && !cancel.load(Ordering::SeqCst) {
             song_info = self.fetch_song_info(title, artist).await;
             // Rate limit for MusicBrainz - delay after every request (API counts all requests)
             sleep(Duration::from_millis(MUSICBRAINZ_RATE_LIMIT_MS)).await;
         }
 
-        if fetch_lyrics {
-            lyrics = self.fetch_lyrics(title, artist).await;
+        if options.lyrics && !cancel.load(Ordering::SeqCst) {
+            lyrics = self
+                .fetch_lyrics(title, artist, options.duration, options.lyrics_preference)
+                .await;
         }
 
         (song_info, lyrics)
@@ -326,6 +517,41 @@ impl MetadataFetcher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_lrclib_result_to_candidate_and_lyrics() {
+        let json = r#"{
+            "id": 3396226,
+            "trackName": "I Want to Live",
+            "artistName": "Borislav Slavov",
+            "albumName": "Baldur's Gate 3",
+            "duration": 233.4,
+            "instrumental": false,
+            "plainLyrics": "I feel your breath upon my neck",
+            "syncedLyrics": null
+        }"#;
+        let result: LrclibResult = serde_json::from_str(json).unwrap();
+
+        assert_eq!(
+            LyricsCandidate::from(&result),
+            LyricsCandidate {
+                id: 3396226,
+                track_name: "I Want to Live".to_string(),
+                artist_name: "Borislav Slavov".to_string(),
+                album_name: Some("Baldur's Gate 3".to_string()),
+                duration: Some(233),
+                has_synced_lyrics: false,
+                has_plain_lyrics: true,
+            }
+        );
+
+        let lyrics = result.into_lyrics();
+        assert_eq!(lyrics.plain_lyrics.as_deref(), Some("I feel your breath upon my neck"));
+        assert!(lyrics.synced_lyrics.is_none());
+        assert_eq!(lyrics.duration, Some(233));
+    }
 
     #[test]
     fn test_escape_lucene_plain_text() {
@@ -368,4 +594,111 @@ mod tests {
         assert_eq!(MetadataFetcher::escape_lucene("Müller"), "Müller");
         assert_eq!(MetadataFetcher::escape_lucene("Beyoncé"), "Beyoncé");
     }
+
+    #[tokio::test]
+    async fn test_fetch_lyrics_uses_exact_get_when_duration_known() {
+        // Minimal Lrclib stand-in: answers one request and returns its request line
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let api = format!("http://{}/api", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            let body = r#"{"id":1,"trackName":"Africa","artistName":"Toto","albumName":null,"duration":295.0,"syncedLyrics":"[00:01.00] I hear the drums","plainLyrics":"I hear the drums"}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+            let request = String::from_utf8_lossy(&request).to_string();
+            request.lines().next().unwrap_or_default().to_string()
+        });
+
+        let fetcher = MetadataFetcher {
+            client: reqwest::Client::builder().no_proxy().build().unwrap(),
+            musicbrainz_api: "http://127.0.0.1:9".to_string(),
+            lrclib_api: api,
+        };
+        let lyrics = fetcher
+            .fetch_lyrics("Africa", Some("Toto"), Some(295), LyricsPreference::SyncedFirst)
+            .await
+            .unwrap();
+
+        let request_line = server.await.unwrap();
+        assert_eq!(
+            request_line,
+            "GET /api/get?track_name=Africa&artist_name=Toto&duration=295 HTTP/1.1"
+        );
+        assert_eq!(lyrics.synced_lyrics.as_deref(), Some("[00:01.00] I hear the drums"));
+        assert_eq!(lyrics.duration, Some(295));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_skips_lyrics_when_cancelled_after_song_info() {
+        // MusicBrainz stand-in that cancels the scan while answering
+        let musicbrainz = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let musicbrainz_api = format!("http://{}/ws/2", musicbrainz.local_addr().unwrap());
+        let lrclib = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let lrclib_api = format!("http://{}/api", lrclib.local_addr().unwrap());
+        let cancel = std::sync::Arc::new(AtomicBool::new(false));
+
+        let server_cancel = cancel.clone();
+        let server = tokio::spawn(async move {
+            let (mut socket, _) = musicbrainz.accept().await.unwrap();
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                let n = socket.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                request.extend_from_slice(&buf[..n]);
+            }
+            server_cancel.store(true, Ordering::SeqCst);
+            let body = r#"{"recordings":[{"id":"r1","title":"Africa","length":295000,"releases":[{"title":"Toto IV","date":"1982-04-08"}]}]}"#;
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            socket.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let fetcher = MetadataFetcher {
+            client: reqwest::Client::builder().no_proxy().build().unwrap(),
+            musicbrainz_api,
+            lrclib_api,
+        };
+        let (song_info, lyrics) = fetcher
+            .fetch_all(
+                "Africa",
+                Some("Toto"),
+                FetchOptions {
+                    song_info: true,
+                    lyrics: true,
+                    lyrics_preference: LyricsPreference::SyncedFirst,
+                    duration: Some(295),
+                },
+                &cancel,
+            )
+            .await;
+        server.await.unwrap();
+
+        assert_eq!(song_info.unwrap().album.as_deref(), Some("Toto IV"));
+        assert!(lyrics.is_none());
+        // Lrclib was never contacted
+        assert!(
+            tokio::time::timeout(Duration::from_millis(100), lrclib.accept())
+                .await
+                .is_err()
+        );
+    }
 }
@@ -1,6 +1,16 @@
+pub mod catalog_export;
+pub mod catalog_import;
+pub mod color_contrast;
 pub mod ffmpeg;
+pub mod hkignore;
+pub mod http_client;
 pub mod library_scanner;
+pub mod log_levels;
 pub mod metadata_fetcher;
+pub mod search_classifier;
+pub mod singer_sheets;
+pub mod stream_cache;
+pub mod thumbnail_data;
 pub mod youtube_api;
 pub mod ytdlp;
 
@@ -10,10 +20,16 @@ pub mod media_controls;
 #[cfg(target_os = "macos")]
 pub mod display_watcher;
 
+#[cfg(target_os = "macos")]
+pub mod window_layout;
+
+pub use catalog_export::{export_catalog, write_catalog_export, CatalogExport};
+pub use catalog_import::{import_catalog, CatalogFormat, CatalogImportResult};
 pub use library_scanner::{
-    LibraryFolder, LibraryScanner, LibraryStats, LibraryVideo, ScanOptions, ScanResult,
+    ArtistCount, CacheStatus, DuplicateContentGroup, DurationMismatch, FilenameParsePreview, FolderProbe, LibraryFacets, LibraryFolder,
+    LibraryDistribution, LibraryScanner, LibraryStats, LibraryVideo, MetadataDurationMismatch, MetadataMergeResult, MigrationResult, ScanOptions, ScanProgress, ScanResult, ThumbResult, ThumbnailSource,
 };
-pub use youtube_api::YouTubeApiService;
+pub use youtube_api::{canonical_video_id, YouTubeApiService};
 pub use ytdlp::{get_expanded_path, YtDlpService};
 
 #[cfg(any(target_os = "macos", target_os = "linux", target_os = "windows"))]
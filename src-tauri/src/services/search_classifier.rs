@@ -0,0 +1,207 @@
+//! Heuristic classification of YouTube search results as karaoke tracks,
+//! instrumentals or original recordings, based on title and channel keywords.
+
+use crate::services::ytdlp::SearchResult;
+use serde::Serialize;
+
+/// What a search result most likely is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResultKind {
+    /// Karaoke track (on-screen lyrics, no lead vocals)
+    Karaoke,
+    /// Instrumental or backing track, usually without lyrics on screen
+    Instrumental,
+    /// Original recording with vocals (music video, lyric video, live performance)
+    LikelyOriginal,
+    /// No keywords either way
+    Unknown,
+}
+
+/// Channels that publish karaoke tracks without "karaoke" in their name (lowercase)
+const KARAOKE_CHANNELS: &[&str] = &["sing king", "singking", "karafun", "ameritz"];
+
+/// Title phrases marking a karaoke track (besides the word "karaoke" itself)
+const KARAOKE_PHRASES: &[&str] = &["sing along", "singalong", "videoke", "noraebang"];
+
+/// Title phrases marking an instrumental or backing track
+const INSTRUMENTAL_PHRASES: &[&str] = &[
+    "instrumental",
+    "backing track",
+    "off vocal",
+    "no vocals",
+    "without vocals",
+    "vocals removed",
+    "minus one",
+    "inst",
+];
+
+/// Title phrases marking an original recording
+const ORIGINAL_PHRASES: &[&str] = &[
+    "official video",
+    "official music video",
+    "official audio",
+    "official lyric video",
+    "official visualizer",
+    "lyric video",
+    "lyrics",
+    "music video",
+    "mv",
+    "live",
+    "acoustic",
+];
+
+/// Lowercase alphanumeric words of a text
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Whether `words` contains the phrase's words consecutively
+fn contains_phrase(words: &[String], phrase: &str) -> bool {
+    let phrase: Vec<&str> = phrase.split(' ').collect();
+    words
+        .windows(phrase.len())
+        .any(|window| window.iter().zip(&phrase).all(|(word, wanted)| word == wanted))
+}
+
+/// Classify a search result by its title and channel. Karaoke keywords win over
+/// instrumental ones ("Karaoke Instrumental" is a karaoke track), and both win
+/// over signs of an original recording.
+pub fn classify_search_result(result: &SearchResult) -> ResultKind {
+    let title = result.title.to_lowercase();
+    let channel = result.channel.trim().to_lowercase();
+    let title_words = words(&title);
+
+    let karaoke_channel = channel.contains("karaoke") || KARAOKE_CHANNELS.contains(&channel.as_str());
+    if title.contains("karaoke")
+        || KARAOKE_PHRASES.iter().any(|phrase| contains_phrase(&title_words, phrase))
+        || karaoke_channel
+    {
+        return ResultKind::Karaoke;
+    }
+
+    if INSTRUMENTAL_PHRASES.iter().any(|phrase| contains_phrase(&title_words, phrase)) {
+        return ResultKind::Instrumental;
+    }
+
+    // Auto-generated "Artist - Topic" channels and VEVO only host original recordings
+    if channel.ends_with(" - topic")
+        || channel.contains("vevo")
+        || ORIGINAL_PHRASES.iter().any(|phrase| contains_phrase(&title_words, phrase))
+    {
+        return ResultKind::LikelyOriginal;
+    }
+
+    ResultKind::Unknown
+}
+
+/// Whether a result can be sung along to, i.e. isn't likely an original recording.
+/// Unknown results are kept, since many karaoke uploads have plain titles.
+pub fn is_karaoke_friendly(result: &SearchResult) -> bool {
+    classify_search_result(result) != ResultKind::LikelyOriginal
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str, channel: &str) -> SearchResult {
+        SearchResult {
+            id: "dQw4w9WgXcQ".to_string(),
+            title: title.to_string(),
+            channel: channel.to_string(),
+            duration: None,
+            thumbnail: None,
+            view_count: None,
+        }
+    }
+
+    fn kind(title: &str, channel: &str) -> ResultKind {
+        classify_search_result(&result(title, channel))
+    }
+
+    #[test]
+    fn test_karaoke_titles() {
+        assert_eq!(
+            kind("Queen - Bohemian Rhapsody (Karaoke Version)", "Sing King"),
+            ResultKind::Karaoke
+        );
+        assert_eq!(
+            kind("ABBA - Dancing Queen | Karaoke Version | KaraFun", "KaraFun"),
+            ResultKind::Karaoke
+        );
+        assert_eq!(
+            kind("Adele - Someone Like You (Karaoke Instrumental)", "Karaoke Hits"),
+            ResultKind::Karaoke
+        );
+        assert_eq!(
+            kind("Frozen - Let It Go | Sing-Along | Disney", "DisneyMusicVEVO"),
+            ResultKind::Karaoke
+        );
+        assert_eq!(kind("Mariah Carey - Hero (KARAOKE)", "Some Uploader"), ResultKind::Karaoke);
+    }
+
+    #[test]
+    fn test_karaoke_channels() {
+        // Plain titles from channels that only publish karaoke tracks
+        assert_eq!(kind("Whitney Houston - I Will Always Love You", "Sing King"), ResultKind::Karaoke);
+        assert_eq!(
+            kind("Don't Stop Believin' - Journey", "Stingray Karaoke"),
+            ResultKind::Karaoke
+        );
+        assert_eq!(kind("Toto - Africa", "Zoom Karaoke Official"), ResultKind::Karaoke);
+    }
+
+    #[test]
+    fn test_instrumental_titles() {
+        assert_eq!(
+            kind("Billie Eilish - bad guy (Instrumental)", "Instrumentals Hub"),
+            ResultKind::Instrumental
+        );
+        assert_eq!(
+            kind("Hotel California - Backing Track for Guitar", "Jam Tracks"),
+            ResultKind::Instrumental
+        );
+        assert_eq!(kind("BTS - Dynamite (Inst.)", "MR Removed"), ResultKind::Instrumental);
+        assert_eq!(kind("LiSA - Gurenge (Off Vocal)", "Anime Tracks"), ResultKind::Instrumental);
+    }
+
+    #[test]
+    fn test_original_titles() {
+        assert_eq!(
+            kind("Rick Astley - Never Gonna Give You Up (Official Music Video)", "Rick Astley"),
+            ResultKind::LikelyOriginal
+        );
+        assert_eq!(
+            kind("Lewis Capaldi - Someone You Loved (Lyrics)", "7clouds"),
+            ResultKind::LikelyOriginal
+        );
+        assert_eq!(kind("Bohemian Rhapsody", "Queen - Topic"), ResultKind::LikelyOriginal);
+        assert_eq!(kind("Hello", "AdeleVEVO"), ResultKind::LikelyOriginal);
+        assert_eq!(
+            kind("Coldplay - Fix You (Live at River Plate)", "Coldplay"),
+            ResultKind::LikelyOriginal
+        );
+        assert_eq!(kind("IU - Blueming MV", "1theK"), ResultKind::LikelyOriginal);
+    }
+
+    #[test]
+    fn test_unknown_and_word_boundaries() {
+        assert_eq!(kind("Journey - Don't Stop Believin'", "Random Uploader"), ResultKind::Unknown);
+        // Keywords only match whole words
+        assert_eq!(kind("Installation guide", "Tech"), ResultKind::Unknown);
+        assert_eq!(kind("Oliver - Delivery", "Band"), ResultKind::Unknown);
+    }
+
+    #[test]
+    fn test_is_karaoke_friendly() {
+        assert!(is_karaoke_friendly(&result("Song (Karaoke)", "Uploader")));
+        assert!(is_karaoke_friendly(&result("Song (Instrumental)", "Uploader")));
+        assert!(is_karaoke_friendly(&result("Song", "Uploader")));
+        assert!(!is_karaoke_friendly(&result("Song (Official Video)", "Uploader")));
+    }
+}
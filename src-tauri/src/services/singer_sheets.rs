@@ -0,0 +1,164 @@
+//! Per-singer "who sang what" sheets for a session, written as Markdown files.
+//!
+//! Each singer gets one file listing the songs they sang (duets appear on every
+//! singer's sheet); songs nobody was assigned to share an "Unassigned" sheet.
+
+use log::info;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// File name (without extension) of the sheet for songs with no singer
+const UNASSIGNED_SHEET_NAME: &str = "Unassigned";
+
+/// Longest file name stem derived from a singer name
+const MAX_FILE_STEM_LENGTH: usize = 64;
+
+/// A song on a singer's sheet
+#[derive(Debug, Clone, PartialEq)]
+pub struct SheetSong {
+    pub title: String,
+    pub artist: Option<String>,
+    /// When the song was played (as stored, UTC)
+    pub played_at: Option<String>,
+}
+
+/// Songs one singer sang in a session, in play order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SingerSheet {
+    /// Singer name, or None for the shared sheet of unassigned songs
+    pub singer_name: Option<String>,
+    pub songs: Vec<SheetSong>,
+}
+
+/// Turn a singer name into a safe file name stem: path separators and other
+/// characters that are special on some file system become `_`.
+pub fn sheet_file_stem(singer_name: &str) -> String {
+    let stem: String = singer_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || matches!(c, ' ' | '-' | '_') { c } else { '_' })
+        .take(MAX_FILE_STEM_LENGTH)
+        .collect();
+    let stem = stem.trim().to_string();
+
+    // Names made only of dots/symbols would otherwise become "_" or hidden files
+    if stem.chars().all(|c| c == '_' || c == ' ') {
+        "Singer".to_string()
+    } else {
+        stem
+    }
+}
+
+/// Render a sheet as Markdown
+pub fn render_sheet(sheet: &SingerSheet, session_name: Option<&str>) -> String {
+    let heading = match &sheet.singer_name {
+        Some(name) => format!("# Songs sung by {}", name),
+        None => "# Songs without a singer".to_string(),
+    };
+
+    let mut out = heading;
+    out.push_str("\n\n");
+    if let Some(session_name) = session_name {
+        out.push_str(&format!("{}\n\n", session_name));
+    }
+    for song in &sheet.songs {
+        out.push_str("- ");
+        if let Some(played_at) = &song.played_at {
+            out.push_str(&format!("{} — ", played_at));
+        }
+        out.push_str(&song.title);
+        if let Some(artist) = &song.artist {
+            out.push_str(&format!(" ({})", artist));
+        }
+        out.push('\n');
+    }
+    out
+}
+
+/// Write one Markdown file per sheet into `output_dir`, which must be an existing
+/// absolute directory. Existing files are never overwritten; a numeric suffix is
+/// added instead. Returns the written file paths in sheet order.
+pub fn write_singer_sheets(
+    sheets: &[SingerSheet],
+    session_name: Option<&str>,
+    output_dir: &Path,
+) -> Result<Vec<PathBuf>, String> {
+    if !output_dir.is_absolute() {
+        return Err("Export directory must be absolute".to_string());
+    }
+    if !output_dir.is_dir() {
+        return Err(format!("Export directory does not exist: {:?}", output_dir));
+    }
+
+    let mut used: HashSet<String> = HashSet::new();
+    let mut written = Vec::with_capacity(sheets.len());
+    for sheet in sheets {
+        let stem = match &sheet.singer_name {
+            Some(name) => sheet_file_stem(name),
+            None => UNASSIGNED_SHEET_NAME.to_string(),
+        };
+
+        let mut file_name = format!("{}.md", stem);
+        let mut suffix = 2;
+        while used.contains(&file_name.to_lowercase()) || output_dir.join(&file_name).exists() {
+            file_name = format!("{} {}.md", stem, suffix);
+            suffix += 1;
+        }
+        used.insert(file_name.to_lowercase());
+
+        let path = output_dir.join(&file_name);
+        fs::write(&path, render_sheet(sheet, session_name))
+            .map_err(|e| format!("Failed to write {:?}: {}", path, e))?;
+        written.push(path);
+    }
+
+    info!("Wrote {} singer sheets to {:?}", written.len(), output_dir);
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sheet_file_stem_escapes_names() {
+        assert_eq!(sheet_file_stem("Alice"), "Alice");
+        assert_eq!(sheet_file_stem("  Bob & Co. "), "Bob _ Co_");
+        assert_eq!(sheet_file_stem("../../etc/passwd"), "______etc_passwd");
+        assert_eq!(sheet_file_stem("Zoë"), "Zoë");
+        assert_eq!(sheet_file_stem("..."), "Singer");
+        assert_eq!(sheet_file_stem(""), "Singer");
+        assert_eq!(sheet_file_stem(&"x".repeat(100)).len(), MAX_FILE_STEM_LENGTH);
+    }
+
+    #[test]
+    fn test_render_sheet() {
+        let sheet = SingerSheet {
+            singer_name: Some("Alice".to_string()),
+            songs: vec![
+                SheetSong {
+                    title: "Dancing Queen".to_string(),
+                    artist: Some("ABBA".to_string()),
+                    played_at: Some("2024-06-01 20:15:00".to_string()),
+                },
+                SheetSong {
+                    title: "Happy Birthday".to_string(),
+                    artist: None,
+                    played_at: None,
+                },
+            ],
+        };
+        assert_eq!(
+            render_sheet(&sheet, Some("Alice's 30th")),
+            "# Songs sung by Alice\n\nAlice's 30th\n\n\
+             - 2024-06-01 20:15:00 — Dancing Queen (ABBA)\n\
+             - Happy Birthday\n"
+        );
+    }
+
+    #[test]
+    fn test_write_singer_sheets_rejects_relative_dir() {
+        assert!(write_singer_sheets(&[], None, Path::new("sheets")).is_err());
+    }
+}
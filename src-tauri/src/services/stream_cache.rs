@@ -0,0 +1,217 @@
+//! In-memory cache of resolved YouTube stream URLs.
+//!
+//! Resolving a stream URL runs yt-dlp (several seconds), but the signed URLs stay valid
+//! for hours. Entries expire shortly before the URL's own `expire` timestamp.
+
+use crate::services::ytdlp::{StreamInfo, YtDlpError, YtDlpService};
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use log::debug;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard};
+
+/// Lifetime for URLs without an `expire` parameter
+const DEFAULT_TTL_SECS: i64 = 60 * 60;
+
+/// Stop using a URL this long before YouTube expires it (a song must finish playing)
+const EXPIRY_MARGIN_SECS: i64 = 15 * 60;
+
+static STREAM_URL_CACHE: Mutex<StreamUrlCache> = Mutex::new(StreamUrlCache {
+    entries: None,
+});
+
+/// A cached stream, without the signed URL itself
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CachedStream {
+    pub video_id: String,
+    /// Requested format, None for the default best-compatible format
+    pub format_id: Option<String>,
+    pub resolved_at: String,
+    pub expires_at: String,
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    info: StreamInfo,
+    resolved_at: DateTime<Utc>,
+    expires_at: DateTime<Utc>,
+}
+
+type CacheKey = (String, Option<String>);
+
+#[derive(Debug, Default)]
+pub struct StreamUrlCache {
+    // Option so the static can be built in a const context
+    entries: Option<HashMap<CacheKey, CacheEntry>>,
+}
+
+impl StreamUrlCache {
+    /// Cached stream info if it hasn't expired
+    pub fn get(&self, video_id: &str, format_id: Option<&str>, now: DateTime<Utc>) -> Option<StreamInfo> {
+        let key = (video_id.to_string(), format_id.map(String::from));
+        self.entries
+            .as_ref()?
+            .get(&key)
+            .filter(|entry| entry.expires_at > now)
+            .map(|entry| entry.info.clone())
+    }
+
+    pub fn insert(&mut self, video_id: &str, format_id: Option<&str>, info: StreamInfo, now: DateTime<Utc>) {
+        let expires_at = cache_expiry(&info.url, now);
+        let entries = self.entries.get_or_insert_with(HashMap::new);
+        entries.retain(|_, entry| entry.expires_at > now);
+        entries.insert(
+            (video_id.to_string(), format_id.map(String::from)),
+            CacheEntry {
+                info,
+                resolved_at: now,
+                expires_at,
+            },
+        );
+    }
+
+    /// Unexpired entries, soonest to expire first
+    pub fn list(&self, now: DateTime<Utc>) -> Vec<CachedStream> {
+        let mut entries: Vec<(&CacheKey, &CacheEntry)> = self
+            .entries
+            .iter()
+            .flatten()
+            .filter(|(_, entry)| entry.expires_at > now)
+            .collect();
+        entries.sort_by(|a, b| a.1.expires_at.cmp(&b.1.expires_at).then_with(|| a.0.cmp(b.0)));
+
+        entries
+            .into_iter()
+            .map(|((video_id, format_id), entry)| CachedStream {
+                video_id: video_id.clone(),
+                format_id: format_id.clone(),
+                resolved_at: entry.resolved_at.to_rfc3339(),
+                expires_at: entry.expires_at.to_rfc3339(),
+            })
+            .collect()
+    }
+
+    /// Remove all entries, returning how many were cached
+    pub fn clear(&mut self) -> usize {
+        self.entries.take().map(|entries| entries.len()).unwrap_or(0)
+    }
+}
+
+/// When to stop using a URL: shortly before its `expire` query parameter, or a default TTL
+fn cache_expiry(url: &str, now: DateTime<Utc>) -> DateTime<Utc> {
+    let default_expiry = now + Duration::seconds(DEFAULT_TTL_SECS);
+    let expire = url::Url::parse(url).ok().and_then(|url| {
+        url.query_pairs()
+            .find(|(key, _)| key == "expire")
+            .and_then(|(_, value)| value.parse::<i64>().ok())
+            .and_then(|secs| Utc.timestamp_opt(secs, 0).single())
+    });
+
+    match expire {
+        Some(expire) => expire - Duration::seconds(EXPIRY_MARGIN_SECS),
+        None => default_expiry,
+    }
+}
+
+fn lock_cache() -> MutexGuard<'static, StreamUrlCache> {
+    STREAM_URL_CACHE.lock().unwrap_or_else(|poisoned| {
+        STREAM_URL_CACHE.clear_poison();
+        poisoned.into_inner()
+    })
+}
+
+/// Get a stream URL, resolving it with yt-dlp only if there's no unexpired cached one
+pub async fn cached_stream_url(
+    service: &YtDlpService,
+    video_id: &str,
+    format_id: Option<&str>,
+) -> Result<StreamInfo, YtDlpError> {
+    let cached = lock_cache().get(video_id, format_id, Utc::now());
+    if let Some(info) = cached {
+        debug!("Using cached stream URL for {} (format: {:?})", video_id, format_id);
+        return Ok(info);
+    }
+
+    let info = service.get_stream_url(video_id, format_id).await?;
+    lock_cache().insert(video_id, format_id, info.clone(), Utc::now());
+    Ok(info)
+}
+
+/// List unexpired cached streams
+pub fn cached_streams() -> Vec<CachedStream> {
+    lock_cache().list(Utc::now())
+}
+
+/// Drop all cached stream URLs so the next request re-resolves them
+pub fn clear_stream_cache() -> usize {
+    lock_cache().clear()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream(url: &str) -> StreamInfo {
+        StreamInfo {
+            url: url.to_string(),
+            format: "mp4".to_string(),
+            quality: "best".to_string(),
+        }
+    }
+
+    fn at(secs: i64) -> DateTime<Utc> {
+        Utc.timestamp_opt(secs, 0).single().unwrap()
+    }
+
+    #[test]
+    fn test_cache_expiry_uses_url_expire_with_margin() {
+        let now = at(1_700_000_000);
+        let url = format!(
+            "https://rr1---sn-abc.googlevideo.com/videoplayback?expire={}&sig=secret",
+            1_700_000_000 + 6 * 60 * 60
+        );
+        assert_eq!(
+            cache_expiry(&url, now),
+            at(1_700_000_000 + 6 * 60 * 60 - EXPIRY_MARGIN_SECS)
+        );
+
+        // No (or invalid) expire parameter falls back to the default TTL
+        assert_eq!(
+            cache_expiry("https://example.com/video.mp4", now),
+            at(1_700_000_000 + DEFAULT_TTL_SECS)
+        );
+        assert_eq!(
+            cache_expiry("https://example.com/video.mp4?expire=soon", now),
+            at(1_700_000_000 + DEFAULT_TTL_SECS)
+        );
+    }
+
+    #[test]
+    fn test_get_returns_entry_until_expiry() {
+        let now = at(1_700_000_000);
+        let mut cache = StreamUrlCache::default();
+        cache.insert("abc123def45", None, stream("https://example.com/a.mp4"), now);
+
+        assert!(cache.get("abc123def45", None, now).is_some());
+        // Different format is a different entry
+        assert!(cache.get("abc123def45", Some("22"), now).is_none());
+        assert!(cache.get("abc123def45", None, now + Duration::seconds(DEFAULT_TTL_SECS)).is_none());
+    }
+
+    #[test]
+    fn test_list_hides_urls_and_clear_empties() {
+        let now = at(1_700_000_000);
+        let mut cache = StreamUrlCache::default();
+        cache.insert("abc123def45", Some("22"), stream("https://example.com/a.mp4?sig=secret"), now);
+
+        let listed = cache.list(now);
+        assert_eq!(listed.len(), 1);
+        assert_eq!(listed[0].video_id, "abc123def45");
+        assert_eq!(listed[0].format_id.as_deref(), Some("22"));
+        assert!(!serde_json::to_string(&listed).unwrap().contains("secret"));
+
+        assert_eq!(cache.clear(), 1);
+        assert!(cache.list(now).is_empty());
+        assert_eq!(cache.clear(), 0);
+    }
+}
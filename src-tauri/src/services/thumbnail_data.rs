@@ -0,0 +1,253 @@
+//! Thumbnails as `data:` URLs, so the UI renders local and YouTube thumbnails the
+//! same way. YouTube thumbnails are cached on disk and keep working offline.
+
+use crate::services::http_client::build_client;
+use crate::services::library_scanner::LibraryFolder;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+use log::{debug, warn};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Largest thumbnail returned or downloaded (hqdefault.jpg is ~30 KB)
+pub const MAX_THUMBNAIL_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Timeout for downloading a YouTube thumbnail
+const DOWNLOAD_TIMEOUT_SECS: u64 = 15;
+
+/// The only host YouTube thumbnails are downloaded from
+const YOUTUBE_THUMBNAIL_HOST: &str = "i.ytimg.com";
+
+/// Suffix of thumbnails generated during library scans
+const LOCAL_THUMBNAIL_SUFFIX: &str = ".thumb.jpg";
+
+/// MIME type of an image from its magic bytes, None if it isn't a supported image
+fn image_mime_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0xff, 0xd8, 0xff]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else {
+        None
+    }
+}
+
+/// Encode image bytes as a `data:` URL. Fails for anything that isn't a JPEG,
+/// PNG or WebP image, so arbitrary files never reach the webview.
+pub fn to_data_url(bytes: &[u8]) -> Result<String, String> {
+    let mime = image_mime_type(bytes).ok_or("Thumbnail is not a supported image")?;
+    Ok(format!("data:{};base64,{}", mime, BASE64.encode(bytes)))
+}
+
+/// Read a file of at most `MAX_THUMBNAIL_BYTES`, or None if it doesn't exist
+fn read_capped(path: &Path) -> Result<Option<Vec<u8>>, String> {
+    let size = match fs::metadata(path) {
+        Ok(metadata) if metadata.is_file() => metadata.len(),
+        _ => return Ok(None),
+    };
+    if size > MAX_THUMBNAIL_BYTES {
+        return Err(format!(
+            "Thumbnail is too large ({} bytes, max {})",
+            size, MAX_THUMBNAIL_BYTES
+        ));
+    }
+    fs::read(path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read thumbnail {:?}: {}", path, e))
+}
+
+/// Read a generated `.thumb.jpg` thumbnail. Returns None if the file is missing, and
+/// an error for files that aren't thumbnails or are outside every library folder.
+pub fn read_local_thumbnail(folders: &[LibraryFolder], path: &str) -> Result<Option<Vec<u8>>, String> {
+    if !path.ends_with(LOCAL_THUMBNAIL_SUFFIX) {
+        return Err(format!("Not a library thumbnail: {}", path));
+    }
+    let Ok(canonical) = Path::new(path).canonicalize() else {
+        return Ok(None);
+    };
+
+    let inside_library = folders.iter().any(|folder| {
+        Path::new(&folder.path)
+            .canonicalize()
+            .is_ok_and(|root| canonical.starts_with(root))
+    });
+    if !inside_library {
+        return Err(format!("Thumbnail is outside the library folders: {}", path));
+    }
+    read_capped(&canonical)
+}
+
+/// Cache file name for a YouTube thumbnail URL such as
+/// `https://i.ytimg.com/vi/<id>/hqdefault.jpg`, None for any other URL
+pub fn youtube_cache_file_name(thumbnail_url: &str) -> Option<String> {
+    let url = url::Url::parse(thumbnail_url).ok()?;
+    if url.scheme() != "https" || url.host_str() != Some(YOUTUBE_THUMBNAIL_HOST) {
+        return None;
+    }
+
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    let [prefix, video_id, file_name] = segments.as_slice() else {
+        return None;
+    };
+    let safe = |s: &str| {
+        !s.is_empty() && s.len() <= 32 && s.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    let stem = file_name.strip_suffix(".jpg")?;
+    (*prefix == "vi" && safe(video_id) && safe(stem)).then(|| format!("{}_{}.jpg", video_id, stem))
+}
+
+/// Download a thumbnail, giving up once it exceeds `MAX_THUMBNAIL_BYTES`
+async fn download_thumbnail(thumbnail_url: &str) -> Result<Vec<u8>, String> {
+    let client = build_client(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))?;
+    let mut response = client
+        .get(thumbnail_url)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| format!("Failed to download thumbnail: {}", e))?;
+
+    let too_large = || format!("Thumbnail is larger than {} bytes", MAX_THUMBNAIL_BYTES);
+    if response.content_length().is_some_and(|len| len > MAX_THUMBNAIL_BYTES) {
+        return Err(too_large());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response
+        .chunk()
+        .await
+        .map_err(|e| format!("Failed to download thumbnail: {}", e))?
+    {
+        bytes.extend_from_slice(&chunk);
+        if bytes.len() as u64 > MAX_THUMBNAIL_BYTES {
+            return Err(too_large());
+        }
+    }
+    Ok(bytes)
+}
+
+/// Get a YouTube thumbnail from the disk cache in `cache_dir`, downloading and
+/// caching it first when `download` is set. Returns None when it isn't cached and
+/// can't (or mustn't) be downloaded, e.g. while offline.
+pub async fn youtube_thumbnail(
+    cache_dir: &Path,
+    thumbnail_url: &str,
+    download: bool,
+) -> Result<Option<Vec<u8>>, String> {
+    let file_name = youtube_cache_file_name(thumbnail_url)
+        .ok_or_else(|| format!("Not a YouTube thumbnail URL: {}", thumbnail_url))?;
+    let cache_path: PathBuf = cache_dir.join(file_name);
+
+    if let Some(bytes) = read_capped(&cache_path)? {
+        debug!("Thumbnail cache hit: {:?}", cache_path);
+        return Ok(Some(bytes));
+    }
+    if !download {
+        return Ok(None);
+    }
+
+    let bytes = match download_thumbnail(thumbnail_url).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            warn!("{}", e);
+            return Ok(None);
+        }
+    };
+    if image_mime_type(&bytes).is_none() {
+        return Err("Downloaded thumbnail is not a supported image".to_string());
+    }
+
+    // A failed cache write only costs a download next time
+    if let Err(e) = fs::create_dir_all(cache_dir).and_then(|_| fs::write(&cache_path, &bytes)) {
+        warn!("Failed to cache thumbnail {:?}: {}", cache_path, e);
+    }
+    Ok(Some(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::scratch_dir;
+
+    const JPEG: &[u8] = &[0xff, 0xd8, 0xff, 0xe0, 0x00, 0x10];
+
+    fn folder(path: &Path) -> LibraryFolder {
+        LibraryFolder {
+            id: 1,
+            path: path.to_string_lossy().to_string(),
+            name: "Music".to_string(),
+            last_scan_at: None,
+            file_count: 0,
+            filename_pattern: None,
+        }
+    }
+
+    #[test]
+    fn test_to_data_url() {
+        assert_eq!(to_data_url(JPEG).unwrap(), "data:image/jpeg;base64,/9j/4AAQ");
+        assert!(to_data_url(b"\x89PNG\r\n\x1a\nrest").unwrap().starts_with("data:image/png;"));
+        assert!(to_data_url(b"#!/bin/sh\n").is_err());
+    }
+
+    #[test]
+    fn test_youtube_cache_file_name() {
+        assert_eq!(
+            youtube_cache_file_name("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg").as_deref(),
+            Some("dQw4w9WgXcQ_hqdefault.jpg")
+        );
+        assert_eq!(youtube_cache_file_name("http://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"), None);
+        assert_eq!(youtube_cache_file_name("https://evil.example/vi/dQw4w9WgXcQ/hqdefault.jpg"), None);
+        assert_eq!(youtube_cache_file_name("https://i.ytimg.com/vi/../hqdefault.jpg"), None);
+        assert_eq!(youtube_cache_file_name("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.png"), None);
+    }
+
+    #[test]
+    fn test_read_local_thumbnail() {
+        let library = scratch_dir("thumbnail-library");
+        let outside = scratch_dir("thumbnail-outside");
+        let folders = [folder(&library)];
+
+        let thumb = library.join("Song.thumb.jpg");
+        fs::write(&thumb, JPEG).unwrap();
+        assert_eq!(
+            read_local_thumbnail(&folders, &thumb.to_string_lossy()).unwrap().as_deref(),
+            Some(JPEG)
+        );
+
+        // Missing files are not an error
+        let missing = library.join("Missing.thumb.jpg");
+        assert_eq!(read_local_thumbnail(&folders, &missing.to_string_lossy()).unwrap(), None);
+
+        // Only thumbnails inside the library can be read
+        let stray = outside.join("Song.thumb.jpg");
+        fs::write(&stray, JPEG).unwrap();
+        assert!(read_local_thumbnail(&folders, &stray.to_string_lossy()).is_err());
+        let video = library.join("Song.mp4");
+        fs::write(&video, "video").unwrap();
+        assert!(read_local_thumbnail(&folders, &video.to_string_lossy()).is_err());
+
+        // Oversized files are rejected
+        let huge = library.join("Huge.thumb.jpg");
+        fs::write(&huge, vec![0u8; MAX_THUMBNAIL_BYTES as usize + 1]).unwrap();
+        assert!(read_local_thumbnail(&folders, &huge.to_string_lossy()).is_err());
+
+        let _ = fs::remove_dir_all(&library);
+        let _ = fs::remove_dir_all(&outside);
+    }
+
+    #[tokio::test]
+    async fn test_youtube_thumbnail_uses_cache() {
+        let cache = scratch_dir("thumbnail-cache");
+        let url = "https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg";
+
+        // Not cached and downloading disabled: no thumbnail, no network access
+        assert_eq!(youtube_thumbnail(&cache, url, false).await.unwrap(), None);
+
+        fs::write(cache.join("dQw4w9WgXcQ_hqdefault.jpg"), JPEG).unwrap();
+        assert_eq!(youtube_thumbnail(&cache, url, false).await.unwrap().as_deref(), Some(JPEG));
+
+        assert!(youtube_thumbnail(&cache, "https://example.com/a.jpg", true).await.is_err());
+        let _ = fs::remove_dir_all(&cache);
+    }
+}
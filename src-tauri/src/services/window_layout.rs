@@ -0,0 +1,241 @@
+//! Automatic window layout persistence (macOS only)
+//!
+//! When the `remember_window_layout` setting is enabled, window geometry is saved
+//! for the current display configuration whenever a window is moved or resized
+//! (debounced) and on exit. The frontend restores the saved layout on startup
+//! through the existing display configuration flow, so nothing happens on first
+//! run until a layout has been saved.
+
+use crate::services::get_display_configuration;
+use crate::AppState;
+use log::{debug, warn};
+use rusqlite::Connection;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+/// Settings key enabling automatic layout saving ("true" | "false")
+pub const REMEMBER_WINDOW_LAYOUT_KEY: &str = "remember_window_layout";
+
+/// Delay after the last move/resize before saving
+const SAVE_DEBOUNCE: Duration = Duration::from_millis(750);
+
+/// Tracked windows as (window label, window_type in window_state)
+const TRACKED_WINDOWS: &[(&str, &str)] = &[("main", "main"), ("player", "video")];
+
+/// Position and size of a window in physical pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct WindowGeometry {
+    x: i32,
+    y: i32,
+    width: i32,
+    height: i32,
+    is_fullscreen: bool,
+}
+
+/// Find or create the display config row for a hash, returning its id
+fn ensure_display_config(
+    conn: &Connection,
+    config_hash: &str,
+    display_names: &[String],
+) -> rusqlite::Result<i64> {
+    let names_json = serde_json::to_string(display_names).unwrap_or_else(|_| "[]".to_string());
+    conn.execute(
+        "INSERT OR IGNORE INTO display_configs (config_hash, display_names) VALUES (?1, ?2)",
+        rusqlite::params![config_hash, names_json],
+    )?;
+    conn.query_row(
+        "SELECT id FROM display_configs WHERE config_hash = ?1",
+        [config_hash],
+        |row| row.get(0),
+    )
+}
+
+/// Save (or replace) a window's geometry for a display config
+fn save_window_geometry(
+    conn: &Connection,
+    display_config_id: i64,
+    window_type: &str,
+    geometry: WindowGeometry,
+    is_detached: bool,
+) -> rusqlite::Result<()> {
+    conn.execute(
+        "INSERT OR REPLACE INTO window_state
+             (display_config_id, window_type, target_display_id, x, y, width, height, is_detached, is_fullscreen, updated_at)
+             VALUES (?1, ?2, NULL, ?3, ?4, ?5, ?6, ?7, ?8, CURRENT_TIMESTAMP)",
+        rusqlite::params![
+            display_config_id,
+            window_type,
+            geometry.x,
+            geometry.y,
+            geometry.width,
+            geometry.height,
+            is_detached as i32,
+            geometry.is_fullscreen as i32
+        ],
+    )?;
+    Ok(())
+}
+
+/// Read the current geometry of a window (None if minimized or unavailable)
+fn window_geometry(window: &tauri::WebviewWindow) -> Option<WindowGeometry> {
+    if window.is_minimized().unwrap_or(false) {
+        return None;
+    }
+    let position = window.outer_position().ok()?;
+    let size = window.inner_size().ok()?;
+    Some(WindowGeometry {
+        x: position.x,
+        y: position.y,
+        width: size.width as i32,
+        height: size.height as i32,
+        is_fullscreen: window.is_fullscreen().unwrap_or(false),
+    })
+}
+
+fn is_enabled(app: &AppHandle) -> bool {
+    let state = app.state::<AppState>();
+    let db = match state.db.lock() {
+        Ok(db) => db,
+        Err(e) => {
+            warn!("Failed to lock database for window layout: {}", e);
+            return false;
+        }
+    };
+    matches!(db.get_setting(REMEMBER_WINDOW_LAYOUT_KEY), Ok(Some(v)) if v == "true")
+}
+
+/// Save the geometry of all tracked windows for the current display configuration.
+/// Does nothing unless `remember_window_layout` is enabled.
+pub fn save_current_layout(app: &AppHandle) {
+    if !is_enabled(app) {
+        return;
+    }
+
+    let config = match get_display_configuration() {
+        Ok(config) => config,
+        Err(e) => {
+            warn!("Skipping window layout save, display configuration unavailable: {}", e);
+            return;
+        }
+    };
+
+    // Read window geometry before taking the database lock; window getters
+    // dispatch to the main thread
+    let geometries: Vec<(&str, WindowGeometry)> = TRACKED_WINDOWS
+        .iter()
+        .filter_map(|(label, window_type)| {
+            let window = app.get_webview_window(label)?;
+            window_geometry(&window).map(|g| (*window_type, g))
+        })
+        .collect();
+    if geometries.is_empty() {
+        debug!("No windows available, skipping window layout save");
+        return;
+    }
+    let player_detached = geometries.iter().any(|(window_type, _)| *window_type == "video");
+
+    let state = app.state::<AppState>();
+    let db = match state.db.lock() {
+        Ok(db) => db,
+        Err(e) => {
+            warn!("Failed to lock database for window layout: {}", e);
+            return;
+        }
+    };
+    let conn = db.connection();
+
+    let display_names: Vec<String> = config.displays.iter().map(|d| d.name.clone()).collect();
+    let result = (|| -> rusqlite::Result<()> {
+        let config_id = ensure_display_config(conn, &config.config_hash, &display_names)?;
+        for (window_type, geometry) in &geometries {
+            save_window_geometry(conn, config_id, window_type, *geometry, *window_type == "video")?;
+        }
+        if !player_detached {
+            // Player is attached; keep its last detached geometry but restore it attached
+            conn.execute(
+                "UPDATE window_state SET is_detached = 0 WHERE display_config_id = ?1 AND window_type = 'video'",
+                [config_id],
+            )?;
+        }
+        Ok(())
+    })();
+
+    match result {
+        Ok(()) => debug!(
+            "Saved window layout ({} windows) for config {}",
+            geometries.len(),
+            &config.config_hash[..8.min(config.config_hash.len())]
+        ),
+        Err(e) => warn!("Failed to save window layout: {}", e),
+    }
+}
+
+/// Save the layout after windows stop moving/resizing for `SAVE_DEBOUNCE`
+pub fn schedule_layout_save(app: &AppHandle) {
+    let state = app.state::<AppState>();
+    let generation = state.window_layout_generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SAVE_DEBOUNCE).await;
+        let state = app.state::<AppState>();
+        if state.window_layout_generation.load(Ordering::SeqCst) == generation {
+            save_current_layout(&app);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::run_migrations;
+
+    fn setup_test_db() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        run_migrations(&conn).unwrap();
+        conn
+    }
+
+    fn geometry(x: i32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y: 50,
+            width: 1200,
+            height: 800,
+            is_fullscreen: false,
+        }
+    }
+
+    #[test]
+    fn test_ensure_display_config_reuses_existing_row() {
+        let conn = setup_test_db();
+        let names = vec!["Built-in Display".to_string()];
+
+        let first = ensure_display_config(&conn, "abc123", &names).unwrap();
+        let second = ensure_display_config(&conn, "abc123", &names).unwrap();
+        assert_eq!(first, second);
+
+        let other = ensure_display_config(&conn, "def456", &names).unwrap();
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn test_save_window_geometry_replaces_previous() {
+        let conn = setup_test_db();
+        let config_id = ensure_display_config(&conn, "abc123", &[]).unwrap();
+
+        save_window_geometry(&conn, config_id, "main", geometry(10), false).unwrap();
+        save_window_geometry(&conn, config_id, "main", geometry(20), false).unwrap();
+
+        let (count, x): (i32, i32) = conn
+            .query_row(
+                "SELECT COUNT(*), MAX(x) FROM window_state WHERE display_config_id = ?1",
+                [config_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(count, 1);
+        assert_eq!(x, 20);
+    }
+}
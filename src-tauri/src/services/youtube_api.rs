@@ -3,21 +3,19 @@
 //! Provides YouTube search functionality using the official API,
 //! which requires a user-provided API key but doesn't need yt-dlp.
 
-use crate::services::ytdlp::SearchResult;
+use crate::services::http_client;
+use crate::services::ytdlp::{SearchResult, VideoInfo};
 use log::{debug, info, warn};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::time::Duration;
 use thiserror::Error;
 
 /// YouTube Data API v3 base URL
 const YOUTUBE_API_BASE: &str = "https://www.googleapis.com/youtube/v3";
 
-/// User agent for API requests
-const USER_AGENT: &str = concat!(
-    "HomeKaraoke/",
-    env!("CARGO_PKG_VERSION"),
-    " (+https://github.com/zalun/karaoke-app)"
-);
+/// Maximum number of video IDs accepted by a single videos.list request
+pub const MAX_VIDEO_IDS_PER_REQUEST: usize = 50;
 
 /// Errors that can occur when using the YouTube Data API
 #[derive(Error, Debug)]
@@ -46,7 +44,6 @@ pub enum YouTubeApiError {
 #[serde(rename_all = "camelCase")]
 struct SearchResponse {
     items: Option<Vec<SearchItem>>,
-    #[allow(dead_code)]
     next_page_token: Option<String>,
     error: Option<ApiError>,
 }
@@ -64,6 +61,7 @@ struct VideosResponse {
 #[serde(rename_all = "camelCase")]
 struct VideoItem {
     id: String,
+    snippet: Option<Snippet>,
     content_details: Option<ContentDetails>,
 }
 
@@ -111,6 +109,7 @@ struct VideoId {
 struct Snippet {
     title: String,
     channel_title: String,
+    description: Option<String>,
     thumbnails: Thumbnails,
 }
 
@@ -122,6 +121,13 @@ struct Thumbnails {
     default: Option<ThumbnailInfo>,
 }
 
+impl Thumbnails {
+    /// URL of the best available thumbnail
+    fn best(self) -> Option<String> {
+        self.high.or(self.medium).or(self.default).map(|t| t.url)
+    }
+}
+
 /// Single thumbnail info
 #[derive(Debug, Deserialize)]
 struct ThumbnailInfo {
@@ -141,11 +147,7 @@ impl YouTubeApiService {
             return Err("API key cannot be empty".to_string());
         }
 
-        let client = reqwest::Client::builder()
-            .user_agent(USER_AGENT)
-            .timeout(Duration::from_secs(15))
-            .build()
-            .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+        let client = http_client::build_client(Duration::from_secs(15))?;
 
         Ok(Self { client, api_key })
     }
@@ -159,8 +161,23 @@ impl YouTubeApiService {
         query: &str,
         max_results: u32,
     ) -> Result<Vec<SearchResult>, YouTubeApiError> {
+        self.search_page(query, max_results, None)
+            .await
+            .map(|(results, _)| results)
+    }
+
+    /// Search for a page of videos on YouTube
+    ///
+    /// Like `search`, but accepts a `page_token` from a previous response and
+    /// returns the token for the next page (None when there are no more results).
+    pub async fn search_page(
+        &self,
+        query: &str,
+        max_results: u32,
+        page_token: Option<&str>,
+    ) -> Result<(Vec<SearchResult>, Option<String>), YouTubeApiError> {
         if query.trim().is_empty() {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), None));
         }
 
         let max_results = max_results.min(50); // API limit
@@ -171,7 +188,7 @@ impl YouTubeApiService {
         );
 
         // Use query builder to avoid API key appearing in debug logs
-        let response = self
+        let mut request = self
             .client
             .get(format!("{}/search", YOUTUBE_API_BASE))
             .query(&[
@@ -180,7 +197,11 @@ impl YouTubeApiService {
                 ("q", query),
                 ("maxResults", &max_results.to_string()),
                 ("key", &self.api_key),
-            ])
+            ]);
+        if let Some(token) = page_token {
+            request = request.query(&[("pageToken", token)]);
+        }
+        let response = request
             .send()
             .await
             .map_err(|e| YouTubeApiError::Network(e.to_string()))?;
@@ -227,10 +248,11 @@ impl YouTubeApiService {
             return Err(Self::classify_error(&error));
         }
 
+        let next_page_token = body.next_page_token;
         let items = body.items.unwrap_or_default();
 
         if items.is_empty() {
-            return Ok(Vec::new());
+            return Ok((Vec::new(), None));
         }
 
         // Convert to SearchResult format (compatible with yt-dlp results)
@@ -240,13 +262,7 @@ impl YouTubeApiService {
                 let video_id = item.id.video_id?;
 
                 // Get best available thumbnail
-                let thumbnail = item
-                    .snippet
-                    .thumbnails
-                    .high
-                    .or(item.snippet.thumbnails.medium)
-                    .or(item.snippet.thumbnails.default)
-                    .map(|t| t.url);
+                let thumbnail = item.snippet.thumbnails.best();
 
                 Some(SearchResult {
                     id: video_id,
@@ -280,7 +296,7 @@ impl YouTubeApiService {
                             r
                         })
                         .collect();
-                    return Ok(results_with_duration);
+                    return Ok((results_with_duration, next_page_token));
                 }
                 Err(e) => {
                     // Log but don't fail - duration is optional
@@ -289,18 +305,16 @@ impl YouTubeApiService {
             }
         }
 
-        Ok(results)
+        Ok((results, next_page_token))
     }
 
-    /// Fetch durations for multiple videos in a single API call
+    /// Fetch durations for multiple videos in a single API call (at most 50 IDs)
     ///
     /// Returns a map of video_id -> duration_seconds
-    async fn fetch_video_durations(
+    pub async fn fetch_video_durations(
         &self,
         video_ids: &[&str],
-    ) -> Result<std::collections::HashMap<String, u64>, YouTubeApiError> {
-        use std::collections::HashMap;
-
+    ) -> Result<HashMap<String, u64>, YouTubeApiError> {
         if video_ids.is_empty() {
             return Ok(HashMap::new());
         }
@@ -356,6 +370,80 @@ impl YouTubeApiService {
         Ok(durations)
     }
 
+    /// Fetch title, channel, duration and thumbnail for multiple videos in a single
+    /// API call (at most [`MAX_VIDEO_IDS_PER_REQUEST`] IDs)
+    ///
+    /// Returns a map of video_id -> info; unavailable videos are missing from the map
+    pub async fn fetch_video_infos(
+        &self,
+        video_ids: &[&str],
+    ) -> Result<HashMap<String, VideoInfo>, YouTubeApiError> {
+        if video_ids.is_empty() {
+            return Ok(HashMap::new());
+        }
+
+        let ids = video_ids.join(",");
+
+        debug!("Fetching info for {} videos", video_ids.len());
+
+        // Use query builder to avoid API key appearing in debug logs
+        let response = self
+            .client
+            .get(format!("{}/videos", YOUTUBE_API_BASE))
+            .query(&[
+                ("part", "snippet,contentDetails"),
+                ("id", &ids),
+                ("key", &self.api_key),
+            ])
+            .send()
+            .await
+            .map_err(|e| YouTubeApiError::Network(e.to_string()))?;
+
+        if !response.status().is_success() {
+            return Err(YouTubeApiError::Network(format!(
+                "Videos API returned status {}",
+                response.status()
+            )));
+        }
+
+        let body: VideosResponse = response
+            .json()
+            .await
+            .map_err(|e| YouTubeApiError::Parse(e.to_string()))?;
+
+        if let Some(error) = body.error {
+            return Err(Self::classify_error(&error));
+        }
+
+        let infos: HashMap<String, VideoInfo> = body
+            .items
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|item| {
+                let snippet = item.snippet?;
+                let duration = item
+                    .content_details
+                    .and_then(|details| details.duration)
+                    .and_then(|duration| Self::parse_iso8601_duration(&duration));
+
+                Some((
+                    item.id.clone(),
+                    VideoInfo {
+                        id: item.id,
+                        title: snippet.title,
+                        channel: snippet.channel_title,
+                        duration,
+                        thumbnail: snippet.thumbnails.best(),
+                        description: snippet.description,
+                    },
+                ))
+            })
+            .collect();
+
+        debug!("Fetched info for {} videos", infos.len());
+        Ok(infos)
+    }
+
     /// Parse ISO 8601 duration format (e.g., "PT4M13S") to seconds
     fn parse_iso8601_duration(duration: &str) -> Option<u64> {
         // Format: PT#H#M#S (hours, minutes, seconds are optional)
@@ -463,10 +551,87 @@ impl YouTubeApiService {
     }
 }
 
+/// Length of a canonical YouTube video ID
+const VIDEO_ID_LENGTH: usize = 11;
+
+fn is_canonical_video_id(id: &str) -> bool {
+    id.len() == VIDEO_ID_LENGTH
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Extract the canonical 11-character video ID from a YouTube URL or ID.
+///
+/// Accepts bare IDs (optionally with trailing URL params, e.g. `dQw4w9WgXcQ&t=30s`),
+/// `youtube.com/watch?v=`, `youtu.be/`, `/embed/`, `/shorts/` and `/live/` URLs.
+/// Returns None if no valid ID can be found.
+pub fn canonical_video_id(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    // Bare IDs never contain a slash
+    if !input.contains('/') {
+        let id = input.split(['?', '&', '#']).next()?;
+        return is_canonical_video_id(id).then(|| id.to_string());
+    }
+
+    let with_scheme = if input.contains("://") {
+        input.to_string()
+    } else {
+        format!("https://{}", input)
+    };
+    let url = url::Url::parse(&with_scheme).ok()?;
+    let host = url.host_str()?;
+    let host = host
+        .strip_prefix("www.")
+        .or_else(|| host.strip_prefix("m."))
+        .or_else(|| host.strip_prefix("music."))
+        .unwrap_or(host);
+
+    let id = match host {
+        "youtu.be" => url.path_segments()?.next()?.to_string(),
+        "youtube.com" | "youtube-nocookie.com" => {
+            if let Some((_, v)) = url.query_pairs().find(|(k, _)| k == "v") {
+                v.into_owned()
+            } else {
+                let mut segments = url.path_segments()?;
+                match segments.next()? {
+                    "embed" | "shorts" | "live" | "v" => segments.next()?.to_string(),
+                    _ => return None,
+                }
+            }
+        }
+        _ => return None,
+    };
+
+    is_canonical_video_id(&id).then_some(id)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_canonical_video_id_from_bare_ids() {
+        assert_eq!(canonical_video_id("dQw4w9WgXcQ"), Some("dQw4w9WgXcQ".to_string()));
+        assert_eq!(canonical_video_id(" dQw4w9WgXcQ&t=30s"), Some("dQw4w9WgXcQ".to_string()));
+        assert_eq!(canonical_video_id("dQw4w9WgXcQ?si=abc"), Some("dQw4w9WgXcQ".to_string()));
+        assert_eq!(canonical_video_id("short"), None);
+        assert_eq!(canonical_video_id(""), None);
+    }
+
+    #[test]
+    fn test_canonical_video_id_from_urls() {
+        let expected = Some("dQw4w9WgXcQ".to_string());
+        assert_eq!(canonical_video_id("https://www.youtube.com/watch?v=dQw4w9WgXcQ&list=PL1"), expected);
+        assert_eq!(canonical_video_id("https://m.youtube.com/watch?feature=share&v=dQw4w9WgXcQ"), expected);
+        assert_eq!(canonical_video_id("https://youtu.be/dQw4w9WgXcQ?t=42"), expected);
+        assert_eq!(canonical_video_id("youtube.com/shorts/dQw4w9WgXcQ"), expected);
+        assert_eq!(canonical_video_id("https://www.youtube.com/embed/dQw4w9WgXcQ"), expected);
+        assert_eq!(canonical_video_id("https://example.com/watch?v=dQw4w9WgXcQ"), None);
+        assert_eq!(canonical_video_id("https://www.youtube.com/channel/UC123"), None);
+    }
+
     #[test]
     fn test_new_rejects_empty_key() {
         assert!(YouTubeApiService::new("".to_string()).is_err());
@@ -1,7 +1,7 @@
 use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use thiserror::Error;
 use tokio::process::Command;
 
@@ -95,6 +95,25 @@ pub fn get_expanded_path() -> String {
     paths.join(PATH_SEPARATOR)
 }
 
+/// A directory searched for CLI tools, and whether it exists on this machine
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BinPathStatus {
+    pub path: String,
+    pub exists: bool,
+}
+
+/// Directories searched for CLI tools (besides PATH), in search order
+pub fn search_bin_paths() -> Vec<BinPathStatus> {
+    get_local_bin_path()
+        .into_iter()
+        .chain(COMMON_BIN_PATHS.iter().map(|path| path.to_string()))
+        .map(|path| BinPathStatus {
+            exists: Path::new(&path).is_dir(),
+            path,
+        })
+        .collect()
+}
+
 /// Check if a path exists and is executable
 fn is_executable(path: &PathBuf) -> bool {
     if !path.exists() {
@@ -174,6 +193,54 @@ pub enum YtDlpError {
     NoResults,
 }
 
+/// Likely cause of a failed yt-dlp run, inferred from its stderr
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum YtDlpFailureKind {
+    /// YouTube changed and the installed yt-dlp can't extract it any more
+    NeedsUpdate,
+    /// Connectivity problem (DNS, timeouts, refused connections, 5xx)
+    Network,
+    Other,
+}
+
+/// Network failure patterns in yt-dlp stderr (lowercase)
+const NETWORK_ERROR_PATTERNS: &[&str] = &[
+    "unable to download webpage",
+    "urlopen error",
+    "timed out",
+    "name or service not known",
+    "temporary failure in name resolution",
+    "nodename nor servname",
+    "getaddrinfo failed",
+    "connection refused",
+    "connection reset",
+    "network is unreachable",
+    "http error 5",
+];
+
+/// Extractor breakage patterns in yt-dlp stderr (lowercase)
+const NEEDS_UPDATE_PATTERNS: &[&str] = &[
+    "unable to extract",
+    "extractor error",
+    "signature",
+    "nsig",
+    "please report this issue",
+    "update to the latest version",
+];
+
+/// Classify yt-dlp stderr. Network patterns win, since yt-dlp suggests updating
+/// on many failures that are really connectivity problems.
+pub fn classify_stderr(stderr: &str) -> YtDlpFailureKind {
+    let stderr = stderr.to_lowercase();
+    if NETWORK_ERROR_PATTERNS.iter().any(|p| stderr.contains(p)) {
+        YtDlpFailureKind::Network
+    } else if NEEDS_UPDATE_PATTERNS.iter().any(|p| stderr.contains(p)) {
+        YtDlpFailureKind::NeedsUpdate
+    } else {
+        YtDlpFailureKind::Other
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchResult {
     pub id: String,
@@ -201,6 +268,104 @@ pub struct StreamInfo {
     pub quality: String,
 }
 
+/// A single playable stream format offered by YouTube
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StreamFormat {
+    /// yt-dlp format ID, passed back to `get_stream_url` to select this format
+    pub format_id: String,
+    pub ext: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub fps: Option<f64>,
+    /// Exact size in bytes if known, otherwise yt-dlp's estimate
+    pub filesize: Option<u64>,
+}
+
+/// Extract formats the player can use from yt-dlp's `--dump-json` output.
+/// Only progressive (audio + video) non-HLS formats are kept, highest resolution first.
+fn parse_stream_formats(info: &serde_json::Value) -> Vec<StreamFormat> {
+    let has_codec = |f: &serde_json::Value, key: &str| {
+        f[key].as_str().map(|c| c != "none").unwrap_or(false)
+    };
+
+    let mut formats: Vec<StreamFormat> = info["formats"]
+        .as_array()
+        .map(|formats| formats.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter(|f| has_codec(*f, "vcodec") && has_codec(*f, "acodec"))
+        .filter(|f| {
+            !f["protocol"]
+                .as_str()
+                .map(|p| p.contains("m3u8"))
+                .unwrap_or(false)
+        })
+        .filter_map(|f| {
+            Some(StreamFormat {
+                format_id: f["format_id"].as_str()?.to_string(),
+                ext: f["ext"].as_str().unwrap_or("unknown").to_string(),
+                width: f["width"].as_u64().map(|w| w as u32),
+                height: f["height"].as_u64().map(|h| h as u32),
+                fps: f["fps"].as_f64(),
+                filesize: f["filesize"].as_u64().or_else(|| f["filesize_approx"].as_u64()),
+            })
+        })
+        .collect();
+
+    formats.sort_by(|a, b| b.height.cmp(&a.height));
+    formats
+}
+
+/// A named section of a video (e.g. "Verse 1", "Chorus"), times in seconds
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    pub start_time: f64,
+    pub end_time: f64,
+}
+
+/// Drop chapters with invalid times, clamp ends to the video duration and sort by start.
+/// A chapter is invalid if its times are not finite, it ends before it starts,
+/// or it starts at or after the end of the video.
+pub fn validate_chapters(chapters: Vec<Chapter>, duration: Option<f64>) -> Vec<Chapter> {
+    let mut valid: Vec<Chapter> = chapters
+        .into_iter()
+        .filter(|c| c.start_time.is_finite() && c.end_time.is_finite())
+        .filter(|c| c.start_time >= 0.0 && c.end_time > c.start_time)
+        .filter_map(|mut c| {
+            if let Some(duration) = duration {
+                if c.start_time >= duration {
+                    return None;
+                }
+                c.end_time = c.end_time.min(duration);
+            }
+            Some(c)
+        })
+        .collect();
+
+    valid.sort_by(|a, b| a.start_time.total_cmp(&b.start_time));
+    valid
+}
+
+/// Extract chapters from yt-dlp's `--dump-json` output, validated against the video duration
+fn parse_chapters(info: &serde_json::Value) -> Vec<Chapter> {
+    let chapters = info["chapters"]
+        .as_array()
+        .map(|chapters| chapters.as_slice())
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|c| {
+            Some(Chapter {
+                title: c["title"].as_str().unwrap_or_default().trim().to_string(),
+                start_time: c["start_time"].as_f64()?,
+                end_time: c["end_time"].as_f64()?,
+            })
+        })
+        .collect();
+
+    validate_chapters(chapters, info["duration"].as_f64())
+}
+
 pub struct YtDlpService;
 
 impl YtDlpService {
@@ -229,6 +394,24 @@ impl YtDlpService {
         }
     }
 
+    /// Get the installed yt-dlp version (e.g. "2024.12.13"), None if not installed
+    pub async fn get_version(&self) -> Option<String> {
+        let path = find_ytdlp_path()?;
+        let output = Command::new(&path)
+            .arg("--version")
+            .env("PATH", get_expanded_path())
+            .no_window()
+            .output()
+            .await
+            .ok()?;
+
+        if !output.status.success() {
+            return None;
+        }
+        let version = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        (!version.is_empty()).then_some(version)
+    }
+
     /// Validate YouTube video ID format (alphanumeric, dash, underscore, 11 chars)
     fn validate_video_id(video_id: &str) -> Result<(), YtDlpError> {
         if video_id.is_empty() || video_id.len() > 20 {
@@ -240,6 +423,17 @@ impl YtDlpService {
         Ok(())
     }
 
+    /// Validate yt-dlp format ID (alphanumeric, dash, underscore)
+    fn validate_format_id(format_id: &str) -> Result<(), YtDlpError> {
+        if format_id.is_empty() || format_id.len() > 32 {
+            return Err(YtDlpError::ExecutionError("Invalid format ID length".to_string()));
+        }
+        if !format_id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+            return Err(YtDlpError::ExecutionError("Invalid characters in format ID".to_string()));
+        }
+        Ok(())
+    }
+
     /// Sanitize search query (remove potentially dangerous characters)
     fn sanitize_query(query: &str) -> String {
         // Remove shell metacharacters and limit length
@@ -336,19 +530,31 @@ impl YtDlpService {
     }
 
     /// Get streaming URL for a video
-    pub async fn get_stream_url(&self, video_id: &str) -> Result<StreamInfo, YtDlpError> {
+    /// Pass a `format_id` from `list_formats` to pick a specific quality,
+    /// otherwise the best compatible format is chosen.
+    pub async fn get_stream_url(
+        &self,
+        video_id: &str,
+        format_id: Option<&str>,
+    ) -> Result<StreamInfo, YtDlpError> {
         Self::validate_video_id(video_id)?;
-        debug!("Getting stream URL for video: {}", video_id);
+        if let Some(format_id) = format_id {
+            Self::validate_format_id(format_id)?;
+        }
+        debug!("Getting stream URL for video: {} (format: {:?})", video_id, format_id);
 
         let url = format!("https://www.youtube.com/watch?v={}", video_id);
 
         // Prefer H.264 (avc1) codec for best compatibility with WebKitGTK/GStreamer on Linux
         // Exclude HLS (m3u8) streams - HTML5 video doesn't support HLS natively on WebKitGTK
         // Format priority: best mp4 with H.264 (no HLS) > best mp4 (no HLS) > best (no HLS)
+        let format_selector = format_id.unwrap_or(
+            "best[ext=mp4][vcodec^=avc][protocol!*=m3u8]/best[ext=mp4][protocol!*=m3u8]/best[protocol!*=m3u8]/best",
+        );
         let output = Command::new(get_ytdlp_command())
             .arg(&url)
             .arg("-f")
-            .arg("best[ext=mp4][vcodec^=avc][protocol!*=m3u8]/best[ext=mp4][protocol!*=m3u8]/best[protocol!*=m3u8]/best")
+            .arg(format_selector)
             .arg("--get-url")
             .arg("--no-warnings")
             .env("PATH", get_expanded_path())
@@ -383,10 +589,24 @@ impl YtDlpService {
         Ok(StreamInfo {
             url: stream_url,
             format: "mp4".to_string(),
-            quality: "best".to_string(),
+            quality: format_id.unwrap_or("best").to_string(),
         })
     }
 
+    /// List the stream formats available for a video (for a quality picker)
+    pub async fn list_formats(&self, video_id: &str) -> Result<Vec<StreamFormat>, YtDlpError> {
+        debug!("Listing formats for video: {}", video_id);
+        let v = self.dump_json(video_id).await?;
+
+        let formats = parse_stream_formats(&v);
+        if formats.is_empty() {
+            return Err(YtDlpError::NoResults);
+        }
+
+        info!("Found {} playable formats for video: {}", formats.len(), video_id);
+        Ok(formats)
+    }
+
     /// Get video info without downloading
     pub async fn get_video_info(&self, video_id: &str) -> Result<VideoInfo, YtDlpError> {
         Self::validate_video_id(video_id)?;
@@ -431,6 +651,45 @@ impl YtDlpService {
             description: v["description"].as_str().map(|s| s.to_string()),
         })
     }
+
+    /// Get a video's chapters (empty if the uploader defined none)
+    pub async fn get_chapters(&self, video_id: &str) -> Result<Vec<Chapter>, YtDlpError> {
+        let v = self.dump_json(video_id).await?;
+        Ok(parse_chapters(&v))
+    }
+
+    /// Fetch a video's full yt-dlp metadata (`--dump-json`) without downloading it
+    async fn dump_json(&self, video_id: &str) -> Result<serde_json::Value, YtDlpError> {
+        Self::validate_video_id(video_id)?;
+
+        let url = format!("https://www.youtube.com/watch?v={}", video_id);
+
+        let output = Command::new(get_ytdlp_command())
+            .arg(&url)
+            .arg("--dump-json")
+            .arg("--no-warnings")
+            .arg("--no-download")
+            .env("PATH", get_expanded_path())
+            .no_window()
+            .output()
+            .await
+            .map_err(|e| {
+                if e.kind() == std::io::ErrorKind::NotFound {
+                    YtDlpError::NotFound
+                } else {
+                    YtDlpError::ExecutionError(e.to_string())
+                }
+            })?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!("yt-dlp --dump-json failed for {}: {}", video_id, stderr);
+            return Err(YtDlpError::ExecutionError(stderr.to_string()));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        serde_json::from_str(&stdout).map_err(|e| YtDlpError::ParseError(e.to_string()))
+    }
 }
 
 impl Default for YtDlpService {
@@ -438,3 +697,155 @@ impl Default for YtDlpService {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_stderr_needs_update() {
+        assert_eq!(
+            classify_stderr("ERROR: [youtube] abc123def45: Unable to extract uploader id; please report this issue"),
+            YtDlpFailureKind::NeedsUpdate
+        );
+        assert_eq!(
+            classify_stderr("WARNING: [youtube] nsig extraction failed: You may experience throttling"),
+            YtDlpFailureKind::NeedsUpdate
+        );
+    }
+
+    #[test]
+    fn test_classify_stderr_network() {
+        assert_eq!(
+            classify_stderr(
+                "ERROR: Unable to download webpage: <urlopen error [Errno -3] Temporary failure in name resolution>"
+            ),
+            YtDlpFailureKind::Network
+        );
+        assert_eq!(classify_stderr("ERROR: HTTP Error 503: Service Unavailable"), YtDlpFailureKind::Network);
+    }
+
+    #[test]
+    fn test_classify_stderr_other() {
+        assert_eq!(classify_stderr("ERROR: Private video"), YtDlpFailureKind::Other);
+        assert_eq!(classify_stderr(""), YtDlpFailureKind::Other);
+    }
+
+    #[test]
+    fn test_parse_stream_formats_keeps_playable_formats() {
+        let info: serde_json::Value = serde_json::from_str(
+            r#"{
+                "id": "dQw4w9WgXcQ",
+                "formats": [
+                    {"format_id": "139", "ext": "m4a", "vcodec": "none", "acodec": "mp4a.40.5", "protocol": "https", "filesize": 1200000},
+                    {"format_id": "18", "ext": "mp4", "vcodec": "avc1.42001E", "acodec": "mp4a.40.2", "protocol": "https",
+                     "width": 640, "height": 360, "fps": 25, "filesize": 8500000},
+                    {"format_id": "137", "ext": "mp4", "vcodec": "avc1.640028", "acodec": "none", "protocol": "https",
+                     "width": 1920, "height": 1080, "fps": 25, "filesize": 40000000},
+                    {"format_id": "22", "ext": "mp4", "vcodec": "avc1.64001F", "acodec": "mp4a.40.2", "protocol": "https",
+                     "width": 1280, "height": 720, "fps": 29.97, "filesize_approx": 21000000},
+                    {"format_id": "96", "ext": "mp4", "vcodec": "avc1.640028", "acodec": "mp4a.40.2", "protocol": "m3u8_native",
+                     "width": 1920, "height": 1080, "fps": 25},
+                    {"format_id": "sb0", "ext": "mhtml", "vcodec": "none", "acodec": "none", "protocol": "mhtml"}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let formats = parse_stream_formats(&info);
+        assert_eq!(
+            formats,
+            vec![
+                StreamFormat {
+                    format_id: "22".to_string(),
+                    ext: "mp4".to_string(),
+                    width: Some(1280),
+                    height: Some(720),
+                    fps: Some(29.97),
+                    filesize: Some(21000000),
+                },
+                StreamFormat {
+                    format_id: "18".to_string(),
+                    ext: "mp4".to_string(),
+                    width: Some(640),
+                    height: Some(360),
+                    fps: Some(25.0),
+                    filesize: Some(8500000),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_stream_formats_missing_formats() {
+        let info: serde_json::Value = serde_json::from_str(r#"{"id": "dQw4w9WgXcQ"}"#).unwrap();
+        assert!(parse_stream_formats(&info).is_empty());
+    }
+
+    #[test]
+    fn test_validate_format_id() {
+        assert!(YtDlpService::validate_format_id("22").is_ok());
+        assert!(YtDlpService::validate_format_id("hls-1080p").is_ok());
+        assert!(YtDlpService::validate_format_id("").is_err());
+        assert!(YtDlpService::validate_format_id("best/worst").is_err());
+        assert!(YtDlpService::validate_format_id("22+140").is_err());
+    }
+
+    #[test]
+    fn test_parse_chapters() {
+        let info = serde_json::json!({
+            "duration": 200.0,
+            "chapters": [
+                {"title": "Chorus", "start_time": 60.0, "end_time": 120.0},
+                {"title": " Intro ", "start_time": 0.0, "end_time": 60.0},
+                {"title": "Outro", "start_time": 120.0, "end_time": 215.5}
+            ]
+        });
+
+        let chapters = parse_chapters(&info);
+        let titles: Vec<&str> = chapters.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["Intro", "Chorus", "Outro"]);
+        // Clamped to the video duration
+        assert_eq!(chapters[2].end_time, 200.0);
+    }
+
+    #[test]
+    fn test_parse_chapters_missing() {
+        assert!(parse_chapters(&serde_json::json!({"duration": 200.0})).is_empty());
+        assert!(parse_chapters(&serde_json::json!({"chapters": null})).is_empty());
+    }
+
+    #[test]
+    fn test_validate_chapters_drops_invalid_times() {
+        let chapter = |title: &str, start_time: f64, end_time: f64| Chapter {
+            title: title.to_string(),
+            start_time,
+            end_time,
+        };
+        let chapters = vec![
+            chapter("ok", 10.0, 20.0),
+            chapter("negative", -5.0, 10.0),
+            chapter("backwards", 30.0, 25.0),
+            chapter("empty", 40.0, 40.0),
+            chapter("past end", 100.0, 110.0),
+            chapter("nan", f64::NAN, 10.0),
+        ];
+
+        let valid = validate_chapters(chapters.clone(), Some(100.0));
+        assert_eq!(valid, vec![chapter("ok", 10.0, 20.0)]);
+
+        // Without a known duration only the start/end ordering is checked
+        let valid = validate_chapters(chapters, None);
+        let titles: Vec<&str> = valid.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, vec!["ok", "past end"]);
+    }
+
+    #[test]
+    fn test_search_bin_paths_lists_common_paths() {
+        let paths = search_bin_paths();
+        for common in COMMON_BIN_PATHS {
+            let status = paths.iter().find(|p| p.path == *common).unwrap();
+            assert_eq!(status.exists, Path::new(common).is_dir());
+        }
+    }
+}
@@ -0,0 +1,12 @@
+//! Helpers shared by the unit tests of several modules
+
+use std::fs;
+use std::path::PathBuf;
+
+/// Create an empty directory under the system temp dir, unique to `name` and this test run
+pub(crate) fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("homekaraoke-test-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
@@ -310,7 +310,7 @@ function App() {
         // Use appropriate search method
         const results = method === "api"
           ? await youtubeService.apiSearch(query, MAX_SEARCH_RESULTS)
-          : await youtubeService.search(query, MAX_SEARCH_RESULTS);
+          : (await youtubeService.search(query, MAX_SEARCH_RESULTS)).results;
 
         log.info(`Search returned ${results.length} results`);
         setSearchResults(results);
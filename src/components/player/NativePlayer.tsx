@@ -54,6 +54,8 @@ export interface NativePlayerProps {
   volume: number;
   isMuted: boolean;
   seekTime: number | null;
+  /** Speed multiplier (1.0 is normal speed) */
+  playbackRate?: number;
   /** Key to force reload even when streamUrl is the same (e.g., replay same video) */
   playbackKey?: string | number;
   onReady?: () => void;
@@ -84,6 +86,7 @@ export const NativePlayer = forwardRef<NativePlayerRef, NativePlayerProps>(funct
   volume,
   isMuted,
   seekTime,
+  playbackRate = 1,
   playbackKey,
   onReady,
   onTimeUpdate,
@@ -224,6 +227,15 @@ export const NativePlayer = forwardRef<NativePlayerRef, NativePlayerProps>(funct
     video.volume = isMuted ? 0 : volume;
   }, [volume, isMuted]);
 
+  // Handle playback rate changes (defaultPlaybackRate survives loading a new source)
+  useEffect(() => {
+    const video = videoRef.current;
+    if (!video) return;
+
+    video.defaultPlaybackRate = playbackRate;
+    video.playbackRate = playbackRate;
+  }, [playbackRate]);
+
   // Handle seeking
   useEffect(() => {
     const video = videoRef.current;
@@ -208,6 +208,7 @@ vi.mock("../../stores", () => ({
   },
   playVideo: () => mockPlayVideo(),
   notify: (...args: unknown[]) => mockNotify(...args),
+  getValidatedLocalFileUrl: (filePath: string) => `asset://localhost/${filePath}`,
 }));
 
 vi.mock("../../services", () => ({
@@ -384,7 +385,7 @@ describe("PlayerControls", () => {
 
     it("clicking previous plays the previous song", async () => {
       const prevVideo = { id: "prev-1", title: "Prev Song", youtubeId: "prev123" };
-      mockQueueStore.playPrevious.mockReturnValue({ id: "queue-prev", video: prevVideo, addedAt: new Date() });
+      mockQueueStore.playPrevious.mockResolvedValue({ id: "queue-prev", video: prevVideo, addedAt: new Date() });
       setupVideoPlaying({ hasPrevious: true });
       render(<PlayerControls />);
 
@@ -396,7 +397,7 @@ describe("PlayerControls", () => {
 
     it("clicking next plays the next song", async () => {
       const nextVideo = { id: "next-1", title: "Next Song", youtubeId: "next123" };
-      mockQueueStore.playNext.mockReturnValue({ id: "queue-next", video: nextVideo, addedAt: new Date() });
+      mockQueueStore.playNext.mockResolvedValue({ id: "queue-next", video: nextVideo, addedAt: new Date() });
       setupVideoPlaying({ hasNext: true });
       render(<PlayerControls />);
 
@@ -1,6 +1,5 @@
 import { useRef, useCallback, useEffect } from "react";
-import { convertFileSrc } from "@tauri-apps/api/core";
-import { usePlayerStore, useQueueStore, useSessionStore, useSettingsStore, SETTINGS_KEYS, parseOverlaySeconds, playVideo, notify } from "../../stores";
+import { usePlayerStore, useQueueStore, useSessionStore, useSettingsStore, SETTINGS_KEYS, parseOverlaySeconds, playVideo, notify, getValidatedLocalFileUrl } from "../../stores";
 import { windowManager, youtubeService, createLogger, emitSignal, APP_SIGNALS } from "../../services";
 
 const log = createLogger("PlayerControls");
@@ -103,11 +102,11 @@ export function PlayerControls() {
     const rawMode = settingsState.getSetting(SETTINGS_KEYS.PLAYBACK_MODE);
     const playbackMode: "youtube" | "ytdlp" = rawMode === "ytdlp" ? "ytdlp" : "youtube";
 
-    // For local files, convert file path to asset URL
+    // For local files, use the asset URL of the file once it was checked to be in the library
     let streamUrl = state.currentVideo?.streamUrl ?? null;
     let effectivePlaybackMode = playbackMode;
     if (state.currentVideo?.source === "local" && state.currentVideo?.filePath) {
-      streamUrl = convertFileSrc(state.currentVideo.filePath);
+      streamUrl = getValidatedLocalFileUrl(state.currentVideo.filePath);
       effectivePlaybackMode = "ytdlp"; // Local files use native player
     }
 
@@ -404,7 +403,7 @@ export function PlayerControls() {
 
   const handlePrevious = useCallback(async () => {
     log.info("Playing previous");
-    const prevItem = playPrevious();
+    const prevItem = await playPrevious();
     if (prevItem) {
       try {
         await playVideo(prevItem.video);
@@ -416,7 +415,7 @@ export function PlayerControls() {
 
   const handleNext = useCallback(async () => {
     log.info("Playing next");
-    const nextItem = playNext();
+    const nextItem = await playNext();
     if (nextItem) {
       try {
         await playVideo(nextItem.video);
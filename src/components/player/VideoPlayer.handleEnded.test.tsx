@@ -58,6 +58,8 @@ interface MockQueueState {
 
 interface MockSessionState {
   session: null;
+  sessionSettings: { autoplay?: boolean | null };
+  playbackDefaults: { playback_rate?: number | null };
   queueSingerAssignments: Map<string, number[]>;
   singers: never[];
   loadQueueItemSingers: ReturnType<typeof vi.fn>;
@@ -104,6 +106,8 @@ const createMockQueueStore = (): MockQueueState => ({
 
 const createMockSessionStore = (): MockSessionState => ({
   session: null,
+  sessionSettings: {},
+  playbackDefaults: {},
   queueSingerAssignments: new Map(),
   singers: [],
   loadQueueItemSingers: vi.fn(),
@@ -164,10 +168,6 @@ let capturedOnEnded: (() => void) | null = null;
 // Mock Definitions
 // =============================================================================
 
-vi.mock("@tauri-apps/api/core", () => ({
-  convertFileSrc: (path: string) => `asset://localhost/${path}`,
-}));
-
 vi.mock("../../stores", () => ({
   usePlayerStore: Object.assign(
     (selector?: (state: MockPlayerState) => unknown) => {
@@ -266,6 +266,8 @@ vi.mock("../../services", () => ({
 
 vi.mock("../../hooks", () => ({
   useWakeLock: vi.fn(),
+  useLocalFileUrl: (filePath: string | null | undefined) =>
+    filePath ? `asset://localhost/${filePath}` : undefined,
 }));
 
 // Mock child player components to capture onEnded callback
@@ -58,6 +58,8 @@ interface MockQueueState {
 
 interface MockSessionState {
   session: null;
+  sessionSettings: { autoplay?: boolean | null };
+  playbackDefaults: { playback_rate?: number | null };
   queueSingerAssignments: Map<string, number[]>;
   singers: never[];
   loadQueueItemSingers: ReturnType<typeof vi.fn>;
@@ -104,6 +106,8 @@ const createMockQueueStore = (): MockQueueState => ({
 
 const createMockSessionStore = (): MockSessionState => ({
   session: null,
+  sessionSettings: {},
+  playbackDefaults: {},
   queueSingerAssignments: new Map(),
   singers: [],
   loadQueueItemSingers: vi.fn(),
@@ -150,10 +154,6 @@ const mockSettingsStore = {
 // Mock Definitions
 // =============================================================================
 
-vi.mock("@tauri-apps/api/core", () => ({
-  convertFileSrc: (path: string) => `asset://localhost/${path}`,
-}));
-
 vi.mock("../../stores", () => ({
   usePlayerStore: Object.assign(
     (selector?: (state: MockPlayerState) => unknown) => {
@@ -247,6 +247,8 @@ vi.mock("../../services", () => ({
 
 vi.mock("../../hooks", () => ({
   useWakeLock: vi.fn(),
+  useLocalFileUrl: (filePath: string | null | undefined) =>
+    filePath ? `asset://localhost/${filePath}` : undefined,
 }));
 
 // Mock child player components
@@ -1,5 +1,4 @@
 import { useRef, useEffect, useCallback, useMemo, useState } from "react";
-import { convertFileSrc } from "@tauri-apps/api/core";
 import {
   usePlayerStore,
   useQueueStore,
@@ -13,7 +12,7 @@ import {
   notify,
 } from "../../stores";
 import { youtubeService, createLogger, windowManager, emitSignal, APP_SIGNALS } from "../../services";
-import { useWakeLock } from "../../hooks";
+import { useWakeLock, useLocalFileUrl } from "../../hooks";
 import {
   NextSongOverlay,
   COUNTDOWN_START_THRESHOLD_SECONDS,
@@ -105,6 +104,10 @@ export function VideoPlayer() {
   // Get hosted session for join overlay
   const hostedSession = useSessionStore((state) => state.hostedSession);
 
+  // Session-wide tempo baseline (queue items have no override of their own yet).
+  // The session's transpose is stored but not applied: the players can't shift pitch.
+  const playbackRate = useSessionStore((state) => state.playbackDefaults.playback_rate) ?? 1;
+
   // Get playback mode from settings with runtime validation
   const rawPlaybackMode = useSettingsStore((state) =>
     state.getSetting(SETTINGS_KEYS.PLAYBACK_MODE)
@@ -119,16 +122,20 @@ export function VideoPlayer() {
   const parsedPrefetch = parseInt(rawPrefetch, 10);
   const prefetchSeconds = isNaN(parsedPrefetch) ? 20 : parsedPrefetch;
 
+  // Local files are loaded only after the backend checks they're inside a library folder
+  const hasLocalFile = currentVideo?.source === "local" && !!currentVideo?.filePath;
+  const currentLocalFileUrl = useLocalFileUrl(hasLocalFile ? currentVideo?.filePath : null, true);
+
   // Handle detach button click
   const handleDetach = useCallback(async () => {
     if (isDetached) return;
 
     log.info("Detaching player window");
 
-    // For local files, convert the file path to a URL for the detached player
+    // For local files, pass the validated file URL to the detached player
     let streamUrl = currentVideo?.streamUrl || null;
     if (currentVideo?.source === "local" && currentVideo?.filePath) {
-      streamUrl = convertFileSrc(currentVideo.filePath);
+      streamUrl = currentLocalFileUrl ?? null;
     }
 
     const playerState = {
@@ -150,7 +157,7 @@ export function VideoPlayer() {
     } catch (err) {
       log.error("Failed to detach player", err);
     }
-  }, [isDetached, currentVideo, playbackMode, isPlaying, currentTime, duration, volume, isMuted, setIsDetached, setIsLoading]);
+  }, [isDetached, currentVideo, currentLocalFileUrl, playbackMode, isPlaying, currentTime, duration, volume, isMuted, setIsDetached, setIsLoading]);
 
   // Prevent screen from sleeping while playing (only when not detached)
   useWakeLock(isPlaying && !isDetached);
@@ -163,6 +170,9 @@ export function VideoPlayer() {
   // Subscribe to first queue item for overlay and prefetch cache invalidation
   const nextQueueItem = useQueueStore((state) => state.queue[0]);
   const nextQueueVideoId = nextQueueItem?.video.youtubeId;
+  const nextLocalFileUrl = useLocalFileUrl(
+    nextQueueItem?.video.source === "local" ? nextQueueItem.video.filePath : null
+  );
   useEffect(() => {
     invalidatePrefetchIfStale(nextQueueVideoId);
     // Also reset prefetch trigger if queue's first item changed
@@ -291,9 +301,11 @@ export function VideoPlayer() {
     emitSignal(APP_SIGNALS.PLAYBACK_ENDED, undefined).catch(() => {});
     emitSignal(APP_SIGNALS.SONG_ENDED, undefined).catch(() => {});
 
-    // Check autoplay setting
-    const autoplayNext = useSettingsStore.getState().getSetting(SETTINGS_KEYS.AUTOPLAY_NEXT);
-    if (autoplayNext !== "true") {
+    // Check autoplay setting (the active session's default overrides the global one)
+    const sessionAutoplay = useSessionStore.getState().sessionSettings.autoplay;
+    const autoplayNext =
+      sessionAutoplay ?? useSettingsStore.getState().getSetting(SETTINGS_KEYS.AUTOPLAY_NEXT) === "true";
+    if (!autoplayNext) {
       log.info("Autoplay disabled, stopping playback");
       return;
     }
@@ -474,26 +486,14 @@ export function VideoPlayer() {
   // Determine what to show
   const hasVideoId = !!currentVideo?.youtubeId;
   const hasStreamUrl = !!currentVideo?.streamUrl;
-  const hasLocalFile = currentVideo?.source === "local" && !!currentVideo?.filePath;
   const canPlayYouTube = playbackMode === "youtube" && hasVideoId;
   const canPlayNative = playbackMode === "ytdlp" && hasStreamUrl;
-  const canPlayLocal = hasLocalFile;
-
-  // Get the URL for local files (convert file path to URL that webview can access)
-  // Also check next queue item to preload and keep video element mounted
-  const nextLocalFilePath = nextQueueItem?.video.source === "local" ? nextQueueItem.video.filePath : null;
+  // Local files play only once the backend confirmed they're inside a library folder
+  const canPlayLocal = hasLocalFile && !!currentLocalFileUrl;
 
-  const localFileUrl = useMemo(() => {
-    if (hasLocalFile && currentVideo?.filePath) {
-      return convertFileSrc(currentVideo.filePath);
-    }
-    // If next item is local, preload it to keep video element mounted
-    if (nextLocalFilePath) {
-      return convertFileSrc(nextLocalFilePath);
-    }
-    // No local file - return undefined to enable "dummy mode" for priming
-    return undefined;
-  }, [hasLocalFile, currentVideo?.filePath, nextLocalFilePath]);
+  // If the current video isn't local, preload a local next item to keep the video element
+  // mounted; with no local file the URL is undefined to enable "dummy mode" for priming
+  const localFileUrl = hasLocalFile ? currentLocalFileUrl : nextLocalFileUrl;
 
   // Show placeholder when no video or when detached (video plays in separate window)
   if ((!canPlayYouTube && !canPlayNative && !canPlayLocal) || isDetached) {
@@ -572,6 +572,7 @@ export function VideoPlayer() {
           volume={volume}
           isMuted={isMuted}
           seekTime={seekTime}
+          playbackRate={playbackRate}
           onReady={handleReady}
           onTimeUpdate={handleTimeUpdate}
           onEnded={handleEnded}
@@ -587,6 +588,7 @@ export function VideoPlayer() {
           volume={volume}
           isMuted={isMuted}
           seekTime={seekTime}
+          playbackRate={playbackRate}
           playbackKey={currentVideo.id}
           onReady={handleReady}
           onTimeUpdate={handleTimeUpdate}
@@ -607,6 +609,7 @@ export function VideoPlayer() {
           volume={volume}
           isMuted={isMuted}
           seekTime={canPlayLocal ? seekTime : null}
+          playbackRate={playbackRate}
           playbackKey={currentVideo?.id}
           onReady={canPlayLocal ? handleReady : undefined}
           onTimeUpdate={canPlayLocal ? handleTimeUpdate : undefined}
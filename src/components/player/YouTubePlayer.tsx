@@ -20,6 +20,8 @@ export interface YouTubePlayerProps {
   volume: number;
   isMuted: boolean;
   seekTime: number | null;
+  /** Speed multiplier (1.0 is normal speed) */
+  playbackRate?: number;
   onReady?: () => void;
   onTimeUpdate?: (currentTime: number, duration: number) => void;
   onEnded?: () => void;
@@ -36,6 +38,7 @@ export function YouTubePlayer({
   volume,
   isMuted,
   seekTime,
+  playbackRate = 1,
   onReady,
   onTimeUpdate,
   onEnded,
@@ -58,6 +61,8 @@ export function YouTubePlayer({
   // Track current volume/mute settings via refs for use in callbacks
   const volumeRef = useRef(volume);
   volumeRef.current = volume;
+  const playbackRateRef = useRef(playbackRate);
+  playbackRateRef.current = playbackRate;
   const isMutedRef = useRef(isMuted);
   isMutedRef.current = isMuted;
   const [isLoading, setIsLoading] = useState(true);
@@ -208,6 +213,11 @@ export function YouTubePlayer({
                 autoplayRetryRef.current.reset();
                 setShowPlayButton(false);
 
+                // Each video starts at normal speed, so reapply the rate
+                if (event.target.getPlaybackRate() !== playbackRateRef.current) {
+                  event.target.setPlaybackRate(playbackRateRef.current);
+                }
+
                 // Handle pending unmute (autoplay workaround)
                 if (pendingUnmuteRef.current) {
                   log.info("Playback started, unmuting now");
@@ -411,6 +421,18 @@ export function YouTubePlayer({
     }
   }, [volume, isMuted]);
 
+  // Handle playback rate changes (YouTube rounds to its nearest supported rate)
+  useEffect(() => {
+    const player = playerRef.current;
+    if (!player || !isReadyRef.current) return;
+
+    try {
+      player.setPlaybackRate(playbackRate);
+    } catch {
+      // Player might not be ready yet
+    }
+  }, [playbackRate]);
+
   // Handle seeking
   useEffect(() => {
     const player = playerRef.current;
@@ -1,6 +1,9 @@
 import { useState, useCallback, useRef, useEffect, useImperativeHandle, forwardRef } from "react";
 import { Globe, HardDrive } from "lucide-react";
 import { useAppStore, useLibraryStore, useSearchHistoryStore } from "../../stores";
+import { createLogger, sessionService } from "../../services";
+
+const log = createLogger("SearchBar");
 
 interface SearchBarProps {
   onSearch: (query: string) => void;
@@ -53,6 +56,16 @@ export const SearchBar = forwardRef<SearchBarRef, SearchBarProps>(function Searc
       ? topMatch.slice(inputValue.length)
       : null;
 
+  // Restore the session's last search after a restart (unless the user already typed)
+  useEffect(() => {
+    sessionService
+      .getLastSearch()
+      .then((query) => {
+        if (query) setInputValue((current) => current || query);
+      })
+      .catch((error) => log.debug("Could not restore last search:", error));
+  }, []);
+
   // Load suggestions when search mode changes or on mount
   useEffect(() => {
     getSuggestions(searchType);
@@ -75,6 +88,9 @@ export const SearchBar = forwardRef<SearchBarRef, SearchBarProps>(function Searc
       if (query) {
         setSearchQuery(query);
         onSearch(query);
+        sessionService.setLastSearch(inputValue.trim()).catch((error) => {
+          log.debug("Could not save last search:", error);
+        });
         // Record the search (use raw input, not modified query)
         await recordSearch(searchType, inputValue.trim());
         setShowDropdown(false);
@@ -100,6 +116,9 @@ export const SearchBar = forwardRef<SearchBarRef, SearchBarProps>(function Searc
 
       setSearchQuery(query);
       onSearch(query);
+      sessionService.setLastSearch(suggestion).catch((error) => {
+        log.debug("Could not save last search:", error);
+      });
       await recordSearch(searchType, suggestion);
     },
     [isLocalMode, addKaraoke, setSearchQuery, onSearch, recordSearch, searchType]
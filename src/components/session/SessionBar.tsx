@@ -1,9 +1,9 @@
 import { useState, useEffect, useRef } from "react";
 import { Play, Square, Users, UserPlus, X, Trash2, Pencil, Check, FolderOpen, Star, Radio, Loader2 } from "lucide-react";
 import { listen } from "@tauri-apps/api/event";
-import { useSessionStore, useFavoritesStore, useAuthStore, notify } from "../../stores";
+import { useSessionStore, useFavoritesStore, useAuthStore, useQueueStore, notify } from "../../stores";
 import { SingerAvatar, SingerChip } from "../singers";
-import { sessionService, createLogger, HOSTED_SESSION_STATUS } from "../../services";
+import { sessionService, queueService, createLogger, HOSTED_SESSION_STATUS } from "../../services";
 import { HostSessionModal } from "./HostSessionModal";
 
 const log = createLogger("SessionBar");
@@ -131,6 +131,29 @@ export function SessionBar() {
     };
   }, [openLoadDialog]);
 
+  // Pick up the queue order after a scheduled fair shuffle
+  useEffect(() => {
+    let mounted = true;
+    let unlistenFn: (() => void) | null = null;
+
+    queueService.onAutoShuffled(() => {
+      if (mounted) {
+        useQueueStore.getState().reloadQueueOrder();
+      }
+    }).then((fn) => {
+      if (mounted) {
+        unlistenFn = fn;
+      } else {
+        fn(); // Component already unmounted, clean up immediately
+      }
+    });
+
+    return () => {
+      mounted = false;
+      if (unlistenFn) unlistenFn();
+    };
+  }, []);
+
   // Focus rename input when dialog opens
   useEffect(() => {
     if (showRenameDialog && renameInputRef.current) {
@@ -23,7 +23,7 @@ import {
 import { invoke } from "@tauri-apps/api/core";
 import { useSettingsStore, useLibraryStore, usePlayerStore, SETTINGS_KEYS, notify } from "../../stores";
 import { updateService, createLogger, youtubeService } from "../../services";
-import type { SettingsTab } from "../../stores";
+import type { SettingsTab, LyricsPreference } from "../../stores";
 
 const log = createLogger("SettingsDialog");
 
@@ -436,6 +436,28 @@ function DisplaySettings({ getSetting, setSetting }: SettingsSectionProps) {
       </SettingRow>
       */}
 
+      <SettingRow
+        label="Pause on Display Disconnect"
+        description="Pause playback and allow sleep when a screen is unplugged (macOS)"
+      >
+        <ToggleSwitch
+          checked={getSetting(SETTINGS_KEYS.PAUSE_ON_DISPLAY_DISCONNECT) === "true"}
+          onChange={(v) =>
+            handleChange(SETTINGS_KEYS.PAUSE_ON_DISPLAY_DISCONNECT, v ? "true" : "false")
+          }
+        />
+      </SettingRow>
+
+      <SettingRow
+        label="Readable Singer Colors"
+        description="Lighten singer colors that are hard to read on the dark background"
+      >
+        <ToggleSwitch
+          checked={getSetting(SETTINGS_KEYS.ENFORCE_CONTRAST) === "true"}
+          onChange={(v) => handleChange(SETTINGS_KEYS.ENFORCE_CONTRAST, v ? "true" : "false")}
+        />
+      </SettingRow>
+
       {/* TODO: Issue #158: Remember Player Position
       <SettingRow
         label="Remember Player Position"
@@ -474,6 +496,16 @@ function QueueSettings({ getSetting, setSetting }: SettingsSectionProps) {
     <div>
       <h4 className="text-lg font-medium text-white mb-4">Queue & History</h4>
 
+      <SettingRow
+        label="Keep Empty Sessions"
+        description="Archive sessions with no songs or singers when they end, instead of deleting them"
+      >
+        <ToggleSwitch
+          checked={getSetting(SETTINGS_KEYS.PRESERVE_EMPTY_SESSIONS) === "true"}
+          onChange={(v) => handleChange(SETTINGS_KEYS.PRESERVE_EMPTY_SESSIONS, v ? "true" : "false")}
+        />
+      </SettingRow>
+
       {/* Hosted Session Section */}
       <div className="mb-6">
         <div className="text-sm font-medium text-gray-300 mb-3">Hosted Session</div>
@@ -491,6 +523,28 @@ function QueueSettings({ getSetting, setSetting }: SettingsSectionProps) {
         </SettingRow>
       </div>
 
+      {/* Fair Rotation Section */}
+      <div className="mb-6">
+        <div className="text-sm font-medium text-gray-300 mb-3">Fair Rotation</div>
+
+        <SettingRow
+          label="Shuffle After Adding"
+          description="Fair shuffle the queue after this many songs are added (the next song stays put)"
+        >
+          <SelectInput
+            value={getSetting(SETTINGS_KEYS.AUTO_SHUFFLE_AFTER_ADDS)}
+            options={[
+              { value: "0", label: "Off" },
+              { value: "1", label: "Every song" },
+              { value: "3", label: "Every 3 songs" },
+              { value: "5", label: "Every 5 songs" },
+              { value: "10", label: "Every 10 songs" },
+            ]}
+            onChange={(v) => handleChange(SETTINGS_KEYS.AUTO_SHUFFLE_AFTER_ADDS, v)}
+          />
+        </SettingRow>
+      </div>
+
       {/* Search History Section */}
       <div className="mb-6">
         <div className="text-sm font-medium text-gray-300 mb-3">Search History</div>
@@ -821,6 +875,21 @@ function AdvancedSettings({
             </p>
           </div>
 
+          <SettingRow
+            label="Search Results"
+            description="Number of results per YouTube search"
+          >
+            <SelectInput
+              value={getSetting(SETTINGS_KEYS.DEFAULT_SEARCH_RESULTS)}
+              options={[
+                { value: "10", label: "10" },
+                { value: "25", label: "25" },
+                { value: "50", label: "50" },
+              ]}
+              onChange={(v) => handleChange(SETTINGS_KEYS.DEFAULT_SEARCH_RESULTS, v)}
+            />
+          </SettingRow>
+
           <SettingRow
             label="Video Streaming Mode"
             description="How to play YouTube videos"
@@ -1018,6 +1087,7 @@ function LibrarySettings({ getSetting, setSetting }: SettingsSectionProps) {
     createHkmeta: true,
     fetchSongInfo: true,
     fetchLyrics: true,
+    lyricsPreference: "synced_first" as LyricsPreference,
     regenerate: false,
     generateThumbnails: true,
   });
@@ -1070,6 +1140,7 @@ function LibrarySettings({ getSetting, setSetting }: SettingsSectionProps) {
           create_hkmeta: scanOptions.createHkmeta,
           fetch_song_info: scanOptions.fetchSongInfo,
           fetch_lyrics: scanOptions.fetchLyrics,
+          lyrics_preference: scanOptions.lyricsPreference,
           regenerate: scanOptions.regenerate,
           generate_thumbnails: scanOptions.generateThumbnails,
         });
@@ -1093,6 +1164,7 @@ function LibrarySettings({ getSetting, setSetting }: SettingsSectionProps) {
         create_hkmeta: scanOptions.createHkmeta,
         fetch_song_info: scanOptions.fetchSongInfo,
         fetch_lyrics: scanOptions.fetchLyrics,
+        lyrics_preference: scanOptions.lyricsPreference,
         regenerate: scanOptions.regenerate,
         generate_thumbnails: scanOptions.generateThumbnails,
       });
@@ -1254,6 +1326,26 @@ function LibrarySettings({ getSetting, setSetting }: SettingsSectionProps) {
               Fetch lyrics from Lrclib
             </span>
           </label>
+          {scanOptions.fetchLyrics && (
+            <div className="flex items-center gap-2 pl-6">
+              <span className="text-sm text-gray-400">Lyrics to keep</span>
+              <SelectInput
+                value={scanOptions.lyricsPreference}
+                options={[
+                  { value: "synced_first", label: "Synced, else plain" },
+                  { value: "plain_first", label: "Plain, else synced" },
+                  { value: "synced_only", label: "Synced only" },
+                  { value: "plain_only", label: "Plain only" },
+                ]}
+                onChange={(value) =>
+                  setScanOptions((prev) => ({
+                    ...prev,
+                    lyricsPreference: value as LyricsPreference,
+                  }))
+                }
+              />
+            </div>
+          )}
           <label className="flex items-center gap-2 cursor-pointer">
             <input
               type="checkbox"
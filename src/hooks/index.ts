@@ -3,3 +3,4 @@ export { useMediaControls } from "./useMediaControls";
 export { useDisplayWatcher } from "./useDisplayWatcher";
 export { useUpdateCheck } from "./useUpdateCheck";
 export { useKeyboardShortcuts } from "./useKeyboardShortcuts";
+export { useLocalFileUrl } from "./useLocalFileUrl";
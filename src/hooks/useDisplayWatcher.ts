@@ -1,7 +1,7 @@
 import { useEffect } from "react";
 import { listen } from "@tauri-apps/api/event";
 import { displayManagerService, createLogger } from "../services";
-import { useDisplayStore } from "../stores";
+import { useDisplayStore, usePlayerStore } from "../stores";
 
 const log = createLogger("useDisplayWatcher");
 
@@ -17,6 +17,7 @@ export function useDisplayWatcher() {
     let mounted = true;
     let unlistenConfigChange: (() => void) | null = null;
     let unlistenSaveLayout: (() => void) | null = null;
+    let unlistenShouldPause: (() => void) | null = null;
 
     // Helper to check and restore saved layout for a config
     const checkAndRestoreSavedLayout = async (
@@ -86,6 +87,16 @@ export function useDisplayWatcher() {
         log.debug("Failed to set up save-display-layout listener", err);
       }
 
+      // Pause playback when the backend reports a removed display (setting-gated in Rust)
+      try {
+        unlistenShouldPause = await displayManagerService.onPlaybackShouldPause(() => {
+          if (!mounted) return;
+          usePlayerStore.getState().setIsPlaying(false);
+        });
+      } catch (err) {
+        log.debug("Failed to set up playback pause listener", err);
+      }
+
       // Listen for display configuration changes
       try {
         unlistenConfigChange =
@@ -121,6 +132,9 @@ export function useDisplayWatcher() {
       if (unlistenSaveLayout) {
         unlistenSaveLayout();
       }
+      if (unlistenShouldPause) {
+        unlistenShouldPause();
+      }
     };
   }, [setCurrentConfig, setPendingRestore, setShowRestoreDialog, saveCurrentLayout]);
 }
@@ -19,7 +19,7 @@ vi.mock("../stores", () => ({
   },
   useQueueStore: {
     getState: vi.fn(() => ({
-      playNext: vi.fn(),
+      playNext: vi.fn(() => Promise.resolve(null)),
       hasNext: vi.fn(() => false),
     })),
   },
@@ -162,12 +162,11 @@ export function useKeyboardShortcuts(options: KeyboardShortcutsOptions = {}) {
         log.debug("Keyboard: Next video");
         const { playNext, hasNext } = useQueueStore.getState();
         if (hasNext()) {
-          const nextItem = playNext();
-          if (nextItem) {
-            playVideo(nextItem.video).catch((err) => {
+          playNext()
+            .then((nextItem) => (nextItem ? playVideo(nextItem.video) : undefined))
+            .catch((err) => {
               log.error("Failed to play next video:", err);
             });
-          }
         }
         break;
       }
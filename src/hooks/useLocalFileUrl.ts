@@ -0,0 +1,38 @@
+import { useEffect, useState } from "react";
+import { getLocalFileUrl, notify } from "../stores";
+import { createLogger } from "../services";
+
+const log = createLogger("useLocalFileUrl");
+
+/**
+ * Asset URL for a local file, once the backend has confirmed it is inside a library
+ * folder. Undefined while checking, or if the file was rejected.
+ */
+export function useLocalFileUrl(
+  filePath: string | null | undefined,
+  notifyOnError = false
+): string | undefined {
+  const [resolved, setResolved] = useState<{ filePath: string; url: string } | null>(null);
+
+  useEffect(() => {
+    if (!filePath) return;
+
+    let cancelled = false;
+    getLocalFileUrl(filePath)
+      .then((url) => {
+        if (!cancelled) setResolved({ filePath, url });
+      })
+      .catch((err) => {
+        log.error(`Refusing to load local file ${filePath}:`, err);
+        if (!cancelled && notifyOnError) {
+          notify("error", "Can't play this file: it is missing or outside the library folders");
+        }
+      });
+
+    return () => {
+      cancelled = true;
+    };
+  }, [filePath, notifyOnError]);
+
+  return resolved && resolved.filePath === filePath ? resolved.url : undefined;
+}
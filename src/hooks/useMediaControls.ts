@@ -38,7 +38,7 @@ export function useMediaControls() {
       log.debug("No next track available");
       return;
     }
-    const nextItem = playNext();
+    const nextItem = await playNext();
     if (nextItem) {
       try {
         await playVideo(nextItem.video);
@@ -55,7 +55,7 @@ export function useMediaControls() {
       log.debug("No previous track available");
       return;
     }
-    const prevItem = playPrevious();
+    const prevItem = await playPrevious();
     if (prevItem) {
       try {
         await playVideo(prevItem.video);
@@ -0,0 +1,24 @@
+import { invoke } from "@tauri-apps/api/core";
+import { createLogger } from "./logger";
+
+const log = createLogger("ApiUserAgentService");
+
+/**
+ * User agent sent with MusicBrainz, Lrclib and YouTube API requests.
+ * MusicBrainz asks apps to include contact info, so self-hosters can set their own.
+ */
+export const apiUserAgentService = {
+  async get(): Promise<string> {
+    return await invoke<string>("get_api_user_agent");
+  },
+
+  /**
+   * Set a custom user agent, or pass null to restore the default.
+   * Rejects with a `validation` error if it is empty, too long, or not printable ASCII.
+   * Returns the user agent now in use.
+   */
+  async set(userAgent: string | null): Promise<string> {
+    log.info(`Setting API user agent: ${userAgent ?? "(default)"}`);
+    return await invoke<string>("set_api_user_agent", { userAgent });
+  },
+};
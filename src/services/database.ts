@@ -0,0 +1,20 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export type SchemaState = "UpToDate" | "NeedsMigration" | "TooNew";
+
+export interface SchemaStatus {
+  state: SchemaState;
+  stored_version: number;
+  expected_version: number;
+}
+
+export const databaseService = {
+  /** Rejects if the database was written by a newer version of the app */
+  async checkSchema(): Promise<SchemaStatus> {
+    return invoke<SchemaStatus>("db_check_schema");
+  },
+
+  async repairSchema(): Promise<SchemaStatus> {
+    return invoke<SchemaStatus>("db_repair_schema");
+  },
+};
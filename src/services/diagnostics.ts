@@ -0,0 +1,36 @@
+import { invoke } from "@tauri-apps/api/core";
+
+export interface BinPathStatus {
+  path: string;
+  exists: boolean;
+}
+
+/** What the app sees when looking for yt-dlp and ffmpeg */
+export interface ToolDiscovery {
+  process_path: string | null;
+  expanded_path: string;
+  ytdlp_path: string | null;
+  ffmpeg_path: string | null;
+  ffprobe_path: string | null;
+  search_paths: BinPathStatus[];
+}
+
+/** App, tool and database versions for the About screen (null = not installed) */
+export interface Versions {
+  app_version: string;
+  ytdlp_version: string | null;
+  ffmpeg_version: string | null;
+  ffprobe_version: string | null;
+  sqlite_version: string;
+  schema_version: number;
+}
+
+export const diagnosticsService = {
+  async toolDiscovery(): Promise<ToolDiscovery> {
+    return invoke<ToolDiscovery>("diagnostics_tool_discovery");
+  },
+
+  async getVersions(): Promise<Versions> {
+    return invoke<Versions>("get_versions");
+  },
+};
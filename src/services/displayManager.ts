@@ -41,6 +41,33 @@ export interface WindowState {
   is_fullscreen: boolean;
 }
 
+export interface MonitorInfo {
+  name: string | null;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  scale_factor: number;
+  is_primary: boolean;
+}
+
+export interface WindowPlacement {
+  display_id: number;
+  x: number;
+  y: number;
+  width: number;
+  height: number;
+  is_fullscreen: boolean;
+}
+
+/** Suggested control and player window placement for a karaoke setup */
+export interface KaraokeLayout {
+  control: WindowPlacement;
+  player: WindowPlacement;
+  /** Both windows share one display, side by side */
+  single_display: boolean;
+}
+
 export const displayManagerService = {
   /**
    * Get the current display configuration
@@ -50,6 +77,23 @@ export const displayManagerService = {
     return await invoke<DisplayConfiguration>("display_get_configuration");
   },
 
+  /**
+   * List connected monitors (works on all platforms)
+   */
+  async listMonitors(): Promise<MonitorInfo[]> {
+    log.debug("Listing monitors");
+    return await invoke<MonitorInfo[]>("window_list_monitors");
+  },
+
+  /**
+   * Suggest controls on the main display and the player fullscreen on the
+   * largest other display (or side by side when there is only one)
+   */
+  async suggestKaraokeLayout(): Promise<KaraokeLayout> {
+    log.debug("Suggesting karaoke layout");
+    return await invoke<KaraokeLayout>("display_suggest_karaoke_layout");
+  },
+
   /**
    * Save a display configuration to the database
    */
@@ -94,6 +138,16 @@ export const displayManagerService = {
     await invoke("display_delete_config", { configId });
   },
 
+  /**
+   * Merge a display config into another for the same monitors (e.g. after a
+   * resolution change). Window layouts the kept config lacks are moved over;
+   * the merged config is deleted. Returns the number of layouts moved.
+   */
+  async mergeConfigs(keepId: number, mergeId: number): Promise<number> {
+    log.info(`Merging display config ${mergeId} into ${keepId}`);
+    return await invoke<number>("display_merge_configs", { keepId, mergeId });
+  },
+
   /**
    * Save window state for a display configuration
    */
@@ -142,6 +196,18 @@ export const displayManagerService = {
     await invoke("window_clear_states", { displayConfigId });
   },
 
+  /**
+   * Listen for the backend asking to pause playback because a display was removed
+   */
+  async onPlaybackShouldPause(
+    callback: (config: DisplayConfiguration) => void
+  ): Promise<UnlistenFn> {
+    return await listen<DisplayConfiguration>("playback:should-pause", (event) => {
+      log.info(`Display removed (${event.payload.displays.length} left), pausing playback`);
+      callback(event.payload);
+    });
+  },
+
   /**
    * Listen for display configuration changes
    */
@@ -4,6 +4,20 @@ import type { FavoriteVideo, SingerFavorite } from "./session";
 
 const log = createLogger("FavoritesService");
 
+/** Result of importing favorites from a CSV song list */
+export interface FavoritesCsvImportResult {
+  imported: number;
+  already_favorited: number;
+  skipped: number;
+}
+
+export interface SingersImportResult {
+  singers_created: number;
+  /** Backup singers merged into an existing singer with the same unique name or name */
+  singers_merged: number;
+  favorites_imported: number;
+}
+
 export const favoritesService = {
   async addFavorite(
     singerId: number,
@@ -18,6 +32,11 @@ export const favoritesService = {
     await invoke("remove_favorite", { singerId, videoId });
   },
 
+  async dedupeSingerFavorites(singerId: number): Promise<number> {
+    log.info(`Deduplicating favorites for singer ${singerId}`);
+    return await invoke<number>("dedupe_singer_favorites", { singerId });
+  },
+
   async getSingerFavorites(singerId: number): Promise<SingerFavorite[]> {
     log.debug(`Getting favorites for singer ${singerId}`);
     return await invoke<SingerFavorite[]>("get_singer_favorites", { singerId });
@@ -39,4 +58,31 @@ export const favoritesService = {
     log.debug(`Checking favorites for video ${videoId}`);
     return await invoke<number[]>("check_video_favorites", { videoId });
   },
+
+  /** Import favorites from a CSV song list (columns: title, artist, youtube_id) */
+  async importFavoritesCsv(singerId: number, csvPath: string): Promise<FavoritesCsvImportResult> {
+    log.info(`Importing favorites for singer ${singerId} from ${csvPath}`);
+    return await invoke<FavoritesCsvImportResult>("import_favorites_csv", { singerId, csvPath });
+  },
+
+  /** Versioned JSON backup of all persistent singers and their favorites */
+  async exportSingersAndFavorites(): Promise<string> {
+    log.info("Exporting singers and favorites");
+    return await invoke<string>("export_singers_and_favorites");
+  },
+
+  /** Import a singers/favorites backup, merging into existing singers or replacing them */
+  async importSingersAndFavorites(json: string, merge: boolean): Promise<SingersImportResult> {
+    log.info(`Importing singers and favorites (merge: ${merge})`);
+    return await invoke<SingersImportResult>("import_singers_and_favorites", { json, merge });
+  },
+
+  /** Favorites the singer hasn't sung in their recent sessions, least recently sung first */
+  async suggestSongsForSinger(singerId: number, limit?: number): Promise<FavoriteVideo[]> {
+    log.debug(`Suggesting songs for singer ${singerId}`);
+    return await invoke<FavoriteVideo[]>("suggest_songs_for_singer", {
+      singerId,
+      limit: limit ?? null,
+    });
+  },
 };
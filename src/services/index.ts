@@ -1,26 +1,45 @@
 export { youtubeService } from "./youtube";
+export { searchService } from "./search";
+export type { CombinedSearchResult, CombinedSearchResults } from "./search";
 export { windowManager } from "./windowManager";
 export { keepAwakeService } from "./keepAwake";
+export { partyModeService } from "./partyMode";
+export { apiUserAgentService } from "./apiUserAgent";
+export { logLevelsService } from "./logLevels";
+export type { LogLevel, LogLevels, LogTarget } from "./logLevels";
 export { mediaControlsService } from "./mediaControls";
-export type { MediaControlsMetadata } from "./mediaControls";
+export type {
+  MediaControlsMetadata,
+  MediaControlsStatus,
+  MediaPlaybackState,
+} from "./mediaControls";
 export { displayManagerService } from "./displayManager";
 export type {
   DisplayInfo,
   DisplayConfiguration,
   SavedDisplayConfig,
   WindowState,
+  WindowPlacement,
+  KaraokeLayout,
 } from "./displayManager";
 export { logger, createLogger } from "./logger";
 export { authService } from "./auth";
 export type { AuthTokens, User } from "./auth";
 export { createAnonClient, createAuthenticatedClient, isSupabaseConfigured } from "./supabase";
 export { sessionService, HOSTED_SESSION_STATUS } from "./session";
-export type { Singer, Session, FavoriteVideo, SingerFavorite, HostedSessionStatus } from "./session";
+export type { Singer, Session, SessionSettings, PlaybackAdjustments, FavoriteVideo, SingerFavorite, HostedSessionStatus, GlobalStats, SongPlayCount, SingerActivity, SingerSongPreference } from "./session";
 export { queueService } from "./queue";
-export type { QueueItemData, QueueState } from "./queue";
+export type { QueueItemData, QueueState, RotationMode, UnavailableItem, UnavailableReason } from "./queue";
 export { favoritesService } from "./favorites";
+export type { FavoritesCsvImportResult, SingersImportResult } from "./favorites";
+export { setlistService } from "./setlist";
+export type { Setlist } from "./setlist";
 export { updateService } from "./update";
 export type { UpdateInfo } from "./update";
+export { databaseService } from "./database";
+export type { SchemaState, SchemaStatus } from "./database";
+export { diagnosticsService } from "./diagnostics";
+export type { BinPathStatus, ToolDiscovery, Versions } from "./diagnostics";
 export {
   hostedSessionService,
   getPersistedSessionId,
@@ -0,0 +1,28 @@
+import { invoke } from "@tauri-apps/api/core";
+import { createLogger } from "./logger";
+
+const log = createLogger("LogLevelsService");
+
+export type LogTarget = "stdout" | "webview" | "file";
+export type LogLevel = "off" | "error" | "warn" | "info" | "debug" | "trace";
+
+export interface LogLevels {
+  stdout: LogLevel;
+  webview: LogLevel;
+  file: LogLevel;
+}
+
+/**
+ * Backend log verbosity per output. Changes apply immediately and
+ * reset to the defaults (info, info, debug) when the app restarts.
+ */
+export const logLevelsService = {
+  async get(): Promise<LogLevels> {
+    return await invoke<LogLevels>("get_log_levels");
+  },
+
+  async set(target: LogTarget, level: LogLevel): Promise<void> {
+    log.info(`Setting ${target} log level to ${level}`);
+    await invoke("set_log_level", { target, level });
+  },
+};
@@ -11,7 +11,19 @@ export interface MediaControlsMetadata {
   thumbnailUrl?: string;
 }
 
+export type MediaPlaybackState = "stopped" | "playing" | "paused";
+
+/** Whether OS media controls initialized (they can fail on some Linux setups) */
+export interface MediaControlsStatus {
+  available: boolean;
+  playback_state: MediaPlaybackState | null;
+}
+
 export const mediaControlsService = {
+  async getStatus(): Promise<MediaControlsStatus> {
+    return await invoke<MediaControlsStatus>("media_controls_status");
+  },
+
   async updateMetadata(metadata: MediaControlsMetadata): Promise<void> {
     try {
       log.info(
@@ -0,0 +1,30 @@
+import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
+import { createLogger } from "./logger";
+
+const log = createLogger("PartyModeService");
+
+/**
+ * Party mode disables destructive actions (clearing the queue or history,
+ * saving, loading or deleting sessions, managing favorites) while guests are around.
+ * Guarded commands reject with a `forbidden` error while it is on.
+ */
+export const partyModeService = {
+  async get(): Promise<boolean> {
+    return await invoke<boolean>("get_party_mode");
+  },
+
+  async set(enabled: boolean): Promise<void> {
+    log.info(`Setting party mode: ${enabled}`);
+    await invoke("set_party_mode", { enabled });
+  },
+
+  /**
+   * Listen for party mode being turned on or off
+   */
+  async onChanged(callback: (enabled: boolean) => void): Promise<UnlistenFn> {
+    return await listen<boolean>("party-mode-changed", (event) => {
+      callback(event.payload);
+    });
+  },
+};
@@ -1,5 +1,8 @@
 import { invoke } from "@tauri-apps/api/core";
+import { listen, type UnlistenFn } from "@tauri-apps/api/event";
 import { createLogger } from "./logger";
+import type { Chapter } from "../types";
+import type { PlaybackAdjustments } from "./session";
 
 const log = createLogger("QueueService");
 
@@ -16,12 +19,49 @@ export interface QueueItemData {
   position: number;
   added_at: string;
   played_at?: string;
+  chapters?: Chapter[] | null;
+  /** This song's own tempo and key override (unset fields use the session defaults) */
+  playback_adjustments?: PlaybackAdjustments;
 }
 
 export interface QueueState {
   queue: QueueItemData[];
   history: QueueItemData[];
   history_index: number;
+  /** Item playing when state was saved (null if none or it was removed) */
+  current_item_id: string | null;
+  /** Session-wide playback rate and transpose for songs without their own override */
+  playback_defaults?: PlaybackAdjustments;
+}
+
+export interface QueueRuntime {
+  total_secs: number;
+  unknown_count: number;
+}
+
+/** Payload of the "queue:duration-backfill-progress" event */
+export interface DurationBackfillProgress {
+  processed: number;
+  total: number;
+  filled: number;
+}
+
+/** How queue_apply_rotation orders the queue */
+export type RotationMode = "fifo" | "fair" | "random";
+
+/** Why a pending queue item would fail to play */
+export type UnavailableReason = "file_missing" | "no_source" | "video_unavailable";
+
+export interface UnavailableItem {
+  item_id: string;
+  reason: UnavailableReason;
+}
+
+/** Library filters for picking random songs (snake_case, as sent to the backend) */
+export interface RandomLibraryFilters {
+  folder_id?: number | null;
+  has_lyrics?: boolean | null;
+  has_cdg?: boolean | null;
 }
 
 export const queueService = {
@@ -36,11 +76,43 @@ export const queueService = {
     await invoke("queue_remove_item", { itemId });
   },
 
+  /** Swap an item's video for another version, keeping its position and singers */
+  async replaceSource(itemId: string, newVideo: QueueItemData): Promise<void> {
+    log.debug(`Replacing source of queue item ${itemId} with ${newVideo.video_id}`);
+    await invoke("queue_replace_source", { itemId, newVideo });
+  },
+
   async reorder(itemId: string, newPosition: number): Promise<void> {
     log.debug(`Reordering queue item ${itemId} to position ${newPosition}`);
     await invoke("queue_reorder", { itemId, newPosition });
   },
 
+  /**
+   * Move a history item to a new position. The backend keeps history_index
+   * on the same song, so reload persisted state afterwards.
+   */
+  async reorderHistory(itemId: string, newPosition: number): Promise<void> {
+    log.debug(`Reordering history item ${itemId} to position ${newPosition}`);
+    await invoke("queue_reorder_history", { itemId, newPosition });
+  },
+
+  /**
+   * Add `count` random library songs matching `filters` to the end of the queue.
+   * Returns the added items (fewer if fewer songs match).
+   */
+  async addRandomFromLibrary(
+    count: number,
+    filters: RandomLibraryFilters = {},
+    seed?: number
+  ): Promise<QueueItemData[]> {
+    log.info(`Adding ${count} random library songs to queue`);
+    return await invoke<QueueItemData[]>("queue_add_random_from_library", {
+      count,
+      filters,
+      seed: seed ?? null,
+    });
+  },
+
   async clearQueue(): Promise<void> {
     log.info("Clearing queue");
     await invoke("queue_clear");
@@ -51,6 +123,31 @@ export const queueService = {
     await invoke("queue_fair_shuffle");
   },
 
+  /**
+   * Reorder the queue by the session's rotation mode. Pass a seed for a repeatable
+   * random order. Returns the number of items reordered.
+   */
+  async applyRotation(seed?: number): Promise<number> {
+    log.info("Applying queue rotation");
+    return await invoke<number>("queue_apply_rotation", { seed: seed ?? null });
+  },
+
+  /** Pending items in the order a fair shuffle would give them (the queue is not changed) */
+  async previewFairShuffle(): Promise<QueueItemData[]> {
+    log.debug("Previewing fair shuffle");
+    return await invoke<QueueItemData[]>("queue_preview_fair_shuffle");
+  },
+
+  async assignRoundRobin(singerIds: number[]): Promise<void> {
+    log.info(`Assigning ${singerIds.length} singers round-robin`);
+    await invoke("queue_assign_round_robin", { singerIds });
+  },
+
+  async setItemChapters(itemId: string, chapters: Chapter[]): Promise<Chapter[]> {
+    log.debug(`Setting ${chapters.length} chapters on item: ${itemId}`);
+    return await invoke<Chapter[]>("queue_set_item_chapters", { itemId, chapters });
+  },
+
   // History operations
   async moveToHistory(itemId: string): Promise<void> {
     log.debug(`Moving item to history: ${itemId}`);
@@ -77,12 +174,54 @@ export const queueService = {
     await invoke("queue_set_history_index", { index });
   },
 
+  /** The item "next" would play (next history item or head of the queue), without moving anything */
+  async resolveNext(): Promise<QueueItemData | null> {
+    return await invoke<QueueItemData | null>("queue_resolve_next");
+  },
+
+  /** The item "previous" would play (null at the start of history), without moving anything */
+  async resolvePrev(): Promise<QueueItemData | null> {
+    return await invoke<QueueItemData | null>("queue_resolve_prev");
+  },
+
+  /** Listen for the scheduled rotation after `auto_shuffle_after_adds` songs (payload: session id) */
+  async onAutoShuffled(callback: (sessionId: number) => void): Promise<UnlistenFn> {
+    return await listen<number>("queue:auto-shuffled", (event) => {
+      callback(event.payload);
+    });
+  },
+
+  async setCurrentItem(itemId: string | null): Promise<void> {
+    log.debug(`Setting current item to ${itemId}`);
+    await invoke("queue_set_current_item", { itemId });
+  },
+
   // Fair queue
   async computeFairPosition(singerId: number | null): Promise<number> {
     log.debug(`Computing fair position for singer: ${singerId}`);
     return await invoke<number>("queue_compute_fair_position", { singerId });
   },
 
+  // Runtime
+  async getTotalRuntime(): Promise<QueueRuntime> {
+    log.debug("Computing queue total runtime");
+    return await invoke<QueueRuntime>("queue_total_runtime");
+  },
+
+  async backfillDurations(): Promise<number> {
+    log.info("Backfilling missing queue durations");
+    return await invoke<number>("queue_backfill_durations");
+  },
+
+  /**
+   * Find pending items that would fail to play. YouTube videos are only looked up
+   * when checkOnline is set.
+   */
+  async validateItems(checkOnline = false): Promise<UnavailableItem[]> {
+    log.info(`Validating queue items (online: ${checkOnline})`);
+    return await invoke<UnavailableItem[]>("queue_validate_items", { checkOnline });
+  },
+
   // State recovery
   async getState(): Promise<QueueState | null> {
     log.debug("Fetching queue state");
@@ -0,0 +1,32 @@
+import { invoke } from "@tauri-apps/api/core";
+import type { SearchResult } from "../types";
+import type { LibraryVideo } from "../stores/libraryStore";
+
+export type CombinedSearchResult =
+  | ({ source: "library" } & LibraryVideo)
+  | ({ source: "youtube" } & SearchResult);
+
+export interface CombinedSearchResults {
+  /** Library matches first, then YouTube results not already in the library */
+  results: CombinedSearchResult[];
+  library_error: string | null;
+  youtube_error: string | null;
+}
+
+export const searchService = {
+  /**
+   * Search the local library and YouTube together
+   * YouTube results already present in the library (by youtube_id) are omitted
+   */
+  async searchAll(
+    query: string,
+    options: { maxResults?: number; includeYoutube?: boolean; includeLibrary?: boolean } = {}
+  ): Promise<CombinedSearchResults> {
+    return invoke<CombinedSearchResults>("search_all", {
+      query,
+      maxResults: options.maxResults ?? null,
+      includeYoutube: options.includeYoutube ?? true,
+      includeLibrary: options.includeLibrary ?? true,
+    });
+  },
+};
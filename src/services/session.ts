@@ -1,5 +1,6 @@
 import { invoke } from "@tauri-apps/api/core";
 import { createLogger } from "./logger";
+import type { RotationMode } from "./queue";
 
 const log = createLogger("SessionService");
 
@@ -81,6 +82,63 @@ export interface Session {
   hosted_session_status?: HostedSessionStatus;
 }
 
+/** Per-session playback defaults; unset fields fall back to the global settings */
+export interface SessionSettings {
+  /** Volume (0-100) applied when the session is activated */
+  default_volume?: number | null;
+  autoplay?: boolean | null;
+  show_lyrics?: boolean | null;
+}
+
+/**
+ * Tempo and key changes: a song's own override or the session-wide baseline.
+ * Unset fields mean no change; a song's override beats the session default.
+ */
+export interface PlaybackAdjustments {
+  /** Speed multiplier, 0.5-2.0 (1.0 is normal speed) */
+  playback_rate?: number | null;
+  /** Key change in semitones, -12 to 12 */
+  transpose?: number | null;
+}
+
+/**
+ * A singer's preferred key and tempo for one song, applied to the queue item when
+ * they are the first singer assigned to it (for duets, the adder's preference wins).
+ */
+export interface SingerSongPreference {
+  singer_id: number;
+  video_id: string;
+  /** Key change in semitones, -12 to 12 */
+  pitch_semitones: number | null;
+  /** Speed as a percentage of normal, 50-200 */
+  tempo_percent: number | null;
+  notes: string | null;
+}
+
+/** A song's play count across all sessions */
+export interface SongPlayCount {
+  video_id: string;
+  title: string;
+  artist: string | null;
+  play_count: number;
+}
+
+export interface SingerActivity {
+  singer_id: number;
+  name: string;
+  song_count: number;
+}
+
+/** All-time statistics across sessions (trashed sessions excluded) */
+export interface GlobalStats {
+  total_sessions: number;
+  total_songs_played: number;
+  total_unique_singers: number;
+  total_sung_hours: number;
+  top_songs: SongPlayCount[];
+  most_active_singer: SingerActivity | null;
+}
+
 export const sessionService = {
   // Singer CRUD
   async createSinger(
@@ -129,6 +187,11 @@ export const sessionService = {
     });
   },
 
+  async setSingerPersistent(singerId: number, persistent: boolean, force = false): Promise<Singer> {
+    log.info(`Setting singer ${singerId} persistent: ${persistent}`);
+    return await invoke<Singer>("set_singer_persistent", { singerId, persistent, force });
+  },
+
   async getPersistentSingers(): Promise<Singer[]> {
     log.debug("Fetching persistent singers");
     return await invoke<Singer[]>("get_persistent_singers");
@@ -172,6 +235,47 @@ export const sessionService = {
     return await invoke<Singer[]>("get_session_singers", { sessionId });
   },
 
+  /** Sessions the singer took part in, most recent first (excluding trashed sessions) */
+  async getSingerSessions(singerId: number): Promise<Session[]> {
+    log.debug(`Fetching sessions for singer ${singerId}`);
+    return await invoke<Session[]>("get_singer_sessions", { singerId });
+  },
+
+  async getSingerSongPreference(
+    singerId: number,
+    videoId: string
+  ): Promise<SingerSongPreference | null> {
+    return await invoke<SingerSongPreference | null>("get_singer_song_preference", {
+      singerId,
+      videoId,
+    });
+  },
+
+  /** Store a singer's preference for a song; leaving every field empty clears it */
+  async setSingerSongPreference(
+    preference: SingerSongPreference
+  ): Promise<SingerSongPreference | null> {
+    log.info(`Setting singer ${preference.singer_id} preference for ${preference.video_id}`);
+    return await invoke<SingerSongPreference | null>("set_singer_song_preference", {
+      preference,
+    });
+  },
+
+  /** Session singers who have no song in the session's history yet, in join order */
+  async getWaitingSingers(sessionId: number): Promise<Singer[]> {
+    log.debug(`Fetching waiting singers for session ${sessionId}`);
+    return await invoke<Singer[]>("get_waiting_singers", { sessionId });
+  },
+
+  /**
+   * Write a Markdown "who sang what" sheet per singer into outputDir (absolute,
+   * existing). Returns the written file paths.
+   */
+  async exportSingerSheets(sessionId: number, outputDir: string): Promise<string[]> {
+    log.info(`Exporting singer sheets for session ${sessionId}`);
+    return await invoke<string[]>("export_singer_sheets", { sessionId, outputDir });
+  },
+
   // Queue-singer assignments
   async assignSingerToQueueItem(
     queueItemId: string,
@@ -199,25 +303,130 @@ export const sessionService = {
     await invoke("clear_queue_item_singers", { queueItemId });
   },
 
+  async checkSingerRepeat(singerId: number, videoId: string): Promise<boolean> {
+    log.debug(`Checking if singer ${singerId} already sang ${videoId}`);
+    return await invoke<boolean>("check_singer_repeat", { singerId, videoId });
+  },
+
   // Session management
   async getRecentSessions(limit?: number): Promise<Session[]> {
     log.debug(`Fetching recent sessions (limit: ${limit || 10})`);
     return await invoke<Session[]>("get_recent_sessions", { limit: limit || null });
   },
 
+  async getGlobalStats(): Promise<GlobalStats> {
+    log.debug("Fetching global stats");
+    return await invoke<GlobalStats>("get_global_stats");
+  },
+
   async renameSession(sessionId: number, name: string): Promise<Session> {
     log.info(`Renaming session ${sessionId} to: ${name}`);
     return await invoke<Session>("rename_session", { sessionId, name });
   },
 
+  /** Backfill a past session's times (ISO 8601; the active session can't be edited) */
+  async setSessionTimes(sessionId: number, startedAt: string, endedAt?: string): Promise<Session> {
+    log.info(`Setting session ${sessionId} times: ${startedAt} - ${endedAt ?? "open"}`);
+    return await invoke<Session>("set_session_times", {
+      sessionId,
+      startedAt,
+      endedAt: endedAt ?? null,
+    });
+  },
+
+  async nameActiveSession(name: string): Promise<Session> {
+    log.info(`Naming active session: ${name}`);
+    return await invoke<Session>("name_active_session", { name });
+  },
+
+  async getSessionSettings(sessionId: number): Promise<SessionSettings> {
+    return await invoke<SessionSettings>("get_session_settings", { sessionId });
+  },
+
+  async setSessionSettings(sessionId: number, settings: SessionSettings): Promise<SessionSettings> {
+    log.info(`Setting session ${sessionId} settings`);
+    return await invoke<SessionSettings>("set_session_settings", { sessionId, settings });
+  },
+
+  async getPlaybackDefaults(sessionId: number): Promise<PlaybackAdjustments> {
+    return await invoke<PlaybackAdjustments>("get_session_playback_defaults", { sessionId });
+  },
+
+  async setPlaybackDefaults(
+    sessionId: number,
+    defaults: PlaybackAdjustments
+  ): Promise<PlaybackAdjustments> {
+    log.info(`Setting session ${sessionId} playback defaults`);
+    return await invoke<PlaybackAdjustments>("set_session_playback_defaults", {
+      sessionId,
+      defaults,
+    });
+  },
+
+  /** Whether the active session keeps its queue in fair order automatically */
+  async getAutoShuffle(): Promise<boolean> {
+    return await invoke<boolean>("get_auto_shuffle");
+  },
+
+  /** Turn automatic fair shuffling of the active session's queue on or off */
+  async setAutoShuffle(enabled: boolean): Promise<void> {
+    log.info(`Setting auto-shuffle: ${enabled}`);
+    await invoke("set_auto_shuffle", { enabled });
+  },
+
+  /** How the active session's queue is reordered by queue rotation */
+  async getRotationMode(): Promise<RotationMode> {
+    return await invoke<RotationMode>("get_rotation_mode");
+  },
+
+  async setRotationMode(mode: RotationMode): Promise<void> {
+    log.info(`Setting rotation mode: ${mode}`);
+    await invoke("set_rotation_mode", { mode });
+  },
+
+  /** The active session's last search query (null if none), to restore the search box */
+  async getLastSearch(): Promise<string | null> {
+    return await invoke<string | null>("get_last_search");
+  },
+
+  /** Remember the active session's last search query; an empty query clears it */
+  async setLastSearch(query: string): Promise<void> {
+    await invoke("set_last_search", { query });
+  },
+
+  /** Resume the last session that still has queued songs (null if there is none) */
+  async recoverLastSession(): Promise<Session | null> {
+    log.info("Recovering last session");
+    return await invoke<Session | null>("recover_last_session");
+  },
+
   async loadSession(sessionId: number): Promise<Session> {
     log.info(`Loading session: ${sessionId}`);
     return await invoke<Session>("load_session", { sessionId });
   },
 
-  async deleteSession(sessionId: number): Promise<void> {
-    log.info(`Deleting session: ${sessionId}`);
-    await invoke("delete_session", { sessionId });
+  /**
+   * Move a session to the trash (restorable), or delete it permanently with force
+   */
+  async deleteSession(sessionId: number, force = false): Promise<void> {
+    log.info(`Deleting session: ${sessionId} (force: ${force})`);
+    await invoke("delete_session", { sessionId, force });
+  },
+
+  async restoreSession(sessionId: number): Promise<void> {
+    log.info(`Restoring session: ${sessionId}`);
+    await invoke("restore_session", { sessionId });
+  },
+
+  async purgeDeletedSessions(olderThanDays: number): Promise<number> {
+    log.info(`Purging sessions deleted more than ${olderThanDays} days ago`);
+    return await invoke<number>("purge_deleted_sessions", { olderThanDays });
+  },
+
+  /** Delete played history older than `days` from all inactive sessions */
+  async purgeOldHistory(days: number): Promise<number> {
+    log.info(`Purging history played more than ${days} days ago`);
+    return await invoke<number>("purge_old_history", { days });
   },
 
   // Active singer management
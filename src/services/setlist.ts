@@ -0,0 +1,41 @@
+import { invoke } from "@tauri-apps/api/core";
+import { createLogger } from "./logger";
+import type { QueueItemData } from "./queue";
+
+const log = createLogger("SetlistService");
+
+/** A named, reusable list of songs saved from a queue */
+export interface Setlist {
+  id: number;
+  name: string;
+  created_at: string;
+  item_count: number;
+}
+
+export const setlistService = {
+  /**
+   * Save the active session's queue, or the given queue items in that order, as a setlist
+   */
+  async saveSetlist(name: string, itemIds?: string[]): Promise<Setlist> {
+    log.info(`Saving setlist: ${name}`);
+    return await invoke<Setlist>("save_setlist", { name, itemIds: itemIds ?? null });
+  },
+
+  /**
+   * Append a setlist's songs to the active queue. Returns the new queue items (fresh ids).
+   */
+  async loadSetlist(setlistId: number): Promise<QueueItemData[]> {
+    log.info(`Loading setlist ${setlistId}`);
+    return await invoke<QueueItemData[]>("load_setlist", { setlistId });
+  },
+
+  async getSetlists(): Promise<Setlist[]> {
+    log.debug("Getting setlists");
+    return await invoke<Setlist[]>("get_setlists");
+  },
+
+  async deleteSetlist(setlistId: number): Promise<void> {
+    log.info(`Deleting setlist ${setlistId}`);
+    await invoke("delete_setlist", { setlistId });
+  },
+};
@@ -1,16 +1,62 @@
 import { invoke } from "@tauri-apps/api/core";
-import type { SearchResult, StreamInfo, VideoInfo } from "../types";
+import type {
+  Chapter,
+  PlaybackSource,
+  SearchResult,
+  StreamFormat,
+  StreamInfo,
+  VideoInfo,
+  YouTubeBlock,
+  YouTubeBlockKind,
+  YouTubeSearchResponse,
+} from "../types";
 
 export type SearchMethod = "api" | "ytdlp" | "none";
 
+export interface CachedStream {
+  video_id: string;
+  format_id: string | null;
+  resolved_at: string;
+  expires_at: string;
+}
+
+export interface VideoInfoBatchEntry {
+  video_id: string;
+  info: VideoInfo | null;
+  error: string | null;
+}
+
+export type YouTubeHealthStatus =
+  | "healthy"
+  | "needs_update"
+  | "network_error"
+  | "not_installed"
+  | "unknown";
+
+export interface YouTubeHealth {
+  status: YouTubeHealthStatus;
+  message: string | null;
+  checked_at: string;
+}
+
 export const youtubeService = {
   /**
-   * Search using yt-dlp (legacy method)
+   * Search using the configured method (API with yt-dlp fallback)
+   * Pass the returned next_page_token to fetch the next page of API results.
+   * Without maxResults, the "default_search_results" setting is used.
+   * With karaokeOnly, results that look like original recordings are dropped.
    */
-  async search(query: string, maxResults = 10): Promise<SearchResult[]> {
-    return invoke<SearchResult[]>("youtube_search", {
+  async search(
+    query: string,
+    maxResults?: number,
+    pageToken?: string,
+    karaokeOnly?: boolean
+  ): Promise<YouTubeSearchResponse> {
+    return invoke<YouTubeSearchResponse>("youtube_search", {
       query,
-      maxResults,
+      maxResults: maxResults ?? null,
+      pageToken: pageToken ?? null,
+      karaokeOnly: karaokeOnly ?? null,
     });
   },
 
@@ -41,18 +87,89 @@ export const youtubeService = {
     return invoke<SearchMethod>("youtube_get_search_method");
   },
 
-  async getStreamUrl(videoId: string): Promise<StreamInfo> {
-    return invoke<StreamInfo>("youtube_get_stream_url", { videoId });
+  /**
+   * Hide search results whose channel (or title, for "keyword") contains pattern
+   * Returns the updated blocklist
+   */
+  async addBlock(pattern: string, kind: YouTubeBlockKind): Promise<YouTubeBlock[]> {
+    return invoke<YouTubeBlock[]>("youtube_add_block", { pattern, kind });
+  },
+
+  async listBlocks(): Promise<YouTubeBlock[]> {
+    return invoke<YouTubeBlock[]>("youtube_list_blocks");
+  },
+
+  /**
+   * Get a stream URL, optionally for a specific format from listFormats()
+   */
+  async getStreamUrl(videoId: string, formatId?: string): Promise<StreamInfo> {
+    return invoke<StreamInfo>("youtube_get_stream_url", { videoId, formatId: formatId ?? null });
+  },
+
+  /** Cached stream URLs (for debugging stale playback; signed URLs are not included) */
+  async getStreamCacheStatus(): Promise<CachedStream[]> {
+    return invoke<CachedStream[]>("youtube_stream_cache_status");
+  },
+
+  /** Drop cached stream URLs so they are re-resolved; returns entries removed */
+  async clearStreamCache(): Promise<number> {
+    return invoke<number>("youtube_clear_stream_cache");
+  },
+
+  async listFormats(videoId: string): Promise<StreamFormat[]> {
+    return invoke<StreamFormat[]>("youtube_list_formats", { videoId });
+  },
+
+  /**
+   * Get a video's chapters for section markers (empty if the uploader defined none)
+   */
+  async getChapters(videoId: string): Promise<Chapter[]> {
+    return invoke<Chapter[]>("youtube_get_chapters", { videoId });
+  },
+
+  /**
+   * Resolve a song to a matching local library file, falling back to a YouTube stream URL
+   */
+  async resolvePlaybackSource(
+    videoId: string,
+    title: string,
+    artist?: string | null
+  ): Promise<PlaybackSource> {
+    return invoke<PlaybackSource>("resolve_playback_source", {
+      videoId,
+      title,
+      artist: artist ?? null,
+    });
+  },
+
+  /**
+   * Canonicalize a local file path, rejecting files outside the library folders
+   */
+  async resolveLocalFile(filePath: string): Promise<string> {
+    return invoke<string>("resolve_local_file", { filePath });
   },
 
   async getVideoInfo(videoId: string): Promise<VideoInfo> {
     return invoke<VideoInfo>("youtube_get_info", { videoId });
   },
 
+  /**
+   * Get info for many videos at once (Data API when configured, otherwise yt-dlp).
+   * Entries follow the input order; failed lookups have `info: null` and an error.
+   */
+  async getVideoInfoBatch(videoIds: string[]): Promise<VideoInfoBatchEntry[]> {
+    return invoke<VideoInfoBatchEntry[]>("youtube_get_info_batch", { videoIds });
+  },
+
   async checkAvailable(): Promise<boolean> {
     return invoke<boolean>("youtube_check_available");
   },
 
+  /** Run (or reuse a recent) reference search to detect yt-dlp breakage */
+  async healthCheck(force = false): Promise<YouTubeHealth> {
+    return invoke<YouTubeHealth>("youtube_health_check", { force });
+  },
+
   async installYtDlp(method: "brew" | "pip" | "curl"): Promise<{ success: boolean; message: string; output: string }> {
     return invoke<{ success: boolean; message: string; output: string }>("youtube_install_ytdlp", { method });
   },
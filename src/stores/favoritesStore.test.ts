@@ -30,6 +30,7 @@ vi.mock("./queueStore", () => ({
       addToQueue: vi.fn(() => ({ id: "queue-item-1" })),
     })),
   },
+  flushPendingOperations: vi.fn(() => Promise.resolve()),
 }));
 
 vi.mock("./sessionStore", () => ({
@@ -7,7 +7,7 @@ import {
   type FavoriteVideo,
   type SingerFavorite,
 } from "../services";
-import { useQueueStore } from "./queueStore";
+import { useQueueStore, flushPendingOperations } from "./queueStore";
 import { useSessionStore } from "./sessionStore";
 
 const log = createLogger("FavoritesStore");
@@ -198,7 +198,9 @@ export const useFavoritesStore = create<FavoritesState>((set, get) => ({
           filePath: favorite.video.file_path,
         });
 
-        // Auto-assign the singer to this queue item
+        // Make sure the item is stored before assigning, so the backend can apply
+        // the singer's key and tempo preference for the song
+        await flushPendingOperations();
         await sessionStore.assignSingerToQueueItem(queueItem.id, singerId);
       }
 
@@ -2,6 +2,8 @@ export {
   usePlayerStore,
   getStreamUrlWithCache,
   invalidatePrefetchIfStale,
+  getLocalFileUrl,
+  getValidatedLocalFileUrl,
   playVideo,
   stopVideo,
   pausePlayback,
@@ -37,6 +39,7 @@ export {
   type LibraryFolder,
   type LibraryVideo,
   type ScanOptions,
+  type LyricsPreference,
   type ScanResult,
   type LibraryStats,
   type SearchMode,
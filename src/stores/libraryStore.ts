@@ -25,6 +25,7 @@ export interface LibraryFolder {
   name: string;
   last_scan_at: string | null;
   file_count: number;
+  filename_pattern: string | null;
 }
 
 export interface LibraryVideo {
@@ -39,14 +40,82 @@ export interface LibraryVideo {
   youtube_id: string | null;
   is_available: boolean;
   thumbnail_path: string | null;
+  aspect_ratio: string | null;
+  /** Thumbnail to display: the local one when generated, else YouTube's */
+  thumbnail: ThumbnailSource;
+  /** The other available thumbnail, if any */
+  thumbnail_alternative: ThumbnailSource | null;
 }
 
+export type ThumbnailSource =
+  | { kind: "local"; path: string }
+  | { kind: "youtube"; url: string }
+  | { kind: "none" };
+
+/** A file whose .hkmeta.json duration differs from its real length by over 15% */
+export interface MetadataDurationMismatch {
+  file_path: string;
+  stored_duration: number;
+  actual_duration: number;
+  /** Difference as a fraction of the actual duration (0.2 = 20%) */
+  difference_ratio: number;
+}
+
+/** Result of generating thumbnails without a full scan */
+export interface ThumbResult {
+  generated: number;
+  failed: number;
+  /** Videos that already had a thumbnail (when not regenerating) */
+  skipped: number;
+}
+
+/** Quick check of a folder before adding it to the library */
+export interface FolderProbe {
+  path: string;
+  video_count: number;
+  /** Counting stopped at the limit; estimated_total is extrapolated */
+  count_capped: boolean;
+  estimated_total: number;
+  avg_stat_ms: number;
+  /** Slow enough to suggest a network mount */
+  is_slow: boolean;
+  metadata_writable: boolean;
+  /** Rough time to fetch song info for every file during a scan */
+  estimated_fetch_secs: number;
+}
+
+export interface BulkTagResult {
+  updated: number;
+  /** Files that already had the requested tags */
+  unchanged: number;
+}
+
+export interface MetadataMergeResult {
+  videos_migrated: number;
+  hkmeta_copied: number;
+  thumbnails_copied: number;
+  skipped_newer: number;
+}
+
+/** Result of moving legacy sidecar metadata into .homekaraoke */
+export interface MigrationResult {
+  moved: number;
+  discarded_older: number;
+  failed: number;
+}
+
+export type LyricsPreference = "synced_first" | "plain_first" | "synced_only" | "plain_only";
+
 export interface ScanOptions {
   create_hkmeta: boolean;
   fetch_song_info: boolean;
   fetch_lyrics: boolean;
   regenerate: boolean;
   generate_thumbnails: boolean;
+  /** Companion subfolder checked for .lrc/.txt lyrics (defaults to "Lyrics") */
+  lyrics_subfolder?: string | null;
+  /** Whether to store synced (LRC) or plain lyrics (defaults to "synced_first") */
+  lyrics_preference?: LyricsPreference;
 }
 
 export interface ScanResult {
@@ -58,6 +127,46 @@ export interface ScanResult {
   thumbnails_failed: number;
   errors: string[];
   duration_ms: number;
+  /** Files already processed by an interrupted scan (resumed scans only) */
+  files_skipped: number;
+  /** The scan was cancelled before finishing; it can be resumed */
+  cancelled: boolean;
+}
+
+/** Payload of the `library:scan-progress` event, emitted while fetching metadata */
+export interface ScanMetadataProgress {
+  folder_id: number;
+  fetched: number;
+  total: number;
+  title: string;
+  artist: string | null;
+  estimated_remaining_secs: number;
+}
+
+/** Saved progress of an interrupted folder scan */
+export interface ScanCheckpoint {
+  folder_id: number;
+  last_file_path: string;
+  files_processed: number;
+  options: ScanOptions;
+}
+
+/** A Lrclib match offered when automatic lyrics matching picked the wrong song */
+export interface LyricsCandidate {
+  id: number;
+  track_name: string;
+  artist_name: string;
+  album_name: string | null;
+  /** Duration in seconds */
+  duration: number | null;
+  has_synced_lyrics: boolean;
+  has_plain_lyrics: boolean;
+}
+
+export interface LyricsResult {
+  synced_lyrics: string | null;
+  plain_lyrics: string | null;
+  duration: number | null;
 }
 
 export interface LibraryStats {
@@ -66,6 +175,24 @@ export interface LibraryStats {
   last_scan_at: string | null;
 }
 
+/** How out of date a folder's last scan is, estimated from a sample of files */
+export interface FolderCacheStatus {
+  folder_id: number;
+  name: string;
+  last_scan_at: string | null;
+  available: boolean;
+  scanned_files: number;
+  current_files: number;
+  sampled: number;
+  changed: number;
+  stale_fraction: number;
+}
+
+export interface CacheStatus {
+  folders: FolderCacheStatus[];
+  stale_fraction: number;
+}
+
 export type SearchMode = "youtube" | "local";
 
 interface LibraryState {
@@ -94,13 +221,28 @@ interface LibraryState {
   loadFolders: () => Promise<void>;
   addFolder: (path: string) => Promise<LibraryFolder>;
   removeFolder: (folderId: number) => Promise<void>;
+  relocateFolder: (folderId: number, newPath: string) => Promise<LibraryFolder>;
   scanFolder: (folderId: number, options?: Partial<ScanOptions>) => Promise<ScanResult>;
   scanAll: (options?: Partial<ScanOptions>) => Promise<ScanResult[]>;
+  getScanCheckpoint: (folderId: number) => Promise<ScanCheckpoint | null>;
+  resumeScan: (folderId: number) => Promise<ScanResult>;
+  cancelScan: () => Promise<void>;
   searchLibrary: (query: string, limit?: number) => Promise<void>;
   clearSearchResults: () => void;
   loadStats: () => Promise<void>;
   checkFileAvailable: (filePath: string) => Promise<boolean>;
   getCachedAvailability: (filePath: string) => boolean | undefined;
+  searchLyricsCandidates: (title: string, artist?: string | null) => Promise<LyricsCandidate[]>;
+  fetchLyricsById: (lrclibId: number) => Promise<LyricsResult | null>;
+  setLyrics: (filePath: string, content: string, format: "lrc" | "plain", lock: boolean) => Promise<void>;
+  mergeFolderMetadata: (fromFolderId: number, intoFolderId: number) => Promise<MetadataMergeResult>;
+  migrateLegacyMetadata: (folderId: number) => Promise<MigrationResult>;
+  auditDurations: (folderId?: number) => Promise<MetadataDurationMismatch[]>;
+  generateThumbnails: (folderId?: number, regenerate?: boolean) => Promise<ThumbResult>;
+  getThumbnailDataUrl: (source: ThumbnailSource, download?: boolean) => Promise<string | null>;
+  bulkTag: (filePaths: string[], addTags: string[], removeTags: string[]) => Promise<BulkTagResult>;
+  probeFolder: (path: string) => Promise<FolderProbe>;
+  getCacheStatus: () => Promise<CacheStatus>;
 }
 
 const DEFAULT_SCAN_OPTIONS: ScanOptions = {
@@ -180,6 +322,27 @@ export const useLibraryStore = create<LibraryState>((set, get) => ({
     }
   },
 
+  relocateFolder: async (folderId: number, newPath: string) => {
+    log.info(`Relocating folder ${folderId} to: ${newPath}`);
+
+    try {
+      const folder = await invoke<LibraryFolder>("library_relocate_folder", {
+        folderId,
+        newPath,
+      });
+
+      set((state) => ({
+        folders: state.folders.map((f) => (f.id === folderId ? folder : f)),
+      }));
+
+      log.info(`Relocated folder ${folderId} to: ${folder.path}`);
+      return folder;
+    } catch (error) {
+      log.error("Failed to relocate folder:", error);
+      throw error;
+    }
+  },
+
   scanFolder: async (folderId: number, options?: Partial<ScanOptions>) => {
     const folder = get().folders.find((f) => f.id === folderId);
     if (!folder) {
@@ -220,6 +383,45 @@ export const useLibraryStore = create<LibraryState>((set, get) => ({
     }
   },
 
+  getScanCheckpoint: async (folderId: number) => {
+    return await invoke<ScanCheckpoint | null>("library_get_scan_checkpoint", { folderId });
+  },
+
+  cancelScan: async () => {
+    log.info("Cancelling library scan");
+    await invoke("library_cancel_scan");
+  },
+
+  resumeScan: async (folderId: number) => {
+    log.info(`Resuming scan of folder: ${folderId}`);
+    set({ isScanning: true, scanProgress: { current: 0, total: 1 } });
+
+    try {
+      const result = await invoke<ScanResult>("library_resume_scan", { folderId });
+
+      log.info(
+        `Resumed scan complete: ${result.files_found} files found, ${result.files_skipped} already done`
+      );
+
+      set((state) => ({
+        folders: state.folders.map((f) =>
+          f.id === folderId
+            ? { ...f, file_count: result.files_found, last_scan_at: new Date().toISOString() }
+            : f
+        ),
+        isScanning: false,
+        scanProgress: null,
+        lastScanCompletedAt: Date.now(),
+      }));
+
+      return result;
+    } catch (error) {
+      log.error("Failed to resume scan:", error);
+      set({ isScanning: false, scanProgress: null });
+      throw error;
+    }
+  },
+
   scanAll: async (options?: Partial<ScanOptions>) => {
     const { folders } = get();
     log.info(`Scanning all ${folders.length} folders`);
@@ -263,6 +465,7 @@ export const useLibraryStore = create<LibraryState>((set, get) => ({
         query,
         limit,
         includeLyrics,
+        dedupe: true,
       });
 
       log.debug(`Found ${results.length} results`);
@@ -349,4 +552,84 @@ export const useLibraryStore = create<LibraryState>((set, get) => ({
 
     return cached.available;
   },
+
+  searchLyricsCandidates: async (title: string, artist?: string | null) => {
+    return await invoke<LyricsCandidate[]>("search_lyrics_candidates", {
+      title,
+      artist: artist ?? null,
+    });
+  },
+
+  fetchLyricsById: async (lrclibId: number) => {
+    log.info(`Fetching lyrics for Lrclib id ${lrclibId}`);
+    return await invoke<LyricsResult | null>("fetch_lyrics_by_id", { lrclibId });
+  },
+
+  setLyrics: async (filePath: string, content: string, format: "lrc" | "plain", lock: boolean) => {
+    log.info(`Setting lyrics for ${filePath} (locked: ${lock})`);
+    await invoke("library_set_lyrics", { filePath, content, format, lock });
+  },
+
+  mergeFolderMetadata: async (fromFolderId: number, intoFolderId: number) => {
+    log.info(`Merging metadata from folder ${fromFolderId} into ${intoFolderId}`);
+    const result = await invoke<MetadataMergeResult>("library_merge_folder_metadata", {
+      fromFolderId,
+      intoFolderId,
+    });
+    log.info(`Migrated metadata for ${result.videos_migrated} videos`);
+    return result;
+  },
+
+  migrateLegacyMetadata: async (folderId: number) => {
+    log.info(`Migrating legacy metadata in folder ${folderId}`);
+    const result = await invoke<MigrationResult>("library_migrate_legacy_metadata", { folderId });
+    log.info(
+      `Moved ${result.moved} legacy files (${result.discarded_older} older discarded, ${result.failed} failed)`
+    );
+    return result;
+  },
+
+  auditDurations: async (folderId?: number) => {
+    log.info(`Auditing stored durations (folder: ${folderId ?? "all"})`);
+    const mismatches = await invoke<MetadataDurationMismatch[]>("library_audit_durations", {
+      folderId: folderId ?? null,
+    });
+    log.info(`Found ${mismatches.length} files with suspicious durations`);
+    return mismatches;
+  },
+
+  generateThumbnails: async (folderId?: number, regenerate = false) => {
+    log.info(`Generating thumbnails (folder: ${folderId ?? "all"}, regenerate: ${regenerate})`);
+    const result = await invoke<ThumbResult>("library_generate_thumbnails", {
+      folderId: folderId ?? null,
+      regenerate,
+    });
+    log.info(
+      `Generated ${result.generated} thumbnails (${result.failed} failed, ${result.skipped} skipped)`
+    );
+    return result;
+  },
+
+  getThumbnailDataUrl: async (source: ThumbnailSource, download = true) => {
+    if (source.kind === "none") {
+      return null;
+    }
+    return invoke<string | null>("get_thumbnail_data_url", { source, download });
+  },
+
+  bulkTag: async (filePaths: string[], addTags: string[], removeTags: string[]) => {
+    log.info(`Tagging ${filePaths.length} files`);
+    return await invoke<BulkTagResult>("library_bulk_tag", { filePaths, addTags, removeTags });
+  },
+
+  probeFolder: async (path: string) => {
+    log.info(`Probing folder: ${path}`);
+    return await invoke<FolderProbe>("library_probe_folder", { path });
+  },
+
+  getCacheStatus: async () => {
+    const status = await invoke<CacheStatus>("library_cache_status");
+    log.debug(`Library cache ${Math.round(status.stale_fraction * 100)}% stale`);
+    return status;
+  },
 }));
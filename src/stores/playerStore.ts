@@ -1,4 +1,5 @@
 import { create } from "zustand";
+import { convertFileSrc } from "@tauri-apps/api/core";
 import { platform } from "@tauri-apps/plugin-os";
 import { youtubeService, createLogger, emitSignal, APP_SIGNALS } from "../services";
 import { notify } from "./notificationStore";
@@ -250,6 +251,36 @@ export async function getStreamUrlWithCache(
 
 }
 
+// Local files the backend confirmed are inside a library folder (file path -> canonical path)
+const validatedLocalFiles = new Map<string, string>();
+
+/**
+ * Get the asset URL for a local file, after the backend confirms it is inside a
+ * library folder. Rejects if the file is missing or outside every library folder.
+ *
+ * @param filePath - File path from a queue item or library video
+ * @returns Promise resolving to the URL the webview may load
+ */
+export async function getLocalFileUrl(filePath: string): Promise<string> {
+  try {
+    const canonicalPath = await youtubeService.resolveLocalFile(filePath);
+    validatedLocalFiles.set(filePath, canonicalPath);
+    return convertFileSrc(canonicalPath);
+  } catch (err) {
+    validatedLocalFiles.delete(filePath);
+    throw err;
+  }
+}
+
+/**
+ * Asset URL of a local file already confirmed by getLocalFileUrl, or null if it
+ * hasn't been checked (or was rejected).
+ */
+export function getValidatedLocalFileUrl(filePath: string): string | null {
+  const canonicalPath = validatedLocalFiles.get(filePath);
+  return canonicalPath ? convertFileSrc(canonicalPath) : null;
+}
+
 /**
  * Invalidate prefetch cache if it doesn't match the expected video ID.
  * Call this when the queue changes to ensure stale prefetches are cleared.
@@ -140,6 +140,7 @@ describe("queueStore signal emissions", () => {
         ],
         history: [],
         history_index: -1,
+        current_item_id: null,
       });
 
       const { loadPersistedState } = useQueueStore.getState();
@@ -107,6 +107,8 @@ interface QueueState {
   reorderQueue: (itemId: string, newPosition: number) => void;
   clearQueue: () => void;
   fairShuffle: () => Promise<void>;
+  /** Reload the pending queue order after the backend reordered it (e.g. auto-shuffle) */
+  reloadQueueOrder: () => Promise<void>;
   clearHistory: () => void;
   moveAllHistoryToQueue: () => void;
 
@@ -114,9 +116,9 @@ interface QueueState {
   playDirect: (video: Video) => QueueItem; // Play directly (e.g., from search) - adds to history
   playFromQueue: (index: number) => QueueItem | null;
   playFromHistory: (index: number) => QueueItem | null;
-  playNext: () => QueueItem | null; // For "next" button - may continue through history
+  playNext: () => Promise<QueueItem | null>; // For "next" button - may continue through history
   playNextFromQueue: () => QueueItem | null; // For auto-play when song ends - always takes from queue
-  playPrevious: () => QueueItem | null;
+  playPrevious: () => Promise<QueueItem | null>;
 
   // State queries
   getCurrentItem: () => QueueItem | null;
@@ -211,9 +213,21 @@ export const useQueueStore = create<QueueState>((set, get) => ({
     // Check if fair queue is enabled and we have an active singer
     const fairQueueEnabled =
       useSettingsStore.getState().getSetting(SETTINGS_KEYS.FAIR_QUEUE_ENABLED) === "true";
-    const { activeSingerId } = useSessionStore.getState();
+    const { activeSingerId, autoShuffle } = useSessionStore.getState();
 
-    if (fairQueueEnabled && activeSingerId !== null) {
+    if (autoShuffle) {
+      // Auto-shuffle: the backend fair-shuffles on add, so reload to see where the song landed
+      try {
+        await queueService.addItem(toQueueItemData(newItem, get().queue.length));
+        const state = await queueService.getState();
+        set((current) => ({
+          queue: state ? state.queue.map(fromQueueItemData) : [...current.queue, newItem],
+        }));
+      } catch (error) {
+        log.error("Failed to add item under auto-shuffle:", error);
+        throw error;
+      }
+    } else if (fairQueueEnabled && activeSingerId !== null) {
       // Fair queue mode: compute fair position and insert there
       log.debug(`Fair queue enabled, computing position for singer ${activeSingerId}`);
 
@@ -418,6 +432,21 @@ export const useQueueStore = create<QueueState>((set, get) => ({
     }
   },
 
+  reloadQueueOrder: async () => {
+    const previousFirstItemId = get().queue[0]?.id ?? null;
+    try {
+      const state = await queueService.getState();
+      if (state) {
+        const newQueue = state.queue.map(fromQueueItemData);
+        set({ queue: newQueue });
+        void emitSignal(APP_SIGNALS.QUEUE_ORDER_CHANGED, undefined);
+        emitNextSongChangedIfDifferent(previousFirstItemId, newQueue);
+      }
+    } catch (error) {
+      log.error("Failed to reload queue order:", error);
+    }
+  },
+
   clearHistory: () => {
     log.info("clearHistory");
 
@@ -576,15 +605,27 @@ export const useQueueStore = create<QueueState>((set, get) => ({
     return history[index];
   },
 
-  playNext: () => {
-    const { queue, history, historyIndex } = get();
+  playNext: async () => {
+    // The backend resolves what "next" is, so navigation follows the persisted queue
+    let resolved: QueueItemData | null;
+    try {
+      await flushPendingOperations();
+      resolved = await queueService.resolveNext();
+    } catch (error) {
+      log.error("playNext: failed to resolve next item:", error);
+      return null;
+    }
+    if (!resolved) {
+      log.debug("playNext: nothing to play");
+      return null;
+    }
+    const resolvedId = resolved.id;
 
-    // Calculate effective index (convert -1 to actual end index)
-    const effectiveIndex = historyIndex === -1 ? history.length - 1 : historyIndex;
+    const { queue, history } = get();
 
-    // Check if there are more items ahead in history
-    if (effectiveIndex < history.length - 1) {
-      const nextIndex = effectiveIndex + 1;
+    // Next item ahead in history (navigated back earlier)
+    const nextIndex = history.findIndex((item) => item.id === resolvedId);
+    if (nextIndex !== -1) {
       log.info(`playNext: from history - ${history[nextIndex].video.title}`);
 
       // Persist history index (tracked for session transitions)
@@ -598,13 +639,13 @@ export const useQueueStore = create<QueueState>((set, get) => ({
       return history[nextIndex];
     }
 
-    // Otherwise, take from queue
-    if (queue.length === 0) {
-      log.debug("playNext: queue empty");
+    // Otherwise, take the head of the queue
+    const item = queue[0];
+    if (item?.id !== resolvedId) {
+      log.warn(`playNext: resolved item ${resolvedId} is not the head of the queue`);
       return null;
     }
 
-    const item = queue[0];
     log.info(`playNext: from queue - ${item.video.title}`);
     const newQueue = queue.slice(1);
     const newHistory = [...history, item];
@@ -686,24 +727,28 @@ export const useQueueStore = create<QueueState>((set, get) => ({
     return item;
   },
 
-  playPrevious: () => {
-    const { history, historyIndex } = get();
-
-    if (history.length === 0) {
-      log.debug("playPrevious: history empty");
+  playPrevious: async () => {
+    // The backend resolves what "previous" is, so navigation follows the persisted history
+    let resolved: QueueItemData | null;
+    try {
+      await flushPendingOperations();
+      resolved = await queueService.resolvePrev();
+    } catch (error) {
+      log.error("playPrevious: failed to resolve previous item:", error);
       return null;
     }
-
-    // Calculate effective index
-    const effectiveIndex = historyIndex === -1 ? history.length - 1 : historyIndex;
-
-    // Check if we can go back
-    if (effectiveIndex <= 0) {
+    if (!resolved) {
       log.debug("playPrevious: at start of history");
       return null;
     }
+    const resolvedId = resolved.id;
 
-    const prevIndex = effectiveIndex - 1;
+    const { history } = get();
+    const prevIndex = history.findIndex((item) => item.id === resolvedId);
+    if (prevIndex === -1) {
+      log.warn(`playPrevious: resolved item ${resolvedId} is not in history`);
+      return null;
+    }
     log.info(`playPrevious: ${history[prevIndex].video.title}`);
 
     // Persist history index (tracked for session transitions)
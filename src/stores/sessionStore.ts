@@ -12,6 +12,8 @@ import {
   waitForSignalOrCondition,
   type Singer,
   type Session,
+  type SessionSettings,
+  type PlaybackAdjustments,
   type HostedSession,
 } from "../services";
 import type { SongRequest } from "../types";
@@ -21,7 +23,7 @@ import { useQueueStore, flushPendingOperations } from "./queueStore";
 import { notify } from "./notificationStore";
 import { useAuthStore } from "./authStore";
 import { useSettingsStore, SETTINGS_KEYS } from "./settingsStore";
-import type { Video } from "./playerStore";
+import { usePlayerStore, type Video } from "./playerStore";
 
 const log = createLogger("SessionStore");
 
@@ -127,6 +129,12 @@ interface SessionState {
   _isRefreshingHostedSession: boolean;
   // Session state
   session: Session | null;
+  /** Playback defaults of the active session */
+  sessionSettings: SessionSettings;
+  /** Tempo and key baseline of the active session, for songs without their own override */
+  playbackDefaults: PlaybackAdjustments;
+  /** Whether the backend re-runs the fair shuffle when songs or singers change */
+  autoShuffle: boolean;
   isLoading: boolean;
   showRenameDialog: boolean;
   showLoadDialog: boolean;
@@ -161,6 +169,10 @@ interface SessionState {
   endSession: () => Promise<void>;
   renameSession: (name: string) => Promise<void>;
   switchToSession: (sessionId: number) => Promise<void>;
+  loadSessionSettings: () => Promise<void>;
+  updateSessionSettings: (settings: SessionSettings) => Promise<void>;
+  updatePlaybackDefaults: (defaults: PlaybackAdjustments) => Promise<void>;
+  setAutoShuffle: (enabled: boolean) => Promise<void>;
 
   // Dialog actions
   openRenameDialog: () => void;
@@ -213,6 +225,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
   _hostedSessionPollInterval: null,
   _isRefreshingHostedSession: false,
   session: null,
+  sessionSettings: {},
+  playbackDefaults: {},
+  autoShuffle: false,
   isLoading: false,
   showRenameDialog: false,
   showLoadDialog: false,
@@ -237,6 +252,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       set({ session });
       if (session) {
         log.info(`Active session loaded: ${session.id}`);
+        await get().loadSessionSettings();
         // Load singers for the active session
         await get().loadSingers();
         // Load active singer for the session
@@ -267,7 +283,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       // This prevents race conditions where items are lost during session migration
       await flushPendingOperations();
       const session = await sessionService.startSession(name);
-      set({ session, isLoading: false, singers: [], activeSingerId: null, queueSingerAssignments: new Map() });
+      set({ session, sessionSettings: {}, playbackDefaults: {}, autoShuffle: false, isLoading: false, singers: [], activeSingerId: null, queueSingerAssignments: new Map() });
       // Reload queue/history state (items were migrated to the new session in backend)
       await useQueueStore.getState().loadPersistedState();
       // Load singer assignments for all queue and history items
@@ -296,7 +312,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       // Flush any pending queue operations before ending session
       await flushPendingOperations();
       await sessionService.endSession();
-      set({ session: null, isLoading: false, singers: [], activeSingerId: null, queueSingerAssignments: new Map(), hostedSession: null, showHostModal: false });
+      set({ session: null, sessionSettings: {}, playbackDefaults: {}, autoShuffle: false, isLoading: false, singers: [], activeSingerId: null, queueSingerAssignments: new Map(), hostedSession: null, showHostModal: false });
       // Clear pending singer creations to prevent memory leak
       pendingSingerCreations.clear();
       // Reset queue store (data already archived in DB)
@@ -347,6 +363,7 @@ export const useSessionStore = create<SessionState>((set, get) => ({
           is_active: s.id === sessionId,
         })),
       }));
+      await get().loadSessionSettings();
       // Load singers and queue state for the new session
       await get().loadSingers();
       // Load active singer for the session
@@ -362,6 +379,62 @@ export const useSessionStore = create<SessionState>((set, get) => ({
     }
   },
 
+  loadSessionSettings: async () => {
+    const { session } = get();
+    if (!session) {
+      set({ sessionSettings: {}, playbackDefaults: {}, autoShuffle: false });
+      return;
+    }
+    try {
+      const [sessionSettings, playbackDefaults, autoShuffle] = await Promise.all([
+        sessionService.getSessionSettings(session.id),
+        sessionService.getPlaybackDefaults(session.id),
+        sessionService.getAutoShuffle(),
+      ]);
+      set({ sessionSettings, playbackDefaults, autoShuffle });
+      if (sessionSettings.default_volume != null) {
+        usePlayerStore.getState().setVolume(sessionSettings.default_volume / 100);
+        log.info(`Applied session volume: ${sessionSettings.default_volume}%`);
+      }
+    } catch (error) {
+      log.error("Failed to load session settings:", error);
+      set({ sessionSettings: {}, playbackDefaults: {}, autoShuffle: false });
+    }
+  },
+
+  updateSessionSettings: async (settings: SessionSettings) => {
+    const { session } = get();
+    if (!session) {
+      log.warn("Cannot update session settings: no active session");
+      return;
+    }
+    const sessionSettings = await sessionService.setSessionSettings(session.id, settings);
+    set({ sessionSettings });
+  },
+
+  updatePlaybackDefaults: async (defaults: PlaybackAdjustments) => {
+    const { session } = get();
+    if (!session) {
+      log.warn("Cannot update playback defaults: no active session");
+      return;
+    }
+    const playbackDefaults = await sessionService.setPlaybackDefaults(session.id, defaults);
+    set({ playbackDefaults });
+  },
+
+  setAutoShuffle: async (enabled: boolean) => {
+    if (!get().session) {
+      log.warn("Cannot set auto-shuffle: no active session");
+      return;
+    }
+    await sessionService.setAutoShuffle(enabled);
+    set({ autoShuffle: enabled });
+    // Turning it on shuffles the existing queue
+    if (enabled) {
+      await useQueueStore.getState().reloadQueueOrder();
+    }
+  },
+
   openRenameDialog: () => {
     set({ showRenameDialog: true });
   },
@@ -585,6 +658,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       }
       return { queueSingerAssignments };
     });
+    if (get().autoShuffle) {
+      await useQueueStore.getState().reloadQueueOrder();
+    }
   },
 
   removeSingerFromQueueItem: async (queueItemId: string, singerId: number) => {
@@ -602,6 +678,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       }
       return { queueSingerAssignments };
     });
+    if (get().autoShuffle) {
+      await useQueueStore.getState().reloadQueueOrder();
+    }
   },
 
   clearQueueItemSingers: async (queueItemId: string) => {
@@ -613,6 +692,9 @@ export const useSessionStore = create<SessionState>((set, get) => ({
       queueSingerAssignments.delete(queueItemId);
       return { queueSingerAssignments };
     });
+    if (get().autoShuffle) {
+      await useQueueStore.getState().reloadQueueOrder();
+    }
   },
 
   loadQueueItemSingers: async (queueItemId: string) => {
@@ -17,9 +17,14 @@ export const SETTINGS_KEYS = {
   NEXT_SONG_OVERLAY_SECONDS: "next_song_overlay_seconds",
   SINGER_ANNOUNCEMENT_SECONDS: "singer_announcement_seconds",
   REMEMBER_PLAYER_POSITION: "remember_player_position",
+  REMEMBER_WINDOW_LAYOUT: "remember_window_layout", // auto-save layout per display config (macOS)
+  PAUSE_ON_DISPLAY_DISCONNECT: "pause_on_display_disconnect", // pause when a display is removed (macOS)
+  ENFORCE_CONTRAST: "enforce_contrast", // adjust singer colors to readable contrast
   // Queue & History
   HISTORY_LIMIT: "history_limit",
   CLEAR_QUEUE_ON_EXIT: "clear_queue_on_exit",
+  CHANGEOVER_SECONDS: "changeover_seconds", // gap between songs in runtime estimates
+  PRESERVE_EMPTY_SESSIONS: "preserve_empty_sessions", // archive empty sessions on end instead of deleting
   // Search History
   SEARCH_HISTORY_GLOBAL: "search_history_global",
   SEARCH_HISTORY_SESSION_LIMIT: "search_history_session_limit",
@@ -29,10 +34,12 @@ export const SETTINGS_KEYS = {
   // YouTube
   YOUTUBE_API_KEY: "youtube_api_key", // YouTube Data API v3 key
   YOUTUBE_SEARCH_METHOD: "youtube_search_method", // 'auto' | 'api' | 'ytdlp'
+  DEFAULT_SEARCH_RESULTS: "default_search_results", // 1-50, used when a search omits the count
   // Advanced
   PLAYBACK_MODE: "playback_mode", // 'youtube' | 'ytdlp'
   // Queue behavior
   FAIR_QUEUE_ENABLED: "fair_queue_enabled", // 'true' | 'false'
+  AUTO_SHUFFLE_AFTER_ADDS: "auto_shuffle_after_adds", // fair shuffle after every N added songs, '0' = off
   // Hosted session
   AUTO_ACCEPT_GUEST_REQUESTS: "auto_accept_guest_requests", // 'true' | 'false'
   // Internal (not shown in UI, used for caching)
@@ -49,16 +56,23 @@ export const SETTINGS_DEFAULTS: Record<string, string> = {
   [SETTINGS_KEYS.NEXT_SONG_OVERLAY_SECONDS]: "20",
   [SETTINGS_KEYS.SINGER_ANNOUNCEMENT_SECONDS]: "5",
   [SETTINGS_KEYS.REMEMBER_PLAYER_POSITION]: "true",
+  [SETTINGS_KEYS.REMEMBER_WINDOW_LAYOUT]: "false", // Opt-in
+  [SETTINGS_KEYS.PAUSE_ON_DISPLAY_DISCONNECT]: "true",
+  [SETTINGS_KEYS.ENFORCE_CONTRAST]: "false", // Opt-in
   [SETTINGS_KEYS.HISTORY_LIMIT]: "100",
   [SETTINGS_KEYS.CLEAR_QUEUE_ON_EXIT]: "never",
+  [SETTINGS_KEYS.CHANGEOVER_SECONDS]: "0",
+  [SETTINGS_KEYS.PRESERVE_EMPTY_SESSIONS]: "false", // Empty sessions are deleted on end
   [SETTINGS_KEYS.SEARCH_HISTORY_GLOBAL]: "true", // Show history from all sessions
   [SETTINGS_KEYS.SEARCH_HISTORY_SESSION_LIMIT]: "50", // Max entries per session
   [SETTINGS_KEYS.SEARCH_HISTORY_GLOBAL_LIMIT]: "50", // Max entries when showing global
   [SETTINGS_KEYS.SEARCH_INCLUDE_LYRICS]: "true", // Default to including lyrics in search
   [SETTINGS_KEYS.YOUTUBE_SEARCH_METHOD]: "api", // Default to YouTube API
+  [SETTINGS_KEYS.DEFAULT_SEARCH_RESULTS]: "10",
   [SETTINGS_KEYS.PLAYBACK_MODE]: "youtube", // Default to YouTube embed
   [SETTINGS_KEYS.LAST_VOLUME]: "1", // Default to 100% volume
   [SETTINGS_KEYS.FAIR_QUEUE_ENABLED]: "false", // Default to disabled (append to end)
+  [SETTINGS_KEYS.AUTO_SHUFFLE_AFTER_ADDS]: "0", // Off
   [SETTINGS_KEYS.AUTO_ACCEPT_GUEST_REQUESTS]: "true", // Default on; hosts opt OUT for manual approval
 };
 
@@ -7,12 +7,48 @@ export interface SearchResult {
   view_count?: number;
 }
 
+export type YouTubeBlockKind = "channel" | "keyword";
+
+export interface YouTubeBlock {
+  pattern: string;
+  kind: YouTubeBlockKind;
+}
+
+export interface YouTubeSearchResponse {
+  results: SearchResult[];
+  source: "api" | "ytdlp";
+  has_more: boolean;
+  next_page_token: string | null;
+}
+
 export interface StreamInfo {
   url: string;
   format: string;
   quality: string;
 }
 
+/** A playable stream format (quality) offered for a video */
+export interface StreamFormat {
+  format_id: string;
+  ext: string;
+  width: number | null;
+  height: number | null;
+  fps: number | null;
+  filesize: number | null;
+}
+
+/** A named section of a video (verse, chorus, ...), times in seconds */
+export interface Chapter {
+  title: string;
+  start_time: number;
+  end_time: number;
+}
+
+/** Where the player should load a song from (local library file preferred) */
+export type PlaybackSource =
+  | { source: "local"; file_path: string; matched_by: "youtube_id" | "title_artist" }
+  | ({ source: "youtube" } & StreamInfo);
+
 export interface VideoInfo {
   id: string;
   title: string;